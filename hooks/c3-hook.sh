@@ -9,6 +9,22 @@
 #
 # Then configure Claude Code, Codex, or OMP hooks to call this script.
 
+# Hook protocol version, sent as `version`; C3 also reads it to tell when the
+# installed copy needs reinstalling. GET /capabilities reports what C3 speaks.
+C3_HOOK_VERSION=4
+# Payloads C3 couldn't take (not running yet, restarting) wait here and are
+# replayed by C3 at startup or by the next hook that gets through
+if [ -n "${C3_CONFIG_DIR:-}" ]; then
+    C3_STATE_DIR="$C3_CONFIG_DIR/state"
+elif [ -n "${XDG_STATE_HOME:-}" ]; then
+    C3_STATE_DIR="$XDG_STATE_HOME/c3"
+else
+    C3_STATE_DIR="$HOME/.local/state/c3"
+fi
+C3_SPOOL_DIR="${C3_SPOOL_DIR:-$C3_STATE_DIR/hook-spool}"
+# Installed hooks pass the URL; otherwise use the one the running C3 wrote,
+# which follows its profile's port
+C3_HOOK_URL="${C3_HOOK_URL:-$(cat "$C3_STATE_DIR/hook-url" 2>/dev/null)}"
 C3_HOOK_URL="${C3_HOOK_URL:-http://127.0.0.1:9398/hook}"
 
 # Hook type is passed as first argument (Stop, Notification, PermissionRequest, SessionStart)
@@ -16,6 +32,20 @@ C3_HOOK_URL="${C3_HOOK_URL:-http://127.0.0.1:9398/hook}"
 HOOK_TYPE="${1:-unknown}"
 AGENT_KIND="${C3_AGENT_KIND:-}"
 
+# Claude Code statusLine command: `c3-hook.sh statusline` prints one line about
+# the other sessions, e.g. "c3: 3 other sessions · 1 waiting (api-server)"
+if [ "$HOOK_TYPE" = "statusline" ]; then
+    STATUS_SESSION_ID=$(jq -r '.session_id // empty' 2>/dev/null)
+    curl -sf --get "${C3_HOOK_URL%/hook}/statusline" \
+        --data-urlencode "session_id=$STATUS_SESSION_ID" \
+        --data-urlencode "pane=${TMUX_PANE:-}" \
+        --data-urlencode "format=claude" \
+        --connect-timeout 1 \
+        --max-time 1 \
+        2>/dev/null || echo "c3: not running"
+    exit 0
+fi
+
 # Read the hook data from stdin
 HOOK_DATA=$(cat)
 
@@ -100,6 +130,7 @@ fi
 
 # Build the notification payload
 PAYLOAD=$(jq -n \
+  --argjson version "$C3_HOOK_VERSION" \
   --arg hook_type "$HOOK_TYPE" \
   --arg agent_kind "$AGENT_KIND" \
   --arg cwd "$CWD" \
@@ -114,7 +145,9 @@ PAYLOAD=$(jq -n \
   --arg tmux_window "$TMUX_WINDOW_INDEX" \
   --arg tmux_pane "$TMUX_PANE_INDEX" \
   --arg tmux_window_name "$TMUX_WINDOW_NAME" \
+  --arg tmux_pane_id "${TMUX_PANE:-}" \
   '{
+    version: $version,
     hook_type: $hook_type,
     agent_kind: $agent_kind,
     cwd: $cwd,
@@ -129,16 +162,61 @@ PAYLOAD=$(jq -n \
       session: $tmux_session,
       window: $tmux_window,
       pane: $tmux_pane,
-      window_name: $tmux_window_name
+      window_name: $tmux_window_name,
+      pane_id: (if $tmux_pane_id == "" then null else $tmux_pane_id end)
     }
   }')
 
+# Send earlier spooled payloads as one batch, removing them once C3 has them
+flush_spool() {
+    local files=("$C3_SPOOL_DIR"/*.json)
+    [ -e "${files[0]}" ] || return 0
+    # Claimed by renaming first, so hooks flushing together (or C3 draining
+    # the spool at startup) never send the same payload twice
+    local claimed=() file
+    for file in "${files[@]}"; do
+        mv "$file" "$file.sending.$$" 2>/dev/null && claimed+=("$file.sending.$$")
+    done
+    [ ${#claimed[@]} -gt 0 ] || return 0
+    if jq -s . "${claimed[@]}" 2>/dev/null \
+        | curl -sf -X POST "$C3_HOOK_URL/batch" \
+            -H "Content-Type: application/json" \
+            --data-binary @- \
+            --connect-timeout 1 \
+            --max-time 2 \
+            >/dev/null 2>&1; then
+        rm -f "${claimed[@]}"
+        return
+    fi
+    # Back in the queue for the next hook
+    for file in "${claimed[@]}"; do
+        mv "$file" "${file%.sending.$$}"
+    done
+    return 1
+}
+
+# Older payloads go first so C3 sees them in order; if C3 is unreachable,
+# this one joins the spool with the time it was queued
+send_to_c3() {
+    if flush_spool && curl -sf -X POST "$C3_HOOK_URL" \
+        -H "Content-Type: application/json" \
+        -d "$PAYLOAD" \
+        --connect-timeout 1 \
+        --max-time 2 \
+        >/dev/null 2>&1; then
+        return
+    fi
+    local queued_at
+    queued_at=$(date +%s)
+    local spooled="$C3_SPOOL_DIR/$queued_at-$$.json"
+    # Written aside and renamed so C3 never reads half a file
+    mkdir -p "$C3_SPOOL_DIR" \
+        && echo "$PAYLOAD" | jq -c --argjson queued_at "$queued_at" '. + {queued_at: $queued_at}' \
+            > "$spooled.tmp" \
+        && mv "$spooled.tmp" "$spooled"
+}
+
 # Send to C3 (fire and forget, don't block the agent)
-curl -s -X POST "$C3_HOOK_URL" \
-  -H "Content-Type: application/json" \
-  -d "$PAYLOAD" \
-  --connect-timeout 1 \
-  --max-time 2 \
-  >/dev/null 2>&1 &
+send_to_c3 >/dev/null 2>&1 &
 
 exit 0
@@ -7,6 +7,8 @@
 
 const C3_HOOK_URL = process.env.C3_HOOK_URL || 'http://127.0.0.1:9398/hook';
 const AGENT_KIND = process.env.C3_AGENT_KIND || 'claude';
+// Hook protocol version; see GET /capabilities
+const HOOK_PROTOCOL_VERSION = 4;
 
 function hookTypeFromEvent(event) {
   return event?.hook || event?.hook_type || 'unknown';
@@ -25,6 +27,7 @@ function tmuxContextFromEnv() {
     window,
     pane,
     window_name: process.env.C3_PROJECT_NAME || '',
+    pane_id: process.env.TMUX_PANE || null,
   };
 }
 
@@ -34,6 +37,7 @@ export default async function handler(event = {}) {
   const toolInput = event.tool_input || event.input || null;
 
   const payload = {
+    version: HOOK_PROTOCOL_VERSION,
     hook_type: hookType,
     agent_kind: AGENT_KIND,
     cwd: process.cwd(),
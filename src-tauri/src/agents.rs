@@ -0,0 +1,289 @@
+use crate::aider;
+use crate::jsonl::ConversationState;
+use crate::jsonl_tail::JsonlTails;
+use crate::process_table::PaneChildren;
+use crate::tmux_scanner::{self, AgentPane};
+use serde::Serialize;
+use specta::Type;
+use std::path::PathBuf;
+
+/// Tag for hook payloads that don't say which agent sent them
+pub const UNKNOWN_KIND: &str = "unknown";
+
+/// A coding agent CLI C3 can find in tmux panes. Supporting a new agent means
+/// adding an entry to `AGENTS`: how to spot it, where its log is, how to read
+/// its state, and how to start it.
+pub struct Agent {
+    /// Stored on sessions as `agent_kind` and sent by hooks as `C3_AGENT_KIND`
+    pub kind: &'static str,
+    pub label: &'static str,
+    /// Whether a pane's foreground command is this agent. Gets the pane's
+    /// command and its child processes, for agents that run under node or bun.
+    is_running: fn(&str, &PaneChildren) -> bool,
+    /// Title fragments the agent sets; they linger after it exits to the shell
+    title_markers: &'static [&'static str],
+    /// Conversation log for a session in `cwd`, when the agent keeps a JSONL one
+    pub find_log: fn(&str) -> Option<PathBuf>,
+    /// State of a pane the agent is still running in
+    pub pane_state: fn(&JsonlTails, &AgentPane) -> ConversationState,
+    /// Typed into a new tmux window to start the agent
+    pub launch_command: &'static str,
+    /// Typed into a new tmux window to continue the latest conversation in its cwd
+    pub resume_command: &'static str,
+}
+
+const CLAUDE: Agent = Agent {
+    kind: "claude",
+    label: "Claude Code",
+    // Plain, under node, or a versioned binary (e.g. "2.1.37")
+    is_running: |command, children| {
+        command.contains("claude")
+            || (command == "node" && has_child_matching(children, "claude"))
+            || is_claude_version_binary(command)
+    },
+    title_markers: &["✳", "Claude"],
+    find_log: tmux_scanner::find_claude_jsonl,
+    pane_state: tmux_scanner::claude_pane_state,
+    launch_command: "claude",
+    resume_command: "claude --continue",
+};
+
+/// Detection order: the first agent whose process or title matches wins
+pub const AGENTS: &[Agent] = &[
+    Agent {
+        kind: "omp",
+        label: "OMP",
+        is_running: |command, children| {
+            command.contains("omp")
+                || ((command == "node" || command == "bun") && is_child_omp(children))
+        },
+        title_markers: &["OMP", "omp", "π"],
+        find_log: tmux_scanner::find_active_omp_jsonl,
+        pane_state: tmux_scanner::omp_pane_state,
+        launch_command: "omp",
+        resume_command: "omp --continue",
+    },
+    Agent {
+        kind: "codex",
+        label: "Codex",
+        is_running: |command, children| {
+            command.contains("codex")
+                || (command == "node" && has_child_matching(children, "codex"))
+        },
+        title_markers: &["Codex", "codex"],
+        find_log: tmux_scanner::find_active_codex_jsonl,
+        pane_state: tmux_scanner::codex_pane_state,
+        launch_command: "codex",
+        resume_command: "codex resume --last",
+    },
+    Agent {
+        kind: "aider",
+        label: "Aider",
+        // A Python script; macOS tmux often reports the interpreter instead
+        is_running: |command, children| {
+            command.contains("aider")
+                || ((command.starts_with("python") || command.starts_with("Python"))
+                    && has_child_matching(children, "aider"))
+        },
+        // Aider doesn't set a title, so exited panes aren't recognized
+        title_markers: &[],
+        // Its chat history is markdown, read by `aider::pane_state` itself
+        find_log: |_| None,
+        pane_state: aider::pane_state,
+        launch_command: "aider",
+        resume_command: "aider --restore-chat-history",
+    },
+    CLAUDE,
+];
+
+/// Used for sessions whose kind isn't known, which predate multi-agent support
+pub const FALLBACK: &Agent = &CLAUDE;
+
+pub fn by_kind(kind: &str) -> Option<&'static Agent> {
+    AGENTS.iter().find(|agent| agent.kind == kind)
+}
+
+/// Known agent kind for a hook's `agent_kind`, case-insensitively
+pub fn normalize_kind(kind: Option<&str>) -> &'static str {
+    let kind = kind.unwrap_or_default().to_ascii_lowercase();
+    by_kind(&kind).map_or(UNKNOWN_KIND, |agent| agent.kind)
+}
+
+impl Agent {
+    fn has_title(&self, title: &str) -> bool {
+        self.title_markers
+            .iter()
+            .any(|marker| title.contains(marker))
+    }
+}
+
+/// The agent in a pane: running in it, or, when the pane is back at zsh,
+/// named in its title. None for panes without an agent.
+pub(crate) fn detect(
+    pane_command: &str,
+    children: &PaneChildren,
+    pane_title: &str,
+) -> Option<&'static Agent> {
+    let running: Vec<bool> = AGENTS
+        .iter()
+        .map(|agent| (agent.is_running)(pane_command, children))
+        .collect();
+    let titled: Vec<bool> = AGENTS
+        .iter()
+        .map(|agent| agent.has_title(pane_title))
+        .collect();
+
+    let any_running = running.iter().any(|r| *r);
+    let exited_with_title = pane_command == "zsh" && titled.iter().any(|t| *t);
+    if !any_running && !exited_with_title {
+        return None;
+    }
+    AGENTS
+        .iter()
+        .zip(running.iter().zip(&titled))
+        .find(|(_, (running, titled))| **running || **titled)
+        .map(|(agent, _)| agent)
+}
+
+/// Flags that let an agent run tools without asking
+const YOLO_FLAGS: &[&str] = &[
+    "--dangerously-skip-permissions",
+    "--dangerously-bypass-approvals-and-sandbox",
+    "--yolo",
+    "--ask-for-approval=never",
+    "--permission-mode=bypassPermissions",
+];
+/// Options whose value lets an agent run tools without asking
+const YOLO_OPTIONS: &[(&str, &str)] = &[
+    ("-a", "never"),
+    ("--ask-for-approval", "never"),
+    ("--permission-mode", "bypassPermissions"),
+];
+
+/// Whether a command line turns off permission prompts, e.g.
+/// `claude --dangerously-skip-permissions` or `codex -a never`
+pub(crate) fn is_yolo_command(command: &str) -> bool {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    words.iter().any(|word| YOLO_FLAGS.contains(word))
+        || words
+            .windows(2)
+            .any(|pair| YOLO_OPTIONS.contains(&(pair[0], pair[1])))
+}
+
+/// Whether the pane's agent was started without permission prompts
+pub(crate) fn runs_yolo(children: &PaneChildren) -> bool {
+    children.any(is_yolo_command)
+}
+
+/// Whether a child process of the pane has `pattern` in its command line
+fn has_child_matching(children: &PaneChildren, pattern: &str) -> bool {
+    children.any(|command| command.contains(pattern))
+}
+
+/// Whether a child process of the pane runs omp, as a word of its command
+/// line (Bun-launched scripts show up as `bun .../omp`)
+fn is_child_omp(children: &PaneChildren) -> bool {
+    children.any(|command| {
+        command
+            .split_whitespace()
+            .any(|part| part == "omp" || part.ends_with("/omp"))
+    })
+}
+
+/// Check if the command name looks like a versioned Claude Code binary.
+/// Enterprise Claude Code installs to ~/.local/share/claude/versions/<version>,
+/// and tmux reports pane_current_command as the binary name (e.g. "2.1.37").
+/// Old versions get cleaned up, so we pattern-match instead of checking the file.
+fn is_claude_version_binary(command: &str) -> bool {
+    // Match semver-like patterns: digits.digits.digits (e.g. "2.1.75")
+    let parts: Vec<&str> = command.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// An agent as listed in Settings
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AgentInfo {
+    pub kind: &'static str,
+    pub label: &'static str,
+}
+
+// Tauri command: List the agents C3 knows, in detection order
+#[tauri::command]
+#[specta::specta]
+pub fn list_agents() -> Vec<AgentInfo> {
+    AGENTS
+        .iter()
+        .map(|agent| AgentInfo {
+            kind: agent.kind,
+            label: agent.label,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_table::{ChildCache, ProcessTable};
+
+    fn kind(command: &str, title: &str) -> Option<&'static str> {
+        // An empty process table: only the command and title matter
+        let table = ProcessTable::from_ps("");
+        let cache = ChildCache::default();
+        let children = PaneChildren::new("0", command, &table, &cache);
+        detect(command, &children, title).map(|agent| agent.kind)
+    }
+
+    #[test]
+    fn panes_are_told_apart_by_command_then_title() {
+        assert_eq!(kind("claude", "✳ Fix tests"), Some("claude"));
+        assert_eq!(kind("2.1.37", "⠋ Working"), Some("claude"));
+        assert_eq!(kind("codex", "⠋ Working"), Some("codex"));
+        assert_eq!(kind("omp", "π c3"), Some("omp"));
+        assert_eq!(kind("aider", "~/src"), Some("aider"));
+        // Back at the shell: the title says which agent it was
+        assert_eq!(kind("zsh", "Codex"), Some("codex"));
+        assert_eq!(kind("zsh", "✳ Done"), Some("claude"));
+        assert_eq!(kind("zsh", "~/src"), None);
+        assert_eq!(kind("vim", "Codex"), None);
+    }
+
+    #[test]
+    fn permission_bypassing_command_lines_are_yolo() {
+        assert!(is_yolo_command(
+            "node /opt/homebrew/bin/claude --dangerously-skip-permissions"
+        ));
+        assert!(is_yolo_command(
+            "claude --permission-mode bypassPermissions"
+        ));
+        assert!(is_yolo_command("codex -a never --search"));
+        assert!(is_yolo_command("codex --ask-for-approval=never"));
+        assert!(!is_yolo_command("codex -a on-request"));
+        assert!(!is_yolo_command("claude --permission-mode plan"));
+        assert!(!is_yolo_command("node /opt/homebrew/bin/claude"));
+
+        let table =
+            ProcessTable::from_ps("  7   0 ttys001  claude --dangerously-skip-permissions\n");
+        let cache = ChildCache::default();
+        assert!(runs_yolo(&PaneChildren::new("0", "node", &table, &cache)));
+    }
+
+    #[test]
+    fn every_agent_is_registered_once_and_can_be_started() {
+        for agent in AGENTS {
+            assert_eq!(by_kind(agent.kind).map(|a| a.label), Some(agent.label));
+            assert!(agent.resume_command.starts_with(agent.launch_command));
+        }
+        assert_eq!(FALLBACK.kind, "claude");
+    }
+
+    #[test]
+    fn hook_agent_kinds_are_normalized() {
+        assert_eq!(normalize_kind(Some("Codex")), "codex");
+        assert_eq!(normalize_kind(Some("claude")), "claude");
+        assert_eq!(normalize_kind(Some("cursor")), UNKNOWN_KIND);
+        assert_eq!(normalize_kind(None), UNKNOWN_KIND);
+    }
+}
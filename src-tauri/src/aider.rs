@@ -0,0 +1,170 @@
+use crate::jsonl::{awaiting_input_state, ConversationState};
+use crate::jsonl_tail::JsonlTails;
+use crate::tmux_scanner::AgentPane;
+use crate::{cmd, PendingAction, SessionState};
+use chrono::{DateTime, Utc};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Aider appends every turn here, in the git root it was started from
+const HISTORY_FILE: &str = ".aider.chat.history.md";
+/// Confirmation prompts all offer these choices, e.g.
+/// `Run shell command? (Y)es/(N)o/(D)on't ask again [Yes]:`
+const CONFIRM_MARKER: &str = "(Y)es/(N)o";
+/// History written this recently means aider is still streaming a reply
+const ACTIVE_HISTORY_SECS: u64 = 5;
+const CAPTURE_LINES: usize = 15;
+
+/// What the bottom of an aider pane shows
+#[derive(Debug, Clone, PartialEq)]
+enum PaneStatus {
+    /// Waiting on a yes/no question; the question and, for shell commands, the command
+    Confirm {
+        question: String,
+        command: Option<String>,
+    },
+    /// At the `>` prompt (`architect>`, `ask>` in other chat modes)
+    Prompt,
+    Busy,
+}
+
+/// `.aider.chat.history.md` in `cwd` or the nearest parent that has one
+fn history_path(cwd: &str) -> Option<PathBuf> {
+    Path::new(cwd)
+        .ancestors()
+        .map(|dir| dir.join(HISTORY_FILE))
+        .find(|path| path.is_file())
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).ok()?.modified().ok()
+}
+
+fn is_prompt(line: &str) -> bool {
+    line.strip_suffix('>').is_some_and(|mode| {
+        mode.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    })
+}
+
+fn classify_capture(capture: &str) -> PaneStatus {
+    let lines: Vec<&str> = capture
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+    let Some(last) = lines.last() else {
+        return PaneStatus::Busy;
+    };
+
+    if let Some(index) = last.find(CONFIRM_MARKER) {
+        let question = last[..index].trim().to_string();
+        // The command is printed just above "Run shell command?"
+        let command = question
+            .starts_with("Run shell command")
+            .then(|| lines.iter().rev().nth(1).map(|line| line.to_string()))
+            .flatten();
+        return PaneStatus::Confirm { question, command };
+    }
+    if is_prompt(last) {
+        PaneStatus::Prompt
+    } else {
+        PaneStatus::Busy
+    }
+}
+
+fn capture_pane(target: &str) -> Option<String> {
+    let start = format!("-{}", CAPTURE_LINES);
+    let output = cmd("tmux")
+        .args(["capture-pane", "-p", "-J", "-t", target, "-S", &start])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// State of an aider pane. The pane's last line decides; the chat history
+/// file gives the activity time, and the state when tmux can't be read.
+pub(crate) fn pane_state(_tails: &JsonlTails, pane: &AgentPane) -> ConversationState {
+    let history_modified = history_path(&pane.cwd).and_then(|path| modified_at(&path));
+    let last_message_time = history_modified.map(DateTime::<Utc>::from);
+
+    let status = match capture_pane(&pane.target) {
+        Some(capture) => classify_capture(&capture),
+        None => {
+            let fresh = history_modified
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age.as_secs() < ACTIVE_HISTORY_SECS);
+            if fresh {
+                PaneStatus::Busy
+            } else {
+                PaneStatus::Prompt
+            }
+        }
+    };
+
+    match status {
+        PaneStatus::Confirm { question, command } => ConversationState {
+            state: SessionState::AwaitingPermission,
+            pending_action: Some(PendingAction {
+                action_type: "permission".to_string(),
+                description: question,
+                tool: command.as_ref().map(|_| "Shell".to_string()),
+                command,
+                options: Vec::new(),
+            }),
+            last_message_time,
+        },
+        PaneStatus::Prompt => awaiting_input_state(last_message_time),
+        PaneStatus::Busy => ConversationState {
+            state: SessionState::Processing,
+            pending_action: None,
+            last_message_time,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmations_prompts_and_output_are_told_apart() {
+        let capture = "\
+Tokens: 4.1k sent, 312 received.
+
+npm test -- --watch=false
+
+Run shell command? (Y)es/(N)o/(D)on't ask again [Yes]:
+";
+        assert_eq!(
+            classify_capture(capture),
+            PaneStatus::Confirm {
+                question: "Run shell command?".to_string(),
+                command: Some("npm test -- --watch=false".to_string()),
+            }
+        );
+        assert_eq!(
+            classify_capture("Add src/app.ts to the chat? (Y)es/(N)o [Yes]: "),
+            PaneStatus::Confirm {
+                question: "Add src/app.ts to the chat?".to_string(),
+                command: None,
+            }
+        );
+
+        assert_eq!(classify_capture("src/app.ts\n>\n\n"), PaneStatus::Prompt);
+        assert_eq!(classify_capture("architect> "), PaneStatus::Prompt);
+        assert_eq!(
+            classify_capture("Waiting for claude-3-5-sonnet\n<<<<<<< SEARCH"),
+            PaneStatus::Busy
+        );
+        // A diff line isn't a prompt
+        assert_eq!(
+            classify_capture("=======\n>>>>>>> REPLACE"),
+            PaneStatus::Busy
+        );
+    }
+}
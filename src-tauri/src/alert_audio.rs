@@ -0,0 +1,117 @@
+use crate::cmd;
+use parking_lot::Mutex;
+use std::path::Path;
+
+/// switchaudio-osx's CLI, which afplay's lack of device selection needs
+const SWITCH_AUDIO: &str = "SwitchAudioSource";
+
+/// One alert switches the output device at a time, so a second can't
+/// "restore" the device the first switched to
+static OUTPUT_SWITCH: Mutex<()> = Mutex::new(());
+
+/// `Ping` → `/System/Library/Sounds/Ping.aiff`; paths are used as they are
+pub(crate) fn sound_file(sound: &str) -> String {
+    if sound.starts_with('/') {
+        sound.to_string()
+    } else {
+        format!("/System/Library/Sounds/{}.aiff", sound)
+    }
+}
+
+/// afplay's `-v` takes a linear gain where 1 is the file's own level
+fn afplay_volume(percent: u8) -> String {
+    format!("{:.2}", percent.min(100) as f64 / 100.0)
+}
+
+fn current_output() -> Option<String> {
+    let output = cmd(SWITCH_AUDIO)
+        .args(["-c", "-t", "output"])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn switch_output(device: &str) -> Result<(), String> {
+    let status = cmd(SWITCH_AUDIO)
+        .args(["-t", "output", "-s", device])
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", SWITCH_AUDIO, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("Audio output not found: {}", device))
+    }
+}
+
+/// Play an alert at `volume` percent, on `device` when given. afplay only
+/// plays on the default output, so the default is switched for the length of
+/// the sound and then put back. Blocks until the sound has finished.
+pub(crate) fn play(sound: &str, volume: u8, device: Option<&str>) -> Result<(), String> {
+    let file = sound_file(sound);
+    if !Path::new(&file).exists() {
+        return Err(format!("Sound file not found: {}", file));
+    }
+    let mut afplay = cmd("afplay");
+    afplay.arg("-v").arg(afplay_volume(volume)).arg(&file);
+
+    let Some(device) = device.filter(|d| !d.is_empty()) else {
+        afplay
+            .status()
+            .map_err(|e| format!("Failed to play sound: {}", e))?;
+        return Ok(());
+    };
+    let _switching = OUTPUT_SWITCH.lock();
+    let previous = current_output().filter(|current| current != device);
+    if previous.is_some() {
+        switch_output(device)?;
+    }
+    let played = afplay
+        .status()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to play sound: {}", e));
+    if let Some(previous) = previous {
+        if let Err(e) = switch_output(&previous) {
+            log::warn!("Failed to restore audio output {}: {}", previous, e);
+        }
+    }
+    played
+}
+
+// Tauri command: Audio output devices alerts can be played on
+#[tauri::command]
+#[specta::specta]
+pub fn list_audio_outputs() -> Result<Vec<String>, String> {
+    let output = cmd(SWITCH_AUDIO)
+        .args(["-a", "-t", "output"])
+        .output()
+        .map_err(|_| {
+            "Choosing an output needs switchaudio-osx (brew install switchaudio-osx)".to_string()
+        })?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sounds_and_volumes_map_to_afplay_arguments() {
+        assert_eq!(sound_file("Ping"), "/System/Library/Sounds/Ping.aiff");
+        assert_eq!(sound_file("/tmp/alert.wav"), "/tmp/alert.wav");
+        assert_eq!(afplay_volume(100), "1.00");
+        assert_eq!(afplay_volume(35), "0.35");
+        assert_eq!(afplay_volume(250), "1.00");
+    }
+}
@@ -0,0 +1,342 @@
+use crate::paths;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Tokens start with this, so they're recognizable in configs and scanners
+const TOKEN_PREFIX: &str = "c3_";
+/// Last-used times are saved at most this often per token
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+/// Tokens as stored, loaded on first use
+static TOKENS: Mutex<Option<Vec<StoredToken>>> = Mutex::new(None);
+
+/// What a token may do with the `/api` routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read sessions and subscribe to events, e.g. for status widgets
+    Read,
+    /// Everything, including approving, denying and typing input
+    Control,
+}
+
+impl TokenScope {
+    fn allows(self, needed: TokenScope) -> bool {
+        self == TokenScope::Control || needed == TokenScope::Read
+    }
+}
+
+/// A token as kept in `api-tokens.json`: only a hash of the secret
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredToken {
+    id: String,
+    name: String,
+    scope: TokenScope,
+    /// SHA-256 of the secret, hex
+    hash: String,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A token as shown in settings
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scope: TokenScope,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// A new token with its secret, which is shown only this once
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+impl StoredToken {
+    fn view(&self) -> ApiToken {
+        ApiToken {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            scope: self.scope,
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+        }
+    }
+}
+
+fn tokens_path() -> PathBuf {
+    paths::config_dir().join("api-tokens.json")
+}
+
+fn hash(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn read(path: &Path) -> Vec<StoredToken> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn write(path: &Path, tokens: &[StoredToken]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(tokens).map_err(|e| e.to_string())?;
+    fs::write(path, json).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+/// Run `f` on the tokens, loading them first if needed
+fn with_tokens<T>(f: impl FnOnce(&mut Vec<StoredToken>) -> T) -> T {
+    let mut tokens = TOKENS.lock();
+    f(tokens.get_or_insert_with(|| read(&tokens_path())))
+}
+
+/// The token `secret` belongs to, if it may do `needed`; records its use.
+/// Errs with the HTTP status to answer with.
+fn check(
+    tokens: &mut [StoredToken],
+    secret: Option<&str>,
+    needed: TokenScope,
+    now: DateTime<Utc>,
+) -> Result<bool, &'static str> {
+    let secret = secret.ok_or("401 Unauthorized")?;
+    let hash = hash(secret);
+    let token = tokens
+        .iter_mut()
+        .find(|token| token.hash == hash)
+        .ok_or("401 Unauthorized")?;
+    if !token.scope.allows(needed) {
+        return Err("403 Forbidden");
+    }
+    let stale = token
+        .last_used_at
+        .is_none_or(|used| now - used >= Duration::seconds(LAST_USED_RESOLUTION_SECS));
+    if stale {
+        token.last_used_at = Some(now);
+    }
+    Ok(stale)
+}
+
+/// The secret of an `Authorization: Bearer <secret>` header value
+pub(crate) fn bearer(authorization: &str) -> Option<&str> {
+    let (scheme, secret) = authorization.trim().split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| secret.trim())
+}
+
+/// Whether `host`, a Host header value, names the loopback interface
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    name.eq_ignore_ascii_case("localhost")
+        || name
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Refuse what a web page could have sent: browsers add an Origin header to
+/// cross-site requests, and DNS rebinding shows up as a foreign Host on a
+/// local one. Errs with the HTTP status.
+pub(crate) fn check_origin(
+    origin: Option<&str>,
+    host: Option<&str>,
+    remote: bool,
+) -> Result<(), &'static str> {
+    if origin.is_some() || (!remote && host.is_some_and(|host| !is_loopback_host(host))) {
+        return Err("403 Forbidden");
+    }
+    Ok(())
+}
+
+/// Whether a request with this bearer secret may do `needed`. Local
+/// requests may read without a token until the first one is created, but
+/// never control; `remote` ones, from the LAN server, always need one.
+/// Errs with the HTTP status.
+pub(crate) fn authorize(
+    secret: Option<&str>,
+    needed: TokenScope,
+    remote: bool,
+) -> Result<(), &'static str> {
+    with_tokens(|tokens| {
+        if tokens.is_empty() && !remote && needed == TokenScope::Read {
+            return Ok(());
+        }
+        if check(tokens, secret, needed, Utc::now())? {
+            if let Err(e) = write(&tokens_path(), tokens) {
+                log::warn!("Failed to record API token use: {}", e);
+            }
+        }
+        Ok(())
+    })
+}
+
+// Tauri command: API tokens, without their secrets
+#[tauri::command]
+#[specta::specta]
+pub fn list_api_tokens() -> Vec<ApiToken> {
+    with_tokens(|tokens| tokens.iter().map(StoredToken::view).collect())
+}
+
+// Tauri command: Create an API token; its secret is returned only this once
+#[tauri::command]
+#[specta::specta]
+pub fn create_api_token(name: String, scope: TokenScope) -> Result<CreatedApiToken, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name the token after what will use it".to_string());
+    }
+    let secret = format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    let stored = StoredToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        scope,
+        hash: hash(&secret),
+        created_at: Utc::now(),
+        last_used_at: None,
+    };
+    let token = stored.view();
+    with_tokens(|tokens| {
+        tokens.push(stored);
+        write(&tokens_path(), tokens)
+    })?;
+    log::info!("Created {:?} API token \"{}\"", scope, name);
+    Ok(CreatedApiToken { token, secret })
+}
+
+// Tauri command: Revoke an API token; requests using it are refused from now on
+#[tauri::command]
+#[specta::specta]
+pub fn revoke_api_token(id: String) -> Result<Vec<ApiToken>, String> {
+    with_tokens(|tokens| {
+        let before = tokens.len();
+        tokens.retain(|token| token.id != id);
+        if tokens.len() == before {
+            return Err(format!("No API token {}", id));
+        }
+        write(&tokens_path(), tokens)?;
+        Ok(tokens.iter().map(StoredToken::view).collect())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn token(secret: &str, scope: TokenScope) -> StoredToken {
+        StoredToken {
+            id: format!("{:?}", scope),
+            name: "test".to_string(),
+            scope,
+            hash: hash(secret),
+            created_at: Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap(),
+            last_used_at: None,
+        }
+    }
+
+    #[test]
+    fn scopes_limit_what_a_token_may_do() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 10, 0, 0).unwrap();
+        let mut tokens = vec![
+            token("c3_widget", TokenScope::Read),
+            token("c3_phone", TokenScope::Control),
+        ];
+        assert_eq!(
+            check(&mut tokens, Some("c3_widget"), TokenScope::Read, now),
+            Ok(true)
+        );
+        assert_eq!(
+            check(&mut tokens, Some("c3_widget"), TokenScope::Control, now),
+            Err("403 Forbidden")
+        );
+        assert_eq!(
+            check(&mut tokens, Some("c3_phone"), TokenScope::Control, now),
+            Ok(true)
+        );
+        assert_eq!(
+            check(&mut tokens, Some("c3_other"), TokenScope::Read, now),
+            Err("401 Unauthorized")
+        );
+        assert_eq!(
+            check(&mut tokens, None, TokenScope::Read, now),
+            Err("401 Unauthorized")
+        );
+
+        // Uses within a minute aren't saved again
+        assert_eq!(tokens[0].last_used_at, Some(now));
+        let soon = now + Duration::seconds(30);
+        assert_eq!(
+            check(&mut tokens, Some("c3_widget"), TokenScope::Read, soon),
+            Ok(false)
+        );
+        assert_eq!(tokens[0].last_used_at, Some(now));
+    }
+
+    #[test]
+    fn browser_requests_are_refused() {
+        assert_eq!(check_origin(None, Some("127.0.0.1:9398"), false), Ok(()));
+        assert_eq!(check_origin(None, Some("localhost:9398"), false), Ok(()));
+        assert_eq!(check_origin(None, Some("[::1]:9398"), false), Ok(()));
+        assert_eq!(check_origin(None, None, false), Ok(()));
+        assert_eq!(
+            check_origin(Some("https://example.com"), Some("127.0.0.1:9398"), false),
+            Err("403 Forbidden")
+        );
+        assert_eq!(
+            check_origin(None, Some("evil.example.com:9398"), false),
+            Err("403 Forbidden")
+        );
+        // Over the LAN the Host is the Mac's address, and a token is required
+        assert_eq!(check_origin(None, Some("192.168.1.20:9399"), true), Ok(()));
+        assert_eq!(
+            check_origin(Some("null"), Some("192.168.1.20:9399"), true),
+            Err("403 Forbidden")
+        );
+    }
+
+    #[test]
+    fn stored_tokens_keep_only_a_hash() {
+        let path = std::env::temp_dir()
+            .join(format!("c3-api-tokens-{}", std::process::id()))
+            .join("api-tokens.json");
+        write(&path, &[token("c3_secret", TokenScope::Read)]).unwrap();
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(!json.contains("c3_secret"));
+        assert!(json.contains(&hash("c3_secret")));
+        assert_eq!(read(&path), [token("c3_secret", TokenScope::Read)]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+
+        assert_eq!(bearer("Bearer c3_abc"), Some("c3_abc"));
+        assert_eq!(bearer("bearer  c3_abc "), Some("c3_abc"));
+        assert_eq!(bearer("Basic dXNlcg=="), None);
+    }
+}
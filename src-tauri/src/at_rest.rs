@@ -0,0 +1,303 @@
+use crate::{cmd, load_settings, paths};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Secret holding the age identity C3 created
+const KEY_NAME: &str = "at-rest-key";
+/// Where the identity is kept without a keyring
+const KEY_FILE: &str = "at-rest-key.txt";
+/// How age files start, binary and armored
+const AGE_HEADERS: [&[u8]; 2] = [
+    b"age-encryption.org/v1\n",
+    b"-----BEGIN AGE ENCRYPTED FILE-----",
+];
+
+/// Encrypt the conversation copies C3 writes (session bundles and
+/// transcript exports) with age
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct AtRestSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// age identity file to use, e.g. from `age-keygen` or an age plugin,
+    /// instead of a key C3 creates in the keychain
+    #[serde(default)]
+    pub identity_path: Option<String>,
+    /// More age (or SSH) recipients that can decrypt, e.g. other machines
+    /// that import this one's bundles
+    #[serde(default)]
+    pub recipients: Vec<String>,
+}
+
+/// Where the age identity comes from
+enum Identity {
+    File(PathBuf),
+    /// An `AGE-SECRET-KEY-1…` line
+    Secret(String),
+}
+
+/// Check encryption settings before they are saved
+pub(crate) fn validate(at_rest: &AtRestSettings) -> Result<(), String> {
+    if let Some(path) = at_rest
+        .identity_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        if !Path::new(path).is_file() {
+            return Err(format!("No such identity file: {}", path));
+        }
+    }
+    match extra_recipients(at_rest)
+        .find(|recipient| !recipient.starts_with("age1") && !recipient.starts_with("ssh-"))
+    {
+        Some(bad) => Err(format!("Not an age recipient: {}", bad)),
+        None => Ok(()),
+    }
+}
+
+/// The configured recipients, without blank lines
+fn extra_recipients(at_rest: &AtRestSettings) -> impl Iterator<Item = &str> {
+    at_rest
+        .recipients
+        .iter()
+        .map(|recipient| recipient.trim())
+        .filter(|recipient| !recipient.is_empty())
+}
+
+fn is_encrypted(contents: &[u8]) -> bool {
+    AGE_HEADERS
+        .iter()
+        .any(|header| contents.starts_with(header))
+}
+
+/// The secret key line of `age-keygen` output
+fn secret_key(keygen_output: &str) -> Option<&str> {
+    keygen_output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("AGE-SECRET-KEY-1"))
+}
+
+/// The recipient an identity file names in a comment, as `age-keygen` and
+/// age plugins write them
+fn commented_recipient(identity_file: &str) -> Option<String> {
+    identity_file.lines().find_map(|line| {
+        let comment = line.trim().strip_prefix('#')?;
+        let (label, value) = comment.split_once(':')?;
+        let label = label.trim().to_ascii_lowercase();
+        (label == "public key" || label == "recipient")
+            .then(|| value.trim().to_string())
+            .filter(|value| value.starts_with("age1"))
+    })
+}
+
+/// `path` for files C3 exports: `.age` is appended when encrypting
+pub(crate) fn sealed_path(path: &str) -> String {
+    if load_settings().at_rest.enabled {
+        format!("{}.age", path)
+    } else {
+        path.to_string()
+    }
+}
+
+pub(crate) fn run(
+    mut command: std::process::Command,
+    stdin: &[u8],
+    what: &str,
+) -> Result<Vec<u8>, String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", what, e))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        pipe.write_all(stdin).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn stored_secret() -> Option<String> {
+    crate::secrets::get(KEY_NAME).or_else(|| {
+        fs::read_to_string(paths::config_dir().join(KEY_FILE))
+            .ok()
+            .and_then(|key| secret_key(&key).map(str::to_string))
+    })
+}
+
+fn store_secret(secret: &str) -> Result<(), String> {
+    let Err(e) = crate::secrets::set(KEY_NAME, secret) else {
+        return Ok(());
+    };
+    // Without a keyring (e.g. a headless Linux box) the key goes in a file
+    // only C3's user can read
+    log::warn!("Keeping the at-rest key in a file: {}", e);
+    let path = paths::config_dir().join(KEY_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, format!("{}\n", secret)).map_err(|e| e.to_string())?;
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())
+}
+
+/// The configured identity file, or C3's own key, created on first use
+fn identity(at_rest: &AtRestSettings) -> Result<Identity, String> {
+    if let Some(path) = at_rest
+        .identity_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        return Ok(Identity::File(PathBuf::from(path)));
+    }
+    if let Some(secret) = stored_secret() {
+        return Ok(Identity::Secret(secret));
+    }
+    let generated = run(cmd("age-keygen"), &[], "age-keygen")?;
+    let generated = String::from_utf8_lossy(&generated);
+    let secret = secret_key(&generated)
+        .ok_or("age-keygen printed no key")?
+        .to_string();
+    store_secret(&secret)?;
+    log::info!("Created an age key for encrypting C3's files");
+    Ok(Identity::Secret(secret))
+}
+
+fn own_recipient(identity: &Identity) -> Result<String, String> {
+    let recipient = match identity {
+        Identity::File(path) => {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("Can't read {}: {}", path.display(), e))?;
+            if let Some(recipient) = commented_recipient(&contents) {
+                return Ok(recipient);
+            }
+            let mut keygen = cmd("age-keygen");
+            keygen.arg("-y").arg(path);
+            run(keygen, &[], "age-keygen")?
+        }
+        Identity::Secret(secret) => {
+            let mut keygen = cmd("age-keygen");
+            keygen.arg("-y");
+            run(keygen, format!("{}\n", secret).as_bytes(), "age-keygen")?
+        }
+    };
+    Ok(String::from_utf8_lossy(&recipient).trim().to_string())
+}
+
+/// Write `contents` to `path`, encrypted to this machine and the
+/// configured recipients when turned on
+pub(crate) fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let at_rest = load_settings().at_rest;
+    if !at_rest.enabled {
+        return fs::write(path, contents).map_err(|e| e.to_string());
+    }
+    let own = own_recipient(&identity(&at_rest)?)?;
+    let mut age = cmd("age");
+    for recipient in std::iter::once(own.as_str()).chain(extra_recipients(&at_rest)) {
+        age.arg("-r").arg(recipient);
+    }
+    // Renamed into place, so a failed run never leaves half a file at `path`
+    let partial = path.with_extension("c3-partial");
+    age.arg("-o").arg(&partial);
+    if let Err(e) = run(age, contents, "age") {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, path).map_err(|e| e.to_string())
+}
+
+/// Read `path`, decrypting it if it was written encrypted, whether or not
+/// encryption is still turned on
+pub(crate) fn read(path: &Path) -> Result<Vec<u8>, String> {
+    let contents =
+        fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !is_encrypted(&contents) {
+        return Ok(contents);
+    }
+    let mut age = cmd("age");
+    age.arg("-d");
+    let stdin = match identity(&load_settings().at_rest)? {
+        Identity::File(identity) => {
+            age.arg("-i").arg(identity);
+            Vec::new()
+        }
+        Identity::Secret(secret) => {
+            age.args(["-i", "-"]);
+            format!("{}\n", secret).into_bytes()
+        }
+    };
+    age.arg(path);
+    run(age, &stdin, "age").map_err(|e| format!("Can't decrypt {}: {}", path.display(), e))
+}
+
+// Tauri command: This machine's age recipient, for other machines to encrypt bundles to
+#[tauri::command]
+#[specta::specta]
+pub async fn get_at_rest_recipient() -> Result<String, String> {
+    tokio::task::spawn_blocking(|| own_recipient(&identity(&load_settings().at_rest)?))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn age_keys_and_files_are_recognized() {
+        let keygen = "# created: 2026-03-02T09:00:00Z\n\
+                      # public key: age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p\n\
+                      AGE-SECRET-KEY-1QQPQ9QNRXPH2G0YCQQLL\n";
+        assert_eq!(
+            secret_key(keygen),
+            Some("AGE-SECRET-KEY-1QQPQ9QNRXPH2G0YCQQLL")
+        );
+        assert_eq!(
+            commented_recipient(keygen).as_deref(),
+            Some("age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p")
+        );
+        let plugin =
+            "#       Serial: 1234\n#    Recipient: age1yubikey1qwerty\nAGE-PLUGIN-YUBIKEY-1ABC\n";
+        assert_eq!(
+            commented_recipient(plugin).as_deref(),
+            Some("age1yubikey1qwerty")
+        );
+        assert_eq!(commented_recipient("AGE-SECRET-KEY-1ABC\n"), None);
+
+        assert!(is_encrypted(b"age-encryption.org/v1\n-> X25519 abc\n"));
+        assert!(is_encrypted(b"-----BEGIN AGE ENCRYPTED FILE-----\nYWdl\n"));
+        assert!(!is_encrypted(b"PK\x03\x04"));
+
+        let at_rest = AtRestSettings {
+            enabled: true,
+            identity_path: None,
+            recipients: vec![
+                "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p".to_string(),
+            ],
+        };
+        assert!(validate(&at_rest).is_ok());
+        let blank_line = AtRestSettings {
+            recipients: vec![String::new(), "ssh-ed25519 AAAAC3Nz".to_string()],
+            ..at_rest.clone()
+        };
+        assert!(validate(&blank_line).is_ok());
+        let bad = AtRestSettings {
+            recipients: vec!["AGE-SECRET-KEY-1ABC".to_string()],
+            ..at_rest
+        };
+        assert!(validate(&bad).is_err());
+    }
+}
@@ -0,0 +1,398 @@
+use crate::state_machine::TimelineEntry;
+use crate::{cmd, load_session_meta, load_settings, AppState, C3Session, SessionState};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::Arc;
+
+/// How often new state transitions are matched against the rules
+const CHECK_INTERVAL_SECS: u64 = 2;
+/// Commands still running after this are killed
+const COMMAND_TIMEOUT_SECS: u64 = 300;
+/// Runs kept for the log in settings
+const MAX_LOG_ENTRIES: usize = 100;
+/// Output kept per run, from the end
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+/// "When a session moves from `from` to `to`, run `command`"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct AutomationRule {
+    pub name: String,
+    #[serde(default = "crate::default_true")]
+    pub enabled: bool,
+    /// The state left; None for any, including a new session
+    #[serde(default)]
+    pub from: Option<SessionState>,
+    pub to: SessionState,
+    /// A project name, or a path the project is in; None for every project
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Sessions with this tag; None for every session
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Only sessions with (true) or without (false) permission prompts
+    /// turned off; None for every session
+    #[serde(default)]
+    pub yolo: Option<bool>,
+    /// Run with `sh -c` in the project directory, session fields in `C3_*`
+    /// environment variables
+    pub command: String,
+    /// Log the run without running the command
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A rule that matched, and what came of it
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationRun {
+    pub at: DateTime<Utc>,
+    pub rule: String,
+    pub session_id: String,
+    pub project_name: String,
+    pub from: Option<SessionState>,
+    pub to: SessionState,
+    pub command: String,
+    pub dry_run: bool,
+    /// None for dry runs and commands that failed to start or timed out
+    pub exit_code: Option<i32>,
+    /// The end of stdout and stderr, or why the command didn't run
+    pub output: String,
+}
+
+/// Recent runs, newest last
+#[derive(Default)]
+pub struct RunLog {
+    runs: Mutex<VecDeque<AutomationRun>>,
+}
+
+impl RunLog {
+    fn push(&self, run: AutomationRun) {
+        let mut runs = self.runs.lock();
+        runs.push_back(run);
+        while runs.len() > MAX_LOG_ENTRIES {
+            runs.pop_front();
+        }
+    }
+
+    /// Newest first
+    pub fn recent(&self) -> Vec<AutomationRun> {
+        self.runs.lock().iter().rev().cloned().collect()
+    }
+}
+
+fn state_name(state: &SessionState) -> String {
+    serde_json::to_value(state)
+        .ok()
+        .and_then(|value| value.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+fn in_project(filter: &str, session: &C3Session) -> bool {
+    let filter = filter.trim_end_matches('/');
+    session.project_name == filter
+        || session.project_path.as_deref().is_some_and(|path| {
+            path.strip_prefix(filter)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+}
+
+fn matches(
+    rule: &AutomationRule,
+    entry: &TimelineEntry,
+    session: &C3Session,
+    tag: Option<&str>,
+) -> bool {
+    rule.enabled
+        && !rule.command.trim().is_empty()
+        && rule.to == entry.to
+        && rule
+            .from
+            .as_ref()
+            .is_none_or(|from| entry.from.as_ref() == Some(from))
+        && rule
+            .project
+            .as_deref()
+            .is_none_or(|project| in_project(project, session))
+        && rule
+            .tag
+            .as_deref()
+            .is_none_or(|rule_tag| tag == Some(rule_tag))
+        && rule.yolo.is_none_or(|yolo| session.yolo == yolo)
+}
+
+/// What the command sees of the session
+fn environment(
+    rule: &AutomationRule,
+    entry: &TimelineEntry,
+    session: &C3Session,
+    tag: Option<&str>,
+) -> Vec<(&'static str, String)> {
+    vec![
+        ("C3_RULE", rule.name.clone()),
+        ("C3_SESSION_ID", session.id.clone()),
+        ("C3_PROJECT_NAME", session.project_name.clone()),
+        (
+            "C3_PROJECT_PATH",
+            session.project_path.clone().unwrap_or_default(),
+        ),
+        (
+            "C3_AGENT",
+            session
+                .agent_kind
+                .clone()
+                .unwrap_or_else(|| "claude".to_string()),
+        ),
+        (
+            "C3_FROM_STATE",
+            entry.from.as_ref().map(state_name).unwrap_or_default(),
+        ),
+        ("C3_STATE", state_name(&entry.to)),
+        ("C3_TAG", tag.unwrap_or_default().to_string()),
+        ("C3_YOLO", session.yolo.to_string()),
+        (
+            "C3_TMUX_TARGET",
+            session.tmux_target.clone().unwrap_or_default(),
+        ),
+        (
+            "C3_ERROR_REASON",
+            session.error_reason.clone().unwrap_or_default(),
+        ),
+    ]
+}
+
+fn tail(output: &str) -> String {
+    let output = output.trim();
+    let skip = output.chars().count().saturating_sub(MAX_OUTPUT_CHARS);
+    output.chars().skip(skip).collect()
+}
+
+/// Run the command, returning its exit code and output
+async fn execute(
+    command: &str,
+    cwd: Option<&str>,
+    env: Vec<(&'static str, String)>,
+) -> (Option<i32>, String) {
+    let mut process = cmd("/bin/sh");
+    process.arg("-c").arg(command).envs(env);
+    if let Some(cwd) = cwd.filter(|cwd| Path::new(cwd).is_dir()) {
+        process.current_dir(cwd);
+    }
+    let mut process = tokio::process::Command::from(process);
+    process
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true);
+    let child = match process.spawn() {
+        Ok(child) => child,
+        Err(e) => return (None, format!("Failed to start: {}", e)),
+    };
+    let timeout = tokio::time::Duration::from_secs(COMMAND_TIMEOUT_SECS);
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            (output.status.code(), tail(&text))
+        }
+        Ok(Err(e)) => (None, format!("Failed to run: {}", e)),
+        Err(_) => (None, format!("Killed after {} s", COMMAND_TIMEOUT_SECS)),
+    }
+}
+
+/// Run, or log, every rule matching transitions recorded after `after`.
+/// Returns the newest transition seen.
+fn check(state: &Arc<AppState>, rules: &[AutomationRule], after: DateTime<Utc>) -> DateTime<Utc> {
+    let entries = state.timeline.since(after);
+    let Some(newest) = entries.last().map(|(_, entry)| entry.timestamp) else {
+        return after;
+    };
+    let meta = load_session_meta();
+    for (session_id, entry) in entries {
+        let session = state
+            .sessions
+            .read()
+            .get(&session_id)
+            .cloned()
+            .or_else(|| state.archive.session(&session_id));
+        let Some(session) = session else {
+            continue;
+        };
+        let tag = meta
+            .sessions
+            .get(&session.id)
+            .and_then(|meta| meta.tag.as_deref());
+        for rule in rules
+            .iter()
+            .filter(|rule| matches(rule, &entry, &session, tag))
+        {
+            log::info!(
+                "Automation {:?} matched {} ({})",
+                rule.name,
+                session.id,
+                state_name(&entry.to)
+            );
+            let run = AutomationRun {
+                at: Utc::now(),
+                rule: rule.name.clone(),
+                session_id: session.id.clone(),
+                project_name: session.project_name.clone(),
+                from: entry.from.clone(),
+                to: entry.to.clone(),
+                command: rule.command.clone(),
+                dry_run: rule.dry_run,
+                exit_code: None,
+                output: String::new(),
+            };
+            if rule.dry_run {
+                state.automation_log.push(run);
+                continue;
+            }
+            let env = environment(rule, &entry, &session, tag);
+            let cwd = session.project_path.clone();
+            let state = state.clone();
+            tauri::async_runtime::spawn(async move {
+                let (exit_code, output) = execute(&run.command, cwd.as_deref(), env).await;
+                if exit_code != Some(0) {
+                    log::warn!("Automation {:?} failed: {}", run.rule, output);
+                }
+                state.automation_log.push(AutomationRun {
+                    exit_code,
+                    output,
+                    ..run
+                });
+            });
+        }
+    }
+    newest
+}
+
+/// Run the automation rules in settings on session state transitions
+pub async fn start_automation(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    // Only transitions from now on; nothing replays after a restart
+    let mut after = Utc::now();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Automation shutting down");
+                break;
+            }
+        }
+        let rules = load_settings().automation_rules;
+        if !rules.iter().any(|rule| rule.enabled) {
+            after = Utc::now();
+            continue;
+        }
+        let state_check = state.clone();
+        after = tokio::task::spawn_blocking(move || check(&state_check, &rules, after))
+            .await
+            .unwrap_or_else(|_| Utc::now());
+    }
+}
+
+// Tauri command: Recent automation runs and dry runs, newest first
+#[tauri::command]
+#[specta::specta]
+pub fn get_automation_log(state: tauri::State<'_, Arc<AppState>>) -> Vec<AutomationRun> {
+    state.automation_log.recent()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_machine::StateSource;
+
+    fn session(project_path: &str) -> C3Session {
+        C3Session {
+            project_name: "api-server".to_string(),
+            project_path: Some(project_path.to_string()),
+            agent_kind: Some("codex".to_string()),
+            state: SessionState::Complete,
+            tmux_target: Some("work:2.0".to_string()),
+            ..C3Session::test("tmux:%4")
+        }
+    }
+
+    #[test]
+    fn rules_match_transitions_in_their_project() {
+        let rule = AutomationRule {
+            name: "deploy".to_string(),
+            enabled: true,
+            from: None,
+            to: SessionState::Complete,
+            project: Some("/Users/me/src/api-server/".to_string()),
+            tag: None,
+            yolo: None,
+            command: "./deploy.sh".to_string(),
+            dry_run: false,
+        };
+        let entry = TimelineEntry {
+            timestamp: Utc::now(),
+            from: Some(SessionState::Processing),
+            to: SessionState::Complete,
+            source: StateSource::Hook,
+        };
+        let api = session("/Users/me/src/api-server/web");
+        assert!(matches(&rule, &entry, &api, None));
+        assert!(!matches(
+            &rule,
+            &entry,
+            &session("/Users/me/src/api-server-old"),
+            None
+        ));
+        // By name too
+        let by_name = AutomationRule {
+            project: Some("api-server".to_string()),
+            ..rule.clone()
+        };
+        assert!(matches(&by_name, &entry, &session("/srv/x"), None));
+
+        let from_waiting = AutomationRule {
+            from: Some(SessionState::AwaitingInput),
+            ..rule.clone()
+        };
+        assert!(!matches(&from_waiting, &entry, &api, None));
+        let tagged = AutomationRule {
+            tag: Some("prod".to_string()),
+            ..rule.clone()
+        };
+        assert!(!matches(&tagged, &entry, &api, None));
+        assert!(matches(&tagged, &entry, &api, Some("prod")));
+        let yolo_only = AutomationRule {
+            yolo: Some(true),
+            ..rule.clone()
+        };
+        assert!(!matches(&yolo_only, &entry, &api, None));
+        let yolo = C3Session {
+            yolo: true,
+            ..api.clone()
+        };
+        assert!(matches(&yolo_only, &entry, &yolo, None));
+        let disabled = AutomationRule {
+            enabled: false,
+            ..rule.clone()
+        };
+        assert!(!matches(&disabled, &entry, &api, None));
+
+        let env = environment(&rule, &entry, &api, Some("prod"));
+        let var = |name: &str| {
+            env.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(var("C3_FROM_STATE"), Some("processing"));
+        assert_eq!(var("C3_STATE"), Some("complete"));
+        assert_eq!(var("C3_AGENT"), Some("codex"));
+        assert_eq!(var("C3_TAG"), Some("prod"));
+    }
+}
@@ -0,0 +1,154 @@
+use tauri_specta::{collect_commands, Builder};
+
+/// All commands the frontend can invoke. Event payloads are added as types
+/// so listeners can use them too.
+pub fn builder() -> Builder<tauri::Wry> {
+    Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            crate::get_sessions,
+            crate::get_sessions_since,
+            crate::query::query_sessions,
+            crate::search::search_transcripts,
+            crate::transcript_export::export_transcript,
+            crate::session_files::get_session_files,
+            crate::session_diff::get_session_diff,
+            crate::session_window::open_session_window,
+            crate::session_window::get_session_tail,
+            crate::session_window::close_session_window,
+            crate::supervisor::get_down_subsystems,
+            crate::updater::check_for_updates,
+            crate::agents::list_agents,
+            crate::updater::open_staged_update,
+            crate::worktree::create_worktree,
+            crate::worktree::remove_worktree,
+            crate::gc::get_archived_sessions,
+            crate::gc::undo_archive,
+            crate::get_session_timeline,
+            crate::notification_log::get_notification_decisions,
+            crate::event_log::tail_events,
+            crate::permission_stats::get_permission_stats,
+            crate::time_report::export_time_report,
+            crate::dashboard::get_dashboard_stats,
+            crate::digest::get_weekly_digest,
+            crate::digest::send_weekly_digest,
+            crate::push::test_push,
+            crate::telegram::test_telegram,
+            crate::email::test_email,
+            crate::api_tokens::list_api_tokens,
+            crate::api_tokens::create_api_token,
+            crate::api_tokens::revoke_api_token,
+            crate::lan::get_lan_status,
+            crate::at_rest::get_at_rest_recipient,
+            crate::deep_link::get_x_callback_key,
+            crate::claude_allowlist::get_claude_allowlist,
+            crate::claude_allowlist::add_claude_allow_rule,
+            crate::claude_allowlist::remove_claude_allow_rule,
+            crate::claude_allowlist::suggest_allow_rule,
+            crate::claude_allowlist::always_allow_session,
+            crate::get_debug_info,
+            crate::profiles::get_profiles,
+            crate::profiles::create_profile,
+            crate::profiles::switch_profile,
+            crate::diagnostics::create_diagnostic_bundle,
+            crate::diagnostics::get_diagnostics,
+            crate::session_bundle::export_session_bundle,
+            crate::session_bundle::import_session_bundle,
+            crate::focus_terminal,
+            crate::zoom_pane,
+            crate::focus_session,
+            crate::send_action,
+            crate::approve_session,
+            crate::deny_session,
+            crate::send_session_input,
+            crate::answer_question,
+            crate::remove_session,
+            crate::close_pane,
+            crate::stop_claude,
+            crate::kill_session,
+            crate::play_sound,
+            crate::sound_themes::list_sound_themes,
+            crate::alert_audio::list_audio_outputs,
+            crate::automation::get_automation_log,
+            crate::get_settings,
+            crate::update_settings,
+            crate::terminals::get_available_terminals,
+            crate::get_session_meta,
+            crate::update_session_meta,
+            crate::reorder_sessions,
+            crate::set_session_note,
+            crate::task_chain::set_next_prompts,
+            crate::task_chain::send_next_prompt,
+            crate::upsert_session_group,
+            crate::delete_session_group,
+            crate::assign_session_group,
+            crate::set_group_muted,
+            crate::set_tag_muted,
+            crate::create_new_task,
+            crate::scheduler::list_scheduled_tasks,
+            crate::scheduler::save_scheduled_task,
+            crate::scheduler::set_scheduled_task_enabled,
+            crate::scheduler::delete_scheduled_task,
+            crate::scheduler::run_scheduled_task,
+            crate::launch_queue::get_queue,
+            crate::launch_queue::cancel_queued,
+            crate::resume_session,
+            crate::resync_session,
+            crate::check_hook_status,
+            crate::compat::get_compat_warnings,
+            crate::onboarding::run_onboarding_step,
+            crate::onboarding::install_dependency,
+            crate::uninstall::uninstall_c3_integration,
+            crate::setup_hooks,
+            crate::simulate::simulate_scenario,
+            crate::simulate::stop_simulation,
+            crate::plugins::mac_rounded_corners::enable_rounded_corners::<tauri::Wry>,
+            crate::plugins::mac_rounded_corners::enable_modern_window_style::<tauri::Wry>,
+            crate::plugins::mac_rounded_corners::reposition_traffic_lights::<tauri::Wry>,
+            crate::plugins::compact_window::toggle_compact_window,
+            crate::plugins::compact_window::hide_compact_window,
+            crate::plugins::compact_window::open_main_window,
+            crate::plugins::compact_window::set_compact_window_pinned,
+            crate::plugins::floating_widget::toggle_floating_widget,
+            crate::plugins::floating_widget::hide_floating_widget,
+            crate::plugins::login_item::get_login_item_status,
+            crate::plugins::login_item::set_launch_at_login,
+        ])
+        // Event payloads; events are emitted by name, so these aren't reachable from a command
+        .typ::<crate::events::SessionBatch>()
+        .typ::<crate::gc::ArchivedBatch>()
+        .typ::<crate::sound_themes::HookSound>()
+        .typ::<crate::updater::UpdateInfo>()
+        .typ::<crate::supervisor::SubsystemDown>()
+        .typ::<crate::compat::CompatWarning>()
+        .typ::<crate::onboarding::OnboardingProgress>()
+        .typ::<crate::onboarding::DependencyProgress>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+
+    /// Generated TypeScript for every command and the types they take and return
+    const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/bindings.ts");
+
+    fn typescript() -> Typescript {
+        Typescript::default()
+            // Counters and timestamps stay well below 2^53
+            .bigint(BigIntExportBehavior::Number)
+            .header("// @ts-nocheck\n// Regenerate with `UPDATE_BINDINGS=1 cargo test bindings`")
+    }
+
+    #[test]
+    fn bindings_are_up_to_date() {
+        let generated = builder().export_str(typescript()).unwrap();
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            std::fs::write(BINDINGS_PATH, &generated).unwrap();
+        }
+        let committed = std::fs::read_to_string(BINDINGS_PATH).unwrap_or_default();
+        assert!(
+            committed == generated,
+            "src/bindings.ts is stale; run `UPDATE_BINDINGS=1 cargo test bindings`"
+        );
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{cmd, load_settings, paths, push, telegram};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+
+const POST_TIMEOUT_SECS: &str = "10";
+
+/// What a notification is about, so each channel can pick the ones it sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelEvent {
+    /// An agent is waiting on a permission prompt
+    Permission,
+    /// An agent asked a question or is idle at its prompt
+    Input,
+    /// An agent finished its turn
+    Complete,
+    /// A time limit or memory limit was crossed
+    Alert,
+}
+
+impl ChannelEvent {
+    /// The event a notifying hook stands for
+    pub fn from_hook(hook_type: &str) -> Option<Self> {
+        match hook_type {
+            "PermissionRequest" => Some(Self::Permission),
+            "Notification" => Some(Self::Input),
+            "Stop" => Some(Self::Complete),
+            _ => None,
+        }
+    }
+
+    /// Whether it should interrupt rather than wait to be seen
+    pub fn urgent(self) -> bool {
+        matches!(self, Self::Permission | Self::Alert)
+    }
+}
+
+/// POST `body` as JSON with extra `headers` (`Name: value`)
+pub(crate) fn post_json(url: &str, body: &Value, headers: &[String]) -> Result<(), String> {
+    post(url, body, headers, POST_TIMEOUT_SECS).map(|_| ())
+}
+
+/// POST `body` as JSON and parse the JSON reply, allowing `max_time`
+/// seconds for long polls
+pub(crate) fn post_json_reply(url: &str, body: &Value, max_time: u64) -> Result<Value, String> {
+    let reply = post(url, body, &[], &max_time.to_string())?;
+    serde_json::from_slice(&reply).map_err(|e| format!("Unexpected reply: {}", e))
+}
+
+/// A curl config line setting `option` to `value`
+fn config_line(option: &str, value: &str) -> String {
+    format!(
+        "{} = \"{}\"\n",
+        option,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    )
+}
+
+/// curl reads the URL, which can hold a token (Telegram's does), and the
+/// headers, which can too (ntfy's), from a config on stdin and the body from
+/// a file, keeping them out of the process list
+fn post(url: &str, body: &Value, headers: &[String], max_time: &str) -> Result<Vec<u8>, String> {
+    let dir = paths::state_dir();
+    let _ = fs::create_dir_all(&dir);
+    let path = dir.join(format!("post-{}.json", uuid::Uuid::new_v4().simple()));
+    fs::write(&path, body.to_string()).map_err(|e| e.to_string())?;
+
+    let mut curl = cmd("curl");
+    curl.args(["-fsS", "--max-time", max_time, "-X", "POST", "-K", "-"])
+        .args(["-H", "Content-Type: application/json"])
+        .arg("--data-binary")
+        .arg(format!("@{}", path.display()));
+    let mut config = config_line("url", url);
+    for header in headers {
+        config.push_str(&config_line("header", header));
+    }
+    let sent = run(curl, &config);
+    let _ = fs::remove_file(&path);
+    sent
+}
+
+fn run(mut curl: std::process::Command, config: &str) -> Result<Vec<u8>, String> {
+    let mut child = curl
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(config.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Pass a notification C3 showed on this Mac to the remote channels set up
+/// in settings, in the background
+pub(crate) fn notify(event: ChannelEvent, title: &str, message: &str, session_id: Option<&str>) {
+    let settings = load_settings();
+    let push = push::wants(&settings.push, event);
+    let telegram = telegram::wants(&settings.telegram, event);
+    if !push && !telegram {
+        return;
+    }
+    let (title, message) = (title.to_string(), message.to_string());
+    let session_id = session_id.map(str::to_string);
+    std::thread::spawn(move || {
+        if push {
+            if let Err(e) = push::send(&settings.push, event, &title, &message) {
+                log::warn!("Push notification failed: {}", e);
+            }
+        }
+        if telegram {
+            let sent = telegram::send(&settings.telegram, event, &title, &message, session_id);
+            if let Err(e) = sent {
+                log::warn!("Telegram message failed: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_values_are_quoted() {
+        assert_eq!(
+            config_line("url", "https://api.telegram.org/bot1:abc/getUpdates"),
+            "url = \"https://api.telegram.org/bot1:abc/getUpdates\"\n"
+        );
+        assert_eq!(
+            config_line("header", r#"X-Title: say "hi" \o/"#),
+            "header = \"X-Title: say \\\"hi\\\" \\\\o/\"\n"
+        );
+    }
+}
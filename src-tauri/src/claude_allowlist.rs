@@ -0,0 +1,286 @@
+use crate::{approve_permission, AppState, PendingAction};
+use serde::Serialize;
+use serde_json::{json, Value};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Tools that only read, so allowing them outright is safe
+const READ_ONLY_TOOLS: [&str; 5] = ["Read", "Glob", "Grep", "LS", "NotebookRead"];
+
+/// Result of changing the allowlist
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct AllowlistUpdate {
+    pub rules: Vec<String>,
+    /// Copy of settings.json taken before it was rewritten
+    pub backup_path: Option<String>,
+}
+
+/// `~/.claude/settings.json`, where Claude Code reads `permissions.allow`
+fn settings_path() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").unwrap_or_default();
+    if home.is_empty() {
+        return Err("Could not determine HOME directory".to_string());
+    }
+    Ok(PathBuf::from(home).join(".claude").join("settings.json"))
+}
+
+/// The settings as they are on disk. A file that exists but doesn't parse is
+/// an error rather than empty settings, so it never gets overwritten.
+fn read_settings(path: &Path) -> Result<Value, String> {
+    match fs::read_to_string(path) {
+        Ok(contents) if contents.trim().is_empty() => Ok(json!({})),
+        Ok(contents) => serde_json::from_str::<Value>(&contents)
+            .ok()
+            .filter(Value::is_object)
+            .ok_or_else(|| format!("{} is not a JSON object; fix it by hand", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(json!({})),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn allow_rules(settings: &Value) -> Vec<String> {
+    settings
+        .pointer("/permissions/allow")
+        .and_then(Value::as_array)
+        .map(|rules| {
+            rules
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Set `permissions.allow`, keeping every other key as it was
+fn set_allow_rules(settings: &mut Value, rules: &[String]) -> Result<(), String> {
+    let permissions = settings
+        .as_object_mut()
+        .ok_or("settings.json is not a JSON object")?
+        .entry("permissions")
+        .or_insert_with(|| json!({}));
+    permissions
+        .as_object_mut()
+        .ok_or("`permissions` in settings.json is not an object")?
+        .insert("allow".to_string(), json!(rules));
+    Ok(())
+}
+
+fn validate_rule(rule: &str) -> Result<(), String> {
+    if rule.is_empty() {
+        return Err("Rule is empty".to_string());
+    }
+    if rule.contains(['\n', '\r']) {
+        return Err("Rules are a single line".to_string());
+    }
+    if rule.contains('(') && !rule.ends_with(')') {
+        return Err(format!("Unbalanced rule: {}", rule));
+    }
+    Ok(())
+}
+
+/// Apply `change` to the allowlist, backing settings.json up first like
+/// `setup_hooks` does. The file is replaced in one rename, so Claude Code
+/// never reads it half-written.
+fn update(path: &Path, change: impl FnOnce(&mut Vec<String>)) -> Result<AllowlistUpdate, String> {
+    let mut settings = read_settings(path)?;
+    let mut rules = allow_rules(&settings);
+    let before = rules.clone();
+    change(&mut rules);
+    if rules == before {
+        return Ok(AllowlistUpdate {
+            rules,
+            backup_path: None,
+        });
+    }
+    set_allow_rules(&mut settings, &rules)?;
+    let json = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+
+    let dir = path.parent().ok_or("settings.json has no directory")?;
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let backup_path = if path.exists() {
+        let backup = dir.join(format!(
+            "settings.json.backup.{}",
+            chrono::Utc::now().timestamp()
+        ));
+        fs::copy(path, &backup).map_err(|e| format!("Failed to backup settings: {}", e))?;
+        Some(backup.to_string_lossy().to_string())
+    } else {
+        None
+    };
+    let partial = dir.join("settings.json.c3-partial");
+    fs::write(&partial, json + "\n").map_err(|e| format!("Failed to write settings: {}", e))?;
+    fs::rename(&partial, path).map_err(|e| format!("Failed to write settings: {}", e))?;
+    Ok(AllowlistUpdate { rules, backup_path })
+}
+
+fn add_rule(path: &Path, rule: &str) -> Result<AllowlistUpdate, String> {
+    let rule = rule.trim();
+    validate_rule(rule)?;
+    let update = update(path, |rules| {
+        if !rules.iter().any(|r| r == rule) {
+            rules.push(rule.to_string());
+        }
+    })?;
+    log::info!("Added {} to Claude's permission allowlist", rule);
+    Ok(update)
+}
+
+/// The allowlist rule covering just a permission request: the exact command
+/// for Bash, e.g. `Bash(npm test)`, or the tool's name for read-only tools.
+/// Others, like Edit or WebFetch, have no rule this narrow.
+pub(crate) fn rule_for(action: &PendingAction) -> Option<String> {
+    let tool = action.tool.as_deref().filter(|tool| !tool.is_empty())?;
+    if READ_ONLY_TOOLS.contains(&tool) {
+        return Some(tool.to_string());
+    }
+    if tool != "Bash" {
+        return None;
+    }
+    let command = action.command.as_deref().map(str::trim)?;
+    (!command.is_empty() && !command.contains('\n')).then(|| format!("Bash({})", command))
+}
+
+/// Refuse rules that would allow every use of a tool that changes things,
+/// e.g. a bare `Edit`
+fn check_session_rule(rule: &str) -> Result<(), String> {
+    let rule = rule.trim();
+    if !rule.contains('(') && !READ_ONLY_TOOLS.contains(&rule) {
+        return Err(format!(
+            "{} would allow every {} request; name a command, e.g. Bash(npm test)",
+            rule, rule
+        ));
+    }
+    Ok(())
+}
+
+/// The rule "always allow" would add for a Claude session's pending request
+fn session_rule(state: &AppState, session_id: &str) -> Option<String> {
+    let sessions = state.sessions.read();
+    let session = sessions.get(session_id)?;
+    if session.agent_kind.as_deref().unwrap_or("claude") != "claude" {
+        return None;
+    }
+    rule_for(session.pending_action.as_ref()?)
+}
+
+// Tauri command: Rules in Claude Code's permission allowlist
+#[tauri::command]
+#[specta::specta]
+pub fn get_claude_allowlist() -> Result<Vec<String>, String> {
+    Ok(allow_rules(&read_settings(&settings_path()?)?))
+}
+
+// Tauri command: Add a rule such as `Bash(npm test:*)` to Claude Code's allowlist
+#[tauri::command]
+#[specta::specta]
+pub fn add_claude_allow_rule(rule: String) -> Result<AllowlistUpdate, String> {
+    add_rule(&settings_path()?, &rule)
+}
+
+// Tauri command: Remove a rule from Claude Code's allowlist
+#[tauri::command]
+#[specta::specta]
+pub fn remove_claude_allow_rule(rule: String) -> Result<AllowlistUpdate, String> {
+    let update = update(&settings_path()?, |rules| rules.retain(|r| *r != rule))?;
+    log::info!("Removed {} from Claude's permission allowlist", rule);
+    Ok(update)
+}
+
+// Tauri command: The rule that would always allow a session's pending request
+#[tauri::command]
+#[specta::specta]
+pub fn suggest_allow_rule(
+    state: tauri::State<Arc<AppState>>,
+    session_id: String,
+) -> Option<String> {
+    session_rule(&state, &session_id)
+}
+
+// Tauri command: Add the rule the user confirmed for a session's pending request to the allowlist, and approve it
+#[tauri::command]
+#[specta::specta]
+pub async fn always_allow_session(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    rule: String,
+) -> Result<AllowlistUpdate, String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        check_session_rule(&rule)?;
+        let update = add_rule(&settings_path()?, &rule)?;
+        approve_permission(&state, &session_id)?;
+        Ok(update)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn action(tool: &str, command: Option<&str>) -> PendingAction {
+        PendingAction {
+            action_type: "permission".to_string(),
+            description: String::new(),
+            tool: Some(tool.to_string()),
+            command: command.map(String::from),
+            options: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn rules_cover_the_request() {
+        assert_eq!(
+            rule_for(&action("Bash", Some("npm test -- --watch"))).as_deref(),
+            Some("Bash(npm test -- --watch)")
+        );
+        assert_eq!(
+            rule_for(&action("Bash", Some("rm -rf build/"))).as_deref(),
+            Some("Bash(rm -rf build/)")
+        );
+        assert_eq!(rule_for(&action("Grep", None)).as_deref(), Some("Grep"));
+        assert_eq!(rule_for(&action("Edit", None)), None);
+        assert_eq!(rule_for(&action("WebFetch", None)), None);
+        assert_eq!(rule_for(&action("Bash", None)), None);
+
+        assert!(check_session_rule("Bash(git push:*)").is_ok());
+        assert!(check_session_rule("Read").is_ok());
+        assert!(check_session_rule("Edit").is_err());
+        assert!(check_session_rule(" Write ").is_err());
+    }
+
+    #[test]
+    fn allowlist_edits_keep_other_settings() {
+        let dir = std::env::temp_dir().join(format!("c3-allowlist-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        fs::write(
+            &path,
+            r#"{"model": "opus", "permissions": {"deny": ["Bash(rm:*)"], "allow": ["Edit"]}}"#,
+        )
+        .unwrap();
+
+        let added = add_rule(&path, " Bash(npm test:*) ").unwrap();
+        assert_eq!(added.rules, vec!["Edit", "Bash(npm test:*)"]);
+        assert!(added
+            .backup_path
+            .is_some_and(|backup| Path::new(&backup).exists()));
+        // Already there: nothing to write or back up
+        assert!(add_rule(&path, "Edit").unwrap().backup_path.is_none());
+
+        let settings = read_settings(&path).unwrap();
+        assert_eq!(settings["model"], "opus");
+        assert_eq!(settings["permissions"]["deny"], json!(["Bash(rm:*)"]));
+
+        fs::write(&path, "{ broken").unwrap();
+        assert!(add_rule(&path, "Read").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ broken");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
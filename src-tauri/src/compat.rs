@@ -0,0 +1,263 @@
+use crate::{cmd, tmux_scanner, AppState};
+use parking_lot::Mutex;
+use semver::Version;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+/// How often running sessions' transcripts are checked for their CLI version
+const CHECK_INTERVAL_SECS: u64 = 60;
+/// Newest Claude Code minor release C3's hooks and transcript parsing have
+/// been checked against
+const TESTED_THROUGH: (u64, u64) = (2, 1);
+
+/// Claude Code versions in `[from, before)` that C3's hooks or transcript
+/// parsing are known not to handle
+struct KnownIssue {
+    from: &'static str,
+    before: &'static str,
+    issue: &'static str,
+}
+
+const KNOWN_ISSUES: &[KnownIssue] = &[
+    KnownIssue {
+        from: "0.0.0",
+        before: "1.0.38",
+        issue: "No hooks: sessions are tracked by the tmux scanner alone",
+    },
+    KnownIssue {
+        from: "1.0.38",
+        before: "1.0.62",
+        issue: "No SessionStart hook: new sessions appear once the scanner finds them",
+    },
+    KnownIssue {
+        from: "0.0.0",
+        before: "2.0.45",
+        issue: "No PermissionRequest hook: permission prompts are read from the pane",
+    },
+];
+
+/// A Claude Code CLI whose hooks or transcripts C3 may misread
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatWarning {
+    /// None for the installed CLI
+    pub session_id: Option<String>,
+    pub project_name: Option<String>,
+    pub version: String,
+    pub issues: Vec<String>,
+}
+
+/// Current warnings, per session and for the installed CLI, for windows
+/// opened after they were emitted
+static WARNINGS: Mutex<Vec<CompatWarning>> = Mutex::new(Vec::new());
+
+/// `2.1.37` from `2.1.37 (Claude Code)` or a transcript's `version`
+fn parse_version(text: &str) -> Option<Version> {
+    let token = text.split_whitespace().next()?;
+    Version::parse(token.trim_start_matches('v')).ok()
+}
+
+/// What C3 is known to get wrong with this version
+pub(crate) fn issues(version: &str) -> Vec<String> {
+    let Some(version) = parse_version(version) else {
+        return Vec::new();
+    };
+    let mut issues: Vec<String> = KNOWN_ISSUES
+        .iter()
+        .filter(|known| {
+            let from = Version::parse(known.from).expect("valid version");
+            let before = Version::parse(known.before).expect("valid version");
+            version >= from && version < before
+        })
+        .map(|known| known.issue.to_string())
+        .collect();
+    if (version.major, version.minor) > TESTED_THROUGH {
+        issues.push(format!(
+            "Newer than C3 has been checked against ({}.{}); hook names and transcript lines may have changed",
+            TESTED_THROUGH.0, TESTED_THROUGH.1
+        ));
+    }
+    issues
+}
+
+/// `claude --version` of the CLI on PATH
+fn installed_version() -> Option<String> {
+    let output = cmd("claude").arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version(&String::from_utf8_lossy(&output.stdout)).map(|v| v.to_string())
+}
+
+/// The CLI version stamped on the newest transcript line that has one
+fn transcript_version(lines: &[String]) -> Option<String> {
+    lines.iter().rev().find_map(|line| {
+        let parsed: serde_json::Value = serde_json::from_str(line).ok()?;
+        let version = parsed.get("version")?.as_str()?;
+        parse_version(version).map(|v| v.to_string())
+    })
+}
+
+/// Record a warning, or clear it when `issues` is empty. Returns whether it
+/// changed.
+fn record(warning: CompatWarning) -> bool {
+    let mut warnings = WARNINGS.lock();
+    let index = warnings
+        .iter()
+        .position(|w| w.session_id == warning.session_id);
+    match (index, warning.issues.is_empty()) {
+        (Some(i), _) if warnings[i] == warning => false,
+        (Some(i), true) => {
+            warnings.remove(i);
+            true
+        }
+        (Some(i), false) => {
+            warnings[i] = warning;
+            true
+        }
+        (None, true) => false,
+        (None, false) => {
+            warnings.push(warning);
+            true
+        }
+    }
+}
+
+fn warn(app_handle: &AppHandle, warning: CompatWarning) {
+    if !record(warning.clone()) {
+        return;
+    }
+    if !warning.issues.is_empty() {
+        log::warn!(
+            "Claude Code {} ({}): {}",
+            warning.version,
+            warning.project_name.as_deref().unwrap_or("installed"),
+            warning.issues.join("; ")
+        );
+    }
+    // An empty `issues` tells windows the warning is gone
+    let _ = app_handle.emit("cli-compat-warning", &warning);
+}
+
+/// Check the CLI on PATH, warning about it. Returns its version and issues.
+pub(crate) fn check_installed(app_handle: &AppHandle) -> (Option<String>, Vec<String>) {
+    let Some(version) = installed_version() else {
+        return (None, Vec::new());
+    };
+    let issues = issues(&version);
+    warn(
+        app_handle,
+        CompatWarning {
+            session_id: None,
+            project_name: None,
+            version: version.clone(),
+            issues: issues.clone(),
+        },
+    );
+    (Some(version), issues)
+}
+
+/// Check the installed CLI, and the version each Claude session's transcript
+/// was written by
+fn check(state: &AppState, app_handle: &AppHandle, installed: bool) {
+    if installed {
+        check_installed(app_handle);
+    }
+    let sessions: Vec<(String, String, String)> = state
+        .sessions
+        .read()
+        .values()
+        .filter(|s| s.agent_kind.as_deref().unwrap_or("claude") == "claude")
+        .filter_map(|s| {
+            Some((
+                s.id.clone(),
+                s.project_name.clone(),
+                s.project_path.clone()?,
+            ))
+        })
+        .collect();
+    let mut versions = HashMap::new();
+    for (session_id, project_name, cwd) in sessions {
+        let Some(jsonl) = tmux_scanner::find_claude_jsonl(&cwd) else {
+            continue;
+        };
+        let version = versions
+            .entry(jsonl.clone())
+            .or_insert_with(|| transcript_version(&state.jsonl_tails.read_last_lines(&jsonl, 30)))
+            .clone();
+        if let Some(version) = version {
+            let issues = issues(&version);
+            warn(
+                app_handle,
+                CompatWarning {
+                    session_id: Some(session_id),
+                    project_name: Some(project_name),
+                    version,
+                    issues,
+                },
+            );
+        }
+    }
+    // Sessions that have gone away
+    let live = state.sessions.read();
+    WARNINGS
+        .lock()
+        .retain(|w| w.session_id.as_ref().is_none_or(|id| live.contains_key(id)));
+}
+
+/// Warn when the installed or a running Claude Code CLI is one C3 is known
+/// not to read correctly
+pub async fn start_compat_check(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut installed = true;
+    loop {
+        let state = state.clone();
+        let app_handle = app_handle.clone();
+        let _ = tokio::task::spawn_blocking(move || check(&state, &app_handle, installed)).await;
+        installed = false;
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Compatibility check shutting down");
+                break;
+            }
+        }
+    }
+}
+
+// Tauri command: Current Claude Code compatibility warnings, installed CLI first
+#[tauri::command]
+#[specta::specta]
+pub fn get_compat_warnings() -> Vec<CompatWarning> {
+    let mut warnings = WARNINGS.lock().clone();
+    warnings.sort_by_key(|w| w.session_id.is_some());
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_matched_against_the_known_issues() {
+        assert_eq!(issues("2.1.37 (Claude Code)"), Vec::<String>::new());
+        assert_eq!(issues("2.0.45"), Vec::<String>::new());
+        let old = issues("1.0.50");
+        assert_eq!(old.len(), 2);
+        assert!(old[0].starts_with("No SessionStart hook"));
+        assert!(issues("3.0.0")[0].starts_with("Newer than C3"));
+        assert!(issues("not a version").is_empty());
+
+        let lines = vec![
+            r#"{"type":"user","version":"2.0.30","message":{}}"#.to_string(),
+            r#"{"type":"summary"}"#.to_string(),
+        ];
+        assert_eq!(transcript_version(&lines).as_deref(), Some("2.0.30"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{
+    ignore, load_session_meta, pane_current_command, send_input, session_identity, tmux_scanner,
+    AppSettings, AppState, C3Session, SessionState,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Wait after the Stop hook, so the agent's prompt is ready for typing
+const DELAY_SECS: u64 = 2;
+/// A session's command isn't run again within this, in case running it
+/// ends another turn
+const COOLDOWN: Duration = Duration::from_secs(60);
+
+static LAST_RUN: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// Run `command` in the pane of sessions under the `path` glob when they
+/// complete
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct CompleteAction {
+    pub path: String,
+    pub command: String,
+}
+
+/// The first action whose glob covers the project
+fn action_for<'a>(
+    settings: &'a AppSettings,
+    project_path: Option<&str>,
+) -> Option<&'a CompleteAction> {
+    let path = project_path?;
+    settings.complete_actions.iter().find(|action| {
+        !action.command.trim().is_empty()
+            && ignore::path_pattern(&action.path)
+                .is_some_and(|pattern| ignore::matches_path(&pattern, path))
+    })
+}
+
+/// What to type: the command itself at a shell, or in the agent's shell
+/// mode while it's still running. None for agents without one.
+fn keystrokes(pane_command: &str, agent_kind: Option<&str>, command: &str) -> Option<String> {
+    let command = command.trim();
+    if tmux_scanner::is_shell_command(pane_command) {
+        return Some(command.to_string());
+    }
+    // Claude Code and Codex run a line starting with `!` in the shell
+    match agent_kind.unwrap_or("claude") {
+        "claude" | "codex" => Some(format!("!{}", command)),
+        _ => None,
+    }
+}
+
+/// Claim the session's turn to run its command, unless it ran recently
+fn claim(session_id: &str, now: Instant) -> bool {
+    let mut last_run = LAST_RUN.get_or_init(Default::default).lock();
+    last_run.retain(|_, at| now.duration_since(*at) < COOLDOWN);
+    if last_run.contains_key(session_id) {
+        return false;
+    }
+    last_run.insert(session_id.to_string(), now);
+    true
+}
+
+fn run(state: &AppState, session_id: &str, command: &str) -> Result<(), String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or("the session is gone")?;
+    // Typing now would answer the agent, or interrupt it
+    if session.state != SessionState::Complete || session.pending_action.is_some() {
+        return Err("the session isn't complete any more".to_string());
+    }
+    let tmux_target = session_identity::tmux_pane(&session).ok_or("it isn't running in tmux")?;
+    let pane_command =
+        pane_current_command(tmux_target).ok_or_else(|| format!("no pane {}", tmux_target))?;
+    let keys = keystrokes(&pane_command, session.agent_kind.as_deref(), command)
+        .ok_or("the agent has no shell mode")?;
+    send_input(state, session_id, &keys)?;
+    log::info!("Ran complete action {:?} in {}", command, tmux_target);
+    Ok(())
+}
+
+/// After a Stop hook: type the project's complete action in the session's
+/// pane. Sessions with a queued prompt carry on with that instead.
+pub(crate) fn on_stop(state: &Arc<AppState>, session: &C3Session, settings: &AppSettings) {
+    let Some(action) = action_for(settings, session.project_path.as_deref()) else {
+        return;
+    };
+    let queued = load_session_meta()
+        .sessions
+        .get(&session.id)
+        .is_some_and(|meta| !meta.next_prompts.is_empty());
+    if queued || !claim(&session.id, Instant::now()) {
+        return;
+    }
+    let state = state.clone();
+    let session_id = session.id.clone();
+    let command = action.command.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_secs(DELAY_SECS)).await;
+        let id = session_id.clone();
+        let result = tokio::task::spawn_blocking(move || run(&state, &id, &command)).await;
+        if let Ok(Err(e)) = result {
+            log::info!("Not running complete action for {}: {}", session_id, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn projects_get_their_command_typed_at_a_shell_or_in_shell_mode() {
+        let settings = AppSettings {
+            complete_actions: vec![
+                CompleteAction {
+                    path: "~/src/api".to_string(),
+                    command: "npm test".to_string(),
+                },
+                CompleteAction {
+                    path: "/work/**".to_string(),
+                    command: " git status ".to_string(),
+                },
+            ],
+            ..AppSettings::default()
+        };
+        let home = std::env::var("HOME").unwrap_or_default();
+        let api = format!("{}/src/api", home);
+        assert_eq!(
+            action_for(&settings, Some(&api)).map(|a| a.command.as_str()),
+            Some("npm test")
+        );
+        let web = action_for(&settings, Some("/work/web")).unwrap();
+        assert_eq!(action_for(&settings, Some("/src/other")), None);
+        assert_eq!(action_for(&settings, None), None);
+
+        assert_eq!(
+            keystrokes("node", Some("claude"), &web.command).as_deref(),
+            Some("!git status")
+        );
+        assert_eq!(
+            keystrokes("-zsh", Some("aider"), "git status").as_deref(),
+            Some("git status")
+        );
+        assert_eq!(keystrokes("python3", Some("aider"), "git status"), None);
+
+        let now = Instant::now();
+        assert!(claim("tmux:%4", now));
+        assert!(!claim("tmux:%4", now + Duration::from_secs(5)));
+        assert!(claim("tmux:%4", now + COOLDOWN));
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{AppState, C3Session, SessionState};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often connectivity is checked
+const CHECK_INTERVAL_SECS: u64 = 15;
+/// Failed checks in a row before the machine counts as offline, so one
+/// dropped connection doesn't stall every session
+const OFFLINE_AFTER: u32 = 2;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// Reaching any of these means agents can reach their APIs; the IP answers
+/// when only DNS is down
+const PROBES: &[&str] = &["api.anthropic.com:443", "api.openai.com:443", "1.1.1.1:443"];
+
+/// Whether a TCP connection to any probe succeeds
+fn probe() -> bool {
+    PROBES.iter().any(|probe| {
+        probe.to_socket_addrs().is_ok_and(|mut addrs| {
+            addrs.any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok())
+        })
+    })
+}
+
+/// A change in connectivity
+#[derive(Debug, PartialEq)]
+enum Change {
+    WentOffline,
+    CameBack,
+}
+
+/// Failed checks in a row, and whether they made the machine offline
+#[derive(Default)]
+struct Monitor {
+    failures: u32,
+    offline: bool,
+}
+
+impl Monitor {
+    fn observe(&mut self, reachable: bool) -> Option<Change> {
+        if reachable {
+            self.failures = 0;
+            return std::mem::take(&mut self.offline).then_some(Change::CameBack);
+        }
+        self.failures += 1;
+        let went_offline = !self.offline && self.failures >= OFFLINE_AFTER;
+        self.offline |= went_offline;
+        went_offline.then_some(Change::WentOffline)
+    }
+}
+
+pub(crate) fn is_offline(state: &AppState) -> bool {
+    state.offline.load(Ordering::Relaxed)
+}
+
+/// Whether a session in `session_state` is stalled: working while the
+/// machine is offline, when the agent can only be retrying its API
+pub(crate) fn stalls(state: &AppState, session_state: &SessionState) -> bool {
+    is_offline(state)
+        && matches!(
+            session_state,
+            SessionState::Spawning | SessionState::Processing
+        )
+}
+
+/// Set or clear `stalled` on every session to match connectivity
+fn mark_stalled(state: &AppState) {
+    let updated: Vec<C3Session> = {
+        let mut sessions = state.sessions.write();
+        sessions
+            .values_mut()
+            .filter_map(|session| {
+                let stalled = stalls(state, &session.state);
+                (session.stalled != stalled).then(|| {
+                    session.stalled = stalled;
+                    session.clone()
+                })
+            })
+            .collect()
+    };
+    for session in updated {
+        state.events.session_updated(session);
+    }
+}
+
+/// Check connectivity, marking working sessions stalled while the machine
+/// is offline and rescanning every session once it's back
+pub async fn start_connectivity_monitor(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut monitor = Monitor::default();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Connectivity monitor shutting down");
+                break;
+            }
+        }
+        let reachable = tokio::task::spawn_blocking(probe).await.unwrap_or(true);
+        match monitor.observe(reachable) {
+            Some(Change::WentOffline) => {
+                log::warn!("Offline: working sessions are marked stalled");
+                state.offline.store(true, Ordering::Relaxed);
+            }
+            Some(Change::CameBack) => {
+                log::info!("Back online, rescanning sessions");
+                state.offline.store(false, Ordering::Relaxed);
+                // States hooks set while offline may be stale; let the
+                // scanner correct them
+                state.hook_timestamps.write().clear();
+                state.scan_now.notify_one();
+            }
+            None => {}
+        }
+        mark_stalled(&state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_after_repeated_failures_and_back_on_the_first_success() {
+        let mut monitor = Monitor::default();
+        assert_eq!(monitor.observe(false), None);
+        assert_eq!(monitor.observe(true), None);
+        assert_eq!(monitor.observe(false), None);
+        assert_eq!(monitor.observe(false), Some(Change::WentOffline));
+        assert_eq!(monitor.observe(false), None);
+        assert_eq!(monitor.observe(true), Some(Change::CameBack));
+        assert_eq!(monitor.observe(true), None);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::time_report::{self, Transition};
+use crate::SessionState;
+use chrono::{DateTime, Duration, DurationRound, Local, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+
+/// Most buckets one call returns
+const MAX_BUCKETS: u32 = 400;
+
+/// How wide each point of a series is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsBucket {
+    Hour,
+    /// A local day
+    Day,
+}
+
+/// Activity within one bucket
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsPoint {
+    pub start: DateTime<Utc>,
+    /// Sessions whose first logged transition falls in the bucket
+    pub sessions_started: u32,
+    /// Sessions that reached Complete in the bucket, each counted once
+    pub sessions_completed: u32,
+    /// Times an agent stopped to ask for permission or input
+    pub interruptions: u32,
+    /// Mean seconds an agent waited on a permission prompt or question
+    /// before being answered, for answers given in the bucket
+    pub avg_response_secs: Option<u64>,
+}
+
+/// Series for the dashboard's sparklines, oldest point first
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardStats {
+    pub bucket: StatsBucket,
+    pub points: Vec<StatsPoint>,
+    /// Share of the sessions started in the period that have reached
+    /// Complete, from 0 to 1; None when none started
+    pub completion_rate: Option<f64>,
+}
+
+fn asks_human(state: &SessionState) -> bool {
+    matches!(
+        state,
+        SessionState::AwaitingPermission | SessionState::AwaitingInput
+    )
+}
+
+/// Starts of the last `count` buckets, the last one holding `now`
+fn bucket_starts<Tz: TimeZone>(
+    bucket: StatsBucket,
+    count: u32,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<DateTime<Utc>> {
+    let count = i64::from(count);
+    match bucket {
+        StatsBucket::Hour => {
+            let hour = now.duration_trunc(Duration::hours(1)).unwrap_or(now);
+            (0..count)
+                .rev()
+                .map(|n| hour - Duration::hours(n))
+                .collect()
+        }
+        StatsBucket::Day => {
+            let today = now.with_timezone(tz).date_naive();
+            (0..count)
+                .rev()
+                .filter_map(|n| today.checked_sub_signed(Duration::days(n)))
+                .map(|date| time_report::day_start(date, tz))
+                .collect()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Tally<'a> {
+    started: u32,
+    completed: HashSet<&'a str>,
+    interruptions: u32,
+    response_secs: Vec<i64>,
+}
+
+fn stats<Tz: TimeZone>(
+    transitions: &[Transition],
+    bucket: StatsBucket,
+    count: u32,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> DashboardStats {
+    let starts = bucket_starts(bucket, count.clamp(1, MAX_BUCKETS), now, tz);
+    let index = |at: DateTime<Utc>| {
+        let after = starts.partition_point(|start| *start <= at);
+        (after > 0 && at <= now).then(|| after - 1)
+    };
+
+    let mut by_session: HashMap<&str, Vec<&Transition>> = HashMap::new();
+    for transition in transitions {
+        by_session
+            .entry(&transition.session_id)
+            .or_default()
+            .push(transition);
+    }
+
+    let mut tallies: Vec<Tally> = starts.iter().map(|_| Tally::default()).collect();
+    let (mut started, mut completed) = (0u32, 0u32);
+    for (session_id, session) in by_session.iter_mut() {
+        session.sort_by_key(|transition| transition.at);
+        if let Some(i) = session.first().and_then(|first| index(first.at)) {
+            tallies[i].started += 1;
+            started += 1;
+            completed += u32::from(
+                session
+                    .iter()
+                    .any(|transition| transition.state == SessionState::Complete),
+            );
+        }
+        for (n, transition) in session.iter().enumerate() {
+            let Some(i) = index(transition.at) else {
+                continue;
+            };
+            if transition.state == SessionState::Complete {
+                tallies[i].completed.insert(*session_id);
+            }
+            if asks_human(&transition.state) {
+                tallies[i].interruptions += 1;
+            }
+            // Back to work after asking: the human answered
+            let asked = n
+                .checked_sub(1)
+                .and_then(|prev| session.get(prev))
+                .filter(|prev| asks_human(&prev.state));
+            if let (Some(asked), SessionState::Processing) = (asked, &transition.state) {
+                tallies[i]
+                    .response_secs
+                    .push((transition.at - asked.at).num_seconds());
+            }
+        }
+    }
+
+    let points = starts
+        .iter()
+        .zip(tallies)
+        .map(|(start, tally)| StatsPoint {
+            start: *start,
+            sessions_started: tally.started,
+            sessions_completed: tally.completed.len() as u32,
+            interruptions: tally.interruptions,
+            avg_response_secs: (!tally.response_secs.is_empty()).then(|| {
+                (tally.response_secs.iter().sum::<i64>() / tally.response_secs.len() as i64) as u64
+            }),
+        })
+        .collect();
+    DashboardStats {
+        bucket,
+        points,
+        completion_rate: (started > 0).then(|| f64::from(completed) / f64::from(started)),
+    }
+}
+
+// Tauri command: Sessions started and completed, interruptions and response times per hour or day, for the last `count` buckets
+#[tauri::command]
+#[specta::specta]
+pub async fn get_dashboard_stats(
+    bucket: StatsBucket,
+    count: u32,
+) -> Result<DashboardStats, String> {
+    tokio::task::spawn_blocking(move || {
+        let now = Utc::now();
+        stats(
+            &time_report::load_transitions(now),
+            bucket,
+            count,
+            now,
+            &Local,
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn transition(session_id: &str, at: DateTime<Utc>, state: SessionState) -> Transition {
+        Transition {
+            at,
+            session_id: session_id.to_string(),
+            project_name: "api".to_string(),
+            project_path: None,
+            state,
+        }
+    }
+
+    #[test]
+    fn sessions_interruptions_and_responses_are_bucketed() {
+        let transitions = [
+            // Started before the period: not counted as started
+            transition("old", at(5, 0), SessionState::Processing),
+            transition("old", at(9, 10), SessionState::Complete),
+            transition("a", at(9, 0), SessionState::Processing),
+            transition("a", at(9, 5), SessionState::AwaitingPermission),
+            transition("a", at(9, 7), SessionState::Processing),
+            transition("a", at(9, 50), SessionState::Complete),
+            transition("a", at(10, 5), SessionState::Processing),
+            transition("a", at(10, 20), SessionState::Complete),
+            transition("b", at(10, 0), SessionState::Processing),
+            transition("b", at(10, 10), SessionState::AwaitingInput),
+            transition("b", at(10, 16), SessionState::Processing),
+        ];
+        let hourly = stats(&transitions, StatsBucket::Hour, 3, at(10, 30), &Utc);
+        let points: Vec<_> = hourly
+            .points
+            .iter()
+            .map(|point| {
+                (
+                    point.start.format("%H:%M").to_string(),
+                    point.sessions_started,
+                    point.sessions_completed,
+                    point.interruptions,
+                    point.avg_response_secs,
+                )
+            })
+            .collect();
+        assert_eq!(
+            points,
+            [
+                ("08:00".to_string(), 0, 0, 0, None),
+                ("09:00".to_string(), 1, 2, 1, Some(120)),
+                ("10:00".to_string(), 1, 1, 1, Some(360)),
+            ]
+        );
+        assert_eq!(hourly.completion_rate, Some(0.5));
+
+        let days = stats(&transitions, StatsBucket::Day, 2, at(10, 30), &Utc);
+        assert_eq!(days.points.len(), 2);
+        assert_eq!(days.points[1].sessions_started, 3);
+        assert_eq!(days.points[1].sessions_completed, 2);
+        assert_eq!(days.points[0].sessions_started, 0);
+    }
+}
@@ -0,0 +1,389 @@
+use crate::session_window::open_session_window;
+use crate::{
+    approve_permission, focus_session_id, load_session_meta, paths, query, send_input,
+    show_main_window, AppState, C3Session, SessionState,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_opener::OpenerExt;
+
+/// Per-install key that x-callback-url actions reading sessions or acting
+/// on them must pass as `key`, so web pages can't
+const KEY_FILE: &str = "x-callback-key";
+/// Callback schemes refused, so results never go to a web page
+const WEB_SCHEMES: [&str; 6] = ["http", "https", "ftp", "file", "data", "javascript"];
+
+/// Actions reachable through `c3://` URLs
+#[derive(Debug, PartialEq)]
+pub enum DeepLink {
+    /// `c3://show`
+    Show,
+    /// `c3://digest`: the weekly digest view
+    Digest,
+    /// `c3://focus/<session_id>`
+    Focus(String),
+    /// `c3://approve/<session_id>`: open the permission request in its
+    /// session window to approve, or approve it with the key; None picks
+    /// the longest-waiting one (x-callback-url only)
+    Approve(Option<String>),
+    /// x-callback-url only, with the key: sessions as JSON, passed to
+    /// `x-success` as `result`
+    ListSessions,
+    /// x-callback-url only, with the key: type `text` at the session's prompt
+    SendInput { session_id: String, text: String },
+}
+
+/// x-callback-url targets and key, for Shortcuts and other automation tools
+#[derive(Debug, Default, PartialEq)]
+pub struct Callbacks {
+    pub success: Option<String>,
+    pub error: Option<String>,
+    pub key: Option<String>,
+}
+
+/// The x-callback-url key, created on first use
+fn key() -> Result<String, String> {
+    let path = paths::config_dir().join(KEY_FILE);
+    if let Some(key) = fs::read_to_string(&path)
+        .ok()
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+    {
+        return Ok(key);
+    }
+    let key = uuid::Uuid::new_v4().simple().to_string();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, &key).map_err(|e| e.to_string())?;
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    Ok(key)
+}
+
+/// Whether `given` is this install's key
+fn is_key(given: Option<&str>) -> bool {
+    given.is_some_and(|given| key().is_ok_and(|key| key == given))
+}
+
+/// `url` if it's fine to open with results: an app's scheme like
+/// `shortcuts://`, not a web page
+fn check_callback(url: Option<String>) -> Result<Option<String>, String> {
+    let Some(url) = url else {
+        return Ok(None);
+    };
+    let scheme = url
+        .split_once(':')
+        .map(|(scheme, _)| scheme.to_ascii_lowercase())
+        .unwrap_or_default();
+    if scheme.is_empty() || WEB_SCHEMES.contains(&scheme.as_str()) {
+        return Err(format!("Callbacks must go to an app, not {}", url));
+    }
+    Ok(Some(url))
+}
+
+/// Decode `%XX` escapes (and `+` in query strings), e.g. `tmux%3Awork%3A1.0` → `tmux:work:1.0`
+pub(crate) fn percent_decode(text: &str, plus_as_space: bool) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) if plus_as_space => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+pub(crate) fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+pub(crate) fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key, true), percent_decode(value, true))
+        })
+        .collect()
+}
+
+/// `c3://<action>/<session_id>` or
+/// `c3://x-callback-url/<action>?session=<id>&text=…&x-success=…&x-error=…`
+pub fn parse(url: &str) -> Result<(DeepLink, Callbacks), String> {
+    let rest = url
+        .strip_prefix("c3://")
+        .ok_or_else(|| format!("Not a c3:// URL: {}", url))?;
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let mut params = parse_query(query);
+
+    let (path, x_callback) = match path.strip_prefix("x-callback-url/") {
+        Some(path) => (path, true),
+        None => (path, false),
+    };
+    let (action, argument) = path.split_once('/').unwrap_or((path, ""));
+    let argument = percent_decode(argument.trim_end_matches('/'), false);
+    let session_id = Some(argument)
+        .filter(|id| !id.is_empty())
+        .or_else(|| params.remove("session").filter(|id| !id.is_empty()));
+    let require_session = || {
+        session_id
+            .clone()
+            .ok_or_else(|| format!("{} needs a session id", action))
+    };
+
+    let link = match action {
+        "show" => DeepLink::Show,
+        "digest" => DeepLink::Digest,
+        "focus" => DeepLink::Focus(require_session()?),
+        "approve" if x_callback => DeepLink::Approve(session_id.clone()),
+        "approve" => DeepLink::Approve(Some(require_session()?)),
+        "list-sessions" if x_callback => DeepLink::ListSessions,
+        "send-input" if x_callback => DeepLink::SendInput {
+            session_id: require_session()?,
+            text: params
+                .remove("text")
+                .filter(|t| !t.trim().is_empty())
+                .ok_or("send-input needs text")?,
+        },
+        _ => return Err(format!("Unknown c3:// action: {}", action)),
+    };
+    let callbacks = if x_callback {
+        Callbacks {
+            success: check_callback(params.remove("x-success"))?,
+            error: check_callback(params.remove("x-error"))?,
+            key: params.remove("key"),
+        }
+    } else {
+        Callbacks::default()
+    };
+    Ok((link, callbacks))
+}
+
+/// Permission request that has waited longest
+fn oldest_permission_request(state: &AppState) -> Option<String> {
+    state
+        .sessions
+        .read()
+        .values()
+        .filter(|s| s.state == SessionState::AwaitingPermission)
+        .min_by_key(|s| s.last_activity)
+        .map(|s| s.id.clone())
+}
+
+/// Runs the action; `Some` output is handed to `x-success`. Only `keyed`
+/// links, carrying the x-callback-url key, may read sessions or act on them.
+async fn run(
+    app: &AppHandle,
+    state: Arc<AppState>,
+    link: DeepLink,
+    keyed: bool,
+) -> Result<Option<String>, String> {
+    let needs_key = matches!(link, DeepLink::ListSessions | DeepLink::SendInput { .. });
+    if needs_key && !keyed {
+        return Err("This action needs key=<your key> from Settings → Shortcuts".to_string());
+    }
+    match link {
+        DeepLink::Show => {
+            show_main_window(app);
+            Ok(None)
+        }
+        DeepLink::Digest => {
+            show_main_window(app);
+            let _ = app.emit("show-digest", ());
+            Ok(None)
+        }
+        DeepLink::Focus(session_id) => focus_session_id(state, session_id).await.map(|_| None),
+        DeepLink::Approve(session_id) => {
+            let session_id = session_id
+                .or_else(|| oldest_permission_request(&state))
+                .ok_or("No session is waiting for permission")?;
+            if keyed {
+                return tokio::task::spawn_blocking(move || {
+                    approve_permission(&state, &session_id)
+                })
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|_| None);
+            }
+            // Any page can open a link, so it only brings the request up in
+            // the session's window; approving takes a click there
+            if let Err(e) = focus_session_id(state, session_id.clone()).await {
+                log::warn!("Failed to focus {}: {}", session_id, e);
+            }
+            open_session_window(app.clone(), app.state(), session_id).map(|_| None)
+        }
+        DeepLink::ListSessions => {
+            let mut sessions: Vec<C3Session> = state.sessions.read().values().cloned().collect();
+            query::sort_sessions(&mut sessions, &load_session_meta());
+            serde_json::to_string(&sessions)
+                .map(Some)
+                .map_err(|e| e.to_string())
+        }
+        DeepLink::SendInput { session_id, text } => {
+            tokio::task::spawn_blocking(move || send_input(&state, &session_id, &text))
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|_| None)
+        }
+    }
+}
+
+/// `base` with `key=value` appended to its query string
+fn callback_url(base: &str, key: &str, value: &str) -> String {
+    let separator = if base.contains('?') { '&' } else { '?' };
+    format!("{}{}{}={}", base, separator, key, percent_encode(value))
+}
+
+fn open_callback(app: &AppHandle, url: String) {
+    if let Err(e) = app.opener().open_url(&url, None::<&str>) {
+        log::warn!("Failed to open x-callback URL: {}", e);
+    }
+}
+
+/// Handle `c3://` URLs, including the one that launched the app
+pub fn register(app: &AppHandle, state: Arc<AppState>) {
+    let app_handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            let (link, callbacks) = match parse(url.as_str()) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    log::warn!("Ignoring deep link: {}", e);
+                    continue;
+                }
+            };
+            log::info!("Deep link: {:?}", link);
+            let keyed = is_key(callbacks.key.as_deref());
+            let app_handle = app_handle.clone();
+            let state = state.clone();
+            tauri::async_runtime::spawn(async move {
+                match run(&app_handle, state, link, keyed).await {
+                    Ok(output) => {
+                        if let Some(success) = callbacks.success {
+                            let url = match output {
+                                Some(result) => callback_url(&success, "result", &result),
+                                None => success,
+                            };
+                            open_callback(&app_handle, url);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("Deep link failed: {}", e);
+                        if let Some(error) = callbacks.error {
+                            open_callback(&app_handle, callback_url(&error, "errorMessage", &e));
+                        }
+                    }
+                }
+            });
+        }
+    });
+}
+
+// Tauri command: The key x-callback-url links pass to read sessions or act on them
+#[tauri::command]
+#[specta::specta]
+pub fn get_x_callback_key() -> Result<String, String> {
+    key()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_and_encoded_session_ids() {
+        assert_eq!(parse("c3://show").unwrap().0, DeepLink::Show);
+        assert_eq!(parse("c3://digest").unwrap().0, DeepLink::Digest);
+        assert_eq!(
+            parse("c3://focus/tmux:work:1.0").unwrap().0,
+            DeepLink::Focus("tmux:work:1.0".to_string())
+        );
+        assert_eq!(
+            parse("c3://approve/tmux%3Awork%3A1.0/?src=slack")
+                .unwrap()
+                .0,
+            DeepLink::Approve(Some("tmux:work:1.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_incomplete_links() {
+        assert!(parse("c3://approve").is_err());
+        assert!(parse("c3://delete/tmux:work:1.0").is_err());
+        assert!(parse("c3://list-sessions").is_err());
+        assert!(parse("https://example.com").is_err());
+    }
+
+    #[test]
+    fn x_callback_urls_take_query_parameters() {
+        let (link, callbacks) = parse(
+            "c3://x-callback-url/send-input?session=tmux%3Awork%3A1.0&text=run+the+tests&x-success=shortcuts%3A%2F%2Fx-callback-url%2Fdone",
+        )
+        .unwrap();
+        assert_eq!(
+            link,
+            DeepLink::SendInput {
+                session_id: "tmux:work:1.0".to_string(),
+                text: "run the tests".to_string(),
+            }
+        );
+        assert_eq!(
+            callbacks.success.as_deref(),
+            Some("shortcuts://x-callback-url/done")
+        );
+        assert_eq!(callbacks.key, None);
+        let (_, callbacks) =
+            parse("c3://x-callback-url/list-sessions?key=abc&x-error=shortcuts%3A%2F%2Ffailed")
+                .unwrap();
+        assert_eq!(callbacks.key.as_deref(), Some("abc"));
+
+        // Results never go to a web page
+        assert!(
+            parse("c3://x-callback-url/list-sessions?x-success=https%3A%2F%2Fevil.example")
+                .is_err()
+        );
+        assert!(parse("c3://x-callback-url/show?x-error=HTTP%3A%2F%2Fevil.example").is_err());
+        assert!(parse("c3://x-callback-url/show?x-success=evil.example").is_err());
+
+        // "Approve Claude" with no session picks the oldest permission request
+        assert_eq!(
+            parse("c3://x-callback-url/approve").unwrap().0,
+            DeepLink::Approve(None)
+        );
+        assert_eq!(
+            callback_url("shortcuts://done?a=1", "result", "[\"x y\"]"),
+            "shortcuts://done?a=1&result=%5B%22x%20y%22%5D"
+        );
+    }
+}
@@ -0,0 +1,481 @@
+use crate::redaction::REDACTED;
+use crate::supervisor::{SubsystemHealth, Subsystems};
+use crate::{cmd, AppSettings, AppState, C3Session};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tauri::{AppHandle, Manager};
+
+/// Log lines kept in memory for diagnostic bundles
+const MAX_LOG_LINES: usize = 2000;
+/// `c3.log` is rotated to `c3.log.1` at startup beyond this size
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+/// Settings keys whose values are replaced in the bundle, matched without
+/// case or underscores. An ntfy topic URL is all it takes to post to it.
+const SECRET_KEY_WORDS: &[&str] = &[
+    "token", "secret", "password", "webhook", "apikey", "userkey", "ntfyurl",
+];
+
+/// Whether the hook server is accepting hooks
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HookServerStatus {
+    pub port: u16,
+    pub listening: bool,
+    /// Why binding the port last failed
+    pub bind_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannerHealth {
+    pub cycles: u64,
+    pub last_duration_ms: u64,
+    pub max_duration_ms: u64,
+    pub slow_cycles: u64,
+    pub pane_count: usize,
+    /// Pane changes arrive over tmux control mode rather than by polling
+    pub control_mode: bool,
+    pub last_tmux_error: Option<String>,
+    pub last_tmux_error_at: Option<DateTime<Utc>>,
+}
+
+/// The incremental reader behind transcript-based state detection
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptHealth {
+    pub followed_files: usize,
+    /// Seconds since any transcript was read; None before the first read
+    pub last_read_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsHealth {
+    pub path: String,
+    pub exists: bool,
+    /// Defaults are used in place of a settings file that doesn't parse
+    pub parse_error: Option<String>,
+}
+
+/// The state of C3's own machinery, one section per part of the health panel
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub scanner: ScannerHealth,
+    pub hook_server: HookServerStatus,
+    /// Streaming clients on the event bus
+    pub bus_subscribers: usize,
+    pub transcripts: TranscriptHealth,
+    pub settings: SettingsHealth,
+    pub subsystems: Vec<SubsystemHealth>,
+}
+
+static RECENT_LOGS: OnceLock<Mutex<VecDeque<String>>> = OnceLock::new();
+
+fn recent_logs() -> &'static Mutex<VecDeque<String>> {
+    RECENT_LOGS.get_or_init(|| Mutex::new(VecDeque::with_capacity(MAX_LOG_LINES)))
+}
+
+/// env_logger that also keeps the most recent lines for `create_diagnostic_bundle`
+/// and appends them to `c3.log` in the state directory
+struct RecordingLogger {
+    inner: env_logger::Logger,
+    file: Option<Mutex<fs::File>>,
+}
+
+impl log::Log for RecordingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.inner.matches(record) {
+            return;
+        }
+        self.inner.log(record);
+
+        let line = format!(
+            "{} {:<5} {}: {}",
+            chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Some(file) = &self.file {
+            let _ = writeln!(file.lock(), "{}", line);
+        }
+        let mut logs = recent_logs().lock();
+        if logs.len() >= MAX_LOG_LINES {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+        if let Some(file) = &self.file {
+            let _ = file.lock().flush();
+        }
+    }
+}
+
+/// Open `c3.log` for appending, moving a log over the size limit to `c3.log.1`
+fn open_log_file() -> Option<fs::File> {
+    let dir = crate::paths::state_dir();
+    fs::create_dir_all(&dir).ok()?;
+    let path = dir.join("c3.log");
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_FILE_BYTES) {
+        let _ = fs::rename(&path, dir.join("c3.log.1"));
+    }
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()
+}
+
+/// Install the app logger (RUST_LOG, default `info`)
+pub fn init_logging() {
+    let inner =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).build();
+    let max_level = inner.filter();
+    let file = open_log_file().map(Mutex::new);
+    if log::set_boxed_logger(Box::new(RecordingLogger { inner, file })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Replace the home directory prefix with `~` so bundles don't leak usernames
+fn redact_home(value: &str, home: &str) -> String {
+    if home.is_empty() {
+        return value.to_string();
+    }
+    value.replace(home, "~")
+}
+
+/// Blank out secret-looking values (by key) and home paths
+fn sanitize_value(value: &mut Value, home: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                let key = key.to_lowercase().replace('_', "");
+                let is_secret = SECRET_KEY_WORDS.iter().any(|word| key.contains(word));
+                if is_secret && !entry.is_null() && !entry.is_boolean() {
+                    *entry = Value::String(REDACTED.to_string());
+                } else {
+                    sanitize_value(entry, home);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                sanitize_value(item, home);
+            }
+        }
+        Value::String(s) => *s = redact_home(s, home),
+        _ => {}
+    }
+}
+
+/// Session list without command text and with home paths shortened
+fn redact_session(mut session: C3Session, home: &str) -> C3Session {
+    session.project_path = session.project_path.map(|p| redact_home(&p, home));
+    if let Some(action) = session.pending_action.as_mut() {
+        action.description = REDACTED.to_string();
+        if action.command.is_some() {
+            action.command = Some(REDACTED.to_string());
+        }
+        for option in &mut action.options {
+            *option = REDACTED.to_string();
+        }
+    }
+    if session.summary.is_some() {
+        session.summary = Some(REDACTED.to_string());
+    }
+    session
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = cmd(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn hook_script_sha256(path: &Path) -> Option<String> {
+    let path = path.to_str()?;
+    let output = command_output("shasum", &["-a", "256", path])?;
+    output.split_whitespace().next().map(|s| s.to_string())
+}
+
+fn write_json(dir: &Path, name: &str, value: &Value) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    fs::write(dir.join(name), json).map_err(|e| format!("Failed to write {}: {}", name, e))
+}
+
+fn write_bundle(staging: &Path, contents: &BundleContents) -> Result<(), String> {
+    fs::write(staging.join("logs.txt"), &contents.logs)
+        .map_err(|e| format!("Failed to write logs.txt: {}", e))?;
+    write_json(staging, "health.json", &contents.health)?;
+    write_json(staging, "hook-events.json", &contents.hook_events)?;
+    write_json(staging, "settings.json", &contents.settings)?;
+    write_json(staging, "environment.json", &contents.environment)?;
+    write_json(staging, "sessions.json", &contents.sessions)
+}
+
+struct BundleContents {
+    logs: String,
+    health: Value,
+    hook_events: Value,
+    settings: Value,
+    environment: Value,
+    sessions: Value,
+}
+
+fn gather(state: &AppState, app_handle: AppHandle, home: &str) -> BundleContents {
+    let redactor = state.redactor.read();
+    let logs = recent_logs()
+        .lock()
+        .iter()
+        .map(|line| redact_home(&redactor.redact(line), home))
+        .collect::<Vec<_>>()
+        .join("\n");
+    drop(redactor);
+
+    let mut hook_events = serde_json::json!({
+        "hook_events": *state.hook_events.read(),
+        "state_diagnostics": *state.state_diagnostics.read(),
+        "scan_stats": *state.scan_stats.read(),
+    });
+    sanitize_value(&mut hook_events, home);
+
+    let mut settings = serde_json::to_value(crate::load_settings()).unwrap_or(Value::Null);
+    sanitize_value(&mut settings, home);
+
+    let mut health = app_handle
+        .try_state::<Subsystems>()
+        .and_then(|subsystems| serde_json::to_value(collect_diagnostics(state, &subsystems)).ok())
+        .unwrap_or(Value::Null);
+    sanitize_value(&mut health, home);
+
+    let hook_script = PathBuf::from(home).join(".local/bin/c3-hook.sh");
+    let environment = serde_json::json!({
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "tmux_version": command_output("tmux", &["-V"]),
+        "hook_script_sha256": hook_script_sha256(&hook_script),
+        "hook_status": crate::check_hook_status(app_handle),
+    });
+
+    let sessions: Vec<C3Session> = state
+        .sessions
+        .read()
+        .values()
+        .cloned()
+        .map(|s| redact_session(s, home))
+        .collect();
+    let sessions = serde_json::to_value(sessions).unwrap_or(Value::Null);
+
+    BundleContents {
+        logs,
+        health,
+        hook_events,
+        settings,
+        environment,
+        sessions,
+    }
+}
+
+fn settings_health(path: &Path) -> SettingsHealth {
+    let parse_error = fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<AppSettings>(&content).err())
+        .map(|e| e.to_string());
+    SettingsHealth {
+        path: path.display().to_string(),
+        exists: path.exists(),
+        parse_error,
+    }
+}
+
+fn collect_diagnostics(state: &AppState, subsystems: &Subsystems) -> Diagnostics {
+    let scan_stats = state.scan_stats.read().clone();
+    let (followed_files, last_read) = state.jsonl_tails.status();
+    Diagnostics {
+        scanner: ScannerHealth {
+            cycles: scan_stats.cycles,
+            last_duration_ms: scan_stats.last_duration_ms,
+            max_duration_ms: scan_stats.max_duration_ms,
+            slow_cycles: scan_stats.slow_cycles,
+            pane_count: scan_stats.last_pane_count,
+            control_mode: state.tmux_control.is_live(),
+            last_tmux_error: scan_stats.last_tmux_error,
+            last_tmux_error_at: scan_stats.last_tmux_error_at,
+        },
+        hook_server: state.hook_server.read().clone(),
+        bus_subscribers: state.bus.lag_stats().len(),
+        transcripts: TranscriptHealth {
+            followed_files,
+            last_read_secs: last_read.map(|age| age.as_secs()),
+        },
+        settings: settings_health(&crate::settings_path()),
+        subsystems: subsystems.health(),
+    }
+}
+
+/// Write the bundle to `~/Downloads/c3-diagnostics-<timestamp>.zip`
+fn create_bundle(state: &AppState, app_handle: AppHandle) -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME is not set".to_string())?;
+    let contents = gather(state, app_handle, &home);
+
+    let name = format!(
+        "c3-diagnostics-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let downloads = PathBuf::from(&home).join("Downloads");
+    fs::create_dir_all(&downloads).map_err(|e| e.to_string())?;
+    let zip_path = downloads.join(format!("{}.zip", name));
+
+    let temp = std::env::temp_dir();
+    let staging = temp.join(&name);
+    fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let result = write_bundle(&staging, &contents).and_then(|_| {
+        let output = cmd("zip")
+            .current_dir(&temp)
+            .arg("-q")
+            .arg("-r")
+            .arg(&zip_path)
+            .arg(&name)
+            .output()
+            .map_err(|e| format!("Failed to run zip: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "zip failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    });
+    let _ = fs::remove_dir_all(&staging);
+    result.map(|_| zip_path)
+}
+
+// Tauri command: Health of the scanner, hook server, event bus, transcript reader, settings file and background tasks
+#[tauri::command]
+#[specta::specta]
+pub fn get_diagnostics(
+    state: tauri::State<'_, Arc<AppState>>,
+    subsystems: tauri::State<'_, Subsystems>,
+) -> Diagnostics {
+    collect_diagnostics(&state, &subsystems)
+}
+
+// Tauri command: Export logs, hook events and redacted state as a zip for bug reports
+#[tauri::command]
+#[specta::specta]
+pub async fn create_diagnostic_bundle(
+    state: tauri::State<'_, Arc<AppState>>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let state = state.inner().clone();
+    let path = tokio::task::spawn_blocking(move || create_bundle(&state, app_handle))
+        .await
+        .map_err(|e| e.to_string())??;
+    log::info!("Wrote diagnostic bundle to {}", path.display());
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PendingAction, SessionState};
+
+    #[test]
+    fn settings_secrets_and_home_paths_are_redacted() {
+        let mut settings = serde_json::json!({
+            "terminalApp": "Ghostty",
+            "slackWebhookUrl": "https://hooks.slack.com/services/T000/B000/XXXX",
+            "apiToken": null,
+            "permissionSound": { "enabled": true, "sound": "/Users/alice/sounds/ping.aiff" },
+            "push": {
+                "service": "pushover",
+                "ntfy_url": "https://ntfy.sh/c3-alice-8f2k",
+                "ntfy_token": "tk_abc",
+                "pushover_user_key": "uQiRzpo4DXghDmr9QzzfQu27cmVRsG",
+                "pushover_app_token": "azGDORePK8gMaC0QOYAMyEEuzJnyUi",
+            },
+            "email": { "smtp_user": "alice", "smtp_password": "hunter2" },
+        });
+        sanitize_value(&mut settings, "/Users/alice");
+        assert_eq!(settings["terminalApp"], "Ghostty");
+        assert_eq!(settings["slackWebhookUrl"], REDACTED);
+        assert!(settings["apiToken"].is_null());
+        assert_eq!(settings["permissionSound"]["enabled"], true);
+        assert_eq!(settings["permissionSound"]["sound"], "~/sounds/ping.aiff");
+        assert_eq!(settings["push"]["service"], "pushover");
+        for key in [
+            "ntfy_url",
+            "ntfy_token",
+            "pushover_user_key",
+            "pushover_app_token",
+        ] {
+            assert_eq!(settings["push"][key], REDACTED, "{}", key);
+        }
+        assert_eq!(settings["email"]["smtp_password"], REDACTED);
+    }
+
+    #[test]
+    fn settings_files_that_do_not_parse_are_reported() {
+        let dir = std::env::temp_dir().join(format!("c3-diagnostics-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+
+        let missing = settings_health(&path);
+        assert!(!missing.exists);
+        assert_eq!(missing.parse_error, None);
+
+        fs::write(&path, r#"{"scan_interval_secs": "#).unwrap();
+        let broken = settings_health(&path);
+        assert!(broken.exists);
+        assert!(broken.parse_error.is_some());
+
+        fs::write(&path, "{}").unwrap();
+        assert_eq!(settings_health(&path).parse_error, None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn session_commands_are_redacted() {
+        let session = C3Session {
+            project_path: Some("/Users/alice/src/c3".to_string()),
+            state: SessionState::AwaitingPermission,
+            tmux_target: Some("main:0.0".to_string()),
+            pending_action: Some(PendingAction {
+                action_type: "permission".to_string(),
+                description: "Bash: curl -H 'Authorization: Bearer abc'".to_string(),
+                tool: Some("Bash".to_string()),
+                command: Some("curl -H 'Authorization: Bearer abc'".to_string()),
+                options: Vec::new(),
+            }),
+            ..C3Session::test("tmux:main:0.0")
+        };
+        let redacted = redact_session(session, "/Users/alice");
+        assert_eq!(redacted.project_path.as_deref(), Some("~/src/c3"));
+        let action = redacted.pending_action.unwrap();
+        assert_eq!(action.tool.as_deref(), Some("Bash"));
+        assert_eq!(action.command.as_deref(), Some(REDACTED));
+        assert_eq!(action.description, REDACTED);
+    }
+}
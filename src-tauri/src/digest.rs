@@ -0,0 +1,507 @@
+use crate::time_report::{self, ReportRange, Transition};
+use crate::{cmd, load_settings, paths, slack, tmux_scanner, SessionState};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Timelike, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// How often C3 checks whether the digest is due
+const CHECK_INTERVAL_SECS: u64 = 600;
+/// Local hour on Monday from which last week's digest goes out
+const DIGEST_HOUR: u32 = 9;
+/// Holds the first day of the last week a digest was sent for, in the state dir
+const SENT_FILE: &str = "digest-sent";
+const TOP_PROJECTS: usize = 3;
+const LONGEST_WAITS: usize = 3;
+
+/// Time agents spent on a project over the week
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectTime {
+    pub project_name: String,
+    pub project_path: Option<String>,
+    pub active_secs: u64,
+    pub waiting_secs: u64,
+}
+
+/// A stretch a session spent waiting on a permission prompt or question
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Wait {
+    pub session_id: String,
+    pub project_name: String,
+    pub started: DateTime<Utc>,
+    pub secs: u64,
+}
+
+/// Tokens Claude Code sessions used, read from their transcripts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTotals {
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_creation: u64,
+}
+
+/// A week of agent activity, Monday to Sunday in local time
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WeeklyDigest {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Sessions with any state change in the week
+    pub sessions: u32,
+    pub active_secs: u64,
+    pub waiting_secs: u64,
+    /// Projects agents worked on longest, longest first
+    pub top_projects: Vec<ProjectTime>,
+    pub longest_waits: Vec<Wait>,
+    pub tokens: TokenTotals,
+}
+
+/// Monday of the week before the one `today` is in
+fn last_week_start(today: NaiveDate) -> NaiveDate {
+    today - Duration::days(i64::from(today.weekday().num_days_from_monday()) + 7)
+}
+
+fn asks_human(state: &SessionState) -> bool {
+    matches!(
+        state,
+        SessionState::AwaitingPermission | SessionState::AwaitingInput
+    )
+}
+
+/// Waits that started within `start..end` and were seen to end, longest first
+fn longest_waits(
+    transitions: &[Transition],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<Wait> {
+    let mut by_session: HashMap<&str, Vec<&Transition>> = HashMap::new();
+    for transition in transitions {
+        by_session
+            .entry(&transition.session_id)
+            .or_default()
+            .push(transition);
+    }
+    let mut waits: Vec<Wait> = Vec::new();
+    for session in by_session.values_mut() {
+        session.sort_by_key(|transition| transition.at);
+        for pair in session.windows(2) {
+            let (asked, next) = (pair[0], pair[1]);
+            if asks_human(&asked.state) && asked.at >= start && asked.at < end {
+                waits.push(Wait {
+                    session_id: asked.session_id.clone(),
+                    project_name: asked.project_name.clone(),
+                    started: asked.at,
+                    secs: (next.at - asked.at).num_seconds().max(0) as u64,
+                });
+            }
+        }
+    }
+    waits.sort_by(|a, b| b.secs.cmp(&a.secs).then(a.started.cmp(&b.started)));
+    waits.truncate(LONGEST_WAITS);
+    waits
+}
+
+/// Add up `message.usage` of Claude transcript lines from `start..end`,
+/// each message once (its content blocks repeat the usage)
+fn add_tokens(
+    path: &Path,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    seen: &mut HashSet<String>,
+    totals: &mut TokenTotals,
+) {
+    let Ok(file) = fs::File::open(path) else {
+        return;
+    };
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        if !line.contains("\"usage\"") {
+            continue;
+        }
+        let Ok(entry) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let at = entry["timestamp"]
+            .as_str()
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .map(|at| at.with_timezone(&Utc));
+        if !at.is_some_and(|at| at >= start && at < end) {
+            continue;
+        }
+        let message = &entry["message"];
+        let id = message["id"].as_str().unwrap_or_default();
+        if !id.is_empty() && !seen.insert(id.to_string()) {
+            continue;
+        }
+        let usage = &message["usage"];
+        let count = |key: &str| usage[key].as_u64().unwrap_or(0);
+        totals.input += count("input_tokens");
+        totals.output += count("output_tokens");
+        totals.cache_read += count("cache_read_input_tokens");
+        totals.cache_creation += count("cache_creation_input_tokens");
+    }
+}
+
+/// Transcripts under `dir` (one folder per project) written to since `start`
+fn transcripts_since(dir: &Path, start: DateTime<Utc>) -> Vec<PathBuf> {
+    let Ok(projects) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    projects
+        .flatten()
+        .filter_map(|project| fs::read_dir(project.path()).ok())
+        .flat_map(|files| files.flatten())
+        .map(|file| file.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .filter(|path| {
+            fs::metadata(path)
+                .and_then(|meta| meta.modified())
+                .is_ok_and(|modified| DateTime::<Utc>::from(modified) >= start)
+        })
+        .collect()
+}
+
+fn digest<Tz: TimeZone>(
+    transitions: &[Transition],
+    transcripts: &[PathBuf],
+    week_start: NaiveDate,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> WeeklyDigest {
+    let end = week_start + Duration::days(6);
+    let start_at = time_report::day_start(week_start, tz);
+    let end_at = time_report::day_start(end + Duration::days(1), tz);
+    let range = ReportRange {
+        start: week_start,
+        end,
+    };
+
+    let mut projects: HashMap<String, ProjectTime> = HashMap::new();
+    for row in time_report::report(transitions, &range, now, tz) {
+        let key = row.project_path.clone().unwrap_or(row.project_name.clone());
+        let project = projects.entry(key).or_insert(ProjectTime {
+            project_name: row.project_name,
+            project_path: row.project_path,
+            active_secs: 0,
+            waiting_secs: 0,
+        });
+        project.active_secs += row.active_secs;
+        project.waiting_secs += row.waiting_secs;
+    }
+    let mut top_projects: Vec<ProjectTime> = projects.into_values().collect();
+    let active_secs = top_projects.iter().map(|p| p.active_secs).sum();
+    let waiting_secs = top_projects.iter().map(|p| p.waiting_secs).sum();
+    top_projects.sort_by(|a, b| {
+        b.active_secs
+            .cmp(&a.active_secs)
+            .then_with(|| a.project_name.cmp(&b.project_name))
+    });
+    top_projects.truncate(TOP_PROJECTS);
+
+    let sessions: HashSet<&str> = transitions
+        .iter()
+        .filter(|transition| transition.at >= start_at && transition.at < end_at)
+        .map(|transition| transition.session_id.as_str())
+        .collect();
+
+    let mut tokens = TokenTotals::default();
+    let mut seen = HashSet::new();
+    for path in transcripts {
+        add_tokens(path, start_at, end_at, &mut seen, &mut tokens);
+    }
+
+    WeeklyDigest {
+        start: week_start,
+        end,
+        sessions: sessions.len() as u32,
+        active_secs,
+        waiting_secs,
+        top_projects,
+        longest_waits: longest_waits(transitions, start_at, end_at),
+        tokens,
+    }
+}
+
+/// Last week's digest, as of now
+fn last_week() -> WeeklyDigest {
+    let now = Utc::now();
+    let week_start = last_week_start(now.with_timezone(&Local).date_naive());
+    let since = time_report::day_start(week_start, &Local);
+    digest(
+        &time_report::load_transitions(now),
+        &transcripts_since(&tmux_scanner::claude_projects_dir(), since),
+        week_start,
+        now,
+        &Local,
+    )
+}
+
+fn hours(secs: u64) -> String {
+    format!("{:.1} h", secs as f64 / 3600.0)
+}
+
+fn duration(secs: u64) -> String {
+    match (secs / 3600, secs / 60 % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+/// `1.2M`, `340k` or `950`
+fn tokens(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{}k", count / 1000),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
+
+/// The digest as lines of text: the headline first
+fn summary_lines(digest: &WeeklyDigest) -> Vec<String> {
+    let mut lines = vec![format!(
+        "{} sessions, {} working, {} waiting on you",
+        digest.sessions,
+        hours(digest.active_secs),
+        hours(digest.waiting_secs)
+    )];
+    if !digest.top_projects.is_empty() {
+        let projects: Vec<String> = digest
+            .top_projects
+            .iter()
+            .map(|p| format!("{} {}", p.project_name, hours(p.active_secs)))
+            .collect();
+        lines.push(format!("Top projects: {}", projects.join(", ")));
+    }
+    let t = &digest.tokens;
+    if t.input + t.output + t.cache_read + t.cache_creation > 0 {
+        lines.push(format!(
+            "Claude tokens: {} in, {} out, {} from cache",
+            tokens(t.input + t.cache_creation),
+            tokens(t.output),
+            tokens(t.cache_read)
+        ));
+    }
+    if let Some(wait) = digest.longest_waits.first() {
+        lines.push(format!(
+            "Longest wait: {} on {}",
+            duration(wait.secs),
+            wait.project_name
+        ));
+    }
+    lines
+}
+
+/// Notify with the headline, opening the digest view on click, and post the
+/// whole digest to Slack when a webhook is set
+fn deliver(digest: &WeeklyDigest) -> Result<(), String> {
+    let lines = summary_lines(digest);
+    let subtitle = format!("Week of {}", digest.start.format("%b %-d"));
+    let mut notifier = cmd("terminal-notifier");
+    notifier
+        .args(["-title", "c3 — Weekly digest"])
+        .args(["-subtitle", &subtitle])
+        .args(["-message", &lines[0]])
+        .args(["-open", "c3://digest"]);
+    let icon_path = paths::config_dir().join("icon.png");
+    if icon_path.exists() {
+        notifier.arg("-contentImage").arg(&icon_path);
+    }
+    if let Err(e) = notifier.spawn() {
+        log::warn!("Failed to send the weekly digest notification: {}", e);
+    }
+
+    let webhook = load_settings().slack_webhook_url;
+    if let Some(webhook) = webhook.filter(|url| !url.trim().is_empty()) {
+        let text = format!("*C3 weekly digest* — {}\n{}", subtitle, lines.join("\n"));
+        slack::post(webhook.trim(), &text)?;
+    }
+    Ok(())
+}
+
+fn sent_path() -> PathBuf {
+    paths::state_dir().join(SENT_FILE)
+}
+
+/// The week a digest was last sent for
+fn last_sent() -> Option<NaiveDate> {
+    fs::read_to_string(sent_path()).ok()?.trim().parse().ok()
+}
+
+/// Whether last week's digest is due: from Monday at `DIGEST_HOUR` until sent
+fn due(now: DateTime<Local>, last_sent: Option<NaiveDate>) -> Option<NaiveDate> {
+    let week_start = last_week_start(now.date_naive());
+    let started = now.weekday().num_days_from_monday() > 0 || now.hour() >= DIGEST_HOUR;
+    (started && last_sent != Some(week_start)).then_some(week_start)
+}
+
+/// Send last week's digest on Monday morning, or at the first check after
+/// it if C3 wasn't running, when `weekly_digest` is on
+pub async fn start_weekly_digest(mut shutdown: tokio::sync::watch::Receiver<bool>) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Weekly digest shutting down");
+                break;
+            }
+        }
+        if !load_settings().weekly_digest {
+            continue;
+        }
+        let Some(week_start) = due(Local::now(), last_sent()) else {
+            continue;
+        };
+        let sent = tokio::task::spawn_blocking(move || {
+            let digest = last_week();
+            log::info!("Sending the weekly digest for {}", week_start);
+            // Marked sent even when Slack fails, so it isn't retried every check
+            let _ = fs::create_dir_all(paths::state_dir());
+            if let Err(e) = fs::write(sent_path(), week_start.to_string()) {
+                log::warn!("Failed to record the weekly digest as sent: {}", e);
+            }
+            deliver(&digest)
+        })
+        .await;
+        if let Ok(Err(e)) = sent {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+// Tauri command: Last week's sessions, top projects, tokens and longest waits
+#[tauri::command]
+#[specta::specta]
+pub async fn get_weekly_digest() -> Result<WeeklyDigest, String> {
+    tokio::task::spawn_blocking(last_week)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// Tauri command: Send last week's digest now, as a notification and to Slack
+#[tauri::command]
+#[specta::specta]
+pub async fn send_weekly_digest() -> Result<WeeklyDigest, String> {
+    tokio::task::spawn_blocking(|| {
+        let digest = last_week();
+        deliver(&digest)?;
+        Ok(digest)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn transition(
+        session_id: &str,
+        project: &str,
+        at: DateTime<Utc>,
+        state: SessionState,
+    ) -> Transition {
+        Transition {
+            at,
+            session_id: session_id.to_string(),
+            project_name: project.to_string(),
+            project_path: Some(format!("/src/{}", project)),
+            state,
+        }
+    }
+
+    #[test]
+    fn last_week_is_summed_from_transitions_and_transcripts() {
+        // 2026-03-09 is a Monday
+        let today = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+        let week_start = last_week_start(today);
+        assert_eq!(week_start, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap());
+
+        let transitions = [
+            transition("a", "api", at(2, 9, 0), SessionState::Processing),
+            transition("a", "api", at(2, 10, 0), SessionState::AwaitingPermission),
+            transition("a", "api", at(2, 10, 45), SessionState::Processing),
+            transition("a", "api", at(2, 11, 0), SessionState::Complete),
+            transition("b", "web", at(4, 9, 0), SessionState::Processing),
+            transition("b", "web", at(4, 9, 30), SessionState::AwaitingInput),
+            transition("b", "web", at(4, 9, 40), SessionState::Processing),
+            transition("b", "web", at(4, 9, 50), SessionState::Complete),
+            // This week
+            transition("c", "docs", at(9, 9, 0), SessionState::Processing),
+        ];
+
+        let dir = std::env::temp_dir().join(format!("c3-digest-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("-src-api")).unwrap();
+        let transcript = dir.join("-src-api").join("a.jsonl");
+        let line = |at: &str, id: &str| {
+            format!(
+                "{{\"type\":\"assistant\",\"timestamp\":\"{}\",\"message\":{{\"id\":\"{}\",\"usage\":{{\"input_tokens\":10,\"output_tokens\":500,\"cache_read_input_tokens\":2000}}}}}}\n",
+                at, id
+            )
+        };
+        fs::write(
+            &transcript,
+            line("2026-03-02T09:10:00Z", "msg_1")
+                + &line("2026-03-02T09:10:01Z", "msg_1")
+                + &line("2026-03-02T09:20:00Z", "msg_2")
+                + &line("2026-03-09T09:00:00Z", "msg_3"),
+        )
+        .unwrap();
+        let transcripts = transcripts_since(&dir, at(1, 0, 0));
+        assert_eq!(transcripts, [transcript]);
+
+        let digest = digest(&transitions, &transcripts, week_start, at(11, 12, 0), &Utc);
+        let _ = fs::remove_dir_all(&dir);
+        assert_eq!(digest.sessions, 2);
+        assert_eq!(digest.active_secs, (60 + 15 + 30 + 10) * 60);
+        assert_eq!(digest.waiting_secs, 45 * 60);
+        assert_eq!(digest.top_projects[0].project_name, "api");
+        assert_eq!(digest.longest_waits[0].secs, 45 * 60);
+        assert_eq!(digest.longest_waits[1].project_name, "web");
+        assert_eq!(
+            digest.tokens,
+            TokenTotals {
+                input: 20,
+                output: 1000,
+                cache_read: 4000,
+                cache_creation: 0,
+            }
+        );
+        assert_eq!(
+            summary_lines(&digest),
+            [
+                "2 sessions, 1.9 h working, 0.8 h waiting on you",
+                "Top projects: api 1.2 h, web 0.7 h",
+                "Claude tokens: 20 in, 1k out, 4k from cache",
+                "Longest wait: 45 min on api",
+            ]
+        );
+    }
+
+    #[test]
+    fn the_digest_is_due_from_monday_morning_until_sent() {
+        let local = |day: u32, hour: u32| {
+            Local
+                .with_ymd_and_hms(2026, 3, day, hour, 0, 0)
+                .earliest()
+                .unwrap()
+        };
+        let last_week = NaiveDate::from_ymd_opt(2026, 3, 2);
+        assert_eq!(due(local(9, 8), None), None);
+        assert_eq!(due(local(9, 9), None), last_week);
+        assert_eq!(due(local(11, 7), None), last_week);
+        assert_eq!(due(local(11, 7), last_week), None);
+    }
+}
@@ -0,0 +1,417 @@
+use crate::sla::{self, SlaState};
+use crate::{
+    cmd, load_session_meta, load_settings, notifications_muted, paths, AppState, SessionState,
+};
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::Arc;
+
+const CHECK_INTERVAL_SECS: u64 = 60;
+/// Least time between two emails; sessions going overdue in between wait
+/// for the next one
+const MIN_GAP_MINUTES: i64 = 15;
+const SEND_TIMEOUT_SECS: &str = "30";
+
+/// How emails leave this machine
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTransport {
+    /// The local `sendmail` (Postfix, msmtp, ...)
+    #[default]
+    Sendmail,
+    /// An SMTP server, through curl
+    Smtp,
+}
+
+/// Email when sessions have waited on you too long
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct EmailSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub to: String,
+    /// Sender address; sendmail picks one when empty
+    #[serde(default)]
+    pub from: String,
+    #[serde(default)]
+    pub transport: EmailTransport,
+    /// e.g. `smtps://smtp.example.com:465` or `smtp://localhost:25`
+    #[serde(default)]
+    pub smtp_url: String,
+    /// With a user, the connection must be encrypted
+    #[serde(default)]
+    pub smtp_user: String,
+    #[serde(default)]
+    pub smtp_password: String,
+    /// Minutes a session waits for you before it is emailed about
+    #[serde(default = "default_after_minutes")]
+    pub after_minutes: u32,
+}
+
+fn default_after_minutes() -> u32 {
+    30
+}
+
+impl Default for EmailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            to: String::new(),
+            from: String::new(),
+            transport: EmailTransport::Sendmail,
+            smtp_url: String::new(),
+            smtp_user: String::new(),
+            smtp_password: String::new(),
+            after_minutes: default_after_minutes(),
+        }
+    }
+}
+
+/// A session waiting for you
+#[derive(Debug, Clone, PartialEq)]
+struct Waiting {
+    session_id: String,
+    project_name: String,
+    project_path: Option<String>,
+    permission: bool,
+    /// What it asked, redacted
+    asked: Option<String>,
+    since: DateTime<Utc>,
+}
+
+/// Which sessions were emailed about, and which are due in the next email
+#[derive(Default)]
+struct Mailer {
+    /// The stay (by when it began) each session was last emailed for
+    emailed: HashMap<String, DateTime<Utc>>,
+    pending: Vec<Waiting>,
+    last_sent: Option<DateTime<Utc>>,
+}
+
+impl Mailer {
+    /// Sessions to email about now: the ones that went past `after` since
+    /// the last email, once `MIN_GAP_MINUTES` have passed
+    fn check(
+        &mut self,
+        waiting: Vec<Waiting>,
+        after: Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<Waiting> {
+        self.emailed.retain(|id, since| {
+            waiting
+                .iter()
+                .any(|w| &w.session_id == id && w.since == *since)
+        });
+        self.pending.retain(|pending| waiting.contains(pending));
+        for session in waiting {
+            if now - session.since >= after && !self.emailed.contains_key(&session.session_id) {
+                self.emailed
+                    .insert(session.session_id.clone(), session.since);
+                self.pending.push(session);
+            }
+        }
+        let gap_over = self
+            .last_sent
+            .is_none_or(|sent| now - sent >= Duration::minutes(MIN_GAP_MINUTES));
+        if self.pending.is_empty() || !gap_over {
+            return Vec::new();
+        }
+        self.last_sent = Some(now);
+        std::mem::take(&mut self.pending)
+    }
+}
+
+fn waiting_sessions(state: &AppState) -> Vec<Waiting> {
+    let meta = load_session_meta();
+    let redactor = state.redactor.read();
+    let sessions: Vec<_> = state.sessions.read().values().cloned().collect();
+    sessions
+        .into_iter()
+        .filter(|session| SlaState::of(&session.state) == Some(SlaState::Waiting))
+        .filter(|session| !notifications_muted(&meta, session) && !session.stalled)
+        .filter_map(|session| {
+            let since =
+                sla::entered_at(&state.timeline.for_session(&session.id), SlaState::Waiting)?;
+            let asked = session.pending_action.clone().map(|action| {
+                let action = redactor.redact_action(action);
+                let what = action.command.unwrap_or(action.description);
+                match action.tool {
+                    Some(tool) => format!("{}: {}", tool, what),
+                    None => what,
+                }
+            });
+            Some(Waiting {
+                permission: session.state == SessionState::AwaitingPermission,
+                session_id: session.id,
+                project_name: session.project_name,
+                project_path: session.project_path,
+                asked,
+                since,
+            })
+        })
+        .collect()
+}
+
+fn minutes(since: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match (now - since).num_minutes() {
+        m if m < 60 => format!("{} min", m),
+        m => format!("{} h {} min", m / 60, m % 60),
+    }
+}
+
+/// Subject and plain-text body
+fn compose(sessions: &[Waiting], now: DateTime<Utc>) -> (String, String) {
+    let subject = match sessions {
+        [one] => format!("C3: {} is waiting for you", one.project_name),
+        _ => format!("C3: {} sessions are waiting for you", sessions.len()),
+    };
+    let mut body = String::new();
+    for session in sessions {
+        let what = if session.permission {
+            "needs your permission"
+        } else {
+            "is waiting for your input"
+        };
+        body.push_str(&format!(
+            "{} {} ({})\n",
+            session.project_name,
+            what,
+            minutes(session.since, now)
+        ));
+        if let Some(asked) = &session.asked {
+            body.push_str(&format!("  {}\n", asked));
+        }
+        if let Some(path) = &session.project_path {
+            body.push_str(&format!("  {}\n", path));
+        }
+        body.push('\n');
+    }
+    body.push_str("Sent by C3 because these sessions waited longer than your email threshold.\n");
+    (subject, body)
+}
+
+/// An RFC 5322 message, with the local line endings sendmail expects
+fn message(email: &EmailSettings, subject: &str, body: &str) -> String {
+    let mut headers = vec![
+        format!("To: {}", email.to.trim()),
+        format!("Subject: {}", subject),
+        format!("Date: {}", Local::now().to_rfc2822()),
+        "MIME-Version: 1.0".to_string(),
+        "Content-Type: text/plain; charset=utf-8".to_string(),
+        "Content-Transfer-Encoding: 8bit".to_string(),
+    ];
+    if !email.from.trim().is_empty() {
+        headers.insert(0, format!("From: {}", email.from.trim()));
+    }
+    format!("{}\n\n{}", headers.join("\n"), body)
+}
+
+fn run(mut command: std::process::Command, input: &[u8]) -> Result<(), String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to send email: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to send email: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// curl reads the message from a file so stdin can carry the credentials,
+/// keeping them out of the process list
+fn send_smtp(email: &EmailSettings, message: &str) -> Result<(), String> {
+    if email.smtp_url.trim().is_empty() || email.from.trim().is_empty() {
+        return Err("SMTP needs a server URL and a from address".to_string());
+    }
+    let dir = paths::state_dir();
+    let _ = fs::create_dir_all(&dir);
+    let path = dir.join(format!("email-{}.eml", std::process::id()));
+    fs::write(&path, message).map_err(|e| e.to_string())?;
+
+    let mut curl = cmd("curl");
+    // --crlf: SMTP wants CRLF line endings
+    curl.args(["-sS", "--crlf", "--max-time", SEND_TIMEOUT_SECS, "-K", "-"])
+        .arg("--url")
+        .arg(email.smtp_url.trim())
+        .arg("--mail-from")
+        .arg(email.from.trim())
+        .arg("--mail-rcpt")
+        .arg(email.to.trim())
+        .arg("-T")
+        .arg(&path);
+    let mut config = String::new();
+    if !email.smtp_user.is_empty() {
+        curl.arg("--ssl-reqd");
+        let user = format!("{}:{}", email.smtp_user, email.smtp_password);
+        config = format!(
+            "user = \"{}\"\n",
+            user.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+    }
+    let sent = run(curl, config.as_bytes());
+    let _ = fs::remove_file(&path);
+    sent
+}
+
+fn send(email: &EmailSettings, subject: &str, body: &str) -> Result<(), String> {
+    if email.to.trim().is_empty() {
+        return Err("No email address to send to".to_string());
+    }
+    let message = message(email, subject, body);
+    match email.transport {
+        EmailTransport::Sendmail => {
+            let mut sendmail = cmd("sendmail");
+            sendmail.args(["-t", "-i"]);
+            if !email.from.trim().is_empty() {
+                sendmail.arg("-f").arg(email.from.trim());
+            }
+            run(sendmail, message.as_bytes())
+        }
+        EmailTransport::Smtp => send_smtp(email, &message),
+    }
+}
+
+/// Email about sessions that have waited on you longer than
+/// `after_minutes`, several to a message
+pub async fn start_email_notifier(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut mailer = Mailer::default();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Email notifier shutting down");
+                break;
+            }
+        }
+        let email = load_settings().email;
+        if !email.enabled || email.to.trim().is_empty() {
+            mailer = Mailer::default();
+            continue;
+        }
+        let state_check = state.clone();
+        mailer = tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let after = Duration::minutes(i64::from(email.after_minutes));
+            let due = mailer.check(waiting_sessions(&state_check), after, now);
+            if !due.is_empty() {
+                let (subject, body) = compose(&due, now);
+                match send(&email, &subject, &body) {
+                    Ok(()) => log::info!("Emailed about {} waiting session(s)", due.len()),
+                    Err(e) => log::warn!("{}", e),
+                }
+            }
+            mailer
+        })
+        .await
+        .unwrap_or_default();
+    }
+}
+
+// Tauri command: Send a test email with these (possibly unsaved) settings
+#[tauri::command]
+#[specta::specta]
+pub async fn test_email(email: EmailSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        send(
+            &email,
+            "C3: test email",
+            "C3 will email you here when sessions wait on you too long.\n",
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap() + Duration::minutes(i64::from(minute))
+    }
+
+    fn waiting(session_id: &str, since: DateTime<Utc>) -> Waiting {
+        Waiting {
+            session_id: session_id.to_string(),
+            project_name: session_id.to_string(),
+            project_path: Some(format!("/src/{}", session_id)),
+            permission: true,
+            asked: Some("Bash: npm test".to_string()),
+            since,
+        }
+    }
+
+    fn ids(sessions: &[Waiting]) -> Vec<&str> {
+        sessions.iter().map(|s| s.session_id.as_str()).collect()
+    }
+
+    #[test]
+    fn overdue_sessions_are_batched_and_emailed_once_per_wait() {
+        let after = Duration::minutes(30);
+        let mut mailer = Mailer::default();
+        let (api, web) = (waiting("api", at(0)), waiting("web", at(10)));
+
+        assert!(mailer
+            .check(vec![api.clone(), web.clone()], after, at(20))
+            .is_empty());
+        assert_eq!(
+            ids(&mailer.check(vec![api.clone(), web.clone()], after, at(30))),
+            ["api"]
+        );
+        // web goes overdue within the gap, so it waits for the next email
+        assert!(mailer
+            .check(vec![api.clone(), web.clone()], after, at(40))
+            .is_empty());
+        assert_eq!(
+            ids(&mailer.check(vec![api.clone(), web.clone()], after, at(45))),
+            ["web"]
+        );
+        assert!(mailer
+            .check(vec![api.clone(), web.clone()], after, at(90))
+            .is_empty());
+
+        // api was answered and is waiting again
+        let api_again = waiting("api", at(100));
+        let gamma = waiting("gamma", at(95));
+        assert_eq!(
+            ids(&mailer.check(vec![api_again, gamma], after, at(130))),
+            ["api", "gamma"]
+        );
+    }
+
+    #[test]
+    fn one_message_lists_every_session() {
+        let (subject, body) = compose(&[waiting("api", at(0)), waiting("web", at(50))], at(90));
+        assert_eq!(subject, "C3: 2 sessions are waiting for you");
+        assert!(body
+            .starts_with("api needs your permission (1 h 30 min)\n  Bash: npm test\n  /src/api\n"));
+        assert!(body.contains("web needs your permission (40 min)"));
+
+        let email = EmailSettings {
+            to: "me@example.com".to_string(),
+            from: "c3@example.com".to_string(),
+            ..Default::default()
+        };
+        let message = message(&email, &subject, &body);
+        assert!(message.starts_with("From: c3@example.com\nTo: me@example.com\n"));
+        assert!(message.contains("\n\napi needs your permission"));
+    }
+}
@@ -0,0 +1,199 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// Messages buffered per subscriber before the slowest ones start missing some
+const CAPACITY: usize = 100;
+
+#[derive(Default)]
+struct Counters {
+    /// Messages taken off the channel, received or missed
+    consumed: AtomicU64,
+    missed: AtomicU64,
+    resyncs: AtomicU64,
+}
+
+struct Entry {
+    name: String,
+    counters: Arc<Counters>,
+    /// Messages published before it subscribed
+    published_before: u64,
+}
+
+type Registry = Arc<Mutex<HashMap<u64, Entry>>>;
+
+/// How far behind a streaming client is, for the debug info
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubscriberLag {
+    pub id: u64,
+    pub name: String,
+    /// Published but not yet received
+    pub queued: u64,
+    /// Dropped because the subscriber fell `CAPACITY` behind
+    pub missed: u64,
+    /// Snapshots sent in place of missed messages
+    pub resyncs: u64,
+}
+
+/// Fans serialized `ServerMessage`s out to streaming clients. A client that
+/// falls behind gets a full snapshot in place of the messages it missed,
+/// instead of silently diverging.
+pub struct EventBus {
+    tx: broadcast::Sender<String>,
+    published: AtomicU64,
+    subscribers: Registry,
+    next_id: AtomicU64,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::with_capacity(CAPACITY)
+    }
+}
+
+impl EventBus {
+    fn with_capacity(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            published: AtomicU64::new(0),
+            subscribers: Registry::default(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Send to every subscriber; serializes only when there is one
+    pub fn publish<T: Serialize>(&self, message: &T) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+        match serde_json::to_string(message) {
+            Ok(json) => {
+                self.published.fetch_add(1, Ordering::Relaxed);
+                let _ = self.tx.send(json);
+            }
+            Err(e) => log::warn!("Failed to serialize bus message: {}", e),
+        }
+    }
+
+    /// A receiver for messages published from now on. `name` identifies the
+    /// client in lag stats.
+    pub fn subscribe(&self, name: &str) -> Subscriber {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let counters = Arc::new(Counters::default());
+        let mut subscribers = self.subscribers.lock();
+        let rx = self.tx.subscribe();
+        subscribers.insert(
+            id,
+            Entry {
+                name: name.to_string(),
+                counters: counters.clone(),
+                published_before: self.published.load(Ordering::Relaxed),
+            },
+        );
+        Subscriber {
+            id,
+            rx,
+            counters,
+            registry: self.subscribers.clone(),
+        }
+    }
+
+    pub fn lag_stats(&self) -> Vec<SubscriberLag> {
+        let published = self.published.load(Ordering::Relaxed);
+        let mut stats: Vec<SubscriberLag> = self
+            .subscribers
+            .lock()
+            .iter()
+            .map(|(id, entry)| SubscriberLag {
+                id: *id,
+                name: entry.name.clone(),
+                queued: published
+                    .saturating_sub(entry.published_before)
+                    .saturating_sub(entry.counters.consumed.load(Ordering::Relaxed)),
+                missed: entry.counters.missed.load(Ordering::Relaxed),
+                resyncs: entry.counters.resyncs.load(Ordering::Relaxed),
+            })
+            .collect();
+        stats.sort_by_key(|lag| lag.id);
+        stats
+    }
+}
+
+/// One streaming client's end of the bus; unregisters itself when dropped
+pub struct Subscriber {
+    id: u64,
+    rx: broadcast::Receiver<String>,
+    counters: Arc<Counters>,
+    registry: Registry,
+}
+
+impl Subscriber {
+    /// The next message. After falling behind, `snapshot()` stands in for
+    /// everything missed and live messages resume after it. None once the
+    /// bus is gone.
+    pub async fn recv(&mut self, snapshot: impl FnOnce() -> String) -> Option<String> {
+        match self.rx.recv().await {
+            Ok(message) => {
+                self.counters.consumed.fetch_add(1, Ordering::Relaxed);
+                Some(message)
+            }
+            Err(RecvError::Lagged(missed)) => {
+                log::warn!(
+                    "Bus subscriber {} missed {} messages, resyncing",
+                    self.id,
+                    missed
+                );
+                self.counters.consumed.fetch_add(missed, Ordering::Relaxed);
+                self.counters.missed.fetch_add(missed, Ordering::Relaxed);
+                self.counters.resyncs.fetch_add(1, Ordering::Relaxed);
+                Some(snapshot())
+            }
+            Err(RecvError::Closed) => None,
+        }
+    }
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lagging_subscriber_is_resynced_with_a_snapshot() {
+        let bus = EventBus::with_capacity(2);
+        let mut slow = bus.subscribe("slow");
+        for n in 0..5 {
+            bus.publish(&n);
+        }
+        assert_eq!(
+            bus.lag_stats(),
+            vec![SubscriberLag {
+                id: 1,
+                name: "slow".to_string(),
+                queued: 5,
+                missed: 0,
+                resyncs: 0,
+            }]
+        );
+
+        // The three oldest were overwritten: a snapshot, then the two kept
+        let snapshot = || "snapshot".to_string();
+        assert_eq!(slow.recv(snapshot).await.as_deref(), Some("snapshot"));
+        assert_eq!(slow.recv(snapshot).await.as_deref(), Some("3"));
+        assert_eq!(slow.recv(snapshot).await.as_deref(), Some("4"));
+        let lag = &bus.lag_stats()[0];
+        assert_eq!((lag.queued, lag.missed, lag.resyncs), (0, 3, 1));
+
+        drop(slow);
+        assert!(bus.lag_stats().is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::notification_log::Suppression;
+use crate::state_machine::StateSource;
+use crate::{load_settings, paths, SessionState};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+/// The log, in the config dir
+const LOG_FILE: &str = "events.log";
+/// The log is rotated to `events.log.1` once it grows past this
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+/// Rotated logs kept, `events.log.1` the newest
+const ROTATED_LOGS: usize = 3;
+/// Most events `tail_events` returns
+const MAX_TAIL: usize = 1000;
+
+static ENABLED: OnceLock<AtomicBool> = OnceLock::new();
+/// Appends and rotation must not interleave
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// One line of the event log; `type` says which. The schema is documented
+/// under "Event log" in the README, and only ever gains fields.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Event {
+    /// A session moved to a new state
+    State {
+        session_id: String,
+        from: Option<SessionState>,
+        to: SessionState,
+        source: StateSource,
+    },
+    /// An agent's hook reached C3
+    Hook {
+        hook_type: String,
+        agent_kind: String,
+        cwd: String,
+        session_id: Option<String>,
+        new_state: String,
+        skipped: bool,
+        skip_reason: Option<String>,
+    },
+    /// What C3 did about notifying for a hook
+    Notification {
+        session_id: String,
+        hook_type: String,
+        sent: bool,
+        reason: Option<Suppression>,
+        detail: Option<String>,
+    },
+    /// Something done to a session from C3: `approve`, `input`, `answer`,
+    /// `kill`, or an action sent to a connected client
+    Action { session_id: String, action: String },
+}
+
+#[derive(Serialize)]
+struct Line<'a> {
+    at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+fn enabled_flag() -> &'static AtomicBool {
+    ENABLED.get_or_init(|| AtomicBool::new(load_settings().event_log))
+}
+
+/// Apply the `event_log` setting
+pub(crate) fn configure(enabled: bool) {
+    enabled_flag().store(enabled, Ordering::Relaxed);
+}
+
+fn log_path() -> PathBuf {
+    paths::config_dir().join(LOG_FILE)
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+/// Shift `events.log.N` up one, dropping the oldest, and start a new log
+fn rotate(path: &Path) {
+    for n in (1..ROTATED_LOGS).rev() {
+        let _ = fs::rename(rotated(path, n), rotated(path, n + 1));
+    }
+    if let Err(e) = fs::rename(path, rotated(path, 1)) {
+        log::warn!("Failed to rotate {}: {}", path.display(), e);
+    }
+}
+
+fn append(path: &Path, event: &Event, at: DateTime<Utc>) {
+    let Ok(line) = serde_json::to_string(&Line { at, event }) else {
+        return;
+    };
+    let _guard = LOG_LOCK.lock();
+    if fs::metadata(path).is_ok_and(|meta| meta.len() >= MAX_LOG_BYTES) {
+        rotate(path);
+    }
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let appended = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = appended {
+        log::warn!("Failed to write to the event log: {}", e);
+    }
+}
+
+/// Append an event to the log, if it's turned on
+pub(crate) fn record(event: Event) {
+    if enabled_flag().load(Ordering::Relaxed) {
+        append(&log_path(), &event, Utc::now());
+    }
+}
+
+/// Log an action taken on a session
+pub(crate) fn action(session_id: &str, action: &str) {
+    record(Event::Action {
+        session_id: session_id.to_string(),
+        action: action.to_string(),
+    });
+}
+
+/// The last `count` events, oldest first, reading into the last rotated log
+/// when the current one is short
+fn tail(path: &Path, count: usize) -> Vec<Value> {
+    let _guard = LOG_LOCK.lock();
+    let mut events: Vec<Value> = Vec::new();
+    for file in [path.to_path_buf(), rotated(path, 1)] {
+        let Ok(contents) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let older: Vec<Value> = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        events.splice(0..0, older);
+        if events.len() >= count {
+            break;
+        }
+    }
+    let skip = events.len().saturating_sub(count);
+    events.split_off(skip)
+}
+
+// Tauri command: The last `count` events of the event log (at most 1000), oldest first
+#[tauri::command]
+#[specta::specta]
+pub async fn tail_events(count: u32) -> Result<Vec<Value>, String> {
+    let count = (count as usize).min(MAX_TAIL);
+    tokio::task::spawn_blocking(move || tail(&log_path(), count))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_appended_rotated_and_tailed() {
+        let dir = std::env::temp_dir().join(format!("c3-event-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(LOG_FILE);
+        let at = DateTime::parse_from_rfc3339("2026-03-01T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        append(
+            &path,
+            &Event::State {
+                session_id: "tmux:%4".to_string(),
+                from: Some(SessionState::Processing),
+                to: SessionState::AwaitingPermission,
+                source: StateSource::Hook,
+            },
+            at,
+        );
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"at\":\"2026-03-01T09:00:00Z\",\"type\":\"state\",\"session_id\":\"tmux:%4\",\
+             \"from\":\"processing\",\"to\":\"awaiting_permission\",\"source\":\"hook\"}\n"
+        );
+
+        // Filled to the limit, the next event starts a new log
+        let file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        file.set_len(MAX_LOG_BYTES).unwrap();
+        let approve = Event::Action {
+            session_id: "tmux:%4".to_string(),
+            action: "approve".to_string(),
+        };
+        append(&path, &approve, at);
+        append(&path, &approve, at);
+        assert!(rotated(&path, 1).exists());
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+
+        let events = tail(&path, 3);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["type"], "state");
+        assert_eq!(events[2]["action"], "approve");
+        assert_eq!(tail(&path, 1).len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
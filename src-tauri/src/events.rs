@@ -0,0 +1,300 @@
+use crate::{session_window, AppState, C3Session, ServerMessage};
+use parking_lot::Mutex;
+use serde::Serialize;
+use specta::Type;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::Notify;
+
+/// Updates queued within this window are sent to the frontend as one batch
+const COALESCE_WINDOW_MS: u64 = 250;
+/// Removed-session tombstones kept for `get_sessions_since`
+const MAX_TOMBSTONES: usize = 256;
+
+/// Payload of the `sessions-batch-update` event
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct SessionBatch {
+    pub updated: Vec<C3Session>,
+    pub removed: Vec<String>,
+    /// Sent along with the removal of `from` and the update of `to`
+    pub renamed: Vec<SessionRename>,
+    /// Session revision after this batch
+    pub revision: u64,
+}
+
+/// A session that moved to a new ID, e.g. after the tmux server restarted;
+/// its metadata moves with it
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+pub struct SessionRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Response of `get_sessions_since`
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SessionDelta {
+    pub revision: u64,
+    pub updated: Vec<C3Session>,
+    pub removed: Vec<String>,
+    /// The requested revision was too old to diff against; `updated` holds
+    /// every session and the client should replace its list.
+    pub full: bool,
+}
+
+/// Revision at which each session last changed or was removed
+#[derive(Default)]
+struct RevisionLog {
+    current: u64,
+    changed: HashMap<String, u64>,
+    tombstones: HashMap<String, u64>,
+    /// Tombstones at or below this revision have been pruned
+    pruned_through: u64,
+}
+
+impl RevisionLog {
+    fn record_change(&mut self, session_id: &str) {
+        self.current += 1;
+        self.tombstones.remove(session_id);
+        self.changed.insert(session_id.to_string(), self.current);
+    }
+
+    fn record_removal(&mut self, session_id: &str) {
+        self.current += 1;
+        self.changed.remove(session_id);
+        self.tombstones.insert(session_id.to_string(), self.current);
+        if self.tombstones.len() > MAX_TOMBSTONES {
+            if let Some((oldest_id, oldest_rev)) = self
+                .tombstones
+                .iter()
+                .min_by_key(|(_, rev)| **rev)
+                .map(|(id, rev)| (id.clone(), *rev))
+            {
+                self.tombstones.remove(&oldest_id);
+                self.pruned_through = self.pruned_through.max(oldest_rev);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingEvents {
+    updates: HashMap<String, C3Session>,
+    removals: HashSet<String>,
+    renames: Vec<SessionRename>,
+}
+
+/// Coalesces session updates/removals so a scan cycle or a burst of hooks
+/// reaches the frontend as a single event instead of one per session.
+#[derive(Default)]
+pub struct SessionEvents {
+    pending: Mutex<PendingEvents>,
+    /// Last version of each session sent to the frontend, for diffing
+    last_sent: Mutex<HashMap<String, C3Session>>,
+    revisions: Mutex<RevisionLog>,
+    notify: Notify,
+}
+
+impl SessionEvents {
+    /// Queue a session for the next batch; the latest version wins
+    pub fn session_updated(&self, session: C3Session) {
+        self.revisions.lock().record_change(&session.id);
+        let mut pending = self.pending.lock();
+        pending.removals.remove(&session.id);
+        pending.updates.insert(session.id.clone(), session);
+        drop(pending);
+        self.notify.notify_one();
+    }
+
+    /// Queue a session removal for the next batch
+    pub fn session_removed(&self, session_id: String) {
+        self.revisions.lock().record_removal(&session_id);
+        let mut pending = self.pending.lock();
+        pending.updates.remove(&session_id);
+        pending.removals.insert(session_id);
+        drop(pending);
+        self.notify.notify_one();
+    }
+
+    /// Queue a session's move from `from` to its new ID
+    pub fn session_renamed(&self, from: String, session: C3Session) {
+        let rename = SessionRename {
+            from: from.clone(),
+            to: session.id.clone(),
+        };
+        self.session_removed(from);
+        self.session_updated(session);
+        self.pending.lock().renames.push(rename);
+    }
+
+    /// Drain queued events, dropping updates identical to what was last sent
+    fn take_batch(&self) -> SessionBatch {
+        let pending = std::mem::take(&mut *self.pending.lock());
+        let mut last_sent = self.last_sent.lock();
+        let mut batch = SessionBatch::default();
+
+        for (id, session) in pending.updates {
+            if last_sent.get(&id) == Some(&session) {
+                continue;
+            }
+            last_sent.insert(id, session.clone());
+            batch.updated.push(session);
+        }
+        for id in pending.removals {
+            last_sent.remove(&id);
+            batch.removed.push(id);
+        }
+        batch.renamed = pending.renames;
+        batch.revision = self.revisions.lock().current;
+        batch
+    }
+
+    /// Sessions changed or removed after `since`, given the current session map.
+    /// `since == 0` (or a revision older than the retained tombstones) yields a full list.
+    pub fn delta_since(&self, since: u64, sessions: &HashMap<String, C3Session>) -> SessionDelta {
+        let revisions = self.revisions.lock();
+        if since == 0 || since < revisions.pruned_through || since > revisions.current {
+            return SessionDelta {
+                revision: revisions.current,
+                updated: sessions.values().cloned().collect(),
+                removed: vec![],
+                full: true,
+            };
+        }
+        let updated = sessions
+            .values()
+            .filter(|s| revisions.changed.get(&s.id).is_some_and(|rev| *rev > since))
+            .cloned()
+            .collect();
+        let removed = revisions
+            .tombstones
+            .iter()
+            .filter(|(_, rev)| **rev > since)
+            .map(|(id, _)| id.clone())
+            .collect();
+        SessionDelta {
+            revision: revisions.current,
+            updated,
+            removed,
+            full: false,
+        }
+    }
+}
+
+/// Emit coalesced `sessions-batch-update` events until shutdown, routing
+/// pop-out windows their own session's changes and bus subscribers all of them
+pub async fn start_event_flusher(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = state.events.notify.notified() => {}
+            _ = shutdown.changed() => break,
+        }
+        // Let the rest of the burst arrive before sending
+        tokio::time::sleep(tokio::time::Duration::from_millis(COALESCE_WINDOW_MS)).await;
+
+        let batch = state.events.take_batch();
+        if batch.updated.is_empty() && batch.removed.is_empty() && batch.renamed.is_empty() {
+            continue;
+        }
+        log::debug!(
+            "Emitting session batch: {} updated, {} removed",
+            batch.updated.len(),
+            batch.removed.len()
+        );
+        state.bus.publish(&ServerMessage::Sessions(batch.clone()));
+        session_window::emit_batch(&app_handle, &state.session_windows, batch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SessionState;
+
+    fn session(id: &str, state: SessionState) -> C3Session {
+        C3Session {
+            state,
+            ..C3Session::test(id)
+        }
+    }
+
+    #[test]
+    fn unchanged_sessions_are_not_resent() {
+        let events = SessionEvents::default();
+        let first = session("a", SessionState::Processing);
+        events.session_updated(first.clone());
+        events.session_updated(session("b", SessionState::Processing));
+        assert_eq!(events.take_batch().updated.len(), 2);
+
+        events.session_updated(first);
+        events.session_updated(session("b", SessionState::AwaitingInput));
+        let batch = events.take_batch();
+        assert_eq!(batch.updated.len(), 1);
+        assert_eq!(batch.updated[0].id, "b");
+    }
+
+    #[test]
+    fn removal_supersedes_queued_update() {
+        let events = SessionEvents::default();
+        events.session_updated(session("a", SessionState::Processing));
+        events.session_removed("a".to_string());
+        let batch = events.take_batch();
+        assert!(batch.updated.is_empty());
+        assert_eq!(batch.removed, vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn delta_contains_only_changes_after_revision() {
+        let events = SessionEvents::default();
+        let mut sessions = HashMap::new();
+        for id in ["a", "b", "c"] {
+            let s = session(id, SessionState::Processing);
+            sessions.insert(id.to_string(), s.clone());
+            events.session_updated(s);
+        }
+        let since = events.take_batch().revision;
+        assert_eq!(since, 3);
+
+        let b = session("b", SessionState::AwaitingInput);
+        sessions.insert("b".to_string(), b.clone());
+        events.session_updated(b);
+        sessions.remove("c");
+        events.session_removed("c".to_string());
+
+        let delta = events.delta_since(since, &sessions);
+        assert!(!delta.full);
+        assert_eq!(delta.revision, 5);
+        assert_eq!(delta.updated.len(), 1);
+        assert_eq!(delta.updated[0].id, "b");
+        assert_eq!(delta.removed, vec!["c".to_string()]);
+
+        assert!(events.delta_since(0, &sessions).full);
+        assert!(events.delta_since(5, &sessions).updated.is_empty());
+    }
+
+    #[test]
+    fn renamed_session_is_removed_and_sent_under_its_new_id() {
+        let events = SessionEvents::default();
+        events.session_updated(session("tmux:main:1.0", SessionState::Processing));
+        events.take_batch();
+
+        events.session_renamed(
+            "tmux:main:1.0".to_string(),
+            session("tmux:work:1.0", SessionState::Processing),
+        );
+        let batch = events.take_batch();
+        assert_eq!(batch.removed, vec!["tmux:main:1.0".to_string()]);
+        assert_eq!(batch.updated[0].id, "tmux:work:1.0");
+        assert_eq!(
+            batch.renamed,
+            vec![SessionRename {
+                from: "tmux:main:1.0".to_string(),
+                to: "tmux:work:1.0".to_string(),
+            }]
+        );
+    }
+}
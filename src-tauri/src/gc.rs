@@ -0,0 +1,468 @@
+use crate::{
+    load_session_meta, load_settings, save_session_meta, session_identity, transcript, worktree,
+    AppState, C3Session, SessionState,
+};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+
+/// How often retention rules are applied
+const GC_INTERVAL_SECS: u64 = 300;
+/// How long archived sessions can be brought back with `undo_archive`
+pub const UNDO_WINDOW_SECS: i64 = 600;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveReason {
+    /// Complete for longer than `archive_complete_after_hours`
+    Complete,
+    /// Transcript untouched for longer than `hide_stale_after_days`
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedSession {
+    pub session: C3Session,
+    pub reason: ArchiveReason,
+    pub archived_at: DateTime<Utc>,
+    /// Activity the scanner saw right after archiving; anything newer brings
+    /// the session back
+    #[serde(skip)]
+    seen_activity: Option<Option<DateTime<Utc>>>,
+    /// Its worktree was dealt with once the undo window passed
+    #[serde(skip)]
+    settled: bool,
+}
+
+/// Payload of the `sessions-archived` event
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedBatch {
+    pub session_ids: Vec<String>,
+    pub undo_until: DateTime<Utc>,
+    /// Worktrees of sessions archived earlier that couldn't be removed,
+    /// e.g. with uncommitted changes
+    pub worktree_errors: Vec<String>,
+}
+
+/// Retention settings; zero disables a rule
+#[derive(Debug, Clone, Copy)]
+struct Policy {
+    archive_complete_after_hours: u64,
+    hide_stale_after_days: u64,
+}
+
+/// Sessions the GC took off the board. The scanner keeps skipping their panes
+/// until the agent shows new activity.
+#[derive(Default)]
+pub struct Archive {
+    entries: RwLock<HashMap<String, ArchivedSession>>,
+    /// Sessions restored by undo, with their last activity at that point.
+    /// They aren't collected again until they've been active since.
+    exempt: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+impl Archive {
+    /// Whether the scanner should leave an archived session hidden. Working
+    /// states or a newer message timestamp un-archive it.
+    pub(crate) fn suppresses(
+        &self,
+        session_id: &str,
+        state: &SessionState,
+        activity: Option<DateTime<Utc>>,
+    ) -> bool {
+        let mut entries = self.entries.write();
+        let Some(entry) = entries.get_mut(session_id) else {
+            return false;
+        };
+        let active = matches!(
+            state,
+            SessionState::Processing | SessionState::AwaitingPermission
+        );
+        let newer = match entry.seen_activity {
+            None => {
+                entry.seen_activity = Some(activity);
+                false
+            }
+            Some(seen) => activity > seen,
+        };
+        if active || newer {
+            log::info!("{} is active again, un-archiving", session_id);
+            entries.remove(session_id);
+            return false;
+        }
+        true
+    }
+
+    pub(crate) fn session(&self, session_id: &str) -> Option<C3Session> {
+        self.entries
+            .read()
+            .get(session_id)
+            .map(|archived| archived.session.clone())
+    }
+
+    /// Record what an archived session accomplished
+    pub(crate) fn set_summary(&self, session_id: &str, summary: String) {
+        if let Some(archived) = self.entries.write().get_mut(session_id) {
+            archived.session.summary = Some(summary);
+        }
+    }
+
+    /// A hook arrived for the session: it's in use again
+    pub(crate) fn forget(&self, session_id: &str) {
+        self.entries.write().remove(session_id);
+    }
+
+    /// Drop entries whose tmux pane is gone, once their worktree was dealt
+    /// with; `is_live` gets pane session IDs
+    pub(crate) fn retain_panes(&self, is_live: impl Fn(&str) -> bool) {
+        let keep = |id: &String| !session_identity::is_pane_session(id) || is_live(id);
+        self.entries
+            .write()
+            .retain(|id, archived| keep(id) || !archived.settled);
+        self.exempt.write().retain(|id, _| keep(id));
+    }
+
+    /// Entries whose undo window has passed since the last call, by ID and
+    /// project name
+    fn settle(&self, now: DateTime<Utc>) -> Vec<(String, String)> {
+        let cutoff = now - Duration::seconds(UNDO_WINDOW_SECS);
+        self.entries
+            .write()
+            .iter_mut()
+            .filter(|(_, archived)| !archived.settled && archived.archived_at < cutoff)
+            .map(|(id, archived)| {
+                archived.settled = true;
+                (id.clone(), archived.session.project_name.clone())
+            })
+            .collect()
+    }
+
+    fn is_exempt(&self, session: &C3Session) -> bool {
+        let mut exempt = self.exempt.write();
+        match exempt.get(&session.id) {
+            Some(restored_activity) if session.last_activity <= *restored_activity => true,
+            Some(_) => {
+                exempt.remove(&session.id);
+                false
+            }
+            None => false,
+        }
+    }
+}
+
+fn policy() -> Policy {
+    let settings = load_settings();
+    Policy {
+        archive_complete_after_hours: settings.archive_complete_after_hours,
+        hide_stale_after_days: settings.hide_stale_after_days,
+    }
+}
+
+fn transcript_modified(session: &C3Session) -> Option<DateTime<Utc>> {
+    let (_, path) = transcript::session_transcript(session)?;
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.into())
+}
+
+fn archive_reason(
+    session: &C3Session,
+    transcript_modified: Option<DateTime<Utc>>,
+    policy: Policy,
+    now: DateTime<Utc>,
+) -> Option<ArchiveReason> {
+    if policy.hide_stale_after_days > 0 {
+        let last_change = transcript_modified.unwrap_or(session.last_activity);
+        if now - last_change > Duration::days(policy.hide_stale_after_days as i64) {
+            return Some(ArchiveReason::Stale);
+        }
+    }
+    if policy.archive_complete_after_hours > 0
+        && session.state == SessionState::Complete
+        && now - session.last_activity > Duration::hours(policy.archive_complete_after_hours as i64)
+    {
+        return Some(ArchiveReason::Complete);
+    }
+    None
+}
+
+/// Apply retention rules once; returns the ids that were archived
+fn collect(state: &AppState) -> Vec<String> {
+    let policy = policy();
+    if policy.archive_complete_after_hours == 0 && policy.hide_stale_after_days == 0 {
+        return Vec::new();
+    }
+    let meta = load_session_meta();
+    let now = Utc::now();
+    let candidates: Vec<C3Session> = state
+        .sessions
+        .read()
+        .values()
+        .filter(|s| !meta.sessions.get(&s.id).is_some_and(|m| m.pinned))
+        .cloned()
+        .collect();
+
+    let mut to_archive = Vec::new();
+    for session in candidates {
+        if state.archive.is_exempt(&session) {
+            continue;
+        }
+        let modified = transcript_modified(&session);
+        if let Some(reason) = archive_reason(&session, modified, policy, now) {
+            to_archive.push((session.id.clone(), reason));
+        }
+    }
+
+    let mut archived_ids = Vec::new();
+    let mut sessions = state.sessions.write();
+    let mut entries = state.archive.entries.write();
+    for (id, reason) in to_archive {
+        let Some(session) = sessions.remove(&id) else {
+            continue;
+        };
+        log::info!("Archiving {} ({:?})", id, reason);
+        entries.insert(
+            id.clone(),
+            ArchivedSession {
+                session,
+                reason,
+                archived_at: now,
+                seen_activity: None,
+                settled: false,
+            },
+        );
+        state.events.session_removed(id.clone());
+        archived_ids.push(id);
+    }
+    archived_ids
+}
+
+/// Remove the worktrees of sessions that stayed archived past the undo
+/// window. Ones with changes are kept; returns why, per worktree.
+fn remove_worktrees(state: &AppState) -> Vec<String> {
+    let settled = state.archive.settle(Utc::now());
+    if settled.is_empty() {
+        return Vec::new();
+    }
+    let mut meta = load_session_meta();
+    let mut removed = false;
+    let mut errors = Vec::new();
+    for (id, project_name) in settled {
+        let Some(entry) = meta.sessions.get_mut(&id) else {
+            continue;
+        };
+        let Some(path) = entry.worktree.clone() else {
+            continue;
+        };
+        match worktree::remove(&path, false) {
+            Ok(()) => {
+                entry.worktree = None;
+                removed = true;
+            }
+            Err(e) => {
+                log::warn!("Keeping worktree {}: {}", path, e);
+                errors.push(format!("{} ({}): {}", project_name, path, e));
+            }
+        }
+    }
+    if removed {
+        if let Err(e) = save_session_meta(&meta) {
+            log::warn!("Failed to save session metadata: {}", e);
+        }
+    }
+    errors
+}
+
+/// Periodically archive sessions per the retention settings
+pub async fn start_gc(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(GC_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Session GC shutting down");
+                break;
+            }
+        }
+        let state_gc = state.clone();
+        let swept = tokio::task::spawn_blocking(move || {
+            let errors = remove_worktrees(&state_gc);
+            (collect(&state_gc), errors)
+        })
+        .await;
+        let (archived, worktree_errors) = match swept {
+            Ok(swept) => swept,
+            Err(e) => {
+                log::warn!("Session GC failed: {}", e);
+                continue;
+            }
+        };
+        if !archived.is_empty() || !worktree_errors.is_empty() {
+            let _ = app_handle.emit(
+                "sessions-archived",
+                ArchivedBatch {
+                    session_ids: archived,
+                    undo_until: Utc::now() + Duration::seconds(UNDO_WINDOW_SECS),
+                    worktree_errors,
+                },
+            );
+        }
+    }
+}
+
+// Tauri command: Sessions hidden by the retention rules
+#[tauri::command]
+#[specta::specta]
+pub fn get_archived_sessions(state: tauri::State<Arc<AppState>>) -> Vec<ArchivedSession> {
+    let mut archived: Vec<ArchivedSession> =
+        state.archive.entries.read().values().cloned().collect();
+    archived.sort_by_key(|a| std::cmp::Reverse(a.archived_at));
+    archived
+}
+
+// Tauri command: Bring back sessions archived within the undo window
+#[tauri::command]
+#[specta::specta]
+pub fn undo_archive(
+    state: tauri::State<Arc<AppState>>,
+    session_ids: Vec<String>,
+) -> Result<usize, String> {
+    let cutoff = Utc::now() - Duration::seconds(UNDO_WINDOW_SECS);
+    let mut restored = Vec::new();
+    {
+        let mut entries = state.archive.entries.write();
+        for id in &session_ids {
+            if entries.get(id).is_some_and(|a| a.archived_at >= cutoff) {
+                if let Some(archived) = entries.remove(id) {
+                    restored.push(archived.session);
+                }
+            }
+        }
+    }
+    if restored.is_empty() {
+        return Err("Nothing to undo: the undo window has passed".to_string());
+    }
+
+    let count = restored.len();
+    let mut exempt = state.archive.exempt.write();
+    let mut sessions = state.sessions.write();
+    for session in restored {
+        exempt.insert(session.id.clone(), session.last_activity);
+        sessions.insert(session.id.clone(), session.clone());
+        state.events.session_updated(session);
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(state: SessionState, age: Duration) -> C3Session {
+        C3Session {
+            project_name: "api".to_string(),
+            state,
+            tmux_target: Some("work:1.0".to_string()),
+            last_activity: Utc::now() - age,
+            ..C3Session::test("tmux:work:1.0")
+        }
+    }
+
+    const POLICY: Policy = Policy {
+        archive_complete_after_hours: 24,
+        hide_stale_after_days: 7,
+    };
+
+    #[test]
+    fn archives_old_complete_and_stale_sessions() {
+        let now = Utc::now();
+        let done = session(SessionState::Complete, Duration::hours(25));
+        assert_eq!(
+            archive_reason(&done, None, POLICY, now),
+            Some(ArchiveReason::Complete)
+        );
+        let idle = session(SessionState::AwaitingInput, Duration::hours(25));
+        assert_eq!(archive_reason(&idle, None, POLICY, now), None);
+
+        // A transcript changed recently keeps an old-looking session visible
+        let quiet = session(SessionState::AwaitingInput, Duration::days(8));
+        assert_eq!(
+            archive_reason(&quiet, None, POLICY, now),
+            Some(ArchiveReason::Stale)
+        );
+        assert_eq!(
+            archive_reason(&quiet, Some(now - Duration::days(1)), POLICY, now),
+            None
+        );
+
+        let disabled = Policy {
+            archive_complete_after_hours: 0,
+            hide_stale_after_days: 0,
+        };
+        assert_eq!(archive_reason(&done, None, disabled, now), None);
+    }
+
+    #[test]
+    fn archived_sessions_return_on_new_activity() {
+        let archive = Archive::default();
+        let archived = session(SessionState::Complete, Duration::hours(30));
+        let id = archived.id.clone();
+        archive.entries.write().insert(
+            id.clone(),
+            ArchivedSession {
+                session: archived,
+                reason: ArchiveReason::Complete,
+                archived_at: Utc::now(),
+                seen_activity: None,
+                settled: false,
+            },
+        );
+
+        let old = Some(Utc::now() - Duration::hours(30));
+        assert!(archive.suppresses(&id, &SessionState::Complete, old));
+        assert!(archive.suppresses(&id, &SessionState::Complete, old));
+        assert!(!archive.suppresses(&id, &SessionState::Complete, Some(Utc::now())));
+        // Un-archived for good
+        assert!(!archive.suppresses(&id, &SessionState::Complete, old));
+    }
+
+    #[test]
+    fn entries_settle_once_after_the_undo_window() {
+        let archive = Archive::default();
+        let archived_at = Utc::now();
+        for id in ["a", "b"] {
+            archive.entries.write().insert(
+                id.to_string(),
+                ArchivedSession {
+                    session: C3Session::test(id),
+                    reason: ArchiveReason::Complete,
+                    archived_at,
+                    seen_activity: None,
+                    settled: false,
+                },
+            );
+        }
+        assert!(archive.settle(archived_at).is_empty());
+
+        let later = archived_at + Duration::seconds(UNDO_WINDOW_SECS + 1);
+        let mut settled = archive.settle(later);
+        settled.sort();
+        assert_eq!(
+            settled,
+            vec![
+                ("a".to_string(), "c3".to_string()),
+                ("b".to_string(), "c3".to_string())
+            ]
+        );
+        assert!(archive.settle(later).is_empty());
+    }
+}
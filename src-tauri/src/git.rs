@@ -0,0 +1,87 @@
+use crate::{load_session_meta, save_session_meta};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Commit and branch checked out when C3 first saw a session, kept in
+/// session-meta.json so it outlives the session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBaseline {
+    pub commit: String,
+    /// None when HEAD was detached
+    pub branch: Option<String>,
+    /// Directory the baseline was taken in
+    pub cwd: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Run git in `repo`; stderr becomes the error
+pub(crate) fn git(repo: &str, args: &[&str]) -> Result<String, String> {
+    let output = crate::cmd("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// HEAD and branch of the repo containing `cwd`; None outside a repo or
+/// before the first commit
+pub(crate) fn capture_baseline(cwd: &str) -> Option<GitBaseline> {
+    let commit = git(cwd, &["rev-parse", "HEAD"]).ok()?.trim().to_string();
+    let branch = git(cwd, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+    Some(GitBaseline {
+        commit,
+        branch,
+        cwd: cwd.to_string(),
+        recorded_at: Utc::now(),
+    })
+}
+
+fn persist_baseline(session_id: &str, baseline: &GitBaseline) {
+    let mut store = load_session_meta();
+    store
+        .sessions
+        .entry(session_id.to_string())
+        .or_default()
+        .git_baseline = Some(baseline.clone());
+    if let Err(e) = save_session_meta(&store) {
+        log::warn!("Failed to save git baseline for {}: {}", session_id, e);
+    }
+}
+
+/// Baseline for a session C3 hasn't seen in this run: the persisted one when
+/// it was taken in the same directory (e.g. C3 restarted mid-session),
+/// otherwise the current HEAD
+pub(crate) fn baseline_for_new_session(session_id: &str, cwd: &str) -> Option<GitBaseline> {
+    let persisted = load_session_meta()
+        .sessions
+        .get(session_id)
+        .and_then(|meta| meta.git_baseline.clone())
+        .filter(|baseline| baseline.cwd == cwd);
+    if persisted.is_some() {
+        return persisted;
+    }
+    record_baseline(session_id, cwd)
+}
+
+/// Take and persist a fresh baseline, replacing any previous one
+pub(crate) fn record_baseline(session_id: &str, cwd: &str) -> Option<GitBaseline> {
+    let baseline = capture_baseline(cwd)?;
+    log::info!(
+        "Recorded git baseline for {}: {} ({})",
+        session_id,
+        &baseline.commit[..baseline.commit.len().min(12)],
+        baseline.branch.as_deref().unwrap_or("detached")
+    );
+    persist_baseline(session_id, &baseline);
+    Some(baseline)
+}
@@ -0,0 +1,244 @@
+use crate::git::git;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// PR lookups hit the GitHub API, so they run far less often than tmux scans
+const PR_REFRESH_SECS: u64 = 120;
+const PR_FIELDS: &str = "number,title,url,state,isDraft,reviewDecision,statusCheckRollup";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    Passing,
+    Failing,
+    Pending,
+    /// No checks configured
+    None,
+}
+
+/// Open pull request for the branch a session is working on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestStatus {
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub is_draft: bool,
+    pub ci: CiStatus,
+    /// `APPROVED`, `CHANGES_REQUESTED` or `REVIEW_REQUIRED`; None when the
+    /// repo doesn't require reviews
+    pub review_decision: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Collapse `statusCheckRollup`, which mixes check runs (status/conclusion)
+/// and commit statuses (state)
+fn ci_status(rollup: &[Value]) -> CiStatus {
+    if rollup.is_empty() {
+        return CiStatus::None;
+    }
+    let mut pending = false;
+    for check in rollup {
+        let field = |key: &str| check.get(key).and_then(|v| v.as_str()).unwrap_or("");
+        let outcome = match field("__typename") {
+            "StatusContext" => field("state"),
+            _ if field("status") != "COMPLETED" => "PENDING",
+            _ => field("conclusion"),
+        };
+        match outcome {
+            "SUCCESS" | "NEUTRAL" | "SKIPPED" => {}
+            "PENDING" | "EXPECTED" | "" => pending = true,
+            _ => return CiStatus::Failing,
+        }
+    }
+    if pending {
+        CiStatus::Pending
+    } else {
+        CiStatus::Passing
+    }
+}
+
+/// `gh pr view --json` output; None unless the PR is open
+fn parse_pr(json: &str) -> Option<PullRequestStatus> {
+    let pr: Value = serde_json::from_str(json).ok()?;
+    if pr.get("state").and_then(|s| s.as_str()) != Some("OPEN") {
+        return None;
+    }
+    let rollup = pr
+        .get("statusCheckRollup")
+        .and_then(|r| r.as_array())
+        .cloned()
+        .unwrap_or_default();
+    Some(PullRequestStatus {
+        number: pr.get("number")?.as_u64()?,
+        title: pr.get("title")?.as_str()?.to_string(),
+        url: pr.get("url")?.as_str()?.to_string(),
+        is_draft: pr.get("isDraft").and_then(|v| v.as_bool()).unwrap_or(false),
+        ci: ci_status(&rollup),
+        review_decision: pr
+            .get("reviewDecision")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from),
+        checked_at: Utc::now(),
+    })
+}
+
+fn lookup_pr(repo_root: &str, branch: &str) -> Option<PullRequestStatus> {
+    let output = crate::cmd("gh")
+        .args(["pr", "view", branch, "--json", PR_FIELDS])
+        .current_dir(repo_root)
+        .output()
+        .ok()?;
+    // Exits non-zero when the branch has no PR
+    if !output.status.success() {
+        return None;
+    }
+    parse_pr(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Repo root and checked-out branch for a project dir
+fn repo_branch(cwd: &str) -> Option<(String, String)> {
+    let root = git(cwd, &["rev-parse", "--show-toplevel"]).ok()?;
+    let branch = git(cwd, &["symbolic-ref", "--short", "-q", "HEAD"]).ok()?;
+    Some((root.trim().to_string(), branch.trim().to_string()))
+}
+
+fn gh_available() -> bool {
+    crate::cmd("gh")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Look up PRs for every session's branch (once per repo/branch) and attach them
+fn refresh(state: &Arc<AppState>) {
+    let paths: HashMap<String, String> = state
+        .sessions
+        .read()
+        .values()
+        .filter_map(|s| Some((s.id.clone(), s.project_path.clone()?)))
+        .collect();
+
+    let mut by_branch: HashMap<(String, String), Option<PullRequestStatus>> = HashMap::new();
+    let mut resolved: HashMap<String, Option<PullRequestStatus>> = HashMap::new();
+    for (session_id, cwd) in paths {
+        let pr = match repo_branch(&cwd) {
+            Some(key) => by_branch
+                .entry(key)
+                .or_insert_with_key(|(root, branch)| lookup_pr(root, branch))
+                .clone(),
+            None => None,
+        };
+        resolved.insert(session_id, pr);
+    }
+
+    let mut updated = Vec::new();
+    {
+        let mut sessions = state.sessions.write();
+        for (session_id, pr) in resolved {
+            let Some(session) = sessions.get_mut(&session_id) else {
+                continue;
+            };
+            // checked_at always moves, so compare everything else
+            let unchanged = match (&session.pull_request, &pr) {
+                (Some(old), Some(new)) => {
+                    PullRequestStatus {
+                        checked_at: new.checked_at,
+                        ..old.clone()
+                    } == *new
+                }
+                (None, None) => true,
+                _ => false,
+            };
+            session.pull_request = pr;
+            if !unchanged {
+                updated.push(session.clone());
+            }
+        }
+    }
+    for session in updated {
+        state.events.session_updated(session);
+    }
+}
+
+/// Periodically refresh PR status; does nothing when the GitHub CLI is missing
+pub async fn start_pr_watcher(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    if !tokio::task::spawn_blocking(gh_available)
+        .await
+        .unwrap_or(false)
+    {
+        log::info!("GitHub CLI (gh) not found, PR status disabled");
+        // Park until shutdown; returning early would look like a crash to the supervisor
+        let _ = shutdown.changed().await;
+        return;
+    }
+    log::info!("Starting PR watcher (every {}s)", PR_REFRESH_SECS);
+
+    loop {
+        let state_refresh = state.clone();
+        if let Err(e) = tokio::task::spawn_blocking(move || refresh(&state_refresh)).await {
+            log::warn!("PR status refresh failed: {}", e);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(PR_REFRESH_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("PR watcher shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn ci_status_combines_check_runs_and_statuses() {
+        let success =
+            json!({"__typename": "CheckRun", "status": "COMPLETED", "conclusion": "SUCCESS"});
+        let skipped =
+            json!({"__typename": "CheckRun", "status": "COMPLETED", "conclusion": "SKIPPED"});
+        let running = json!({"__typename": "CheckRun", "status": "IN_PROGRESS", "conclusion": ""});
+        let failed = json!({"__typename": "StatusContext", "state": "FAILURE"});
+
+        assert_eq!(ci_status(&[]), CiStatus::None);
+        assert_eq!(ci_status(&[success.clone(), skipped]), CiStatus::Passing);
+        assert_eq!(
+            ci_status(&[success.clone(), running.clone()]),
+            CiStatus::Pending
+        );
+        assert_eq!(ci_status(&[running, failed, success]), CiStatus::Failing);
+    }
+
+    #[test]
+    fn only_open_prs_are_attached() {
+        let open = json!({
+            "number": 42,
+            "title": "Add login",
+            "url": "https://github.com/acme/api/pull/42",
+            "state": "OPEN",
+            "isDraft": false,
+            "reviewDecision": "APPROVED",
+            "statusCheckRollup": [{"__typename": "CheckRun", "status": "COMPLETED", "conclusion": "SUCCESS"}]
+        });
+        let pr = parse_pr(&open.to_string()).unwrap();
+        assert_eq!(pr.number, 42);
+        assert_eq!(pr.ci, CiStatus::Passing);
+        assert_eq!(pr.review_decision.as_deref(), Some("APPROVED"));
+
+        let merged = json!({"number": 41, "title": "Old", "url": "u", "state": "MERGED"});
+        assert!(parse_pr(&merged.to_string()).is_none());
+    }
+}
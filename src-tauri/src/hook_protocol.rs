@@ -0,0 +1,163 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Sent as `version` in hook payloads. c3-hook.sh declares the same number as
+/// `C3_HOOK_VERSION`; bump both whenever the payload changes.
+pub const HOOK_PROTOCOL_VERSION: u32 = 4;
+/// Payloads without a `version` field predate versioning
+pub const LEGACY_PROTOCOL_VERSION: u32 = 1;
+/// Response header carrying the upgrade hint for outdated scripts
+pub const UPGRADE_HEADER: &str = "X-C3-Hook-Upgrade";
+const HOOK_SCRIPT_MARKER: &str = "C3_HOOK_VERSION=";
+
+const HOOK_TYPES: &[&str] = &[
+    "SessionStart",
+    "PermissionRequest",
+    "Notification",
+    "Stop",
+    "PostToolUse",
+];
+const PAYLOAD_FIELDS: &[&str] = &[
+    "version",
+    "hook_type",
+    "agent_kind",
+    "cwd",
+    "terminal_tty",
+    "session_id",
+    "tool_name",
+    "tool_input",
+    "skip_permissions",
+    "approval_hint",
+    "hook_payload_keys",
+    "tmux",
+    "queued_at",
+];
+
+/// Oldest payload version received since the hooks were last installed
+static OLDEST_SEEN: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// Served at `GET /capabilities` so scripts can adapt to the running app
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    pub app_version: String,
+    pub hook_types: &'static [&'static str],
+    pub payload_fields: &'static [&'static str],
+}
+
+pub fn capabilities(app_version: &str) -> Capabilities {
+    Capabilities {
+        protocol_version: HOOK_PROTOCOL_VERSION,
+        min_protocol_version: LEGACY_PROTOCOL_VERSION,
+        app_version: app_version.to_string(),
+        hook_types: HOOK_TYPES,
+        payload_fields: PAYLOAD_FIELDS,
+    }
+}
+
+/// Note the version of a received payload. True the first time an outdated
+/// one shows up, so the warning is only logged once.
+pub fn record(version: u32) -> bool {
+    let previous = OLDEST_SEEN.fetch_min(version, Ordering::Relaxed);
+    version < HOOK_PROTOCOL_VERSION && previous >= HOOK_PROTOCOL_VERSION
+}
+
+/// Forget outdated payloads, after the hooks were reinstalled
+pub fn reset() {
+    OLDEST_SEEN.store(u32::MAX, Ordering::Relaxed);
+}
+
+pub fn outdated_payload_seen() -> bool {
+    OLDEST_SEEN.load(Ordering::Relaxed) < HOOK_PROTOCOL_VERSION
+}
+
+pub fn upgrade_hint(version: u32) -> Option<String> {
+    (version < HOOK_PROTOCOL_VERSION).then(|| {
+        format!(
+            "c3-hook.sh is outdated (protocol {}, current {}); reinstall hooks from C3 Settings",
+            version, HOOK_PROTOCOL_VERSION
+        )
+    })
+}
+
+/// `C3_HOOK_VERSION=2` in a hook script
+pub fn hook_script_version(script: &str) -> Option<u32> {
+    script.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix(HOOK_SCRIPT_MARKER)
+            .and_then(|v| v.trim().trim_matches('"').parse().ok())
+    })
+}
+
+/// Version of `~/.local/bin/c3-hook.sh`, None when it isn't installed
+pub fn installed_hook_version() -> Option<u32> {
+    let home = std::env::var("HOME").ok()?;
+    let script = fs::read_to_string(PathBuf::from(home).join(".local/bin/c3-hook.sh")).ok()?;
+    Some(hook_script_version(&script).unwrap_or(LEGACY_PROTOCOL_VERSION))
+}
+
+/// The hooks C3 registers in Claude Code's settings or Codex's hooks.json.
+/// Each command names the hook server's URL, since a profile can move it
+/// off the default port.
+pub fn agent_hooks(agent_kind: &str, hook_port: u16) -> Value {
+    let hooks = ["Stop", "Notification", "PermissionRequest", "SessionStart"]
+        .into_iter()
+        .map(|hook_type| {
+            let command = format!(
+                "C3_AGENT_KIND={} C3_HOOK_URL=http://127.0.0.1:{}/hook $HOME/.local/bin/c3-hook.sh {}",
+                agent_kind, hook_port, hook_type
+            );
+            let entry = json!([{ "matcher": "", "hooks": [{ "type": "command", "command": command }] }]);
+            (hook_type.to_string(), entry)
+        })
+        .collect();
+    Value::Object(hooks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_versions_and_upgrade_hints() {
+        assert_eq!(
+            hook_script_version("#!/bin/bash\nC3_HOOK_VERSION=3\n"),
+            Some(3)
+        );
+        assert_eq!(hook_script_version("#!/bin/bash\necho hi\n"), None);
+        // The bundled script must declare the protocol this build speaks
+        assert_eq!(
+            hook_script_version(include_str!("../resources/c3-hook.sh")),
+            Some(HOOK_PROTOCOL_VERSION)
+        );
+
+        assert!(upgrade_hint(LEGACY_PROTOCOL_VERSION).is_some());
+        assert!(upgrade_hint(HOOK_PROTOCOL_VERSION).is_none());
+    }
+
+    #[test]
+    fn installed_hooks_carry_the_hook_port() {
+        let hooks = agent_hooks("codex", 9410);
+        assert_eq!(hooks.as_object().unwrap().len(), 4);
+        assert_eq!(
+            hooks["PermissionRequest"][0]["hooks"][0]["command"],
+            "C3_AGENT_KIND=codex C3_HOOK_URL=http://127.0.0.1:9410/hook $HOME/.local/bin/c3-hook.sh PermissionRequest"
+        );
+    }
+
+    #[test]
+    fn only_the_first_outdated_payload_is_reported() {
+        reset();
+        assert!(!record(HOOK_PROTOCOL_VERSION));
+        assert!(!outdated_payload_seen());
+        assert!(record(LEGACY_PROTOCOL_VERSION));
+        assert!(!record(LEGACY_PROTOCOL_VERSION));
+        assert!(outdated_payload_seen());
+        reset();
+        assert!(!outdated_payload_seen());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{paths, process_hook, AppState, HookNotification};
+use chrono::Utc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::AppHandle;
+
+/// c3-hook.sh writes payloads here when C3 isn't listening
+const SPOOL_DIR: &str = "hook-spool";
+/// Spooled hooks older than this describe states long gone and are dropped
+const MAX_AGE_SECS: i64 = 600;
+
+/// `<state dir>/hook-spool`; c3-hook.sh resolves the same path
+fn spool_dir() -> PathBuf {
+    paths::state_dir().join(SPOOL_DIR)
+}
+
+/// Payloads without `queued_at` were sent live and are always fresh
+fn is_fresh(notification: &HookNotification, now: i64) -> bool {
+    notification
+        .queued_at
+        .is_none_or(|queued_at| now - queued_at <= MAX_AGE_SECS)
+}
+
+/// Spooled payloads oldest first. Files are named `<epoch>-<pid>.json`, so
+/// name order is queue order. Each is claimed by renaming it first, as
+/// c3-hook.sh does, so one a hook is sending isn't applied twice; the claimed
+/// paths come back. Unreadable files come back as None.
+fn read_spool(dir: &Path) -> Vec<(PathBuf, Option<HookNotification>)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    let claim = format!("json.sending.{}", std::process::id());
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let claimed = path.with_extension(&claim);
+            fs::rename(&path, &claimed).ok().map(|()| claimed)
+        })
+        .map(|path| {
+            let notification = fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+            (path, notification)
+        })
+        .collect()
+}
+
+/// Apply hooks in the order they fired, skipping stale ones. Returns how
+/// many were applied.
+pub(crate) fn process_batch(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    notifications: Vec<HookNotification>,
+) -> usize {
+    let now = Utc::now().timestamp();
+    let (fresh, stale): (Vec<_>, Vec<_>) = notifications
+        .into_iter()
+        .partition(|notification| is_fresh(notification, now));
+    if !stale.is_empty() {
+        log::info!("Dropping {} stale queued hooks", stale.len());
+    }
+    let applied = fresh.len();
+    for notification in fresh {
+        process_hook(state, app_handle, notification);
+    }
+    applied
+}
+
+/// Apply and remove payloads spooled while the hook server was down. Run
+/// once the server is listening, so newer hooks can't be overtaken.
+pub(crate) fn drain(state: &Arc<AppState>, app_handle: &AppHandle) {
+    let spooled = read_spool(&spool_dir());
+    if spooled.is_empty() {
+        return;
+    }
+    let mut notifications = Vec::new();
+    for (path, notification) in spooled {
+        match notification {
+            Some(notification) => notifications.push(notification),
+            None => log::warn!("Discarding unreadable spooled hook {}", path.display()),
+        }
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+    let applied = process_batch(state, app_handle, notifications);
+    log::info!("Applied {} hooks queued while C3 was down", applied);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(hook_type: &str, queued_at: Option<i64>) -> String {
+        serde_json::json!({
+            "version": 3,
+            "hook_type": hook_type,
+            "cwd": "/src/c3",
+            "queued_at": queued_at,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn spool_is_read_in_order_and_stale_hooks_are_skipped() {
+        let dir = std::env::temp_dir().join(format!("c3-hook-spool-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("1700000100-42.json"), payload("Stop", Some(100))).unwrap();
+        fs::write(
+            dir.join("1700000050-41.json"),
+            payload("SessionStart", Some(50)),
+        )
+        .unwrap();
+        fs::write(dir.join("1700000200-43.json"), "{ truncated").unwrap();
+        fs::write(dir.join("notes.txt"), "not a payload").unwrap();
+
+        let spooled = read_spool(&dir);
+        let hook_types: Vec<Option<&str>> = spooled
+            .iter()
+            .map(|(_, n)| n.as_ref().map(|n| n.hook_type.as_str()))
+            .collect();
+        assert_eq!(hook_types, vec![Some("SessionStart"), Some("Stop"), None]);
+        // Claimed, so a second reader gets nothing
+        assert!(!dir.join("1700000100-42.json").exists());
+        assert!(read_spool(&dir).is_empty());
+
+        let stop = spooled[1].1.as_ref().unwrap();
+        assert!(is_fresh(stop, 100 + MAX_AGE_SECS));
+        assert!(!is_fresh(stop, 101 + MAX_AGE_SECS));
+        let live: HookNotification = serde_json::from_str(&payload("Stop", None)).unwrap();
+        assert!(is_fresh(&live, i64::MAX));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
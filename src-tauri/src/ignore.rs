@@ -0,0 +1,130 @@
+use glob::Pattern;
+
+/// Projects, tmux sessions and agents C3 should never track, from the
+/// `ignored_paths`, `ignored_tmux_sessions` and `disabled_agents` settings
+#[derive(Default)]
+pub struct IgnoreList {
+    paths: Vec<Pattern>,
+    tmux_sessions: Vec<Pattern>,
+    agents: Vec<String>,
+}
+
+/// `~/scratch/**` → `/Users/ann/scratch/**`
+pub(crate) fn expand_home(pattern: &str, home: Option<&str>) -> String {
+    match (pattern.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) => format!("{}{}", home, rest),
+        _ => pattern.to_string(),
+    }
+}
+
+/// A project path glob from settings, with `~` expanded
+pub(crate) fn path_pattern(pattern: &str) -> Option<Pattern> {
+    let home = std::env::var("HOME").ok();
+    Pattern::new(&expand_home(pattern.trim(), home.as_deref())).ok()
+}
+
+/// `dir/**` also covers `dir` itself
+pub(crate) fn matches_path(pattern: &Pattern, cwd: &str) -> bool {
+    let with_slash = format!("{}/", cwd.trim_end_matches('/'));
+    pattern.matches(cwd) || pattern.matches(&with_slash)
+}
+
+fn compile(patterns: &[String], home: Option<&str>, kind: &str) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| match Pattern::new(&expand_home(p, home)) {
+            Ok(pattern) => Some(pattern),
+            Err(e) => {
+                log::warn!("Ignoring invalid {} pattern {:?}: {}", kind, p, e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl IgnoreList {
+    /// Invalid patterns are skipped (update_settings rejects them up front)
+    pub fn new(paths: &[String], tmux_sessions: &[String], disabled_agents: &[String]) -> Self {
+        let home = std::env::var("HOME").ok();
+        Self {
+            paths: compile(paths, home.as_deref(), "ignored path"),
+            tmux_sessions: compile(tmux_sessions, None, "ignored tmux session"),
+            agents: disabled_agents.to_vec(),
+        }
+    }
+
+    pub fn ignores_path(&self, cwd: &str) -> bool {
+        self.paths.iter().any(|p| matches_path(p, cwd))
+    }
+
+    /// Matches the session part of a `session:window.pane` target
+    pub fn ignores_tmux_target(&self, target: &str) -> bool {
+        let session = target.split(':').next().unwrap_or(target);
+        self.tmux_sessions.iter().any(|p| p.matches(session))
+    }
+
+    /// Agent kinds switched off in Settings
+    pub fn ignores_agent(&self, kind: &str) -> bool {
+        self.agents.iter().any(|agent| agent == kind)
+    }
+
+    pub fn has_tmux_patterns(&self) -> bool {
+        !self.tmux_sessions.is_empty()
+    }
+
+    pub fn ignores(&self, cwd: &str, tmux_target: Option<&str>) -> bool {
+        self.ignores_path(cwd) || tmux_target.is_some_and(|t| self.ignores_tmux_target(t))
+    }
+}
+
+/// Reject patterns that don't parse, so a typo isn't silently ignored
+pub fn validate_patterns(paths: &[String], tmux_sessions: &[String]) -> Result<(), String> {
+    for pattern in paths.iter().filter(|p| !p.trim().is_empty()) {
+        Pattern::new(pattern.trim())
+            .map_err(|e| format!("Invalid ignored path {:?}: {}", pattern, e))?;
+    }
+    for pattern in tmux_sessions.iter().filter(|p| !p.trim().is_empty()) {
+        Pattern::new(pattern.trim())
+            .map_err(|e| format!("Invalid ignored tmux session {:?}: {}", pattern, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(paths: &[&str], sessions: &[&str]) -> IgnoreList {
+        let strings = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        IgnoreList {
+            paths: compile(&strings(paths), Some("/Users/ann"), "path"),
+            tmux_sessions: compile(&strings(sessions), None, "session"),
+            agents: vec!["aider".to_string()],
+        }
+    }
+
+    #[test]
+    fn path_globs_cover_the_directory_and_below() {
+        let ignore = list(&["~/scratch/**", "/tmp/*"], &[]);
+        assert!(ignore.ignores_path("/Users/ann/scratch"));
+        assert!(ignore.ignores_path("/Users/ann/scratch/a/b"));
+        assert!(ignore.ignores_path("/tmp/try"));
+        assert!(!ignore.ignores_path("/Users/ann/scratchpad"));
+        assert!(!ignore.ignores_path("/Users/ann/code/api"));
+    }
+
+    #[test]
+    fn tmux_patterns_match_the_session_name() {
+        let ignore = list(&[], &["dotfiles", "tmp-*"]);
+        assert!(ignore.ignores_tmux_target("dotfiles:1.0"));
+        assert!(ignore.ignores_tmux_target("tmp-42:0.1"));
+        assert!(!ignore.ignores_tmux_target("work:1.0"));
+        assert!(ignore.ignores("/Users/ann/code", Some("dotfiles:2.0")));
+        assert!(!ignore.ignores("/Users/ann/code", None));
+        assert!(ignore.ignores_agent("aider"));
+        assert!(!ignore.ignores_agent("claude"));
+        assert!(validate_patterns(&["[".to_string()], &[]).is_err());
+    }
+}
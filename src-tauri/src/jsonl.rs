@@ -0,0 +1,722 @@
+use crate::{PendingAction, SessionState};
+use chrono::{DateTime, Utc};
+
+/// State derived from reading JSONL conversation files
+#[derive(Debug)]
+pub(crate) struct ConversationState {
+    pub state: SessionState,
+    pub pending_action: Option<PendingAction>,
+    pub last_message_time: Option<DateTime<Utc>>,
+}
+
+/// Longest question shown in place of "Waiting for user input"
+const MAX_QUESTION_CHARS: usize = 200;
+/// Claude's multiple-choice question tool
+pub(crate) const ASK_TOOL: &str = "AskUserQuestion";
+
+pub(crate) fn awaiting_input_state(last_message_time: Option<DateTime<Utc>>) -> ConversationState {
+    ConversationState {
+        state: SessionState::AwaitingInput,
+        pending_action: Some(input_action()),
+        last_message_time,
+    }
+}
+
+fn input_action() -> PendingAction {
+    PendingAction {
+        action_type: "input".to_string(),
+        description: "Waiting for user input".to_string(),
+        tool: None,
+        command: None,
+        options: Vec::new(),
+    }
+}
+
+/// Waiting for an answer to `question`, described with its options after it:
+/// "Which would you prefer? 1. Inject a clock · 2. Widen the TTL"
+fn question_action(question: &str, options: Vec<String>, tool: Option<&str>) -> PendingAction {
+    let mut description = question.to_string();
+    if !options.is_empty() {
+        description = format!("{} {}", description, options.join(" · "));
+    }
+    if description.chars().count() > MAX_QUESTION_CHARS {
+        let cut: String = description.chars().take(MAX_QUESTION_CHARS - 1).collect();
+        description = format!("{}…", cut.trim_end());
+    }
+    PendingAction {
+        action_type: "input".to_string(),
+        description,
+        tool: tool.map(str::to_string),
+        command: None,
+        options,
+    }
+}
+
+/// The first question of an AskUserQuestion call. Its options can be
+/// answered from C3 only when it is the call's one single-choice question.
+pub(crate) fn ask_tool_question(input: &serde_json::Value) -> Option<PendingAction> {
+    let questions = input.get("questions")?.as_array()?;
+    let first = questions.first()?;
+    let question = first.get("question")?.as_str()?;
+    let single_choice = questions.len() == 1
+        && !first
+            .get("multiSelect")
+            .and_then(|m| m.as_bool())
+            .unwrap_or(false);
+    let options = first
+        .get("options")
+        .and_then(|o| o.as_array())
+        .filter(|_| single_choice)
+        .map(|options| {
+            options
+                .iter()
+                .filter_map(|o| o.get("label").and_then(|l| l.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(question_action(question, options, Some(ASK_TOOL)))
+}
+
+/// The question asked by an assistant message's last tool call, when that is AskUserQuestion
+fn ask_tool_action(blocks: &[serde_json::Value]) -> Option<PendingAction> {
+    let call = blocks
+        .iter()
+        .rev()
+        .find(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))?;
+    if call.get("name").and_then(|n| n.as_str()) != Some(ASK_TOOL) {
+        return None;
+    }
+    ask_tool_question(call.get("input")?)
+}
+
+/// Text of a message's last text block
+fn last_text(message: &serde_json::Value) -> Option<&str> {
+    match message.get("content")? {
+        serde_json::Value::String(text) => Some(text),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .rev()
+            .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .find_map(|b| b.get("text").and_then(|t| t.as_str())),
+        _ => None,
+    }
+}
+
+/// A line without markdown emphasis or heading and quote markers
+fn plain_line(line: &str) -> String {
+    line.replace("**", "")
+        .trim()
+        .trim_start_matches(['#', '>'])
+        .trim()
+        .to_string()
+}
+
+/// The label of a numbered or bulleted list item: "1. Inject a clock" from
+/// "1. **Inject a clock** - deterministic". None for other lines.
+fn option_label(line: &str) -> Option<String> {
+    let line = plain_line(line);
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let label =
+        if digits > 0 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") ")) {
+            line.as_str()
+        } else {
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .or_else(|| line.strip_prefix("• "))?
+        };
+    let end = [" - ", " — ", ": "]
+        .iter()
+        .filter_map(|sep| label.find(sep))
+        .min()
+        .unwrap_or(label.len());
+    Some(label[..end].trim().to_string())
+}
+
+/// The question a reply ends on, with the options listed around it. None when
+/// the reply doesn't end by asking something.
+fn trailing_question(text: &str) -> Option<PendingAction> {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let asked = lines
+        .iter()
+        .rposition(|line| plain_line(line).ends_with('?'))?;
+    let is_nested = |line: &&str| line.starts_with([' ', '\t']);
+
+    // Anything after the question must be the options it offers
+    let mut options = Vec::new();
+    for line in lines[asked + 1..].iter().filter(|l| !is_nested(l)) {
+        options.push(option_label(line)?);
+    }
+    // or they come just before it: "1. A\n2. B\n\nWhich would you prefer?"
+    if options.is_empty() {
+        for line in lines[..asked].iter().rev().filter(|l| !is_nested(l)) {
+            match option_label(line) {
+                Some(option) => options.insert(0, option),
+                None => break,
+            }
+        }
+    }
+
+    // Only the sentence that asks, not the explanation leading up to it
+    let line = plain_line(lines[asked]);
+    let start = line[..line.len() - 1]
+        .match_indices(['.', '!', '?'])
+        .rev()
+        .find(|(i, _)| line[i + 1..].starts_with(' '))
+        .map_or(0, |(i, _)| i + 1);
+    Some(question_action(line[start..].trim(), options, None))
+}
+
+/// The question Claude's last message asks, with AskUserQuestion or at the
+/// end of a reply that finished its turn
+pub(crate) fn pending_question(last_lines: &[String]) -> Option<PendingAction> {
+    let parsed = last_lines
+        .iter()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find(is_conversation_message)?;
+    if parsed.get("type").and_then(|t| t.as_str()) != Some("assistant") {
+        return None;
+    }
+    let message = parsed.get("message")?;
+    if let Some(blocks) = message.get("content").and_then(|c| c.as_array()) {
+        if blocks
+            .iter()
+            .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use"))
+        {
+            return ask_tool_action(blocks);
+        }
+    }
+    trailing_question(last_text(message)?)
+}
+
+/// Check if a JSONL message is a real conversation message (not system noise)
+pub(crate) fn is_conversation_message(parsed: &serde_json::Value) -> bool {
+    let msg_type = parsed.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+    // Skip non-conversation message types entirely
+    if matches!(
+        msg_type,
+        "progress" | "system" | "file-history-snapshot" | "summary"
+    ) {
+        return false;
+    }
+
+    // Skip messages with isMeta flag
+    if parsed
+        .get("isMeta")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+    {
+        return false;
+    }
+
+    // For user messages, skip internal Claude Code bookkeeping
+    if msg_type == "user" {
+        let content = parsed.get("message").and_then(|m| m.get("content"));
+
+        if let Some(serde_json::Value::String(text)) = content {
+            // These are Claude Code internal messages, not real user input
+            if text.starts_with("<local-command-caveat>")
+                || text.starts_with("<bash-input>")
+                || text.starts_with("<bash-stdout>")
+                || text.starts_with("<bash-stderr>")
+                || text == "[Request interrupted by user]"
+            {
+                return false;
+            }
+        }
+
+        // Also check array content for interrupt markers
+        if let Some(serde_json::Value::Array(blocks)) = content {
+            let has_interrupt = blocks.iter().any(|b| {
+                b.get("type").and_then(|t| t.as_str()) == Some("text")
+                    && b.get("text")
+                        .and_then(|t| t.as_str())
+                        .map(|t| t.contains("[Request interrupted by user]"))
+                        .unwrap_or(false)
+            });
+            if has_interrupt {
+                return false;
+            }
+        }
+    }
+
+    // Must be "user" or "assistant" type with a role
+    let role = parsed
+        .get("message")
+        .and_then(|m| m.get("role"))
+        .and_then(|r| r.as_str())
+        .unwrap_or("");
+
+    matches!(
+        (msg_type, role),
+        ("user", "user") | ("assistant", "assistant")
+    )
+}
+
+/// Extract a timestamp from a JSONL message
+pub(crate) fn extract_message_timestamp(parsed: &serde_json::Value) -> Option<DateTime<Utc>> {
+    // Try top-level timestamp first (ISO 8601 string)
+    if let Some(ts) = parsed.get("timestamp").and_then(|v| v.as_str()) {
+        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
+            return Some(dt);
+        }
+    }
+    // Try nested message timestamp
+    if let Some(ts) = parsed
+        .get("message")
+        .and_then(|m| m.get("timestamp"))
+        .and_then(|v| v.as_str())
+    {
+        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
+            return Some(dt);
+        }
+    }
+    // Try nested data.message.timestamp (progress messages)
+    if let Some(ts) = parsed
+        .get("data")
+        .and_then(|d| d.get("message"))
+        .and_then(|m| m.get("timestamp"))
+        .and_then(|v| v.as_str())
+    {
+        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
+            return Some(dt);
+        }
+    }
+    None
+}
+
+/// Determine Claude Code session state from the last lines of its JSONL transcript.
+/// `file_age_secs` is how long ago the transcript was last written.
+pub(crate) fn detect_claude_state(last_lines: &[String], file_age_secs: u64) -> ConversationState {
+    if last_lines.is_empty() {
+        return ConversationState {
+            state: SessionState::Processing,
+            pending_action: None,
+            last_message_time: None,
+        };
+    }
+
+    // Find the latest timestamp from any message in the last lines
+    let mut latest_timestamp: Option<DateTime<Utc>> = None;
+    for line in last_lines.iter().rev() {
+        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
+            if let Some(ts) = extract_message_timestamp(&parsed) {
+                if latest_timestamp.is_none() || ts > latest_timestamp.unwrap() {
+                    latest_timestamp = Some(ts);
+                    break; // Lines are in order, so the last one with a timestamp is the most recent
+                }
+            }
+        }
+    }
+
+    // Walk backwards through lines, skipping noise, to find last real message
+    for line in last_lines.iter().rev() {
+        let parsed: serde_json::Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+
+        if !is_conversation_message(&parsed) {
+            continue;
+        }
+
+        let msg_type = parsed.get("type").and_then(|v| v.as_str()).unwrap_or("");
+        let message = parsed.get("message").unwrap_or(&serde_json::Value::Null);
+        let content = message.get("content");
+
+        match msg_type {
+            "user" => {
+                // Check if this is a tool_result (part of ongoing tool use chain)
+                if let Some(serde_json::Value::Array(blocks)) = content {
+                    let has_tool_result = blocks
+                        .iter()
+                        .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"));
+                    if has_tool_result {
+                        return ConversationState {
+                            state: SessionState::Processing,
+                            pending_action: None,
+                            last_message_time: latest_timestamp,
+                        };
+                    }
+                }
+                // Real user message — if file is stale, Claude already
+                // processed it and is waiting for more input. If fresh,
+                // Claude is actively generating a response.
+                if file_age_secs > 15 {
+                    return ConversationState {
+                        state: SessionState::AwaitingInput,
+                        pending_action: Some(PendingAction {
+                            action_type: "input".to_string(),
+                            description: "Waiting for user input".to_string(),
+                            tool: None,
+                            command: None,
+                            options: Vec::new(),
+                        }),
+                        last_message_time: latest_timestamp,
+                    };
+                }
+                return ConversationState {
+                    state: SessionState::Processing,
+                    pending_action: None,
+                    last_message_time: latest_timestamp,
+                };
+            }
+
+            "assistant" => {
+                if let Some(serde_json::Value::Array(blocks)) = content {
+                    let block_types: Vec<&str> = blocks
+                        .iter()
+                        .filter_map(|b| b.get("type").and_then(|t| t.as_str()))
+                        .collect();
+
+                    // AskUserQuestion waits for an answer as soon as it's written
+                    if let Some(action) = ask_tool_action(blocks) {
+                        return ConversationState {
+                            state: SessionState::AwaitingInput,
+                            pending_action: Some(action),
+                            last_message_time: latest_timestamp,
+                        };
+                    }
+
+                    // Has tool_use → either actively running or awaiting permission
+                    if block_types.contains(&"tool_use") {
+                        if file_age_secs > 5 {
+                            // Stale file + tool_use = likely awaiting permission
+                            let tool_name = blocks
+                                .iter()
+                                .filter(|b| {
+                                    b.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                                })
+                                .last()
+                                .and_then(|b| b.get("name"))
+                                .and_then(|n| n.as_str())
+                                .map(|s| s.to_string());
+
+                            let command = blocks
+                                .iter()
+                                .filter(|b| {
+                                    b.get("type").and_then(|t| t.as_str()) == Some("tool_use")
+                                })
+                                .last()
+                                .and_then(|b| b.get("input"))
+                                .and_then(|i| i.get("command"))
+                                .and_then(|c| c.as_str())
+                                .map(|s| {
+                                    if s.len() > 100 {
+                                        format!("{}...", &s[..97])
+                                    } else {
+                                        s.to_string()
+                                    }
+                                });
+
+                            return ConversationState {
+                                state: SessionState::AwaitingPermission,
+                                pending_action: Some(PendingAction {
+                                    action_type: "permission".to_string(),
+                                    description: format!(
+                                        "Wants to use {}",
+                                        tool_name.as_deref().unwrap_or("a tool")
+                                    ),
+                                    tool: tool_name,
+                                    command,
+                                    options: Vec::new(),
+                                }),
+                                last_message_time: latest_timestamp,
+                            };
+                        }
+                        // Fresh file + tool_use = actively processing
+                        return ConversationState {
+                            state: SessionState::Processing,
+                            pending_action: None,
+                            last_message_time: latest_timestamp,
+                        };
+                    }
+
+                    // Text/thinking only, no tool_use → Claude finished, waiting for input
+                    if block_types.contains(&"text") {
+                        return ConversationState {
+                            state: SessionState::AwaitingInput,
+                            pending_action: Some(
+                                last_text(message)
+                                    .and_then(trailing_question)
+                                    .unwrap_or_else(input_action),
+                            ),
+                            last_message_time: latest_timestamp,
+                        };
+                    }
+
+                    // Only thinking block (no text yet) → still processing
+                    if block_types.contains(&"thinking") && !block_types.contains(&"text") {
+                        return ConversationState {
+                            state: SessionState::Processing,
+                            pending_action: None,
+                            last_message_time: latest_timestamp,
+                        };
+                    }
+                }
+
+                // String content → simple text response, waiting for input
+                if content.map(|c| c.is_string()).unwrap_or(false) {
+                    return ConversationState {
+                        state: SessionState::AwaitingInput,
+                        pending_action: Some(
+                            last_text(message)
+                                .and_then(trailing_question)
+                                .unwrap_or_else(input_action),
+                        ),
+                        last_message_time: latest_timestamp,
+                    };
+                }
+            }
+
+            _ => continue,
+        }
+    }
+
+    // No real conversation messages found in the last 30 lines.
+    // If file is stale, Claude is idle waiting for input.
+    if file_age_secs > 15 {
+        return ConversationState {
+            state: SessionState::AwaitingInput,
+            pending_action: Some(PendingAction {
+                action_type: "input".to_string(),
+                description: "Waiting for user input".to_string(),
+                tool: None,
+                command: None,
+                options: Vec::new(),
+            }),
+            last_message_time: latest_timestamp,
+        };
+    }
+    ConversationState {
+        state: SessionState::Processing,
+        pending_action: None,
+        last_message_time: latest_timestamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! fixture {
+        ($name:literal) => {
+            include_str!(concat!("../fixtures/jsonl/claude/", $name, ".jsonl"))
+        };
+    }
+
+    /// A transcript, how long ago it was written, and the state it should yield
+    struct Case {
+        name: &'static str,
+        contents: &'static str,
+        file_age_secs: u64,
+        state: SessionState,
+        tool: Option<&'static str>,
+    }
+
+    fn lines(contents: &str) -> Vec<String> {
+        contents
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn ts(value: &str) -> Option<DateTime<Utc>> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn fixture_corpus() {
+        let cases = [
+            Case {
+                name: "plan mode, plan not yet approved",
+                contents: fixture!("plan_mode"),
+                file_age_secs: 30,
+                state: SessionState::AwaitingPermission,
+                tool: Some("ExitPlanMode"),
+            },
+            Case {
+                name: "plan mode, plan being written",
+                contents: fixture!("plan_mode"),
+                file_age_secs: 1,
+                state: SessionState::Processing,
+                tool: None,
+            },
+            Case {
+                name: "AskUserQuestion waits for an answer at once",
+                contents: fixture!("ask_user_question"),
+                file_age_secs: 1,
+                state: SessionState::AwaitingInput,
+                tool: Some("AskUserQuestion"),
+            },
+            Case {
+                name: "tool result waiting for the next assistant turn",
+                contents: fixture!("tool_chain_running"),
+                file_age_secs: 60,
+                state: SessionState::Processing,
+                tool: None,
+            },
+            Case {
+                name: "tool chain ending in a text reply",
+                contents: fixture!("tool_chain_finished"),
+                file_age_secs: 1,
+                state: SessionState::AwaitingInput,
+                tool: None,
+            },
+            Case {
+                name: "interrupt marker is skipped",
+                contents: fixture!("interrupted"),
+                file_age_secs: 1,
+                state: SessionState::AwaitingInput,
+                tool: None,
+            },
+            Case {
+                name: "compaction summary being answered",
+                contents: fixture!("compaction"),
+                file_age_secs: 2,
+                state: SessionState::Processing,
+                tool: None,
+            },
+            Case {
+                name: "compaction summary left idle",
+                contents: fixture!("compaction"),
+                file_age_secs: 60,
+                state: SessionState::AwaitingInput,
+                tool: None,
+            },
+            Case {
+                name: "malformed trailing lines are ignored",
+                contents: fixture!("malformed"),
+                file_age_secs: 1,
+                state: SessionState::AwaitingInput,
+                tool: None,
+            },
+            Case {
+                name: "only bookkeeping, fresh",
+                contents: fixture!("noise_only"),
+                file_age_secs: 1,
+                state: SessionState::Processing,
+                tool: None,
+            },
+            Case {
+                name: "only bookkeeping, stale",
+                contents: fixture!("noise_only"),
+                file_age_secs: 60,
+                state: SessionState::AwaitingInput,
+                tool: None,
+            },
+        ];
+
+        for case in cases {
+            let detected = detect_claude_state(&lines(case.contents), case.file_age_secs);
+            assert_eq!(detected.state, case.state, "{}", case.name);
+            assert_eq!(
+                detected
+                    .pending_action
+                    .as_ref()
+                    .and_then(|a| a.tool.as_deref()),
+                case.tool,
+                "{}",
+                case.name
+            );
+        }
+    }
+
+    #[test]
+    fn a_reply_ending_in_a_question_describes_the_wait() {
+        let question = "Which would you prefer? 1. Inject a clock · 2. Widen the TTL in tests";
+        let detected = detect_claude_state(&lines(fixture!("question")), 1);
+        assert_eq!(detected.state, SessionState::AwaitingInput);
+        assert_eq!(
+            detected.pending_action.map(|a| a.description).as_deref(),
+            Some(question)
+        );
+        let pending = pending_question(&lines(fixture!("question"))).unwrap();
+        assert_eq!(pending.description, question);
+        assert_eq!(
+            pending.options,
+            ["1. Inject a clock", "2. Widen the TTL in tests"]
+        );
+        assert_eq!(
+            pending_question(&lines(fixture!("tool_chain_finished"))),
+            None
+        );
+        assert_eq!(pending_question(&lines(fixture!("plan_mode"))), None);
+
+        let described = |text: &str| trailing_question(text).map(|a| a.description);
+        assert_eq!(
+            described("Tests pass. Should I proceed with approach A or B?").as_deref(),
+            Some("Should I proceed with approach A or B?")
+        );
+        assert_eq!(
+            described("How should I handle the old config?\n- **Migrate** it\n- Delete it")
+                .as_deref(),
+            Some("How should I handle the old config? Migrate it · Delete it")
+        );
+        // A question earlier in the reply isn't what it ends on
+        assert_eq!(
+            described("Why did it fail? The lock was held. Fixed it."),
+            None
+        );
+        assert_eq!(described("All 29 tests pass."), None);
+    }
+
+    #[test]
+    fn ask_user_question_offers_its_options() {
+        let detected = detect_claude_state(&lines(fixture!("ask_user_question")), 1);
+        let action = detected.pending_action.unwrap();
+        assert_eq!(
+            action.description,
+            "Where should cached responses live? In memory · On disk · Redis"
+        );
+        assert_eq!(action.options, ["In memory", "On disk", "Redis"]);
+        assert_eq!(
+            pending_question(&lines(fixture!("ask_user_question"))),
+            Some(action)
+        );
+
+        // Several questions, or one taking several answers, are answered in Claude
+        let two = serde_json::json!({ "questions": [
+            { "question": "Which store?", "options": [{ "label": "Disk" }] },
+            { "question": "Which TTL?", "options": [{ "label": "1h" }] }
+        ]});
+        let asked = ask_tool_question(&two).unwrap();
+        assert_eq!(asked.description, "Which store?");
+        assert!(asked.options.is_empty());
+        let multi = serde_json::json!({ "questions": [
+            { "question": "Which stores?", "multiSelect": true, "options": [{ "label": "Disk" }] }
+        ]});
+        assert!(ask_tool_question(&multi).unwrap().options.is_empty());
+    }
+
+    #[test]
+    fn empty_transcript_is_processing() {
+        let detected = detect_claude_state(&[], 600);
+        assert_eq!(detected.state, SessionState::Processing);
+        assert_eq!(detected.last_message_time, None);
+    }
+
+    #[test]
+    fn last_message_time_comes_from_latest_timestamped_line() {
+        let detected = detect_claude_state(&lines(fixture!("malformed")), 1);
+        assert_eq!(detected.last_message_time, ts("2026-03-02T14:00:30.000Z"));
+
+        // Progress records carry their timestamp under data.message
+        let detected = detect_claude_state(&lines(fixture!("noise_only")), 60);
+        assert_eq!(detected.last_message_time, ts("2026-03-02T15:00:05.000Z"));
+    }
+
+    #[test]
+    fn bookkeeping_records_are_not_conversation_messages() {
+        for line in lines(fixture!("noise_only")) {
+            let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+            assert!(!is_conversation_message(&parsed), "{}", line);
+        }
+        let interrupt: serde_json::Value =
+            serde_json::from_str(lines(fixture!("interrupted")).last().unwrap()).unwrap();
+        assert!(!is_conversation_message(&interrupt));
+    }
+}
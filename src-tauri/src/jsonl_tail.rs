@@ -0,0 +1,215 @@
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Lines kept per file; callers ask for at most the last 50.
+const MAX_TAIL_LINES: usize = 200;
+/// How much of an unseen file is read on first contact. Transcripts can be
+/// hundreds of MB, but state detection only ever looks at the tail.
+const INITIAL_READ_BYTES: u64 = 512 * 1024;
+/// Files not read for this long are dropped from the cache
+const IDLE_EVICT_AFTER: Duration = Duration::from_secs(600);
+
+/// Read position and rolling tail buffer for one JSONL file
+#[derive(Debug)]
+struct TailState {
+    /// Byte offset up to which the file has been consumed
+    offset: u64,
+    /// File identity, used to detect rotation (replaced file, same path)
+    file_id: u64,
+    /// Bytes after the last newline — a record still being written
+    partial: Vec<u8>,
+    lines: VecDeque<String>,
+    last_read: Instant,
+}
+
+impl TailState {
+    fn new(file_id: u64) -> Self {
+        Self {
+            offset: 0,
+            file_id,
+            partial: Vec::new(),
+            lines: VecDeque::new(),
+            last_read: Instant::now(),
+        }
+    }
+}
+
+/// Incremental reader for append-only JSONL transcripts.
+/// Only data appended since the previous read is pulled from disk; the file is
+/// re-read from scratch when it is truncated or replaced.
+#[derive(Default)]
+pub struct JsonlTails {
+    /// Each file has its own lock, so reading one doesn't hold up the others
+    files: Mutex<HashMap<PathBuf, Arc<Mutex<TailState>>>>,
+}
+
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.ino()
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> u64 {
+    0
+}
+
+impl JsonlTails {
+    /// Return the last `n` complete lines of `path`
+    pub fn read_last_lines(&self, path: &Path, n: usize) -> Vec<String> {
+        let metadata = match fs::metadata(path) {
+            Ok(m) => m,
+            Err(_) => {
+                self.files.lock().remove(path);
+                return vec![];
+            }
+        };
+        let len = metadata.len();
+        let id = file_id(&metadata);
+
+        let tail = {
+            let mut files = self.files.lock();
+            if files.len() > 64 {
+                // Files being read right now are in use
+                files.retain(|_, tail| {
+                    tail.try_lock()
+                        .is_none_or(|tail| tail.last_read.elapsed() < IDLE_EVICT_AFTER)
+                });
+            }
+            files
+                .entry(path.to_path_buf())
+                .or_insert_with(|| Arc::new(Mutex::new(TailState::new(id))))
+                .clone()
+        };
+        let mut tail = tail.lock();
+        if tail.file_id != id || len < tail.offset {
+            log::debug!("{} was truncated or rotated, re-reading", path.display());
+            *tail = TailState::new(id);
+        }
+        tail.last_read = Instant::now();
+
+        if len > tail.offset {
+            if let Err(e) = read_appended(path, len, &mut tail) {
+                log::warn!("Failed to read {}: {}", path.display(), e);
+                *tail = TailState::new(id);
+                return vec![];
+            }
+        }
+
+        let skip = tail.lines.len().saturating_sub(n);
+        tail.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// Forget the read position for `path` so the next read starts over
+    pub fn reset(&self, path: &Path) {
+        self.files.lock().remove(path);
+    }
+
+    /// Files being followed, and how long ago the most recent read was
+    pub fn status(&self) -> (usize, Option<Duration>) {
+        let files = self.files.lock();
+        let latest = files
+            .values()
+            .map(|tail| {
+                tail.try_lock()
+                    .map_or(Duration::ZERO, |tail| tail.last_read.elapsed())
+            })
+            .min();
+        (files.len(), latest)
+    }
+}
+
+fn read_appended(path: &Path, len: u64, tail: &mut TailState) -> std::io::Result<()> {
+    let mut file = fs::File::open(path)?;
+    let mut start = tail.offset;
+    let mut skip_first_line = false;
+    if start == 0 && len > INITIAL_READ_BYTES {
+        // Start near the end and drop the (likely cut) first line
+        start = len - INITIAL_READ_BYTES;
+        skip_first_line = true;
+    }
+
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = Vec::with_capacity((len - start) as usize);
+    file.take(len - start).read_to_end(&mut buf)?;
+    tail.offset = start + buf.len() as u64;
+
+    let mut data = std::mem::take(&mut tail.partial);
+    data.extend_from_slice(&buf);
+
+    let mut segments = data.split(|b| *b == b'\n').peekable();
+    if skip_first_line {
+        segments.next();
+    }
+    while let Some(segment) = segments.next() {
+        if segments.peek().is_none() {
+            // No trailing newline yet: keep the incomplete record for next time
+            tail.partial = segment.to_vec();
+            break;
+        }
+        let line = String::from_utf8_lossy(segment);
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            continue;
+        }
+        tail.lines.push_back(line.to_string());
+    }
+
+    while tail.lines.len() > MAX_TAIL_LINES {
+        tail.lines.pop_front();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "c3-tail-{name}-{}-{}.jsonl",
+            std::process::id(),
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ))
+    }
+
+    #[test]
+    fn appended_lines_are_picked_up_and_partial_records_held_back() {
+        let path = temp_path("append");
+        let tails = JsonlTails::default();
+        fs::write(&path, "{\"a\":1}\n{\"a\":2}\n").unwrap();
+        assert_eq!(
+            tails.read_last_lines(&path, 10),
+            vec!["{\"a\":1}", "{\"a\":2}"]
+        );
+
+        let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "{{\"a\":3}}\n{{\"a\":").unwrap();
+        assert_eq!(
+            tails.read_last_lines(&path, 2),
+            vec!["{\"a\":2}", "{\"a\":3}"]
+        );
+
+        writeln!(file, "4}}").unwrap();
+        assert_eq!(tails.read_last_lines(&path, 1), vec!["{\"a\":4}"]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn truncated_file_is_reread_from_scratch() {
+        let path = temp_path("truncate");
+        let tails = JsonlTails::default();
+        fs::write(&path, "{\"a\":1}\n{\"a\":2}\n{\"a\":3}\n").unwrap();
+        assert_eq!(tails.read_last_lines(&path, 10).len(), 3);
+
+        fs::write(&path, "{\"b\":1}\n").unwrap();
+        assert_eq!(tails.read_last_lines(&path, 10), vec!["{\"b\":1}"]);
+        let _ = fs::remove_file(path);
+    }
+}
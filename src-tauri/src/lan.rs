@@ -0,0 +1,382 @@
+use crate::{cmd, handle_hook_request, load_settings, paths, AppState, HOOK_PORT_MIN};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use specta::Type;
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{crypto, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+/// How often settings are checked for the server being turned on, off or moved
+const CHECK_INTERVAL_SECS: u64 = 5;
+/// Connections that haven't finished the TLS handshake by then are dropped
+const HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+
+static STATUS: Mutex<LanStatus> = Mutex::new(LanStatus {
+    listening: false,
+    urls: Vec::new(),
+    fingerprint: None,
+    error: None,
+});
+
+/// Serving the `/api` routes to other devices on the network, over TLS
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct LanSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// PEM certificate chain to serve instead of a self-signed one
+    #[serde(default)]
+    pub cert_path: Option<String>,
+    /// PEM private key of `cert_path`
+    #[serde(default)]
+    pub key_path: Option<String>,
+}
+
+fn default_port() -> u16 {
+    9399
+}
+
+impl Default for LanSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_port(),
+            cert_path: None,
+            key_path: None,
+        }
+    }
+}
+
+impl LanSettings {
+    /// The provided certificate and key, if both are set
+    fn provided(&self) -> Option<(&str, &str)> {
+        let cert = self.cert_path.as_deref().map(str::trim);
+        let key = self.key_path.as_deref().map(str::trim);
+        cert.zip(key)
+            .filter(|(cert, key)| !cert.is_empty() && !key.is_empty())
+    }
+}
+
+/// Whether the LAN server is up, and how to reach it
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LanStatus {
+    pub listening: bool,
+    pub urls: Vec<String>,
+    /// SHA-256 of the served certificate, to check against on the device
+    pub fingerprint: Option<String>,
+    /// Why the server last failed to start
+    pub error: Option<String>,
+}
+
+/// Check LAN settings before they are saved
+pub(crate) fn validate(lan: &LanSettings, hook_port: u16) -> Result<(), String> {
+    if !lan.enabled {
+        return Ok(());
+    }
+    if lan.port < HOOK_PORT_MIN {
+        return Err(format!(
+            "LAN port must be between {} and 65535",
+            HOOK_PORT_MIN
+        ));
+    }
+    if lan.port == hook_port {
+        return Err("LAN port must differ from the hook port".to_string());
+    }
+    let cert = lan.cert_path.as_deref().unwrap_or_default().trim();
+    let key = lan.key_path.as_deref().unwrap_or_default().trim();
+    if cert.is_empty() != key.is_empty() {
+        return Err("Set both a certificate and its key, or neither".to_string());
+    }
+    for path in [cert, key].into_iter().filter(|path| !path.is_empty()) {
+        if !Path::new(path).is_file() {
+            return Err(format!("No such file: {}", path));
+        }
+    }
+    Ok(())
+}
+
+fn self_signed_paths() -> (PathBuf, PathBuf) {
+    let dir = paths::config_dir();
+    (dir.join("lan-cert.pem"), dir.join("lan-key.pem"))
+}
+
+fn hostname() -> Option<String> {
+    cmd("hostname")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|host| !host.is_empty())
+}
+
+/// Address of the interface that reaches the network
+fn local_ip() -> Option<IpAddr> {
+    // Connecting a UDP socket sends nothing; it only picks the route
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("192.0.2.1:9").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// Names a self-signed certificate is issued for
+fn certificate_names(host: Option<&str>, ip: Option<IpAddr>) -> Vec<String> {
+    let mut names = vec!["localhost".to_string()];
+    if let Some(host) = host {
+        names.push(host.to_string());
+        if !host.contains('.') {
+            names.push(format!("{}.local", host));
+        }
+    }
+    names.extend(ip.map(|ip| ip.to_string()));
+    names
+}
+
+fn create_self_signed(cert_path: &Path, key_path: &Path, names: Vec<String>) -> Result<(), String> {
+    let certified = rcgen::generate_simple_self_signed(names)
+        .map_err(|e| format!("Failed to create a certificate: {}", e))?;
+    if let Some(parent) = cert_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(key_path, certified.key_pair.serialize_pem()).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(key_path, fs::Permissions::from_mode(0o600));
+    }
+    fs::write(cert_path, certified.cert.pem()).map_err(|e| e.to_string())
+}
+
+type Identity = (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>);
+
+fn load(cert_path: &Path, key_path: &Path) -> Result<Identity, String> {
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Can't read {}: {}", cert_path.display(), e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificate in {}", cert_path.display()));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| format!("Can't read {}: {}", key_path.display(), e))?;
+    Ok((certs, key))
+}
+
+/// Colon-separated SHA-256, the way browsers show certificate fingerprints
+fn fingerprint(cert: &CertificateDer) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn acceptor((certs, key): Identity) -> Result<TlsAcceptor, String> {
+    let config = ServerConfig::builder_with_provider(Arc::new(crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("Certificate and key don't match: {}", e))?;
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// The TLS acceptor and status for `lan`, creating the self-signed
+/// certificate on first use
+fn setup(lan: &LanSettings) -> Result<(TlsAcceptor, LanStatus), String> {
+    let host = hostname();
+    let ip = local_ip();
+    let identity = match lan.provided() {
+        Some((cert, key)) => load(Path::new(cert), Path::new(key))?,
+        None => {
+            let (cert, key) = self_signed_paths();
+            if !cert.exists() || !key.exists() {
+                create_self_signed(&cert, &key, certificate_names(host.as_deref(), ip))?;
+                log::info!("Created a self-signed certificate at {}", cert.display());
+            }
+            load(&cert, &key)?
+        }
+    };
+    let status = LanStatus {
+        listening: true,
+        urls: ip
+            .map(|ip| ip.to_string())
+            .into_iter()
+            .chain(host)
+            .map(|host| format!("https://{}:{}", host, lan.port))
+            .collect(),
+        fingerprint: Some(fingerprint(&identity.0[0])),
+        error: None,
+    };
+    Ok((acceptor(identity)?, status))
+}
+
+/// Wait until the LAN settings are no longer `lan`; true if C3 is shutting
+/// down instead
+async fn wait_for_change(
+    lan: &LanSettings,
+    shutdown: &mut tokio::sync::watch::Receiver<bool>,
+) -> bool {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {
+                if load_settings().lan != *lan {
+                    return false;
+                }
+            }
+            _ = shutdown.changed() => return true,
+        }
+    }
+}
+
+/// Serve until the settings change; true if C3 is shutting down instead
+async fn serve(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    lan: &LanSettings,
+    shutdown: &mut tokio::sync::watch::Receiver<bool>,
+) -> bool {
+    let lan_setup = lan.clone();
+    let setup = tokio::task::spawn_blocking(move || setup(&lan_setup))
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+    let addr = format!("0.0.0.0:{}", lan.port);
+    let (listener, acceptor) = match setup {
+        Ok((acceptor, status)) => match TcpListener::bind(&addr).await {
+            Ok(listener) => {
+                log::info!("C3 API listening on https://{}", addr);
+                *STATUS.lock() = status;
+                (listener, acceptor)
+            }
+            Err(e) => {
+                log::error!("Failed to bind the LAN server on {}: {}", addr, e);
+                STATUS.lock().error = Some(e.to_string());
+                return wait_for_change(lan, shutdown).await;
+            }
+        },
+        Err(e) => {
+            log::error!("LAN server not started: {}", e);
+            STATUS.lock().error = Some(e);
+            return wait_for_change(lan, shutdown).await;
+        }
+    };
+
+    let mut check = tokio::time::interval(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS));
+    check.tick().await;
+    let shutting_down = loop {
+        tokio::select! {
+            result = listener.accept() => {
+                let Ok((stream, peer)) = result else {
+                    continue;
+                };
+                let (acceptor, state, app_handle) =
+                    (acceptor.clone(), state.clone(), app_handle.clone());
+                tokio::spawn(async move {
+                    let handshake = tokio::time::timeout(
+                        tokio::time::Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+                        acceptor.accept(stream),
+                    );
+                    match handshake.await {
+                        Ok(Ok(mut tls)) => {
+                            handle_hook_request(&mut tls, state, app_handle, true).await;
+                            let _ = tls.shutdown().await;
+                        }
+                        Ok(Err(e)) => log::debug!("TLS handshake with {} failed: {}", peer, e),
+                        Err(_) => log::debug!("TLS handshake with {} timed out", peer),
+                    }
+                });
+            }
+            _ = check.tick() => {
+                if load_settings().lan != *lan {
+                    break false;
+                }
+            }
+            _ = shutdown.changed() => break true,
+        }
+    };
+    *STATUS.lock() = LanStatus::default();
+    shutting_down
+}
+
+/// Serve the `/api` routes over TLS on all interfaces while turned on in
+/// settings. Requests always need an API token here.
+pub async fn start_lan_server(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        let lan = load_settings().lan;
+        let shutting_down = if lan.enabled {
+            serve(&state, &app_handle, &lan, &mut shutdown).await
+        } else {
+            *STATUS.lock() = LanStatus::default();
+            wait_for_change(&lan, &mut shutdown).await
+        };
+        if shutting_down {
+            log::info!("LAN server shutting down");
+            break;
+        }
+    }
+}
+
+// Tauri command: Whether the LAN server is listening, its URLs and certificate fingerprint
+#[tauri::command]
+#[specta::specta]
+pub fn get_lan_status() -> LanStatus {
+    STATUS.lock().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn self_signed_certificates_are_served() {
+        let names = certificate_names(Some("studio"), "192.168.1.20".parse().ok());
+        assert_eq!(
+            names,
+            ["localhost", "studio", "studio.local", "192.168.1.20"]
+        );
+
+        let dir = std::env::temp_dir().join(format!("c3-lan-{}", std::process::id()));
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        create_self_signed(&cert, &key, names).unwrap();
+        let identity = load(&cert, &key).unwrap();
+        let print = fingerprint(&identity.0[0]);
+        assert_eq!(print.len(), 32 * 3 - 1);
+        assert!(acceptor(identity).is_ok());
+        assert!(load(&key, &key).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn lan_settings_are_checked_when_turned_on() {
+        let mut lan = LanSettings {
+            port: 80,
+            ..Default::default()
+        };
+        assert!(validate(&lan, 9398).is_ok());
+        lan.enabled = true;
+        assert!(validate(&lan, 9398).is_err());
+        lan.port = 9398;
+        assert!(validate(&lan, 9398).is_err());
+        lan.port = 9399;
+        assert!(validate(&lan, 9398).is_ok());
+        lan.cert_path = Some("/etc/c3/cert.pem".to_string());
+        assert_eq!(
+            validate(&lan, 9398),
+            Err("Set both a certificate and its key, or neither".to_string())
+        );
+        lan.key_path = Some("/nonexistent/key.pem".to_string());
+        assert!(validate(&lan, 9398).is_err());
+    }
+}
@@ -0,0 +1,278 @@
+use crate::git::git;
+use crate::{load_settings, start_agent, task_chain, AppState, SessionState};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use specta::Type;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How often queued launches are checked for room
+const DISPATCH_INTERVAL_SECS: u64 = 5;
+/// A started agent counts as working for this long, until its session shows
+/// up in the scanner or hooks
+const STARTUP_GRACE: Duration = Duration::from_secs(30);
+
+/// Room is checked and taken by one launch at a time
+static DISPATCH_LOCK: Mutex<()> = Mutex::new(());
+
+/// An agent start, or a chained prompt, waiting for fewer sessions to be
+/// working
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedLaunch {
+    pub id: String,
+    /// What asked for it, e.g. `Scheduled: standup`
+    pub label: String,
+    pub cwd: String,
+    /// The repo whose limit it counts against
+    pub repo: String,
+    pub queued_at: DateTime<Utc>,
+    /// Agent CLI to start in a new tmux window
+    pub agent_command: Option<String>,
+    /// First message for a started agent, or the prompt to send
+    pub prompt: Option<String>,
+    /// The session a chained prompt goes to, instead of starting an agent
+    pub session_id: Option<String>,
+}
+
+/// Launches waiting for room, oldest first
+#[derive(Default)]
+pub struct LaunchQueue {
+    queued: Mutex<VecDeque<QueuedLaunch>>,
+    /// Repos of recent launches whose sessions may not be working yet
+    started: Mutex<Vec<(Instant, String)>>,
+}
+
+/// What became of a launch request
+pub(crate) enum Requested {
+    /// Started right away, in this tmux target
+    Started(String),
+    /// Waiting in the queue
+    Queued,
+}
+
+/// `max_working_sessions` and `max_working_sessions_per_repo`; zero is no limit
+#[derive(Debug, Clone, Copy)]
+struct Limits {
+    global: u32,
+    per_repo: u32,
+}
+
+/// The repo a directory is in, or the directory outside one
+fn repo_of(cwd: &str) -> String {
+    git(cwd, &["rev-parse", "--show-toplevel"])
+        .map(|root| root.trim().to_string())
+        .ok()
+        .filter(|root| !root.is_empty())
+        .unwrap_or_else(|| cwd.trim_end_matches('/').to_string())
+}
+
+fn in_repo(path: &str, repo: &str) -> bool {
+    path.strip_prefix(repo)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Whether one more session may start working in `repo`, given the project
+/// paths of those working now
+fn fits(limits: Limits, working: &[String], repo: &str) -> bool {
+    let under = |limit: u32, count: usize| limit == 0 || count < limit as usize;
+    under(limits.global, working.len())
+        && under(
+            limits.per_repo,
+            working.iter().filter(|path| in_repo(path, repo)).count(),
+        )
+}
+
+impl LaunchQueue {
+    /// Project paths of working sessions, and of launches still starting
+    fn working(&self, state: &AppState) -> Vec<String> {
+        let mut started = self.started.lock();
+        started.retain(|(at, _)| at.elapsed() < STARTUP_GRACE);
+        state
+            .sessions
+            .read()
+            .values()
+            .filter(|s| matches!(s.state, SessionState::Spawning | SessionState::Processing))
+            .map(|s| s.project_path.clone().unwrap_or_default())
+            .chain(started.iter().map(|(_, repo)| repo.clone()))
+            .collect()
+    }
+
+    /// Queued launches, oldest first
+    pub fn list(&self) -> Vec<QueuedLaunch> {
+        self.queued.lock().iter().cloned().collect()
+    }
+}
+
+fn limits() -> Limits {
+    let settings = load_settings();
+    Limits {
+        global: settings.max_working_sessions,
+        per_repo: settings.max_working_sessions_per_repo,
+    }
+}
+
+fn execute(state: &AppState, launch: &QueuedLaunch) -> Result<String, String> {
+    let target = match (&launch.session_id, &launch.agent_command) {
+        (Some(session_id), _) => {
+            task_chain::send_if_next(state, session_id, launch.prompt.as_deref().unwrap_or(""))?
+        }
+        (None, Some(agent_command)) => {
+            start_agent(&launch.cwd, agent_command, launch.prompt.as_deref())?
+        }
+        (None, None) => return Err("Nothing to launch".to_string()),
+    };
+    state
+        .launch_queue
+        .started
+        .lock()
+        .push((Instant::now(), launch.repo.clone()));
+    Ok(target)
+}
+
+/// A launch for `label`: an agent start when `agent_command` is given, else
+/// `prompt` sent to `session_id`
+pub(crate) fn launch(
+    label: String,
+    cwd: &str,
+    agent_command: Option<&str>,
+    prompt: Option<&str>,
+    session_id: Option<&str>,
+) -> QueuedLaunch {
+    QueuedLaunch {
+        id: uuid::Uuid::new_v4().to_string(),
+        label,
+        cwd: cwd.to_string(),
+        repo: repo_of(cwd),
+        queued_at: Utc::now(),
+        agent_command: agent_command.map(String::from),
+        prompt: prompt.map(String::from),
+        session_id: session_id.map(String::from),
+    }
+}
+
+/// Start an automatic launch now if the limits allow and nothing is queued
+/// ahead of it, else queue it
+pub(crate) fn request(state: &AppState, launch: QueuedLaunch) -> Result<Requested, String> {
+    let _guard = DISPATCH_LOCK.lock();
+    let queue = &state.launch_queue;
+    if queue.queued.lock().is_empty() && fits(limits(), &queue.working(state), &launch.repo) {
+        return execute(state, &launch).map(Requested::Started);
+    }
+    log::info!(
+        "Queued {:?}: too many sessions working in {}",
+        launch.label,
+        launch.repo
+    );
+    queue.queued.lock().push_back(launch);
+    Ok(Requested::Queued)
+}
+
+/// Start queued launches there is room for, oldest first. One repo at its
+/// limit doesn't hold up the others.
+fn dispatch(state: &AppState) {
+    let _guard = DISPATCH_LOCK.lock();
+    let queue = &state.launch_queue;
+    if queue.queued.lock().is_empty() {
+        return;
+    }
+    let limits = limits();
+    let mut index = 0;
+    loop {
+        let next = {
+            let mut queued = queue.queued.lock();
+            let working = queue.working(state);
+            let found = queued
+                .iter()
+                .skip(index)
+                .position(|launch| fits(limits, &working, &launch.repo));
+            match found {
+                Some(offset) => {
+                    index += offset;
+                    queued.remove(index)
+                }
+                None => None,
+            }
+        };
+        let Some(launch) = next else {
+            break;
+        };
+        match execute(state, &launch) {
+            Ok(target) => log::info!("Started queued {:?} in {}", launch.label, target),
+            Err(e) => log::warn!("Queued {:?} failed: {}", launch.label, e),
+        }
+    }
+}
+
+/// Start queued launches as sessions finish
+pub async fn start_launch_queue(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(DISPATCH_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Launch queue shutting down");
+                break;
+            }
+        }
+        let state = state.clone();
+        let _ = tokio::task::spawn_blocking(move || dispatch(&state)).await;
+    }
+}
+
+// Tauri command: Automatic launches waiting for fewer sessions to be working, oldest first
+#[tauri::command]
+#[specta::specta]
+pub fn get_queue(state: tauri::State<'_, Arc<AppState>>) -> Vec<QueuedLaunch> {
+    state.launch_queue.list()
+}
+
+// Tauri command: Drop a queued launch
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_queued(
+    state: tauri::State<'_, Arc<AppState>>,
+    id: String,
+) -> Result<Vec<QueuedLaunch>, String> {
+    let mut queued = state.launch_queue.queued.lock();
+    let before = queued.len();
+    queued.retain(|launch| launch.id != id);
+    if queued.len() == before {
+        return Err(format!("Queued launch not found: {}", id));
+    }
+    log::info!("Cancelled queued launch {}", id);
+    Ok(queued.iter().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_count_working_sessions_globally_and_per_repo() {
+        let limits = Limits {
+            global: 3,
+            per_repo: 2,
+        };
+        let working = vec![
+            "/src/api".to_string(),
+            "/src/api/web".to_string(),
+            "/src/api-docs".to_string(),
+        ];
+        assert!(!fits(limits, &working[..2], "/src/api"));
+        assert!(fits(limits, &working[..2], "/src/blog"));
+        // The sibling repo sharing a prefix isn't counted
+        assert!(fits(limits, &working[1..], "/src/api"));
+        assert!(!fits(limits, &working, "/src/blog"));
+
+        let unlimited = Limits {
+            global: 0,
+            per_repo: 0,
+        };
+        assert!(fits(unlimited, &working, "/src/api"));
+    }
+}
@@ -0,0 +1,191 @@
+use crate::query::sort_sessions;
+use crate::{C3Session, SessionMetaStore, SessionState};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// macOS system icons, so launchers can show state without bundling images
+const SYSTEM_ICONS: &str = "/System/Library/CoreServices/CoreTypes.bundle/Contents/Resources";
+
+/// One row of an Alfred Script Filter (also read by Raycast script commands)
+#[derive(Debug, Serialize)]
+pub struct ScriptFilterItem {
+    pub uid: String,
+    pub title: String,
+    pub subtitle: String,
+    /// tmux target, passed to the launcher's action
+    pub arg: String,
+    /// False for sessions without a tmux pane, which can't be focused by target
+    pub valid: bool,
+    #[serde(rename = "match")]
+    pub match_text: String,
+    pub icon: ScriptFilterIcon,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptFilterIcon {
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScriptFilter {
+    pub items: Vec<ScriptFilterItem>,
+}
+
+fn state_label(state: &SessionState) -> &'static str {
+    match state {
+        SessionState::AwaitingPermission => "Needs permission",
+        SessionState::AwaitingInput => "Awaiting input",
+        SessionState::Processing | SessionState::Spawning => "Working",
+        SessionState::Complete => "Complete",
+        SessionState::Error => "Error",
+    }
+}
+
+fn state_icon(state: &SessionState, app_icon: &str) -> String {
+    let icon = match state {
+        SessionState::AwaitingPermission => "AlertStopIcon.icns",
+        SessionState::AwaitingInput => "AlertNoteIcon.icns",
+        SessionState::Error => "AlertCautionIcon.icns",
+        SessionState::Processing | SessionState::Spawning => "Clock.icns",
+        SessionState::Complete => return app_icon.to_string(),
+    };
+    format!("{}/{}", SYSTEM_ICONS, icon)
+}
+
+fn time_ago(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = (now - at).num_seconds().max(0);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn subtitle(session: &C3Session, home: Option<&str>, now: DateTime<Utc>) -> String {
+    let mut parts = vec![state_label(&session.state).to_string()];
+    if let Some(action) = session
+        .pending_action
+        .as_ref()
+        .filter(|_| session.state == SessionState::AwaitingPermission)
+    {
+        let detail = action.command.as_deref().unwrap_or(&action.description);
+        parts.push(match action.tool.as_deref() {
+            Some(tool) => format!("{}: {}", tool, detail),
+            None => detail.to_string(),
+        });
+    }
+    if let Some(path) = &session.project_path {
+        parts.push(match home.and_then(|home| path.strip_prefix(home)) {
+            Some(rest) => format!("~{}", rest),
+            None => path.clone(),
+        });
+    }
+    parts.push(time_ago(session.last_activity, now));
+    parts.join(" · ")
+}
+
+/// Sessions in display order as an Alfred Script Filter document. `app_icon`
+/// is used for Complete sessions.
+pub fn script_filter(
+    sessions: Vec<C3Session>,
+    meta: &SessionMetaStore,
+    app_icon: &str,
+    home: Option<&str>,
+) -> ScriptFilter {
+    let mut sessions = sessions;
+    sort_sessions(&mut sessions, meta);
+    let now = Utc::now();
+    let items = sessions
+        .iter()
+        .map(|session| {
+            let tag = meta
+                .sessions
+                .get(&session.id)
+                .and_then(|m| m.tag.as_deref());
+            let title = match tag {
+                Some(tag) => format!("{} [{}]", session.project_name, tag),
+                None => session.project_name.clone(),
+            };
+            ScriptFilterItem {
+                uid: session.id.clone(),
+                match_text: format!(
+                    "{} {} {}",
+                    session.project_name,
+                    tag.unwrap_or_default(),
+                    session.agent_kind.as_deref().unwrap_or_default()
+                ),
+                title,
+                subtitle: subtitle(session, home, now),
+                arg: session.tmux_target.clone().unwrap_or_default(),
+                valid: session.tmux_target.is_some(),
+                icon: ScriptFilterIcon {
+                    path: state_icon(&session.state, app_icon),
+                },
+            }
+        })
+        .collect();
+    ScriptFilter { items }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PendingAction, SessionMeta};
+    use chrono::Duration;
+
+    fn session(id: &str, state: SessionState, tmux_target: Option<&str>) -> C3Session {
+        C3Session {
+            project_name: id.to_string(),
+            project_path: Some(format!("/Users/ann/code/{}", id)),
+            state,
+            tmux_target: tmux_target.map(String::from),
+            last_activity: Utc::now() - Duration::minutes(5),
+            ..C3Session::test(id)
+        }
+    }
+
+    #[test]
+    fn items_follow_display_order_with_tmux_target_as_arg() {
+        let mut waiting = session("api", SessionState::AwaitingPermission, Some("work:1.0"));
+        waiting.pending_action = Some(PendingAction {
+            action_type: "permission".to_string(),
+            tool: Some("Bash".to_string()),
+            command: Some("cargo test".to_string()),
+            description: "Run tests".to_string(),
+            options: Vec::new(),
+        });
+        let done = session("web", SessionState::Complete, None);
+        let mut meta = SessionMetaStore::default();
+        meta.sessions.insert(
+            "api".to_string(),
+            SessionMeta {
+                tag: Some("urgent".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let filter = script_filter(
+            vec![done, waiting],
+            &meta,
+            "/app/icon.png",
+            Some("/Users/ann"),
+        );
+        let api = &filter.items[0];
+        assert_eq!(api.title, "api [urgent]");
+        assert_eq!(
+            api.subtitle,
+            "Needs permission · Bash: cargo test · ~/code/api · 5m ago"
+        );
+        assert_eq!(api.arg, "work:1.0");
+        assert!(api.valid);
+        assert!(api.icon.path.ends_with("AlertStopIcon.icns"));
+
+        let web = &filter.items[1];
+        assert!(!web.valid);
+        assert_eq!(web.icon.path, "/app/icon.png");
+
+        let json = serde_json::to_value(&filter).unwrap();
+        assert_eq!(json["items"][0]["match"], "api urgent claude");
+    }
+}
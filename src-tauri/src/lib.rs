@@ -1,21 +1,104 @@
+mod agents;
+mod aider;
+mod alert_audio;
+mod api_tokens;
+mod at_rest;
+mod automation;
+mod bindings;
+mod channels;
+mod claude_allowlist;
+mod compat;
+mod complete_actions;
+mod connectivity;
+mod dashboard;
+mod deep_link;
+mod diagnostics;
+mod digest;
+mod email;
+mod event_bus;
+mod event_log;
+mod events;
+mod gc;
+mod git;
+mod github;
+mod hook_protocol;
+mod hook_spool;
+mod ignore;
+mod jsonl;
+mod jsonl_tail;
+mod lan;
+mod launch_queue;
+mod launcher;
+mod meta_sync;
+mod notification_log;
+mod onboarding;
+mod otel;
+mod pane_signal;
+mod paths;
+mod permission_stats;
 mod plugins;
+mod process_table;
+mod profiles;
+mod push;
+mod query;
+mod redaction;
+mod resources;
+mod scheduler;
+mod search;
+mod secrets;
+mod session_bundle;
+mod session_diff;
+mod session_files;
+mod session_identity;
+mod session_window;
+mod simulate;
+mod sla;
+mod slack;
+mod sound_themes;
+mod state_machine;
+mod statusline;
+mod summaries;
+mod supervisor;
+mod task_chain;
+mod telegram;
+mod terminals;
+mod time_report;
+mod tmux_control;
+mod tmux_restart;
 mod tmux_scanner;
-
+mod tmux_status;
+mod transcript;
+mod transcript_export;
+mod uninstall;
+mod updater;
+mod vscode;
+mod worktree;
+
+use crate::api_tokens::TokenScope;
+use crate::plugins::sleep_wake::PowerEvent;
+use crate::state_machine::{Decision, StateSource};
 use chrono::{DateTime, Utc};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use specta::Type;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use tauri::menu::{MenuBuilder, MenuItemBuilder};
-use tauri::tray::TrayIconBuilder;
+use tauri::menu::{CheckMenuItemBuilder, MenuBuilder, MenuItemBuilder, SubmenuBuilder};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{AppHandle, Emitter, Manager, RunEvent, WindowEvent};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, watch};
+use tokio::sync::watch;
 
+/// Default port for the hook server; profiles can pick another via `hook_port`
 const HOOK_SERVER_PORT: u16 = 9398;
+/// Most text `POST /api/sessions/<id>/input` takes; longer bodies get 413
+const MAX_API_INPUT_BYTES: usize = 64 * 1024;
+/// Largest hook or spooled batch the hook server reads; bigger ones get 413.
+/// Permission requests carry the tool's input, e.g. a whole file to write.
+const MAX_HOOK_BODY_BYTES: usize = 16 * 1024 * 1024;
 
 // Wrapper so we can store the shutdown sender in Tauri state
 struct ShutdownHandle(std::sync::Mutex<Option<watch::Sender<bool>>>);
@@ -47,19 +130,8 @@ pub(crate) fn cmd(program: &str) -> std::process::Command {
     c
 }
 
-// Known terminal apps (in preference order for auto-detection)
-const KNOWN_TERMINALS: &[&str] = &[
-    "Ghostty",
-    "iTerm",
-    "Alacritty",
-    "kitty",
-    "WezTerm",
-    "Warp",
-    "Terminal",
-];
-
 // Sound configuration for a specific notification type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct SoundConfig {
     #[serde(default = "default_true")]
     pub enabled: bool,
@@ -77,10 +149,17 @@ impl Default for SoundConfig {
 }
 
 // App settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 pub struct AppSettings {
     #[serde(default = "default_terminal")]
     pub terminal_app: String,
+    /// Bundle identifier of `terminal_app`, looked up when it's saved, used
+    /// to activate it from AppleScript and notification clicks
+    #[serde(default)]
+    pub terminal_bundle_id: Option<String>,
+    /// Terminals to detect and focus besides the known ones
+    #[serde(default)]
+    pub custom_terminals: Vec<terminals::CustomTerminal>,
     #[serde(default = "default_agent")]
     pub default_agent: String,
     #[serde(default = "default_true")]
@@ -91,6 +170,159 @@ pub struct AppSettings {
     pub input_sound: SoundConfig,
     #[serde(default)]
     pub complete_sound: SoundConfig,
+    /// Seconds between tmux scan cycles
+    #[serde(default = "default_scan_interval_secs")]
+    pub scan_interval_secs: u64,
+    /// Seconds the scanner defers to a hook-set state
+    #[serde(default = "default_hook_grace_period_secs")]
+    pub hook_grace_period_secs: u64,
+    /// How the scanner and hooks settle a disagreement about a session's state
+    #[serde(default)]
+    pub state_arbitration: state_machine::Arbitration,
+    /// With `newest_evidence`, seconds a hook counts as newer than it is
+    #[serde(default = "default_hook_confidence_secs")]
+    pub hook_confidence_secs: u64,
+    /// With `newest_evidence`, seconds the scanner's transcript evidence
+    /// counts as newer than it is
+    #[serde(default)]
+    pub scanner_confidence_secs: u64,
+    /// Read Claude panes' content (permission box, "esc to interrupt",
+    /// input prompt) as a third state signal, weighed by `pane_signal`
+    #[serde(default)]
+    pub pane_content_detection: bool,
+    /// Scan less often, and skip reading pane content, on battery or in
+    /// Low Power Mode
+    #[serde(default)]
+    pub battery_saver: bool,
+    /// Minimum gap between notifications for the same session
+    #[serde(default = "default_notification_debounce_ms")]
+    pub notification_debounce_ms: u64,
+    /// Extra regexes masked in commands, previews, diagnostics and notifications
+    #[serde(default)]
+    pub redaction_patterns: Vec<String>,
+    /// Port of the local hook server (applied on restart)
+    #[serde(default = "default_hook_port")]
+    pub hook_port: u16,
+    /// Archive Complete sessions after this many hours (0 = never)
+    #[serde(default = "default_archive_complete_after_hours")]
+    pub archive_complete_after_hours: u64,
+    /// Hide sessions whose transcript hasn't changed in this many days (0 = never)
+    #[serde(default = "default_hide_stale_after_days")]
+    pub hide_stale_after_days: u64,
+    /// Project path globs C3 never tracks, e.g. `~/scratch/**`
+    #[serde(default)]
+    pub ignored_paths: Vec<String>,
+    /// tmux session-name globs C3 never tracks, e.g. `dotfiles`
+    #[serde(default)]
+    pub ignored_tmux_sessions: Vec<String>,
+    /// Agent kinds (see `agents::AGENTS`) C3 neither scans for nor takes hooks from
+    #[serde(default)]
+    pub disabled_agents: Vec<String>,
+    /// Global shortcut that shows or hides the floating widget (empty = none)
+    #[serde(default = "default_widget_shortcut")]
+    pub widget_shortcut: String,
+    /// Register C3 as a login item
+    #[serde(default)]
+    pub launch_at_login: bool,
+    /// Keep the window hidden (tray only) when started at login
+    #[serde(default)]
+    pub start_hidden: bool,
+    /// Stable only, or prereleases too
+    #[serde(default)]
+    pub update_channel: updater::UpdateChannel,
+    /// Download new releases in the background, or just point at them
+    #[serde(default)]
+    pub update_mode: updater::UpdateMode,
+    /// Alert sound volume, 0–100
+    #[serde(default = "default_alert_volume")]
+    pub alert_volume: u8,
+    /// Output device alerts play on (None = the system default)
+    #[serde(default)]
+    pub alert_output_device: Option<String>,
+    /// Named sound theme used for alerts (None = the per-event sounds above)
+    #[serde(default)]
+    pub sound_theme: Option<String>,
+    /// Themes for projects under a path glob; the first match wins
+    #[serde(default)]
+    pub project_sound_themes: Vec<sound_themes::ProjectSoundTheme>,
+    /// Commands typed in a session's pane when it completes, for projects
+    /// under a path glob; the first match wins
+    #[serde(default)]
+    pub complete_actions: Vec<complete_actions::CompleteAction>,
+    /// Time limits per state, globally or for a tag, checked by `sla`
+    #[serde(default)]
+    pub sla_rules: Vec<sla::SlaRule>,
+    /// Shell commands run on state transitions, by `automation`
+    #[serde(default)]
+    pub automation_rules: Vec<automation::AutomationRule>,
+    /// Style tmux panes waiting for the user, and their windows in the status line
+    #[serde(default)]
+    pub tmux_attention_marker: bool,
+    /// tmux style for marked panes, e.g. `bg=colour52`
+    #[serde(default = "default_tmux_attention_style")]
+    pub tmux_attention_style: String,
+    /// Rename tmux windows after their sessions' state, e.g. `⏳ api-server`
+    #[serde(default)]
+    pub tmux_window_names: bool,
+    /// Zoom a session's tmux pane when focusing it from C3
+    #[serde(default)]
+    pub zoom_on_focus: bool,
+    /// Ask before sending a session's queued next prompt, instead of
+    /// sending it `chain_delay_secs` after the agent stops
+    #[serde(default = "default_true")]
+    pub chain_confirm: bool,
+    #[serde(default = "default_chain_delay_secs")]
+    pub chain_delay_secs: u64,
+    /// Folder shared between machines (iCloud Drive, Syncthing, ...) where
+    /// tags, notes, pins and groups are synced, merged by `meta_sync`
+    #[serde(default)]
+    pub meta_sync_dir: Option<String>,
+    /// Automatic launches (scheduled tasks, chained prompts) wait in
+    /// `launch_queue` while this many sessions are working; 0 for no limit
+    #[serde(default = "default_max_working_sessions")]
+    pub max_working_sessions: u32,
+    /// The same, counting only sessions in the launch's repo
+    #[serde(default = "default_max_working_sessions_per_repo")]
+    pub max_working_sessions_per_repo: u32,
+    /// Summarize stopped sessions with `claude -p` instead of the first
+    /// lines of their final reply
+    #[serde(default)]
+    pub summarize_with_claude: bool,
+    /// Shell command that summarizes a stopped session instead, given the
+    /// transcript's path on stdin and printing the summary
+    #[serde(default)]
+    pub summarizer_command: Option<String>,
+    /// OTLP/HTTP collector sent a trace per session, e.g. `http://localhost:4318`
+    #[serde(default)]
+    pub otlp_endpoint: Option<String>,
+    /// Append every session event to `events.log` in the config dir
+    #[serde(default)]
+    pub event_log: bool,
+    /// Notify when a session's agent processes use more memory than this; 0 to never
+    #[serde(default = "default_memory_alert_mb")]
+    pub memory_alert_mb: u32,
+    /// Send a summary of last week's sessions on Monday morning
+    #[serde(default)]
+    pub weekly_digest: bool,
+    /// Slack incoming webhook the weekly digest is also posted to
+    #[serde(default)]
+    pub slack_webhook_url: Option<String>,
+    /// Mobile push through ntfy or Pushover, by `push`
+    #[serde(default)]
+    pub push: push::PushSettings,
+    /// Telegram bot with approve/deny buttons, by `telegram`
+    #[serde(default)]
+    pub telegram: telegram::TelegramSettings,
+    /// Email about sessions left waiting, by `email`
+    #[serde(default)]
+    pub email: email::EmailSettings,
+    /// Serving the API to other devices over TLS, by `lan`; loopback only
+    /// when off
+    #[serde(default)]
+    pub lan: lan::LanSettings,
+    /// Encrypting session bundles and transcript exports, by `at_rest`
+    #[serde(default)]
+    pub at_rest: at_rest::AtRestSettings,
 }
 
 fn default_terminal() -> String {
@@ -101,14 +333,134 @@ fn default_agent() -> String {
     "codex".to_string()
 }
 
+fn default_alert_volume() -> u8 {
+    100
+}
+
+fn default_tmux_attention_style() -> String {
+    "bg=colour52".to_string()
+}
+
+fn default_hook_port() -> u16 {
+    HOOK_SERVER_PORT
+}
+
+fn default_archive_complete_after_hours() -> u64 {
+    24
+}
+
+fn default_hide_stale_after_days() -> u64 {
+    7
+}
+
+fn default_widget_shortcut() -> String {
+    "CmdOrCtrl+Shift+Period".to_string()
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_chain_delay_secs() -> u64 {
+    10
+}
+
+fn default_max_working_sessions() -> u32 {
+    4
+}
+
+fn default_max_working_sessions_per_repo() -> u32 {
+    2
+}
+
+fn default_memory_alert_mb() -> u32 {
+    4096
+}
+
+fn default_scan_interval_secs() -> u64 {
+    3
+}
+
+fn default_hook_grace_period_secs() -> u64 {
+    10
+}
+
+fn default_hook_confidence_secs() -> u64 {
+    2
+}
+
+fn default_notification_debounce_ms() -> u64 {
+    1000
+}
+
+const SCAN_INTERVAL_SECS_MIN: u64 = 1;
+const SCAN_INTERVAL_SECS_MAX: u64 = 30;
+const HOOK_GRACE_PERIOD_SECS_MIN: u64 = 2;
+const HOOK_GRACE_PERIOD_SECS_MAX: u64 = 60;
+const NOTIFICATION_DEBOUNCE_MS_MAX: u64 = 10_000;
+const CONFIDENCE_SECS_MAX: u64 = 60;
+/// Ports below this need root, so a typo here would leave hooks unreachable
+const HOOK_PORT_MIN: u16 = 1024;
+
+impl AppSettings {
+    /// Clamp timing values into their supported ranges
+    fn clamp_timings(&mut self) {
+        self.scan_interval_secs = self
+            .scan_interval_secs
+            .clamp(SCAN_INTERVAL_SECS_MIN, SCAN_INTERVAL_SECS_MAX);
+        self.hook_grace_period_secs = self
+            .hook_grace_period_secs
+            .clamp(HOOK_GRACE_PERIOD_SECS_MIN, HOOK_GRACE_PERIOD_SECS_MAX);
+        self.notification_debounce_ms = self
+            .notification_debounce_ms
+            .min(NOTIFICATION_DEBOUNCE_MS_MAX);
+        self.hook_confidence_secs = self.hook_confidence_secs.min(CONFIDENCE_SECS_MAX);
+        self.scanner_confidence_secs = self.scanner_confidence_secs.min(CONFIDENCE_SECS_MAX);
+    }
+
+    fn validate_hook_port(&self) -> Result<(), String> {
+        if self.hook_port < HOOK_PORT_MIN {
+            return Err(format!(
+                "Hook port must be between {} and 65535",
+                HOOK_PORT_MIN
+            ));
+        }
+        Ok(())
+    }
+
+    fn timings(&self) -> Timings {
+        Timings {
+            scan_interval_secs: self.scan_interval_secs,
+            hook_grace_period_secs: self.hook_grace_period_secs,
+            notification_debounce_ms: self.notification_debounce_ms,
+            battery_saver: self.battery_saver,
+            arbiter: state_machine::Arbiter {
+                policy: self.state_arbitration,
+                grace_period: std::time::Duration::from_secs(self.hook_grace_period_secs),
+                hook_confidence: std::time::Duration::from_secs(self.hook_confidence_secs),
+                scanner_confidence: std::time::Duration::from_secs(self.scanner_confidence_secs),
+                pane_signals: self.pane_content_detection,
+            },
+        }
+    }
+}
+
+/// Live copy of the timing settings, so hot paths don't re-read settings.json
+#[derive(Debug, Clone, Copy)]
+pub struct Timings {
+    pub scan_interval_secs: u64,
+    pub hook_grace_period_secs: u64,
+    pub notification_debounce_ms: u64,
+    pub battery_saver: bool,
+    pub arbiter: state_machine::Arbiter,
+}
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
             terminal_app: default_terminal(),
+            terminal_bundle_id: None,
+            custom_terminals: Vec::new(),
             default_agent: default_agent(),
             notifications_enabled: true,
             permission_sound: SoundConfig::default(),
@@ -117,27 +469,68 @@ impl Default for AppSettings {
                 enabled: false,
                 sound: None,
             },
+            scan_interval_secs: default_scan_interval_secs(),
+            hook_grace_period_secs: default_hook_grace_period_secs(),
+            state_arbitration: state_machine::Arbitration::default(),
+            hook_confidence_secs: default_hook_confidence_secs(),
+            scanner_confidence_secs: 0,
+            pane_content_detection: false,
+            battery_saver: false,
+            notification_debounce_ms: default_notification_debounce_ms(),
+            redaction_patterns: Vec::new(),
+            hook_port: default_hook_port(),
+            archive_complete_after_hours: default_archive_complete_after_hours(),
+            hide_stale_after_days: default_hide_stale_after_days(),
+            ignored_paths: Vec::new(),
+            ignored_tmux_sessions: Vec::new(),
+            disabled_agents: Vec::new(),
+            widget_shortcut: default_widget_shortcut(),
+            launch_at_login: false,
+            start_hidden: false,
+            update_channel: updater::UpdateChannel::default(),
+            update_mode: updater::UpdateMode::default(),
+            sla_rules: Vec::new(),
+            automation_rules: Vec::new(),
+            sound_theme: None,
+            project_sound_themes: Vec::new(),
+            complete_actions: Vec::new(),
+            alert_volume: default_alert_volume(),
+            alert_output_device: None,
+            tmux_attention_marker: false,
+            tmux_attention_style: default_tmux_attention_style(),
+            tmux_window_names: false,
+            zoom_on_focus: false,
+            chain_confirm: true,
+            chain_delay_secs: default_chain_delay_secs(),
+            meta_sync_dir: None,
+            max_working_sessions: default_max_working_sessions(),
+            max_working_sessions_per_repo: default_max_working_sessions_per_repo(),
+            summarize_with_claude: false,
+            summarizer_command: None,
+            otlp_endpoint: None,
+            event_log: false,
+            memory_alert_mb: default_memory_alert_mb(),
+            weekly_digest: false,
+            slack_webhook_url: None,
+            push: push::PushSettings::default(),
+            telegram: telegram::TelegramSettings::default(),
+            email: email::EmailSettings::default(),
+            lan: lan::LanSettings::default(),
+            at_rest: at_rest::AtRestSettings::default(),
         }
     }
 }
 
-fn config_dir() -> PathBuf {
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .map(|p| p.join(".config").join("c3"))
-        .unwrap_or_else(|_| PathBuf::from("."))
-}
-
 fn settings_path() -> PathBuf {
-    config_dir().join("settings.json")
+    profiles::profile_dir().join("settings.json")
 }
 
 fn session_meta_path() -> PathBuf {
-    config_dir().join("session-meta.json")
+    profiles::profile_dir().join("session-meta.json")
 }
 
 // Session metadata (tags, pins, custom groups)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
 pub struct SessionMeta {
     #[serde(default)]
     pub tag: Option<String>,
@@ -147,9 +540,28 @@ pub struct SessionMeta {
     pub group_id: Option<String>,
     #[serde(default, rename = "groupAssignment")]
     pub group_assignment: Option<String>,
+    #[serde(
+        default,
+        rename = "gitBaseline",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub git_baseline: Option<git::GitBaseline>,
+    /// Position set by drag-reorder; sessions without one follow, by urgency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+    /// Free-form scratchpad, e.g. "waiting on design answer"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// Prompts to send after the next Stops, first one first, by `task_chain`
+    #[serde(default, rename = "nextPrompts", skip_serializing_if = "Vec::is_empty")]
+    pub next_prompts: Vec<String>,
+    /// Worktree `create_worktree` made for the session, removed by the GC
+    /// once the session stays archived
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionGroup {
     pub id: String,
@@ -158,31 +570,80 @@ pub struct SessionGroup {
     #[serde(default)]
     pub match_text: Vec<String>,
     pub created_at: DateTime<Utc>,
+    /// No notifications or sounds for the group's sessions
+    #[serde(default)]
+    pub muted: bool,
 }
 
 // All session metadata keyed by tmux target
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
 pub struct SessionMetaStore {
     #[serde(default)]
     pub sessions: HashMap<String, SessionMeta>,
     #[serde(default)]
     pub groups: Vec<SessionGroup>,
+    /// Tags whose sessions send no notifications or sounds
+    #[serde(default, rename = "mutedTags")]
+    pub muted_tags: Vec<String>,
+}
+
+/// The group a session is in: the one it was put in, or else the oldest
+/// group whose match text is in its name or path, as the frontend assigns them
+fn session_group<'a>(store: &'a SessionMetaStore, session: &C3Session) -> Option<&'a SessionGroup> {
+    let meta = store.sessions.get(&session.id);
+    if let Some(group_id) = meta.and_then(|m| m.group_id.as_deref()) {
+        return store.groups.iter().find(|g| g.id == group_id);
+    }
+    if meta.and_then(|m| m.group_assignment.as_deref()) == Some("manual") {
+        return None;
+    }
+    let haystack = format!(
+        "{}\n{}",
+        session.project_name,
+        session.project_path.as_deref().unwrap_or("")
+    )
+    .to_lowercase();
+    let mut groups: Vec<&SessionGroup> = store.groups.iter().collect();
+    groups.sort_by_key(|g| g.created_at);
+    groups.into_iter().find(|group| {
+        group
+            .match_text
+            .iter()
+            .map(|text| text.trim().to_lowercase())
+            .any(|needle| !needle.is_empty() && haystack.contains(&needle))
+    })
+}
+
+/// Whether a session's group or tag is muted
+pub(crate) fn notifications_muted(store: &SessionMetaStore, session: &C3Session) -> bool {
+    let tag = store.sessions.get(&session.id).and_then(|m| m.tag.as_ref());
+    tag.is_some_and(|tag| store.muted_tags.contains(tag))
+        || session_group(store, session).is_some_and(|group| group.muted)
 }
 
-fn session_meta_is_empty(meta: &SessionMeta) -> bool {
-    meta.tag.is_none() && !meta.pinned && meta.group_id.is_none() && meta.group_assignment.is_none()
+pub(crate) fn session_meta_is_empty(meta: &SessionMeta) -> bool {
+    meta.tag.is_none()
+        && !meta.pinned
+        && meta.group_id.is_none()
+        && meta.group_assignment.is_none()
+        && meta.git_baseline.is_none()
+        && meta.order.is_none()
+        && meta.notes.is_none()
+        && meta.next_prompts.is_empty()
 }
 
 fn load_session_meta() -> SessionMetaStore {
     let path = session_meta_path();
-    if path.exists() {
+    let mut store = if path.exists() {
         fs::read_to_string(&path)
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
             .unwrap_or_default()
     } else {
         SessionMetaStore::default()
-    }
+    };
+    meta_sync::merge(&mut store);
+    store
 }
 
 fn save_session_meta(store: &SessionMetaStore) -> Result<(), String> {
@@ -191,19 +652,29 @@ fn save_session_meta(store: &SessionMetaStore) -> Result<(), String> {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
     let json = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    if let Err(e) = meta_sync::publish(store) {
+        log::warn!("Failed to sync session metadata: {}", e);
+    }
+    Ok(())
 }
 
 fn load_settings() -> AppSettings {
     let path = settings_path();
-    if path.exists() {
+    let mut settings: AppSettings = if path.exists() {
         fs::read_to_string(&path)
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
             .unwrap_or_default()
     } else {
         AppSettings::default()
+    };
+    settings.clamp_timings();
+    if settings.validate_hook_port().is_err() {
+        settings.hook_port = HOOK_SERVER_PORT;
     }
+    secrets::reveal(&mut settings);
+    settings
 }
 
 fn save_settings(settings: &AppSettings) -> Result<(), String> {
@@ -211,34 +682,39 @@ fn save_settings(settings: &AppSettings) -> Result<(), String> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    let json = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
-    fs::write(&path, json).map_err(|e| e.to_string())
+    let concealed = secrets::conceal(settings, stored_settings().as_ref());
+    let json = serde_json::to_string_pretty(&concealed).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())?;
+    event_log::configure(settings.event_log);
+    if meta_sync::configure(settings.meta_sync_dir.as_deref()) {
+        // Share what this machine already has with the new directory
+        if let Err(e) = meta_sync::publish(&load_session_meta()) {
+            log::warn!("Failed to sync session metadata: {}", e);
+        }
+    }
+    Ok(())
 }
 
-/// Detect which terminal app is installed and running
-fn detect_terminal() -> Option<String> {
-    for &term in KNOWN_TERMINALS {
-        // Check if app is running
-        let check = cmd("pgrep").args(["-x", term]).output();
+/// settings.json as it is on disk, with references to secrets left in
+fn stored_settings() -> Option<AppSettings> {
+    fs::read_to_string(settings_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+}
 
-        if check.map(|o| o.status.success()).unwrap_or(false) {
-            return Some(term.to_string());
-        }
+/// Move credentials that older versions left in settings.json into the keyring
+fn migrate_secrets() {
+    if !stored_settings().is_some_and(|settings| secrets::has_plain_text(&settings)) {
+        return;
     }
-
-    // Fallback: check what's installed
-    for &term in KNOWN_TERMINALS {
-        let app_path = format!("/Applications/{}.app", term);
-        if std::path::Path::new(&app_path).exists() {
-            return Some(term.to_string());
-        }
+    match save_settings(&load_settings()) {
+        Ok(()) => log::info!("Moved credentials from settings.json to the keyring"),
+        Err(e) => log::warn!("Failed to move credentials to the keyring: {}", e),
     }
-
-    None
 }
 
 // Session state enum
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
 #[serde(rename_all = "snake_case")]
 pub enum SessionState {
     Spawning,
@@ -250,17 +726,20 @@ pub enum SessionState {
 }
 
 // Pending action for sessions awaiting input
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
 pub struct PendingAction {
     #[serde(rename = "type")]
     pub action_type: String,
     pub description: String,
     pub tool: Option<String>,
     pub command: Option<String>,
+    /// Choices offered with a question, answered with `answer_question`
+    #[serde(default)]
+    pub options: Vec<String>,
 }
 
 // Session metrics
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Type)]
 pub struct SessionMetrics {
     #[serde(rename = "tokensUsed")]
     pub tokens_used: Option<u64>,
@@ -268,10 +747,20 @@ pub struct SessionMetrics {
     pub task_count: Option<u32>,
     #[serde(rename = "startTime")]
     pub start_time: Option<DateTime<Utc>>,
+    /// The agent's process, sampled by `resources`
+    #[serde(default)]
+    pub pid: Option<u32>,
+    /// Resident memory of the agent's process and its children
+    #[serde(rename = "rssBytes", default)]
+    pub rss_bytes: Option<u64>,
+    /// CPU the agent's process and its children used since the last sample;
+    /// 100 is one core
+    #[serde(rename = "cpuPercent", default)]
+    pub cpu_percent: Option<f64>,
 }
 
 // Main session struct
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Type)]
 pub struct C3Session {
     pub id: String,
     #[serde(rename = "projectName")]
@@ -283,6 +772,9 @@ pub struct C3Session {
     pub state: SessionState,
     #[serde(rename = "tmuxTarget")]
     pub tmux_target: Option<String>,
+    /// tmux's `%N` pane ID, which survives window moves and renumbering
+    #[serde(rename = "tmuxPaneId", default)]
+    pub tmux_pane_id: Option<String>,
     #[serde(rename = "terminalTty")]
     pub terminal_tty: Option<String>,
     #[serde(rename = "lastActivity")]
@@ -290,6 +782,52 @@ pub struct C3Session {
     #[serde(rename = "pendingAction")]
     pub pending_action: Option<PendingAction>,
     pub metrics: Option<SessionMetrics>,
+    /// Why the session is in the Error state (e.g. crash exit status or API error)
+    #[serde(rename = "errorReason", default)]
+    pub error_reason: Option<String>,
+    /// Commit and branch when C3 first saw the session
+    #[serde(rename = "gitBaseline", default)]
+    pub git_baseline: Option<git::GitBaseline>,
+    /// Open PR for the session's branch, refreshed by the PR watcher
+    #[serde(rename = "pullRequest", default)]
+    pub pull_request: Option<github::PullRequestStatus>,
+    /// What the session accomplished, written by `summaries` when it stops
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Started with permission prompts turned off, e.g. with
+    /// `--dangerously-skip-permissions`
+    #[serde(default)]
+    pub yolo: bool,
+    /// Working while the machine is offline, so most likely stuck retrying
+    /// its API
+    #[serde(default)]
+    pub stalled: bool,
+}
+
+#[cfg(test)]
+impl C3Session {
+    /// A Claude session that's working, for tests to fill in the rest of
+    pub(crate) fn test(id: &str) -> Self {
+        C3Session {
+            id: id.to_string(),
+            project_name: "c3".to_string(),
+            project_path: None,
+            agent_kind: Some("claude".to_string()),
+            state: SessionState::Processing,
+            tmux_target: None,
+            tmux_pane_id: None,
+            terminal_tty: None,
+            last_activity: Utc::now(),
+            pending_action: None,
+            metrics: None,
+            error_reason: None,
+            git_baseline: None,
+            pull_request: None,
+            summary: None,
+            yolo: false,
+            stalled: false,
+        }
+    }
 }
 
 // Legacy action protocol kept for future approve/deny integration
@@ -297,7 +835,7 @@ pub struct C3Session {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ClientMessage {
     Register {
-        session: C3Session,
+        session: Box<C3Session>,
     },
     StateChange {
         #[serde(rename = "sessionId")]
@@ -326,6 +864,13 @@ pub enum ServerMessage {
         action: String,
     },
     Ping,
+    /// A coalesced batch of session changes, as sent to the frontend
+    Sessions(events::SessionBatch),
+    /// Every session, sent in place of messages a subscriber missed
+    Snapshot {
+        revision: u64,
+        sessions: Vec<C3Session>,
+    },
 }
 
 // Debug hook event log entry
@@ -359,7 +904,8 @@ pub struct StateDiagnostic {
 // Shared state
 pub struct AppState {
     pub sessions: RwLock<HashMap<String, C3Session>>,
-    pub tx: broadcast::Sender<String>,
+    /// Streams `ServerMessage`s to external clients, resyncing ones that lag
+    pub bus: event_bus::EventBus,
     /// Tracks when a session was last updated by a hook (session_id -> timestamp)
     /// The tmux scanner won't override states set by hooks for a grace period.
     pub hook_timestamps: RwLock<HashMap<String, std::time::Instant>>,
@@ -369,29 +915,158 @@ pub struct AppState {
     pub notification_timestamps: RwLock<HashMap<String, std::time::Instant>>,
     /// Recent hook events for debugging
     pub hook_events: RwLock<Vec<HookEvent>>,
+    /// Why each session's recent notifications were sent or suppressed
+    pub notification_log: notification_log::NotificationLog,
     /// Recent state classification decisions for debugging false positives
     pub state_diagnostics: RwLock<Vec<StateDiagnostic>>,
+    /// Panes where C3 asked the agent to exit (tmux target -> request).
+    /// The scanner keeps these panes visible as Complete once they drop back to a shell.
+    pub stop_requests: RwLock<HashMap<String, StopRequest>>,
+    /// Per-file read offsets and tail buffers for incremental JSONL reading
+    pub jsonl_tails: jsonl_tail::JsonlTails,
+    /// Timing of recent tmux scan cycles
+    pub scan_stats: RwLock<tmux_scanner::ScanStats>,
+    /// Child processes of tmux panes, for spotting agents run under node or bun
+    pub child_cache: process_table::ChildCache,
+    /// Pane changes pushed by tmux control mode, which spare scans `list-panes`
+    pub tmux_control: tmux_control::TmuxControl,
+    /// Recognizes sessions again after the tmux server restarts
+    pub tmux_server: tmux_restart::TmuxServerWatch,
+    /// Coalesces session updates into `sessions-batch-update` events and tracks
+    /// the session revision used by `get_sessions_since`
+    pub events: events::SessionEvents,
+    /// Wakes the tmux scanner for an immediate scan cycle
+    pub scan_now: tokio::sync::Notify,
+    /// The connectivity monitor can't reach the internet
+    pub offline: std::sync::atomic::AtomicBool,
+    /// On battery or in Low Power Mode with `battery_saver` on, so the
+    /// scanner slows down
+    pub saving_power: std::sync::atomic::AtomicBool,
+    /// Scan interval, hook grace period and notification debounce from settings
+    pub timings: RwLock<Timings>,
+    /// Recent state transitions per session
+    pub timeline: state_machine::Timeline,
+    /// A simulation replay is running; the real tmux scanner is paused
+    pub simulation_active: std::sync::atomic::AtomicBool,
+    /// Masks secrets before they are stored, emitted or shown in notifications
+    pub redactor: RwLock<redaction::Redactor>,
+    /// Port the hook server was started on (from the profile's settings)
+    pub hook_port: u16,
+    /// Whether the hook server got its port
+    pub hook_server: RwLock<diagnostics::HookServerStatus>,
+    /// Sessions hidden by the retention rules, restorable within the undo window
+    pub archive: gc::Archive,
+    /// Projects and tmux sessions the scanner and hook handler skip
+    pub ignore: RwLock<ignore::IgnoreList>,
+    /// Pop-out windows that receive only their own session's updates
+    pub session_windows: session_window::SessionWindows,
+    /// Recent automation rule runs, for the log in settings
+    pub automation_log: automation::RunLog,
+    /// Automatic launches waiting for fewer sessions to be working
+    pub launch_queue: launch_queue::LaunchQueue,
 }
 
-/// How long (seconds) the tmux scanner should defer to hook-set state
-/// Also used to suppress Notification hooks that follow a Stop hook
-const HOOK_GRACE_PERIOD_SECS: u64 = 10;
+/// A pending or confirmed `stop_claude` request for a tmux pane
+#[derive(Debug, Clone)]
+pub struct StopRequest {
+    pub agent_kind: String,
+    pub requested_at: std::time::Instant,
+    /// Set once the scanner has seen the pane back at a shell prompt
+    pub confirmed: bool,
+}
 
 impl AppState {
     pub fn new() -> Self {
-        let (tx, _) = broadcast::channel(100);
+        let settings = load_settings();
         Self {
             sessions: RwLock::new(HashMap::new()),
-            tx,
+            bus: event_bus::EventBus::default(),
             hook_timestamps: RwLock::new(HashMap::new()),
             stop_timestamps: RwLock::new(HashMap::new()),
             notification_timestamps: RwLock::new(HashMap::new()),
             hook_events: RwLock::new(Vec::new()),
+            notification_log: notification_log::NotificationLog::default(),
             state_diagnostics: RwLock::new(Vec::new()),
+            stop_requests: RwLock::new(HashMap::new()),
+            jsonl_tails: jsonl_tail::JsonlTails::default(),
+            scan_stats: RwLock::new(tmux_scanner::ScanStats::default()),
+            child_cache: process_table::ChildCache::default(),
+            tmux_control: tmux_control::TmuxControl::default(),
+            tmux_server: tmux_restart::TmuxServerWatch::default(),
+            events: events::SessionEvents::default(),
+            scan_now: tokio::sync::Notify::new(),
+            offline: std::sync::atomic::AtomicBool::new(false),
+            saving_power: std::sync::atomic::AtomicBool::new(false),
+            timings: RwLock::new(settings.timings()),
+            timeline: state_machine::Timeline::default(),
+            simulation_active: std::sync::atomic::AtomicBool::new(false),
+            redactor: RwLock::new(redaction::Redactor::new(&settings.redaction_patterns)),
+            hook_port: settings.hook_port,
+            hook_server: RwLock::new(diagnostics::HookServerStatus {
+                port: settings.hook_port,
+                ..Default::default()
+            }),
+            archive: gc::Archive::default(),
+            ignore: RwLock::new(ignore::IgnoreList::new(
+                &settings.ignored_paths,
+                &settings.ignored_tmux_sessions,
+                &settings.disabled_agents,
+            )),
+            session_windows: session_window::SessionWindows::default(),
+            automation_log: automation::RunLog::default(),
+            launch_queue: launch_queue::LaunchQueue::default(),
         }
     }
 
-    pub fn log_hook_event(&self, event: HookEvent) {
+    /// How long (seconds) the tmux scanner should defer to hook-set state.
+    /// Also used to suppress Notification hooks that follow a Stop hook.
+    pub fn hook_grace_period_secs(&self) -> u64 {
+        self.timings.read().hook_grace_period_secs
+    }
+
+    /// Whether the scanner must leave a session's state to its last hook,
+    /// under the arbitration policy from settings. `scanner_evidence` is when
+    /// the scanner's newest evidence was written (the transcript's last message).
+    pub fn hook_protected(
+        &self,
+        session_id: &str,
+        scanner_evidence: Option<DateTime<Utc>>,
+    ) -> bool {
+        let since_hook = self
+            .hook_timestamps
+            .read()
+            .get(session_id)
+            .map(|t| t.elapsed());
+        let evidence_age = scanner_evidence.map(|t| (Utc::now() - t).to_std().unwrap_or_default());
+        self.timings
+            .read()
+            .arbiter
+            .hook_protected(since_hook, evidence_age)
+    }
+
+    /// A `ServerMessage::Snapshot` of every session, for bus subscribers that lagged
+    pub fn snapshot_message(&self) -> String {
+        let delta = self.events.delta_since(0, &self.sessions.read());
+        let snapshot = ServerMessage::Snapshot {
+            revision: delta.revision,
+            sessions: delta.updated,
+        };
+        serde_json::to_string(&snapshot).unwrap_or_default()
+    }
+
+    pub fn log_hook_event(&self, mut event: HookEvent) {
+        event.skip_reason = event
+            .skip_reason
+            .map(|reason| self.redactor.read().redact_string(reason));
+        event_log::record(event_log::Event::Hook {
+            hook_type: event.hook_type.clone(),
+            agent_kind: event.agent_kind.clone(),
+            cwd: event.cwd.clone(),
+            session_id: event.matched_session.clone(),
+            new_state: event.new_state.clone(),
+            skipped: event.skipped,
+            skip_reason: event.skip_reason.clone(),
+        });
         let mut events = self.hook_events.write();
         events.push(event);
         // Keep last 50 events
@@ -401,7 +1076,12 @@ impl AppState {
         }
     }
 
-    pub fn log_state_diagnostic(&self, diagnostic: StateDiagnostic) {
+    pub fn log_state_diagnostic(&self, mut diagnostic: StateDiagnostic) {
+        {
+            let redactor = self.redactor.read();
+            diagnostic.reason = redactor.redact_string(diagnostic.reason);
+            diagnostic.pane_title = diagnostic.pane_title.map(|t| redactor.redact_string(t));
+        }
         let mut diagnostics = self.state_diagnostics.write();
         diagnostics.push(diagnostic);
         if diagnostics.len() > 100 {
@@ -413,15 +1093,37 @@ impl AppState {
 
 // Tauri command: Get all sessions
 #[tauri::command]
+#[specta::specta]
 fn get_sessions(state: tauri::State<Arc<AppState>>) -> Vec<C3Session> {
     state.sessions.read().values().cloned().collect()
 }
 
+// Tauri command: Get sessions changed or removed after a revision
+#[tauri::command]
+#[specta::specta]
+fn get_sessions_since(state: tauri::State<Arc<AppState>>, revision: u64) -> events::SessionDelta {
+    let sessions = state.sessions.read();
+    state.events.delta_since(revision, &sessions)
+}
+
+// Tauri command: Get recent state transitions for a session
+#[tauri::command]
+#[specta::specta]
+fn get_session_timeline(
+    state: tauri::State<Arc<AppState>>,
+    session_id: String,
+) -> Vec<state_machine::TimelineEntry> {
+    state.timeline.for_session(&session_id)
+}
+
 // Tauri command: Get debug info
 #[tauri::command]
+#[specta::specta]
 fn get_debug_info(state: tauri::State<Arc<AppState>>) -> serde_json::Value {
     let events = state.hook_events.read().clone();
     let diagnostics = state.state_diagnostics.read().clone();
+    let scan_stats = state.scan_stats.read().clone();
+    let arbiter = state.timings.read().arbiter;
     let timestamps: Vec<serde_json::Value> = {
         let ts = state.hook_timestamps.read();
         ts.iter()
@@ -429,7 +1131,7 @@ fn get_debug_info(state: tauri::State<Arc<AppState>>) -> serde_json::Value {
                 serde_json::json!({
                     "session_id": id,
                     "age_secs": instant.elapsed().as_secs(),
-                    "protected": instant.elapsed().as_secs() < HOOK_GRACE_PERIOD_SECS,
+                    "protected": arbiter.hook_protected(Some(instant.elapsed()), None),
                 })
             })
             .collect()
@@ -454,39 +1156,62 @@ fn get_debug_info(state: tauri::State<Arc<AppState>>) -> serde_json::Value {
         "hook_events": events,
         "hook_timestamps": timestamps,
         "state_diagnostics": diagnostics,
+        "scan_stats": scan_stats,
+        "bus_subscribers": state.bus.lag_stats(),
         "sessions": sessions,
     })
 }
 
 // Tauri command: Get settings
 #[tauri::command]
+#[specta::specta]
 fn get_settings() -> AppSettings {
     load_settings()
 }
 
 // Tauri command: Update settings
 #[tauri::command]
-fn update_settings(settings: AppSettings) -> Result<(), String> {
-    save_settings(&settings)
-}
-
-// Tauri command: Get available terminals
-#[tauri::command]
-fn get_available_terminals() -> Vec<String> {
-    let mut available = vec!["auto".to_string()];
-
-    for &term in KNOWN_TERMINALS {
-        let app_path = format!("/Applications/{}.app", term);
-        if std::path::Path::new(&app_path).exists() {
-            available.push(term.to_string());
-        }
+#[specta::specta]
+fn update_settings(
+    app: AppHandle,
+    state: tauri::State<Arc<AppState>>,
+    mut settings: AppSettings,
+) -> Result<(), String> {
+    settings.clamp_timings();
+    settings.validate_hook_port()?;
+    redaction::validate_patterns(&settings.redaction_patterns)?;
+    ignore::validate_patterns(&settings.ignored_paths, &settings.ignored_tmux_sessions)?;
+    terminals::validate(&settings.custom_terminals)?;
+    lan::validate(&settings.lan, settings.hook_port)?;
+    at_rest::validate(&settings.at_rest)?;
+    let previous = load_settings();
+    if settings.terminal_app == "auto" {
+        settings.terminal_bundle_id = None;
+    } else if settings.terminal_app != previous.terminal_app
+        || settings.terminal_bundle_id.is_none()
+    {
+        settings.terminal_bundle_id = terminals::resolve_bundle_id(&settings.terminal_app);
     }
-
-    available
+    plugins::floating_widget::validate_shortcut(&settings.widget_shortcut)?;
+    if settings.launch_at_login != previous.launch_at_login {
+        plugins::login_item::apply(&app, settings.launch_at_login)?;
+    }
+    save_settings(&settings)?;
+    *state.timings.write() = settings.timings();
+    *state.redactor.write() = redaction::Redactor::new(&settings.redaction_patterns);
+    *state.ignore.write() = ignore::IgnoreList::new(
+        &settings.ignored_paths,
+        &settings.ignored_tmux_sessions,
+        &settings.disabled_agents,
+    );
+    // Pick up a changed scan interval right away
+    state.scan_now.notify_one();
+    plugins::floating_widget::set_shortcut(&app, &settings.widget_shortcut)
 }
 
 // Tauri command: Focus terminal
 #[tauri::command]
+#[specta::specta]
 async fn focus_terminal(tmux_target: String) -> Result<(), String> {
     focus_tmux_target(&tmux_target).await
 }
@@ -503,29 +1228,41 @@ async fn focus_tmux_target(tmux_target: &str) -> Result<(), String> {
     let window = window_pane.get(0).unwrap_or(&"0");
     let pane = window_pane.get(1).unwrap_or(&"0");
 
-    // Get terminal app from settings
+    // The terminal a client has the session open in, else the one from settings
     let settings = load_settings();
-    let terminal = if settings.terminal_app == "auto" {
-        detect_terminal().unwrap_or_else(|| "Terminal".to_string())
-    } else {
-        settings.terminal_app.clone()
-    };
-
-    // Activate terminal using osascript
-    let activate_script = format!("tell application \"{}\" to activate", terminal);
-    let activate_result = cmd("osascript").args(["-e", &activate_script]).output();
-
-    if let Err(e) = activate_result {
-        log::warn!("Failed to activate {}: {}", terminal, e);
-    }
+    let client = terminals::attached(session, &settings);
+    let terminal = client
+        .as_ref()
+        .and_then(|(_, terminal)| terminal.clone())
+        .unwrap_or_else(|| terminals::configured(&settings));
+    let target = format!("{}:{}.{}", session, window, pane);
 
-    // Small delay to let terminal focus
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    match &client {
+        Some((tty, _)) => {
+            if let Err(e) = terminals::activate(&terminal) {
+                log::warn!("{}", e);
+            }
 
-    let target = format!("{}:{}.{}", session, window, pane);
+            // Small delay to let terminal focus
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    // Switch the client to the target session (needed when pane is in a different tmux session)
-    let _ = cmd("tmux").args(["switch-client", "-t", &target]).output();
+            // Switch the client to the target session (needed when pane is in a different tmux session)
+            let _ = cmd("tmux")
+                .args(["switch-client", "-c", tty, "-t", &target])
+                .output();
+        }
+        // No tmux client would show the pane, so open a window attached to it
+        None => {
+            let exists = cmd("tmux")
+                .args(["has-session", "-t", session])
+                .output()
+                .is_ok_and(|o| o.status.success());
+            if !exists {
+                return Err(format!("tmux session not found: {}", session));
+            }
+            terminals::open_attached(&terminal, session)?;
+        }
+    }
 
     // Select the window and pane
     let _ = cmd("tmux")
@@ -534,10 +1271,57 @@ async fn focus_tmux_target(tmux_target: &str) -> Result<(), String> {
 
     let _ = cmd("tmux").args(["select-pane", "-t", &target]).output();
 
+    // Selecting a pane unzooms a window zoomed on a sibling; zoom this one instead
+    if settings.zoom_on_focus {
+        if let Err(e) = zoom_tmux_pane(&target, true) {
+            log::warn!("Failed to zoom {}: {}", target, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// `resize-pane -Z` toggles the window's zoom, so a window zoomed on another
+/// pane takes two: one to unzoom it, one to zoom the target
+fn zoom_toggles(zoomed: bool, active: bool, panes: u32, zoom: bool) -> u32 {
+    match (zoom, zoomed) {
+        (true, true) if active => 0,
+        (true, true) => 2,
+        (true, false) if panes > 1 => 1,
+        (false, true) => 1,
+        _ => 0,
+    }
+}
+
+fn zoom_tmux_pane(tmux_target: &str, zoom: bool) -> Result<(), String> {
+    let info = tmux_display(
+        tmux_target,
+        "#{window_zoomed_flag} #{pane_active} #{window_panes}",
+    )
+    .ok_or_else(|| format!("Pane not found: {}", tmux_target))?;
+    let fields: Vec<&str> = info.split_whitespace().collect();
+    let flag = |i: usize| fields.get(i) == Some(&"1");
+    let panes = fields.get(2).and_then(|n| n.parse().ok()).unwrap_or(1);
+    for _ in 0..zoom_toggles(flag(0), flag(1), panes, zoom) {
+        let output = cmd("tmux")
+            .args(["resize-pane", "-Z", "-t", tmux_target])
+            .output()
+            .map_err(|e| format!("Failed to run tmux: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+    }
     Ok(())
 }
 
-fn normalize_tty(tty: &str) -> String {
+// Tauri command: Zoom a tmux pane to fill its window, or unzoom it
+#[tauri::command]
+#[specta::specta]
+fn zoom_pane(tmux_target: String, zoom: bool) -> Result<(), String> {
+    zoom_tmux_pane(&tmux_target, zoom)
+}
+
+pub(crate) fn normalize_tty(tty: &str) -> String {
     tty.strip_prefix("/dev/").unwrap_or(tty).trim().to_string()
 }
 
@@ -615,6 +1399,34 @@ fn tmux_target_from_hook(notification: &HookNotification) -> Option<String> {
         })
 }
 
+fn tmux_pane_id_from_hook(notification: &HookNotification) -> Option<String> {
+    notification
+        .tmux
+        .as_ref()
+        .and_then(|tmux_ctx| tmux_ctx.pane_id.clone())
+        .filter(|pane_id| !pane_id.is_empty())
+}
+
+/// Hooks from ignored projects, tmux sessions or disabled agents are
+/// acknowledged but not tracked
+fn hook_is_ignored(state: &AppState, notification: &HookNotification) -> bool {
+    {
+        let ignore = state.ignore.read();
+        let agent_kind = agents::normalize_kind(notification.agent_kind.as_deref());
+        if ignore.ignores_path(&notification.cwd) || ignore.ignores_agent(agent_kind) {
+            return true;
+        }
+        if !ignore.has_tmux_patterns() {
+            return false;
+        }
+    }
+    // May shell out to tmux, so resolve the target without holding the lock
+    let Some(target) = tmux_target_from_hook(notification) else {
+        return false;
+    };
+    state.ignore.read().ignores_tmux_target(&target)
+}
+
 pub(crate) fn is_unresolved_hook_session(session: &C3Session) -> bool {
     session.id.starts_with("hook:")
         && session.tmux_target.is_none()
@@ -628,17 +1440,27 @@ async fn focus_session_id(state: Arc<AppState>, session_id: String) -> Result<()
     };
 
     let mut session = session.ok_or_else(|| "Session not found".to_string())?;
-    let tmux_target = session.tmux_target.clone().or_else(|| {
-        infer_tmux_target(
-            session.project_path.as_deref(),
-            session.terminal_tty.as_deref(),
-        )
-    });
+    if session.id.starts_with(vscode::SESSION_PREFIX) {
+        return vscode::focus(session.project_path.as_deref());
+    }
+    // Ask tmux where the pane is now, in case it moved since the last scan
+    let tmux_target = session
+        .tmux_pane_id
+        .as_deref()
+        .and_then(pane_target)
+        .or_else(|| session.tmux_target.clone())
+        .or_else(|| {
+            infer_tmux_target(
+                session.project_path.as_deref(),
+                session.terminal_tty.as_deref(),
+            )
+        });
 
     if let Some(tmux_target) = tmux_target {
-        if session.tmux_target.is_none() {
+        if session.tmux_target.as_ref() != Some(&tmux_target) {
             session.tmux_target = Some(tmux_target.clone());
-            state.sessions.write().insert(session_id, session);
+            state.sessions.write().insert(session_id, session.clone());
+            state.events.session_updated(session);
         }
         return focus_tmux_target(&tmux_target).await;
     }
@@ -646,29 +1468,11 @@ async fn focus_session_id(state: Arc<AppState>, session_id: String) -> Result<()
     // Hook-only sessions may be plain terminal processes, not tmux panes.
     // In that case we can reliably focus the configured terminal app; exact
     // tab selection depends on the terminal exposing a selectable tab API.
-    activate_terminal_app()
-}
-
-fn configured_terminal() -> String {
-    let settings = load_settings();
-    if settings.terminal_app == "auto" {
-        detect_terminal().unwrap_or_else(|| "Terminal".to_string())
-    } else {
-        settings.terminal_app
-    }
-}
-
-fn activate_terminal_app() -> Result<(), String> {
-    let terminal = configured_terminal();
-    let activate_script = format!("tell application \"{}\" to activate", terminal);
-    cmd("osascript")
-        .args(["-e", &activate_script])
-        .output()
-        .map_err(|e| format!("Failed to activate {}: {}", terminal, e))?;
-    Ok(())
+    terminals::activate(&terminals::configured(&load_settings()))
 }
 
 #[tauri::command]
+#[specta::specta]
 async fn focus_session(
     state: tauri::State<'_, Arc<AppState>>,
     session_id: String,
@@ -678,31 +1482,37 @@ async fn focus_session(
 
 // Tauri command: Send action to session
 #[tauri::command]
+#[specta::specta]
 async fn send_action(
     state: tauri::State<'_, Arc<AppState>>,
     session_id: String,
     action: String,
 ) -> Result<(), String> {
-    let msg = ServerMessage::Action { session_id, action };
-    let json = serde_json::to_string(&msg).map_err(|e| e.to_string())?;
-    let _ = state.tx.send(json);
+    event_log::action(&session_id, &action);
+    state
+        .bus
+        .publish(&ServerMessage::Action { session_id, action });
     Ok(())
 }
 
 // Tauri command: Remove session
 #[tauri::command]
+#[specta::specta]
 fn remove_session(state: tauri::State<Arc<AppState>>, session_id: String) {
     state.sessions.write().remove(&session_id);
+    state.events.session_removed(session_id);
 }
 
 // Tauri command: Get session metadata
 #[tauri::command]
+#[specta::specta]
 fn get_session_meta() -> SessionMetaStore {
     load_session_meta()
 }
 
 // Tauri command: Update session metadata (tag or pin)
 #[tauri::command]
+#[specta::specta]
 fn update_session_meta(
     session_id: String,
     tag: Option<String>,
@@ -726,6 +1536,7 @@ fn update_session_meta(
 }
 
 #[tauri::command]
+#[specta::specta]
 fn upsert_session_group(group: SessionGroup) -> Result<SessionMetaStore, String> {
     if group.id.trim().is_empty() {
         return Err("Group id is required".to_string());
@@ -755,6 +1566,7 @@ fn upsert_session_group(group: SessionGroup) -> Result<SessionMetaStore, String>
 }
 
 #[tauri::command]
+#[specta::specta]
 fn delete_session_group(group_id: String) -> Result<SessionMetaStore, String> {
     let mut store = load_session_meta();
     store.groups.retain(|g| g.id != group_id);
@@ -772,6 +1584,7 @@ fn delete_session_group(group_id: String) -> Result<SessionMetaStore, String> {
 }
 
 #[tauri::command]
+#[specta::specta]
 fn assign_session_group(
     session_id: String,
     group_id: Option<String>,
@@ -797,27 +1610,112 @@ fn assign_session_group(
     Ok(store)
 }
 
-// Tauri command: Create new tmux task
+// Tauri command: Mute or unmute notifications from a group's sessions
 #[tauri::command]
-async fn create_new_task() -> Result<String, String> {
-    // Find the first attached tmux session to create the window in
+#[specta::specta]
+fn set_group_muted(group_id: String, muted: bool) -> Result<SessionMetaStore, String> {
+    let mut store = load_session_meta();
+    let group = store
+        .groups
+        .iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| format!("Unknown group id: {group_id}"))?;
+    group.muted = muted;
+    save_session_meta(&store)?;
+    Ok(store)
+}
+
+// Tauri command: Mute or unmute notifications from sessions with a tag
+#[tauri::command]
+#[specta::specta]
+fn set_tag_muted(tag: String, muted: bool) -> Result<SessionMetaStore, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag is required".to_string());
+    }
+    let mut store = load_session_meta();
+    store.muted_tags.retain(|t| t != tag);
+    if muted {
+        store.muted_tags.push(tag.to_string());
+        store.muted_tags.sort();
+    }
+    save_session_meta(&store)?;
+    Ok(store)
+}
+
+// Tauri command: Set or clear (empty text) a session's note
+#[tauri::command]
+#[specta::specta]
+fn set_session_note(session_id: String, text: String) -> Result<SessionMetaStore, String> {
+    let mut store = load_session_meta();
+    let text = text.trim();
+    store.sessions.entry(session_id).or_default().notes = if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    };
+
+    store.sessions.retain(|_, m| !session_meta_is_empty(m));
+    save_session_meta(&store)?;
+    Ok(store)
+}
+
+// Tauri command: Persist a drag-reorder; `session_ids` get positions 0.. in the order given
+#[tauri::command]
+#[specta::specta]
+fn reorder_sessions(session_ids: Vec<String>) -> Result<SessionMetaStore, String> {
+    let mut store = load_session_meta();
+    for (position, session_id) in session_ids.into_iter().enumerate() {
+        store.sessions.entry(session_id).or_default().order = Some(position as u32);
+    }
+
+    store.sessions.retain(|_, m| !session_meta_is_empty(m));
+    save_session_meta(&store)?;
+    Ok(store)
+}
+
+/// The agent CLI new windows start with, from settings
+pub(crate) fn default_agent_command() -> &'static str {
+    agents::by_kind(&load_settings().default_agent).map_or("codex", |agent| agent.launch_command)
+}
+
+/// Start an agent CLI in a new tmux window in `cwd`, with `prompt` as its
+/// first message when given. Returns the new pane's target.
+pub(crate) fn start_agent(
+    cwd: &str,
+    agent_command: &str,
+    prompt: Option<&str>,
+) -> Result<String, String> {
+    let command = match prompt.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(prompt) => format!("{} {}", agent_command, shell_quote(prompt)),
+        None => agent_command.to_string(),
+    };
+    open_tmux_window(cwd, &command)
+}
+
+/// Open a window in the attached tmux session, starting in `cwd`, and type
+/// `command` into it. Returns the new pane's target.
+pub(crate) fn open_tmux_window(cwd: &str, command: &str) -> Result<String, String> {
+    // Find the first attached tmux session to create the window in; C3's
+    // control-mode clients are attached to every session, so skip those
     let list_output = cmd("tmux")
-        .args(["list-sessions", "-F", "#{session_name}:#{session_attached}"])
+        .args([
+            "list-clients",
+            "-F",
+            "#{client_control_mode}:#{client_session}",
+        ])
         .output()
-        .map_err(|e| format!("Failed to list tmux sessions: {}", e))?;
+        .map_err(|e| format!("Failed to list tmux clients: {}", e))?;
 
     let stdout = String::from_utf8_lossy(&list_output.stdout);
     let session_name = stdout
         .lines()
-        .find(|l| l.ends_with(":1")) // attached session
-        .and_then(|l| l.split(':').next())
+        .find_map(|l| l.strip_prefix("0:"))
         .unwrap_or("0")
         .to_string();
 
-    // Create a new window in the attached session, starting in the user's home directory.
     // Trailing colon means "this session, auto-assign window index" — without it,
     // tmux interprets the bare name as a window index and fails with "index in use".
-    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
     let target_session = format!("{}:", session_name);
     let create_window = cmd("tmux")
         .args([
@@ -825,7 +1723,7 @@ async fn create_new_task() -> Result<String, String> {
             "-t",
             &target_session,
             "-c",
-            &home,
+            cwd,
             "-P",
             "-F",
             "#{session_name}:#{window_index}.#{pane_index}",
@@ -842,49 +1740,74 @@ async fn create_new_task() -> Result<String, String> {
         .trim()
         .to_string();
 
-    let settings = load_settings();
-    let agent_command = match settings.default_agent.as_str() {
-        "claude" => "claude",
-        "codex" => "codex",
-        _ => "codex",
-    };
-
-    // Start the configured agent in the new window
     let _ = cmd("tmux")
-        .args(["send-keys", "-t", &target, agent_command, "Enter"])
+        .args(["send-keys", "-t", &target, command, "Enter"])
         .output();
 
     Ok(target)
 }
 
-// Tauri command: Play sound (system or custom file)
+// Tauri command: Create new tmux task
 #[tauri::command]
-async fn play_sound(sound: String) -> Result<(), String> {
-    // Determine if it's a custom file path or system sound name
-    let sound_file = if sound.starts_with('/') {
-        // Custom file path - use directly
-        sound
-    } else {
-        // System sound - look in /System/Library/Sounds/
-        format!("/System/Library/Sounds/{}.aiff", sound)
-    };
-
-    // Check if sound file exists
-    if !std::path::Path::new(&sound_file).exists() {
-        return Err(format!("Sound file not found: {}", sound_file));
-    }
+#[specta::specta]
+async fn create_new_task() -> Result<String, String> {
+    // New windows start in the user's home directory
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    open_tmux_window(&home, default_agent_command())
+}
 
-    // Play using afplay (macOS command-line audio player)
-    let result = cmd("afplay").arg(&sound_file).spawn();
+// Tauri command: Recompute a session's state from tmux and its transcript, ignoring the hook grace period
+#[tauri::command]
+#[specta::specta]
+async fn resync_session(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<C3Session, String> {
+    tmux_scanner::resync_session(state.inner(), &session_id).await
+}
 
-    match result {
-        Ok(_) => Ok(()),
-        Err(e) => Err(format!("Failed to play sound: {}", e)),
+// Tauri command: Continue a finished session's conversation in a new tmux window
+#[tauri::command]
+#[specta::specta]
+async fn resume_session(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<String, String> {
+    let session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    if !matches!(session.state, SessionState::Complete | SessionState::Error) {
+        return Err(format!("{} is still running", session.project_name));
     }
+    let cwd = session
+        .project_path
+        .ok_or_else(|| format!("{} has no project path", session.project_name))?;
+    let agent = agents::by_kind(session.agent_kind.as_deref().unwrap_or_default())
+        .unwrap_or(agents::FALLBACK);
+    open_tmux_window(&cwd, agent.resume_command)
+}
+
+// Tauri command: Play sound (system or custom file) at the alert volume and output
+#[tauri::command]
+#[specta::specta]
+async fn play_sound(sound: String) -> Result<(), String> {
+    let settings = load_settings();
+    tokio::task::spawn_blocking(move || {
+        alert_audio::play(
+            &sound,
+            settings.alert_volume,
+            settings.alert_output_device.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 // Hook status response
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub struct HookStatus {
     pub hooks_installed: bool,
     pub claude_hooks_installed: bool,
@@ -894,10 +1817,18 @@ pub struct HookStatus {
     pub jq_installed: bool,
     pub terminal_notifier_installed: bool,
     pub tmux_installed: bool,
+    /// `C3_HOOK_VERSION` of the installed script
+    pub hook_script_version: Option<u32>,
+    /// The installed script, or a hook that posted recently, speaks an older protocol
+    pub hook_script_outdated: bool,
+    /// `claude --version` of the CLI on PATH
+    pub claude_version: Option<String>,
+    /// What C3 is known to misread with that version, from `compat`
+    pub claude_compat_issues: Vec<String>,
 }
 
 // Setup result response
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Type)]
 pub struct SetupResult {
     pub success: bool,
     pub message: String,
@@ -906,7 +1837,13 @@ pub struct SetupResult {
 
 // Tauri command: Check hook installation status
 #[tauri::command]
+#[specta::specta]
 fn check_hook_status(app_handle: AppHandle) -> HookStatus {
+    hook_status(&app_handle)
+}
+
+/// Hooks installed per agent, their dependencies, and the Claude Code version
+pub(crate) fn hook_status(app_handle: &AppHandle) -> HookStatus {
     let home = std::env::var("HOME").unwrap_or_default();
 
     // Check if hook script is installed
@@ -976,6 +1913,11 @@ fn check_hook_status(app_handle: AppHandle) -> HookStatus {
         .ok()
         .map(|d| d.join("resources").join("c3-hook.sh"));
 
+    let hook_script_version = hook_protocol::installed_hook_version();
+    let hook_script_outdated = hook_protocol::outdated_payload_seen()
+        || hook_script_version.is_some_and(|v| v < hook_protocol::HOOK_PROTOCOL_VERSION);
+    let (claude_version, claude_compat_issues) = compat::check_installed(app_handle);
+
     HookStatus {
         hooks_installed: hook_script_exists && (claude_hooks_installed || codex_hooks_installed || omp_hooks_installed),
         claude_hooks_installed: claude_hooks_installed && hook_script_exists,
@@ -985,12 +1927,22 @@ fn check_hook_status(app_handle: AppHandle) -> HookStatus {
         jq_installed,
         terminal_notifier_installed,
         tmux_installed,
+        hook_script_version,
+        hook_script_outdated,
+        claude_version,
+        claude_compat_issues,
     }
 }
 
 // Tauri command: Set up C3 hooks
 #[tauri::command]
+#[specta::specta]
 fn setup_hooks(app_handle: AppHandle) -> SetupResult {
+    install_hooks(&app_handle)
+}
+
+/// Install c3-hook.sh and register it with each agent's settings
+pub(crate) fn install_hooks(app_handle: &AppHandle) -> SetupResult {
     let home = std::env::var("HOME").unwrap_or_default();
     if home.is_empty() {
         return SetupResult {
@@ -1049,7 +2001,7 @@ fn setup_hooks(app_handle: AppHandle) -> SetupResult {
     }
 
     // Step 3: Copy icon to config directory for terminal-notifier
-    let config_dir = PathBuf::from(&home).join(".config/c3");
+    let config_dir = paths::config_dir();
     let _ = fs::create_dir_all(&config_dir);
     let icon_source = app_handle
         .path()
@@ -1098,32 +2050,8 @@ fn setup_hooks(app_handle: AppHandle) -> SetupResult {
         serde_json::json!({})
     };
 
-    let c3_hooks = serde_json::json!({
-        "Stop": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh Stop" }]
-            }
-        ],
-        "Notification": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh Notification" }]
-            }
-        ],
-        "PermissionRequest": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh PermissionRequest" }]
-            }
-        ],
-        "SessionStart": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh SessionStart" }]
-            }
-        ]
-    });
+    let hook_port = load_settings().hook_port;
+    let c3_hooks = hook_protocol::agent_hooks("claude", hook_port);
 
     // Merge: preserve user's other settings and other hook types
     let mut settings = existing.clone();
@@ -1196,32 +2124,7 @@ fn setup_hooks(app_handle: AppHandle) -> SetupResult {
         serde_json::json!({})
     };
 
-    let codex_c3_hooks = serde_json::json!({
-        "Stop": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=codex $HOME/.local/bin/c3-hook.sh Stop" }]
-            }
-        ],
-        "Notification": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=codex $HOME/.local/bin/c3-hook.sh Notification" }]
-            }
-        ],
-        "PermissionRequest": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=codex $HOME/.local/bin/c3-hook.sh PermissionRequest" }]
-            }
-        ],
-        "SessionStart": [
-            {
-                "matcher": "",
-                "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=codex $HOME/.local/bin/c3-hook.sh SessionStart" }]
-            }
-        ]
-    });
+    let codex_c3_hooks = hook_protocol::agent_hooks("codex", hook_port);
 
     let mut codex_settings = codex_existing.clone();
     if !codex_settings.is_object() {
@@ -1297,6 +2200,8 @@ fn setup_hooks(app_handle: AppHandle) -> SetupResult {
         };
     }
 
+    // Outdated payloads seen so far came from the scripts just replaced
+    hook_protocol::reset();
     SetupResult {
         success: true,
         message:
@@ -1306,11 +2211,11 @@ fn setup_hooks(app_handle: AppHandle) -> SetupResult {
     }
 }
 
-// Tauri command: Close tmux pane
+// Tauri command: Close tmux pane, by `%N` pane ID or target
 #[tauri::command]
+#[specta::specta]
 async fn close_pane(
     state: tauri::State<'_, Arc<AppState>>,
-    app_handle: AppHandle,
     tmux_target: String,
 ) -> Result<(), String> {
     // Kill the tmux pane
@@ -1319,9 +2224,13 @@ async fn close_pane(
     match result {
         Ok(output) if output.status.success() => {
             // Remove the session from our state
-            let session_id = format!("tmux:{}", tmux_target);
-            state.sessions.write().remove(&session_id);
-            let _ = app_handle.emit("session-removed", session_id);
+            let mut sessions = state.sessions.write();
+            let session_id = session_identity::at_pane(&sessions, &tmux_target)
+                .map(|session| session.id.clone());
+            if let Some(session_id) = session_id {
+                sessions.remove(&session_id);
+                state.events.session_removed(session_id);
+            }
             Ok(())
         }
         Ok(output) => {
@@ -1332,11 +2241,305 @@ async fn close_pane(
     }
 }
 
+fn tmux_display(tmux_target: &str, format: &str) -> Option<String> {
+    let output = cmd("tmux")
+        .args(["display-message", "-p", "-t", tmux_target, format])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+pub(crate) fn pane_current_command(tmux_target: &str) -> Option<String> {
+    tmux_display(tmux_target, "#{pane_current_command}")
+}
+
+/// Current `session:window.pane` of a `%N` pane ID
+fn pane_target(pane_id: &str) -> Option<String> {
+    tmux_display(pane_id, "#{session_name}:#{window_index}.#{pane_index}")
+        .filter(|target| !target.is_empty())
+}
+
+/// Accept a pending permission prompt by answering it in the agent's pane
+pub(crate) fn approve_permission(state: &AppState, session_id: &str) -> Result<(), String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    if session.state != SessionState::AwaitingPermission {
+        return Err(format!(
+            "{} is not waiting for permission",
+            session.project_name
+        ));
+    }
+    let tmux_target = session_identity::tmux_pane(&session)
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} is not running in tmux", session.project_name))?;
+
+    // Claude Code, OMP and Aider preselect "Yes"; Codex approves with `y`
+    let key = match session.agent_kind.as_deref() {
+        Some("codex") => "y",
+        _ => "Enter",
+    };
+    tmux_send_keys(&tmux_target, &[key])?;
+    log::info!("Approved permission request in {}", tmux_target);
+    event_log::action(session_id, "approve");
+
+    // Let the scanner pick up the new state instead of holding the hook's
+    state.hook_timestamps.write().remove(session_id);
+    state.scan_now.notify_one();
+    Ok(())
+}
+
+/// Refuse a pending permission prompt in the agent's pane
+pub(crate) fn deny_permission(state: &AppState, session_id: &str) -> Result<(), String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    if session.state != SessionState::AwaitingPermission {
+        return Err(format!(
+            "{} is not waiting for permission",
+            session.project_name
+        ));
+    }
+    let tmux_target = session_identity::tmux_pane(&session)
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} is not running in tmux", session.project_name))?;
+
+    // Claude Code and OMP cancel with Escape; Codex and Aider take `n`
+    let keys: &[&str] = match session.agent_kind.as_deref() {
+        Some("codex") => &["n"],
+        Some("aider") => &["n", "Enter"],
+        _ => &["Escape"],
+    };
+    tmux_send_keys(&tmux_target, keys)?;
+    log::info!("Denied permission request in {}", tmux_target);
+    event_log::action(session_id, "deny");
+
+    state.hook_timestamps.write().remove(session_id);
+    state.scan_now.notify_one();
+    Ok(())
+}
+
+/// Type `text` at the agent's prompt and submit it
+pub(crate) fn send_input(state: &AppState, session_id: &str, text: &str) -> Result<(), String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let tmux_target = session_identity::tmux_pane(&session)
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} is not running in tmux", session.project_name))?;
+    // -l sends the text literally, so words like "Enter" aren't read as keys
+    tmux_send_keys(&tmux_target, &["-l", text])?;
+    tmux_send_keys(&tmux_target, &["Enter"])?;
+    log::info!("Sent input to {}", tmux_target);
+    event_log::action(session_id, "input");
+    Ok(())
+}
+
+/// Pick one of the options a session's question offers. AskUserQuestion's
+/// list starts on its first option; a question asked in a reply is answered
+/// by typing the option.
+pub(crate) fn answer_pending_question(
+    state: &AppState,
+    session_id: &str,
+    option_index: usize,
+) -> Result<(), String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let action = session
+        .pending_action
+        .as_ref()
+        .filter(|action| !action.options.is_empty())
+        .ok_or_else(|| format!("{} is not asking a question", session.project_name))?;
+    let option = action
+        .options
+        .get(option_index)
+        .ok_or_else(|| format!("The question has no option {}", option_index + 1))?;
+    let tmux_target = session_identity::tmux_pane(&session)
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} is not running in tmux", session.project_name))?;
+
+    if action.tool.as_deref() == Some(jsonl::ASK_TOOL) {
+        let mut keys = vec!["Down"; option_index];
+        keys.push("Enter");
+        tmux_send_keys(&tmux_target, &keys)?;
+    } else {
+        tmux_send_keys(&tmux_target, &["-l", option])?;
+        tmux_send_keys(&tmux_target, &["Enter"])?;
+    }
+    log::info!("Answered \"{}\" in {}", option, tmux_target);
+    event_log::action(session_id, "answer");
+
+    // Let the scanner pick up the new state instead of holding the hook's
+    state.hook_timestamps.write().remove(session_id);
+    state.scan_now.notify_one();
+    Ok(())
+}
+
+// Tauri command: Approve a session's pending permission request
+#[tauri::command]
+#[specta::specta]
+async fn approve_session(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<(), String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || approve_permission(&state, &session_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Deny a session's pending permission request
+#[tauri::command]
+#[specta::specta]
+async fn deny_session(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<(), String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || deny_permission(&state, &session_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Type a reply at a session's prompt
+#[tauri::command]
+#[specta::specta]
+async fn send_session_input(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    text: String,
+) -> Result<(), String> {
+    if text.trim().is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || send_input(&state, &session_id, &text))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Answer a session's question with one of its options
+#[tauri::command]
+#[specta::specta]
+async fn answer_question(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    option_index: u32,
+) -> Result<(), String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        answer_pending_question(&state, &session_id, option_index as usize)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn tmux_send_keys(tmux_target: &str, keys: &[&str]) -> Result<(), String> {
+    let output = cmd("tmux")
+        .args(["send-keys", "-t", tmux_target])
+        .args(keys)
+        .output()
+        .map_err(|e| format!("Failed to execute tmux: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to send keys to {}: {}",
+            tmux_target,
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+// Tauri command: Stop the agent process but keep the pane and its shell alive
+#[tauri::command]
+#[specta::specta]
+async fn stop_claude(
+    state: tauri::State<'_, Arc<AppState>>,
+    tmux_target: String,
+) -> Result<(), String> {
+    let current = pane_current_command(&tmux_target)
+        .ok_or_else(|| format!("No tmux pane found for {}", tmux_target))?;
+    if tmux_scanner::is_shell_command(&current) {
+        return Err("No agent is running in this pane".to_string());
+    }
+
+    let (session_id, agent_kind) = {
+        let sessions = state.sessions.read();
+        let session = session_identity::at_pane(&sessions, &tmux_target);
+        (
+            session.map(|s| s.id.clone()),
+            session
+                .and_then(|s| s.agent_kind.clone())
+                .unwrap_or_else(|| "claude".to_string()),
+        )
+    };
+
+    let send_key = |key: &str| -> Result<(), String> {
+        let output = cmd("tmux")
+            .args(["send-keys", "-t", &tmux_target, key])
+            .output()
+            .map_err(|e| format!("Failed to execute tmux: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to send {}: {}",
+                key,
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    };
+
+    // First Ctrl-C interrupts the running turn, the second one exits from the prompt.
+    send_key("C-c")?;
+    tokio::time::sleep(tokio::time::Duration::from_millis(150)).await;
+    send_key("C-c")?;
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+    // Only fall back to Ctrl-D while the agent is still in the foreground,
+    // otherwise it would reach the shell and close the pane.
+    if let Some(current) = pane_current_command(&tmux_target) {
+        if !tmux_scanner::is_shell_command(&current) {
+            send_key("C-d")?;
+        }
+    }
+
+    // Let the scanner take over immediately instead of deferring to the last hook state
+    if let Some(session_id) = session_id {
+        state.hook_timestamps.write().remove(&session_id);
+    }
+    state.stop_requests.write().insert(
+        tmux_target,
+        StopRequest {
+            agent_kind,
+            requested_at: std::time::Instant::now(),
+            confirmed: false,
+        },
+    );
+    Ok(())
+}
+
 // Tauri command: Kill the terminal/pane for a known session
 #[tauri::command]
+#[specta::specta]
 async fn kill_session(
     state: tauri::State<'_, Arc<AppState>>,
-    app_handle: AppHandle,
     session_id: String,
 ) -> Result<(), String> {
     let session = {
@@ -1345,12 +2548,14 @@ async fn kill_session(
     }
     .ok_or_else(|| "Session not found".to_string())?;
 
-    let tmux_target = session.tmux_target.clone().or_else(|| {
-        infer_tmux_target(
-            session.project_path.as_deref(),
-            session.terminal_tty.as_deref(),
-        )
-    });
+    let tmux_target = session_identity::tmux_pane(&session)
+        .map(str::to_string)
+        .or_else(|| {
+            infer_tmux_target(
+                session.project_path.as_deref(),
+                session.terminal_tty.as_deref(),
+            )
+        });
     let tmux_target = tmux_target.ok_or_else(|| {
         "No tmux target found for this session. C3 can only kill tmux-backed terminals.".to_string()
     })?;
@@ -1359,14 +2564,18 @@ async fn kill_session(
 
     match result {
         Ok(output) if output.status.success() => {
-            let tmux_session_id = format!("tmux:{}", tmux_target);
+            event_log::action(&session_id, "kill");
             let mut sessions = state.sessions.write();
             sessions.remove(&session_id);
-            sessions.remove(&tmux_session_id);
+            let pane_session_id = session_identity::at_pane(&sessions, &tmux_target)
+                .map(|pane_session| pane_session.id.clone());
+            if let Some(pane_session_id) = &pane_session_id {
+                sessions.remove(pane_session_id);
+            }
             drop(sessions);
-            let _ = app_handle.emit("session-removed", session_id);
-            if tmux_session_id != session.id {
-                let _ = app_handle.emit("session-removed", tmux_session_id);
+            state.events.session_removed(session_id);
+            if let Some(pane_session_id) = pane_session_id {
+                state.events.session_removed(pane_session_id);
             }
             Ok(())
         }
@@ -1379,8 +2588,8 @@ async fn kill_session(
 }
 
 // Tmux context from hook
-#[derive(Debug, Clone, Deserialize, Default)]
-struct TmuxContext {
+#[derive(Debug, Clone, Deserialize, Default, Type)]
+pub(crate) struct TmuxContext {
     #[serde(default)]
     session: String,
     #[serde(default)]
@@ -1389,12 +2598,18 @@ struct TmuxContext {
     pane: String,
     #[serde(default)]
     window_name: String,
+    /// `$TMUX_PANE`, e.g. `%12`; sent since protocol version 4
+    #[serde(default)]
+    pane_id: Option<String>,
 }
 
 // Hook notification from Claude Code
-#[derive(Debug, Clone, Deserialize)]
-struct HookNotification {
-    hook_type: String,
+#[derive(Debug, Clone, Deserialize, Type)]
+pub(crate) struct HookNotification {
+    /// Hook protocol version; missing from scripts older than protocol 2
+    #[serde(default)]
+    version: Option<u32>,
+    pub hook_type: String,
     cwd: String,
     #[serde(default)]
     terminal_tty: Option<String>,
@@ -1414,15 +2629,9 @@ struct HookNotification {
     hook_payload_keys: Vec<String>,
     #[serde(default)]
     tmux: Option<TmuxContext>,
-}
-
-fn normalize_agent_kind(agent_kind: Option<&str>) -> String {
-    match agent_kind.unwrap_or("").to_ascii_lowercase().as_str() {
-        "codex" => "codex".to_string(),
-        "omp" => "omp".to_string(),
-        "claude" => "claude".to_string(),
-        _ => "unknown".to_string(),
-    }
+    /// Unix time the script spooled this payload because C3 wasn't listening
+    #[serde(default)]
+    queued_at: Option<i64>,
 }
 
 fn hook_payload_keys_summary(notification: &HookNotification) -> String {
@@ -1461,17 +2670,73 @@ fn log_hook_permission_diagnostic(
     });
 }
 
+/// Pending action for a PermissionRequest hook, with secrets masked before the
+/// command is truncated for display
+fn hook_permission_action(state: &AppState, notification: &HookNotification) -> PendingAction {
+    if let Some(asked) = ask_tool_action(notification) {
+        return asked;
+    }
+    let command = notification
+        .tool_input
+        .as_ref()
+        .and_then(|i| i.get("command"))
+        .and_then(|c| c.as_str())
+        .map(|s| state.redactor.read().redact(s).into_owned())
+        .map(|s| {
+            if s.len() > 100 {
+                format!("{}...", &s[..97])
+            } else {
+                s
+            }
+        });
+    PendingAction {
+        action_type: "permission".to_string(),
+        description: format!(
+            "Wants to use {}",
+            notification.tool_name.as_deref().unwrap_or("a tool")
+        ),
+        tool: notification.tool_name.clone(),
+        command,
+        options: Vec::new(),
+    }
+}
+
+/// The question of a PermissionRequest hook for AskUserQuestion
+fn ask_tool_action(notification: &HookNotification) -> Option<PendingAction> {
+    if notification.hook_type != "PermissionRequest"
+        || notification.tool_name.as_deref() != Some(jsonl::ASK_TOOL)
+    {
+        return None;
+    }
+    jsonl::ask_tool_question(notification.tool_input.as_ref()?)
+}
+
+/// The question a Claude session's last message asked, read from the hook's
+/// conversation or else the newest transcript in its directory
+fn claude_question(
+    state: &AppState,
+    cwd: &str,
+    conversation: Option<&str>,
+) -> Option<PendingAction> {
+    let path = conversation
+        .map(|uuid| tmux_scanner::cwd_to_project_dir(cwd).join(format!("{}.jsonl", uuid)))
+        .filter(|path| path.exists())
+        .or_else(|| tmux_scanner::find_claude_jsonl(cwd))?;
+    jsonl::pending_question(&state.jsonl_tails.read_last_lines(&path, 30))
+}
+
 fn shell_quote(value: &str) -> String {
     format!("'{}'", value.replace('\'', "'\\''"))
 }
 
 /// Send an OS notification via terminal-notifier
-fn send_os_notification(
+pub(crate) fn send_os_notification(
     message: &str,
     title: &str,
     subtitle: &str,
     tmux: &Option<TmuxContext>,
     session_id: Option<&str>,
+    hook_port: u16,
 ) {
     let mut notifier = cmd("terminal-notifier");
     notifier
@@ -1484,9 +2749,8 @@ fn send_os_notification(
 
     // Use C3's icon as content image (-appIcon is broken on modern macOS,
     // -sender breaks -execute click handling, so -contentImage is the best option)
-    let home = std::env::var("HOME").unwrap_or_default();
-    let icon_path = format!("{home}/.config/c3/icon.png");
-    if std::path::Path::new(&icon_path).exists() {
+    let icon_path = paths::config_dir().join("icon.png");
+    if icon_path.exists() {
         notifier.arg("-contentImage").arg(&icon_path);
     }
 
@@ -1495,16 +2759,17 @@ fn send_os_notification(
     if let Some(session_id) = session_id {
         notifier.arg("-execute").arg(format!(
             "curl -fsS {} >/dev/null 2>&1",
-            shell_quote(&format!("http://127.0.0.1:9398/focus/{}", session_id)),
+            shell_quote(&format!(
+                "http://127.0.0.1:{}/focus/{}",
+                hook_port, session_id
+            )),
         ));
     } else if let Some(tmux_ctx) = tmux {
         if !tmux_ctx.session.is_empty() && !tmux_ctx.window.is_empty() {
             let settings = load_settings();
-            let terminal = if settings.terminal_app == "auto" {
-                detect_terminal().unwrap_or_else(|| "Terminal".to_string())
-            } else {
-                settings.terminal_app
-            };
+            let terminal = terminals::attached(&tmux_ctx.session, &settings)
+                .and_then(|(_, terminal)| terminal)
+                .unwrap_or_else(|| terminals::configured(&settings));
             let pane = if tmux_ctx.pane.is_empty() {
                 "0"
             } else {
@@ -1513,8 +2778,8 @@ fn send_os_notification(
             let target = format!("{}:{}.{}", tmux_ctx.session, tmux_ctx.window, pane);
             let window_target = format!("{}:{}", tmux_ctx.session, tmux_ctx.window);
             let switch_script = format!(
-                "osascript -e {}; tmux switch-client -t {}; tmux select-window -t {}; tmux select-pane -t {}",
-                shell_quote(&format!("tell application \"{}\" to activate", terminal)),
+                "{}; tmux switch-client -t {}; tmux select-window -t {}; tmux select-pane -t {}",
+                terminals::activate_shell(&terminal),
                 shell_quote(&target),
                 shell_quote(&window_target),
                 shell_quote(&target),
@@ -1522,16 +2787,13 @@ fn send_os_notification(
             notifier.arg("-execute").arg(&switch_script);
         }
     } else {
-        let settings = load_settings();
-        let terminal = if settings.terminal_app == "auto" {
-            detect_terminal().unwrap_or_else(|| "Terminal".to_string())
-        } else {
-            settings.terminal_app
+        let terminal = terminals::configured(&load_settings());
+        match (&terminal.bundle_id, &terminal.focus_command) {
+            (Some(bundle_id), None) => notifier.arg("-activate").arg(bundle_id),
+            _ => notifier
+                .arg("-execute")
+                .arg(terminals::activate_shell(&terminal)),
         };
-        notifier.arg("-execute").arg(format!(
-            "osascript -e {}",
-            shell_quote(&format!("tell application \"{}\" to activate", terminal)),
-        ));
     }
 
     if let Err(e) = notifier.spawn() {
@@ -1539,8 +2801,76 @@ fn send_os_notification(
     }
 }
 
-// Handle HTTP hook request
-async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_handle: AppHandle) {
+/// Headers of an `/api` request
+struct ApiHeaders {
+    authorization: Option<String>,
+    origin: Option<String>,
+    host: Option<String>,
+    content_length: usize,
+}
+
+impl ApiHeaders {
+    fn secret(&self) -> Option<&str> {
+        self.authorization.as_deref().and_then(api_tokens::bearer)
+    }
+
+    /// Whether the request may do `needed`; errs with the HTTP status
+    fn authorize(&self, needed: TokenScope, remote: bool) -> Result<(), &'static str> {
+        api_tokens::check_origin(self.origin.as_deref(), self.host.as_deref(), remote)?;
+        api_tokens::authorize(self.secret(), needed, remote)
+    }
+}
+
+/// Read headers up to the blank line; None if the connection fails
+async fn read_api_headers(
+    reader: &mut (impl tokio::io::AsyncBufRead + Unpin),
+) -> Option<ApiHeaders> {
+    use tokio::io::AsyncBufReadExt;
+    let mut headers = ApiHeaders {
+        authorization: None,
+        origin: None,
+        host: None,
+        content_length: 0,
+    };
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await.is_err() {
+            return None;
+        }
+        if header == "\r\n" || header == "\n" {
+            return Some(headers);
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("authorization") {
+            headers.authorization = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("origin") {
+            headers.origin = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("host") {
+            headers.host = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            headers.content_length = value.trim().parse().unwrap_or(0);
+        }
+    }
+}
+
+/// Answer for an `/api` request without a token allowed to make it
+fn api_denied(status: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nWWW-Authenticate: Bearer\r\nContent-Length: 0\r\n\r\n",
+        status
+    )
+}
+
+// Handle HTTP hook request. `remote` requests come over the LAN server,
+// which serves only the token-protected `/api` routes.
+async fn handle_hook_request(
+    mut stream: impl tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    remote: bool,
+) {
     use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 
     let mut reader = BufReader::new(&mut stream);
@@ -1551,6 +2881,16 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         return;
     }
 
+    let is_api = request_line
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|path| path.starts_with("/api/"));
+    if remote && !is_api {
+        let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
     // Handle GET /sessions (debug endpoint)
     if request_line.starts_with("GET /sessions") {
         // Drain headers
@@ -1590,11 +2930,8 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         return;
     }
 
-    // Handle GET /focus/<session_id> for notification click callbacks.
-    if request_line.starts_with("GET /focus/") {
-        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
-        let session_id = path.strip_prefix("/focus/").unwrap_or_default().to_string();
-
+    // Handle GET /capabilities: protocol version and payload fields, for hook scripts
+    if request_line.starts_with("GET /capabilities") {
         // Drain headers
         loop {
             let mut header = String::new();
@@ -1605,15 +2942,11 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                 break;
             }
         }
-
-        let result = focus_session_id(state.clone(), session_id).await;
-        let (status, body) = match result {
-            Ok(_) => ("200 OK", "focused".to_string()),
-            Err(e) => ("404 Not Found", e),
-        };
+        let app_version = app_handle.package_info().version.to_string();
+        let body =
+            serde_json::to_string(&hook_protocol::capabilities(&app_version)).unwrap_or_default();
         let response = format!(
-            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
-            status,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
             body.len(),
             body
         );
@@ -1621,50 +2954,382 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         return;
     }
 
-    // Only handle POST /hook
-    if !request_line.starts_with("POST /hook") {
-        let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
-        let _ = stream.write_all(response.as_bytes()).await;
-        return;
-    }
-
-    // Read headers to find Content-Length
-    let mut content_length: usize = 0;
-    loop {
-        let mut header = String::new();
-        if reader.read_line(&mut header).await.is_err() {
+    // Handle GET /api/events: session changes as Server-Sent Events
+    if request_line.starts_with("GET /api/events") {
+        let Some(headers) = read_api_headers(&mut reader).await else {
+            return;
+        };
+        if let Err(status) = headers.authorize(TokenScope::Read, remote) {
+            let _ = stream.write_all(api_denied(status).as_bytes()).await;
             return;
         }
-        if header == "\r\n" || header == "\n" {
-            break;
+        let mut subscriber = state.bus.subscribe("sse");
+        let head =
+            "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\r\n";
+        if stream.write_all(head.as_bytes()).await.is_err() {
+            return;
         }
-        if header.to_lowercase().starts_with("content-length:") {
-            if let Some(len) = header.split(':').nth(1) {
-                content_length = len.trim().parse().unwrap_or(0);
+        // Start from a snapshot so clients don't also need GET /api/sessions
+        let mut message = Some(state.snapshot_message());
+        while let Some(data) = message {
+            let event = format!("data: {}\n\n", data);
+            if stream.write_all(event.as_bytes()).await.is_err() {
+                break;
             }
+            message = subscriber.recv(|| state.snapshot_message()).await;
         }
-    }
-
-    // Read body
-    let mut body = vec![0u8; content_length];
-    if reader.read_exact(&mut body).await.is_err() {
-        let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
-        let _ = stream.write_all(response.as_bytes()).await;
         return;
     }
 
-    // Parse JSON
-    let notification: HookNotification = match serde_json::from_slice(&body) {
-        Ok(n) => n,
-        Err(e) => {
-            log::error!("Failed to parse hook notification: {}", e);
-            let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
-        }
-    };
+    // Handle GET /statusline?session_id=…&pane=…[&format=json]: the other
+    // sessions, for the agent's own status line
+    if request_line.starts_with("GET /statusline") {
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let params =
+            deep_link::parse_query(path.split_once('?').map(|(_, q)| q).unwrap_or_default());
 
-    let agent_kind = normalize_agent_kind(notification.agent_kind.as_deref());
+        // Drain headers
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).await.is_err() {
+                return;
+            }
+            if header == "\r\n" || header == "\n" {
+                break;
+            }
+        }
+
+        let status = statusline::statusline(
+            &state.sessions.read(),
+            params.get("session_id").map(String::as_str),
+            params.get("pane").map(String::as_str),
+        );
+        let (status, content_type, body) = match params.get("format").map(String::as_str) {
+            None | Some("claude") => (
+                "200 OK",
+                "text/plain; charset=utf-8",
+                statusline::format_claude(&status),
+            ),
+            Some("json") => (
+                "200 OK",
+                "application/json",
+                serde_json::to_string(&status).unwrap_or_default(),
+            ),
+            Some(other) => (
+                "400 Bad Request",
+                "text/plain; charset=utf-8",
+                format!("Unknown format: {}", other),
+            ),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Handle GET /api/sessions[?format=script-filter] for launcher extensions
+    if request_line.starts_with("GET /api/sessions") {
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let format = path
+            .split_once('?')
+            .map(|(_, query)| query)
+            .unwrap_or_default()
+            .split('&')
+            .find_map(|param| param.strip_prefix("format="))
+            .map(str::to_string);
+
+        let Some(headers) = read_api_headers(&mut reader).await else {
+            return;
+        };
+        if let Err(status) = headers.authorize(TokenScope::Read, remote) {
+            let _ = stream.write_all(api_denied(status).as_bytes()).await;
+            return;
+        }
+
+        let sessions: Vec<C3Session> = state.sessions.read().values().cloned().collect();
+        let meta = load_session_meta();
+        let (status, body) = match format.as_deref() {
+            Some("script-filter") => {
+                let app_icon = app_handle
+                    .path()
+                    .resource_dir()
+                    .map(|d| d.join("resources").join("icon.png"))
+                    .unwrap_or_default();
+                let home = std::env::var("HOME").ok();
+                let filter = launcher::script_filter(
+                    sessions,
+                    &meta,
+                    &app_icon.to_string_lossy(),
+                    home.as_deref(),
+                );
+                ("200 OK", serde_json::to_string(&filter).unwrap_or_default())
+            }
+            None => {
+                let mut sessions = sessions;
+                query::sort_sessions(&mut sessions, &meta);
+                (
+                    "200 OK",
+                    serde_json::to_string(&sessions).unwrap_or_default(),
+                )
+            }
+            Some(other) => (
+                "400 Bad Request",
+                serde_json::json!({ "error": format!("Unknown format: {}", other) }).to_string(),
+            ),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Handle GET /focus/<session_id> for notification click callbacks.
+    if request_line.starts_with("GET /focus/") {
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let session_id = path.strip_prefix("/focus/").unwrap_or_default().to_string();
+
+        // Drain headers
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).await.is_err() {
+                return;
+            }
+            if header == "\r\n" || header == "\n" {
+                break;
+            }
+        }
+
+        let result = focus_session_id(state.clone(), session_id).await;
+        let (status, body) = match result {
+            Ok(_) => ("200 OK", "focused".to_string()),
+            Err(e) => ("404 Not Found", e),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Handle POST /api/focus-next: jump to the session that most needs attention
+    if request_line.starts_with("POST /api/focus-next") {
+        // The request body, if any, is ignored
+        let Some(headers) = read_api_headers(&mut reader).await else {
+            return;
+        };
+        if let Err(status) = headers.authorize(TokenScope::Control, remote) {
+            let _ = stream.write_all(api_denied(status).as_bytes()).await;
+            return;
+        }
+
+        let next = query::most_urgent_session(&state.sessions.read()).map(|s| s.id.clone());
+        let (status, body) = match next {
+            Some(session_id) => match focus_session_id(state.clone(), session_id.clone()).await {
+                Ok(_) => ("200 OK", format!("focused:{}", session_id)),
+                Err(e) => ("500 Internal Server Error", e),
+            },
+            None => ("404 Not Found", "no session needs attention".to_string()),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Handle POST /api/sessions/<id>/approve, /deny and /input (the body is
+    // the text to type), the same actions as the session window
+    if request_line.starts_with("POST /api/sessions/") {
+        let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+        let (session_id, action) = path
+            .strip_prefix("/api/sessions/")
+            .and_then(|rest| rest.rsplit_once('/'))
+            .map(|(id, action)| (deep_link::percent_decode(id, false), action.to_string()))
+            .unwrap_or_default();
+        let Some(headers) = read_api_headers(&mut reader).await else {
+            return;
+        };
+        if let Err(status) = headers.authorize(TokenScope::Control, remote) {
+            let _ = stream.write_all(api_denied(status).as_bytes()).await;
+            return;
+        }
+        if headers.content_length > MAX_API_INPUT_BYTES {
+            let response = "HTTP/1.1 413 Payload Too Large\r\nContent-Length: 0\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes()).await;
+            return;
+        }
+        let mut body = vec![0u8; headers.content_length];
+        if reader.read_exact(&mut body).await.is_err() {
+            let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes()).await;
+            return;
+        }
+        let text = String::from_utf8_lossy(&body).into_owned();
+
+        let state_action = state.clone();
+        let result = tokio::task::spawn_blocking(move || match action.as_str() {
+            "approve" => approve_permission(&state_action, &session_id),
+            "deny" => deny_permission(&state_action, &session_id),
+            "input" if text.trim().is_empty() => Err("Nothing to send".to_string()),
+            "input" => send_input(&state_action, &session_id, &text),
+            _ => Err(format!("Unknown action: {}", action)),
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+        let (status, body) = match result {
+            Ok(()) => ("200 OK", "ok".to_string()),
+            Err(e) => ("400 Bad Request", e),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Only handle POST /hook, POST /hook/batch and POST /simulate
+    let is_simulate = request_line.starts_with("POST /simulate");
+    let is_batch = request_line.starts_with("POST /hook/batch");
+    if !request_line.starts_with("POST /hook") && !is_simulate {
+        let response = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n";
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Read headers to find Content-Length
+    let mut content_length: usize = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await.is_err() {
+            return;
+        }
+        if header == "\r\n" || header == "\n" {
+            break;
+        }
+        if header.to_lowercase().starts_with("content-length:") {
+            if let Some(len) = header.split(':').nth(1) {
+                content_length = len.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    // Read body
+    if content_length > MAX_HOOK_BODY_BYTES {
+        let response = "HTTP/1.1 413 Payload Too Large\r\nContent-Length: 0\r\n\r\n";
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+    let mut body = vec![0u8; content_length];
+    if reader.read_exact(&mut body).await.is_err() {
+        let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Replay a simulation scenario (development builds only)
+    if is_simulate {
+        let result = serde_json::from_slice::<simulate::Scenario>(&body)
+            .map_err(|e| format!("invalid scenario: {}", e))
+            .and_then(|scenario| simulate::start_scenario(state.clone(), app_handle, scenario));
+        let (status, body) = match result {
+            Ok(()) => ("200 OK", "started".to_string()),
+            Err(e) => ("400 Bad Request", e),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Replay hooks the script spooled while C3 was unreachable, oldest first
+    if is_batch {
+        let (status, body) = match serde_json::from_slice::<Vec<HookNotification>>(&body) {
+            Ok(notifications) => {
+                let applied = hook_spool::process_batch(&state, &app_handle, notifications);
+                ("200 OK", format!("applied:{}", applied))
+            }
+            Err(e) => {
+                log::error!("Failed to parse hook batch: {}", e);
+                ("400 Bad Request", String::new())
+            }
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return;
+    }
+
+    // Parse JSON
+    let notification: HookNotification = match serde_json::from_slice(&body) {
+        Ok(n) => n,
+        Err(e) => {
+            log::error!("Failed to parse hook notification: {}", e);
+            let response = "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n";
+            let _ = stream.write_all(response.as_bytes()).await;
+            return;
+        }
+    };
+
+    let version = notification
+        .version
+        .unwrap_or(hook_protocol::LEGACY_PROTOCOL_VERSION);
+    if hook_protocol::record(version) {
+        log::warn!(
+            "Hook from {} uses protocol {} (current {}); reinstall hooks to upgrade",
+            notification.cwd,
+            version,
+            hook_protocol::HOOK_PROTOCOL_VERSION
+        );
+    }
+    // Old scripts are still served; the header tells them to upgrade
+    let upgrade = hook_protocol::upgrade_hint(version)
+        .map(|hint| format!("{}: {}\r\n", hook_protocol::UPGRADE_HEADER, hint))
+        .unwrap_or_default();
+
+    let body = process_hook(&state, &app_handle, notification);
+    let response = format!(
+        "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\n\r\n{}",
+        upgrade,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+}
+
+/// Apply a parsed hook notification: update the matched session, record
+/// diagnostics and send notifications. Returns the HTTP response body.
+pub(crate) fn process_hook(
+    state: &Arc<AppState>,
+    app_handle: &AppHandle,
+    notification: HookNotification,
+) -> String {
+    let agent_kind = agents::normalize_kind(notification.agent_kind.as_deref()).to_string();
 
     log::info!(
         "Hook received: {} from {} ({}, skip_perms={})",
@@ -1674,11 +3339,36 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         notification.skip_permissions
     );
 
+    // Sent by the guided setup to check the hook script reaches C3
+    if notification.hook_type == onboarding::TEST_HOOK_TYPE {
+        return onboarding::receive_test_hook(notification.session_id.as_deref());
+    }
+
+    // C3's own `claude -p` summarizing a session
+    if summaries::is_summarizer_cwd(&notification.cwd) {
+        return "skipped:summarizer".to_string();
+    }
+
+    if hook_is_ignored(state, &notification) {
+        log::info!("Ignoring hook from {} (ignore list)", notification.cwd);
+        state.log_hook_event(HookEvent {
+            timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
+            hook_type: notification.hook_type.clone(),
+            agent_kind: agent_kind.clone(),
+            cwd: notification.cwd.clone(),
+            matched_session: None,
+            new_state: "n/a".to_string(),
+            skipped: true,
+            skip_reason: Some("ignore list".to_string()),
+        });
+        return "skipped:ignored".to_string();
+    }
+
     // Skip PermissionRequest when running with --dangerously-skip-permissions
     if notification.skip_permissions && notification.hook_type == "PermissionRequest" {
         log::info!("Skipping PermissionRequest (--dangerously-skip-permissions)");
         log_hook_permission_diagnostic(
-            &state,
+            state,
             &notification,
             &agent_kind,
             None,
@@ -1700,14 +3390,7 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
             skipped: true,
             skip_reason: Some("--dangerously-skip-permissions".to_string()),
         });
-        let body = "skipped:skip_permissions";
-        let response = format!(
-            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-            body.len(),
-            body
-        );
-        let _ = stream.write_all(response.as_bytes()).await;
-        return;
+        return "skipped:skip_permissions".to_string();
     }
 
     // Suppress Notification hooks that fire shortly after a Stop hook for the same session
@@ -1719,19 +3402,23 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                 .values()
                 .find(|s| s.project_path.as_deref() == Some(&notification.cwd))
                 .map(|s| s.id.clone());
-            if let Some(ref sid) = matching_sid {
+            matching_sid.filter(|sid| {
                 let stops = state.stop_timestamps.read();
                 stops
                     .get(sid)
-                    .map(|t| t.elapsed().as_secs() < HOOK_GRACE_PERIOD_SECS)
+                    .map(|t| t.elapsed().as_secs() < state.hook_grace_period_secs())
                     .unwrap_or(false)
-            } else {
-                false
-            }
+            })
         };
 
-        if recently_stopped {
+        if let Some(sid) = recently_stopped {
             log::info!("Suppressing Notification hook — Stop fired recently for this session");
+            state.notification_log.record(
+                &sid,
+                &notification.hook_type,
+                Some(notification_log::Suppression::StopRecently),
+                None,
+            );
             state.log_hook_event(HookEvent {
                 timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
                 hook_type: notification.hook_type.clone(),
@@ -1742,17 +3429,25 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                 skipped: true,
                 skip_reason: Some("Stop fired recently".to_string()),
             });
-            let body = "skipped:stop_recently";
-            let response = format!(
-                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-                body.len(),
-                body
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
+            return "skipped:stop_recently".to_string();
         }
     }
 
+    if notification.hook_type == "PermissionRequest" {
+        let command = notification
+            .tool_input
+            .as_ref()
+            .and_then(|i| i.get("command"))
+            .and_then(|c| c.as_str())
+            .map(|s| state.redactor.read().redact(s).into_owned());
+        permission_stats::record(
+            &agent_kind,
+            notification.tool_name.as_deref(),
+            command.as_deref(),
+            permission_stats::hook_time(notification.queued_at),
+        );
+    }
+
     // Load settings for notifications/sounds
     let settings = load_settings();
 
@@ -1781,30 +3476,49 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
     let (new_state, notif_message, notif_subtitle) = match hook_info {
         Some(info) => info,
         None => {
-            let body = "unknown_hook";
-            let response = format!(
-                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-                body.len(),
-                body
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
+            return "unknown_hook".to_string();
         }
     };
 
-    // Prefer the exact tmux pane, then the hook session id, then path matches
-    // constrained to the same agent kind. Multiple agents commonly share a cwd.
+    // Prefer the Claude conversation, then the exact tmux pane, then the hook
+    // session id, then path matches constrained to the same agent kind.
+    // Multiple agents commonly share a cwd.
     let hook_tmux_target = tmux_target_from_hook(&notification);
+    let hook_pane_id = tmux_pane_id_from_hook(&notification);
+    let conversation = (agent_kind == "claude")
+        .then(|| session_identity::claude_uuid_from_hook(notification.session_id.as_deref()))
+        .flatten();
+
+    // A Claude turn that ends by asking something is described by the question
+    let question = (agent_kind == "claude"
+        && matches!(notification.hook_type.as_str(), "Stop" | "Notification"))
+    .then(|| claude_question(state, &notification.cwd, conversation.as_deref()))
+    .flatten();
+    let asked = question.clone().or_else(|| ask_tool_action(&notification));
+    let (notif_message, notif_subtitle) = match &asked {
+        Some(asked) => (asked.description.as_str(), "Question"),
+        None => (notif_message, notif_subtitle),
+    };
     let (session_id, project_name) = {
         let sessions = state.sessions.read();
         let kind_matches = |session: &&C3Session| {
-            agent_kind == "unknown"
+            agent_kind == agents::UNKNOWN_KIND
                 || session.agent_kind.as_deref() == Some(agent_kind.as_str())
         };
 
-        let found = hook_tmux_target
+        let found = conversation
             .as_ref()
-            .and_then(|target| sessions.get(&format!("tmux:{}", target)));
+            .and_then(|uuid| sessions.get(&session_identity::claude_session_id(uuid)));
+        let found = found.or_else(|| {
+            hook_pane_id
+                .as_ref()
+                .and_then(|pane_id| session_identity::for_pane(&sessions, pane_id))
+        });
+        let found = found.or_else(|| {
+            hook_tmux_target
+                .as_ref()
+                .and_then(|target| session_identity::for_target(&sessions, target))
+        });
         let found = found.or_else(|| {
             notification
                 .session_id
@@ -1835,6 +3549,36 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
     let mut session_id: Option<String> = session_id;
     let mut project_name: Option<String> = project_name;
 
+    // The pane's session takes its conversation's ID: once known, and again
+    // when a new conversation starts in the pane
+    if let (Some(uuid), Some(target), Some(sid)) = (&conversation, &hook_tmux_target, &session_id) {
+        let new_id = session_identity::claude_session_id(uuid);
+        let rekey = state
+            .sessions
+            .read()
+            .get(sid)
+            .filter(|session| {
+                session.id != new_id
+                    && session_identity::is_pane_session(&session.id)
+                    && match (&hook_pane_id, &session.tmux_pane_id) {
+                        (Some(hook_pane), Some(pane)) => hook_pane == pane,
+                        _ => session.tmux_target.as_ref() == Some(target),
+                    }
+            })
+            .map(|session| session_identity::Rekey {
+                session: session.clone(),
+                id: new_id.clone(),
+                target: target.clone(),
+                pane_id: hook_pane_id.clone(),
+            });
+        if let Some(rekey) = rekey {
+            session_identity::rekey(state, vec![rekey]);
+            session_id = Some(new_id);
+        }
+    }
+    let mut notify_allowed = true;
+    let mut created_session = false;
+
     if session_id.is_none() {
         let tmux_target = tmux_target_from_hook(&notification);
         let fallback_hook_id = notification
@@ -1847,38 +3591,33 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         {
             let sid = tmux_target
                 .as_ref()
-                .map(|target| format!("tmux:{}", target))
+                .map(|target| match (&conversation, &hook_pane_id) {
+                    (Some(uuid), _) => session_identity::claude_session_id(uuid),
+                    (None, Some(pane_id)) => session_identity::tmux_session_id(pane_id),
+                    (None, None) => session_identity::tmux_session_id(target),
+                })
                 .or(fallback_hook_id)
                 .unwrap();
+            if let Some(target) = &tmux_target {
+                session_identity::adopt_target_meta(&sid, target);
+            }
             let name = std::path::Path::new(&notification.cwd)
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| agent_kind.clone());
 
             let pending_action = if new_state == SessionState::AwaitingPermission {
-                Some(PendingAction {
-                    action_type: "permission".to_string(),
-                    description: format!(
-                        "Wants to use {}",
-                        notification.tool_name.as_deref().unwrap_or("a tool")
-                    ),
-                    tool: notification.tool_name.clone(),
-                    command: notification
-                        .tool_input
-                        .as_ref()
-                        .and_then(|i| i.get("command"))
-                        .and_then(|c| c.as_str())
-                        .map(|s| {
-                            if s.len() > 100 {
-                                format!("{}...", &s[..97])
-                            } else {
-                                s.to_string()
-                            }
-                        }),
-                })
+                Some(hook_permission_action(state, &notification))
             } else {
-                None
+                question.clone()
+            };
+            let git_baseline = if notification.hook_type == "SessionStart" {
+                git::record_baseline(&sid, &notification.cwd)
+            } else {
+                git::baseline_for_new_session(&sid, &notification.cwd)
             };
+            created_session = true;
+            state.archive.forget(&sid);
 
             let session = C3Session {
                 id: sid.clone(),
@@ -1887,17 +3626,35 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                 agent_kind: Some(agent_kind.clone()),
                 state: new_state.clone(),
                 tmux_target,
+                tmux_pane_id: hook_pane_id.clone(),
                 terminal_tty: notification.terminal_tty.clone(),
                 last_activity: Utc::now(),
                 pending_action,
                 metrics: None,
+                error_reason: None,
+                git_baseline,
+                pull_request: None,
+                summary: None,
+                yolo: notification.skip_permissions,
+                stalled: connectivity::stalls(state, &new_state),
             };
 
-            state.sessions.write().insert(sid.clone(), session.clone());
-            let _ = app_handle.emit("session-update", session);
+            if let Decision::Apply(effects) =
+                state_machine::decide(None, &new_state, StateSource::Hook, false)
+            {
+                state.sessions.write().insert(sid.clone(), session.clone());
+                if effects.timeline {
+                    state
+                        .timeline
+                        .record(&sid, None, new_state.clone(), StateSource::Hook);
+                }
+                if effects.emit {
+                    state.events.session_updated(session);
+                }
+            }
             if new_state == SessionState::AwaitingPermission {
                 log_hook_permission_diagnostic(
-                    &state,
+                    state,
                     &notification,
                     &agent_kind,
                     Some(sid.clone()),
@@ -1920,7 +3677,7 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
             );
             if new_state == SessionState::AwaitingPermission {
                 log_hook_permission_diagnostic(
-                    &state,
+                    state,
                     &notification,
                     &agent_kind,
                     None,
@@ -1960,7 +3717,7 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
 
         if unresolved_without_context {
             state.sessions.write().remove(sid);
-            let _ = app_handle.emit("session-removed", sid.clone());
+            state.events.session_removed(sid.clone());
             state.log_hook_event(HookEvent {
                 timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
                 hook_type: notification.hook_type.clone(),
@@ -1971,52 +3728,52 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                 skipped: true,
                 skip_reason: Some("removed unresolved hook-only session".to_string()),
             });
-            let body = "skipped:no_tmux_context";
-            let response = format!(
-                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-                body.len(),
-                body
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
+            return "skipped:no_tmux_context".to_string();
         }
 
-        // Check if we should skip this state change
-        let should_skip = {
-            let sessions = state.sessions.read();
-            sessions
-                .get(sid)
-                .map(|s| {
-                    s.state == SessionState::Complete && new_state == SessionState::AwaitingInput
-                })
-                .unwrap_or(false)
+        let current_state = state.sessions.read().get(sid).map(|s| s.state.clone());
+        let hook_protected = state.hook_protected(sid, None);
+        let effects = match state_machine::decide(
+            current_state.as_ref(),
+            &new_state,
+            StateSource::Hook,
+            hook_protected,
+        ) {
+            Decision::Apply(effects) => effects,
+            Decision::Skip(reason) => {
+                log::info!(
+                    "Hook: ignoring {} -> {:?} for {} ({})",
+                    notification.hook_type,
+                    new_state,
+                    sid,
+                    reason
+                );
+                state.log_hook_event(HookEvent {
+                    timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
+                    hook_type: notification.hook_type.clone(),
+                    agent_kind: agent_kind.clone(),
+                    cwd: notification.cwd.clone(),
+                    matched_session: Some(sid.clone()),
+                    new_state: format!("{:?}", new_state),
+                    skipped: true,
+                    skip_reason: Some(reason.to_string()),
+                });
+                if !notif_message.is_empty() {
+                    state.notification_log.record(
+                        sid,
+                        &notification.hook_type,
+                        Some(notification_log::Suppression::StateRejected),
+                        Some(reason.to_string()),
+                    );
+                }
+                return format!("matched:{}", sid);
+            }
         };
-
-        if should_skip {
-            log::info!("Hook: ignoring Notification->AwaitingInput, session already Complete");
-            state.log_hook_event(HookEvent {
-                timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
-                hook_type: notification.hook_type.clone(),
-                agent_kind: agent_kind.clone(),
-                cwd: notification.cwd.clone(),
-                matched_session: Some(sid.clone()),
-                new_state: format!("{:?}", new_state),
-                skipped: true,
-                skip_reason: Some("session already Complete".to_string()),
-            });
-            let body = format!("matched:{}", sid);
-            let response = format!(
-                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-                body.len(),
-                body
-            );
-            let _ = stream.write_all(response.as_bytes()).await;
-            return;
-        }
+        notify_allowed = effects.notify;
 
         if new_state == SessionState::AwaitingPermission {
             log_hook_permission_diagnostic(
-                &state,
+                state,
                 &notification,
                 &agent_kind,
                 Some(sid.clone()),
@@ -2031,12 +3788,22 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
             );
         }
 
+        // A new agent started in an existing pane: its changes start from here
+        let fresh_baseline = if notification.hook_type == "SessionStart" && !created_session {
+            git::record_baseline(sid, &notification.cwd)
+        } else {
+            None
+        };
+
         let mut sessions = state.sessions.write();
         if let Some(session) = sessions.get_mut(sid) {
             let old_state = session.state.clone();
+            if fresh_baseline.is_some() {
+                session.git_baseline = fresh_baseline;
+            }
             session.state = new_state.clone();
             session.last_activity = Utc::now();
-            if session.agent_kind.is_none() || session.agent_kind.as_deref() == Some("unknown") {
+            if session.agent_kind.is_none() || session.agent_kind.as_deref() == Some(agents::UNKNOWN_KIND) {
                 session.agent_kind = Some(agent_kind.clone());
             }
             if session.terminal_tty.is_none() {
@@ -2045,37 +3812,28 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
             if session.tmux_target.is_none() {
                 session.tmux_target = tmux_target_from_hook(&notification);
             }
+            if session.tmux_pane_id.is_none() {
+                session.tmux_pane_id = hook_pane_id.clone();
+            }
+            session.yolo |= notification.skip_permissions;
+            session.stalled = connectivity::stalls(state, &new_state);
 
-            // Set pending action for permission requests
+            // Set pending action for permission requests and questions
             if new_state == SessionState::AwaitingPermission {
-                session.pending_action = Some(PendingAction {
-                    action_type: "permission".to_string(),
-                    description: format!(
-                        "Wants to use {}",
-                        notification.tool_name.as_deref().unwrap_or("a tool")
-                    ),
-                    tool: notification.tool_name.clone(),
-                    command: notification
-                        .tool_input
-                        .as_ref()
-                        .and_then(|i| i.get("command"))
-                        .and_then(|c| c.as_str())
-                        .map(|s| {
-                            if s.len() > 100 {
-                                format!("{}...", &s[..97])
-                            } else {
-                                s.to_string()
-                            }
-                        }),
-                });
+                session.pending_action = Some(hook_permission_action(state, &notification));
             } else {
-                session.pending_action = None;
+                session.pending_action = question.clone();
             }
 
             let session_clone = session.clone();
             drop(sessions);
 
             log::info!("Hook: {} -> {:?} (was {:?})", sid, new_state, old_state);
+            if effects.timeline {
+                state
+                    .timeline
+                    .record(sid, Some(old_state), new_state.clone(), StateSource::Hook);
+            }
             state.log_hook_event(HookEvent {
                 timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
                 hook_type: notification.hook_type.clone(),
@@ -2097,20 +3855,32 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
                     .stop_timestamps
                     .write()
                     .insert(sid.clone(), std::time::Instant::now());
+                task_chain::on_stop(state, &session_clone, &settings);
+                complete_actions::on_stop(state, &session_clone, &settings);
+                summaries::on_stop(state, &session_clone, &settings);
+            }
+            let muted = notifications_muted(&load_session_meta(), &session_clone);
+            let offline = connectivity::is_offline(state);
+            let project_path = session_clone.project_path.clone();
+            if effects.emit {
+                state.events.session_updated(session_clone);
             }
-            let _ = app_handle.emit("session-update", session_clone);
 
-            // Tell the frontend to play the appropriate sound for this hook event.
-            // This is separate from state-change sounds because the scanner may have
-            // already set the state (e.g. AwaitingInput) before the hook fires.
+            // Tell the frontend to play this hook event's sound, from the
+            // project's theme. This is separate from state-change sounds because
+            // the scanner may have already set the state (e.g. AwaitingInput)
+            // before the hook fires.
             let sound_type = match notification.hook_type.as_str() {
                 "PermissionRequest" => Some("permission"),
                 "Notification" => Some("input"),
                 "Stop" => Some("complete"),
                 _ => None,
             };
-            if let Some(st) = sound_type {
-                let _ = app_handle.emit("hook-sound", st);
+            let sound = sound_type
+                .filter(|_| !muted && !offline && settings.notifications_enabled)
+                .and_then(|st| sound_themes::sound_for(&settings, st, project_path.as_deref()));
+            if let Some(sound) = sound {
+                let _ = app_handle.emit("hook-sound", sound);
             }
         }
     } else {
@@ -2145,13 +3915,18 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         notif_subtitle.to_string()
     };
 
-    // Debounce notifications per session — suppress if <1s since last notification for this session
+    // Debounce notifications per session — suppress if too soon after the last one for this session
+    let debounce_ms = state.timings.read().notification_debounce_ms;
     let should_notify = if let Some(ref sid) = session_id {
         let mut timestamps = state.notification_timestamps.write();
         let now = std::time::Instant::now();
         if let Some(last) = timestamps.get(sid) {
-            if now.duration_since(*last).as_millis() < 1000 {
-                log::info!("Suppressing notification for {} — debounce (<1s)", sid);
+            if now.duration_since(*last).as_millis() < u128::from(debounce_ms) {
+                log::info!(
+                    "Suppressing notification for {} — debounce (<{}ms)",
+                    sid,
+                    debounce_ms
+                );
                 false
             } else {
                 timestamps.insert(sid.clone(), now);
@@ -2165,36 +3940,77 @@ async fn handle_hook_request(mut stream: TcpStream, state: Arc<AppState>, app_ha
         true
     };
 
+    // Muted groups and tags get no notifications (nor sounds, above)
+    let muted = session_id
+        .as_ref()
+        .and_then(|sid| state.sessions.read().get(sid).cloned())
+        .is_some_and(|session| notifications_muted(&load_session_meta(), &session));
+    if muted {
+        log::info!("Suppressing notification — group or tag muted");
+    }
+    // Agents stop on connection errors while offline; that isn't news
+    let offline = connectivity::is_offline(state);
+    if offline {
+        log::info!("Suppressing notification — offline");
+    }
+
+    // Record why this hook did or didn't notify, for the diagnostics view
+    let suppression = if !settings.notifications_enabled {
+        Some(notification_log::Suppression::Disabled)
+    } else if muted {
+        Some(notification_log::Suppression::Muted)
+    } else if offline {
+        Some(notification_log::Suppression::Offline)
+    } else if !notify_allowed {
+        Some(notification_log::Suppression::StateRejected)
+    } else if !should_notify {
+        Some(notification_log::Suppression::Debounce)
+    } else if notification_log::focus_active() {
+        Some(notification_log::Suppression::DoNotDisturb)
+    } else {
+        None
+    };
+    if let Some(sid) = session_id.as_ref().filter(|_| !notif_message.is_empty()) {
+        let detail = (suppression == Some(notification_log::Suppression::Debounce))
+            .then(|| format!("within {}ms of the last one", debounce_ms));
+        state
+            .notification_log
+            .record(sid, &notification.hook_type, suppression, detail);
+    }
+
     // Send OS notification if enabled and this hook type warrants one
     // Sounds are handled by the frontend via session-update events
-    if should_notify && settings.notifications_enabled && !notif_message.is_empty() {
+    if should_notify
+        && notify_allowed
+        && !muted
+        && !offline
+        && settings.notifications_enabled
+        && !notif_message.is_empty()
+    {
         let title = if let Some(ref name) = project_name {
             format!("c3 — {}", name)
         } else {
             "c3".to_string()
         };
 
+        let message = state.redactor.read().redact(notif_message).into_owned();
         send_os_notification(
-            notif_message,
+            &message,
             &title,
             &subtitle,
             &notification.tmux,
             session_id.as_deref(),
+            state.hook_port,
         );
+        if let Some(event) = channels::ChannelEvent::from_hook(&notification.hook_type) {
+            channels::notify(event, &title, &message, session_id.as_deref());
+        }
     }
 
-    // Respond
-    let body = if session_id.is_some() {
-        format!("matched:{}", session_id.unwrap())
-    } else {
-        "no_match".to_string()
-    };
-    let response = format!(
-        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
-        body.len(),
-        body
-    );
-    let _ = stream.write_all(response.as_bytes()).await;
+    match session_id {
+        Some(sid) => format!("matched:{}", sid),
+        None => "no_match".to_string(),
+    }
 }
 
 // Start HTTP hook server
@@ -2203,7 +4019,7 @@ async fn start_hook_server(
     app_handle: AppHandle,
     mut shutdown: watch::Receiver<bool>,
 ) {
-    let addr = format!("127.0.0.1:{}", HOOK_SERVER_PORT);
+    let addr = format!("127.0.0.1:{}", state.hook_port);
     let listener = match TcpListener::bind(&addr).await {
         Ok(l) => l,
         Err(e) => {
@@ -2212,11 +4028,27 @@ async fn start_hook_server(
                 addr,
                 e
             );
+            let mut status = state.hook_server.write();
+            status.listening = false;
+            status.bind_error = Some(e.to_string());
             return;
         }
     };
+    {
+        let mut status = state.hook_server.write();
+        status.listening = true;
+        status.bind_error = None;
+    }
 
     log::info!("C3 hook server listening on http://{}", addr);
+    // For hooks not run with C3_HOOK_URL, e.g. OMP's and the status line
+    if let Err(e) = fs::write(
+        paths::state_dir().join("hook-url"),
+        format!("http://{}/hook\n", addr),
+    ) {
+        log::warn!("Failed to record the hook server URL: {}", e);
+    }
+    hook_spool::drain(&state, &app_handle);
 
     loop {
         tokio::select! {
@@ -2224,7 +4056,7 @@ async fn start_hook_server(
                 if let Ok((stream, _)) = result {
                     let state = state.clone();
                     let app_handle = app_handle.clone();
-                    tokio::spawn(handle_hook_request(stream, state, app_handle));
+                    tokio::spawn(handle_hook_request(stream, state, app_handle, false));
                 }
             }
             _ = shutdown.changed() => {
@@ -2233,49 +4065,88 @@ async fn start_hook_server(
             }
         }
     }
+    state.hook_server.write().listening = false;
     // listener is dropped here, port is released
 }
 
+async fn hook_server_reachable(port: u16) -> bool {
+    let addr = format!("127.0.0.1:{}", port);
+    matches!(
+        tokio::time::timeout(
+            tokio::time::Duration::from_secs(2),
+            TcpStream::connect(&addr)
+        )
+        .await,
+        Ok(Ok(_))
+    )
+}
+
+// React to system sleep/wake. Hook grace windows are measured with Instant,
+// which stops during sleep, so they'd otherwise outlive the sleep itself.
+async fn handle_power_events(
+    state: Arc<AppState>,
+    mut power_events: tokio::sync::mpsc::UnboundedReceiver<PowerEvent>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    loop {
+        let event = tokio::select! {
+            event = power_events.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = shutdown.changed() => break,
+        };
+
+        match event {
+            PowerEvent::WillSleep => log::info!("System going to sleep"),
+            PowerEvent::DidWake => {
+                log::info!("System woke up, rescanning sessions");
+                state.hook_timestamps.write().clear();
+                state.stop_timestamps.write().clear();
+                state.scan_now.notify_one();
+
+                // The supervisor restarts it if it died; just make that visible
+                if !hook_server_reachable(state.hook_port).await {
+                    log::warn!("Hook server not reachable after wake");
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    diagnostics::init_logging();
+    paths::migrate_legacy_config();
+    profiles::init();
 
     let state = Arc::new(AppState::new());
+    let commands = bindings::builder();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(plugins::login_item::plugin())
         .manage(state.clone())
-        .invoke_handler(tauri::generate_handler![
-            get_sessions,
-            get_debug_info,
-            focus_terminal,
-            focus_session,
-            send_action,
-            remove_session,
-            close_pane,
-            kill_session,
-            play_sound,
-            get_settings,
-            update_settings,
-            get_available_terminals,
-            get_session_meta,
-            update_session_meta,
-            upsert_session_group,
-            delete_session_group,
-            assign_session_group,
-            create_new_task,
-            check_hook_status,
-            setup_hooks,
-            plugins::mac_rounded_corners::enable_rounded_corners,
-            plugins::mac_rounded_corners::enable_modern_window_style,
-            plugins::mac_rounded_corners::reposition_traffic_lights
-        ])
+        .invoke_handler(commands.invoke_handler())
         .on_window_event(|window, event| {
-            // Hide window instead of closing — keep running in tray
+            // Hide window instead of closing — keep running in tray.
+            // Pop-out session windows close for real.
             if let WindowEvent::CloseRequested { api, .. } = event {
+                if session_window::is_session_window_label(window.label()) {
+                    return;
+                }
                 window.hide().unwrap_or_default();
                 api.prevent_close();
             }
@@ -2285,50 +4156,332 @@ pub fn run() {
 
             // Store the shutdown sender so we can trigger it on exit
             app.manage(ShutdownHandle(std::sync::Mutex::new(Some(shutdown_tx))));
+            app.manage(supervisor::Subsystems::default());
+            migrate_secrets();
+
+            // The window starts hidden; stay in the tray after a login launch if asked to
+            if plugins::login_item::launched_at_login() && load_settings().start_hidden {
+                log::info!("Started at login, staying in the tray");
+            } else {
+                show_main_window(app.handle());
+            }
 
             // Build system tray
             let show = MenuItemBuilder::with_id("show", "Show C3").build(app)?;
+            let compact = MenuItemBuilder::with_id("compact", "Compact View").build(app)?;
+            let widget = MenuItemBuilder::with_id("widget", "Floating Widget").build(app)?;
             let quit = MenuItemBuilder::with_id("quit", "Quit").build(app)?;
+            let profile_items = profiles::list()
+                .into_iter()
+                .map(|name| {
+                    CheckMenuItemBuilder::with_id(format!("profile:{}", name), &name)
+                        .checked(name == profiles::active())
+                        .build(app)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut profile_menu = SubmenuBuilder::new(app, "Profile");
+            for item in &profile_items {
+                profile_menu = profile_menu.item(item);
+            }
+            let profile_menu = profile_menu.build()?;
             let tray_menu = MenuBuilder::new(app)
                 .item(&show)
+                .item(&compact)
+                .item(&widget)
+                .item(&profile_menu)
                 .separator()
                 .item(&quit)
                 .build()?;
 
+            // Left click toggles the compact window; the menu is on right click
             let _tray = TrayIconBuilder::new()
                 .menu(&tray_menu)
-                .menu_on_left_click(true)
+                .show_menu_on_left_click(false)
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        rect,
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        if let Err(e) =
+                            plugins::compact_window::toggle(tray.app_handle(), Some(rect))
+                        {
+                            log::error!("Failed to toggle compact window: {}", e);
+                        }
+                    }
+                })
                 .on_menu_event(|app, event| match event.id().as_ref() {
-                    "show" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
+                    "show" => show_main_window(app),
+                    "compact" => {
+                        if let Err(e) = plugins::compact_window::toggle(app, None) {
+                            log::error!("Failed to toggle compact window: {}", e);
+                        }
+                    }
+                    "widget" => {
+                        if let Err(e) = plugins::floating_widget::toggle(app) {
+                            log::error!("Failed to toggle floating widget: {}", e);
                         }
                     }
                     "quit" => {
                         app.exit(0);
                     }
-                    _ => {}
+                    id => {
+                        if let Some(name) = id.strip_prefix("profile:") {
+                            if let Err(e) = profiles::switch_to(app, name) {
+                                log::error!("Failed to switch profile: {}", e);
+                            }
+                        }
+                    }
                 })
                 .build(app)?;
 
+            // Background tasks are supervised: restarted with backoff if they
+            // panic or exit, with a `subsystem-down` event to the UI
             let state_hook = state.clone();
-            let state_tmux = state.clone();
             let app_handle_hook = app.handle().clone();
-            let app_handle_tmux = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Hook server",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    start_hook_server(state_hook.clone(), app_handle_hook.clone(), shutdown)
+                },
+            );
 
-            // Start HTTP hook server in background
-            let shutdown_hook = shutdown_rx.clone();
-            tauri::async_runtime::spawn(async move {
-                start_hook_server(state_hook, app_handle_hook, shutdown_hook).await;
-            });
+            // Tmux scanner (fallback, lower frequency)
+            let state_tmux = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Tmux scanner",
+                shutdown_rx.clone(),
+                move |shutdown| tmux_scanner::start_tmux_scanner(state_tmux.clone(), shutdown),
+            );
+
+            // Push tmux pane changes to the scanner over control mode
+            let state_control = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "tmux control mode",
+                shutdown_rx.clone(),
+                move |shutdown| tmux_control::start_tmux_control(state_control.clone(), shutdown),
+            );
+
+            // Attach GitHub PR status to sessions on a slow cadence
+            let state_pr = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "PR watcher",
+                shutdown_rx.clone(),
+                move |shutdown| github::start_pr_watcher(state_pr.clone(), shutdown),
+            );
+
+            // Archive finished and stale sessions per the retention settings
+            let state_gc = state.clone();
+            let app_handle_gc = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Cleanup",
+                shutdown_rx.clone(),
+                move |shutdown| gc::start_gc(state_gc.clone(), app_handle_gc.clone(), shutdown),
+            );
+
+            // Alert when sessions stay working or waiting past their limits
+            let state_sla = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "SLA checker",
+                shutdown_rx.clone(),
+                move |shutdown| sla::start_sla_checker(state_sla.clone(), shutdown),
+            );
+
+            // Mark working sessions stalled while offline, rescan when back
+            let state_connectivity = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Connectivity monitor",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    connectivity::start_connectivity_monitor(state_connectivity.clone(), shutdown)
+                },
+            );
+
+            // Sample agents' CPU and memory, alerting on runaway sessions
+            let state_resources = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Resource monitor",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    resources::start_resource_monitor(state_resources.clone(), shutdown)
+                },
+            );
+
+            // Take approve/deny presses from the Telegram bot, when set up
+            let state_telegram = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Telegram bot",
+                shutdown_rx.clone(),
+                move |shutdown| telegram::start_telegram_bot(state_telegram.clone(), shutdown),
+            );
+
+            // Serve the API to other devices over TLS, when turned on
+            let state_lan = state.clone();
+            let app_handle_lan = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "LAN server",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    lan::start_lan_server(state_lan.clone(), app_handle_lan.clone(), shutdown)
+                },
+            );
+
+            // Email about sessions that have waited on the user too long
+            let state_email = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Email notifier",
+                shutdown_rx.clone(),
+                move |shutdown| email::start_email_notifier(state_email.clone(), shutdown),
+            );
+
+            // Summarize last week on Monday morning, when opted in
+            supervisor::spawn(
+                app.handle(),
+                "Weekly digest",
+                shutdown_rx.clone(),
+                digest::start_weekly_digest,
+            );
+
+            // Run the user's commands on state transitions
+            let state_automation = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Automation",
+                shutdown_rx.clone(),
+                move |shutdown| automation::start_automation(state_automation.clone(), shutdown),
+            );
+
+            // Log state transitions for time reports
+            let state_time_log = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Time log",
+                shutdown_rx.clone(),
+                move |shutdown| time_report::start_time_log(state_time_log.clone(), shutdown),
+            );
+
+            // Send session traces to an OpenTelemetry collector, when one is set
+            let state_otel = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "OTLP exporter",
+                shutdown_rx.clone(),
+                move |shutdown| otel::start_otel_exporter(state_otel.clone(), shutdown),
+            );
+
+            // Warn about Claude Code versions C3 is known to misread
+            let state_compat = state.clone();
+            let app_handle_compat = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Compatibility check",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    compat::start_compat_check(
+                        state_compat.clone(),
+                        app_handle_compat.clone(),
+                        shutdown,
+                    )
+                },
+            );
+
+            // Start scheduled tasks as they come due
+            let state_scheduler = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Scheduler",
+                shutdown_rx.clone(),
+                move |shutdown| scheduler::start_scheduler(state_scheduler.clone(), shutdown),
+            );
+
+            // Start queued launches as working sessions finish
+            let state_queue = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Launch queue",
+                shutdown_rx.clone(),
+                move |shutdown| launch_queue::start_launch_queue(state_queue.clone(), shutdown),
+            );
 
-            // Start tmux scanner in background (fallback, lower frequency)
-            let shutdown_tmux = shutdown_rx.clone();
+            // Reflect session state in tmux, when switched on
+            let state_tmux_status = state.clone();
+            supervisor::spawn(
+                app.handle(),
+                "Tmux status",
+                shutdown_rx.clone(),
+                move |shutdown| tmux_status::start_tmux_status(state_tmux_status.clone(), shutdown),
+            );
+
+            // Reload metadata synced from other machines
+            let app_handle_sync = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Meta sync",
+                shutdown_rx.clone(),
+                move |shutdown| meta_sync::start_meta_sync(app_handle_sync.clone(), shutdown),
+            );
+
+            // Look for new releases on the configured channel
+            let app_handle_updates = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Update checker",
+                shutdown_rx.clone(),
+                move |shutdown| updater::start_update_checker(app_handle_updates.clone(), shutdown),
+            );
+
+            // Global shortcut for the floating widget
+            if let Err(e) = plugins::floating_widget::set_shortcut(
+                app.handle(),
+                &load_settings().widget_shortcut,
+            ) {
+                log::warn!("Widget shortcut unavailable: {}", e);
+            }
+
+            // c3:// links from notifications, Slack and other tools
+            deep_link::register(app.handle(), state.clone());
+
+            // Rescan and re-check the hook server after the system wakes
+            let (power_tx, power_rx) = tokio::sync::mpsc::unbounded_channel();
+            plugins::sleep_wake::watch_power_events(move |event| {
+                let _ = power_tx.send(event);
+            });
+            let state_power = state.clone();
+            let shutdown_power = shutdown_rx.clone();
             tauri::async_runtime::spawn(async move {
-                tmux_scanner::start_tmux_scanner(state_tmux, app_handle_tmux, shutdown_tmux).await;
+                handle_power_events(state_power, power_rx, shutdown_power).await;
             });
 
+            // Flush coalesced session events to the frontend
+            let state_events = state.clone();
+            let app_handle_events = app.handle().clone();
+            supervisor::spawn(
+                app.handle(),
+                "Event flusher",
+                shutdown_rx.clone(),
+                move |shutdown| {
+                    events::start_event_flusher(
+                        state_events.clone(),
+                        app_handle_events.clone(),
+                        shutdown,
+                    )
+                },
+            );
+
             Ok(())
         })
         .build(tauri::generate_context!())
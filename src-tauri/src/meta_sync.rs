@@ -0,0 +1,408 @@
+use crate::session_identity::CLAUDE_PREFIX;
+use crate::{cmd, load_settings, paths, SessionGroup, SessionMeta, SessionMetaStore};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter};
+
+/// Each machine writes only its own `session-meta.<machine>.json` in the
+/// synced directory, so iCloud or Syncthing never has two writers for a file
+const REPLICA_PREFIX: &str = "session-meta.";
+const REPLICA_SUFFIX: &str = ".json";
+/// Names this machine's replica; kept in the machine-local state dir
+const MACHINE_ID_FILE: &str = "machine-id";
+/// Deletions are remembered this long, then forgotten
+const TOMBSTONE_DAYS: i64 = 90;
+/// How often other machines' replicas are checked for changes
+const WATCH_INTERVAL_SECS: u64 = 30;
+
+/// The synced directory from settings, cached so loading metadata doesn't
+/// re-read settings.json
+static SYNC_DIR: OnceLock<RwLock<Option<PathBuf>>> = OnceLock::new();
+/// Replica rewrites from concurrent saves must not interleave
+static REPLICA_LOCK: Mutex<()> = Mutex::new(());
+
+/// A value and when it was last changed; None records a deletion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Stamped<T> {
+    value: Option<T>,
+    updated_at: DateTime<Utc>,
+}
+
+/// One machine's changes. Only Claude sessions are synced: their IDs are
+/// conversation UUIDs, where tmux pane IDs mean a different pane elsewhere.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Replica {
+    #[serde(default)]
+    sessions: HashMap<String, Stamped<SessionMeta>>,
+    #[serde(default)]
+    groups: Option<Stamped<Vec<SessionGroup>>>,
+    #[serde(default)]
+    muted_tags: Option<Stamped<Vec<String>>>,
+}
+
+fn sync_dir_cell() -> &'static RwLock<Option<PathBuf>> {
+    SYNC_DIR.get_or_init(|| RwLock::new(dir_from(load_settings().meta_sync_dir.as_deref())))
+}
+
+fn dir_from(setting: Option<&str>) -> Option<PathBuf> {
+    setting
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+fn sync_dir() -> Option<PathBuf> {
+    sync_dir_cell().read().clone()
+}
+
+/// Apply the `meta_sync_dir` setting. True when it changed.
+pub(crate) fn configure(setting: Option<&str>) -> bool {
+    let dir = dir_from(setting);
+    let mut current = sync_dir_cell().write();
+    let changed = *current != dir;
+    *current = dir;
+    changed
+}
+
+fn is_synced(session_id: &str) -> bool {
+    session_id.starts_with(CLAUDE_PREFIX)
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+/// `<host>-<random>`: readable in the synced directory, unique even when two
+/// machines share a host name
+fn machine_id() -> String {
+    let path = paths::state_dir().join(MACHINE_ID_FILE);
+    if let Some(id) = fs::read_to_string(&path)
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+    {
+        return id;
+    }
+    let host = cmd("hostname")
+        .arg("-s")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .unwrap_or_default();
+    let host: String = host
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    let random = uuid::Uuid::new_v4().simple().to_string();
+    let id = format!(
+        "{}-{}",
+        if host.is_empty() { "mac" } else { &host },
+        &random[..8]
+    );
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(e) = fs::write(&path, &id) {
+        log::warn!("Failed to save machine id: {}", e);
+    }
+    id
+}
+
+fn replica_path(dir: &Path) -> PathBuf {
+    dir.join(format!(
+        "{}{}{}",
+        REPLICA_PREFIX,
+        machine_id(),
+        REPLICA_SUFFIX
+    ))
+}
+
+/// Replica files in the synced directory, sorted by name. Conflict copies
+/// made by the sync tool (`session-meta.x.sync-conflict-….json`,
+/// `session-meta.x 2.json`) are read as replicas too.
+fn replica_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| {
+                    name.starts_with(REPLICA_PREFIX) && name.ends_with(REPLICA_SUFFIX)
+                })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn read_replica(path: &Path) -> Option<Replica> {
+    let contents = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents)
+        .map_err(|e| log::warn!("Skipping synced metadata {}: {}", path.display(), e))
+        .ok()
+}
+
+fn newer<T: Clone>(current: &mut Option<Stamped<T>>, candidate: &Stamped<T>) {
+    if current
+        .as_ref()
+        .is_none_or(|current| candidate.updated_at >= current.updated_at)
+    {
+        *current = Some(candidate.clone());
+    }
+}
+
+/// The newest value of every key across replicas
+fn winners(replicas: &[Replica]) -> Replica {
+    let mut sessions: HashMap<String, Option<Stamped<SessionMeta>>> = HashMap::new();
+    let mut merged = Replica::default();
+    for replica in replicas {
+        for (id, stamped) in &replica.sessions {
+            newer(sessions.entry(id.clone()).or_default(), stamped);
+        }
+        if let Some(groups) = &replica.groups {
+            newer(&mut merged.groups, groups);
+        }
+        if let Some(tags) = &replica.muted_tags {
+            newer(&mut merged.muted_tags, tags);
+        }
+    }
+    merged.sessions = sessions
+        .into_iter()
+        .filter_map(|(id, stamped)| Some((id, stamped?)))
+        .collect();
+    merged
+}
+
+/// Overlay the synced values on the local store. Keys no machine has
+/// synced yet keep their local value.
+fn apply(store: &mut SessionMetaStore, winners: &Replica) {
+    for (id, stamped) in &winners.sessions {
+        if !is_synced(id) {
+            continue;
+        }
+        match &stamped.value {
+            Some(meta) => {
+                store.sessions.insert(id.clone(), meta.clone());
+            }
+            None => {
+                store.sessions.remove(id);
+            }
+        }
+    }
+    if let Some(groups) = &winners.groups {
+        store.groups = groups.value.clone().unwrap_or_default();
+    }
+    if let Some(tags) = &winners.muted_tags {
+        store.muted_tags = tags.value.clone().unwrap_or_default();
+    }
+}
+
+/// Record in `own` what `store` changes relative to the synced values.
+/// True when there was anything to record.
+fn record_changes(
+    store: &SessionMetaStore,
+    winners: &Replica,
+    own: &mut Replica,
+    now: DateTime<Utc>,
+) -> bool {
+    let mut changed = false;
+    fn stamp<T>(value: Option<T>, now: DateTime<Utc>) -> Stamped<T> {
+        Stamped {
+            value,
+            updated_at: now,
+        }
+    }
+    for (id, meta) in store.sessions.iter().filter(|(id, _)| is_synced(id)) {
+        let synced = winners.sessions.get(id).and_then(|s| s.value.as_ref());
+        if !synced.is_some_and(|synced| same(synced, meta)) {
+            own.sessions
+                .insert(id.clone(), stamp(Some(meta.clone()), now));
+            changed = true;
+        }
+    }
+    for (id, stamped) in &winners.sessions {
+        if stamped.value.is_some() && !store.sessions.contains_key(id) {
+            own.sessions.insert(id.clone(), stamp(None, now));
+            changed = true;
+        }
+    }
+    let synced_groups = winners.groups.as_ref().and_then(|s| s.value.clone());
+    if !same(&synced_groups.unwrap_or_default(), &store.groups) {
+        own.groups = Some(stamp(Some(store.groups.clone()), now));
+        changed = true;
+    }
+    let synced_tags = winners.muted_tags.as_ref().and_then(|s| s.value.clone());
+    if !same(&synced_tags.unwrap_or_default(), &store.muted_tags) {
+        own.muted_tags = Some(stamp(Some(store.muted_tags.clone()), now));
+        changed = true;
+    }
+    if changed {
+        let cutoff = now - Duration::days(TOMBSTONE_DAYS);
+        own.sessions
+            .retain(|_, s| s.value.is_some() || s.updated_at >= cutoff);
+    }
+    changed
+}
+
+/// Overlay what other machines synced on the local store
+pub(crate) fn merge(store: &mut SessionMetaStore) {
+    let Some(dir) = sync_dir() else {
+        return;
+    };
+    let replicas: Vec<Replica> = replica_files(&dir)
+        .iter()
+        .filter_map(|path| read_replica(path))
+        .collect();
+    apply(store, &winners(&replicas));
+}
+
+/// Write this machine's changes to the synced directory
+pub(crate) fn publish(store: &SessionMetaStore) -> Result<(), String> {
+    let Some(dir) = sync_dir() else {
+        return Ok(());
+    };
+    let _guard = REPLICA_LOCK.lock();
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let own_path = replica_path(&dir);
+    let replicas: Vec<Replica> = replica_files(&dir)
+        .iter()
+        .filter_map(|path| read_replica(path))
+        .collect();
+    let mut own = read_replica(&own_path).unwrap_or_default();
+    if !record_changes(store, &winners(&replicas), &mut own, Utc::now()) {
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(&own).map_err(|e| e.to_string())?;
+    // Not named like a replica, so other machines never read it half-written
+    let partial = own_path.with_extension("json.c3-partial");
+    fs::write(&partial, json).map_err(|e| format!("Failed to write synced metadata: {}", e))?;
+    fs::rename(&partial, &own_path).map_err(|e| format!("Failed to write synced metadata: {}", e))
+}
+
+/// Other machines' replicas and when they last changed
+fn fingerprint(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let own = replica_path(dir);
+    replica_files(dir)
+        .into_iter()
+        .filter(|path| *path != own)
+        .map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+type Snapshot = Option<(PathBuf, Vec<(PathBuf, Option<SystemTime>)>)>;
+
+async fn snapshot() -> Snapshot {
+    let dir = sync_dir()?;
+    tokio::task::spawn_blocking(move || {
+        let files = fingerprint(&dir);
+        (dir, files)
+    })
+    .await
+    .ok()
+}
+
+/// Tell the UI to reload session metadata when another machine's replica
+/// changes, or the synced directory does
+pub async fn start_meta_sync(
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut last = snapshot().await;
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(WATCH_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Metadata sync watcher shutting down");
+                break;
+            }
+        }
+        let current = snapshot().await;
+        if current != last {
+            log::info!("Synced session metadata changed");
+            let _ = app_handle.emit("session-meta-changed", ());
+            last = current;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "claude:0b5e8a34-9d0c-4a8e-bb2a-6d2f3c1e7a90";
+    const B: &str = "claude:7f1c2d3e-4b5a-4c6d-8e9f-0a1b2c3d4e5f";
+
+    fn tagged(tag: &str) -> SessionMeta {
+        SessionMeta {
+            tag: Some(tag.to_string()),
+            ..SessionMeta::default()
+        }
+    }
+
+    /// A machine: its store, and its replica after saving
+    fn save(store: &SessionMetaStore, replicas: &[Replica], minutes: i64) -> Replica {
+        let now = DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(minutes);
+        let mut own = Replica::default();
+        record_changes(store, &winners(replicas), &mut own, now);
+        own
+    }
+
+    #[test]
+    fn the_last_change_to_each_key_wins() {
+        // The laptop tags A and B; pane sessions stay local
+        let mut laptop = SessionMetaStore::default();
+        laptop.sessions.insert(A.to_string(), tagged("api"));
+        laptop.sessions.insert(B.to_string(), tagged("blog"));
+        laptop
+            .sessions
+            .insert("tmux:%3".to_string(), tagged("local"));
+        let laptop_replica = save(&laptop, &[], 1);
+        assert_eq!(laptop_replica.sessions.len(), 2);
+
+        // The desktop picks them up, then retags A and drops B
+        let mut desktop = SessionMetaStore::default();
+        apply(
+            &mut desktop,
+            &winners(std::slice::from_ref(&laptop_replica)),
+        );
+        assert_eq!(desktop.sessions[B].tag.as_deref(), Some("blog"));
+        desktop.sessions.insert(A.to_string(), tagged("backend"));
+        desktop.sessions.remove(B);
+        let desktop_replica = save(&desktop, std::slice::from_ref(&laptop_replica), 2);
+        // Unchanged keys aren't rewritten
+        assert_eq!(desktop_replica.sessions.len(), 2);
+        assert!(desktop_replica.groups.is_none());
+
+        // Meanwhile, before syncing, the laptop had renamed A's tag again
+        laptop.sessions.insert(A.to_string(), tagged("api-v2"));
+        let mut laptop_later = laptop_replica.clone();
+        laptop_later.sessions.insert(
+            A.to_string(),
+            Stamped {
+                value: Some(tagged("api-v2")),
+                updated_at: DateTime::<Utc>::UNIX_EPOCH + Duration::minutes(3),
+            },
+        );
+
+        let replicas = [desktop_replica, laptop_later];
+        apply(&mut laptop, &winners(&replicas));
+        assert_eq!(laptop.sessions[A].tag.as_deref(), Some("api-v2"));
+        assert!(!laptop.sessions.contains_key(B));
+        assert_eq!(laptop.sessions["tmux:%3"].tag.as_deref(), Some("local"));
+    }
+}
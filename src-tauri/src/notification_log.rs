@@ -0,0 +1,195 @@
+use crate::{event_log, AppState};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::Serialize;
+use specta::Type;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Decisions kept per session
+const MAX_DECISIONS: usize = 50;
+/// Sessions with decisions; the least recently notified are dropped beyond this
+const MAX_SESSIONS: usize = 200;
+
+/// Why a hook that warrants a notification didn't raise one, or might not
+/// have been shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Suppression {
+    /// A notification for the session was sent within the debounce window
+    Debounce,
+    /// Claude's Notification hook that follows a Stop
+    StopRecently,
+    /// The state machine ignored the hook (detail says why)
+    StateRejected,
+    /// Notifications are turned off in settings
+    Disabled,
+    /// The session's group or a tag is muted
+    Muted,
+    /// The machine was offline, so the agent most likely stopped on a
+    /// connection error
+    Offline,
+    /// Sent, but a macOS Focus (Do Not Disturb) was on and may have held it
+    DoNotDisturb,
+}
+
+/// What C3 did with one hook's notification
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationDecision {
+    pub timestamp: DateTime<Utc>,
+    pub hook_type: String,
+    pub sent: bool,
+    pub reason: Option<Suppression>,
+    pub detail: Option<String>,
+}
+
+/// Recent notification decisions per session, kept in memory
+#[derive(Default)]
+pub struct NotificationLog {
+    sessions: Mutex<HashMap<String, VecDeque<NotificationDecision>>>,
+}
+
+impl NotificationLog {
+    pub fn record(
+        &self,
+        session_id: &str,
+        hook_type: &str,
+        reason: Option<Suppression>,
+        detail: Option<String>,
+    ) {
+        let sent = matches!(reason, None | Some(Suppression::DoNotDisturb));
+        event_log::record(event_log::Event::Notification {
+            session_id: session_id.to_string(),
+            hook_type: hook_type.to_string(),
+            sent,
+            reason,
+            detail: detail.clone(),
+        });
+        let mut sessions = self.sessions.lock();
+        let decisions = sessions.entry(session_id.to_string()).or_default();
+        decisions.push_back(NotificationDecision {
+            timestamp: Utc::now(),
+            hook_type: hook_type.to_string(),
+            sent,
+            reason,
+            detail,
+        });
+        while decisions.len() > MAX_DECISIONS {
+            decisions.pop_front();
+        }
+
+        if sessions.len() > MAX_SESSIONS {
+            let oldest = sessions
+                .iter()
+                .min_by_key(|(_, decisions)| decisions.back().map(|d| d.timestamp))
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+            }
+        }
+    }
+
+    /// Keep a session's decisions when it moves to a new ID
+    pub fn rename(&self, from: &str, to: &str) {
+        let mut sessions = self.sessions.lock();
+        if let Some(decisions) = sessions.remove(from) {
+            sessions.insert(to.to_string(), decisions);
+        }
+    }
+
+    /// Decisions for one session, oldest first
+    pub fn for_session(&self, session_id: &str) -> Vec<NotificationDecision> {
+        self.sessions
+            .lock()
+            .get(session_id)
+            .map(|decisions| decisions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+fn assertions_path() -> PathBuf {
+    PathBuf::from(std::env::var("HOME").unwrap_or_default())
+        .join("Library/DoNotDisturb/DB/Assertions.json")
+}
+
+/// Whether a Focus assertion record is present in macOS's Do Not Disturb store
+fn focus_asserted(assertions: &serde_json::Value) -> bool {
+    assertions
+        .get("data")
+        .and_then(|data| data.as_array())
+        .is_some_and(|data| {
+            data.iter().any(|entry| {
+                entry
+                    .get("storeAssertionRecords")
+                    .and_then(|records| records.as_array())
+                    .is_some_and(|records| !records.is_empty())
+            })
+        })
+}
+
+/// Whether a Focus mode is switched on. Scheduled Focus modes and a store
+/// C3 can't read (no Full Disk Access) count as off.
+pub(crate) fn focus_active() -> bool {
+    std::fs::read_to_string(assertions_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .is_some_and(|assertions| focus_asserted(&assertions))
+}
+
+// Tauri command: Get recent notification decisions for a session, including suppressed ones
+#[tauri::command]
+#[specta::specta]
+pub fn get_notification_decisions(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Vec<NotificationDecision> {
+    state.notification_log.for_session(&session_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suppressed_and_sent_notifications_are_kept_per_session() {
+        let log = NotificationLog::default();
+        log.record("a", "Stop", None, None);
+        log.record("a", "Notification", Some(Suppression::StopRecently), None);
+        log.record(
+            "b",
+            "PermissionRequest",
+            Some(Suppression::DoNotDisturb),
+            None,
+        );
+        for _ in 0..MAX_DECISIONS {
+            log.record("c", "Stop", Some(Suppression::Debounce), None);
+        }
+        log.record("c", "Stop", Some(Suppression::Muted), None);
+
+        let a = log.for_session("a");
+        assert_eq!(a.len(), 2);
+        assert!(a[0].sent);
+        assert!(!a[1].sent);
+        assert_eq!(a[1].reason, Some(Suppression::StopRecently));
+        // Focus only may hide a notification that was sent
+        assert!(log.for_session("b")[0].sent);
+        let c = log.for_session("c");
+        assert_eq!(c.len(), MAX_DECISIONS);
+        assert_eq!(c.last().and_then(|d| d.reason), Some(Suppression::Muted));
+
+        log.rename("a", "claude-1");
+        assert!(log.for_session("a").is_empty());
+        assert_eq!(log.for_session("claude-1").len(), 2);
+
+        let focus = serde_json::json!({
+            "data": [{ "storeAssertionRecords": [{ "assertionDetails": {} }] }]
+        });
+        assert!(focus_asserted(&focus));
+        assert!(!focus_asserted(
+            &serde_json::json!({ "data": [{ "storeAssertionRecords": [] }] })
+        ));
+        assert!(!focus_asserted(&serde_json::json!({})));
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{cmd, hook_status, install_hooks, send_os_notification, AppState, HookStatus};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// Hook type the round-trip test sends; `process_hook` answers it without
+/// touching any session
+pub(crate) const TEST_HOOK_TYPE: &str = "C3OnboardingTest";
+/// How long the round trip may take, the script posting in the background
+const ROUND_TRIP_TIMEOUT: Duration = Duration::from_secs(5);
+/// Homebrew formulae the hooks and notifications need
+const DEPENDENCIES: &[&str] = &["jq", "tmux", "terminal-notifier"];
+
+/// Session ids of test hooks that reached the hook server
+static RECEIVED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// A step of the guided setup, run in this order
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// `brew install` whichever of jq, tmux and terminal-notifier is missing
+    Dependencies,
+    /// Install c3-hook.sh and register it with the agents, as Settings does
+    Hooks,
+    /// Run the installed script and wait for its payload to reach C3
+    HookRoundTrip,
+    /// Show a test notification
+    Notification,
+}
+
+/// A line of `brew install` output, emitted as `dependency-progress`
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DependencyProgress {
+    pub name: String,
+    /// None in the final event
+    pub line: Option<String>,
+    pub done: bool,
+    pub success: bool,
+}
+
+/// One thing a step checked or did
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OnboardingItem {
+    pub name: String,
+    pub ok: bool,
+    /// What went wrong, or what to do next
+    pub detail: Option<String>,
+}
+
+/// Where a step is; emitted as `onboarding-progress` after each item, and
+/// returned once `done`
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OnboardingProgress {
+    pub step: OnboardingStep,
+    pub items: Vec<OnboardingItem>,
+    pub done: bool,
+    pub success: bool,
+}
+
+/// Collects a step's items, telling the window about each
+struct Reporter<'a> {
+    app_handle: &'a AppHandle,
+    progress: OnboardingProgress,
+}
+
+impl<'a> Reporter<'a> {
+    fn new(app_handle: &'a AppHandle, step: OnboardingStep) -> Self {
+        Self {
+            app_handle,
+            progress: OnboardingProgress {
+                step,
+                items: Vec::new(),
+                done: false,
+                success: false,
+            },
+        }
+    }
+
+    fn item(&mut self, name: &str, ok: bool, detail: Option<String>) {
+        if !ok {
+            log::warn!(
+                "Onboarding {:?}: {} failed: {}",
+                self.progress.step,
+                name,
+                detail.as_deref().unwrap_or("")
+            );
+        }
+        self.progress.items.push(OnboardingItem {
+            name: name.to_string(),
+            ok,
+            detail,
+        });
+        let _ = self.app_handle.emit("onboarding-progress", &self.progress);
+    }
+
+    fn finish(mut self) -> OnboardingProgress {
+        self.progress.done = true;
+        self.progress.success = self.progress.items.iter().all(|item| item.ok);
+        let _ = self.app_handle.emit("onboarding-progress", &self.progress);
+        self.progress
+    }
+}
+
+fn installed(program: &str) -> bool {
+    cmd("which")
+        .arg(program)
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// The last line of a failed command's errors, or of its output
+fn failure(output: &std::process::Output) -> String {
+    let last = |bytes: &[u8]| {
+        String::from_utf8_lossy(bytes)
+            .lines()
+            .rfind(|line| !line.trim().is_empty())
+            .map(|line| line.trim().to_string())
+    };
+    last(&output.stderr)
+        .or_else(|| last(&output.stdout))
+        .unwrap_or_else(|| "no output".to_string())
+}
+
+fn emit_progress(app_handle: &AppHandle, name: &str, line: Option<String>, done: bool, ok: bool) {
+    let progress = DependencyProgress {
+        name: name.to_string(),
+        line,
+        done,
+        success: ok,
+    };
+    let _ = app_handle.emit("dependency-progress", &progress);
+}
+
+/// `brew install` a dependency, emitting its output line by line. Returns the
+/// last error line when it fails.
+fn brew_install(app_handle: &AppHandle, name: &str) -> Result<(), String> {
+    if !installed("brew") {
+        return Err("Homebrew is needed to install it; see https://brew.sh".to_string());
+    }
+    log::info!("Installing {} with Homebrew", name);
+    let mut child = cmd("brew")
+        .args(["install", name])
+        .env("HOMEBREW_NO_AUTO_UPDATE", "1")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run brew: {}", e))?;
+
+    let stderr = child.stderr.take();
+    let (app_handle_err, name_err) = (app_handle.clone(), name.to_string());
+    let errors = std::thread::spawn(move || {
+        let mut last = None;
+        for line in stderr
+            .into_iter()
+            .flat_map(|s| BufReader::new(s).lines().map_while(Result::ok))
+        {
+            if !line.trim().is_empty() {
+                last = Some(line.trim().to_string());
+            }
+            emit_progress(&app_handle_err, &name_err, Some(line), false, false);
+        }
+        last
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            emit_progress(app_handle, name, Some(line), false, false);
+        }
+    }
+    let status = child.wait().map_err(|e| e.to_string());
+    let last_error = errors.join().ok().flatten();
+    let result = match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(last_error.unwrap_or_else(|| format!("brew {}", status))),
+        Err(e) => Err(e),
+    };
+    emit_progress(app_handle, name, None, true, result.is_ok());
+    result
+}
+
+fn dependencies(reporter: &mut Reporter) {
+    let missing: Vec<&str> = DEPENDENCIES
+        .iter()
+        .copied()
+        .filter(|dep| !installed(dep))
+        .collect();
+    for dep in DEPENDENCIES.iter().filter(|dep| !missing.contains(dep)) {
+        reporter.item(dep, true, Some("Already installed".to_string()));
+    }
+    if missing.is_empty() {
+        return;
+    }
+    for dep in missing {
+        match brew_install(reporter.app_handle, dep) {
+            Ok(()) => reporter.item(dep, true, Some("Installed with Homebrew".to_string())),
+            Err(e) => reporter.item(dep, false, Some(e)),
+        }
+    }
+}
+
+fn hooks(reporter: &mut Reporter) {
+    let result = install_hooks(reporter.app_handle);
+    let detail = match &result.backup_path {
+        Some(backup) => format!("{} (previous settings saved to {})", result.message, backup),
+        None => result.message,
+    };
+    reporter.item("Install hooks", result.success, Some(detail));
+}
+
+/// Run the installed hook script the way an agent would, and wait for C3 to
+/// receive what it posts
+fn hook_round_trip(reporter: &mut Reporter, hook_port: u16) {
+    let home = std::env::var("HOME").unwrap_or_default();
+    let script = PathBuf::from(&home).join(".local/bin/c3-hook.sh");
+    if !script.exists() {
+        reporter.item(
+            "Hook script",
+            false,
+            Some("c3-hook.sh isn't installed; run the hooks step first".to_string()),
+        );
+        return;
+    }
+    reporter.item("Hook script", true, Some(script.display().to_string()));
+
+    let marker = uuid::Uuid::new_v4().to_string();
+    let started = Instant::now();
+    let child = cmd("/bin/bash")
+        .arg(&script)
+        .arg(TEST_HOOK_TYPE)
+        .env(
+            "C3_HOOK_URL",
+            format!("http://127.0.0.1:{}/hook", hook_port),
+        )
+        .env("C3_AGENT_KIND", "claude")
+        .current_dir(&home)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            reporter.item("Run hook script", false, Some(e.to_string()));
+            return;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let payload = serde_json::json!({ "session_id": marker }).to_string();
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    match child.wait_with_output() {
+        Ok(output) if output.status.success() => reporter.item("Run hook script", true, None),
+        Ok(output) => {
+            reporter.item("Run hook script", false, Some(failure(&output)));
+            return;
+        }
+        Err(e) => {
+            reporter.item("Run hook script", false, Some(e.to_string()));
+            return;
+        }
+    }
+
+    while started.elapsed() < ROUND_TRIP_TIMEOUT {
+        let mut received = RECEIVED.lock();
+        if let Some(i) = received.iter().position(|id| *id == marker) {
+            received.remove(i);
+            reporter.item(
+                "C3 received the hook",
+                true,
+                Some(format!("in {} ms", started.elapsed().as_millis())),
+            );
+            return;
+        }
+        drop(received);
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    reporter.item(
+        "C3 received the hook",
+        false,
+        Some(format!(
+            "Nothing arrived on port {} within {} s; check that jq and curl work in a terminal",
+            hook_port,
+            ROUND_TRIP_TIMEOUT.as_secs()
+        )),
+    );
+}
+
+fn notification(reporter: &mut Reporter, hook_port: u16) {
+    if !installed("terminal-notifier") {
+        reporter.item(
+            "terminal-notifier",
+            false,
+            Some("Not installed; run the dependencies step first".to_string()),
+        );
+        return;
+    }
+    send_os_notification(
+        "Sessions that need you will show up like this",
+        "C3",
+        "Test notification",
+        &None,
+        None,
+        hook_port,
+    );
+    reporter.item(
+        "Send test notification",
+        true,
+        Some(
+            "If it didn't appear, allow notifications for terminal-notifier in System Settings → Notifications"
+                .to_string(),
+        ),
+    );
+}
+
+/// Note a test hook reaching the hook server. Returns the response body.
+pub(crate) fn receive_test_hook(session_id: Option<&str>) -> String {
+    if let Some(id) = session_id {
+        let mut received = RECEIVED.lock();
+        received.push(id.to_string());
+        // Only the newest matter; a test that timed out never collects its own
+        let excess = received.len().saturating_sub(10);
+        received.drain(..excess);
+    }
+    "test:received".to_string()
+}
+
+// Tauri command: brew install jq, terminal-notifier or tmux, emitting dependency-progress; returns the hook status after
+#[tauri::command]
+#[specta::specta]
+pub async fn install_dependency(app_handle: AppHandle, name: String) -> Result<HookStatus, String> {
+    if !DEPENDENCIES.contains(&name.as_str()) {
+        return Err(format!("Not a dependency C3 installs: {}", name));
+    }
+    tokio::task::spawn_blocking(move || {
+        brew_install(&app_handle, &name)?;
+        Ok(hook_status(&app_handle))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Run one step of the guided setup, emitting onboarding-progress as it goes
+#[tauri::command]
+#[specta::specta]
+pub async fn run_onboarding_step(
+    app_handle: AppHandle,
+    state: tauri::State<'_, Arc<AppState>>,
+    step: OnboardingStep,
+) -> Result<OnboardingProgress, String> {
+    let hook_port = state.hook_port;
+    tokio::task::spawn_blocking(move || {
+        let mut reporter = Reporter::new(&app_handle, step);
+        match step {
+            OnboardingStep::Dependencies => dependencies(&mut reporter),
+            OnboardingStep::Hooks => hooks(&mut reporter),
+            OnboardingStep::HookRoundTrip => hook_round_trip(&mut reporter, hook_port),
+            OnboardingStep::Notification => notification(&mut reporter, hook_port),
+        }
+        reporter.finish()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn failures_report_the_last_line_of_output() {
+        let output = std::process::Output {
+            status: std::process::ExitStatus::from_raw(256),
+            stdout: b"==> Fetching tmux\n".to_vec(),
+            stderr: b"Error: No such keg\nError: tmux: network unreachable\n\n".to_vec(),
+        };
+        assert_eq!(failure(&output), "Error: tmux: network unreachable");
+        let quiet = std::process::Output {
+            stderr: Vec::new(),
+            ..output
+        };
+        assert_eq!(failure(&quiet), "==> Fetching tmux");
+    }
+}
@@ -0,0 +1,531 @@
+use crate::state_machine::TimelineEntry;
+use crate::transcript::{self, MessagePart, TranscriptMessage};
+use crate::{cmd, load_settings, AppState, C3Session, SessionState};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// How often new transitions are turned into spans and sent
+const EXPORT_INTERVAL_SECS: u64 = 10;
+/// The collector is given up on after this, and the batch dropped
+const SEND_TIMEOUT_SECS: &str = "10";
+const SERVICE_NAME: &str = "c3";
+/// Name of the span covering a session's whole life
+const SESSION_SPAN: &str = "Session";
+
+/// A finished span, ready to export
+#[derive(Debug, Clone)]
+struct Span {
+    session_id: String,
+    trace_id: String,
+    span_id: String,
+    parent_span_id: Option<String>,
+    name: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    attributes: Vec<(&'static str, String)>,
+    error: bool,
+}
+
+/// A state span not yet ended
+struct OpenState {
+    state: SessionState,
+    span_id: String,
+    start: DateTime<Utc>,
+}
+
+/// The trace of one session, from the first transition C3 saw
+struct SessionTrace {
+    session_id: String,
+    trace_id: String,
+    root_span_id: String,
+    start: DateTime<Utc>,
+    last: DateTime<Utc>,
+    attributes: Vec<(&'static str, String)>,
+    open: Option<OpenState>,
+}
+
+fn new_trace_id() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+/// States that get a span of their own
+fn traced(state: &SessionState) -> bool {
+    matches!(
+        state,
+        SessionState::Processing | SessionState::AwaitingPermission
+    )
+}
+
+fn session_attributes(session: &C3Session) -> Vec<(&'static str, String)> {
+    let mut attributes = vec![
+        ("c3.session.id", session.id.clone()),
+        ("c3.project.name", session.project_name.clone()),
+    ];
+    if let Some(path) = &session.project_path {
+        attributes.push(("c3.project.path", path.clone()));
+    }
+    if let Some(kind) = &session.agent_kind {
+        attributes.push(("c3.agent.kind", kind.clone()));
+    }
+    attributes
+}
+
+impl SessionTrace {
+    fn span(
+        &self,
+        span_id: String,
+        parent_span_id: Option<String>,
+        name: String,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Span {
+        Span {
+            session_id: self.session_id.clone(),
+            trace_id: self.trace_id.clone(),
+            span_id,
+            parent_span_id,
+            name,
+            start,
+            end,
+            attributes: self.attributes.clone(),
+            error: false,
+        }
+    }
+
+    fn state_span(&self, open: OpenState, end: DateTime<Utc>) -> Span {
+        self.span(
+            open.span_id,
+            Some(self.root_span_id.clone()),
+            format!("{:?}", open.state),
+            open.start,
+            end,
+        )
+    }
+
+    /// The open state span, ended now, and the session span
+    fn finish(mut self, now: DateTime<Utc>) -> Vec<Span> {
+        let mut spans: Vec<Span> = self
+            .open
+            .take()
+            .map(|open| self.state_span(open, now))
+            .into_iter()
+            .collect();
+        let end = if spans.is_empty() { self.last } else { now };
+        spans.push(self.span(
+            self.root_span_id.clone(),
+            None,
+            SESSION_SPAN.to_string(),
+            self.start,
+            end,
+        ));
+        spans
+    }
+}
+
+/// Sessions being traced
+#[derive(Default)]
+struct Tracer {
+    sessions: HashMap<String, SessionTrace>,
+    /// Whether the last send failed, so a collector that's down is logged once
+    failing: bool,
+}
+
+impl Tracer {
+    /// Apply a transition. Returns the state span it ends, if any.
+    fn transition(&mut self, session: &C3Session, entry: &TimelineEntry) -> Option<Span> {
+        let trace = self
+            .sessions
+            .entry(session.id.clone())
+            .or_insert_with(|| SessionTrace {
+                session_id: session.id.clone(),
+                trace_id: new_trace_id(),
+                root_span_id: new_span_id(),
+                start: entry.timestamp,
+                last: entry.timestamp,
+                attributes: session_attributes(session),
+                open: None,
+            });
+        trace.last = entry.timestamp;
+        let ended = trace
+            .open
+            .take()
+            .map(|open| trace.state_span(open, entry.timestamp));
+        if traced(&entry.to) {
+            trace.open = Some(OpenState {
+                state: entry.to.clone(),
+                span_id: new_span_id(),
+                start: entry.timestamp,
+            });
+        }
+        ended
+    }
+
+    /// End the traces of sessions no longer in `live`
+    fn finish(&mut self, live: &HashSet<String>, now: DateTime<Utc>) -> Vec<Span> {
+        let gone: Vec<String> = self
+            .sessions
+            .keys()
+            .filter(|id| !live.contains(*id))
+            .cloned()
+            .collect();
+        gone.into_iter()
+            .filter_map(|id| self.sessions.remove(&id))
+            .flat_map(|trace| trace.finish(now))
+            .collect()
+    }
+}
+
+/// Tool calls whose results arrived during `state_span`, as its siblings.
+/// Calls and results are paired in order, as agents run them.
+fn tool_spans(messages: &[TranscriptMessage], state_span: &Span) -> Vec<Span> {
+    let mut pending: VecDeque<(String, DateTime<Utc>)> = VecDeque::new();
+    let mut spans = Vec::new();
+    for message in messages {
+        let Some(at) = message.timestamp else {
+            continue;
+        };
+        if at > state_span.end {
+            break;
+        }
+        for part in &message.parts {
+            match part {
+                MessagePart::ToolCall { name, .. } => pending.push_back((name.clone(), at)),
+                MessagePart::ToolResult { is_error, .. } => {
+                    let Some((name, start)) = pending.pop_front() else {
+                        continue;
+                    };
+                    if at <= state_span.start {
+                        continue;
+                    }
+                    let mut attributes = state_span.attributes.clone();
+                    attributes.push(("c3.tool.name", name.clone()));
+                    spans.push(Span {
+                        span_id: new_span_id(),
+                        name,
+                        start,
+                        end: at,
+                        attributes,
+                        error: *is_error,
+                        ..state_span.clone()
+                    });
+                }
+                MessagePart::Text(_) => {}
+            }
+        }
+    }
+    spans
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+/// An OTLP/HTTP JSON `ExportTraceServiceRequest`
+fn export_request(spans: &[Span]) -> Value {
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|span| {
+            let attributes: Vec<Value> = span
+                .attributes
+                .iter()
+                .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+                .collect();
+            let mut otlp = json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "name": span.name,
+                // SPAN_KIND_INTERNAL
+                "kind": 1,
+                "startTimeUnixNano": unix_nanos(span.start),
+                "endTimeUnixNano": unix_nanos(span.end),
+                "attributes": attributes,
+            });
+            if let Some(parent) = &span.parent_span_id {
+                otlp["parentSpanId"] = json!(parent);
+            }
+            if span.error {
+                // STATUS_CODE_ERROR
+                otlp["status"] = json!({ "code": 2 });
+            }
+            otlp
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{ "key": "service.name", "value": { "stringValue": SERVICE_NAME } }]
+            },
+            "scopeSpans": [{ "scope": { "name": SERVICE_NAME }, "spans": spans }]
+        }]
+    })
+}
+
+/// The traces URL of a collector given as `http://localhost:4318` or in full
+fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{}/v1/traces", endpoint)
+    }
+}
+
+fn send(endpoint: &str, request: &Value) -> Result<(), String> {
+    let mut child = cmd("curl")
+        .args(["-fsS", "--max-time", SEND_TIMEOUT_SECS, "-X", "POST"])
+        .args([
+            "-H",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+        ])
+        .arg(traces_url(endpoint))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(request.to_string().as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+fn find_session(state: &AppState, session_id: &str) -> Option<C3Session> {
+    state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .or_else(|| state.archive.session(session_id))
+}
+
+/// Spans for transitions recorded after `after`, and for sessions that are
+/// gone, sent to the collector. Returns the newest transition seen.
+fn export(
+    state: &AppState,
+    tracer: &mut Tracer,
+    after: DateTime<Utc>,
+    endpoint: &str,
+    stopping: bool,
+) -> DateTime<Utc> {
+    let entries = state.timeline.since(after);
+    let newest = entries.last().map_or(after, |(_, entry)| entry.timestamp);
+    let mut spans = Vec::new();
+    for (session_id, entry) in entries {
+        if let Some(session) = find_session(state, &session_id) {
+            spans.extend(tracer.transition(&session, &entry));
+        }
+    }
+    // Everything still open is ended when C3 quits
+    let live: HashSet<String> = if stopping {
+        HashSet::new()
+    } else {
+        state.sessions.read().keys().cloned().collect()
+    };
+    spans.extend(tracer.finish(&live, Utc::now()));
+
+    let mut transcripts: HashMap<String, Vec<TranscriptMessage>> = HashMap::new();
+    let mut tools = Vec::new();
+    for span in spans.iter().filter(|span| span.parent_span_id.is_some()) {
+        if !transcripts.contains_key(&span.session_id) {
+            let messages = find_session(state, &span.session_id)
+                .and_then(|session| transcript::session_transcript(&session))
+                .and_then(|(kind, path)| transcript::read_transcript(&kind, &path).ok())
+                .unwrap_or_default();
+            transcripts.insert(span.session_id.clone(), messages);
+        }
+        tools.extend(tool_spans(&transcripts[&span.session_id], span));
+    }
+    spans.extend(tools);
+    if spans.is_empty() {
+        return newest;
+    }
+
+    match send(endpoint, &export_request(&spans)) {
+        Ok(()) => {
+            if tracer.failing {
+                log::info!("OTLP collector at {} is reachable again", endpoint);
+            }
+            tracer.failing = false;
+        }
+        Err(e) => {
+            if !tracer.failing {
+                log::warn!("Failed to send spans to {}: {}", endpoint, e);
+            }
+            tracer.failing = true;
+        }
+    }
+    newest
+}
+
+/// Send a trace per session to the OTLP collector in settings, with spans
+/// for the time spent working and waiting on permission and for each tool call
+pub async fn start_otel_exporter(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut tracer = Tracer::default();
+    let mut after = Utc::now();
+    loop {
+        let stopping = tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(EXPORT_INTERVAL_SECS)) => false,
+            _ = shutdown.changed() => true,
+        };
+        let endpoint = load_settings()
+            .otlp_endpoint
+            .filter(|endpoint| !endpoint.trim().is_empty());
+        match endpoint {
+            Some(endpoint) => {
+                let state_export = state.clone();
+                (tracer, after) = tokio::task::spawn_blocking(move || {
+                    let newest = export(&state_export, &mut tracer, after, &endpoint, stopping);
+                    (tracer, newest)
+                })
+                .await
+                .unwrap_or_else(|_| (Tracer::default(), Utc::now()));
+            }
+            // Traces start over from the next transition once it's set
+            None => {
+                tracer = Tracer::default();
+                after = Utc::now();
+            }
+        }
+        if stopping {
+            log::info!("OTLP exporter shutting down");
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_machine::StateSource;
+    use crate::transcript::Role;
+    use chrono::TimeZone;
+
+    fn at(minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 9, minute, second).unwrap()
+    }
+
+    fn session() -> C3Session {
+        C3Session {
+            project_name: "api-server".to_string(),
+            project_path: Some("/src/api-server".to_string()),
+            ..C3Session::test("tmux:%4")
+        }
+    }
+
+    fn entry(to: SessionState, timestamp: DateTime<Utc>) -> TimelineEntry {
+        TimelineEntry {
+            timestamp,
+            from: None,
+            to,
+            source: StateSource::Hook,
+        }
+    }
+
+    fn message(role: Role, minute: u32, second: u32, parts: Vec<MessagePart>) -> TranscriptMessage {
+        TranscriptMessage {
+            role,
+            timestamp: Some(at(minute, second)),
+            parts,
+        }
+    }
+
+    #[test]
+    fn a_session_is_one_trace_with_state_and_tool_spans() {
+        let session = session();
+        let mut tracer = Tracer::default();
+        assert!(tracer
+            .transition(&session, &entry(SessionState::Processing, at(0, 0)))
+            .is_none());
+        let working = tracer
+            .transition(&session, &entry(SessionState::AwaitingPermission, at(1, 0)))
+            .unwrap();
+        let waiting = tracer
+            .transition(&session, &entry(SessionState::AwaitingInput, at(2, 0)))
+            .unwrap();
+        assert!(tracer
+            .transition(&session, &entry(SessionState::Complete, at(3, 0)))
+            .is_none());
+        assert_eq!(working.name, "Processing");
+        assert_eq!((working.start, working.end), (at(0, 0), at(1, 0)));
+        assert_eq!(waiting.name, "AwaitingPermission");
+        assert_eq!(waiting.trace_id, working.trace_id);
+        assert_eq!(waiting.parent_span_id, working.parent_span_id);
+
+        let call = |name: &str| MessagePart::ToolCall {
+            name: name.to_string(),
+            input: Value::Null,
+        };
+        let result = |is_error| MessagePart::ToolResult {
+            output: String::new(),
+            is_error,
+        };
+        let messages = [
+            message(Role::Assistant, 0, 10, vec![call("Read"), call("Grep")]),
+            message(Role::User, 0, 12, vec![result(false), result(true)]),
+            // Run once permission was given, so it ends in a later span
+            message(Role::Assistant, 0, 50, vec![call("Bash")]),
+            message(Role::User, 1, 30, vec![result(false)]),
+        ];
+        let tools = tool_spans(&messages, &working);
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[0].name, "Read");
+        assert_eq!((tools[1].start, tools[1].end), (at(0, 10), at(0, 12)));
+        assert!(tools[1].error);
+        assert_eq!(tools[1].parent_span_id, working.parent_span_id);
+        let bash = tool_spans(&messages, &waiting);
+        assert_eq!(bash[0].name, "Bash");
+        assert_eq!(bash[0].start, at(0, 50));
+
+        // Nothing is sent for a session until it's gone
+        assert!(tracer
+            .finish(&HashSet::from([session.id.clone()]), at(4, 0))
+            .is_empty());
+        let ended = tracer.finish(&HashSet::new(), at(4, 0));
+        assert_eq!(ended.len(), 1);
+        assert_eq!(ended[0].name, SESSION_SPAN);
+        assert_eq!(ended[0].parent_span_id, None);
+        assert_eq!(working.parent_span_id.as_ref(), Some(&ended[0].span_id));
+        assert_eq!((ended[0].start, ended[0].end), (at(0, 0), at(3, 0)));
+        assert!(tracer.sessions.is_empty());
+
+        let request = export_request(&[working, tools[1].clone()]);
+        let spans = &request["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(spans[0]["traceId"].as_str().map(str::len), Some(32));
+        assert_eq!(spans[0]["spanId"].as_str().map(str::len), Some(16));
+        assert_eq!(spans[0]["startTimeUnixNano"], "1772355600000000000");
+        assert_eq!(
+            spans[0]["attributes"][1]["value"]["stringValue"],
+            "api-server"
+        );
+        assert_eq!(spans[1]["status"]["code"], 2);
+
+        assert_eq!(
+            traces_url("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("https://otel.internal/v1/traces"),
+            "https://otel.internal/v1/traces"
+        );
+    }
+}
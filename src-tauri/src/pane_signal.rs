@@ -0,0 +1,159 @@
+use crate::jsonl::ConversationState;
+use crate::state_machine::{self, Signal};
+use crate::{cmd, PendingAction, SessionState};
+
+/// Lines from the bottom of the pane that are searched
+const CAPTURE_LINES: usize = 40;
+/// How sure the title and transcript are. Lower than what's on screen: an
+/// ambiguous transcript is resolved by guessing from its file age.
+const TRANSCRIPT_CONFIDENCE: u8 = 60;
+/// Claude's "Do you want to ...?" box with numbered choices
+const PERMISSION_BOX_CONFIDENCE: u8 = 90;
+/// The "esc to interrupt" footer shown while Claude works
+const INTERRUPT_FOOTER_CONFIDENCE: u8 = 85;
+/// An empty input prompt; shown under the footer while working, so weakest
+const INPUT_PROMPT_CONFIDENCE: u8 = 70;
+
+/// Visible text of a pane's last screenful
+pub(crate) fn capture(target: &str) -> Option<String> {
+    let output = cmd("tmux")
+        .args(["capture-pane", "-p", "-t", target])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// What a Claude pane's content shows, strongest evidence first
+fn classify(capture: &str) -> Option<Signal> {
+    let mut lines: Vec<&str> = capture.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let lines = &lines[lines.len().saturating_sub(CAPTURE_LINES)..];
+    let signal = |state, confidence| Some(Signal { state, confidence });
+
+    let asks = lines.iter().any(|line| line.contains("Do you want to"));
+    let choices = lines
+        .iter()
+        .any(|line| line.contains("1. Yes") && line.contains('❯'));
+    if asks && choices {
+        return signal(SessionState::AwaitingPermission, PERMISSION_BOX_CONFIDENCE);
+    }
+    if lines
+        .iter()
+        .any(|line| line.to_lowercase().contains("esc to interrupt"))
+    {
+        return signal(SessionState::Processing, INTERRUPT_FOOTER_CONFIDENCE);
+    }
+    let prompt = lines.iter().any(|line| {
+        let inner = line.trim_matches(|c: char| c == '│' || c.is_whitespace());
+        inner == ">" || inner == "❯" || inner.starts_with("> ") || inner.starts_with("❯ ")
+    });
+    if prompt || lines.iter().any(|line| line.contains("? for shortcuts")) {
+        return signal(SessionState::AwaitingInput, INPUT_PROMPT_CONFIDENCE);
+    }
+    None
+}
+
+/// Weigh what the pane shows against the title and transcript's state
+pub(crate) fn reconcile(conv_state: ConversationState, capture: &str) -> ConversationState {
+    let Some(pane) = classify(capture) else {
+        return conv_state;
+    };
+    let transcript = Signal {
+        state: conv_state.state.clone(),
+        confidence: TRANSCRIPT_CONFIDENCE,
+    };
+    let strongest = state_machine::strongest([transcript, pane]);
+    if strongest.state == conv_state.state {
+        return conv_state;
+    }
+    let pending_action = match strongest.state {
+        SessionState::AwaitingPermission => Some(PendingAction {
+            action_type: "permission".to_string(),
+            description: "Permission prompt on screen".to_string(),
+            tool: None,
+            command: None,
+            options: Vec::new(),
+        }),
+        SessionState::AwaitingInput => Some(PendingAction {
+            action_type: "input".to_string(),
+            description: "Waiting for user input".to_string(),
+            tool: None,
+            command: None,
+            options: Vec::new(),
+        }),
+        _ => None,
+    };
+    ConversationState {
+        state: strongest.state,
+        pending_action,
+        last_message_time: conv_state.last_message_time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jsonl::awaiting_input_state;
+
+    const PERMISSION: &str = "\
+● Bash(rm -rf target)
+╭──────────────────────────────────────────╮
+│ Bash command                             │
+│   rm -rf target                          │
+│ Do you want to proceed?                  │
+│ ❯ 1. Yes                                 │
+│   2. No, and tell Claude what to do      │
+╰──────────────────────────────────────────╯
+
+
+";
+
+    const WORKING: &str = "\
+✻ Compiling… (12s · esc to interrupt)
+╭──────────────────────────────────────────╮
+│ >                                        │
+╰──────────────────────────────────────────╯
+  ? for shortcuts
+";
+
+    const IDLE: &str = "\
+● Done.
+╭──────────────────────────────────────────╮
+│ >                                        │
+╰──────────────────────────────────────────╯
+  ? for shortcuts
+";
+
+    #[test]
+    fn on_screen_prompts_outweigh_the_transcript_guess() {
+        assert_eq!(
+            classify(PERMISSION).map(|s| s.state),
+            Some(SessionState::AwaitingPermission)
+        );
+        assert_eq!(
+            classify(WORKING).map(|s| s.state),
+            Some(SessionState::Processing)
+        );
+        assert_eq!(
+            classify(IDLE).map(|s| s.state),
+            Some(SessionState::AwaitingInput)
+        );
+        assert_eq!(classify("$ cargo build\n"), None);
+
+        // The transcript's file-age guess said input; the pane shows a permission box
+        let reconciled = reconcile(awaiting_input_state(None), PERMISSION);
+        assert_eq!(reconciled.state, SessionState::AwaitingPermission);
+        assert_eq!(
+            reconciled.pending_action.map(|a| a.action_type).as_deref(),
+            Some("permission")
+        );
+        // Nothing recognizable leaves the transcript's state alone
+        let kept = reconcile(awaiting_input_state(None), "");
+        assert_eq!(kept.state, SessionState::AwaitingInput);
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Overrides both the config and state directories, e.g. for an isolated test instance
+const CONFIG_DIR_OVERRIDE: &str = "C3_CONFIG_DIR";
+/// Files copied from `~/.config/c3` the first time a different config dir is used
+const MIGRATED_FILES: &[&str] = &["settings.json", "session-meta.json", "icon.png"];
+/// Written to the new config dir once migration has run
+const MIGRATION_MARKER: &str = ".migrated";
+
+fn env_path(env: &dyn Fn(&str) -> Option<String>, key: &str) -> Option<PathBuf> {
+    // The XDG spec says relative paths are invalid and must be ignored
+    env(key)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+}
+
+fn resolve_config_dir(env: &dyn Fn(&str) -> Option<String>) -> PathBuf {
+    if let Some(dir) = env_path(env, CONFIG_DIR_OVERRIDE) {
+        return dir;
+    }
+    if let Some(dir) = env_path(env, "XDG_CONFIG_HOME") {
+        return dir.join("c3");
+    }
+    match env_path(env, "HOME") {
+        Some(home) => home.join(".config").join("c3"),
+        None => std::env::temp_dir().join("c3"),
+    }
+}
+
+fn resolve_state_dir(env: &dyn Fn(&str) -> Option<String>) -> PathBuf {
+    if let Some(dir) = env_path(env, CONFIG_DIR_OVERRIDE) {
+        return dir.join("state");
+    }
+    if let Some(dir) = env_path(env, "XDG_STATE_HOME") {
+        return dir.join("c3");
+    }
+    match env_path(env, "HOME") {
+        Some(home) => home.join(".local").join("state").join("c3"),
+        None => std::env::temp_dir().join("c3").join("state"),
+    }
+}
+
+fn process_env(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Settings, session metadata and the notification icon:
+/// `$C3_CONFIG_DIR`, else `$XDG_CONFIG_HOME/c3`, else `~/.config/c3`
+pub fn config_dir() -> PathBuf {
+    resolve_config_dir(&process_env)
+}
+
+/// Logs and other machine-local state:
+/// `$C3_CONFIG_DIR/state`, else `$XDG_STATE_HOME/c3`, else `~/.local/state/c3`
+pub fn state_dir() -> PathBuf {
+    resolve_state_dir(&process_env)
+}
+
+/// The config and state directories as resolved without `$C3_CONFIG_DIR`.
+/// They're named `c3`, so everything in them is C3's.
+pub fn default_dirs() -> (PathBuf, PathBuf) {
+    let env = |key: &str| {
+        if key == CONFIG_DIR_OVERRIDE {
+            None
+        } else {
+            process_env(key)
+        }
+    };
+    (resolve_config_dir(&env), resolve_state_dir(&env))
+}
+
+/// Copy files from the pre-XDG location (`~/.config/c3`) when `$XDG_CONFIG_HOME`
+/// points somewhere else. Runs once; existing files in the new dir are kept.
+/// Skipped for `$C3_CONFIG_DIR` so isolated instances start clean.
+pub fn migrate_legacy_config() {
+    if process_env(CONFIG_DIR_OVERRIDE).is_some() {
+        return;
+    }
+    let Some(home) = process_env("HOME").map(PathBuf::from) else {
+        return;
+    };
+    let legacy = home.join(".config").join("c3");
+    let current = config_dir();
+    if current == legacy || !legacy.is_dir() || current.join(MIGRATION_MARKER).exists() {
+        return;
+    }
+
+    if let Err(e) = fs::create_dir_all(&current) {
+        log::warn!("Failed to create {}: {}", current.display(), e);
+        return;
+    }
+    for name in MIGRATED_FILES {
+        let from = legacy.join(name);
+        let to = current.join(name);
+        if !from.exists() || to.exists() {
+            continue;
+        }
+        match fs::copy(&from, &to) {
+            Ok(_) => log::info!("Migrated {} to {}", from.display(), to.display()),
+            Err(e) => log::warn!("Failed to migrate {}: {}", from.display(), e),
+        }
+    }
+    let _ = fs::write(
+        current.join(MIGRATION_MARKER),
+        legacy.to_string_lossy().as_bytes(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn defaults_to_home_dirs() {
+        let env = env(&[("HOME", "/home/ann")]);
+        assert_eq!(
+            resolve_config_dir(&env),
+            PathBuf::from("/home/ann/.config/c3")
+        );
+        assert_eq!(
+            resolve_state_dir(&env),
+            PathBuf::from("/home/ann/.local/state/c3")
+        );
+    }
+
+    #[test]
+    fn xdg_dirs_win_over_home_but_not_override() {
+        let env_xdg = env(&[
+            ("HOME", "/home/ann"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("XDG_STATE_HOME", "/xdg/state"),
+        ]);
+        assert_eq!(
+            resolve_config_dir(&env_xdg),
+            PathBuf::from("/xdg/config/c3")
+        );
+        assert_eq!(resolve_state_dir(&env_xdg), PathBuf::from("/xdg/state/c3"));
+
+        let env_override = env(&[
+            ("HOME", "/home/ann"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("C3_CONFIG_DIR", "/tmp/c3-test"),
+        ]);
+        assert_eq!(
+            resolve_config_dir(&env_override),
+            PathBuf::from("/tmp/c3-test")
+        );
+        assert_eq!(
+            resolve_state_dir(&env_override),
+            PathBuf::from("/tmp/c3-test/state")
+        );
+    }
+
+    #[test]
+    fn relative_xdg_paths_are_ignored() {
+        let env = env(&[
+            ("HOME", "/home/ann"),
+            ("XDG_CONFIG_HOME", "relative/config"),
+        ]);
+        assert_eq!(
+            resolve_config_dir(&env),
+            PathBuf::from("/home/ann/.config/c3")
+        );
+    }
+}
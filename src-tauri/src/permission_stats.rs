@@ -0,0 +1,266 @@
+use crate::paths;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// One line per permission request, in the state dir
+const LOG_FILE: &str = "permission-requests.jsonl";
+/// Requests older than this are dropped when the log is read
+const RETENTION_DAYS: i64 = 90;
+/// Period reported when the caller doesn't give one
+const DEFAULT_DAYS: u32 = 30;
+/// Command patterns listed per tool
+const TOP_COMMANDS: usize = 5;
+
+/// Appends from hooks and the pruning rewrite must not interleave
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// A permission request as logged. Only the command's pattern is kept, never
+/// the command itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PermissionRequest {
+    at: DateTime<Utc>,
+    agent_kind: String,
+    tool: String,
+    #[serde(default)]
+    pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandCount {
+    pub pattern: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolStats {
+    pub tool: String,
+    pub count: u32,
+    /// Share of all requests, rounded to a whole percent
+    pub percent: u32,
+    /// Most requested command patterns, for tools that run commands
+    pub top_commands: Vec<CommandCount>,
+}
+
+/// Which tools asked for permission most often, most frequent first
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionStats {
+    pub since: DateTime<Utc>,
+    pub total: u32,
+    pub tools: Vec<ToolStats>,
+}
+
+fn log_path() -> PathBuf {
+    paths::state_dir().join(LOG_FILE)
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    word.split_once('=').is_some_and(|(name, _)| {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    })
+}
+
+fn is_subcommand(word: &str) -> bool {
+    word.starts_with(|c: char| c.is_ascii_alphabetic())
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+/// The program and its subcommand, e.g. `npm test` for `CI=1 npm test -- -u`,
+/// the granularity allowlist entries like `Bash(npm test:*)` work at
+pub(crate) fn command_pattern(command: &str) -> Option<String> {
+    let mut words = command
+        .split_whitespace()
+        .skip_while(|word| is_env_assignment(word));
+    let program = words.next()?;
+    Some(match words.next() {
+        Some(sub) if is_subcommand(sub) => format!("{} {}", program, sub),
+        _ => program.to_string(),
+    })
+}
+
+/// When a hook fired: spooled hooks carry the time they were queued
+pub(crate) fn hook_time(queued_at: Option<i64>) -> DateTime<Utc> {
+    queued_at
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .unwrap_or_else(Utc::now)
+}
+
+/// Log a permission request. `command` should already be redacted.
+pub(crate) fn record(
+    agent_kind: &str,
+    tool: Option<&str>,
+    command: Option<&str>,
+    at: DateTime<Utc>,
+) {
+    let request = PermissionRequest {
+        at,
+        agent_kind: agent_kind.to_string(),
+        tool: tool.unwrap_or("unknown").to_string(),
+        pattern: command.and_then(command_pattern),
+    };
+    let Ok(line) = serde_json::to_string(&request) else {
+        return;
+    };
+    let path = log_path();
+    let _guard = LOG_LOCK.lock();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let appended = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = appended {
+        log::warn!("Failed to log permission request: {}", e);
+    }
+}
+
+/// Requests within the retention period, rewriting the log without the rest
+fn load(path: &Path, now: DateTime<Utc>) -> Vec<PermissionRequest> {
+    let _guard = LOG_LOCK.lock();
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let cutoff = now - Duration::days(RETENTION_DAYS);
+    let lines = contents.lines().count();
+    let requests: Vec<PermissionRequest> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<PermissionRequest>(line).ok())
+        .filter(|request| request.at >= cutoff)
+        .collect();
+    if requests.len() < lines {
+        let kept: String = requests
+            .iter()
+            .filter_map(|request| serde_json::to_string(request).ok())
+            .map(|line| line + "\n")
+            .collect();
+        if let Err(e) = fs::write(path, kept) {
+            log::warn!("Failed to prune {}: {}", path.display(), e);
+        }
+    }
+    requests
+}
+
+fn aggregate(requests: &[PermissionRequest], since: DateTime<Utc>) -> PermissionStats {
+    let mut by_tool: HashMap<&str, (u32, HashMap<&str, u32>)> = HashMap::new();
+    let mut total = 0;
+    for request in requests.iter().filter(|request| request.at >= since) {
+        total += 1;
+        let (count, patterns) = by_tool.entry(&request.tool).or_default();
+        *count += 1;
+        if let Some(pattern) = &request.pattern {
+            *patterns.entry(pattern).or_default() += 1;
+        }
+    }
+    let mut tools: Vec<ToolStats> = by_tool
+        .into_iter()
+        .map(|(tool, (count, patterns))| {
+            let mut top_commands: Vec<CommandCount> = patterns
+                .into_iter()
+                .map(|(pattern, count)| CommandCount {
+                    pattern: pattern.to_string(),
+                    count,
+                })
+                .collect();
+            top_commands.sort_by(|a, b| b.count.cmp(&a.count).then(a.pattern.cmp(&b.pattern)));
+            top_commands.truncate(TOP_COMMANDS);
+            ToolStats {
+                tool: tool.to_string(),
+                count,
+                percent: (count as f64 * 100.0 / total as f64).round() as u32,
+                top_commands,
+            }
+        })
+        .collect();
+    tools.sort_by(|a, b| b.count.cmp(&a.count).then(a.tool.cmp(&b.tool)));
+    PermissionStats {
+        since,
+        total,
+        tools,
+    }
+}
+
+// Tauri command: Permission requests of the last `days` days (default 30), by tool
+#[tauri::command]
+#[specta::specta]
+pub fn get_permission_stats(days: Option<u32>) -> PermissionStats {
+    let now = Utc::now();
+    let days = days.unwrap_or(DEFAULT_DAYS).min(RETENTION_DAYS as u32);
+    let since = now - Duration::days(days as i64);
+    aggregate(&load(&log_path(), now), since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_patterns_keep_program_and_subcommand() {
+        assert_eq!(command_pattern("npm test").as_deref(), Some("npm test"));
+        assert_eq!(
+            command_pattern("CI=1 cargo build --release").as_deref(),
+            Some("cargo build")
+        );
+        assert_eq!(command_pattern("ls -la /tmp").as_deref(), Some("ls"));
+        assert_eq!(command_pattern("rm ./secrets.env").as_deref(), Some("rm"));
+        assert_eq!(command_pattern("   "), None);
+    }
+
+    #[test]
+    fn requests_are_counted_by_tool_and_pattern() {
+        let now = Utc::now();
+        let request = |days_ago: i64, tool: &str, pattern: Option<&str>| PermissionRequest {
+            at: now - Duration::days(days_ago),
+            agent_kind: "claude".to_string(),
+            tool: tool.to_string(),
+            pattern: pattern.map(String::from),
+        };
+        let requests = [
+            request(1, "Bash", Some("npm test")),
+            request(2, "Bash", Some("cargo build")),
+            request(3, "Bash", Some("npm test")),
+            request(4, "Edit", None),
+            // Outside the period
+            request(40, "WebFetch", None),
+        ];
+        let stats = aggregate(&requests, now - Duration::days(30));
+        assert_eq!(stats.total, 4);
+        assert_eq!(
+            stats.tools,
+            vec![
+                ToolStats {
+                    tool: "Bash".to_string(),
+                    count: 3,
+                    percent: 75,
+                    top_commands: vec![
+                        CommandCount {
+                            pattern: "npm test".to_string(),
+                            count: 2,
+                        },
+                        CommandCount {
+                            pattern: "cargo build".to_string(),
+                            count: 1,
+                        },
+                    ],
+                },
+                ToolStats {
+                    tool: "Edit".to_string(),
+                    count: 1,
+                    percent: 25,
+                    top_commands: Vec::new(),
+                },
+            ]
+        );
+    }
+}
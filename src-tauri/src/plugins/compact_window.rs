@@ -0,0 +1,170 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Rect, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+/// Window label; the frontend renders the condensed list for it
+pub const COMPACT_LABEL: &str = "compact";
+
+const WIDTH: f64 = 320.0;
+const HEIGHT: f64 = 420.0;
+/// Gap between the menu bar and the window, in logical pixels
+const MARGIN: f64 = 6.0;
+
+/// Cleared while the user has pinned the window open
+static AUTO_HIDE: AtomicBool = AtomicBool::new(true);
+
+/// Top-left corner that centres `window` under `anchor` (the tray icon),
+/// kept inside `area`
+fn position_below(
+    anchor: (PhysicalPosition<i32>, PhysicalSize<u32>),
+    window: PhysicalSize<u32>,
+    area: (PhysicalPosition<i32>, PhysicalSize<u32>),
+    margin: i32,
+) -> PhysicalPosition<i32> {
+    let (anchor_pos, anchor_size) = anchor;
+    let (area_pos, area_size) = area;
+    let centred = anchor_pos.x + anchor_size.width as i32 / 2 - window.width as i32 / 2;
+    let max_x = area_pos.x + area_size.width as i32 - window.width as i32 - margin;
+    let x = centred.min(max_x).max(area_pos.x + margin);
+    let y = (anchor_pos.y + anchor_size.height as i32).max(area_pos.y) + margin;
+    PhysicalPosition::new(x, y)
+}
+
+/// Place the window under the tray icon, or at the top-right of the screen
+/// when the click position isn't known (e.g. opened from a shortcut)
+fn place(app: &AppHandle, window: &WebviewWindow, tray: Option<Rect>) -> tauri::Result<()> {
+    let monitor = match &tray {
+        Some(rect) => {
+            let pos = rect.position.to_physical::<f64>(1.0);
+            app.monitor_from_point(pos.x, pos.y)?
+        }
+        None => None,
+    };
+    let Some(monitor) = monitor.or(app.primary_monitor()?) else {
+        return Ok(());
+    };
+    let scale = monitor.scale_factor();
+    let work_area = monitor.work_area();
+    let area = (work_area.position, work_area.size);
+    let anchor = match tray {
+        Some(rect) => (
+            rect.position.to_physical(scale),
+            rect.size.to_physical(scale),
+        ),
+        // A zero-sized anchor at the right edge of the menu bar
+        None => (
+            PhysicalPosition::new(area.0.x + area.1.width as i32, area.0.y),
+            PhysicalSize::new(0, 0),
+        ),
+    };
+    let size = window.outer_size()?;
+    let margin = (MARGIN * scale).round() as i32;
+    window.set_position(position_below(anchor, size, area, margin))?;
+    Ok(())
+}
+
+fn build(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        COMPACT_LABEL,
+        WebviewUrl::App("index.html#compact".into()),
+    )
+    .title("C3")
+    .inner_size(WIDTH, HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .build()?;
+
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            if AUTO_HIDE.load(Ordering::Relaxed) {
+                let _ = handle.hide();
+            }
+        }
+    });
+    Ok(window)
+}
+
+/// Show the compact window under the tray icon, or hide it if it's open
+pub fn toggle(app: &AppHandle, tray: Option<Rect>) -> tauri::Result<()> {
+    let window = match app.get_webview_window(COMPACT_LABEL) {
+        Some(window) if window.is_visible()? => return window.hide(),
+        Some(window) => window,
+        None => build(app)?,
+    };
+    place(app, &window, tray)?;
+    window.show()?;
+    window.set_focus()
+}
+
+// Tauri command: Toggle the compact session list near the menu bar
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_compact_window(app: AppHandle) -> Result<(), String> {
+    toggle(&app, None).map_err(|e| e.to_string())
+}
+
+// Tauri command: Hide the compact session list
+#[tauri::command]
+#[specta::specta]
+pub fn hide_compact_window(app: AppHandle) -> Result<(), String> {
+    match app.get_webview_window(COMPACT_LABEL) {
+        Some(window) => window.hide().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+// Tauri command: Swap the compact window for the full dashboard
+#[tauri::command]
+#[specta::specta]
+pub fn open_main_window(app: AppHandle) -> Result<(), String> {
+    hide_compact_window(app.clone())?;
+    crate::show_main_window(&app);
+    Ok(())
+}
+
+// Tauri command: Keep the compact window open when it loses focus
+#[tauri::command]
+#[specta::specta]
+pub fn set_compact_window_pinned(pinned: bool) {
+    AUTO_HIDE.store(!pinned, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: (PhysicalPosition<i32>, PhysicalSize<u32>) = (
+        PhysicalPosition { x: 0, y: 50 },
+        PhysicalSize {
+            width: 3000,
+            height: 1900,
+        },
+    );
+    const WINDOW: PhysicalSize<u32> = PhysicalSize {
+        width: 640,
+        height: 840,
+    };
+
+    #[test]
+    fn centres_under_the_tray_icon_and_stays_on_screen() {
+        let icon = (PhysicalPosition::new(2000, 0), PhysicalSize::new(44, 48));
+        assert_eq!(
+            position_below(icon, WINDOW, AREA, 12),
+            PhysicalPosition::new(1702, 62)
+        );
+
+        // Icon near the right edge: pushed left to fit
+        let icon = (PhysicalPosition::new(2900, 0), PhysicalSize::new(44, 48));
+        assert_eq!(
+            position_below(icon, WINDOW, AREA, 12),
+            PhysicalPosition::new(2348, 62)
+        );
+    }
+}
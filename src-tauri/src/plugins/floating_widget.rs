@@ -0,0 +1,281 @@
+use crate::profiles;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// Window label; the frontend renders the state counts for it
+pub const WIDGET_LABEL: &str = "widget";
+
+const WIDTH: f64 = 220.0;
+const HEIGHT: f64 = 40.0;
+/// Default distance from the bottom-right corner of the screen, in logical pixels
+const MARGIN: f64 = 16.0;
+/// Wait for a drag to settle before writing positions to disk
+const SAVE_DELAY_MS: u64 = 500;
+
+/// Shortcut currently bound to `toggle`, so it can be swapped on settings change
+static SHORTCUT: Mutex<Option<Shortcut>> = Mutex::new(None);
+/// Bumped on every move; a pending save only runs if no newer move happened
+static SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Offset from a display's top-left corner, in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct SavedPosition {
+    x: i32,
+    y: i32,
+}
+
+/// Last widget position on each display, keyed by `display_key`
+type Positions = HashMap<String, SavedPosition>;
+
+fn positions_path() -> PathBuf {
+    profiles::profile_dir().join("widget-positions.json")
+}
+
+fn load_positions() -> Positions {
+    fs::read_to_string(positions_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_positions(positions: &Positions) -> Result<(), String> {
+    let path = positions_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(positions).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+/// Name plus resolution, so an external monitor swapped for another model
+/// doesn't inherit a position that may be off-screen
+fn display_key(monitor: &Monitor) -> String {
+    let size = monitor.size();
+    format!(
+        "{}@{}x{}",
+        monitor.name().map(String::as_str).unwrap_or("display"),
+        size.width,
+        size.height
+    )
+}
+
+/// Absolute position for a saved offset (or the bottom-right corner when
+/// there is none), kept fully inside `area`
+fn resolve_position(
+    saved: Option<SavedPosition>,
+    origin: PhysicalPosition<i32>,
+    area: (PhysicalPosition<i32>, PhysicalSize<u32>),
+    window: PhysicalSize<u32>,
+    margin: i32,
+) -> PhysicalPosition<i32> {
+    let (area_pos, area_size) = area;
+    let max_x = area_pos.x + area_size.width as i32 - window.width as i32;
+    let max_y = area_pos.y + area_size.height as i32 - window.height as i32;
+    let (x, y) = match saved {
+        Some(saved) => (origin.x + saved.x, origin.y + saved.y),
+        None => (max_x - margin, max_y - margin),
+    };
+    PhysicalPosition::new(x.min(max_x).max(area_pos.x), y.min(max_y).max(area_pos.y))
+}
+
+/// The display under the mouse, so the widget shows up where the user is looking
+fn target_monitor(app: &AppHandle) -> tauri::Result<Option<Monitor>> {
+    let cursor = app.cursor_position()?;
+    match app.monitor_from_point(cursor.x, cursor.y)? {
+        Some(monitor) => Ok(Some(monitor)),
+        None => app.primary_monitor(),
+    }
+}
+
+fn place(app: &AppHandle, window: &WebviewWindow) -> tauri::Result<()> {
+    let Some(monitor) = target_monitor(app)? else {
+        return Ok(());
+    };
+    let saved = load_positions().get(&display_key(&monitor)).copied();
+    let work_area = monitor.work_area();
+    let margin = (MARGIN * monitor.scale_factor()).round() as i32;
+    let position = resolve_position(
+        saved,
+        *monitor.position(),
+        (work_area.position, work_area.size),
+        window.outer_size()?,
+        margin,
+    );
+    window.set_position(position)
+}
+
+/// Remember where the widget was dropped on its current display
+fn remember_position(window: &WebviewWindow, position: PhysicalPosition<i32>) {
+    let Ok(Some(monitor)) = window.current_monitor() else {
+        return;
+    };
+    let key = display_key(&monitor);
+    let origin = monitor.position();
+    let saved = SavedPosition {
+        x: position.x - origin.x,
+        y: position.y - origin.y,
+    };
+
+    let generation = SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(SAVE_DELAY_MS)).await;
+        if SAVE_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let mut positions = load_positions();
+        positions.insert(key, saved);
+        if let Err(e) = save_positions(&positions) {
+            log::warn!("Failed to save widget position: {}", e);
+        }
+    });
+}
+
+fn build(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        WIDGET_LABEL,
+        WebviewUrl::App("index.html#widget".into()),
+    )
+    .title("C3 Widget")
+    .inner_size(WIDTH, HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .visible_on_all_workspaces(true)
+    .skip_taskbar(true)
+    .focused(false)
+    .visible(false)
+    .build()?;
+
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(position) = event {
+            if handle.is_visible().unwrap_or(false) {
+                remember_position(&handle, *position);
+            }
+        }
+    });
+    Ok(window)
+}
+
+/// Show the floating widget, or hide it if it's showing
+pub fn toggle(app: &AppHandle) -> tauri::Result<()> {
+    let window = match app.get_webview_window(WIDGET_LABEL) {
+        Some(window) if window.is_visible()? => return window.hide(),
+        Some(window) => window,
+        None => build(app)?,
+    };
+    place(app, &window)?;
+    window.show()
+}
+
+/// Rejects shortcuts the global-shortcut plugin can't parse, e.g. `Cmd+Shift+`
+pub fn validate_shortcut(shortcut: &str) -> Result<(), String> {
+    if shortcut.trim().is_empty() {
+        return Ok(());
+    }
+    shortcut
+        .trim()
+        .parse::<Shortcut>()
+        .map(|_| ())
+        .map_err(|e| format!("Invalid widget shortcut {:?}: {}", shortcut, e))
+}
+
+/// Bind `shortcut` to `toggle`, replacing the previous binding. Empty unbinds.
+pub fn set_shortcut(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    let shortcut = shortcut.trim();
+    let next = match shortcut {
+        "" => None,
+        s => Some(s.parse::<Shortcut>().map_err(|e| e.to_string())?),
+    };
+    let mut current = SHORTCUT.lock();
+    if *current == next {
+        return Ok(());
+    }
+    let global_shortcut = app.global_shortcut();
+    if let Some(previous) = current.take() {
+        if let Err(e) = global_shortcut.unregister(previous) {
+            log::warn!("Failed to unregister widget shortcut: {}", e);
+        }
+    }
+    if let Some(next) = next {
+        global_shortcut
+            .on_shortcut(next, |app, _, event| {
+                if event.state == ShortcutState::Pressed {
+                    if let Err(e) = toggle(app) {
+                        log::error!("Failed to toggle floating widget: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to register {}: {}", shortcut, e))?;
+        *current = Some(next);
+    }
+    Ok(())
+}
+
+// Tauri command: Show or hide the floating status widget
+#[tauri::command]
+#[specta::specta]
+pub fn toggle_floating_widget(app: AppHandle) -> Result<(), String> {
+    toggle(&app).map_err(|e| e.to_string())
+}
+
+// Tauri command: Hide the floating status widget
+#[tauri::command]
+#[specta::specta]
+pub fn hide_floating_widget(app: AppHandle) -> Result<(), String> {
+    match app.get_webview_window(WIDGET_LABEL) {
+        Some(window) => window.hide().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: PhysicalSize<u32> = PhysicalSize {
+        width: 440,
+        height: 80,
+    };
+
+    fn area(x: i32, width: u32) -> (PhysicalPosition<i32>, PhysicalSize<u32>) {
+        (PhysicalPosition::new(x, 50), PhysicalSize::new(width, 1000))
+    }
+
+    #[test]
+    fn saved_offsets_are_relative_to_the_display_and_kept_on_screen() {
+        // Second display to the right of a 3000px one
+        let origin = PhysicalPosition::new(3000, 0);
+        let saved = Some(SavedPosition { x: 100, y: 200 });
+        assert_eq!(
+            resolve_position(saved, origin, area(3000, 2000), WINDOW, 32),
+            PhysicalPosition::new(3100, 200)
+        );
+
+        // Saved on a bigger display with the same name: clamped inside
+        let saved = Some(SavedPosition { x: 1900, y: 2000 });
+        assert_eq!(
+            resolve_position(saved, origin, area(3000, 2000), WINDOW, 32),
+            PhysicalPosition::new(4560, 970)
+        );
+
+        // Nothing saved: bottom-right corner
+        assert_eq!(
+            resolve_position(None, origin, area(3000, 2000), WINDOW, 32),
+            PhysicalPosition::new(4528, 938)
+        );
+        assert!(validate_shortcut("CmdOrCtrl+Shift+C").is_ok());
+        assert!(validate_shortcut("").is_ok());
+        assert!(validate_shortcut("Shift+").is_err());
+    }
+}
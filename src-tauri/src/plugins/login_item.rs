@@ -0,0 +1,68 @@
+use crate::{load_settings, save_settings};
+use serde::Serialize;
+use specta::Type;
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_autostart::ManagerExt;
+
+/// Passed by the login item, so a login launch can be told from a manual one
+const AUTOSTART_ARG: &str = "--autostart";
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginItemStatus {
+    /// Whether the login item is actually registered with the system
+    pub enabled: bool,
+    pub start_hidden: bool,
+}
+
+/// Registers C3 as a login item when enabled (a LaunchAgent on macOS)
+pub fn plugin<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_autostart::Builder::new()
+        .arg(AUTOSTART_ARG)
+        .build()
+}
+
+pub fn launched_at_login() -> bool {
+    std::env::args().any(|arg| arg == AUTOSTART_ARG)
+}
+
+/// Register or remove the login item
+pub fn apply(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if autolaunch.is_enabled().map_err(|e| e.to_string())? == enabled {
+        return Ok(());
+    }
+    let result = if enabled {
+        autolaunch.enable()
+    } else {
+        autolaunch.disable()
+    };
+    result.map_err(|e| format!("Failed to update login item: {}", e))?;
+    log::info!(
+        "Start at login {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(())
+}
+
+// Tauri command: Whether C3 starts at login, and hidden
+#[tauri::command]
+#[specta::specta]
+pub fn get_login_item_status(app: AppHandle) -> Result<LoginItemStatus, String> {
+    Ok(LoginItemStatus {
+        enabled: app.autolaunch().is_enabled().map_err(|e| e.to_string())?,
+        start_hidden: load_settings().start_hidden,
+    })
+}
+
+// Tauri command: Turn start at login on or off
+#[tauri::command]
+#[specta::specta]
+pub fn set_launch_at_login(app: AppHandle, enabled: bool) -> Result<LoginItemStatus, String> {
+    apply(&app, enabled)?;
+    let mut settings = load_settings();
+    settings.launch_at_login = enabled;
+    save_settings(&settings)?;
+    get_login_item_status(app)
+}
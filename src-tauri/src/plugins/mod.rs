@@ -1 +1,6 @@
+pub mod compact_window;
+pub mod floating_widget;
+pub mod login_item;
 pub mod mac_rounded_corners;
+pub mod power_source;
+pub mod sleep_wake;
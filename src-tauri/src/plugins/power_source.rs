@@ -0,0 +1,117 @@
+// objc 0.2 macros emit unexpected cfgs, and the cocoa crate is deprecated wholesale
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+/// Whether the machine is running on battery or in Low Power Mode, when
+/// C3 should do less in the background
+#[cfg(target_os = "macos")]
+pub fn saving_power() -> bool {
+    macos::on_battery() || macos::low_power_mode()
+}
+
+/// Whether the machine is running on battery
+#[cfg(not(target_os = "macos"))]
+pub fn saving_power() -> bool {
+    sysfs::on_battery(std::path::Path::new(sysfs::POWER_SUPPLY_DIR))
+}
+
+/// IOKit's power source snapshot, and NSProcessInfo for Low Power Mode
+#[cfg(target_os = "macos")]
+mod macos {
+    use cocoa::base::{id, BOOL, NO};
+    use cocoa::foundation::NSString;
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::ffi::{c_void, CStr};
+
+    type CFTypeRef = *const c_void;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPSCopyPowerSourcesInfo() -> CFTypeRef;
+        fn IOPSGetProvidingPowerSourceType(snapshot: CFTypeRef) -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// `kIOPSBatteryPowerValue`
+    const BATTERY_POWER: &str = "Battery Power";
+
+    pub fn on_battery() -> bool {
+        unsafe {
+            let snapshot = IOPSCopyPowerSourcesInfo();
+            if snapshot.is_null() {
+                return false;
+            }
+            // Owned by the snapshot; a CFString is an NSString
+            let source = IOPSGetProvidingPowerSourceType(snapshot);
+            let on_battery = !source.is_null() && {
+                let utf8 = NSString::UTF8String(source as id);
+                !utf8.is_null() && CStr::from_ptr(utf8).to_bytes() == BATTERY_POWER.as_bytes()
+            };
+            CFRelease(snapshot);
+            on_battery
+        }
+    }
+
+    /// Low Power Mode, which Macs have from macOS 12
+    pub fn low_power_mode() -> bool {
+        unsafe {
+            let info: id = msg_send![class!(NSProcessInfo), processInfo];
+            let supported: BOOL = msg_send![info, respondsToSelector: sel!(isLowPowerModeEnabled)];
+            if supported == NO {
+                return false;
+            }
+            let enabled: BOOL = msg_send![info, isLowPowerModeEnabled];
+            enabled != NO
+        }
+    }
+}
+
+/// Linux reports batteries under /sys/class/power_supply
+#[cfg(not(target_os = "macos"))]
+mod sysfs {
+    use std::fs;
+    use std::path::Path;
+
+    pub const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+    /// Whether any battery is discharging
+    pub fn on_battery(dir: &Path) -> bool {
+        let Ok(supplies) = fs::read_dir(dir) else {
+            return false;
+        };
+        supplies.flatten().any(|supply| {
+            let read =
+                |name: &str| fs::read_to_string(supply.path().join(name)).unwrap_or_default();
+            read("type").trim() == "Battery" && read("status").trim() == "Discharging"
+        })
+    }
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::sysfs::on_battery;
+    use std::fs;
+
+    #[test]
+    fn a_discharging_battery_means_battery_power() {
+        let dir = std::env::temp_dir().join(format!("c3-power-supply-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let supply = |name: &str, kind: &str, status: &str| {
+            let path = dir.join(name);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("type"), format!("{}\n", kind)).unwrap();
+            fs::write(path.join("status"), format!("{}\n", status)).unwrap();
+        };
+        supply("AC", "Mains", "");
+        supply("BAT0", "Battery", "Charging");
+        assert!(!on_battery(&dir));
+        supply("BAT0", "Battery", "Discharging");
+        assert!(on_battery(&dir));
+        assert!(!on_battery(&dir.join("missing")));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,129 @@
+// objc 0.2 macros emit unexpected cfgs, and the cocoa crate is deprecated wholesale
+#![allow(unexpected_cfgs)]
+#![allow(deprecated)]
+
+/// System power transitions C3 reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerEvent {
+    /// Only reported by the macOS observer
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    WillSleep,
+    DidWake,
+}
+
+/// Call `callback` whenever the system goes to sleep or wakes up.
+/// Must be called once, from the main thread (during app setup).
+pub fn watch_power_events<F>(callback: F)
+where
+    F: Fn(PowerEvent) + Send + Sync + 'static,
+{
+    #[cfg(target_os = "macos")]
+    macos::register(Box::new(callback));
+
+    #[cfg(not(target_os = "macos"))]
+    clock_gap::spawn(callback);
+}
+
+/// NSWorkspace sleep/wake notifications
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PowerEvent;
+    use cocoa::base::{id, nil};
+    use cocoa::foundation::NSString;
+    use objc::declare::ClassDecl;
+    use objc::runtime::{Object, Sel};
+    use objc::{class, msg_send, sel, sel_impl};
+    use std::sync::OnceLock;
+
+    type Callback = Box<dyn Fn(PowerEvent) + Send + Sync>;
+
+    static CALLBACK: OnceLock<Callback> = OnceLock::new();
+
+    extern "C" fn will_sleep(_this: &Object, _cmd: Sel, _notification: id) {
+        if let Some(callback) = CALLBACK.get() {
+            callback(PowerEvent::WillSleep);
+        }
+    }
+
+    extern "C" fn did_wake(_this: &Object, _cmd: Sel, _notification: id) {
+        if let Some(callback) = CALLBACK.get() {
+            callback(PowerEvent::DidWake);
+        }
+    }
+
+    pub fn register(callback: Callback) {
+        if CALLBACK.set(callback).is_err() {
+            log::warn!("Power event observer already registered");
+            return;
+        }
+
+        unsafe {
+            let Some(mut decl) = ClassDecl::new("C3PowerObserver", class!(NSObject)) else {
+                log::error!("Failed to declare power observer class");
+                return;
+            };
+            decl.add_method(
+                sel!(willSleep:),
+                will_sleep as extern "C" fn(&Object, Sel, id),
+            );
+            decl.add_method(sel!(didWake:), did_wake as extern "C" fn(&Object, Sel, id));
+            let observer_class = decl.register();
+
+            // Lives for the rest of the process, like the app itself
+            let observer: id = msg_send![observer_class, new];
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let center: id = msg_send![workspace, notificationCenter];
+
+            let will_sleep_name = NSString::alloc(nil).init_str("NSWorkspaceWillSleepNotification");
+            let did_wake_name = NSString::alloc(nil).init_str("NSWorkspaceDidWakeNotification");
+            let _: () = msg_send![center, addObserver: observer
+                                          selector: sel!(willSleep:)
+                                              name: will_sleep_name
+                                            object: nil];
+            let _: () = msg_send![center, addObserver: observer
+                                          selector: sel!(didWake:)
+                                              name: did_wake_name
+                                            object: nil];
+        }
+
+        log::info!("Watching NSWorkspace sleep/wake notifications");
+    }
+}
+
+/// Fallback for platforms without a wake notification we subscribe to:
+/// the monotonic clock stops while suspended but the wall clock does not,
+/// so a wall-clock jump much larger than the monotonic one means we slept.
+#[cfg(not(target_os = "macos"))]
+mod clock_gap {
+    use super::PowerEvent;
+    use std::time::{Duration, Instant, SystemTime};
+
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+    /// Gaps smaller than this are scheduling jitter or NTP adjustments
+    const MIN_SLEEP_GAP: Duration = Duration::from_secs(15);
+
+    pub fn spawn<F>(callback: F)
+    where
+        F: Fn(PowerEvent) + Send + Sync + 'static,
+    {
+        let spawned = std::thread::Builder::new()
+            .name("c3-wake-detector".to_string())
+            .spawn(move || loop {
+                let wall_before = SystemTime::now();
+                let mono_before = Instant::now();
+                std::thread::sleep(CHECK_INTERVAL);
+                let mono_elapsed = mono_before.elapsed();
+                let wall_elapsed = wall_before.elapsed().unwrap_or_default();
+                if wall_elapsed.saturating_sub(mono_elapsed) > MIN_SLEEP_GAP {
+                    log::info!(
+                        "Detected system wake ({}s wall-clock gap)",
+                        wall_elapsed.as_secs()
+                    );
+                    callback(PowerEvent::DidWake);
+                }
+            });
+        if let Err(e) = spawned {
+            log::error!("Failed to start wake detector: {}", e);
+        }
+    }
+}
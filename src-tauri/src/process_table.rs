@@ -0,0 +1,210 @@
+use crate::cmd;
+use parking_lot::Mutex;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// How long a pane's child processes are trusted without reading `ps` again,
+/// while the pane's foreground command stays the same. Covers quitting one
+/// node agent and starting another between two scans.
+const CHILDREN_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub(crate) struct Process {
+    pub(crate) pid: u32,
+    pub(crate) ppid: u32,
+    pub(crate) tty: String,
+    pub(crate) command: String,
+}
+
+/// Rows of `ps -ax -o pid=,ppid=,tty=,command=`
+pub(crate) fn parse_ps(output: &str) -> Vec<Process> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pid = parts.next()?.parse().ok()?;
+            let ppid = parts.next()?.parse().ok()?;
+            let tty = parts.next()?.to_string();
+            let command = parts.collect::<Vec<_>>().join(" ");
+            Some(Process {
+                pid,
+                ppid,
+                tty,
+                command,
+            })
+        })
+        .collect()
+}
+
+fn read_processes() -> Vec<Process> {
+    cmd("ps")
+        .args(["-ax", "-o", "pid=,ppid=,tty=,command="])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_ps(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Every process, read with one `ps` the first time a scan cycle asks for it
+#[derive(Default)]
+pub(crate) struct ProcessTable {
+    processes: OnceLock<Vec<Process>>,
+    read_ms: OnceLock<u64>,
+}
+
+impl ProcessTable {
+    #[cfg(test)]
+    pub(crate) fn from_ps(output: &str) -> Self {
+        let table = Self::default();
+        let _ = table.processes.set(parse_ps(output));
+        table
+    }
+
+    pub(crate) fn processes(&self) -> &[Process] {
+        self.processes.get_or_init(|| {
+            let started = Instant::now();
+            let processes = read_processes();
+            let _ = self.read_ms.set(started.elapsed().as_millis() as u64);
+            processes
+        })
+    }
+
+    /// How long `ps` took, None when nothing this cycle needed it
+    pub(crate) fn read_ms(&self) -> Option<u64> {
+        self.read_ms.get().copied()
+    }
+}
+
+struct CachedChildren {
+    pane_command: String,
+    commands: Vec<String>,
+    read_at: Instant,
+}
+
+/// Command lines of each tmux pane process's children, keyed by the pane's
+/// PID and kept between scan cycles
+#[derive(Default)]
+pub struct ChildCache {
+    panes: Mutex<HashMap<String, CachedChildren>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ChildCache {
+    fn children(&self, table: &ProcessTable, pane_pid: &str, pane_command: &str) -> Vec<String> {
+        let mut panes = self.panes.lock();
+        if let Some(cached) = panes.get(pane_pid).filter(|cached| {
+            cached.pane_command == pane_command && cached.read_at.elapsed() < CHILDREN_TTL
+        }) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return cached.commands.clone();
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let pid: Option<u32> = pane_pid.parse().ok();
+        let commands: Vec<String> = table
+            .processes()
+            .iter()
+            .filter(|process| Some(process.ppid) == pid)
+            .map(|process| process.command.clone())
+            .collect();
+        panes.insert(
+            pane_pid.to_string(),
+            CachedChildren {
+                pane_command: pane_command.to_string(),
+                commands: commands.clone(),
+                read_at: Instant::now(),
+            },
+        );
+        commands
+    }
+
+    /// Forget panes that are gone
+    pub(crate) fn retain(&self, live_pids: &HashSet<String>) {
+        self.panes.lock().retain(|pid, _| live_pids.contains(pid));
+    }
+
+    /// Lookups answered from the cache and from the process table since the last call
+    pub(crate) fn take_counts(&self) -> (u64, u64) {
+        (
+            self.hits.swap(0, Ordering::Relaxed),
+            self.misses.swap(0, Ordering::Relaxed),
+        )
+    }
+}
+
+/// Children of one pane's process, looked up only when an agent check needs them
+pub(crate) struct PaneChildren<'a> {
+    pane_pid: &'a str,
+    pane_command: &'a str,
+    table: &'a ProcessTable,
+    cache: &'a ChildCache,
+    commands: OnceCell<Vec<String>>,
+}
+
+impl<'a> PaneChildren<'a> {
+    pub(crate) fn new(
+        pane_pid: &'a str,
+        pane_command: &'a str,
+        table: &'a ProcessTable,
+        cache: &'a ChildCache,
+    ) -> Self {
+        Self {
+            pane_pid,
+            pane_command,
+            table,
+            cache,
+            commands: OnceCell::new(),
+        }
+    }
+
+    /// Whether any child's command line matches
+    pub(crate) fn any(&self, matches: impl Fn(&str) -> bool) -> bool {
+        self.commands
+            .get_or_init(|| {
+                self.cache
+                    .children(self.table, self.pane_pid, self.pane_command)
+            })
+            .iter()
+            .any(|command| matches(command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PS: &str = "\
+  100     1 ??       tmux new-session -d
+  110   100 ttys001  -zsh
+  120   110 ttys001  node /opt/homebrew/bin/claude
+  130   100 ttys002  -zsh
+  140   130 ttys002  bun /Users/ann/.bun/bin/omp
+";
+
+    #[test]
+    fn pane_children_come_from_one_table_then_the_cache() {
+        let table = ProcessTable::from_ps(PS);
+        let cache = ChildCache::default();
+        let claude = PaneChildren::new("110", "node", &table, &cache);
+        assert!(claude.any(|command| command.contains("claude")));
+        // Asked again for the same pane: answered without another lookup
+        assert!(!claude.any(|command| command.contains("codex")));
+        let omp = PaneChildren::new("130", "bun", &table, &cache);
+        assert!(omp.any(|command| command.ends_with("/omp")));
+        cache.retain(&HashSet::from(["110".into(), "130".into()]));
+        assert_eq!(cache.take_counts(), (0, 2));
+
+        // The next cycle's table is never read while the panes are cached
+        let empty = ProcessTable::from_ps("");
+        assert!(PaneChildren::new("110", "node", &empty, &cache).any(|c| c.contains("claude")));
+        // A new foreground command reads the table again
+        assert!(!PaneChildren::new("130", "node", &empty, &cache).any(|c| c.contains("omp")));
+        cache.retain(&HashSet::from(["110".into()]));
+        assert_eq!(cache.take_counts(), (1, 1));
+        assert!(!cache.panes.lock().contains_key("130"));
+    }
+}
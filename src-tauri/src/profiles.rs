@@ -0,0 +1,206 @@
+use crate::paths;
+use serde::Serialize;
+use specta::Type;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+/// Profile whose files live directly in the config dir, as before profiles existed
+pub const DEFAULT_PROFILE: &str = "default";
+/// Set when switching profiles so the restarted process wins over `--profile`
+const PROFILE_ENV: &str = "C3_PROFILE";
+/// Remembers the profile picked from the tray or settings across launches
+const ACTIVE_PROFILE_FILE: &str = "active-profile";
+const MAX_NAME_LEN: usize = 32;
+
+static ACTIVE_PROFILE: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Profile names must be 1-{} letters, digits, '-' or '_'",
+            MAX_NAME_LEN
+        ))
+    }
+}
+
+/// `--profile work` or `--profile=work`
+fn parse_profile_flag(args: &[String]) -> Option<String> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--profile" {
+            return iter.next().cloned();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+fn dir_for(name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        paths::config_dir()
+    } else {
+        paths::config_dir().join("profiles").join(name)
+    }
+}
+
+/// Pick the active profile: `$C3_PROFILE`, then `--profile`, then the last one
+/// selected in the app. Must run before settings are loaded.
+pub fn init() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let remembered = fs::read_to_string(paths::config_dir().join(ACTIVE_PROFILE_FILE))
+        .ok()
+        .map(|s| s.trim().to_string());
+    let requested = std::env::var(PROFILE_ENV)
+        .ok()
+        .filter(|s| !s.is_empty())
+        .or_else(|| parse_profile_flag(&args))
+        .or(remembered);
+
+    let name = match requested {
+        Some(name) if validate_name(&name).is_ok() => name,
+        Some(name) => {
+            log::warn!("Ignoring invalid profile name {:?}", name);
+            DEFAULT_PROFILE.to_string()
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    };
+    if let Err(e) = fs::create_dir_all(dir_for(&name)) {
+        log::warn!("Failed to create profile dir for {}: {}", name, e);
+    }
+    log::info!("Using profile '{}'", name);
+    let _ = ACTIVE_PROFILE.set(name);
+}
+
+pub fn active() -> &'static str {
+    ACTIVE_PROFILE
+        .get()
+        .map(|s| s.as_str())
+        .unwrap_or(DEFAULT_PROFILE)
+}
+
+/// Where the active profile keeps settings.json and session-meta.json
+pub fn profile_dir() -> PathBuf {
+    dir_for(active())
+}
+
+/// All profiles, default first
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(paths::config_dir().join("profiles"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+                .filter(|name| validate_name(name).is_ok() && name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+fn profile_list() -> ProfileList {
+    ProfileList {
+        active: active().to_string(),
+        profiles: list(),
+    }
+}
+
+/// Remember `name` and restart into it
+pub fn switch_to(app_handle: &AppHandle, name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    if !list().iter().any(|p| p == name) {
+        return Err(format!("Profile '{}' does not exist", name));
+    }
+    if name == active() {
+        return Ok(());
+    }
+    let config_dir = paths::config_dir();
+    fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    fs::write(config_dir.join(ACTIVE_PROFILE_FILE), name).map_err(|e| e.to_string())?;
+
+    log::info!("Switching to profile '{}', restarting", name);
+    std::env::set_var(PROFILE_ENV, name);
+    app_handle.restart()
+}
+
+// Tauri command: List profiles and the active one
+#[tauri::command]
+#[specta::specta]
+pub fn get_profiles() -> ProfileList {
+    profile_list()
+}
+
+// Tauri command: Create a profile, starting from the active profile's settings
+#[tauri::command]
+#[specta::specta]
+pub fn create_profile(name: String) -> Result<ProfileList, String> {
+    validate_name(&name)?;
+    if list().contains(&name) {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+    let dir = dir_for(&name);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let current_settings = profile_dir().join("settings.json");
+    if current_settings.exists() {
+        fs::copy(&current_settings, dir.join("settings.json")).map_err(|e| e.to_string())?;
+    }
+    Ok(profile_list())
+}
+
+// Tauri command: Switch profile (restarts the app)
+#[tauri::command]
+#[specta::specta]
+pub fn switch_profile(app_handle: AppHandle, name: String) -> Result<(), String> {
+    switch_to(&app_handle, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn profile_flag_forms() {
+        assert_eq!(
+            parse_profile_flag(&args(&["--profile", "work"])),
+            Some("work".to_string())
+        );
+        assert_eq!(
+            parse_profile_flag(&args(&["-v", "--profile=personal"])),
+            Some("personal".to_string())
+        );
+        assert_eq!(parse_profile_flag(&args(&["--profile"])), None);
+        assert_eq!(parse_profile_flag(&args(&[])), None);
+    }
+
+    #[test]
+    fn profile_names_cannot_escape_the_profiles_dir() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("side_project-2").is_ok());
+        for bad in ["", "../work", "a/b", "with space", &"x".repeat(33)] {
+            assert!(validate_name(bad).is_err(), "{:?}", bad);
+        }
+    }
+}
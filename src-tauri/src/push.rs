@@ -0,0 +1,231 @@
+use crate::channels::{self, ChannelEvent};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+/// Window `max_per_hour` counts pushes in
+const RATE_WINDOW: Duration = Duration::from_secs(3600);
+
+/// When recent pushes went out, for rate limiting
+static SENT: Mutex<VecDeque<Instant>> = Mutex::new(VecDeque::new());
+
+/// Where mobile pushes go
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum PushService {
+    #[default]
+    Off,
+    /// A topic on ntfy.sh or a self-hosted ntfy server
+    Ntfy,
+    Pushover,
+}
+
+/// Push notifications to a phone, for when you've stepped away
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct PushSettings {
+    #[serde(default)]
+    pub service: PushService,
+    /// Topic URL, e.g. `https://ntfy.sh/my-c3-alerts`
+    #[serde(default)]
+    pub ntfy_url: String,
+    /// Access token for a protected topic
+    #[serde(default)]
+    pub ntfy_token: Option<String>,
+    #[serde(default)]
+    pub pushover_user_key: String,
+    /// API token of a Pushover application
+    #[serde(default)]
+    pub pushover_app_token: String,
+    /// Which notifications are pushed
+    #[serde(default = "default_events")]
+    pub events: Vec<ChannelEvent>,
+    /// Most pushes in an hour; the rest are dropped. 0 for no limit
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+fn default_events() -> Vec<ChannelEvent> {
+    vec![ChannelEvent::Permission, ChannelEvent::Alert]
+}
+
+fn default_max_per_hour() -> u32 {
+    20
+}
+
+impl Default for PushSettings {
+    fn default() -> Self {
+        Self {
+            service: PushService::Off,
+            ntfy_url: String::new(),
+            ntfy_token: None,
+            pushover_user_key: String::new(),
+            pushover_app_token: String::new(),
+            events: default_events(),
+            max_per_hour: default_max_per_hour(),
+        }
+    }
+}
+
+/// Whether `event` is pushed with these settings
+pub(crate) fn wants(push: &PushSettings, event: ChannelEvent) -> bool {
+    push.service != PushService::Off && push.events.contains(&event)
+}
+
+/// Record a push at `now` unless `max` went out in the last hour
+fn take(sent: &mut VecDeque<Instant>, max: u32, now: Instant) -> bool {
+    while sent
+        .front()
+        .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+    {
+        sent.pop_front();
+    }
+    if max > 0 && sent.len() >= max as usize {
+        return false;
+    }
+    sent.push_back(now);
+    true
+}
+
+/// ntfy's JSON publishing goes to the server root, naming the topic
+fn ntfy_request(
+    url: &str,
+    event: ChannelEvent,
+    title: &str,
+    message: &str,
+) -> Result<(String, Value), String> {
+    let url = url.trim().trim_end_matches('/');
+    let (server, topic) = url
+        .rsplit_once('/')
+        .filter(|(server, topic)| server.contains("://") && !topic.is_empty())
+        .ok_or_else(|| format!("Not an ntfy topic URL: {}", url))?;
+    let body = json!({
+        "topic": topic,
+        "title": title,
+        "message": message,
+        "priority": if event.urgent() { 4 } else { 3 },
+    });
+    Ok((server.to_string(), body))
+}
+
+fn pushover_body(push: &PushSettings, event: ChannelEvent, title: &str, message: &str) -> Value {
+    json!({
+        "token": push.pushover_app_token.trim(),
+        "user": push.pushover_user_key.trim(),
+        "title": title,
+        "message": message,
+        "priority": if event.urgent() { 1 } else { 0 },
+    })
+}
+
+fn post(
+    push: &PushSettings,
+    event: ChannelEvent,
+    title: &str,
+    message: &str,
+) -> Result<(), String> {
+    match push.service {
+        PushService::Off => Err("Push notifications are off".to_string()),
+        PushService::Ntfy => {
+            let (server, body) = ntfy_request(&push.ntfy_url, event, title, message)?;
+            let headers: Vec<String> = push
+                .ntfy_token
+                .iter()
+                .filter(|token| !token.trim().is_empty())
+                .map(|token| format!("Authorization: Bearer {}", token.trim()))
+                .collect();
+            channels::post_json(&server, &body, &headers)
+        }
+        PushService::Pushover => {
+            if push.pushover_user_key.trim().is_empty() || push.pushover_app_token.trim().is_empty()
+            {
+                return Err("Pushover needs a user key and an app token".to_string());
+            }
+            channels::post_json(
+                PUSHOVER_URL,
+                &pushover_body(push, event, title, message),
+                &[],
+            )
+        }
+    }
+}
+
+/// Push a notification, unless `max_per_hour` have gone out already
+pub(crate) fn send(
+    push: &PushSettings,
+    event: ChannelEvent,
+    title: &str,
+    message: &str,
+) -> Result<(), String> {
+    if !take(&mut SENT.lock(), push.max_per_hour, Instant::now()) {
+        log::info!(
+            "Dropping push notification — over {} an hour",
+            push.max_per_hour
+        );
+        return Ok(());
+    }
+    post(push, event, title, message)
+}
+
+// Tauri command: Send a test push with these (possibly unsaved) settings
+#[tauri::command]
+#[specta::specta]
+pub async fn test_push(push: PushSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        post(
+            &push,
+            ChannelEvent::Permission,
+            "c3 — test",
+            "Push notifications from C3 reach this device",
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pushes_past_the_hourly_limit_are_dropped() {
+        let start = Instant::now();
+        let mut sent = VecDeque::new();
+        assert!(take(&mut sent, 2, start));
+        assert!(take(&mut sent, 2, start + Duration::from_secs(60)));
+        assert!(!take(&mut sent, 2, start + Duration::from_secs(120)));
+        // The first has aged out
+        assert!(take(&mut sent, 2, start + RATE_WINDOW));
+        assert!(!take(&mut sent, 2, start + RATE_WINDOW));
+        assert!(take(&mut sent, 0, start + RATE_WINDOW));
+    }
+
+    #[test]
+    fn ntfy_topics_are_published_to_their_server() {
+        let (server, body) = ntfy_request(
+            "https://ntfy.example.com/c3-alerts/",
+            ChannelEvent::Permission,
+            "c3 — api",
+            "Claude needs your permission",
+        )
+        .unwrap();
+        assert_eq!(server, "https://ntfy.example.com");
+        assert_eq!(body["topic"], "c3-alerts");
+        assert_eq!(body["priority"], 4);
+        assert!(ntfy_request("c3-alerts", ChannelEvent::Complete, "", "").is_err());
+        assert!(ntfy_request("https://ntfy.sh/", ChannelEvent::Complete, "", "").is_err());
+
+        let push = PushSettings {
+            service: PushService::Ntfy,
+            events: vec![ChannelEvent::Permission],
+            ..Default::default()
+        };
+        assert!(wants(&push, ChannelEvent::Permission));
+        assert!(!wants(&push, ChannelEvent::Complete));
+        let off = PushSettings::default();
+        assert!(!wants(&off, ChannelEvent::Permission));
+    }
+}
@@ -0,0 +1,339 @@
+use crate::{load_session_meta, AppState, C3Session, SessionMetaStore, SessionState};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Criteria for `query_sessions`; empty fields match everything
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFilter {
+    #[serde(default)]
+    pub states: Vec<SessionState>,
+    /// Glob against the project path, e.g. `~/work/**`
+    #[serde(default)]
+    pub path_glob: Option<String>,
+    /// Session tag, compared case-insensitively
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Case-insensitive substring of the project name
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionQueryResult {
+    /// Matching sessions: pinned first, then manual order, then by urgency
+    pub sessions: Vec<C3Session>,
+    /// Sessions per state among those matching every criterion except `states`,
+    /// so state chips can show counts while one of them is selected
+    pub state_counts: HashMap<SessionState, usize>,
+    pub total: usize,
+}
+
+struct CompiledFilter<'a> {
+    filter: &'a SessionFilter,
+    path_glob: Option<glob::Pattern>,
+    text: Option<String>,
+}
+
+impl<'a> CompiledFilter<'a> {
+    fn new(filter: &'a SessionFilter, home: Option<&str>) -> Result<Self, String> {
+        let path_glob = filter
+            .path_glob
+            .as_deref()
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .map(|g| match (g.strip_prefix('~'), home) {
+                (Some(rest), Some(home)) => format!("{}{}", home, rest),
+                _ => g.to_string(),
+            })
+            .map(|g| glob::Pattern::new(&g).map_err(|e| format!("Invalid path glob: {}", e)))
+            .transpose()?;
+        let text = filter
+            .text
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase);
+        Ok(Self {
+            filter,
+            path_glob,
+            text,
+        })
+    }
+
+    /// Everything except the state criterion
+    fn matches_facets(&self, session: &C3Session, meta: &SessionMetaStore) -> bool {
+        if let Some(glob) = &self.path_glob {
+            match session.project_path.as_deref() {
+                Some(path) if glob.matches(path) => {}
+                _ => return false,
+            }
+        }
+        if let Some(tag) = self.filter.tag.as_deref().filter(|t| !t.is_empty()) {
+            let session_tag = meta
+                .sessions
+                .get(&session.id)
+                .and_then(|m| m.tag.as_deref());
+            if !session_tag.is_some_and(|t| t.eq_ignore_ascii_case(tag)) {
+                return false;
+            }
+        }
+        if let Some(text) = &self.text {
+            if !session.project_name.to_lowercase().contains(text) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_state(&self, session: &C3Session) -> bool {
+        self.filter.states.is_empty() || self.filter.states.contains(&session.state)
+    }
+}
+
+/// Lower is more urgent: sessions blocked on the user come first
+fn urgency(state: &SessionState) -> u8 {
+    match state {
+        SessionState::AwaitingPermission => 0,
+        SessionState::AwaitingInput => 1,
+        SessionState::Error => 2,
+        SessionState::Processing => 3,
+        SessionState::Spawning => 4,
+        SessionState::Complete => 5,
+    }
+}
+
+/// The session that has waited longest at the most urgent state that needs
+/// the user (permission, then input, then error)
+pub(crate) fn most_urgent_session(sessions: &HashMap<String, C3Session>) -> Option<&C3Session> {
+    sessions
+        .values()
+        .filter(|s| {
+            matches!(
+                s.state,
+                SessionState::AwaitingPermission
+                    | SessionState::AwaitingInput
+                    | SessionState::Error
+            )
+        })
+        .min_by_key(|s| (urgency(&s.state), s.last_activity))
+}
+
+/// Pinned first, then drag-reorder positions, then urgency, then most recent
+pub(crate) fn sort_sessions(sessions: &mut [C3Session], meta: &SessionMetaStore) {
+    sessions.sort_by_key(|s| {
+        let session_meta = meta.sessions.get(&s.id);
+        let pinned = session_meta.is_some_and(|m| m.pinned);
+        // None sorts after every position
+        let order = session_meta
+            .and_then(|m| m.order)
+            .map_or(u64::MAX, u64::from);
+        (!pinned, order, urgency(&s.state), Reverse(s.last_activity))
+    });
+}
+
+fn run_query(
+    sessions: &HashMap<String, C3Session>,
+    meta: &SessionMetaStore,
+    filter: &SessionFilter,
+    home: Option<&str>,
+) -> Result<SessionQueryResult, String> {
+    let compiled = CompiledFilter::new(filter, home)?;
+    let mut state_counts: HashMap<SessionState, usize> = HashMap::new();
+    let mut matched: Vec<C3Session> = Vec::new();
+
+    for session in sessions.values() {
+        if !compiled.matches_facets(session, meta) {
+            continue;
+        }
+        *state_counts.entry(session.state.clone()).or_default() += 1;
+        if compiled.matches_state(session) {
+            matched.push(session.clone());
+        }
+    }
+    sort_sessions(&mut matched, meta);
+
+    Ok(SessionQueryResult {
+        total: matched.len(),
+        sessions: matched,
+        state_counts,
+    })
+}
+
+// Tauri command: Filter sessions by state, path glob, tag and project name
+#[tauri::command]
+#[specta::specta]
+pub fn query_sessions(
+    state: tauri::State<Arc<AppState>>,
+    filter: SessionFilter,
+) -> Result<SessionQueryResult, String> {
+    let meta = load_session_meta();
+    let home = std::env::var("HOME").ok();
+    let sessions = state.sessions.read();
+    run_query(&sessions, &meta, &filter, home.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SessionMeta;
+    use chrono::{Duration, Utc};
+
+    fn session(id: &str, name: &str, path: &str, state: SessionState, age_secs: i64) -> C3Session {
+        C3Session {
+            project_name: name.to_string(),
+            project_path: Some(path.to_string()),
+            state,
+            last_activity: Utc::now() - Duration::seconds(age_secs),
+            ..C3Session::test(id)
+        }
+    }
+
+    fn fixture() -> (HashMap<String, C3Session>, SessionMetaStore) {
+        let sessions = [
+            session(
+                "a",
+                "api",
+                "/home/ann/work/api",
+                SessionState::Processing,
+                30,
+            ),
+            session(
+                "b",
+                "web",
+                "/home/ann/work/web",
+                SessionState::AwaitingInput,
+                10,
+            ),
+            session(
+                "c",
+                "dotfiles",
+                "/home/ann/personal/dotfiles",
+                SessionState::Complete,
+                5,
+            ),
+            session(
+                "d",
+                "api-docs",
+                "/home/ann/work/api-docs",
+                SessionState::Complete,
+                60,
+            ),
+        ]
+        .into_iter()
+        .map(|s| (s.id.clone(), s))
+        .collect();
+        let mut meta = SessionMetaStore::default();
+        meta.sessions.insert(
+            "b".to_string(),
+            SessionMeta {
+                tag: Some("Urgent".to_string()),
+                ..Default::default()
+            },
+        );
+        (sessions, meta)
+    }
+
+    fn ids(result: &SessionQueryResult) -> Vec<&str> {
+        result.sessions.iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn empty_filter_returns_everything_most_urgent_first() {
+        let (sessions, meta) = fixture();
+        let result = run_query(&sessions, &meta, &SessionFilter::default(), None).unwrap();
+        assert_eq!(ids(&result), vec!["b", "a", "c", "d"]);
+        assert_eq!(result.total, 4);
+        assert_eq!(result.state_counts[&SessionState::Complete], 2);
+    }
+
+    #[test]
+    fn pinned_then_manual_order_then_urgency() {
+        let (sessions, mut meta) = fixture();
+        meta.sessions.insert(
+            "d".to_string(),
+            SessionMeta {
+                pinned: true,
+                ..Default::default()
+            },
+        );
+        meta.sessions.insert(
+            "c".to_string(),
+            SessionMeta {
+                order: Some(1),
+                ..Default::default()
+            },
+        );
+        meta.sessions.get_mut("b").unwrap().order = Some(2);
+        let result = run_query(&sessions, &meta, &SessionFilter::default(), None).unwrap();
+        assert_eq!(ids(&result), vec!["d", "c", "b", "a"]);
+    }
+
+    #[test]
+    fn most_urgent_prefers_permission_then_oldest_wait() {
+        let (mut sessions, _) = fixture();
+        assert_eq!(most_urgent_session(&sessions).unwrap().id, "b");
+
+        for (id, age) in [("p1", 20), ("p2", 90)] {
+            sessions.insert(
+                id.to_string(),
+                session(id, id, "/tmp", SessionState::AwaitingPermission, age),
+            );
+        }
+        assert_eq!(most_urgent_session(&sessions).unwrap().id, "p2");
+
+        sessions.retain(|_, s| s.state == SessionState::Complete);
+        assert!(most_urgent_session(&sessions).is_none());
+    }
+
+    #[test]
+    fn path_glob_expands_home_and_counts_ignore_state_filter() {
+        let (sessions, meta) = fixture();
+        let filter = SessionFilter {
+            states: vec![SessionState::Complete],
+            path_glob: Some("~/work/**".to_string()),
+            ..Default::default()
+        };
+        let result = run_query(&sessions, &meta, &filter, Some("/home/ann")).unwrap();
+        assert_eq!(ids(&result), vec!["d"]);
+        assert_eq!(result.state_counts[&SessionState::Processing], 1);
+        assert_eq!(result.state_counts[&SessionState::AwaitingInput], 1);
+        assert_eq!(result.state_counts[&SessionState::Complete], 1);
+    }
+
+    #[test]
+    fn tag_and_text_are_case_insensitive() {
+        let (sessions, meta) = fixture();
+        let by_tag = SessionFilter {
+            tag: Some("urgent".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&run_query(&sessions, &meta, &by_tag, None).unwrap()),
+            vec!["b"]
+        );
+
+        let by_text = SessionFilter {
+            text: Some("API".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ids(&run_query(&sessions, &meta, &by_text, None).unwrap()),
+            vec!["a", "d"]
+        );
+    }
+
+    #[test]
+    fn invalid_glob_is_an_error() {
+        let (sessions, meta) = fixture();
+        let filter = SessionFilter {
+            path_glob: Some("[".to_string()),
+            ..Default::default()
+        };
+        assert!(run_query(&sessions, &meta, &filter, None).is_err());
+    }
+}
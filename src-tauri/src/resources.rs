@@ -0,0 +1,414 @@
+use crate::channels::{self, ChannelEvent};
+use crate::{
+    cmd, load_session_meta, load_settings, notifications_muted, send_os_notification,
+    session_identity, tmux_scanner, vscode, AppState, C3Session, SessionMetrics,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// How often agent processes are sampled
+const SAMPLE_INTERVAL_SECS: u64 = 10;
+/// A session alerted for memory is alerted again only after dropping below
+/// this share of the limit
+const REARM_FRACTION: f64 = 0.8;
+/// Deepest process tree walked below an agent, in case of a cycle from PID reuse
+const MAX_TREE: usize = 256;
+
+/// One row of `ps -ax -o pid=,ppid=,rss=,time=,command=`
+#[derive(Debug, Clone)]
+struct Usage {
+    pid: u32,
+    ppid: u32,
+    rss_kb: u64,
+    cpu_secs: f64,
+    command: String,
+}
+
+/// CPU time as ps prints it: `[DD-][HH:]MM:SS[.ss]`
+fn parse_cpu_time(text: &str) -> Option<f64> {
+    let (days, clock) = match text.split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, text),
+    };
+    let secs = clock.split(':').try_fold(0.0, |total, part| {
+        Some(total * 60.0 + part.parse::<f64>().ok()?)
+    })?;
+    Some(days * 86_400.0 + secs)
+}
+
+fn parse_ps(output: &str) -> Vec<Usage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(Usage {
+                pid: parts.next()?.parse().ok()?,
+                ppid: parts.next()?.parse().ok()?,
+                rss_kb: parts.next()?.parse().ok()?,
+                cpu_secs: parse_cpu_time(parts.next()?)?,
+                command: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
+
+fn read_usage() -> Vec<Usage> {
+    cmd("ps")
+        .args(["-ax", "-o", "pid=,ppid=,rss=,time=,command="])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_ps(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// The process of each tmux pane, keyed by both its `%N` ID and its target
+fn pane_pids() -> HashMap<String, u32> {
+    let Ok(output) = cmd("tmux")
+        .args([
+            "list-panes",
+            "-a",
+            "-F",
+            "#{pane_id}\t#{session_name}:#{window_index}.#{pane_index}\t#{pane_pid}",
+        ])
+        .output()
+    else {
+        return HashMap::new();
+    };
+    let mut pids = HashMap::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if let [pane_id, target, pid] = parts[..] {
+            if let Ok(pid) = pid.trim().parse() {
+                pids.insert(pane_id.to_string(), pid);
+                pids.insert(target.to_string(), pid);
+            }
+        }
+    }
+    pids
+}
+
+fn is_shell(command: &str) -> bool {
+    let program = command.split_whitespace().next().unwrap_or_default();
+    tmux_scanner::is_shell_command(program.rsplit('/').next().unwrap_or(program))
+}
+
+/// The agent's process in a pane: the pane's own process, or what its
+/// shell is running. None at an idle shell.
+fn agent_pid(
+    processes: &HashMap<u32, &Usage>,
+    children: &HashMap<u32, Vec<u32>>,
+    pane_pid: u32,
+) -> Option<u32> {
+    let pane = processes.get(&pane_pid)?;
+    if !is_shell(&pane.command) {
+        return Some(pane_pid);
+    }
+    children
+        .get(&pane_pid)?
+        .iter()
+        .copied()
+        .find(|pid| processes.get(pid).is_some_and(|p| !is_shell(&p.command)))
+}
+
+/// A process and everything under it
+fn tree(children: &HashMap<u32, Vec<u32>>, pid: u32) -> Vec<u32> {
+    let mut pids = vec![pid];
+    let mut seen = HashSet::from([pid]);
+    let mut next = 0;
+    while next < pids.len() && pids.len() < MAX_TREE {
+        for child in children.get(&pids[next]).into_iter().flatten() {
+            if seen.insert(*child) {
+                pids.push(*child);
+            }
+        }
+        next += 1;
+    }
+    pids
+}
+
+/// What an agent's process tree is using
+#[derive(Debug, Clone, PartialEq)]
+struct Resources {
+    pid: u32,
+    rss_bytes: u64,
+    /// None on the first sample, with nothing to compare against
+    cpu_percent: Option<f64>,
+}
+
+/// CPU time of every process at the last sample, to turn the next into a rate
+#[derive(Default)]
+struct Sampler {
+    cpu_secs: HashMap<u32, f64>,
+    sampled_at: Option<Instant>,
+}
+
+impl Sampler {
+    /// Resources of each session's agent, given the process its pane or
+    /// terminal runs
+    fn sample(
+        &mut self,
+        usage: &[Usage],
+        roots: &HashMap<String, u32>,
+        now: Instant,
+    ) -> HashMap<String, Resources> {
+        let processes: HashMap<u32, &Usage> = usage.iter().map(|p| (p.pid, p)).collect();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for process in usage {
+            children.entry(process.ppid).or_default().push(process.pid);
+        }
+        let elapsed = self
+            .sampled_at
+            .map(|at| now.duration_since(at).as_secs_f64())
+            .filter(|secs| *secs > 0.0);
+
+        let mut sampled = HashMap::new();
+        for (session_id, root) in roots {
+            let Some(pid) = agent_pid(&processes, &children, *root) else {
+                continue;
+            };
+            let pids = tree(&children, pid);
+            let rss_kb: u64 = pids
+                .iter()
+                .filter_map(|pid| processes.get(pid))
+                .map(|p| p.rss_kb)
+                .sum();
+            // A process missing last time started since, so all its time is new
+            let cpu_secs: f64 = pids
+                .iter()
+                .filter_map(|pid| processes.get(pid))
+                .map(|p| (p.cpu_secs - self.cpu_secs.get(&p.pid).unwrap_or(&0.0)).max(0.0))
+                .sum();
+            sampled.insert(
+                session_id.clone(),
+                Resources {
+                    pid,
+                    rss_bytes: rss_kb * 1024,
+                    cpu_percent: elapsed.map(|secs| (cpu_secs / secs * 1000.0).round() / 10.0),
+                },
+            );
+        }
+        self.cpu_secs = usage.iter().map(|p| (p.pid, p.cpu_secs)).collect();
+        self.sampled_at = Some(now);
+        sampled
+    }
+}
+
+/// The process each session's agent runs under: its tmux pane's, or the
+/// agent itself for VS Code sessions
+fn session_roots(sessions: &[C3Session], panes: &HashMap<String, u32>) -> HashMap<String, u32> {
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let root = match session.id.strip_prefix(vscode::SESSION_PREFIX) {
+                Some(pid) => pid.parse().ok()?,
+                None => *panes.get(session_identity::tmux_pane(session)?)?,
+            };
+            Some((session.id.clone(), root))
+        })
+        .collect()
+}
+
+/// The session's metrics with its resources filled in, or cleared when its
+/// agent wasn't found
+fn with_resources(
+    metrics: Option<&SessionMetrics>,
+    resources: Option<&Resources>,
+) -> Option<SessionMetrics> {
+    let mut metrics = metrics.cloned().unwrap_or_default();
+    metrics.pid = resources.map(|r| r.pid);
+    metrics.rss_bytes = resources.map(|r| r.rss_bytes);
+    metrics.cpu_percent = resources.and_then(|r| r.cpu_percent);
+    (metrics != SessionMetrics::default()).then_some(metrics)
+}
+
+pub(crate) fn format_memory(bytes: u64) -> String {
+    const MB: u64 = 1024 * 1024;
+    if bytes < 1024 * MB {
+        format!("{} MB", bytes / MB)
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024 * MB) as f64)
+    }
+}
+
+/// Whether to alert for a session using `rss_bytes`, once per crossing of the limit
+fn crossed(
+    alerted: &mut HashSet<String>,
+    session_id: &str,
+    rss_bytes: u64,
+    limit_bytes: u64,
+) -> bool {
+    if (rss_bytes as f64) < limit_bytes as f64 * REARM_FRACTION {
+        alerted.remove(session_id);
+    }
+    rss_bytes >= limit_bytes && alerted.insert(session_id.to_string())
+}
+
+/// Sample every session's agent, update its metrics, and notify for those
+/// over the memory limit
+fn sample(state: &AppState, sampler: &mut Sampler, alerted: &mut HashSet<String>) {
+    let sessions: Vec<C3Session> = state.sessions.read().values().cloned().collect();
+    let roots = session_roots(&sessions, &pane_pids());
+    let sampled = sampler.sample(&read_usage(), &roots, Instant::now());
+
+    let updated: Vec<C3Session> = {
+        let mut sessions = state.sessions.write();
+        sessions
+            .values_mut()
+            .filter_map(|session| {
+                let metrics = with_resources(session.metrics.as_ref(), sampled.get(&session.id));
+                (metrics != session.metrics).then(|| {
+                    session.metrics = metrics;
+                    session.clone()
+                })
+            })
+            .collect()
+    };
+    for session in updated {
+        state.events.session_updated(session);
+    }
+
+    alerted.retain(|id| sampled.contains_key(id));
+    let settings = load_settings();
+    if settings.memory_alert_mb == 0 {
+        return;
+    }
+    let limit_bytes = settings.memory_alert_mb as u64 * 1024 * 1024;
+    let meta = load_session_meta();
+    for session in &sessions {
+        let Some(resources) = sampled.get(&session.id) else {
+            continue;
+        };
+        if !crossed(alerted, &session.id, resources.rss_bytes, limit_bytes) {
+            continue;
+        }
+        log::warn!(
+            "{} (PID {}) is using {} of memory",
+            session.id,
+            resources.pid,
+            format_memory(resources.rss_bytes)
+        );
+        if !settings.notifications_enabled || notifications_muted(&meta, session) {
+            continue;
+        }
+        let message = format!("Over the {} memory limit", format_memory(limit_bytes));
+        let title = format!("c3 — {}", session.project_name);
+        let subtitle = format!("Using {} of memory", format_memory(resources.rss_bytes));
+        send_os_notification(
+            &message,
+            &title,
+            &subtitle,
+            &None,
+            Some(&session.id),
+            state.hook_port,
+        );
+        channels::notify(
+            ChannelEvent::Alert,
+            &title,
+            &format!("{}: {}", subtitle, message),
+            Some(&session.id),
+        );
+    }
+}
+
+/// Sample the CPU and memory of each session's agent process into its
+/// metrics, notifying when one goes over `memory_alert_mb`
+pub async fn start_resource_monitor(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut sampler = Sampler::default();
+    let mut alerted: HashSet<String> = HashSet::new();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(SAMPLE_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Resource monitor shutting down");
+                break;
+            }
+        }
+        let state_sample = state.clone();
+        (sampler, alerted) = match tokio::task::spawn_blocking(move || {
+            sample(&state_sample, &mut sampler, &mut alerted);
+            (sampler, alerted)
+        })
+        .await
+        {
+            Ok(kept) => kept,
+            Err(e) => {
+                log::error!("Resource sampling failed: {}", e);
+                (Sampler::default(), HashSet::new())
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const PS: &str = "\
+  100     1   4000     0:01.50 tmux new-session -d
+  110   100   3000     0:00.10 -zsh
+  120   110 900000     1:02.00 node /opt/homebrew/bin/claude
+  121   120 100000     0:03.00 npx mcp-server
+  130   100   3000     0:00.10 /bin/bash
+  200     1 500000  1-00:00:00 claude --resume
+";
+
+    #[test]
+    fn agent_trees_are_sampled_for_memory_and_cpu() {
+        assert_eq!(parse_cpu_time("0:01.50"), Some(1.5));
+        assert_eq!(parse_cpu_time("01:02:03"), Some(3723.0));
+        assert_eq!(parse_cpu_time("1-00:00:00"), Some(86_400.0));
+
+        let usage = parse_ps(PS);
+        let roots = HashMap::from([
+            ("tmux:%1".to_string(), 110),
+            ("tmux:%2".to_string(), 130),
+            ("vscode:200".to_string(), 200),
+        ]);
+        let mut sampler = Sampler::default();
+        let start = Instant::now();
+        let first = sampler.sample(&usage, &roots, start);
+        assert_eq!(
+            first["tmux:%1"],
+            Resources {
+                pid: 120,
+                rss_bytes: 1_000_000 * 1024,
+                cpu_percent: None,
+            }
+        );
+        // An idle shell has no agent
+        assert!(!first.contains_key("tmux:%2"));
+        assert_eq!(first["vscode:200"].pid, 200);
+
+        // Five CPU seconds in ten: half a core
+        let busier = PS.replace("1:02.00", "1:07.00");
+        let second = sampler.sample(&parse_ps(&busier), &roots, start + Duration::from_secs(10));
+        assert_eq!(second["tmux:%1"].cpu_percent, Some(50.0));
+        assert_eq!(second["vscode:200"].cpu_percent, Some(0.0));
+
+        let metrics = with_resources(None, Some(&second["tmux:%1"])).unwrap();
+        assert_eq!(metrics.pid, Some(120));
+        assert_eq!(with_resources(Some(&metrics), None), None);
+        assert_eq!(format_memory(second["tmux:%1"].rss_bytes), "976 MB");
+        assert_eq!(format_memory(8 * 1024 * 1024 * 1024), "8.0 GB");
+    }
+
+    #[test]
+    fn memory_alerts_once_per_crossing() {
+        let mut alerted = HashSet::new();
+        let limit = 1000;
+        assert!(!crossed(&mut alerted, "tmux:%1", 900, limit));
+        assert!(crossed(&mut alerted, "tmux:%1", 1200, limit));
+        assert!(!crossed(&mut alerted, "tmux:%1", 1500, limit));
+        // Dipping just under the limit doesn't re-arm it
+        assert!(!crossed(&mut alerted, "tmux:%1", 950, limit));
+        assert!(!crossed(&mut alerted, "tmux:%1", 1100, limit));
+        assert!(!crossed(&mut alerted, "tmux:%1", 700, limit));
+        assert!(crossed(&mut alerted, "tmux:%1", 1100, limit));
+    }
+}
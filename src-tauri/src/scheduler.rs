@@ -0,0 +1,453 @@
+use crate::ignore::expand_home;
+use crate::launch_queue::{self, Requested};
+use crate::{agents, default_agent_command, profiles, start_agent, AppState};
+use chrono::{DateTime, Datelike, Duration, Local, Timelike, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// How often schedules are checked for runs that are due
+const CHECK_INTERVAL_SECS: u64 = 30;
+/// Runs missed while the Mac slept or C3 was closed are made up, once, if
+/// they were due this recently
+const CATCH_UP_MINUTES: i64 = 60;
+/// How far ahead the next run is looked for
+const NEXT_RUN_HORIZON_DAYS: i64 = 366;
+
+/// Read-modify-write of schedules.json from commands and the scheduler
+static SCHEDULES_LOCK: Mutex<()> = Mutex::new(());
+
+/// "Every weekday at 9:00, start claude in ~/notes with this prompt"
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledTask {
+    /// Empty when saving a new task
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "crate::default_true")]
+    pub enabled: bool,
+    /// Cron fields in local time, `minute hour day month weekday` (e.g.
+    /// `0 9 * * 1-5`), or `@hourly`, `@daily`, `@weekly`, `@monthly`
+    pub schedule: String,
+    /// Where the agent starts; `~` is expanded
+    pub cwd: String,
+    /// Agent kind, e.g. `claude`; None for the default agent
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// First message for the agent
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Last saved or enabled; runs due before this aren't made up
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+    /// Why the last run failed to start
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// A scheduled task and when it runs next
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleEntry {
+    pub task: ScheduledTask,
+    /// None when disabled or the schedule never matches
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// A parsed cron expression: each field is a bit set of the values it allows
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Cron {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day and weekday were both restricted; as in cron, either one matching
+    /// is enough
+    either_day: bool,
+}
+
+fn number(value: &str, field: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{:?} is not a number in {:?}", value, field))
+}
+
+/// `*`, `5`, `1-5`, `*/15`, `0-30/10` and comma lists of them
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut bits = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, number(step, part)?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (number(start, part)?, number(end, part)?)
+        } else {
+            let value = number(range, part)?;
+            // `5/15` runs from 5 to the end of the range
+            (value, if step > 1 { max } else { value })
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(format!("{:?} is outside {}-{}", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+pub(crate) fn parse_cron(expression: &str) -> Result<Cron, String> {
+    let expression = match expression.trim() {
+        "@hourly" => "0 * * * *",
+        "@daily" | "@midnight" => "0 0 * * *",
+        "@weekly" => "0 0 * * 0",
+        "@monthly" => "0 0 1 * *",
+        other => other,
+    };
+    let fields: Vec<&str> = expression.split_whitespace().collect();
+    let [minute, hour, day, month, weekday] = fields[..] else {
+        return Err("Expected five fields: minute hour day month weekday".to_string());
+    };
+    let mut weekdays = parse_field(weekday, 0, 7)?;
+    // Sunday is 0 or 7
+    if weekdays & (1 << 7) != 0 {
+        weekdays = (weekdays | 1) & !(1 << 7);
+    }
+    Ok(Cron {
+        minutes: parse_field(minute, 0, 59)?,
+        hours: parse_field(hour, 0, 23)?,
+        days: parse_field(day, 1, 31)?,
+        months: parse_field(month, 1, 12)?,
+        weekdays,
+        either_day: !day.starts_with('*') && !weekday.starts_with('*'),
+    })
+}
+
+impl Cron {
+    fn matches(&self, time: &DateTime<Local>) -> bool {
+        let has = |bits: u64, value: u32| bits & (1 << value) != 0;
+        let day = has(self.days, time.day());
+        let weekday = has(self.weekdays, time.weekday().num_days_from_sunday());
+        let day_matches = if self.either_day {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        day_matches
+            && has(self.minutes, time.minute())
+            && has(self.hours, time.hour())
+            && has(self.months, time.month())
+    }
+}
+
+fn floor_minute(time: DateTime<Utc>) -> DateTime<Utc> {
+    time - Duration::seconds(time.second() as i64) - Duration::nanoseconds(time.nanosecond() as i64)
+}
+
+/// The latest minute in (`after`, `now`] the schedule matches
+fn latest_due(cron: &Cron, after: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut minute = floor_minute(now);
+    while minute > after {
+        if cron.matches(&minute.with_timezone(&Local)) {
+            return Some(minute);
+        }
+        minute -= Duration::minutes(1);
+    }
+    None
+}
+
+/// The first minute after `now` the schedule matches
+fn next_run(cron: &Cron, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let end = now + Duration::days(NEXT_RUN_HORIZON_DAYS);
+    let mut minute = floor_minute(now) + Duration::minutes(1);
+    while minute < end {
+        if cron.matches(&minute.with_timezone(&Local)) {
+            return Some(minute);
+        }
+        minute += Duration::minutes(1);
+    }
+    None
+}
+
+fn schedules_path() -> PathBuf {
+    profiles::profile_dir().join("schedules.json")
+}
+
+fn load() -> Vec<ScheduledTask> {
+    fs::read_to_string(schedules_path())
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(tasks: &[ScheduledTask]) -> Result<(), String> {
+    let path = schedules_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let json = serde_json::to_string_pretty(tasks).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Change the saved tasks, returning them with their next runs
+fn update(
+    change: impl FnOnce(&mut Vec<ScheduledTask>) -> Result<(), String>,
+) -> Result<Vec<ScheduleEntry>, String> {
+    let _guard = SCHEDULES_LOCK.lock();
+    let mut tasks = load();
+    change(&mut tasks)?;
+    save(&tasks)?;
+    Ok(entries(tasks))
+}
+
+fn entries(tasks: Vec<ScheduledTask>) -> Vec<ScheduleEntry> {
+    let now = Utc::now();
+    tasks
+        .into_iter()
+        .map(|task| {
+            let next_run = parse_cron(&task.schedule)
+                .ok()
+                .filter(|_| task.enabled)
+                .and_then(|cron| next_run(&cron, now));
+            ScheduleEntry { task, next_run }
+        })
+        .collect()
+}
+
+/// The task's directory and agent command
+fn resolve(task: &ScheduledTask) -> Result<(String, &'static str), String> {
+    let home = std::env::var("HOME").ok();
+    let cwd = expand_home(task.cwd.trim(), home.as_deref());
+    if !Path::new(&cwd).is_dir() {
+        return Err(format!("{} doesn't exist", cwd));
+    }
+    let agent = match task.agent.as_deref().filter(|kind| !kind.is_empty()) {
+        Some(kind) => {
+            agents::by_kind(kind)
+                .ok_or_else(|| format!("Unknown agent: {}", kind))?
+                .launch_command
+        }
+        None => default_agent_command(),
+    };
+    Ok((cwd, agent))
+}
+
+/// Start the task's agent through the same path as new tasks and worktrees.
+/// Scheduled runs pass `queue` to wait behind the concurrency limits; runs
+/// the user asked for start right away.
+fn run(
+    task: &ScheduledTask,
+    at: DateTime<Utc>,
+    queue: Option<&AppState>,
+) -> Result<String, String> {
+    let result = resolve(task).and_then(|(cwd, agent)| match queue {
+        Some(state) => {
+            let label = format!("Scheduled: {}", task.name);
+            let launch =
+                launch_queue::launch(label, &cwd, Some(agent), task.prompt.as_deref(), None);
+            launch_queue::request(state, launch).map(|requested| match requested {
+                Requested::Started(target) => target,
+                Requested::Queued => "the launch queue".to_string(),
+            })
+        }
+        None => start_agent(&cwd, agent, task.prompt.as_deref()),
+    });
+    match &result {
+        Ok(target) => log::info!("Started scheduled task {:?} in {}", task.name, target),
+        Err(e) => log::warn!("Scheduled task {:?} failed: {}", task.name, e),
+    }
+    let _guard = SCHEDULES_LOCK.lock();
+    let mut tasks = load();
+    if let Some(saved) = tasks.iter_mut().find(|t| t.id == task.id) {
+        saved.last_run = Some(at);
+        saved.last_error = result.as_ref().err().cloned();
+        if let Err(e) = save(&tasks) {
+            log::warn!("Failed to save schedules: {}", e);
+        }
+    }
+    result
+}
+
+/// Start every enabled task that came due since its last run
+fn run_due(state: &AppState, now: DateTime<Utc>) {
+    let due: Vec<(ScheduledTask, DateTime<Utc>)> = load()
+        .into_iter()
+        .filter(|task| task.enabled)
+        .filter_map(|task| {
+            let cron = parse_cron(&task.schedule)
+                .map_err(|e| log::warn!("Invalid schedule for {:?}: {}", task.name, e))
+                .ok()?;
+            let after = [
+                Some(task.updated_at),
+                task.last_run,
+                Some(now - Duration::minutes(CATCH_UP_MINUTES)),
+            ]
+            .into_iter()
+            .flatten()
+            .max()?;
+            let at = latest_due(&cron, after, now)?;
+            Some((task, at))
+        })
+        .collect();
+    for (task, at) in due {
+        let _ = run(&task, at, Some(state));
+    }
+}
+
+/// Start scheduled tasks as they come due
+pub async fn start_scheduler(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("Scheduler shutting down");
+                break;
+            }
+        }
+        let state = state.clone();
+        let _ = tokio::task::spawn_blocking(move || run_due(&state, Utc::now())).await;
+    }
+}
+
+// Tauri command: List scheduled tasks with their next runs
+#[tauri::command]
+#[specta::specta]
+pub fn list_scheduled_tasks() -> Vec<ScheduleEntry> {
+    entries(load())
+}
+
+// Tauri command: Add a scheduled task (empty id) or replace the one with its id
+#[tauri::command]
+#[specta::specta]
+pub fn save_scheduled_task(task: ScheduledTask) -> Result<Vec<ScheduleEntry>, String> {
+    parse_cron(&task.schedule)?;
+    if task.name.trim().is_empty() || task.cwd.trim().is_empty() {
+        return Err("A scheduled task needs a name and a folder".to_string());
+    }
+    update(|tasks| {
+        let mut task = ScheduledTask {
+            name: task.name.trim().to_string(),
+            schedule: task.schedule.trim().to_string(),
+            updated_at: Utc::now(),
+            ..task
+        };
+        match tasks
+            .iter_mut()
+            .find(|t| !task.id.is_empty() && t.id == task.id)
+        {
+            Some(saved) => {
+                task.last_run = saved.last_run;
+                task.last_error = saved.last_error.take();
+                *saved = task;
+            }
+            None => {
+                task.id = uuid::Uuid::new_v4().to_string();
+                task.last_run = None;
+                task.last_error = None;
+                tasks.push(task);
+            }
+        }
+        Ok(())
+    })
+}
+
+// Tauri command: Switch a scheduled task on or off
+#[tauri::command]
+#[specta::specta]
+pub fn set_scheduled_task_enabled(id: String, enabled: bool) -> Result<Vec<ScheduleEntry>, String> {
+    update(|tasks| {
+        let task = tasks
+            .iter_mut()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Scheduled task not found: {}", id))?;
+        task.enabled = enabled;
+        task.updated_at = Utc::now();
+        Ok(())
+    })
+}
+
+// Tauri command: Delete a scheduled task
+#[tauri::command]
+#[specta::specta]
+pub fn delete_scheduled_task(id: String) -> Result<Vec<ScheduleEntry>, String> {
+    update(|tasks| {
+        tasks.retain(|t| t.id != id);
+        Ok(())
+    })
+}
+
+// Tauri command: Start a scheduled task now, whatever its schedule; returns its tmux target
+#[tauri::command]
+#[specta::specta]
+pub async fn run_scheduled_task(id: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let task = load()
+            .into_iter()
+            .find(|t| t.id == id)
+            .ok_or_else(|| format!("Scheduled task not found: {}", id))?;
+        run(&task, Utc::now(), None)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        // 2026-03-02 is a Monday
+        Local
+            .with_ymd_and_hms(2026, 3, day, hour, minute, 0)
+            .single()
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn weekday_mornings() {
+        let cron = parse_cron("0 9 * * 1-5").unwrap();
+        // Monday 9:00 came due at 9:00:20, once
+        let now = local(2, 9, 0) + Duration::seconds(20);
+        assert_eq!(
+            latest_due(&cron, now - Duration::hours(1), now),
+            Some(local(2, 9, 0))
+        );
+        assert_eq!(latest_due(&cron, local(2, 9, 0), now), None);
+        // Friday's run is followed by Monday's
+        assert_eq!(next_run(&cron, local(6, 9, 0)), Some(local(9, 9, 0)));
+
+        assert_eq!(
+            parse_cron("*/15 * * * *").unwrap().minutes,
+            1 | 1 << 15 | 1 << 30 | 1 << 45
+        );
+        assert_eq!(parse_cron("0 0 * * 7").unwrap().weekdays, 1);
+        assert_eq!(
+            parse_cron("@daily").unwrap(),
+            parse_cron("0 0 * * *").unwrap()
+        );
+        assert!(parse_cron("0 9 * *").is_err());
+        assert!(parse_cron("60 9 * * *").is_err());
+        assert!(parse_cron("0 9 * * mon").is_err());
+
+        // Day and weekday both set: either one
+        let either = parse_cron("0 12 1 * 1").unwrap();
+        assert!(either.matches(&local(2, 12, 0).with_timezone(&Local)));
+        assert!(either.matches(&local(1, 12, 0).with_timezone(&Local)));
+        assert!(!either.matches(&local(3, 12, 0).with_timezone(&Local)));
+    }
+}
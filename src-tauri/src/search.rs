@@ -0,0 +1,311 @@
+use crate::transcript::{self, Role};
+use crate::{tmux_scanner, AppState};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Stop after this many matches across all transcripts
+const MAX_MATCHES: usize = 200;
+/// A single transcript can contribute at most this many matches
+const MAX_MATCHES_PER_TRANSCRIPT: usize = 20;
+/// Characters of context on each side of a match
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+const MIN_QUERY_CHARS: usize = 2;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchScope {
+    /// Transcripts of sessions currently shown in C3
+    #[default]
+    Active,
+    /// Every transcript on disk, including sessions C3 no longer tracks
+    All,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptMatch {
+    /// C3 session id when the transcript belongs to a tracked session
+    pub session_id: Option<String>,
+    pub agent_kind: String,
+    pub project_path: Option<String>,
+    pub transcript_path: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub role: Role,
+    pub snippet: String,
+}
+
+struct Transcript {
+    path: PathBuf,
+    agent_kind: String,
+    session_id: Option<String>,
+    project_path: Option<String>,
+}
+
+/// Transcripts of tracked sessions, keyed by path
+fn active_transcripts(state: &AppState) -> HashMap<PathBuf, Transcript> {
+    state
+        .sessions
+        .read()
+        .values()
+        .filter_map(|session| {
+            let (agent_kind, path) = transcript::session_transcript(session)?;
+            Some((
+                path.clone(),
+                Transcript {
+                    path,
+                    agent_kind,
+                    session_id: Some(session.id.clone()),
+                    project_path: session.project_path.clone(),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn all_transcripts(active: HashMap<PathBuf, Transcript>) -> Vec<Transcript> {
+    let mut transcripts = active;
+    for (agent_kind, dir) in [
+        ("claude", tmux_scanner::claude_projects_dir()),
+        ("codex", tmux_scanner::codex_sessions_dir()),
+        ("omp", tmux_scanner::omp_sessions_dir()),
+    ] {
+        let mut files = Vec::new();
+        tmux_scanner::collect_jsonl_files(&dir, &mut files);
+        for path in files {
+            transcripts
+                .entry(path.clone())
+                .or_insert_with(|| Transcript {
+                    project_path: transcript::transcript_cwd(&path),
+                    path,
+                    agent_kind: agent_kind.to_string(),
+                    session_id: None,
+                });
+        }
+    }
+    transcripts.into_values().collect()
+}
+
+/// Up to `SNIPPET_CONTEXT_CHARS` characters either side of the match, on one line
+fn snippet(text: &str, byte_start: usize, byte_len: usize) -> String {
+    let before: String = text[..byte_start]
+        .chars()
+        .rev()
+        .take(SNIPPET_CONTEXT_CHARS)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    let after: String = text[byte_start + byte_len..]
+        .chars()
+        .take(SNIPPET_CONTEXT_CHARS)
+        .collect();
+    let mut snippet = String::new();
+    if before.len() < byte_start {
+        snippet.push('…');
+    }
+    snippet.push_str(&before);
+    snippet.push_str(&text[byte_start..byte_start + byte_len]);
+    snippet.push_str(&after);
+    if byte_start + byte_len + after.len() < text.len() {
+        snippet.push('…');
+    }
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Case-insensitive position of `needle_lower` in `text`, as a byte range of `text`
+fn find_case_insensitive(text: &str, needle_lower: &str) -> Option<(usize, usize)> {
+    // Lowercasing can change byte lengths, so compare char by char
+    let needle: Vec<char> = needle_lower.chars().collect();
+    let indices: Vec<(usize, char)> = text.char_indices().collect();
+    for start in 0..indices.len() {
+        if start + needle.len() > indices.len() {
+            break;
+        }
+        let matched = needle.iter().enumerate().all(|(i, n)| {
+            let mut lower = indices[start + i].1.to_lowercase();
+            lower.next() == Some(*n) && lower.next().is_none()
+        });
+        if matched {
+            let byte_start = indices[start].0;
+            let byte_end = indices
+                .get(start + needle.len())
+                .map(|(i, _)| *i)
+                .unwrap_or(text.len());
+            return Some((byte_start, byte_end - byte_start));
+        }
+    }
+    None
+}
+
+/// Whether `query` is written the same inside a JSON string, so raw lines
+/// without it can be skipped. Quotes, backslashes, control characters and
+/// non-ASCII text may be escaped there.
+fn raw_matchable(query: &str) -> bool {
+    query.is_ascii()
+        && serde_json::to_string(query).is_ok_and(|json| json[1..json.len() - 1] == *query)
+}
+
+fn search_transcript(transcript: &Transcript, query_lower: &str, out: &mut Vec<TranscriptMatch>) {
+    let Ok(file) = fs::File::open(&transcript.path) else {
+        return;
+    };
+    let prefilter = raw_matchable(query_lower);
+    let mut found = 0;
+    for line in BufReader::new(file).lines().map_while(Result::ok) {
+        // Cheap pre-filter before parsing JSON
+        if prefilter && !line.to_lowercase().contains(query_lower) {
+            continue;
+        }
+        let Some(message) = transcript::parse_line(&transcript.agent_kind, &line) else {
+            continue;
+        };
+        let text = message.text();
+        let Some((start, len)) = find_case_insensitive(&text, query_lower) else {
+            continue;
+        };
+        out.push(TranscriptMatch {
+            session_id: transcript.session_id.clone(),
+            agent_kind: transcript.agent_kind.clone(),
+            project_path: transcript.project_path.clone(),
+            transcript_path: transcript.path.to_string_lossy().to_string(),
+            timestamp: message.timestamp,
+            role: message.role,
+            snippet: snippet(&text, start, len),
+        });
+        found += 1;
+        if found >= MAX_MATCHES_PER_TRANSCRIPT {
+            return;
+        }
+    }
+}
+
+fn search(state: &AppState, query: &str, scope: SearchScope) -> Vec<TranscriptMatch> {
+    let query_lower = query.trim().to_lowercase();
+    let active = active_transcripts(state);
+    let transcripts = match scope {
+        SearchScope::Active => active.into_values().collect(),
+        SearchScope::All => all_transcripts(active),
+    };
+
+    let mut matches = Vec::new();
+    for transcript in &transcripts {
+        search_transcript(transcript, &query_lower, &mut matches);
+    }
+    matches.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+    matches.truncate(MAX_MATCHES);
+
+    let redactor = state.redactor.read();
+    for m in &mut matches {
+        m.snippet = redactor.redact_string(std::mem::take(&mut m.snippet));
+    }
+    matches
+}
+
+// Tauri command: Search conversation text in agent transcripts
+#[tauri::command]
+#[specta::specta]
+pub async fn search_transcripts(
+    state: tauri::State<'_, Arc<AppState>>,
+    query: String,
+    scope: Option<SearchScope>,
+) -> Result<Vec<TranscriptMatch>, String> {
+    if query.trim().chars().count() < MIN_QUERY_CHARS {
+        return Err(format!(
+            "Search query must be at least {} characters",
+            MIN_QUERY_CHARS
+        ));
+    }
+    let state = state.inner().clone();
+    let scope = scope.unwrap_or_default();
+    tokio::task::spawn_blocking(move || search(&state, &query, scope))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_insensitive_match_returns_original_byte_range() {
+        let text = "Ünïcode then the Migration Script";
+        let (start, len) = find_case_insensitive(text, "migration script").unwrap();
+        assert_eq!(&text[start..start + len], "Migration Script");
+        assert!(find_case_insensitive(text, "missing").is_none());
+    }
+
+    #[test]
+    fn snippet_trims_context_and_collapses_whitespace() {
+        let text = format!("{}\nneedle in\n  the haystack", "x".repeat(100));
+        let (start, len) = find_case_insensitive(&text, "needle").unwrap();
+        let snippet = snippet(&text, start, len);
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with("needle in the haystack"));
+        assert_eq!(
+            snippet.chars().filter(|c| *c == 'x').count(),
+            SNIPPET_CONTEXT_CHARS - 1
+        );
+    }
+
+    #[test]
+    fn finds_codex_messages_but_not_injected_context() {
+        let dir = std::env::temp_dir().join(format!("c3-search-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("conversation.jsonl");
+        fs::write(
+            &path,
+            include_str!("../fixtures/jsonl/codex/conversation.jsonl"),
+        )
+        .unwrap();
+        let transcript = Transcript {
+            path: path.clone(),
+            agent_kind: "codex".to_string(),
+            session_id: Some("tmux:shop:0.0".to_string()),
+            project_path: Some("/Users/dev/code/shop".to_string()),
+        };
+
+        let mut matches = Vec::new();
+        search_transcript(&transcript, "migration script", &mut matches);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].role, Role::User);
+        assert_eq!(matches[0].session_id.as_deref(), Some("tmux:shop:0.0"));
+
+        matches.clear();
+        search_transcript(&transcript, "environment_context", &mut matches);
+        assert!(matches.is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn finds_text_that_json_escapes() {
+        let dir = std::env::temp_dir().join(format!("c3-search-escaped-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("conversation.jsonl");
+        fs::write(
+            &path,
+            r#"{"parentUuid":null,"isSidechain":false,"type":"user","message":{"role":"user","content":"Rename \"Caf\u00e9\" in C:\\tmp"},"uuid":"u1","timestamp":"2026-03-02T17:00:00.000Z"}"#,
+        )
+        .unwrap();
+        let transcript = Transcript {
+            path: path.clone(),
+            agent_kind: "claude".to_string(),
+            session_id: None,
+            project_path: None,
+        };
+
+        for query in ["\"café\"", "c:\\tmp", "rename"] {
+            let mut matches = Vec::new();
+            search_transcript(&transcript, query, &mut matches);
+            assert_eq!(matches.len(), 1, "{}", query);
+        }
+        assert!(raw_matchable("migration script"));
+        assert!(!raw_matchable("say \"hi\""));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
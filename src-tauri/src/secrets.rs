@@ -0,0 +1,363 @@
+use crate::{cmd, profiles, AppSettings};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Keychain service / libsecret attribute all of C3's secrets are under
+const SERVICE: &str = "C3";
+/// What settings.json holds in place of a secret that's in the store
+const REFERENCE_PREFIX: &str = "c3-secret:";
+/// How long a secret that couldn't be looked up (e.g. a locked keyring)
+/// isn't asked for again
+const MISS_RETRY: Duration = Duration::from_secs(60);
+/// Most items `clear_all` deletes one by one from the Keychain
+#[cfg(target_os = "macos")]
+const MAX_CLEARED: usize = 1000;
+
+/// A secret as last looked up
+struct Cached {
+    value: Option<String>,
+    at: Instant,
+}
+
+/// Secrets already looked up, misses included, as settings are loaded on
+/// hot paths
+static CACHE: Mutex<Option<HashMap<String, Cached>>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn lookup(name: &str) -> Option<String> {
+    let output = cmd("security")
+        .args(["find-generic-password", "-s", SERVICE, "-a", name, "-w"])
+        .output()
+        .ok()?;
+    output.status.success().then(|| {
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches('\n')
+            .to_string()
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn store(name: &str, value: &str) -> Result<(), String> {
+    // Commands on stdin keep the secret out of the process list, and hex
+    // keeps it clear of `security`'s quoting
+    let hex: String = value.bytes().map(|byte| format!("{:02x}", byte)).collect();
+    let command = format!(
+        "add-generic-password -U -s {} -a \"{}\" -X {}\n",
+        SERVICE, name, hex
+    );
+    let mut security = cmd("security");
+    security.arg("-i");
+    crate::at_rest::run(security, command.as_bytes(), "security").map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn remove(name: &str) -> Result<(), String> {
+    let mut security = cmd("security");
+    security.args(["delete-generic-password", "-s", SERVICE, "-a", name]);
+    crate::at_rest::run(security, &[], "security").map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn remove_all() -> Result<usize, String> {
+    // Each call deletes the first match, so repeat until none are left
+    let mut removed = 0;
+    while removed < MAX_CLEARED {
+        let mut security = cmd("security");
+        security.args(["delete-generic-password", "-s", SERVICE]);
+        if crate::at_rest::run(security, &[], "security").is_err() {
+            break;
+        }
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn lookup(name: &str) -> Option<String> {
+    let output = cmd("secret-tool")
+        .args(["lookup", "service", SERVICE, "account", name])
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn store(name: &str, value: &str) -> Result<(), String> {
+    let mut secret_tool = cmd("secret-tool");
+    secret_tool
+        .arg("store")
+        .arg(format!("--label=C3: {}", name))
+        .args(["service", SERVICE, "account", name]);
+    crate::at_rest::run(secret_tool, value.as_bytes(), "secret-tool").map(|_| ())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn remove(name: &str) -> Result<(), String> {
+    let mut secret_tool = cmd("secret-tool");
+    secret_tool.args(["clear", "service", SERVICE, "account", name]);
+    crate::at_rest::run(secret_tool, &[], "secret-tool").map(|_| ())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn remove_all() -> Result<usize, String> {
+    // Without secret-tool nothing can have been stored
+    let Ok(output) = cmd("secret-tool")
+        .args(["clear", "service", SERVICE])
+        .output()
+    else {
+        return Ok(0);
+    };
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(1)
+}
+
+fn remember(name: &str, value: Option<String>) {
+    CACHE.lock().get_or_insert_with(HashMap::new).insert(
+        name.to_string(),
+        Cached {
+            value,
+            at: Instant::now(),
+        },
+    );
+}
+
+/// The secret stored under `name` in the Keychain (macOS) or libsecret
+pub(crate) fn get(name: &str) -> Option<String> {
+    if let Some(cached) = CACHE.lock().as_ref().and_then(|cache| cache.get(name)) {
+        if cached.value.is_some() || cached.at.elapsed() < MISS_RETRY {
+            return cached.value.clone();
+        }
+    }
+    let value = lookup(name).filter(|value| !value.is_empty());
+    if value.is_none() {
+        log::info!("No {} in the keyring", name);
+    }
+    remember(name, value.clone());
+    value
+}
+
+/// Store `value` under `name`, replacing what was there
+pub(crate) fn set(name: &str, value: &str) -> Result<(), String> {
+    if get(name).as_deref() == Some(value) {
+        return Ok(());
+    }
+    store(name, value)?;
+    remember(name, Some(value.to_string()));
+    Ok(())
+}
+
+/// Remove the secret stored under `name`
+pub(crate) fn delete(name: &str) -> Result<(), String> {
+    remove(name)?;
+    remember(name, None);
+    Ok(())
+}
+
+/// Remove every secret C3 stored, for uninstalling; returns how many
+/// items went, where the store says
+pub(crate) fn clear_all() -> Result<usize, String> {
+    let removed = remove_all()?;
+    *CACHE.lock() = None;
+    Ok(removed)
+}
+
+/// The settings fields that hold credentials, by name
+fn fields(settings: &mut AppSettings) -> Vec<(&'static str, &mut String)> {
+    let mut fields = vec![
+        ("telegram.bot_token", &mut settings.telegram.bot_token),
+        (
+            "push.pushover_app_token",
+            &mut settings.push.pushover_app_token,
+        ),
+        (
+            "push.pushover_user_key",
+            &mut settings.push.pushover_user_key,
+        ),
+        ("email.smtp_password", &mut settings.email.smtp_password),
+    ];
+    fields.extend(
+        settings
+            .slack_webhook_url
+            .as_mut()
+            .map(|value| ("slack_webhook_url", value)),
+    );
+    fields.extend(
+        settings
+            .push
+            .ntfy_token
+            .as_mut()
+            .map(|value| ("push.ntfy_token", value)),
+    );
+    fields
+}
+
+/// The store name for `field` in `profile`'s settings. It stays the same
+/// when the value changes, so an edit replaces the item instead of adding
+/// one.
+fn secret_name(profile: &str, field: &str) -> String {
+    format!("{}/{}", profile, field)
+}
+
+fn referenced(value: &str) -> Option<&str> {
+    value.strip_prefix(REFERENCE_PREFIX)
+}
+
+/// Replace references with the secrets they name. One that can't be looked
+/// up (e.g. a locked keyring) stays a reference, so saving keeps it.
+pub(crate) fn reveal(settings: &mut AppSettings) {
+    reveal_with(settings, get);
+}
+
+fn reveal_with(settings: &mut AppSettings, get: impl Fn(&str) -> Option<String>) {
+    for (_, value) in fields(settings) {
+        if let Some(secret) = referenced(value).and_then(&get) {
+            *value = secret;
+        }
+    }
+}
+
+/// `settings` as written to disk over `stored`, what's there now:
+/// credentials moved into the store and replaced with references. One the
+/// store refuses stays in plain text. Items this profile no longer
+/// references are deleted.
+pub(crate) fn conceal(settings: &AppSettings, stored: Option<&AppSettings>) -> AppSettings {
+    conceal_with(settings, stored, profiles::active(), set, delete)
+}
+
+fn conceal_with(
+    settings: &AppSettings,
+    stored: Option<&AppSettings>,
+    profile: &str,
+    set: impl Fn(&str, &str) -> Result<(), String>,
+    delete: impl Fn(&str) -> Result<(), String>,
+) -> AppSettings {
+    let mut previous: HashMap<&str, String> = HashMap::new();
+    let mut stored = stored.cloned().unwrap_or_default();
+    for (field, value) in fields(&mut stored) {
+        if let Some(name) = referenced(value) {
+            previous.insert(field, name.to_string());
+        }
+    }
+
+    let mut concealed = settings.clone();
+    let own = format!("{}/", profile);
+    for (field, value) in fields(&mut concealed) {
+        if !value.is_empty() && referenced(value).is_none() {
+            let name = secret_name(profile, field);
+            match set(&name, value) {
+                Ok(()) => *value = format!("{}{}", REFERENCE_PREFIX, name),
+                Err(e) => log::warn!("Keeping {} in settings.json: {}", field, e),
+            }
+        }
+        // Items copied from another profile's settings are left to it
+        let kept = referenced(value);
+        if let Some(old) = previous.remove(field) {
+            if Some(old.as_str()) != kept && old.starts_with(&own) {
+                if let Err(e) = delete(&old) {
+                    log::warn!("Failed to remove {} from the keyring: {}", field, e);
+                }
+            }
+        }
+    }
+    // Fields that are gone altogether, like a cleared ntfy token
+    for (field, old) in previous {
+        if old.starts_with(&own) {
+            if let Err(e) = delete(&old) {
+                log::warn!("Failed to remove {} from the keyring: {}", field, e);
+            }
+        }
+    }
+    concealed
+}
+
+/// Whether `settings` as read from disk still has credentials in plain text
+pub(crate) fn has_plain_text(settings: &AppSettings) -> bool {
+    let mut settings = settings.clone();
+    fields(&mut settings)
+        .into_iter()
+        .any(|(_, value)| !value.is_empty() && referenced(value).is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn credentials_are_swapped_for_references_and_back() {
+        let mut settings = AppSettings {
+            slack_webhook_url: Some("https://hooks.slack.com/services/T0/B0/x".to_string()),
+            ..AppSettings::default()
+        };
+        settings.telegram.bot_token = "123:abc".to_string();
+        assert!(has_plain_text(&settings));
+
+        let store = RefCell::new(HashMap::new());
+        let set = |name: &str, value: &str| {
+            store
+                .borrow_mut()
+                .insert(name.to_string(), value.to_string());
+            Ok(())
+        };
+        let delete = |name: &str| {
+            store.borrow_mut().remove(name);
+            Ok(())
+        };
+        let stored = conceal_with(&settings, None, "work", set, delete);
+        assert!(!has_plain_text(&stored));
+        let slack = stored.slack_webhook_url.clone().unwrap();
+        assert_eq!(slack, "c3-secret:work/slack_webhook_url");
+        assert_eq!(store.borrow().len(), 2);
+        // Empty fields stay empty rather than becoming references
+        assert_eq!(stored.email.smtp_password, "");
+        assert_eq!(stored.push.ntfy_token, None);
+
+        let mut loaded = stored.clone();
+        reveal_with(&mut loaded, |name| store.borrow().get(name).cloned());
+        assert_eq!(loaded.slack_webhook_url, settings.slack_webhook_url);
+        assert_eq!(loaded.telegram.bot_token, "123:abc");
+
+        // A missing secret keeps its reference rather than losing it
+        let mut locked = stored.clone();
+        reveal_with(&mut locked, |_| None);
+        assert_eq!(locked.slack_webhook_url.as_deref(), Some(slack.as_str()));
+
+        // A new token replaces the item; clearing a field removes its item
+        loaded.telegram.bot_token = "456:def".to_string();
+        loaded.slack_webhook_url = None;
+        let resaved = conceal_with(&loaded, Some(&stored), "work", set, delete);
+        assert_eq!(
+            resaved.telegram.bot_token,
+            "c3-secret:work/telegram.bot_token"
+        );
+        assert_eq!(
+            *store.borrow(),
+            HashMap::from([("work/telegram.bot_token".to_string(), "456:def".to_string())])
+        );
+
+        // Settings copied from another profile move to this one's items,
+        // leaving the other profile's alone
+        let copied = conceal_with(&loaded, Some(&resaved), "home", set, delete);
+        assert_eq!(
+            copied.telegram.bot_token,
+            "c3-secret:home/telegram.bot_token"
+        );
+        assert!(store.borrow().contains_key("work/telegram.bot_token"));
+
+        // A store that refuses leaves the value where it was
+        let refused = conceal_with(
+            &settings,
+            None,
+            "work",
+            |_, _| Err("no keyring".to_string()),
+            delete,
+        );
+        assert_eq!(refused.telegram.bot_token, "123:abc");
+    }
+}
@@ -0,0 +1,327 @@
+use crate::at_rest;
+use crate::session_identity::{self, CLAUDE_PREFIX};
+use crate::tmux_scanner::cwd_to_project_dir;
+use crate::{cmd, load_session_meta, open_tmux_window, save_session_meta, AppState, SessionMeta};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Bumped when the bundle layout changes; newer bundles are refused
+const BUNDLE_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const TRANSCRIPT_FILE: &str = "transcript.jsonl";
+/// The zip inside the staging directory, before or after encryption
+const ZIP_FILE: &str = "bundle.zip";
+
+/// What a bundle says about the conversation it carries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    version: u32,
+    agent_kind: String,
+    /// Claude's conversation UUID, what `claude --resume` takes
+    conversation_id: String,
+    project_name: String,
+    project_path: String,
+    /// HOME on the exporting machine, to map the project path onto this one
+    home: String,
+    exported_at: DateTime<Utc>,
+    /// Tag, notes, pin and git baseline; groups and order stay behind
+    #[serde(default)]
+    meta: Option<SessionMeta>,
+}
+
+/// A conversation imported from another machine
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedSession {
+    pub session_id: String,
+    pub project_name: String,
+    pub project_path: String,
+    pub resume_command: String,
+    /// The tmux window it was resumed in, when tmux was running
+    pub tmux_target: Option<String>,
+}
+
+fn home() -> Result<String, String> {
+    std::env::var("HOME")
+        .ok()
+        .filter(|home| !home.is_empty())
+        .ok_or_else(|| "Could not determine HOME directory".to_string())
+}
+
+/// `/Users/me/src/app` exported from `/Users/me` is `/home/me2/src/app` here.
+/// Paths outside the old HOME stay as they are.
+fn map_project_path(path: &str, from_home: &str, to_home: &str) -> String {
+    match path.strip_prefix(from_home) {
+        Some(rest) if !from_home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => {
+            format!("{}{}", to_home, rest)
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Point `cwd` fields at the project's path on this machine. Lines are
+/// edited as text so everything else stays byte for byte.
+fn rewrite_cwd(line: &str, from: &str, to: &str) -> String {
+    if from == to {
+        return line.to_string();
+    }
+    let json = |path: &str| serde_json::to_string(path).unwrap_or_default();
+    let (from, to) = (json(from), json(to));
+    // Without the closing quote, to also catch subdirectories
+    let (from_dir, to_dir) = (&from[..from.len() - 1], &to[..to.len() - 1]);
+    line.replace(&format!("\"cwd\":{}", from), &format!("\"cwd\":{}", to))
+        .replace(
+            &format!("\"cwd\":{}/", from_dir),
+            &format!("\"cwd\":{}/", to_dir),
+        )
+}
+
+fn run(command: &mut std::process::Command, what: &str) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", what, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            what,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn staging_dir(purpose: &str) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join(format!(
+        "c3-{}-{}-{}",
+        purpose,
+        std::process::id(),
+        Utc::now().timestamp_millis()
+    ));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+fn write_bundle(manifest: &Manifest, transcript: &Path, path: &Path) -> Result<(), String> {
+    let staging = staging_dir("bundle")?;
+    let result = (|| {
+        let json = serde_json::to_string_pretty(manifest).map_err(|e| e.to_string())?;
+        fs::write(staging.join(MANIFEST_FILE), json).map_err(|e| e.to_string())?;
+        fs::copy(transcript, staging.join(TRANSCRIPT_FILE))
+            .map_err(|e| format!("Failed to read {}: {}", transcript.display(), e))?;
+        run(
+            cmd("zip")
+                .current_dir(&staging)
+                .args(["-q", ZIP_FILE, MANIFEST_FILE, TRANSCRIPT_FILE]),
+            "zip",
+        )?;
+        let zip = fs::read(staging.join(ZIP_FILE)).map_err(|e| e.to_string())?;
+        at_rest::write(path, &zip).map_err(|e| format!("Failed to write bundle: {}", e))
+    })();
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
+
+fn import_bundle(bundle: &Path, project_path: Option<String>) -> Result<ImportedSession, String> {
+    let staging = staging_dir("import")?;
+    let result = (|| {
+        let zip = staging.join(ZIP_FILE);
+        fs::write(&zip, at_rest::read(bundle)?).map_err(|e| e.to_string())?;
+        run(
+            cmd("unzip")
+                .args(["-q", "-o", "-j"])
+                .arg(&zip)
+                .args([MANIFEST_FILE, TRANSCRIPT_FILE])
+                .arg("-d")
+                .arg(&staging),
+            "unzip",
+        )?;
+        let manifest: Manifest = fs::read_to_string(staging.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .ok_or("Not a C3 session bundle")?;
+        if manifest.version > BUNDLE_VERSION {
+            return Err("This bundle is from a newer C3; update C3 to import it".to_string());
+        }
+        // Also keeps the file name below inside the projects directory
+        let uuid = session_identity::claude_uuid_from_hook(Some(&manifest.conversation_id))
+            .filter(|_| manifest.agent_kind == "claude")
+            .ok_or("Only Claude conversations can be imported")?;
+
+        let local_path = match project_path {
+            Some(path) => path,
+            None => map_project_path(&manifest.project_path, &manifest.home, &home()?),
+        };
+        if !Path::new(&local_path).is_dir() {
+            return Err(format!(
+                "{} doesn't exist on this machine; choose where {} lives",
+                local_path, manifest.project_name
+            ));
+        }
+        let dir = cwd_to_project_dir(&local_path);
+        let destination = dir.join(format!("{}.jsonl", uuid));
+        if destination.exists() {
+            return Err(format!(
+                "This conversation is already at {}",
+                destination.display()
+            ));
+        }
+        let transcript = fs::read_to_string(staging.join(TRANSCRIPT_FILE))
+            .map_err(|e| format!("Failed to read transcript: {}", e))?;
+        let rewritten: String = transcript
+            .lines()
+            .map(|line| rewrite_cwd(line, &manifest.project_path, &local_path) + "\n")
+            .collect();
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let partial = destination.with_extension("jsonl.c3-partial");
+        fs::write(&partial, rewritten).map_err(|e| format!("Failed to write transcript: {}", e))?;
+        fs::rename(&partial, &destination)
+            .map_err(|e| format!("Failed to write transcript: {}", e))?;
+
+        let session_id = session_identity::claude_session_id(&uuid);
+        if let Some(meta) = manifest.meta {
+            let mut store = load_session_meta();
+            store.sessions.entry(session_id.clone()).or_insert(meta);
+            save_session_meta(&store)?;
+        }
+        Ok((manifest.project_name, local_path, uuid, session_id))
+    })();
+    let _ = fs::remove_dir_all(&staging);
+    let (project_name, project_path, uuid, session_id) = result?;
+
+    let resume_command = format!("claude --resume {}", uuid);
+    let tmux_target = open_tmux_window(&project_path, &resume_command)
+        .map_err(|e| log::warn!("Imported {} but couldn't resume it: {}", session_id, e))
+        .ok();
+    log::info!("Imported {} into {}", session_id, project_path);
+    Ok(ImportedSession {
+        session_id,
+        project_name,
+        project_path,
+        resume_command,
+        tmux_target,
+    })
+}
+
+// Tauri command: Package a Claude session's transcript and metadata to continue elsewhere
+#[tauri::command]
+#[specta::specta]
+pub async fn export_session_bundle(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    path: String,
+) -> Result<String, String> {
+    if !Path::new(&path).is_absolute() {
+        return Err("Export path must be absolute".to_string());
+    }
+    let session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .or_else(|| state.archive.session(&session_id))
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let uuid = session
+        .id
+        .strip_prefix(CLAUDE_PREFIX)
+        .filter(|_| session.agent_kind.as_deref().unwrap_or("claude") == "claude")
+        .ok_or("Only Claude sessions with a known conversation can be handed off")?
+        .to_string();
+    let project_path = session
+        .project_path
+        .clone()
+        .ok_or_else(|| format!("{} has no project path", session.project_name))?;
+    let meta = load_session_meta()
+        .sessions
+        .get(&session_id)
+        .cloned()
+        .map(|meta| SessionMeta {
+            group_id: None,
+            group_assignment: None,
+            order: None,
+            worktree: None,
+            ..meta
+        });
+
+    tokio::task::spawn_blocking(move || {
+        let transcript = cwd_to_project_dir(&project_path).join(format!("{}.jsonl", uuid));
+        if !transcript.exists() {
+            return Err(format!("No transcript found for {}", session.project_name));
+        }
+        let manifest = Manifest {
+            version: BUNDLE_VERSION,
+            agent_kind: "claude".to_string(),
+            conversation_id: uuid,
+            project_name: session.project_name.clone(),
+            project_path,
+            home: home()?,
+            exported_at: Utc::now(),
+            meta,
+        };
+        let path = at_rest::sealed_path(&path);
+        write_bundle(&manifest, &transcript, Path::new(&path))?;
+        log::info!("Exported {} to {}", session.project_name, path);
+        Ok(path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Import a session bundle and resume it with `claude --resume`
+#[tauri::command]
+#[specta::specta]
+pub async fn import_session_bundle(
+    path: String,
+    project_path: Option<String>,
+) -> Result<ImportedSession, String> {
+    tokio::task::spawn_blocking(move || import_bundle(Path::new(&path), project_path))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_paths_move_to_this_home() {
+        assert_eq!(
+            map_project_path("/Users/me/src/app", "/Users/me", "/home/me2"),
+            "/home/me2/src/app"
+        );
+        assert_eq!(
+            map_project_path("/Users/meg/app", "/Users/me", "/home/me2"),
+            "/Users/meg/app"
+        );
+        assert_eq!(
+            map_project_path("/srv/app", "/Users/me", "/home/me2"),
+            "/srv/app"
+        );
+
+        let line = r#"{"cwd":"/Users/me/src/app","message":{"content":"cd /Users/me/src/app"}}"#;
+        assert_eq!(
+            rewrite_cwd(line, "/Users/me/src/app", "/home/me2/src/app"),
+            r#"{"cwd":"/home/me2/src/app","message":{"content":"cd /Users/me/src/app"}}"#
+        );
+        assert_eq!(
+            rewrite_cwd(
+                r#"{"cwd":"/Users/me/src/app/web"}"#,
+                "/Users/me/src/app",
+                "/home/me2/src/app"
+            ),
+            r#"{"cwd":"/home/me2/src/app/web"}"#
+        );
+        // A sibling directory sharing the prefix is left alone
+        let sibling = r#"{"cwd":"/Users/me/src/app-old"}"#;
+        assert_eq!(
+            rewrite_cwd(sibling, "/Users/me/src/app", "/home/me2/src/app"),
+            sibling
+        );
+    }
+}
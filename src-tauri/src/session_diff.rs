@@ -0,0 +1,263 @@
+use crate::git::git;
+use crate::transcript;
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use specta::Type;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Git's well-known empty tree, used as the base when the repo had no commits
+/// before the session started
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+/// Full patches larger than this are cut off
+const MAX_PATCH_BYTES: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffFile {
+    pub path: String,
+    /// None for binary files
+    pub insertions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDiff {
+    pub session_id: String,
+    pub project_path: String,
+    /// Commit the working tree is compared against: the session's recorded
+    /// baseline, else the last commit made before it started
+    pub base_commit: String,
+    pub session_start: Option<DateTime<Utc>>,
+    pub files: Vec<DiffFile>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Unified diff, only when requested
+    pub patch: Option<String>,
+    pub patch_truncated: bool,
+}
+
+/// The commit recorded when C3 first saw the session, else the last commit
+/// before `start` on the current branch. Without either the session's changes
+/// can't be told apart from history, so only uncommitted changes (against
+/// HEAD) are shown.
+fn base_commit(
+    repo: &str,
+    recorded: Option<&str>,
+    start: Option<DateTime<Utc>>,
+) -> Result<String, String> {
+    if let Some(commit) = recorded {
+        // Rebased or garbage-collected baselines no longer resolve
+        let object = format!("{}^{{commit}}", commit);
+        if git(repo, &["cat-file", "-e", &object]).is_ok() {
+            return Ok(commit.to_string());
+        }
+        log::info!(
+            "Recorded baseline {} is gone, falling back to session start",
+            commit
+        );
+    }
+    let Some(start) = start else {
+        return git(repo, &["rev-parse", "HEAD"])
+            .map(|s| s.trim().to_string())
+            .or_else(|_| Ok(EMPTY_TREE.to_string()));
+    };
+    let before = format!("--before={}", start.to_rfc3339());
+    let commit = git(repo, &["rev-list", "-1", &before, "HEAD"]).unwrap_or_default();
+    match commit.trim() {
+        "" => Ok(EMPTY_TREE.to_string()),
+        commit => Ok(commit.to_string()),
+    }
+}
+
+/// Parse `git diff --numstat` output; binary files show `-` for both counts
+fn parse_numstat(output: &str) -> Vec<DiffFile> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let insertions = fields.next()?.parse().ok();
+            let deletions = fields.next()?.parse().ok();
+            let path = fields.next()?.to_string();
+            Some(DiffFile {
+                path,
+                insertions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
+struct RepoDiff {
+    base_commit: String,
+    files: Vec<DiffFile>,
+    patch: Option<String>,
+    patch_truncated: bool,
+}
+
+fn diff_since(
+    repo: &str,
+    recorded: Option<&str>,
+    start: Option<DateTime<Utc>>,
+    full: bool,
+) -> Result<RepoDiff, String> {
+    if !Path::new(repo).is_dir() {
+        return Err(format!("Project directory not found: {}", repo));
+    }
+    git(repo, &["rev-parse", "--is-inside-work-tree"])
+        .map_err(|_| format!("Not a git repository: {}", repo))?;
+
+    let base = base_commit(repo, recorded, start)?;
+    let files = parse_numstat(&git(repo, &["diff", "--numstat", &base])?);
+    let (patch, patch_truncated) = if full {
+        let mut patch = git(repo, &["diff", &base])?;
+        let truncated = patch.len() > MAX_PATCH_BYTES;
+        if truncated {
+            let mut end = MAX_PATCH_BYTES;
+            while !patch.is_char_boundary(end) {
+                end -= 1;
+            }
+            patch.truncate(end);
+        }
+        (Some(patch), truncated)
+    } else {
+        (None, false)
+    };
+    Ok(RepoDiff {
+        base_commit: base,
+        files,
+        patch,
+        patch_truncated,
+    })
+}
+
+// Tauri command: Summarize what changed in a session's git repo since it started
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_diff(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    full: Option<bool>,
+) -> Result<SessionDiff, String> {
+    let session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let project_path = session
+        .project_path
+        .clone()
+        .ok_or_else(|| format!("No project path for {}", session.project_name))?;
+
+    tokio::task::spawn_blocking(move || {
+        let session_start = transcript::session_transcript(&session)
+            .and_then(|(agent_kind, path)| transcript::first_message_timestamp(&agent_kind, &path));
+        let recorded = session.git_baseline.as_ref().map(|b| b.commit.as_str());
+        let diff = diff_since(
+            &project_path,
+            recorded,
+            session_start,
+            full.unwrap_or(false),
+        )?;
+        Ok(SessionDiff {
+            session_id: session.id,
+            project_path,
+            base_commit: diff.base_commit,
+            session_start,
+            insertions: diff.files.iter().filter_map(|f| f.insertions).sum(),
+            deletions: diff.files.iter().filter_map(|f| f.deletions).sum(),
+            files: diff.files,
+            patch: diff.patch,
+            patch_truncated: diff.patch_truncated,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git;
+    use std::fs;
+
+    #[test]
+    fn numstat_handles_binary_and_tabs_in_paths() {
+        let files = parse_numstat("3\t1\tsrc/lib.rs\n-\t-\tassets/logo.png\n0\t2\tdocs/a\tb.md\n");
+        assert_eq!(
+            files,
+            vec![
+                DiffFile {
+                    path: "src/lib.rs".to_string(),
+                    insertions: Some(3),
+                    deletions: Some(1),
+                },
+                DiffFile {
+                    path: "assets/logo.png".to_string(),
+                    insertions: None,
+                    deletions: None,
+                },
+                DiffFile {
+                    path: "docs/a\tb.md".to_string(),
+                    insertions: Some(0),
+                    deletions: Some(2),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_against_last_commit_before_session_start() {
+        let dir = std::env::temp_dir().join(format!("c3-session-diff-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let repo = dir.to_str().unwrap();
+        let commit = |message: &str, date: &str| {
+            crate::cmd("git")
+                .args(["-C", repo, "commit", "-qam", message])
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .env("GIT_AUTHOR_NAME", "c3")
+                .env("GIT_AUTHOR_EMAIL", "c3@example.com")
+                .env("GIT_COMMITTER_NAME", "c3")
+                .env("GIT_COMMITTER_EMAIL", "c3@example.com")
+                .status()
+                .unwrap();
+        };
+        git(repo, &["init", "-q"]).unwrap();
+        fs::write(dir.join("a.txt"), "one\n").unwrap();
+        git(repo, &["add", "a.txt"]).unwrap();
+        commit("before", "2026-01-01T09:00:00Z");
+        fs::write(dir.join("a.txt"), "one\ntwo\n").unwrap();
+        commit("during", "2026-01-01T11:00:00Z");
+        fs::write(dir.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let start = "2026-01-01T10:00:00Z".parse().ok();
+        let diff = diff_since(repo, None, start, true).unwrap();
+        assert_eq!(diff.files.len(), 1);
+        assert_eq!(diff.files[0].insertions, Some(2));
+        assert!(diff.patch.unwrap().contains("+three"));
+
+        // Without a start time only uncommitted work shows
+        let diff = diff_since(repo, None, None, false).unwrap();
+        assert_eq!(diff.files[0].insertions, Some(1));
+        assert!(diff.patch.is_none());
+
+        // A recorded baseline wins over the start time
+        let baseline = git::capture_baseline(repo).unwrap();
+        let diff = diff_since(repo, Some(&baseline.commit), start, false).unwrap();
+        assert_eq!(diff.base_commit, baseline.commit);
+        assert_eq!(diff.files[0].insertions, Some(1));
+        assert_eq!(
+            diff_since(repo, Some("0123456789abcdef"), start, false)
+                .unwrap()
+                .files[0]
+                .insertions,
+            Some(2)
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
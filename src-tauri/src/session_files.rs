@@ -0,0 +1,208 @@
+use crate::transcript::{self, MessagePart, TranscriptMessage};
+use crate::AppState;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TouchedFile {
+    /// Absolute path as the agent wrote it (relative patch paths are resolved
+    /// against the project dir)
+    pub path: String,
+    /// Path inside the project, when the file is under it
+    pub relative_path: Option<String>,
+    /// Number of modifying tool calls targeting the file
+    pub edits: usize,
+    pub last_edited: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionFiles {
+    pub session_id: String,
+    pub project_path: Option<String>,
+    /// Most edited first
+    pub files: Vec<TouchedFile>,
+    pub total_edits: usize,
+}
+
+/// Paths named in a Codex `apply_patch` body
+fn apply_patch_paths(patch: &str) -> Vec<String> {
+    const MARKERS: &[&str] = &[
+        "*** Add File: ",
+        "*** Update File: ",
+        "*** Delete File: ",
+        "*** Move to: ",
+    ];
+    patch
+        .lines()
+        .filter_map(|line| {
+            MARKERS
+                .iter()
+                .find_map(|marker| line.strip_prefix(marker))
+                .map(|path| path.trim().to_string())
+        })
+        .filter(|path| !path.is_empty())
+        .collect()
+}
+
+/// Files a tool call modifies; empty for read-only tools
+fn modified_paths(name: &str, input: &Value) -> Vec<String> {
+    let field = |key: &str| {
+        input
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| vec![s.to_string()])
+            .unwrap_or_default()
+    };
+    match name {
+        "Edit" | "Write" | "MultiEdit" => field("file_path"),
+        "NotebookEdit" => field("notebook_path"),
+        // OMP tool names
+        "edit" | "write" => {
+            let mut paths = field("path");
+            if paths.is_empty() {
+                paths = field("file_path");
+            }
+            paths
+        }
+        "apply_patch" => match input {
+            Value::String(patch) => apply_patch_paths(patch),
+            other => other
+                .get("input")
+                .or_else(|| other.get("patch"))
+                .and_then(|v| v.as_str())
+                .map(apply_patch_paths)
+                .unwrap_or_default(),
+        },
+        _ => Vec::new(),
+    }
+}
+
+fn collect_files(messages: &[TranscriptMessage], project_path: Option<&str>) -> Vec<TouchedFile> {
+    let mut files: HashMap<String, TouchedFile> = HashMap::new();
+    for message in messages {
+        for part in &message.parts {
+            let MessagePart::ToolCall { name, input } = part else {
+                continue;
+            };
+            for path in modified_paths(name, input) {
+                let path = match project_path {
+                    Some(project) if !Path::new(&path).is_absolute() => {
+                        Path::new(project).join(&path).to_string_lossy().to_string()
+                    }
+                    _ => path,
+                };
+                let relative_path = project_path.and_then(|project| {
+                    Path::new(&path)
+                        .strip_prefix(project)
+                        .ok()
+                        .map(|rel| rel.to_string_lossy().to_string())
+                });
+                let entry = files.entry(path.clone()).or_insert_with(|| TouchedFile {
+                    path,
+                    relative_path,
+                    edits: 0,
+                    last_edited: None,
+                });
+                entry.edits += 1;
+                if message.timestamp.is_some() {
+                    entry.last_edited = message.timestamp;
+                }
+            }
+        }
+    }
+    let mut files: Vec<TouchedFile> = files.into_values().collect();
+    files.sort_by(|a, b| b.edits.cmp(&a.edits).then_with(|| a.path.cmp(&b.path)));
+    files
+}
+
+// Tauri command: List files a session's agent has modified, with edit counts
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_files(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<SessionFiles, String> {
+    let session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    tokio::task::spawn_blocking(move || {
+        let (agent_kind, transcript_path) = transcript::session_transcript(&session)
+            .ok_or_else(|| format!("No transcript found for {}", session.project_name))?;
+        let messages = transcript::read_transcript(&agent_kind, &transcript_path)?;
+        let files = collect_files(&messages, session.project_path.as_deref());
+        Ok(SessionFiles {
+            session_id: session.id,
+            project_path: session.project_path,
+            total_edits: files.iter().map(|f| f.edits).sum(),
+            files,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(agent_kind: &str, jsonl: &str) -> Vec<TranscriptMessage> {
+        jsonl
+            .lines()
+            .filter_map(|line| transcript::parse_line(agent_kind, line))
+            .collect()
+    }
+
+    #[test]
+    fn counts_claude_edit_tools_and_ignores_reads() {
+        let jsonl = r#"{"type":"assistant","timestamp":"2026-01-05T10:00:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"1","name":"Read","input":{"file_path":"/work/api/src/lib.rs"}}]}}
+{"type":"assistant","timestamp":"2026-01-05T10:01:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"2","name":"Edit","input":{"file_path":"/work/api/src/lib.rs","old_string":"a","new_string":"b"}}]}}
+{"type":"assistant","timestamp":"2026-01-05T10:02:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"3","name":"MultiEdit","input":{"file_path":"/work/api/src/lib.rs","edits":[]}}]}}
+{"type":"assistant","timestamp":"2026-01-05T10:03:00Z","message":{"role":"assistant","content":[{"type":"tool_use","id":"4","name":"Write","input":{"file_path":"/tmp/notes.md","content":"x"}},{"type":"tool_use","id":"5","name":"NotebookEdit","input":{"notebook_path":"/work/api/eda.ipynb","new_source":""}}]}}"#;
+        let files = collect_files(&parse("claude", jsonl), Some("/work/api"));
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "/work/api/src/lib.rs");
+        assert_eq!(files[0].relative_path.as_deref(), Some("src/lib.rs"));
+        assert_eq!(files[0].edits, 2);
+        assert_eq!(
+            files[0].last_edited.map(|t| t.to_rfc3339()).as_deref(),
+            Some("2026-01-05T10:02:00+00:00")
+        );
+        let notes = files.iter().find(|f| f.path == "/tmp/notes.md").unwrap();
+        assert_eq!(notes.relative_path, None);
+        assert!(files.iter().any(|f| f.path == "/work/api/eda.ipynb"));
+    }
+
+    #[test]
+    fn codex_apply_patch_paths_resolve_against_project() {
+        let patch = "*** Begin Patch\n*** Update File: src/main.rs\n@@\n-a\n+b\n*** Add File: docs/new.md\n+hi\n*** End Patch";
+        let input = Value::String(patch.to_string());
+        assert_eq!(
+            modified_paths("apply_patch", &input),
+            vec!["src/main.rs", "docs/new.md"]
+        );
+
+        let messages = vec![TranscriptMessage {
+            role: transcript::Role::Assistant,
+            timestamp: None,
+            parts: vec![MessagePart::ToolCall {
+                name: "apply_patch".to_string(),
+                input,
+            }],
+        }];
+        let files = collect_files(&messages, Some("/work/cli"));
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().any(|f| f.path == "/work/cli/src/main.rs"
+            && f.relative_path.as_deref() == Some("src/main.rs")));
+    }
+}
@@ -0,0 +1,329 @@
+use crate::{load_session_meta, save_session_meta, AppState, C3Session};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Claude sessions are keyed by conversation UUID, which survives pane moves
+pub(crate) const CLAUDE_PREFIX: &str = "claude:";
+/// Other tmux sessions, and Claude ones whose conversation isn't known yet,
+/// by `%N` pane ID; by target when a hook from an older script names only that
+pub(crate) const TMUX_PREFIX: &str = "tmux:";
+
+pub(crate) fn tmux_session_id(pane: &str) -> String {
+    format!("{}{}", TMUX_PREFIX, pane)
+}
+
+pub(crate) fn claude_session_id(uuid: &str) -> String {
+    format!("{}{}", CLAUDE_PREFIX, uuid)
+}
+
+/// Sessions of tmux panes, which go away with their pane
+pub(crate) fn is_pane_session(id: &str) -> bool {
+    id.starts_with(TMUX_PREFIX) || id.starts_with(CLAUDE_PREFIX)
+}
+
+fn is_uuid(text: &str) -> bool {
+    text.len() == 36
+        && text.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// The UUID Claude names its conversation logs after (`<uuid>.jsonl`)
+pub(crate) fn claude_uuid_from_log(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?;
+    is_uuid(stem).then(|| stem.to_string())
+}
+
+/// The UUID in a Claude hook's `session_id`
+pub(crate) fn claude_uuid_from_hook(session_id: Option<&str>) -> Option<String> {
+    session_id.filter(|id| is_uuid(id)).map(str::to_string)
+}
+
+/// The session of a tmux pane, whatever it's keyed by
+pub(crate) fn for_pane<'a>(
+    sessions: &'a HashMap<String, C3Session>,
+    pane_id: &str,
+) -> Option<&'a C3Session> {
+    sessions
+        .values()
+        .find(|s| is_pane_session(&s.id) && s.tmux_pane_id.as_deref() == Some(pane_id))
+}
+
+/// The session last seen at a `session:window.pane` target
+pub(crate) fn for_target<'a>(
+    sessions: &'a HashMap<String, C3Session>,
+    target: &str,
+) -> Option<&'a C3Session> {
+    sessions
+        .values()
+        .find(|s| is_pane_session(&s.id) && s.tmux_target.as_deref() == Some(target))
+}
+
+/// The session of a pane named by `%N` pane ID or by target
+pub(crate) fn at_pane<'a>(
+    sessions: &'a HashMap<String, C3Session>,
+    pane: &str,
+) -> Option<&'a C3Session> {
+    for_pane(sessions, pane).or_else(|| for_target(sessions, pane))
+}
+
+/// Where to send tmux commands for a session: its pane ID, which stays right
+/// when the pane moves between scans, or else its target
+pub(crate) fn tmux_pane(session: &C3Session) -> Option<&str> {
+    session
+        .tmux_pane_id
+        .as_deref()
+        .or(session.tmux_target.as_deref())
+}
+
+/// A scanned pane: where it is and, for Claude, the conversation its log is from
+pub(crate) struct ScannedPane<'a> {
+    pub(crate) pane_id: &'a str,
+    pub(crate) target: &'a str,
+    pub(crate) conversation: Option<&'a str>,
+}
+
+/// The session ID of each scanned pane. A Claude pane's conversation claims
+/// its session wherever the pane moved; a pane otherwise keeps the ID it had,
+/// and takes its conversation's ID once one is known. Logs read by several
+/// panes in one directory say nothing about which pane they belong to.
+pub(crate) fn resolve_pane_ids(
+    sessions: &HashMap<String, C3Session>,
+    panes: &[ScannedPane],
+) -> Vec<String> {
+    let mut readers: HashMap<&str, usize> = HashMap::new();
+    for conversation in panes.iter().filter_map(|pane| pane.conversation) {
+        *readers.entry(conversation).or_default() += 1;
+    }
+    let conversation_ids: Vec<Option<String>> = panes
+        .iter()
+        .map(|pane| {
+            pane.conversation
+                .filter(|conversation| readers[conversation] == 1)
+                .map(claude_session_id)
+        })
+        .collect();
+
+    let mut ids: Vec<Option<String>> = conversation_ids
+        .iter()
+        .map(|id| id.clone().filter(|id| sessions.contains_key(id)))
+        .collect();
+    let mut taken: HashSet<String> = ids.iter().flatten().cloned().collect();
+    for (i, pane) in panes.iter().enumerate() {
+        if ids[i].is_some() {
+            continue;
+        }
+        let kept = for_pane(sessions, pane.pane_id)
+            .map(|session| session.id.clone())
+            .filter(|id| id.starts_with(CLAUDE_PREFIX) && !taken.contains(id));
+        let id = kept
+            .or_else(|| conversation_ids[i].clone().filter(|id| !taken.contains(id)))
+            .unwrap_or_else(|| tmux_session_id(pane.pane_id));
+        taken.insert(id.clone());
+        ids[i] = Some(id);
+    }
+    ids.into_iter().flatten().collect()
+}
+
+/// Sessions still under another ID than their pane resolved to, e.g. a
+/// Claude pane whose conversation just became known, or a hook-made session
+/// known only by target. Sessions another pane resolved to stay put.
+pub(crate) fn rekeys(
+    sessions: &HashMap<String, C3Session>,
+    panes: &[ScannedPane],
+    ids: &[String],
+) -> Vec<Rekey> {
+    let resolved: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    panes
+        .iter()
+        .zip(ids)
+        .filter(|(_, id)| !sessions.contains_key(*id))
+        .filter_map(|(pane, id)| {
+            let session = for_pane(sessions, pane.pane_id).or_else(|| {
+                for_target(sessions, pane.target).filter(|s| s.tmux_pane_id.is_none())
+            })?;
+            (!resolved.contains(session.id.as_str())).then(|| Rekey {
+                session: session.clone(),
+                id: id.clone(),
+                target: pane.target.to_string(),
+                pane_id: Some(pane.pane_id.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Move `tmux:<target>` metadata, saved before sessions were keyed by
+/// conversation and pane ID, to the pane's new session
+pub(crate) fn adopt_target_meta(session_id: &str, target: &str) {
+    let legacy_id = format!("{}{}", TMUX_PREFIX, target);
+    if session_id == legacy_id {
+        return;
+    }
+    let mut store = load_session_meta();
+    let Some(meta) = store.sessions.remove(&legacy_id) else {
+        return;
+    };
+    log::info!("Moving metadata of {} to {}", legacy_id, session_id);
+    store.sessions.insert(session_id.to_string(), meta);
+    if let Err(e) = save_session_meta(&store) {
+        log::warn!("Failed to save moved session metadata: {}", e);
+    }
+}
+
+/// A session taking a new ID, at the given pane
+pub(crate) struct Rekey {
+    pub(crate) session: C3Session,
+    pub(crate) id: String,
+    pub(crate) target: String,
+    /// None keeps the session's own
+    pub(crate) pane_id: Option<String>,
+}
+
+/// Move sessions to their new IDs and panes along with everything keyed by ID
+pub(crate) fn rekey(state: &Arc<AppState>, moves: Vec<Rekey>) {
+    if moves.is_empty() {
+        return;
+    }
+    let mut store = load_session_meta();
+    let mut meta_moved = false;
+    for Rekey {
+        session: old,
+        id: new_id,
+        target,
+        pane_id,
+    } in moves
+    {
+        log::info!("{} is now {}", old.id, new_id);
+        let session = C3Session {
+            id: new_id.clone(),
+            tmux_target: Some(target),
+            tmux_pane_id: pane_id.or_else(|| old.tmux_pane_id.clone()),
+            ..old.clone()
+        };
+        {
+            let mut sessions = state.sessions.write();
+            sessions.remove(&old.id);
+            sessions.insert(new_id.clone(), session.clone());
+        }
+        if old.id == new_id {
+            state.events.session_updated(session);
+            continue;
+        }
+        for timestamps in [&state.hook_timestamps, &state.stop_timestamps] {
+            let mut timestamps = timestamps.write();
+            if let Some(at) = timestamps.remove(&old.id) {
+                timestamps.insert(new_id.clone(), at);
+            }
+        }
+        state.timeline.rename(&old.id, &new_id);
+        state.notification_log.rename(&old.id, &new_id);
+        if let Some(meta) = store.sessions.remove(&old.id) {
+            store.sessions.insert(new_id, meta);
+            meta_moved = true;
+        }
+        state.events.session_renamed(old.id, session);
+    }
+    if meta_moved {
+        if let Err(e) = save_session_meta(&store) {
+            log::warn!("Failed to save moved session metadata: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A: &str = "0b5f6c1e-2d3a-4b8c-9e7f-1a2b3c4d5e6f";
+    const B: &str = "7c8d9e0f-1a2b-4c3d-8e5f-6a7b8c9d0e1f";
+
+    fn session(id: &str, pane_id: Option<&str>, target: &str) -> (String, C3Session) {
+        let session = C3Session {
+            project_path: Some("/src/c3".to_string()),
+            tmux_target: Some(target.to_string()),
+            tmux_pane_id: pane_id.map(String::from),
+            ..C3Session::test(id)
+        };
+        (id.to_string(), session)
+    }
+
+    fn pane<'a>(
+        pane_id: &'a str,
+        target: &'a str,
+        conversation: Option<&'a str>,
+    ) -> ScannedPane<'a> {
+        ScannedPane {
+            pane_id,
+            target,
+            conversation,
+        }
+    }
+
+    #[test]
+    fn conversation_logs_are_recognized() {
+        let log = format!("/home/me/.claude/projects/-src-c3/{}.jsonl", A);
+        assert_eq!(claude_uuid_from_log(Path::new(&log)).as_deref(), Some(A));
+        assert_eq!(claude_uuid_from_log(Path::new("/tmp/history.jsonl")), None);
+        assert_eq!(claude_uuid_from_hook(Some(B)).as_deref(), Some(B));
+        assert_eq!(claude_uuid_from_hook(Some("abc")), None);
+    }
+
+    #[test]
+    fn panes_keep_their_session_as_they_move() {
+        const NEW: &str = "f0e1d2c3-b4a5-4968-8776-5a4b3c2d1e0f";
+        const SHARED: &str = "11111111-2222-4333-8444-555555555555";
+        let sessions: HashMap<String, C3Session> = [
+            session(&claude_session_id(A), Some("%1"), "main:1.0"),
+            session("tmux:%2", Some("%2"), "main:2.0"),
+            session(&claude_session_id(B), Some("%3"), "main:3.0"),
+            session("tmux:%8", Some("%8"), "main:8.0"),
+            // Made by a hook that didn't send the pane ID
+            session("tmux:main:6.0", None, "main:6.0"),
+        ]
+        .into_iter()
+        .collect();
+
+        let panes = [
+            // A's window moved to 4 and another pane took its old place
+            pane("%1", "main:4.0", Some(A)),
+            pane("%7", "main:1.0", None),
+            // Its log is known now
+            pane("%2", "main:2.0", Some(NEW)),
+            // Two panes reading one log keep what they had
+            pane("%3", "main:3.0", Some(SHARED)),
+            pane("%5", "main:5.0", Some(SHARED)),
+            // Windows were renumbered
+            pane("%8", "main:7.0", None),
+            pane("%6", "main:6.0", None),
+        ];
+        let ids = resolve_pane_ids(&sessions, &panes);
+        assert_eq!(
+            ids,
+            vec![
+                claude_session_id(A),
+                "tmux:%7".to_string(),
+                claude_session_id(NEW),
+                claude_session_id(B),
+                "tmux:%5".to_string(),
+                "tmux:%8".to_string(),
+                "tmux:%6".to_string(),
+            ]
+        );
+
+        // Only the pane whose conversation became known and the hook-made
+        // session change ID
+        let rekeys: Vec<(String, String)> = rekeys(&sessions, &panes, &ids)
+            .into_iter()
+            .map(|rekey| (rekey.session.id, rekey.id))
+            .collect();
+        assert_eq!(
+            rekeys,
+            vec![
+                ("tmux:%2".to_string(), claude_session_id(NEW)),
+                ("tmux:main:6.0".to_string(), "tmux:%6".to_string()),
+            ]
+        );
+    }
+}
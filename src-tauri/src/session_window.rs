@@ -0,0 +1,334 @@
+use crate::events::SessionBatch;
+use crate::redaction::Redactor;
+use crate::transcript::{self, MessagePart, Role, TranscriptMessage};
+use crate::{cmd, session_identity, AppState, C3Session};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::{
+    AppHandle, Emitter, EventTarget, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent,
+};
+
+/// Pop-out window labels are `session-<n>`
+const LABEL_PREFIX: &str = "session-";
+/// Transcript messages shown in a session window
+const TAIL_MESSAGES: usize = 30;
+/// Pane lines shown in the preview
+const PANE_LINES: usize = 40;
+/// Input keys whose value best describes a tool call, in order of preference
+const TOOL_SUMMARY_KEYS: &[&str] = &["command", "cmd", "file_path", "path", "pattern", "url"];
+
+/// Open pop-out windows, by session id
+#[derive(Default)]
+pub struct SessionWindows {
+    labels: RwLock<HashMap<String, String>>,
+    next_id: AtomicU64,
+}
+
+/// One transcript turn, flattened for display
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TailMessage {
+    pub role: Role,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub text: String,
+}
+
+/// What a session window shows besides the session itself
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTail {
+    pub messages: Vec<TailMessage>,
+    /// Bottom of the tmux pane, None for hook-only sessions
+    pub pane: Option<String>,
+}
+
+pub(crate) fn is_session_window_label(label: &str) -> bool {
+    label.starts_with(LABEL_PREFIX)
+}
+
+/// Whether an event listener belongs to a pop-out window
+fn is_session_window(target: &EventTarget) -> bool {
+    match target {
+        EventTarget::AnyLabel { label }
+        | EventTarget::Window { label }
+        | EventTarget::Webview { label }
+        | EventTarget::WebviewWindow { label } => is_session_window_label(label),
+        _ => false,
+    }
+}
+
+/// Send a batch to the main windows, and each pop-out window only the
+/// changes to its own session (`null` once the session is gone)
+pub fn emit_batch(app: &AppHandle, windows: &SessionWindows, batch: SessionBatch) {
+    // A pop-out follows its session to the new ID
+    for rename in &batch.renamed {
+        let mut labels = windows.labels.write();
+        if let Some(label) = labels.remove(&rename.from) {
+            labels.insert(rename.to.clone(), label);
+        }
+    }
+    for (session_id, label) in windows.labels.read().iter() {
+        let update = if batch.removed.contains(session_id) {
+            Some(None)
+        } else {
+            batch
+                .updated
+                .iter()
+                .find(|s| &s.id == session_id)
+                .map(|s| Some(s.clone()))
+        };
+        if let Some(session) = update {
+            let _ = app.emit_to(label.as_str(), "session-window-update", session);
+        }
+    }
+    let _ = app.emit_filter("sessions-batch-update", batch, |target| {
+        !is_session_window(target)
+    });
+}
+
+/// `Bash: cargo test`, `Read: src/lib.rs`
+fn tool_call_summary(name: &str, input: &serde_json::Value) -> String {
+    let detail = TOOL_SUMMARY_KEYS
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()));
+    match detail {
+        Some(detail) => format!("{}: {}", name, detail),
+        None => name.to_string(),
+    }
+}
+
+/// Text and tool calls of the last `limit` non-empty messages. Tool results
+/// are left out except for errors, which are usually why the user is watching.
+fn tail_messages(
+    messages: Vec<TranscriptMessage>,
+    limit: usize,
+    redactor: &Redactor,
+) -> Vec<TailMessage> {
+    let mut tail: Vec<TailMessage> = messages
+        .into_iter()
+        .rev()
+        .filter_map(|message| {
+            let lines: Vec<String> = message
+                .parts
+                .iter()
+                .filter_map(|part| match part {
+                    MessagePart::Text(text) if !text.trim().is_empty() => {
+                        Some(text.trim().to_string())
+                    }
+                    MessagePart::ToolCall { name, input } => {
+                        Some(format!("→ {}", tool_call_summary(name, input)))
+                    }
+                    MessagePart::ToolResult {
+                        output,
+                        is_error: true,
+                    } => Some(format!("✗ {}", output.lines().next().unwrap_or_default())),
+                    _ => None,
+                })
+                .collect();
+            if lines.is_empty() {
+                return None;
+            }
+            Some(TailMessage {
+                role: message.role,
+                timestamp: message.timestamp,
+                text: redactor.redact_string(lines.join("\n")),
+            })
+        })
+        .take(limit)
+        .collect();
+    tail.reverse();
+    tail
+}
+
+fn capture_pane(tmux_target: &str) -> Option<String> {
+    let start = format!("-{}", PANE_LINES);
+    let output = cmd("tmux")
+        .args(["capture-pane", "-p", "-J", "-t", tmux_target, "-S", &start])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let capture = String::from_utf8_lossy(&output.stdout);
+    Some(capture.trim_end().to_string())
+}
+
+// Tauri command: Pop a session out into its own window
+#[tauri::command]
+#[specta::specta]
+pub fn open_session_window(
+    app: AppHandle,
+    state: tauri::State<Arc<AppState>>,
+    session_id: String,
+) -> Result<(), String> {
+    let project_name = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .map(|s| s.project_name.clone())
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+
+    let existing = state
+        .session_windows
+        .labels
+        .read()
+        .get(&session_id)
+        .cloned();
+    if let Some(window) = existing.and_then(|label| app.get_webview_window(&label)) {
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let label = format!(
+        "{}{}",
+        LABEL_PREFIX,
+        state
+            .session_windows
+            .next_id
+            .fetch_add(1, Ordering::Relaxed)
+    );
+    let url = format!(
+        "index.html#session/{}",
+        crate::deep_link::percent_encode(&session_id)
+    );
+    let window = WebviewWindowBuilder::new(&app, &label, WebviewUrl::App(url.into()))
+        .title(format!("C3 — {}", project_name))
+        .inner_size(520.0, 640.0)
+        .min_inner_size(360.0, 400.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    state
+        .session_windows
+        .labels
+        .write()
+        .insert(session_id.clone(), label);
+    let state_window = state.inner().clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            state_window
+                .session_windows
+                .labels
+                .write()
+                .remove(&session_id);
+        }
+    });
+    Ok(())
+}
+
+// Tauri command: Transcript tail and pane preview for a session window
+#[tauri::command]
+#[specta::specta]
+pub async fn get_session_tail(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<SessionTail, String> {
+    let session: C3Session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let state = state.inner().clone();
+
+    tokio::task::spawn_blocking(move || {
+        let messages = match transcript::session_transcript(&session) {
+            Some((agent_kind, path)) => transcript::read_transcript(&agent_kind, &path)?,
+            None => Vec::new(),
+        };
+        let redactor = state.redactor.read();
+        Ok(SessionTail {
+            messages: tail_messages(messages, TAIL_MESSAGES, &redactor),
+            pane: session_identity::tmux_pane(&session)
+                .and_then(capture_pane)
+                .map(|pane| redactor.redact_string(pane)),
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Close a session's pop-out window, if it has one
+#[tauri::command]
+#[specta::specta]
+pub fn close_session_window(
+    app: AppHandle,
+    state: tauri::State<Arc<AppState>>,
+    session_id: String,
+) -> Result<(), String> {
+    let label = state
+        .session_windows
+        .labels
+        .read()
+        .get(&session_id)
+        .cloned();
+    match label.and_then(|label| app.get_webview_window(&label)) {
+        Some(window) => window.destroy().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(role: Role, parts: Vec<MessagePart>) -> TranscriptMessage {
+        TranscriptMessage {
+            role,
+            timestamp: None,
+            parts,
+        }
+    }
+
+    #[test]
+    fn tail_keeps_text_tool_calls_and_errors() {
+        let messages = vec![
+            message(Role::User, vec![MessagePart::Text("migrate the db".into())]),
+            message(
+                Role::Assistant,
+                vec![
+                    MessagePart::Text("Running it.".into()),
+                    MessagePart::ToolCall {
+                        name: "Bash".into(),
+                        input: json!({"command": "sqlx migrate run", "timeout": 60}),
+                    },
+                ],
+            ),
+            message(
+                Role::User,
+                vec![MessagePart::ToolResult {
+                    output: "applied 3 migrations".into(),
+                    is_error: false,
+                }],
+            ),
+            message(
+                Role::User,
+                vec![MessagePart::ToolResult {
+                    output: "error: relation exists\nat line 4".into(),
+                    is_error: true,
+                }],
+            ),
+        ];
+
+        let tail = tail_messages(messages, 2, &Redactor::new(&[]));
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].text, "Running it.\n→ Bash: sqlx migrate run");
+        assert_eq!(tail[1].text, "✗ error: relation exists");
+    }
+
+    #[test]
+    fn batch_listeners_exclude_session_windows() {
+        let target = |label: &str| EventTarget::WebviewWindow {
+            label: label.to_string(),
+        };
+        assert!(is_session_window(&target("session-3")));
+        assert!(!is_session_window(&target("main")));
+        assert!(!is_session_window(&EventTarget::App));
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{process_hook, AppState, HookNotification, PendingAction, SessionState};
+use serde::Deserialize;
+use specta::Type;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tauri::AppHandle;
+
+/// A recorded sequence of hook payloads and tmux pane lists to replay
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    /// Playback speed multiplier (2.0 replays twice as fast)
+    #[serde(default = "default_speed")]
+    pub speed: f64,
+    pub steps: Vec<ScenarioStep>,
+}
+
+fn default_speed() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ScenarioStep {
+    /// Offset from the start of the replay
+    #[serde(default)]
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub action: StepAction,
+}
+
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StepAction {
+    /// Same payload c3-hook.sh posts to /hook
+    Hook(Box<HookNotification>),
+    /// The complete set of agent panes, as if returned by one tmux scan.
+    /// Sessions for panes missing from the list are removed.
+    Panes(Vec<SimulatedPane>),
+}
+
+/// An agent pane with its state already decided
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct SimulatedPane {
+    pub target: String,
+    pub cwd: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default = "default_agent_kind")]
+    pub agent_kind: String,
+    pub state: SessionState,
+    #[serde(default)]
+    pub pending_action: Option<PendingAction>,
+    #[serde(default)]
+    pub error_reason: Option<String>,
+}
+
+fn default_agent_kind() -> String {
+    "claude".to_string()
+}
+
+/// Start replaying `scenario` in the background. The real tmux scanner is
+/// paused until the replay finishes or is stopped.
+pub fn start_scenario(
+    state: Arc<AppState>,
+    app_handle: AppHandle,
+    scenario: Scenario,
+) -> Result<(), String> {
+    if !cfg!(debug_assertions) {
+        return Err("Simulation is only available in development builds".to_string());
+    }
+    if scenario.speed.is_nan() || scenario.speed <= 0.0 {
+        return Err("Scenario speed must be positive".to_string());
+    }
+    if state.simulation_active.swap(true, Ordering::SeqCst) {
+        return Err("A simulation is already running".to_string());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        log::info!(
+            "Simulation '{}' started ({} steps)",
+            scenario.name,
+            scenario.steps.len()
+        );
+        let started = tokio::time::Instant::now();
+        for (i, step) in scenario.steps.iter().enumerate() {
+            let offset_ms = (step.at_ms as f64 / scenario.speed) as u64;
+            tokio::time::sleep_until(started + tokio::time::Duration::from_millis(offset_ms)).await;
+            if !state.simulation_active.load(Ordering::SeqCst) {
+                log::info!("Simulation '{}' stopped at step {}", scenario.name, i);
+                return;
+            }
+            match &step.action {
+                StepAction::Hook(notification) => {
+                    let result = process_hook(&state, &app_handle, (**notification).clone());
+                    log::info!(
+                        "Simulation step {}: hook {} -> {}",
+                        i,
+                        notification.hook_type,
+                        result
+                    );
+                }
+                StepAction::Panes(panes) => {
+                    log::info!("Simulation step {}: {} panes", i, panes.len());
+                    crate::tmux_scanner::apply_simulated_panes(&state, panes);
+                }
+            }
+        }
+        state.simulation_active.store(false, Ordering::SeqCst);
+        // Let the real scanner take over again right away
+        state.scan_now.notify_one();
+        log::info!("Simulation '{}' finished", scenario.name);
+    });
+    Ok(())
+}
+
+// Tauri command: Replay a simulation scenario (development builds only)
+#[tauri::command]
+#[specta::specta]
+pub fn simulate_scenario(
+    state: tauri::State<Arc<AppState>>,
+    app_handle: AppHandle,
+    scenario: Scenario,
+) -> Result<(), String> {
+    start_scenario(state.inner().clone(), app_handle, scenario)
+}
+
+// Tauri command: Stop the running simulation
+#[tauri::command]
+#[specta::specta]
+pub fn stop_simulation(state: tauri::State<Arc<AppState>>) {
+    if state.simulation_active.swap(false, Ordering::SeqCst) {
+        state.scan_now.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_scenario_parses() {
+        let scenario: Scenario =
+            serde_json::from_str(include_str!("../fixtures/simulate/permission_flow.json"))
+                .unwrap();
+        assert!(!scenario.steps.is_empty());
+        assert!(scenario
+            .steps
+            .windows(2)
+            .all(|pair| pair[0].at_ms <= pair[1].at_ms));
+        assert!(scenario
+            .steps
+            .iter()
+            .any(|step| matches!(step.action, StepAction::Hook(_))));
+        assert!(scenario
+            .steps
+            .iter()
+            .any(|step| matches!(step.action, StepAction::Panes(_))));
+    }
+}
@@ -0,0 +1,278 @@
+use crate::channels::{self, ChannelEvent};
+use crate::state_machine::TimelineEntry;
+use crate::{
+    load_session_meta, load_settings, notifications_muted, send_os_notification, AppState,
+    SessionState,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// How often sessions are checked against the time limits
+const CHECK_INTERVAL_SECS: u64 = 30;
+/// Alerts per stay in a state: at the limit, then at twice and four times it
+const MAX_ALERTS: u32 = 3;
+
+/// What a time limit watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaState {
+    /// Spawning or Processing
+    Working,
+    /// Waiting for input or permission
+    Waiting,
+}
+
+impl SlaState {
+    pub(crate) fn of(state: &SessionState) -> Option<Self> {
+        match state {
+            SessionState::Spawning | SessionState::Processing => Some(Self::Working),
+            SessionState::AwaitingInput | SessionState::AwaitingPermission => Some(Self::Waiting),
+            SessionState::Complete | SessionState::Error => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Working => "Working",
+            Self::Waiting => "Waiting for you",
+        }
+    }
+}
+
+/// "Alert if a session stays `state` longer than `minutes`"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SlaRule {
+    pub state: SlaState,
+    pub minutes: u32,
+    /// Sessions with this tag; None for every session. A tag's rule wins over
+    /// the global one.
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// The limit in minutes for a session in `state` with `tag`
+fn limit_for(rules: &[SlaRule], state: SlaState, tag: Option<&str>) -> Option<u32> {
+    let matching = |rule: &&SlaRule| rule.state == state && rule.minutes > 0;
+    rules
+        .iter()
+        .filter(matching)
+        .find(|rule| tag.is_some() && rule.tag.as_deref() == tag)
+        .or_else(|| {
+            rules
+                .iter()
+                .filter(matching)
+                .find(|rule| rule.tag.is_none())
+        })
+        .map(|rule| rule.minutes)
+}
+
+/// When the session last entered `state`, going back through timeline
+/// entries of that kind, e.g. Processing after Spawning
+pub(crate) fn entered_at(timeline: &[TimelineEntry], state: SlaState) -> Option<DateTime<Utc>> {
+    timeline
+        .iter()
+        .rev()
+        .take_while(|entry| SlaState::of(&entry.to) == Some(state))
+        .last()
+        .map(|entry| entry.timestamp)
+}
+
+/// How many alerts are due `minutes` into a stay: one at the limit, then
+/// one each time the overrun doubles
+fn alerts_due(minutes: i64, limit: u32) -> u32 {
+    let limit = limit as i64;
+    (0..MAX_ALERTS)
+        .take_while(|n| minutes >= limit << n)
+        .count() as u32
+}
+
+fn format_minutes(minutes: i64) -> String {
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{} min", m),
+        (h, 0) => format!("{} h", h),
+        (h, m) => format!("{} h {} min", h, m),
+    }
+}
+
+/// A session's current stay in a watched state
+struct Stay {
+    state: SlaState,
+    entered: DateTime<Utc>,
+    alerts: u32,
+}
+
+/// Notify for sessions over their time limit, once per alert level
+fn check(state: &AppState, stays: &mut HashMap<String, Stay>, rules: &[SlaRule]) {
+    let now = Utc::now();
+    let meta = load_session_meta();
+    let sessions: Vec<_> = state.sessions.read().values().cloned().collect();
+    stays.retain(|id, _| sessions.iter().any(|s| &s.id == id));
+
+    for session in sessions {
+        let Some(kind) = SlaState::of(&session.state) else {
+            stays.remove(&session.id);
+            continue;
+        };
+        let known = stays.get(&session.id).filter(|stay| stay.state == kind);
+        let entered = entered_at(&state.timeline.for_session(&session.id), kind)
+            .or(known.map(|stay| stay.entered))
+            .unwrap_or(now);
+        let stay = stays.entry(session.id.clone()).or_insert(Stay {
+            state: kind,
+            entered,
+            alerts: 0,
+        });
+        if stay.state != kind || stay.entered != entered {
+            *stay = Stay {
+                state: kind,
+                entered,
+                alerts: 0,
+            };
+        }
+
+        let tag = meta
+            .sessions
+            .get(&session.id)
+            .and_then(|meta| meta.tag.as_deref());
+        let Some(limit) = limit_for(rules, kind, tag) else {
+            continue;
+        };
+        // A stalled session is over its limit because of the connection
+        if notifications_muted(&meta, &session) || session.stalled {
+            continue;
+        }
+        let minutes = (now - entered).num_minutes();
+        let due = alerts_due(minutes, limit);
+        if due <= stay.alerts {
+            continue;
+        }
+        stay.alerts = due;
+        log::info!(
+            "{} has been {} for {} min (limit {} min)",
+            session.id,
+            kind.label().to_lowercase(),
+            minutes,
+            limit
+        );
+        let message = if due == 1 {
+            format!("Over the {} limit", format_minutes(limit as i64))
+        } else {
+            format!("Still over the {} limit", format_minutes(limit as i64))
+        };
+        let title = format!("c3 — {}", session.project_name);
+        let subtitle = format!("{} for {}", kind.label(), format_minutes(minutes));
+        send_os_notification(
+            &message,
+            &title,
+            &subtitle,
+            &None,
+            Some(&session.id),
+            state.hook_port,
+        );
+        channels::notify(
+            ChannelEvent::Alert,
+            &title,
+            &format!("{}: {}", subtitle, message),
+            Some(&session.id),
+        );
+    }
+}
+
+/// Check sessions against the time limits in settings, sending a
+/// notification when one is exceeded and again as the overrun grows
+pub async fn start_sla_checker(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut stays: HashMap<String, Stay> = HashMap::new();
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                log::info!("SLA checker shutting down");
+                break;
+            }
+        }
+        let settings = load_settings();
+        if settings.sla_rules.is_empty() || !settings.notifications_enabled {
+            stays.clear();
+            continue;
+        }
+        let state_check = state.clone();
+        let rules = settings.sla_rules;
+        stays = tokio::task::spawn_blocking(move || {
+            check(&state_check, &mut stays, &rules);
+            stays
+        })
+        .await
+        .unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_machine::StateSource;
+    use chrono::Duration;
+
+    fn rule(state: SlaState, minutes: u32, tag: Option<&str>) -> SlaRule {
+        SlaRule {
+            state,
+            minutes,
+            tag: tag.map(String::from),
+        }
+    }
+
+    #[test]
+    fn tag_limits_win_over_global_ones() {
+        let rules = [
+            rule(SlaState::Working, 30, None),
+            rule(SlaState::Working, 120, Some("migration")),
+            rule(SlaState::Waiting, 10, None),
+        ];
+        assert_eq!(limit_for(&rules, SlaState::Working, None), Some(30));
+        assert_eq!(limit_for(&rules, SlaState::Working, Some("web")), Some(30));
+        assert_eq!(
+            limit_for(&rules, SlaState::Working, Some("migration")),
+            Some(120)
+        );
+        assert_eq!(
+            limit_for(&rules, SlaState::Waiting, Some("migration")),
+            Some(10)
+        );
+        assert_eq!(limit_for(&rules[..2], SlaState::Waiting, None), None);
+    }
+
+    #[test]
+    fn stays_start_at_the_first_state_of_their_kind() {
+        let now = Utc::now();
+        let entry = |minutes_ago: i64, to: SessionState| TimelineEntry {
+            timestamp: now - Duration::minutes(minutes_ago),
+            from: None,
+            to,
+            source: StateSource::Hook,
+        };
+        let timeline = [
+            entry(50, SessionState::AwaitingInput),
+            entry(40, SessionState::Spawning),
+            entry(35, SessionState::Processing),
+        ];
+        assert_eq!(
+            entered_at(&timeline, SlaState::Working),
+            Some(now - Duration::minutes(40))
+        );
+        assert_eq!(entered_at(&timeline, SlaState::Waiting), None);
+
+        // At the limit, then as the overrun doubles, and no more
+        let limit = 30;
+        let due: Vec<u32> = [29, 30, 59, 60, 119, 120, 1000]
+            .iter()
+            .map(|minutes| alerts_due(*minutes, limit))
+            .collect();
+        assert_eq!(due, vec![0, 1, 1, 2, 2, 3, 3]);
+        assert_eq!(format_minutes(125), "2 h 5 min");
+    }
+}
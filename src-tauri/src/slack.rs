@@ -0,0 +1,8 @@
+use crate::channels;
+use serde_json::json;
+
+/// Post `text` (Slack mrkdwn) to an incoming webhook
+pub(crate) fn post(webhook_url: &str, text: &str) -> Result<(), String> {
+    channels::post_json(webhook_url, &json!({ "text": text }), &[])
+        .map_err(|e| format!("Slack webhook failed: {}", e))
+}
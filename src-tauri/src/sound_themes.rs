@@ -0,0 +1,206 @@
+use crate::{ignore, paths, AppSettings, SoundConfig};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::Path;
+
+/// Themes that ship with C3, made of macOS system sounds
+const BUNDLED: &str = include_str!("../resources/sound-themes.json");
+/// User theme packs: `<config dir>/sound-themes/<pack>/theme.json`, next to
+/// the sound files it names
+const PACKS_DIR: &str = "sound-themes";
+const PACK_FILE: &str = "theme.json";
+
+/// A named set of alert sounds. Each is a system sound name (`Ping`) or a
+/// file path; None falls back to the per-event sound in settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SoundTheme {
+    pub name: String,
+    #[serde(default)]
+    pub permission: Option<String>,
+    #[serde(default)]
+    pub input: Option<String>,
+    #[serde(default)]
+    pub complete: Option<String>,
+    /// Shipped with C3 rather than a user pack
+    #[serde(default)]
+    pub bundled: bool,
+}
+
+/// Use `theme` for sessions in projects under the `path` glob
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ProjectSoundTheme {
+    pub path: String,
+    pub theme: String,
+}
+
+/// Payload of the `hook-sound` event
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct HookSound {
+    /// `permission`, `input` or `complete`
+    pub kind: String,
+    /// What to hand `play_sound`
+    pub sound: String,
+}
+
+fn bundled() -> Vec<SoundTheme> {
+    let mut themes: Vec<SoundTheme> = serde_json::from_str(BUNDLED).unwrap_or_default();
+    for theme in &mut themes {
+        theme.bundled = true;
+    }
+    themes
+}
+
+/// A pack's theme, with its sound files made absolute. The name defaults to
+/// the pack's directory name.
+fn read_pack(dir: &Path) -> Option<SoundTheme> {
+    let contents = fs::read_to_string(dir.join(PACK_FILE)).ok()?;
+    let mut theme: SoundTheme = match serde_json::from_str(&contents) {
+        Ok(theme) => theme,
+        Err(e) => {
+            log::warn!("Skipping sound theme {}: {}", dir.display(), e);
+            return None;
+        }
+    };
+    if theme.name.trim().is_empty() {
+        theme.name = dir.file_name()?.to_string_lossy().to_string();
+    }
+    theme.bundled = false;
+    for sound in [&mut theme.permission, &mut theme.input, &mut theme.complete]
+        .into_iter()
+        .flatten()
+    {
+        let file = dir.join(&*sound);
+        if file.exists() {
+            *sound = file.to_string_lossy().to_string();
+        }
+    }
+    Some(theme)
+}
+
+fn packs(dir: &Path) -> Vec<SoundTheme> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut themes: Vec<SoundTheme> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| read_pack(&path))
+        .collect();
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes
+}
+
+/// Bundled themes, then user packs; a pack named like a bundled theme replaces it
+fn all_themes() -> Vec<SoundTheme> {
+    let user = packs(&paths::config_dir().join(PACKS_DIR));
+    let mut themes: Vec<SoundTheme> = bundled()
+        .into_iter()
+        .filter(|theme| !user.iter().any(|pack| pack.name == theme.name))
+        .collect();
+    themes.extend(user);
+    themes
+}
+
+/// The theme for a project: the first override whose glob covers it, else
+/// the global one
+fn theme_name<'a>(settings: &'a AppSettings, project_path: Option<&str>) -> Option<&'a str> {
+    project_path
+        .and_then(|path| {
+            settings.project_sound_themes.iter().find(|project| {
+                ignore::path_pattern(&project.path)
+                    .is_some_and(|pattern| ignore::matches_path(&pattern, path))
+            })
+        })
+        .map(|project| project.theme.as_str())
+        .or(settings.sound_theme.as_deref())
+}
+
+fn pick(
+    settings: &AppSettings,
+    themes: &[SoundTheme],
+    kind: &str,
+    project_path: Option<&str>,
+) -> Option<String> {
+    let (config, themed): (&SoundConfig, fn(&SoundTheme) -> &Option<String>) = match kind {
+        "permission" => (&settings.permission_sound, |t| &t.permission),
+        "input" => (&settings.input_sound, |t| &t.input),
+        "complete" => (&settings.complete_sound, |t| &t.complete),
+        _ => return None,
+    };
+    if !config.enabled {
+        return None;
+    }
+    let theme = theme_name(settings, project_path)
+        .and_then(|name| themes.iter().find(|theme| theme.name == name));
+    theme
+        .and_then(|theme| themed(theme).clone())
+        .or_else(|| config.sound.clone())
+        .or_else(|| Some("Ping".to_string()))
+}
+
+/// The sound for an alert of `kind` in a project, or None when that kind of
+/// alert is switched off
+pub(crate) fn sound_for(
+    settings: &AppSettings,
+    kind: &str,
+    project_path: Option<&str>,
+) -> Option<HookSound> {
+    let sound = pick(settings, &all_themes(), kind, project_path)?;
+    Some(HookSound {
+        kind: kind.to_string(),
+        sound,
+    })
+}
+
+// Tauri command: Bundled sound themes and the user's theme packs
+#[tauri::command]
+#[specta::specta]
+pub fn list_sound_themes() -> Vec<SoundTheme> {
+    all_themes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn project_themes_override_the_global_one() {
+        let themes = bundled();
+        assert!(themes.iter().any(|theme| theme.name == "Classic"));
+
+        let mut settings = AppSettings {
+            sound_theme: Some("Soft".to_string()),
+            project_sound_themes: vec![ProjectSoundTheme {
+                path: "/src/prod-api/**".to_string(),
+                theme: "Alarm".to_string(),
+            }],
+            ..AppSettings::default()
+        };
+        let sound = |settings: &AppSettings, kind, path| pick(settings, &themes, kind, path);
+
+        assert_eq!(
+            sound(&settings, "permission", Some("/src/prod-api")).as_deref(),
+            Some("Sosumi")
+        );
+        assert_eq!(
+            sound(&settings, "permission", Some("/src/blog")).as_deref(),
+            Some("Tink")
+        );
+        // Switched-off alerts stay off whatever the theme
+        assert_eq!(sound(&settings, "complete", Some("/src/blog")), None);
+
+        // Without a theme, the per-event sound
+        settings.sound_theme = None;
+        settings.input_sound.sound = Some("Hero".to_string());
+        assert_eq!(
+            sound(&settings, "input", Some("/src/blog")).as_deref(),
+            Some("Hero")
+        );
+        assert_eq!(
+            sound(&settings, "permission", None).as_deref(),
+            Some("Ping")
+        );
+    }
+}
@@ -0,0 +1,407 @@
+use crate::{event_log, SessionState};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+/// Timeline entries kept per session
+const MAX_TIMELINE_ENTRIES: usize = 50;
+/// Sessions with a timeline; the least recently changed are dropped beyond this
+const MAX_TIMELINE_SESSIONS: usize = 200;
+
+/// Where a state change came from. Hooks are reported by the agent itself and
+/// win over the tmux scanner, which only infers state from titles and JSONL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum StateSource {
+    Hook,
+    Scanner,
+}
+
+/// How a state the scanner infers is weighed against the last hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Arbitration {
+    /// Hooks win for the grace period after they fire, then the scanner does
+    #[default]
+    HookGracePeriod,
+    /// Once a session has had a hook, only hooks change its state
+    HookAlwaysWins,
+    /// The scanner may override a hook on its next cycle
+    ScannerAlwaysWins,
+    /// Whichever source saw the session more recently wins, each source's
+    /// evidence counted as that much newer as its confidence
+    NewestEvidence,
+}
+
+/// Arbitration settings, kept with the live timings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arbiter {
+    pub policy: Arbitration,
+    pub grace_period: Duration,
+    pub hook_confidence: Duration,
+    pub scanner_confidence: Duration,
+    /// Read Claude panes' content as a signal besides title and transcript
+    pub pane_signals: bool,
+}
+
+/// A state one of the scanner's signals reads, and how sure it is (0–100)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    pub state: SessionState,
+    pub confidence: u8,
+}
+
+/// The most confident of the scanner's signals; the first wins a tie
+pub fn strongest(signals: impl IntoIterator<Item = Signal>) -> Signal {
+    signals
+        .into_iter()
+        .reduce(|best, signal| {
+            if signal.confidence > best.confidence {
+                signal
+            } else {
+                best
+            }
+        })
+        .expect("at least one signal")
+}
+
+impl Arbiter {
+    /// Whether the session's last hook, `since_hook` ago (None for never),
+    /// still outranks the scanner, whose newest evidence (the transcript's
+    /// last message) is `scanner_evidence` old when known
+    pub fn hook_protected(
+        &self,
+        since_hook: Option<Duration>,
+        scanner_evidence: Option<Duration>,
+    ) -> bool {
+        let Some(since_hook) = since_hook else {
+            return false;
+        };
+        match self.policy {
+            Arbitration::HookGracePeriod => since_hook < self.grace_period,
+            Arbitration::HookAlwaysWins => true,
+            Arbitration::ScannerAlwaysWins => false,
+            Arbitration::NewestEvidence => match scanner_evidence {
+                // Ties go to the hook, which the agent reported itself
+                Some(evidence) => {
+                    since_hook.saturating_sub(self.hook_confidence)
+                        <= evidence.saturating_sub(self.scanner_confidence)
+                }
+                // Nothing to compare, e.g. a title spinner alone
+                None => since_hook < self.grace_period,
+            },
+        }
+    }
+}
+
+/// Side effects to run when a transition is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Effects {
+    /// Send the session to the frontend
+    pub emit: bool,
+    /// The transition may raise an OS notification (subject to settings/debounce)
+    pub notify: bool,
+    /// Record the transition in the session timeline
+    pub timeline: bool,
+}
+
+/// Outcome of a proposed state change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Apply(Effects),
+    /// Keep the current state; the reason ends up in the hook event log
+    Skip(&'static str),
+}
+
+/// Decide whether `source` may move a session from `from` (None for a new
+/// session) to `to`. `hook_protected` is true while the session is inside the
+/// hook grace period.
+pub fn decide(
+    from: Option<&SessionState>,
+    to: &SessionState,
+    source: StateSource,
+    hook_protected: bool,
+) -> Decision {
+    let changed = from != Some(to);
+    match source {
+        StateSource::Hook => {
+            // Claude fires Notification after Stop; don't reopen a finished session
+            if from == Some(&SessionState::Complete) && *to == SessionState::AwaitingInput {
+                return Decision::Skip("session already Complete");
+            }
+            Decision::Apply(Effects {
+                emit: true,
+                notify: true,
+                timeline: changed,
+            })
+        }
+        StateSource::Scanner => {
+            if hook_protected && from.is_some() {
+                return Decision::Skip("hook grace period");
+            }
+            Decision::Apply(Effects {
+                emit: changed,
+                notify: false,
+                timeline: changed,
+            })
+        }
+    }
+}
+
+/// One applied state change
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct TimelineEntry {
+    pub timestamp: DateTime<Utc>,
+    pub from: Option<SessionState>,
+    pub to: SessionState,
+    pub source: StateSource,
+}
+
+/// Recent state changes per session, kept in memory
+#[derive(Default)]
+pub struct Timeline {
+    sessions: Mutex<HashMap<String, VecDeque<TimelineEntry>>>,
+}
+
+impl Timeline {
+    pub fn record(
+        &self,
+        session_id: &str,
+        from: Option<SessionState>,
+        to: SessionState,
+        source: StateSource,
+    ) {
+        event_log::record(event_log::Event::State {
+            session_id: session_id.to_string(),
+            from: from.clone(),
+            to: to.clone(),
+            source,
+        });
+        let mut sessions = self.sessions.lock();
+        let entries = sessions.entry(session_id.to_string()).or_default();
+        entries.push_back(TimelineEntry {
+            timestamp: Utc::now(),
+            from,
+            to,
+            source,
+        });
+        while entries.len() > MAX_TIMELINE_ENTRIES {
+            entries.pop_front();
+        }
+
+        if sessions.len() > MAX_TIMELINE_SESSIONS {
+            let oldest = sessions
+                .iter()
+                .min_by_key(|(_, entries)| entries.back().map(|e| e.timestamp))
+                .map(|(id, _)| id.clone());
+            if let Some(oldest) = oldest {
+                sessions.remove(&oldest);
+            }
+        }
+    }
+
+    /// Keep a session's history when it moves to a new ID
+    pub fn rename(&self, from: &str, to: &str) {
+        let mut sessions = self.sessions.lock();
+        if let Some(entries) = sessions.remove(from) {
+            sessions.insert(to.to_string(), entries);
+        }
+    }
+
+    /// Transitions of every session recorded after `after`, oldest first
+    pub fn since(&self, after: DateTime<Utc>) -> Vec<(String, TimelineEntry)> {
+        let mut entries: Vec<(String, TimelineEntry)> = self
+            .sessions
+            .lock()
+            .iter()
+            .flat_map(|(id, entries)| {
+                entries
+                    .iter()
+                    .filter(|entry| entry.timestamp > after)
+                    .map(|entry| (id.clone(), entry.clone()))
+            })
+            .collect();
+        entries.sort_by_key(|(_, entry)| entry.timestamp);
+        entries
+    }
+
+    /// Transitions for one session, oldest first
+    pub fn for_session(&self, session_id: &str) -> Vec<TimelineEntry> {
+        self.sessions
+            .lock()
+            .get(session_id)
+            .map(|entries| entries.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use SessionState::*;
+
+    const ALL_STATES: [SessionState; 6] = [
+        Spawning,
+        Processing,
+        AwaitingInput,
+        AwaitingPermission,
+        Complete,
+        Error,
+    ];
+
+    #[test]
+    fn hook_complete_to_awaiting_input_is_suppressed() {
+        assert_eq!(
+            decide(Some(&Complete), &AwaitingInput, StateSource::Hook, false),
+            Decision::Skip("session already Complete")
+        );
+        // Also while the hook grace period is running
+        assert_eq!(
+            decide(Some(&Complete), &AwaitingInput, StateSource::Hook, true),
+            Decision::Skip("session already Complete")
+        );
+    }
+
+    #[test]
+    fn hook_applies_every_other_edge_and_notifies() {
+        for from in &ALL_STATES {
+            for to in &ALL_STATES {
+                if *from == Complete && *to == AwaitingInput {
+                    continue;
+                }
+                for protected in [false, true] {
+                    assert_eq!(
+                        decide(Some(from), to, StateSource::Hook, protected),
+                        Decision::Apply(Effects {
+                            emit: true,
+                            notify: true,
+                            timeline: from != to,
+                        }),
+                        "{:?} -> {:?}",
+                        from,
+                        to
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn hook_creating_a_session_is_recorded() {
+        for to in &ALL_STATES {
+            assert_eq!(
+                decide(None, to, StateSource::Hook, false),
+                Decision::Apply(Effects {
+                    emit: true,
+                    notify: true,
+                    timeline: true,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn scanner_defers_to_hook_during_grace_period() {
+        for from in &ALL_STATES {
+            for to in &ALL_STATES {
+                assert_eq!(
+                    decide(Some(from), to, StateSource::Scanner, true),
+                    Decision::Skip("hook grace period")
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scanner_emits_only_on_change_and_never_notifies() {
+        for from in &ALL_STATES {
+            for to in &ALL_STATES {
+                let changed = from != to;
+                assert_eq!(
+                    decide(Some(from), to, StateSource::Scanner, false),
+                    Decision::Apply(Effects {
+                        emit: changed,
+                        notify: false,
+                        timeline: changed,
+                    }),
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scanner_discovering_a_session_ignores_grace_period() {
+        // A hook timestamp without a session (e.g. just removed) must not hide a new pane
+        for to in &ALL_STATES {
+            assert_eq!(
+                decide(None, to, StateSource::Scanner, true),
+                Decision::Apply(Effects {
+                    emit: true,
+                    notify: false,
+                    timeline: true,
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn arbitration_policies_weigh_hook_against_scanner() {
+        let secs = Duration::from_secs;
+        let arbiter = |policy| Arbiter {
+            policy,
+            grace_period: secs(10),
+            hook_confidence: secs(2),
+            scanner_confidence: Duration::ZERO,
+            pane_signals: false,
+        };
+        for policy in [
+            Arbitration::HookGracePeriod,
+            Arbitration::HookAlwaysWins,
+            Arbitration::ScannerAlwaysWins,
+            Arbitration::NewestEvidence,
+        ] {
+            assert!(!arbiter(policy).hook_protected(None, Some(secs(1))));
+        }
+
+        let grace = arbiter(Arbitration::HookGracePeriod);
+        assert!(grace.hook_protected(Some(secs(5)), Some(secs(1))));
+        assert!(!grace.hook_protected(Some(secs(11)), None));
+        assert!(
+            arbiter(Arbitration::HookAlwaysWins).hook_protected(Some(secs(3600)), Some(secs(1)))
+        );
+        assert!(!arbiter(Arbitration::ScannerAlwaysWins).hook_protected(Some(secs(1)), None));
+
+        let newest = arbiter(Arbitration::NewestEvidence);
+        // A transcript line 1s old beats a hook 5s old
+        assert!(!newest.hook_protected(Some(secs(5)), Some(secs(1))));
+        // The hook's confidence covers a transcript flushed a little later
+        assert!(newest.hook_protected(Some(secs(3)), Some(secs(1))));
+        assert!(newest.hook_protected(Some(secs(30)), Some(secs(60))));
+        assert!(!newest.hook_protected(Some(secs(30)), None));
+    }
+
+    #[test]
+    fn timeline_keeps_the_most_recent_entries() {
+        let timeline = Timeline::default();
+        for i in 0..(MAX_TIMELINE_ENTRIES + 5) {
+            let to = if i % 2 == 0 {
+                Processing
+            } else {
+                AwaitingInput
+            };
+            timeline.record("tmux:main:0.0", None, to, StateSource::Scanner);
+        }
+        let entries = timeline.for_session("tmux:main:0.0");
+        assert_eq!(entries.len(), MAX_TIMELINE_ENTRIES);
+        // The five oldest (i = 0..5) were dropped
+        assert_eq!(entries.first().unwrap().to, AwaitingInput);
+        assert_eq!(entries.last().unwrap().to, Processing);
+        assert!(timeline.for_session("tmux:other:0.0").is_empty());
+    }
+}
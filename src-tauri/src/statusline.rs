@@ -0,0 +1,154 @@
+use crate::session_identity;
+use crate::{C3Session, SessionState};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Waiting projects named in the status line before the rest are counted
+const NAMED_WAITING: usize = 2;
+
+/// The other sessions, as seen from one of them
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Statusline {
+    pub others: usize,
+    pub waiting: usize,
+    pub working: usize,
+    /// Projects of the waiting sessions, most recently active first
+    pub waiting_projects: Vec<String>,
+}
+
+/// The session asking: by the agent's own session ID (a Claude conversation
+/// UUID or C3's ID), or else by the tmux pane it runs in
+fn own_session<'a>(
+    sessions: &'a HashMap<String, C3Session>,
+    session_id: Option<&str>,
+    pane: Option<&str>,
+) -> Option<&'a C3Session> {
+    session_id
+        .filter(|id| !id.is_empty())
+        .and_then(|id| {
+            sessions
+                .get(id)
+                .or_else(|| sessions.get(&session_identity::claude_session_id(id)))
+        })
+        .or_else(|| {
+            pane.filter(|pane| !pane.is_empty())
+                .and_then(|pane| session_identity::at_pane(sessions, pane))
+        })
+}
+
+pub(crate) fn statusline(
+    sessions: &HashMap<String, C3Session>,
+    session_id: Option<&str>,
+    pane: Option<&str>,
+) -> Statusline {
+    let own = own_session(sessions, session_id, pane).map(|s| s.id.as_str());
+    let mut others: Vec<&C3Session> = sessions
+        .values()
+        .filter(|s| Some(s.id.as_str()) != own && s.state != SessionState::Complete)
+        .collect();
+    others.sort_by_key(|s| std::cmp::Reverse(s.last_activity));
+    let waiting: Vec<&C3Session> = others
+        .iter()
+        .copied()
+        .filter(|s| {
+            matches!(
+                s.state,
+                SessionState::AwaitingInput | SessionState::AwaitingPermission
+            )
+        })
+        .collect();
+    Statusline {
+        others: others.len(),
+        waiting: waiting.len(),
+        working: others
+            .iter()
+            .filter(|s| matches!(s.state, SessionState::Spawning | SessionState::Processing))
+            .count(),
+        waiting_projects: waiting.iter().map(|s| s.project_name.clone()).collect(),
+    }
+}
+
+/// One line for Claude Code's status line, e.g.
+/// `c3: 3 other sessions · 1 waiting (api-server)`
+pub(crate) fn format_claude(status: &Statusline) -> String {
+    let sessions = match status.others {
+        0 => return "c3: no other sessions".to_string(),
+        1 => "1 other session".to_string(),
+        n => format!("{} other sessions", n),
+    };
+    if status.waiting == 0 {
+        return format!("c3: {}", sessions);
+    }
+    let mut named: Vec<String> = status
+        .waiting_projects
+        .iter()
+        .take(NAMED_WAITING)
+        .cloned()
+        .collect();
+    if status.waiting_projects.len() > NAMED_WAITING {
+        named.push(format!(
+            "+{}",
+            status.waiting_projects.len() - NAMED_WAITING
+        ));
+    }
+    format!(
+        "c3: {} · {} waiting ({})",
+        sessions,
+        status.waiting,
+        named.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn session(id: &str, project: &str, state: SessionState, minutes_ago: i64) -> C3Session {
+        C3Session {
+            project_name: project.to_string(),
+            state,
+            last_activity: Utc::now() - Duration::minutes(minutes_ago),
+            ..C3Session::test(id)
+        }
+    }
+
+    #[test]
+    fn other_sessions_are_summarized_for_claude() {
+        let uuid = "0b5e8a34-9d0c-4a8e-bb2a-6d2f3c1e7a90";
+        let own = session_identity::claude_session_id(uuid);
+        let sessions: HashMap<String, C3Session> = [
+            session(&own, "blog", SessionState::Processing, 0),
+            session("tmux:%2", "api-server", SessionState::AwaitingPermission, 5),
+            session("tmux:%3", "docs", SessionState::AwaitingInput, 1),
+            session("tmux:%4", "infra", SessionState::Processing, 2),
+            session("tmux:%5", "old", SessionState::Complete, 60),
+        ]
+        .into_iter()
+        .map(|s| (s.id.clone(), s))
+        .collect();
+
+        let status = statusline(&sessions, Some(uuid), None);
+        assert_eq!(
+            status,
+            Statusline {
+                others: 3,
+                waiting: 2,
+                working: 1,
+                waiting_projects: vec!["docs".to_string(), "api-server".to_string()],
+            }
+        );
+        assert_eq!(
+            format_claude(&status),
+            "c3: 3 other sessions · 2 waiting (docs, api-server)"
+        );
+
+        // Unknown callers see every session
+        assert_eq!(statusline(&sessions, None, None).others, 4);
+        assert_eq!(
+            format_claude(&statusline(&HashMap::new(), None, None)),
+            "c3: no other sessions"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+use crate::transcript::{self, Role, TranscriptMessage};
+use crate::{cmd, paths, AppSettings, AppState, C3Session};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+
+/// Lines of the final reply kept when it is the summary
+const SUMMARY_LINES: usize = 3;
+const MAX_SUMMARY_CHARS: usize = 300;
+/// The end of the conversation passed to `claude -p`
+const MAX_TRANSCRIPT_CHARS: usize = 40_000;
+/// A summarizer is given up on after this, and the final reply used instead
+const SUMMARIZER_TIMEOUT_SECS: u64 = 120;
+const CLAUDE_PROMPT: &str = "This is the end of a coding agent session's transcript. \
+    In one or two sentences, say what was accomplished. Reply with the summary only.";
+
+/// Where summarizers run, so hooks fired by a `claude -p` in one match no session
+fn summarizer_dir() -> PathBuf {
+    paths::state_dir().join("summarizer")
+}
+
+/// Hooks from agents C3 runs to summarize a session
+pub(crate) fn is_summarizer_cwd(cwd: &str) -> bool {
+    Path::new(cwd).starts_with(summarizer_dir())
+}
+
+/// Text of the last assistant message that has any
+fn final_reply(messages: &[TranscriptMessage]) -> Option<String> {
+    messages
+        .iter()
+        .rev()
+        .filter(|message| message.role == Role::Assistant)
+        .map(TranscriptMessage::text)
+        .find(|text| !text.trim().is_empty())
+}
+
+/// Shorten to `MAX_SUMMARY_CHARS`, ending in an ellipsis when cut
+fn clip(text: &str) -> String {
+    if text.chars().count() <= MAX_SUMMARY_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_SUMMARY_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// The first lines of the final reply, leaving out headings, code blocks
+/// and markdown emphasis
+pub(crate) fn reply_summary(messages: &[TranscriptMessage]) -> Option<String> {
+    let reply = final_reply(messages)?;
+    let mut in_code = false;
+    let mut lines = Vec::new();
+    for line in reply.lines().map(str::trim) {
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code || line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        lines.push(line.replace("**", ""));
+        if lines.len() == SUMMARY_LINES {
+            break;
+        }
+    }
+    (!lines.is_empty()).then(|| clip(&lines.join(" ")))
+}
+
+/// The conversation's text as "User: ..." and "Assistant: ...", from the end
+fn transcript_excerpt(messages: &[TranscriptMessage]) -> String {
+    let text = messages
+        .iter()
+        .filter_map(|message| {
+            let text = message.text();
+            let speaker = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+            };
+            (!text.trim().is_empty()).then(|| format!("{}: {}", speaker, text.trim()))
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let skip = text.chars().count().saturating_sub(MAX_TRANSCRIPT_CHARS);
+    text.chars().skip(skip).collect()
+}
+
+/// Run a summarizer with `input` on stdin. Its output, or None when it fails
+/// or takes too long.
+async fn run_summarizer(
+    mut command: tokio::process::Command,
+    name: &str,
+    input: String,
+) -> Option<String> {
+    let dir = summarizer_dir();
+    std::fs::create_dir_all(&dir).ok()?;
+    command
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command
+        .spawn()
+        .map_err(|e| log::warn!("Failed to start summarizer {}: {}", name, e))
+        .ok()?;
+    let mut stdin = child.stdin.take()?;
+    tokio::spawn(async move {
+        let _ = stdin.write_all(input.as_bytes()).await;
+    });
+
+    let timeout = tokio::time::Duration::from_secs(SUMMARIZER_TIMEOUT_SECS);
+    let output = match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) if output.status.success() => output,
+        Ok(Ok(output)) => {
+            log::warn!(
+                "Summarizer {} exited with {}: {}",
+                name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            return None;
+        }
+        Ok(Err(e)) => {
+            log::warn!("Summarizer {} failed: {}", name, e);
+            return None;
+        }
+        Err(_) => {
+            log::warn!(
+                "Summarizer {} took over {} s",
+                name,
+                SUMMARIZER_TIMEOUT_SECS
+            );
+            return None;
+        }
+    };
+    let summary = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!summary.is_empty()).then(|| clip(&summary))
+}
+
+/// Ask `claude -p` for a summary of the end of the conversation
+async fn claude_summary(messages: &[TranscriptMessage]) -> Option<String> {
+    let mut command = tokio::process::Command::from(cmd("claude"));
+    command.args(["-p", CLAUDE_PROMPT]);
+    run_summarizer(command, "claude", transcript_excerpt(messages)).await
+}
+
+/// Run the user's `summarizer_command` with `sh -c`, the transcript's path
+/// on stdin
+async fn command_summary(
+    summarizer: &str,
+    transcript: &Path,
+    agent_kind: &str,
+    project_path: Option<&str>,
+) -> Option<String> {
+    let mut command = tokio::process::Command::from(cmd("/bin/sh"));
+    command
+        .arg("-c")
+        .arg(summarizer)
+        .env("C3_AGENT_KIND", agent_kind)
+        .env("C3_PROJECT_PATH", project_path.unwrap_or_default());
+    let input = format!("{}\n", transcript.display());
+    run_summarizer(command, summarizer, input).await
+}
+
+/// Put the summary on the session, or on its archived copy
+fn store(state: &AppState, session_id: &str, summary: String) {
+    let summary = state.redactor.read().redact(&summary).into_owned();
+    let updated = state.sessions.write().get_mut(session_id).map(|session| {
+        session.summary = Some(summary.clone());
+        session.clone()
+    });
+    match updated {
+        Some(session) => state.events.session_updated(session),
+        None => state.archive.set_summary(session_id, summary),
+    }
+}
+
+/// Summarize what a session that just stopped accomplished, in the background
+pub(crate) fn on_stop(state: &Arc<AppState>, session: &C3Session, settings: &AppSettings) {
+    let Some((agent_kind, path)) = transcript::session_transcript(session) else {
+        return;
+    };
+    let state = state.clone();
+    let session_id = session.id.clone();
+    let project_path = session.project_path.clone();
+    let summarizer = settings
+        .summarizer_command
+        .clone()
+        .filter(|command| !command.trim().is_empty());
+    let use_claude = settings.summarize_with_claude;
+    tauri::async_runtime::spawn(async move {
+        let (kind, transcript) = (agent_kind.clone(), path.clone());
+        let messages = match tokio::task::spawn_blocking(move || {
+            transcript::read_transcript(&kind, &transcript)
+        })
+        .await
+        {
+            Ok(Ok(messages)) => messages,
+            Ok(Err(e)) => {
+                log::warn!("Not summarizing {}: {}", session_id, e);
+                return;
+            }
+            Err(_) => return,
+        };
+        let summary = if let Some(summarizer) = &summarizer {
+            command_summary(summarizer, &path, &agent_kind, project_path.as_deref()).await
+        } else if use_claude {
+            claude_summary(&messages).await
+        } else {
+            None
+        };
+        if let Some(summary) = summary.or_else(|| reply_summary(&messages)) {
+            store(&state, &session_id, summary);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::MessagePart;
+
+    fn message(role: Role, text: &str) -> TranscriptMessage {
+        TranscriptMessage {
+            role,
+            timestamp: None,
+            parts: vec![MessagePart::Text(text.to_string())],
+        }
+    }
+
+    #[tokio::test]
+    async fn a_summarizer_command_reads_the_transcript_path() {
+        let path = Path::new("/src/api/session.jsonl");
+        let summary = command_summary(
+            "read path; echo \"$C3_AGENT_KIND summary of $path\"",
+            path,
+            "claude",
+            None,
+        )
+        .await;
+        assert_eq!(
+            summary.as_deref(),
+            Some("claude summary of /src/api/session.jsonl")
+        );
+        assert_eq!(command_summary("exit 1", path, "claude", None).await, None);
+        assert_eq!(command_summary("true", path, "claude", None).await, None);
+    }
+
+    #[test]
+    fn the_final_reply_opens_the_summary() {
+        let messages = [
+            message(Role::User, "Fix the flaky cache test"),
+            message(
+                Role::Assistant,
+                "## Done\n\nThe test now **injects a clock**.\n\n```\ncargo test\n```\nAll 29 tests pass.\nAnything else?\nMore.",
+            ),
+            TranscriptMessage {
+                role: Role::Assistant,
+                timestamp: None,
+                parts: Vec::new(),
+            },
+        ];
+        assert_eq!(
+            reply_summary(&messages).as_deref(),
+            Some("The test now injects a clock. All 29 tests pass. Anything else?")
+        );
+        assert_eq!(reply_summary(&messages[..1]), None);
+        assert_eq!(
+            transcript_excerpt(&messages[..1]),
+            "User: Fix the flaky cache test"
+        );
+        assert!(clip(&"word ".repeat(100)).ends_with('…'));
+        assert!(is_summarizer_cwd(
+            &summarizer_dir().join("x").to_string_lossy()
+        ));
+        assert!(!is_summarizer_cwd("/src/api"));
+    }
+}
@@ -0,0 +1,241 @@
+use parking_lot::RwLock;
+use serde::Serialize;
+use specta::Type;
+use std::any::Any;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::watch;
+
+/// First restart delay; doubles on each consecutive failure
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A task that ran this long before dying starts over from the initial backoff
+const HEALTHY_AFTER: Duration = Duration::from_secs(120);
+
+/// A background task that died and is waiting to be restarted
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemDown {
+    pub name: String,
+    pub reason: String,
+    pub restart_in_secs: u64,
+}
+
+/// A background task's current run and its failures so far
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemHealth {
+    pub name: String,
+    pub running: bool,
+    /// Seconds since the current run started; None while waiting to restart
+    pub uptime_secs: Option<u64>,
+    pub restarts: u32,
+    /// Why it last stopped, if it ever did
+    pub last_error: Option<String>,
+}
+
+struct Run {
+    started: Option<Instant>,
+    restarts: u32,
+    last_error: Option<String>,
+}
+
+/// Subsystems currently down, for windows opened after the event was sent,
+/// and every subsystem's runs for the diagnostics view
+#[derive(Default)]
+pub struct Subsystems {
+    down: RwLock<HashMap<String, SubsystemDown>>,
+    runs: RwLock<HashMap<String, Run>>,
+}
+
+impl Subsystems {
+    fn started(&self, name: &str) {
+        let mut runs = self.runs.write();
+        match runs.get_mut(name) {
+            Some(run) => {
+                run.started = Some(Instant::now());
+                run.restarts += 1;
+            }
+            None => {
+                runs.insert(
+                    name.to_string(),
+                    Run {
+                        started: Some(Instant::now()),
+                        restarts: 0,
+                        last_error: None,
+                    },
+                );
+            }
+        }
+    }
+
+    fn stopped(&self, name: &str, reason: &str) {
+        if let Some(run) = self.runs.write().get_mut(name) {
+            run.started = None;
+            run.last_error = Some(reason.to_string());
+        }
+    }
+
+    /// Every subsystem started so far, by name
+    pub fn health(&self) -> Vec<SubsystemHealth> {
+        let mut health: Vec<SubsystemHealth> = self
+            .runs
+            .read()
+            .iter()
+            .map(|(name, run)| SubsystemHealth {
+                name: name.clone(),
+                running: run.started.is_some(),
+                uptime_secs: run.started.map(|started| started.elapsed().as_secs()),
+                restarts: run.restarts,
+                last_error: run.last_error.clone(),
+            })
+            .collect();
+        health.sort_by(|a, b| a.name.cmp(&b.name));
+        health
+    }
+}
+
+fn backoff(failures: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(failures))
+        .min(MAX_BACKOFF)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Shutdown was requested, or its sender was dropped on exit
+fn shutting_down(shutdown: &watch::Receiver<bool>) -> bool {
+    *shutdown.borrow() || shutdown.has_changed().is_err()
+}
+
+fn set_down(app: &AppHandle, down: Option<SubsystemDown>, name: &str) {
+    if let Some(subsystems) = app.try_state::<Subsystems>() {
+        let mut map = subsystems.down.write();
+        match &down {
+            Some(down) => map.insert(name.to_string(), down.clone()),
+            None => map.remove(name),
+        };
+    }
+    let _ = match down {
+        Some(down) => app.emit("subsystem-down", down),
+        None => app.emit("subsystem-up", name),
+    };
+}
+
+/// Run `task` in the background and restart it, with backoff, whenever it
+/// panics or returns before shutdown. `task` is called again for each run.
+pub fn spawn<F, Fut>(app: &AppHandle, name: &'static str, shutdown: watch::Receiver<bool>, task: F)
+where
+    F: Fn(watch::Receiver<bool>) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut shutdown = shutdown;
+        let mut failures = 0;
+        loop {
+            let started = Instant::now();
+            if let Some(subsystems) = app.try_state::<Subsystems>() {
+                subsystems.started(name);
+            }
+            let result = tokio::spawn(task(shutdown.clone())).await;
+            if shutting_down(&shutdown) {
+                break;
+            }
+
+            let reason = match result {
+                Ok(()) => "stopped unexpectedly".to_string(),
+                Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+                Err(e) => e.to_string(),
+            };
+            if started.elapsed() >= HEALTHY_AFTER {
+                failures = 0;
+            }
+            let delay = backoff(failures);
+            failures = failures.saturating_add(1);
+            log::error!("{} {}, restarting in {}s", name, reason, delay.as_secs());
+            if let Some(subsystems) = app.try_state::<Subsystems>() {
+                subsystems.stopped(name, &reason);
+            }
+            set_down(
+                &app,
+                Some(SubsystemDown {
+                    name: name.to_string(),
+                    reason,
+                    restart_in_secs: delay.as_secs(),
+                }),
+                name,
+            );
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = shutdown.changed() => break,
+            }
+            log::info!("Restarting {}", name);
+            set_down(&app, None, name);
+        }
+    });
+}
+
+// Tauri command: Background tasks that are down and waiting to restart
+#[tauri::command]
+#[specta::specta]
+pub fn get_down_subsystems(subsystems: tauri::State<Subsystems>) -> Vec<SubsystemDown> {
+    subsystems.down.read().values().cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(0), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(8));
+        assert_eq!(backoff(6), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn subsystem_runs_count_restarts_and_keep_the_last_error() {
+        let subsystems = Subsystems::default();
+        subsystems.started("Tmux scanner");
+        subsystems.stopped("Tmux scanner", "panicked: boom");
+        let health = subsystems.health();
+        assert!(!health[0].running);
+        assert_eq!(health[0].uptime_secs, None);
+
+        subsystems.started("Tmux scanner");
+        subsystems.started("Hook server");
+        let health = subsystems.health();
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].name, "Hook server");
+        assert_eq!(health[1].restarts, 1);
+        assert!(health[1].running);
+        assert_eq!(health[1].last_error.as_deref(), Some("panicked: boom"));
+    }
+
+    #[tokio::test]
+    async fn panic_payloads_become_messages() {
+        let handle = tokio::spawn(async { panic!("scanner blew up: {}", 42) });
+        let err = handle.await.unwrap_err();
+        assert!(err.is_panic());
+        assert_eq!(panic_message(err.into_panic()), "scanner blew up: 42");
+
+        let handle = tokio::spawn(async { std::panic::panic_any(7u8) });
+        assert_eq!(
+            panic_message(handle.await.unwrap_err().into_panic()),
+            "unknown panic"
+        );
+    }
+}
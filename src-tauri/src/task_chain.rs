@@ -0,0 +1,172 @@
+use crate::launch_queue;
+use crate::{
+    load_session_meta, notifications_muted, save_session_meta, send_input, send_os_notification,
+    session_meta_is_empty, AppSettings, AppState, C3Session, SessionMetaStore, SessionState,
+};
+use std::sync::Arc;
+
+/// Prompts queued after this many are refused, to catch pasting a whole file
+const MAX_QUEUED_PROMPTS: usize = 20;
+
+/// Trimmed prompts, one per non-empty entry
+fn clean(prompts: Vec<String>) -> Vec<String> {
+    prompts
+        .into_iter()
+        .map(|prompt| prompt.trim().to_string())
+        .filter(|prompt| !prompt.is_empty())
+        .collect()
+}
+
+/// Whether a queued prompt may still go out: the agent hasn't been set to
+/// work again, e.g. by a reply typed in the meantime
+fn still_idle(session: &C3Session) -> bool {
+    matches!(
+        session.state,
+        SessionState::Complete | SessionState::AwaitingInput
+    )
+}
+
+/// Type a session's first queued prompt at its prompt and take it off the
+/// queue
+fn send_next(state: &AppState, session_id: &str) -> Result<SessionMetaStore, String> {
+    let mut store = load_session_meta();
+    let prompt = store
+        .sessions
+        .get(session_id)
+        .and_then(|meta| meta.next_prompts.first().cloned())
+        .ok_or("No prompt queued for this session")?;
+    send_input(state, session_id, &prompt)?;
+    if let Some(meta) = store.sessions.get_mut(session_id) {
+        meta.next_prompts.remove(0);
+    }
+    store.sessions.retain(|_, m| !session_meta_is_empty(m));
+    save_session_meta(&store)?;
+    log::info!(
+        "Sent queued prompt to {} ({} left)",
+        session_id,
+        store
+            .sessions
+            .get(session_id)
+            .map_or(0, |meta| meta.next_prompts.len())
+    );
+    Ok(store)
+}
+
+/// After a Stop hook: send the session's next queued prompt once the delay
+/// from settings has passed, or ask first
+pub(crate) fn on_stop(state: &Arc<AppState>, session: &C3Session, settings: &AppSettings) {
+    let meta = load_session_meta();
+    let Some(prompt) = meta
+        .sessions
+        .get(&session.id)
+        .and_then(|meta| meta.next_prompts.first().cloned())
+    else {
+        return;
+    };
+    if settings.chain_confirm {
+        if !notifications_muted(&meta, session) {
+            send_os_notification(
+                &prompt,
+                &format!("c3 — {}", session.project_name),
+                "Next prompt ready; send it from the session's menu",
+                &None,
+                Some(&session.id),
+                state.hook_port,
+            );
+        }
+        return;
+    }
+
+    let state = state.clone();
+    let label = format!("Next prompt: {}", session.project_name);
+    let cwd = session.project_path.clone().unwrap_or_default();
+    let session_id = session.id.clone();
+    let delay = tokio::time::Duration::from_secs(settings.chain_delay_secs);
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        // Waits behind other automatic launches when too many are working
+        let result = tokio::task::spawn_blocking(move || {
+            let launch = launch_queue::launch(label, &cwd, None, Some(&prompt), Some(&session_id));
+            launch_queue::request(&state, launch)
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            log::info!("Not sending queued prompt: {}", e);
+        }
+    });
+}
+
+/// Send `prompt` if it's still first in the session's queue and the session
+/// is still idle. Returns the session id.
+pub(crate) fn send_if_next(
+    state: &AppState,
+    session_id: &str,
+    prompt: &str,
+) -> Result<String, String> {
+    let idle = state
+        .sessions
+        .read()
+        .get(session_id)
+        .is_some_and(still_idle);
+    // The queue may have been edited while waiting
+    let unchanged = load_session_meta()
+        .sessions
+        .get(session_id)
+        .and_then(|meta| meta.next_prompts.first().cloned())
+        .is_some_and(|next| next == prompt);
+    if !idle || !unchanged {
+        return Err(format!("{} moved on", session_id));
+    }
+    send_next(state, session_id)?;
+    Ok(session_id.to_string())
+}
+
+// Tauri command: Replace the prompts queued to send after a session's next Stops
+#[tauri::command]
+#[specta::specta]
+pub fn set_next_prompts(
+    session_id: String,
+    prompts: Vec<String>,
+) -> Result<SessionMetaStore, String> {
+    let prompts = clean(prompts);
+    if prompts.len() > MAX_QUEUED_PROMPTS {
+        return Err(format!(
+            "At most {} prompts can be queued",
+            MAX_QUEUED_PROMPTS
+        ));
+    }
+    let mut store = load_session_meta();
+    store.sessions.entry(session_id).or_default().next_prompts = prompts;
+    store.sessions.retain(|_, m| !session_meta_is_empty(m));
+    save_session_meta(&store)?;
+    Ok(store)
+}
+
+// Tauri command: Send a session's next queued prompt now
+#[tauri::command]
+#[specta::specta]
+pub async fn send_next_prompt(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+) -> Result<SessionMetaStore, String> {
+    let state = state.inner().clone();
+    tokio::task::spawn_blocking(move || send_next(&state, &session_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_prompts_are_trimmed_and_blank_ones_dropped() {
+        let prompts = clean(vec![
+            "  now write tests ".to_string(),
+            "".to_string(),
+            "\n".to_string(),
+            "update the changelog".to_string(),
+        ]);
+        assert_eq!(prompts, vec!["now write tests", "update the changelog"]);
+    }
+}
@@ -0,0 +1,434 @@
+use crate::channels::{self, ChannelEvent};
+use crate::{approve_permission, deny_permission, load_settings, AppState, SessionState};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const API_URL: &str = "https://api.telegram.org";
+/// Seconds Telegram holds a getUpdates request open waiting for updates
+const POLL_TIMEOUT_SECS: u64 = 25;
+/// How often to look again while the bot is off or unreachable
+const IDLE_SECS: u64 = 30;
+/// "Later" posts a waiting session again after this long
+const LATER: Duration = Duration::from_secs(15 * 60);
+/// Messages remembered for their buttons
+const MAX_MESSAGES: usize = 200;
+
+/// Which session each recent message with buttons is about, by message id
+static MESSAGES: Mutex<VecDeque<(i64, String)>> = Mutex::new(VecDeque::new());
+
+/// A Telegram bot that posts notifications to one chat, with buttons to
+/// act on them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct TelegramSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// From @BotFather
+    #[serde(default)]
+    pub bot_token: String,
+    /// The only chat the bot posts to and takes button presses from; the
+    /// bot replies with a chat's id when it is messaged
+    #[serde(default)]
+    pub chat_id: Option<i64>,
+    /// Which notifications are posted
+    #[serde(default = "default_events")]
+    pub events: Vec<ChannelEvent>,
+}
+
+fn default_events() -> Vec<ChannelEvent> {
+    vec![ChannelEvent::Permission, ChannelEvent::Input]
+}
+
+impl Default for TelegramSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bot_token: String::new(),
+            chat_id: None,
+            events: default_events(),
+        }
+    }
+}
+
+/// A button under a message
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Button {
+    Approve,
+    Deny,
+    Later,
+}
+
+impl Button {
+    fn data(self) -> &'static str {
+        match self {
+            Button::Approve => "approve",
+            Button::Deny => "deny",
+            Button::Later => "later",
+        }
+    }
+
+    fn from_data(data: &str) -> Option<Self> {
+        [Button::Approve, Button::Deny, Button::Later]
+            .into_iter()
+            .find(|button| button.data() == data)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Button::Approve => "✅ Approve",
+            Button::Deny => "❌ Deny",
+            Button::Later => "⏰ Later",
+        }
+    }
+}
+
+/// What the bot was sent
+#[derive(Debug, PartialEq)]
+enum Incoming {
+    /// A button was pressed under one of C3's messages
+    Press {
+        query_id: String,
+        message_id: i64,
+        text: String,
+        button: Button,
+    },
+    /// Someone messaged the bot from a chat it isn't set up for
+    Unpaired { chat_id: i64 },
+}
+
+/// Whether `event` is posted with these settings
+pub(crate) fn wants(telegram: &TelegramSettings, event: ChannelEvent) -> bool {
+    telegram.enabled
+        && !telegram.bot_token.trim().is_empty()
+        && telegram.chat_id.is_some()
+        && telegram.events.contains(&event)
+}
+
+fn method_url(token: &str, method: &str) -> String {
+    format!("{}/bot{}/{}", API_URL, token.trim(), method)
+}
+
+fn call(token: &str, method: &str, body: &Value) -> Result<Value, String> {
+    let max_time = body["timeout"].as_u64().unwrap_or(0) + 10;
+    let reply = channels::post_json_reply(&method_url(token, method), body, max_time)?;
+    if reply["ok"].as_bool() != Some(true) {
+        let description = reply["description"].as_str().unwrap_or("no description");
+        return Err(format!("Telegram {} failed: {}", method, description));
+    }
+    Ok(reply["result"].clone())
+}
+
+fn buttons(event: ChannelEvent) -> &'static [Button] {
+    match event {
+        ChannelEvent::Permission => &[Button::Approve, Button::Deny, Button::Later],
+        ChannelEvent::Input => &[Button::Later],
+        _ => &[],
+    }
+}
+
+fn keyboard(buttons: &[Button]) -> Value {
+    let row: Vec<Value> = buttons
+        .iter()
+        .map(|button| json!({ "text": button.label(), "callback_data": button.data() }))
+        .collect();
+    json!({ "inline_keyboard": [row] })
+}
+
+fn post_message(
+    telegram: &TelegramSettings,
+    text: &str,
+    buttons: &[Button],
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let chat_id = telegram.chat_id.ok_or("No Telegram chat id set")?;
+    let mut body = json!({ "chat_id": chat_id, "text": text });
+    if session_id.is_some() && !buttons.is_empty() {
+        body["reply_markup"] = keyboard(buttons);
+    }
+    let sent = call(&telegram.bot_token, "sendMessage", &body)?;
+    if let (Some(message_id), Some(session_id)) = (sent["message_id"].as_i64(), session_id) {
+        let mut messages = MESSAGES.lock();
+        if messages.len() >= MAX_MESSAGES {
+            messages.pop_front();
+        }
+        messages.push_back((message_id, session_id));
+    }
+    Ok(())
+}
+
+/// Post a notification to the chat, with buttons for its session
+pub(crate) fn send(
+    telegram: &TelegramSettings,
+    event: ChannelEvent,
+    title: &str,
+    message: &str,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let text = format!("{}\n{}", title, message);
+    post_message(telegram, &text, buttons(event), session_id)
+}
+
+/// The update's press or unpaired message, ignoring everything else
+/// (including presses from other chats)
+fn incoming(update: &Value, chat_id: Option<i64>) -> Option<Incoming> {
+    if let Some(query) = update.get("callback_query") {
+        let message = &query["message"];
+        if chat_id.is_none() || message["chat"]["id"].as_i64() != chat_id {
+            return None;
+        }
+        return Some(Incoming::Press {
+            query_id: query["id"].as_str()?.to_string(),
+            message_id: message["message_id"].as_i64()?,
+            text: message["text"].as_str().unwrap_or_default().to_string(),
+            button: Button::from_data(query["data"].as_str()?)?,
+        });
+    }
+    let from = update["message"]["chat"]["id"].as_i64()?;
+    (Some(from) != chat_id).then_some(Incoming::Unpaired { chat_id: from })
+}
+
+fn session_for(message_id: i64) -> Option<String> {
+    MESSAGES
+        .lock()
+        .iter()
+        .find(|(id, _)| *id == message_id)
+        .map(|(_, session_id)| session_id.clone())
+}
+
+/// Act on a pressed button like the UI would. Returns the note shown to
+/// whoever pressed it.
+fn press(
+    state: &AppState,
+    button: Button,
+    session_id: &str,
+    later: &mut Vec<(Instant, String)>,
+) -> Result<&'static str, String> {
+    match button {
+        Button::Approve => approve_permission(state, session_id).map(|_| "Approved"),
+        Button::Deny => deny_permission(state, session_id).map(|_| "Denied"),
+        Button::Later => {
+            later.push((Instant::now() + LATER, session_id.to_string()));
+            Ok("Reminding you in 15 minutes")
+        }
+    }
+}
+
+fn handle(
+    state: &AppState,
+    telegram: &TelegramSettings,
+    incoming: Incoming,
+    later: &mut Vec<(Instant, String)>,
+) -> Result<(), String> {
+    let token = &telegram.bot_token;
+    match incoming {
+        Incoming::Unpaired { chat_id } => {
+            let text = format!(
+                "This chat's id is {}. Enter it under Settings → Telegram in C3 to get notifications here.",
+                chat_id
+            );
+            call(
+                token,
+                "sendMessage",
+                &json!({ "chat_id": chat_id, "text": text }),
+            )
+            .map(|_| ())
+        }
+        Incoming::Press {
+            query_id,
+            message_id,
+            text,
+            button,
+        } => {
+            let note = match session_for(message_id) {
+                Some(session_id) => press(state, button, &session_id, later),
+                None => Err("C3 no longer knows this session".to_string()),
+            };
+            let note = match note {
+                Ok(note) => {
+                    // Done with: drop the buttons and say what happened
+                    let _ = call(
+                        token,
+                        "editMessageText",
+                        &json!({
+                            "chat_id": telegram.chat_id,
+                            "message_id": message_id,
+                            "text": format!("{}\n\n{}", text, note),
+                        }),
+                    );
+                    note.to_string()
+                }
+                Err(e) => e,
+            };
+            call(
+                token,
+                "answerCallbackQuery",
+                &json!({ "callback_query_id": query_id, "text": note }),
+            )
+            .map(|_| ())
+        }
+    }
+}
+
+/// Post sessions put off with "Later" again if they're still waiting
+fn remind(state: &AppState, telegram: &TelegramSettings, later: &mut Vec<(Instant, String)>) {
+    let now = Instant::now();
+    let (due, rest): (Vec<_>, Vec<_>) = later.drain(..).partition(|(at, _)| *at <= now);
+    *later = rest;
+    for (_, session_id) in due {
+        let Some(session) = state.sessions.read().get(&session_id).cloned() else {
+            continue;
+        };
+        let (event, what) = match session.state {
+            SessionState::AwaitingPermission => (ChannelEvent::Permission, "needs your permission"),
+            SessionState::AwaitingInput => (ChannelEvent::Input, "is waiting for your input"),
+            _ => continue,
+        };
+        let text = format!("c3 — {}\nStill {}", session.project_name, what);
+        if let Err(e) = post_message(telegram, &text, buttons(event), Some(session_id)) {
+            log::warn!("Telegram reminder failed: {}", e);
+        }
+    }
+}
+
+/// Long-poll the bot for button presses, and tell unknown chats their id
+/// so they can be set up
+pub async fn start_telegram_bot(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut offset: i64 = 0;
+    let mut later: Vec<(Instant, String)> = Vec::new();
+    loop {
+        let telegram = load_settings().telegram;
+        if !telegram.enabled || telegram.bot_token.trim().is_empty() {
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(IDLE_SECS)) => continue,
+                _ = shutdown.changed() => {
+                    log::info!("Telegram bot shutting down");
+                    break;
+                }
+            }
+        }
+        let state_poll = state.clone();
+        let poll = tokio::task::spawn_blocking(move || {
+            remind(&state_poll, &telegram, &mut later);
+            let updates = call(
+                &telegram.bot_token,
+                "getUpdates",
+                &json!({
+                    "offset": offset,
+                    "timeout": POLL_TIMEOUT_SECS,
+                    "allowed_updates": ["message", "callback_query"],
+                }),
+            );
+            if let Ok(Value::Array(updates)) = &updates {
+                for update in updates {
+                    if let Some(id) = update["update_id"].as_i64() {
+                        offset = offset.max(id + 1);
+                    }
+                    let Some(incoming) = incoming(update, telegram.chat_id) else {
+                        continue;
+                    };
+                    if let Err(e) = handle(&state_poll, &telegram, incoming, &mut later) {
+                        log::warn!("{}", e);
+                    }
+                }
+            }
+            (updates.err(), offset, later)
+        });
+        let failed;
+        tokio::select! {
+            polled = poll => match polled {
+                Ok((error, next, kept)) => {
+                    (offset, later) = (next, kept);
+                    failed = error;
+                }
+                Err(e) => {
+                    log::warn!("Telegram poll panicked: {}", e);
+                    later = Vec::new();
+                    failed = Some(e.to_string());
+                }
+            },
+            _ = shutdown.changed() => {
+                log::info!("Telegram bot shutting down");
+                break;
+            }
+        }
+        // Back off instead of hammering an unreachable API or a bad token
+        if let Some(e) = failed {
+            log::warn!("Telegram getUpdates failed: {}", e);
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_secs(IDLE_SECS)) => {}
+                _ = shutdown.changed() => {
+                    log::info!("Telegram bot shutting down");
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Tauri command: Post a test message with these (possibly unsaved) settings
+#[tauri::command]
+#[specta::specta]
+pub async fn test_telegram(telegram: TelegramSettings) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        post_message(&telegram, "c3 — test\nC3 can post to this chat", &[], None)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presses_are_taken_only_from_the_paired_chat() {
+        let press = json!({
+            "update_id": 7,
+            "callback_query": {
+                "id": "q1",
+                "data": "approve",
+                "message": { "message_id": 42, "chat": { "id": 1001 }, "text": "c3 — api\nNeeds permission" }
+            }
+        });
+        assert_eq!(
+            incoming(&press, Some(1001)),
+            Some(Incoming::Press {
+                query_id: "q1".to_string(),
+                message_id: 42,
+                text: "c3 — api\nNeeds permission".to_string(),
+                button: Button::Approve,
+            })
+        );
+        assert_eq!(incoming(&press, Some(2002)), None);
+        assert_eq!(incoming(&press, None), None);
+
+        let hello =
+            json!({ "update_id": 8, "message": { "chat": { "id": 3003 }, "text": "/start" } });
+        assert_eq!(
+            incoming(&hello, Some(1001)),
+            Some(Incoming::Unpaired { chat_id: 3003 })
+        );
+        assert_eq!(incoming(&hello, Some(3003)), None);
+    }
+
+    #[test]
+    fn permission_messages_carry_every_button() {
+        let markup = keyboard(buttons(ChannelEvent::Permission));
+        let data: Vec<&str> = markup["inline_keyboard"][0]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|button| button["callback_data"].as_str().unwrap())
+            .collect();
+        assert_eq!(data, ["approve", "deny", "later"]);
+        assert!(data.iter().all(|d| Button::from_data(d).is_some()));
+        assert!(buttons(ChannelEvent::Complete).is_empty());
+    }
+}
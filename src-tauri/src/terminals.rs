@@ -0,0 +1,473 @@
+use crate::{cmd, load_settings, shell_quote, AppSettings};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::{Path, PathBuf};
+
+// Known terminal apps (in preference order for auto-detection)
+const KNOWN_TERMINALS: &[&str] = &[
+    "Ghostty",
+    "iTerm",
+    "Alacritty",
+    "kitty",
+    "WezTerm",
+    "Warp",
+    "Terminal",
+];
+
+/// A terminal app added in settings, e.g. Rio or Tabby. Custom terminals
+/// are tried before the known ones, and replace a known one of the same name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct CustomTerminal {
+    /// App name, as in `/Applications/<name>.app` and its process name
+    pub name: String,
+    /// e.g. `com.raphaelamorim.rio`; found and activated by id when set
+    #[serde(default)]
+    pub bundle_id: Option<String>,
+    /// Shell command run to focus the terminal instead of activating the app
+    #[serde(default)]
+    pub focus_command: Option<String>,
+    /// Shell command that opens a new window running `{command}`, for tmux
+    /// sessions no client is showing
+    #[serde(default)]
+    pub attach_command: Option<String>,
+}
+
+impl CustomTerminal {
+    fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            bundle_id: None,
+            focus_command: None,
+            attach_command: None,
+        }
+    }
+}
+
+/// New-window commands for known terminals that take one on the command line
+const ATTACH_COMMANDS: &[(&str, &str)] = &[
+    ("Ghostty", "open -na Ghostty --args -e {command}"),
+    ("Alacritty", "open -na Alacritty --args -e {command}"),
+    ("kitty", "open -na kitty --args {command}"),
+    ("WezTerm", "open -na WezTerm --args start -- {command}"),
+];
+
+/// Bundle ids already looked up, by app name (None when it has none)
+static BUNDLE_IDS: Mutex<Vec<(String, Option<String>)>> = Mutex::new(Vec::new());
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn app_path(name: &str) -> Option<PathBuf> {
+    let app = format!("{}.app", name);
+    let home = std::env::var("HOME").unwrap_or_default();
+    [
+        Path::new("/Applications").join(&app),
+        Path::new(&home).join("Applications").join(&app),
+    ]
+    .into_iter()
+    .find(|path| path.exists())
+}
+
+/// `CFBundleIdentifier` as printed by `mdls -raw` or `id of application`
+fn parse_bundle_id(output: &[u8]) -> Option<String> {
+    let id = String::from_utf8_lossy(output).trim().to_string();
+    (!id.is_empty() && id != "(null)" && !id.contains(char::is_whitespace)).then_some(id)
+}
+
+/// Look up an app's bundle id: from Spotlight's metadata for its bundle, or
+/// from LaunchServices for apps outside the Applications folders
+pub(crate) fn resolve_bundle_id(name: &str) -> Option<String> {
+    if let Some((_, id)) = BUNDLE_IDS.lock().iter().find(|(n, _)| n == name) {
+        return id.clone();
+    }
+    let from_mdls = app_path(name).and_then(|path| {
+        let output = cmd("mdls")
+            .args(["-name", "kMDItemCFBundleIdentifier", "-raw"])
+            .arg(path)
+            .output()
+            .ok()?;
+        parse_bundle_id(&output.stdout)
+    });
+    let id = from_mdls.or_else(|| {
+        let script = format!("id of application \"{}\"", name);
+        let output = cmd("osascript").args(["-e", &script]).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_bundle_id(&output.stdout)
+    });
+    BUNDLE_IDS.lock().push((name.to_string(), id.clone()));
+    id
+}
+
+/// Custom terminals, then the known ones they don't replace
+fn all(settings: &AppSettings) -> Vec<CustomTerminal> {
+    let mut terminals: Vec<CustomTerminal> = settings
+        .custom_terminals
+        .iter()
+        .filter(|t| !t.name.trim().is_empty())
+        .cloned()
+        .collect();
+    for &name in KNOWN_TERMINALS {
+        if !terminals.iter().any(|t| t.name.eq_ignore_ascii_case(name)) {
+            terminals.push(CustomTerminal::named(name));
+        }
+    }
+    terminals
+}
+
+fn running(terminal: &CustomTerminal) -> bool {
+    cmd("pgrep")
+        .args(["-x", &terminal.name])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn installed(terminal: &CustomTerminal) -> bool {
+    if app_path(&terminal.name).is_some() {
+        return true;
+    }
+    // Apps installed elsewhere, or named differently from their bundle
+    let Some(bundle_id) = non_empty(&terminal.bundle_id) else {
+        return false;
+    };
+    cmd("mdfind")
+        .arg(format!("kMDItemCFBundleIdentifier == '{}'", bundle_id))
+        .output()
+        .map(|o| o.status.success() && !o.stdout.trim_ascii().is_empty())
+        .unwrap_or(false)
+}
+
+/// Detect which terminal app is installed and running
+fn detect(settings: &AppSettings) -> Option<CustomTerminal> {
+    let terminals = all(settings);
+    terminals
+        .iter()
+        .find(|t| running(t))
+        // Fallback: check what's installed
+        .or_else(|| terminals.iter().find(|t| installed(t)))
+        .cloned()
+}
+
+fn with_bundle_id(mut terminal: CustomTerminal, settings: &AppSettings) -> CustomTerminal {
+    if non_empty(&terminal.bundle_id).is_none() {
+        terminal.bundle_id = if terminal.name == settings.terminal_app {
+            settings.terminal_bundle_id.clone()
+        } else {
+            None
+        }
+        .or_else(|| resolve_bundle_id(&terminal.name));
+    }
+    terminal
+}
+
+/// The terminal from settings, or the detected one for `auto`, with its
+/// bundle id filled in when it can be found
+pub(crate) fn configured(settings: &AppSettings) -> CustomTerminal {
+    let terminal = if settings.terminal_app == "auto" {
+        detect(settings).unwrap_or_else(|| CustomTerminal::named("Terminal"))
+    } else {
+        all(settings)
+            .into_iter()
+            .find(|t| t.name == settings.terminal_app)
+            .unwrap_or_else(|| CustomTerminal::named(&settings.terminal_app))
+    };
+    with_bundle_id(terminal, settings)
+}
+
+/// tty and pid of the most recently active client attached to `session`, or
+/// failing that of any client, which `switch-client` can bring to it. C3's
+/// own control-mode clients don't count. From `tmux list-clients -F
+/// '#{client_activity} #{client_control_mode} #{client_tty} #{client_pid} #{client_session}'`.
+fn session_client(list: &str, session: &str) -> Option<(String, u32)> {
+    list.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, ' ');
+            let activity: u64 = fields.next()?.parse().ok()?;
+            if fields.next()? == "1" {
+                return None;
+            }
+            let tty = fields.next()?;
+            let pid = fields.next()?.parse().ok()?;
+            let here = fields.next()? == session;
+            Some(((here, activity), tty.to_string(), pid))
+        })
+        .max_by_key(|(rank, _, _)| *rank)
+        .map(|(_, tty, pid)| (tty, pid))
+}
+
+/// `Ghostty` from `/Applications/Ghostty.app/Contents/MacOS/ghostty`
+fn app_name(command: &str) -> Option<&str> {
+    let bundle = &command[..command.find(".app/Contents/MacOS/")?];
+    Some(&bundle[bundle.rfind('/').map_or(0, |i| i + 1)..])
+}
+
+/// Walk up from the tmux client to the app it's running in
+fn owning_app(pid: u32) -> Option<String> {
+    let mut pid = pid;
+    // Client, shell, login, terminal, with room for wrappers like `ssh` or `mosh`
+    for _ in 0..16 {
+        let output = cmd("ps")
+            .args(["-o", "ppid=,comm=", "-p", &pid.to_string()])
+            .output()
+            .ok()?;
+        let line = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let (ppid, command) = line.split_once(char::is_whitespace)?;
+        if let Some(name) = app_name(command.trim()) {
+            return Some(name.to_string());
+        }
+        pid = ppid.trim().parse().ok()?;
+        if pid <= 1 {
+            return None;
+        }
+    }
+    None
+}
+
+/// The tty of the client to show a tmux session in, and the terminal it runs
+/// in when that can be traced; None when tmux has no clients at all. Settings
+/// for a terminal of that name are used to focus it.
+pub(crate) fn attached(
+    session: &str,
+    settings: &AppSettings,
+) -> Option<(String, Option<CustomTerminal>)> {
+    let output = cmd("tmux")
+        .args([
+            "list-clients",
+            "-F",
+            "#{client_activity} #{client_control_mode} #{client_tty} #{client_pid} #{client_session}",
+        ])
+        .output()
+        .ok()?;
+    let (tty, pid) = session_client(&String::from_utf8_lossy(&output.stdout), session)?;
+    let terminal = owning_app(pid).map(|name| {
+        let terminal = all(settings)
+            .into_iter()
+            .find(|t| t.name.eq_ignore_ascii_case(&name))
+            .unwrap_or_else(|| CustomTerminal::named(&name));
+        with_bundle_id(terminal, settings)
+    });
+    Some((tty, terminal))
+}
+
+/// Text for inside an AppleScript string literal
+fn applescript_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Open a new window of `terminal` attached to a tmux session, so a session
+/// no client shows ends up on screen
+pub(crate) fn open_attached(terminal: &CustomTerminal, session: &str) -> Result<(), String> {
+    // Apps started with `open` don't get the login shell's PATH
+    let tmux = cmd("which")
+        .arg("tmux")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_else(|| "tmux".to_string());
+    let command = format!("{} attach -t {}", tmux, shell_quote(session));
+    let launch = non_empty(&terminal.attach_command).or_else(|| {
+        ATTACH_COMMANDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&terminal.name))
+            .map(|(_, launch)| *launch)
+    });
+    let output = match (launch, terminal.name.as_str()) {
+        (Some(launch), _) => cmd("sh")
+            .args(["-c", &launch.replace("{command}", &command)])
+            .output(),
+        (None, "iTerm") => cmd("osascript")
+            .args([
+                "-e",
+                &format!(
+                    "tell application \"iTerm\" to create window with default profile command \"{}\"",
+                    applescript_string(&command)
+                ),
+                "-e",
+                "tell application \"iTerm\" to activate",
+            ])
+            .output(),
+        // Terminal.app for the rest, since any Mac has it
+        (None, _) => cmd("osascript")
+            .args([
+                "-e",
+                &format!(
+                    "tell application \"Terminal\" to do script \"{}\"",
+                    applescript_string(&command)
+                ),
+                "-e",
+                "tell application \"Terminal\" to activate",
+            ])
+            .output(),
+    }
+    .map_err(|e| format!("Failed to open {}: {}", terminal.name, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to attach {} in {}: {}",
+            session,
+            terminal.name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    log::info!(
+        "Attached tmux session {} in a new {} window",
+        session,
+        terminal.name
+    );
+    Ok(())
+}
+
+fn activate_script(terminal: &CustomTerminal) -> String {
+    match non_empty(&terminal.bundle_id) {
+        Some(bundle_id) => format!("tell application id \"{}\" to activate", bundle_id),
+        None => format!("tell application \"{}\" to activate", terminal.name),
+    }
+}
+
+/// Shell command that brings the terminal forward, for notification clicks
+pub(crate) fn activate_shell(terminal: &CustomTerminal) -> String {
+    match non_empty(&terminal.focus_command) {
+        Some(command) => command.to_string(),
+        None => format!("osascript -e {}", shell_quote(&activate_script(terminal))),
+    }
+}
+
+/// Bring the terminal forward, with its focus command if it has one
+pub(crate) fn activate(terminal: &CustomTerminal) -> Result<(), String> {
+    let output = match non_empty(&terminal.focus_command) {
+        Some(command) => cmd("sh").args(["-c", command]).output(),
+        None => cmd("osascript")
+            .args(["-e", &activate_script(terminal)])
+            .output(),
+    }
+    .map_err(|e| format!("Failed to activate {}: {}", terminal.name, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to activate {}: {}",
+            terminal.name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Reject custom terminals that couldn't be found or activated
+pub(crate) fn validate(terminals: &[CustomTerminal]) -> Result<(), String> {
+    for terminal in terminals {
+        let name = terminal.name.trim();
+        if name.is_empty() {
+            return Err("Custom terminals need an app name".to_string());
+        }
+        // Both end up inside an AppleScript string
+        if name.contains('"') || terminal.bundle_id.as_deref().unwrap_or("").contains('"') {
+            return Err(format!("Terminal {:?} can't contain quotes", name));
+        }
+    }
+    Ok(())
+}
+
+// Tauri command: Get available terminals
+#[tauri::command]
+#[specta::specta]
+pub fn get_available_terminals() -> Vec<String> {
+    let mut available = vec!["auto".to_string()];
+    let settings = load_settings();
+    for terminal in all(&settings) {
+        // Custom terminals are listed even when not found, so they can be picked
+        let custom = settings
+            .custom_terminals
+            .iter()
+            .any(|t| t.name == terminal.name);
+        if custom || installed(&terminal) {
+            available.push(terminal.name);
+        }
+    }
+    available
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_terminals_come_first_and_replace_known_ones() {
+        let rio = CustomTerminal {
+            name: "Rio".to_string(),
+            bundle_id: Some("com.raphaelamorim.rio".to_string()),
+            focus_command: None,
+            attach_command: None,
+        };
+        let kitty = CustomTerminal {
+            name: "kitty".to_string(),
+            bundle_id: None,
+            focus_command: Some("kitty @ focus-window".to_string()),
+            attach_command: None,
+        };
+        let settings = AppSettings {
+            terminal_app: "kitty".to_string(),
+            terminal_bundle_id: Some("net.kovidgoyal.kitty".to_string()),
+            custom_terminals: vec![rio.clone(), kitty.clone(), CustomTerminal::named(" ")],
+            ..AppSettings::default()
+        };
+        let terminals = all(&settings);
+        assert_eq!(terminals[..2], [rio.clone(), kitty.clone()]);
+        assert_eq!(terminals.len(), KNOWN_TERMINALS.len() + 1);
+
+        let focused = configured(&settings);
+        assert_eq!(focused.bundle_id.as_deref(), Some("net.kovidgoyal.kitty"));
+        assert_eq!(focused.focus_command, kitty.focus_command);
+        assert_eq!(activate_shell(&kitty), "kitty @ focus-window");
+        assert_eq!(
+            activate_shell(&rio),
+            "osascript -e 'tell application id \"com.raphaelamorim.rio\" to activate'"
+        );
+        assert!(validate(&[rio]).is_ok());
+        assert_eq!(
+            parse_bundle_id(b"com.mitchellh.ghostty\n").as_deref(),
+            Some("com.mitchellh.ghostty")
+        );
+        assert_eq!(parse_bundle_id(b"(null)"), None);
+        assert!(validate(&[CustomTerminal::named("Ta\"bby")]).is_err());
+    }
+
+    #[test]
+    fn the_latest_client_of_a_session_is_traced_to_its_app() {
+        let clients = "1700000100 0 /dev/ttys003 4101 api\n\
+                       1700000200 0 /dev/ttys007 4202 my session\n\
+                       1700000300 0 /dev/ttys009 4303 my session\n\
+                       1700000400 1 /dev/ttys010 4404 api\n";
+        assert_eq!(
+            session_client(clients, "my session"),
+            Some(("/dev/ttys009".to_string(), 4303))
+        );
+        assert_eq!(
+            session_client(clients, "api"),
+            Some(("/dev/ttys003".to_string(), 4101))
+        );
+        // No client on `web`: the latest one can be switched to it
+        assert_eq!(
+            session_client(clients, "web"),
+            Some(("/dev/ttys009".to_string(), 4303))
+        );
+        assert_eq!(session_client("", "web"), None);
+
+        assert_eq!(
+            applescript_string(r#"tmux attach -t 'say "hi"'"#),
+            r#"tmux attach -t 'say \"hi\"'"#
+        );
+
+        assert_eq!(
+            app_name("/Applications/Ghostty.app/Contents/MacOS/ghostty"),
+            Some("Ghostty")
+        );
+        assert_eq!(
+            app_name("/Users/ann/Applications/Tabby.app/Contents/MacOS/Tabby"),
+            Some("Tabby")
+        );
+        assert_eq!(app_name("-zsh"), None);
+    }
+}
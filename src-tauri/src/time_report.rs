@@ -0,0 +1,426 @@
+use crate::{paths, AppState, SessionState};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// One line per state transition, in the state dir
+const LOG_FILE: &str = "state-transitions.jsonl";
+/// Transitions older than this are dropped when the log is read
+const RETENTION_DAYS: i64 = 400;
+/// How often new timeline entries are written to the log
+const LOG_INTERVAL_SECS: u64 = 5;
+/// A state held longer than this is cut short: C3 was probably closed, or
+/// the session gone, before the change out of it was seen
+const MAX_SPAN_HOURS: i64 = 2;
+
+/// Appends from the logger and the pruning rewrite must not interleave
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// A state transition as logged, with the project it counts towards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Transition {
+    pub(crate) at: DateTime<Utc>,
+    pub(crate) session_id: String,
+    pub(crate) project_name: String,
+    #[serde(default)]
+    pub(crate) project_path: Option<String>,
+    pub(crate) state: SessionState,
+}
+
+/// Local days to report on, both included
+#[derive(Debug, Clone, Deserialize, Type)]
+pub struct ReportRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+/// Time agents spent on one project on one local day
+#[derive(Debug, Clone, PartialEq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeReportRow {
+    pub date: NaiveDate,
+    pub project_name: String,
+    pub project_path: Option<String>,
+    /// Seconds spent working (Processing)
+    pub active_secs: u64,
+    /// Seconds spent blocked on a permission prompt
+    pub waiting_secs: u64,
+    /// Sessions that were active or waiting that day
+    pub sessions: u32,
+}
+
+fn log_path() -> PathBuf {
+    paths::state_dir().join(LOG_FILE)
+}
+
+fn append(path: &Path, transitions: &[Transition]) {
+    let lines: String = transitions
+        .iter()
+        .filter_map(|transition| serde_json::to_string(transition).ok())
+        .map(|line| line + "\n")
+        .collect();
+    let _guard = LOG_LOCK.lock();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let appended = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()));
+    if let Err(e) = appended {
+        log::warn!("Failed to log state transitions: {}", e);
+    }
+}
+
+/// Transitions within the retention period, rewriting the log without the rest
+fn load(path: &Path, now: DateTime<Utc>) -> Vec<Transition> {
+    let _guard = LOG_LOCK.lock();
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let cutoff = now - Duration::days(RETENTION_DAYS);
+    let lines = contents.lines().count();
+    let transitions: Vec<Transition> = contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Transition>(line).ok())
+        .filter(|transition| transition.at >= cutoff)
+        .collect();
+    if transitions.len() < lines {
+        let kept: String = transitions
+            .iter()
+            .filter_map(|transition| serde_json::to_string(transition).ok())
+            .map(|line| line + "\n")
+            .collect();
+        if let Err(e) = fs::write(path, kept) {
+            log::warn!("Failed to prune {}: {}", path.display(), e);
+        }
+    }
+    transitions
+}
+
+/// Every logged transition within the retention period
+pub(crate) fn load_transitions(now: DateTime<Utc>) -> Vec<Transition> {
+    load(&log_path(), now)
+}
+
+/// Write timeline entries recorded after `after` to the log. Returns the
+/// newest entry seen.
+fn log_new(state: &AppState, after: DateTime<Utc>) -> DateTime<Utc> {
+    let entries = state.timeline.since(after);
+    let Some(newest) = entries.last().map(|(_, entry)| entry.timestamp) else {
+        return after;
+    };
+    let transitions: Vec<Transition> = entries
+        .into_iter()
+        .filter_map(|(session_id, entry)| {
+            let session = state
+                .sessions
+                .read()
+                .get(&session_id)
+                .cloned()
+                .or_else(|| state.archive.session(&session_id))?;
+            Some(Transition {
+                at: entry.timestamp,
+                session_id,
+                project_name: session.project_name,
+                project_path: session.project_path,
+                state: entry.to,
+            })
+        })
+        .collect();
+    append(&log_path(), &transitions);
+    newest
+}
+
+/// Keep a log of state transitions, which the in-memory timeline only holds
+/// the last few of, for time reports
+pub async fn start_time_log(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let mut after = Utc::now();
+    loop {
+        let stopping = tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(LOG_INTERVAL_SECS)) => false,
+            _ = shutdown.changed() => true,
+        };
+        let state_log = state.clone();
+        after = tokio::task::spawn_blocking(move || log_new(&state_log, after))
+            .await
+            .unwrap_or(after);
+        if stopping {
+            log::info!("Time log shutting down");
+            break;
+        }
+    }
+}
+
+/// Local midnight starting `date`
+pub(crate) fn day_start<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+    tz.from_local_datetime(&midnight)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[derive(Default)]
+struct DayTotals<'a> {
+    project_name: &'a str,
+    project_path: Option<&'a str>,
+    active_secs: i64,
+    waiting_secs: i64,
+    sessions: HashSet<&'a str>,
+}
+
+/// Active and waiting time per local day and project within `range`
+pub(crate) fn report<Tz: TimeZone>(
+    transitions: &[Transition],
+    range: &ReportRange,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<TimeReportRow> {
+    let range_start = day_start(range.start, tz);
+    let range_end = range
+        .end
+        .succ_opt()
+        .map_or(now, |after| day_start(after, tz))
+        .min(now);
+
+    let mut by_session: HashMap<&str, Vec<&Transition>> = HashMap::new();
+    for transition in transitions {
+        by_session
+            .entry(&transition.session_id)
+            .or_default()
+            .push(transition);
+    }
+
+    let mut totals: BTreeMap<(NaiveDate, &str), DayTotals> = BTreeMap::new();
+    for session in by_session.values_mut() {
+        session.sort_by_key(|transition| transition.at);
+        for (i, transition) in session.iter().enumerate() {
+            let counted = matches!(
+                transition.state,
+                SessionState::Processing | SessionState::AwaitingPermission
+            );
+            if !counted {
+                continue;
+            }
+            let cap = transition.at + Duration::hours(MAX_SPAN_HOURS);
+            let next = session.get(i + 1).map_or(cap, |next| next.at.min(cap));
+            let mut start = transition.at.max(range_start);
+            let end = next.min(range_end);
+            while start < end {
+                let date = start.with_timezone(tz).date_naive();
+                let day_end = date
+                    .succ_opt()
+                    .map_or(end, |after| day_start(after, tz))
+                    .min(end);
+                let project = transition
+                    .project_path
+                    .as_deref()
+                    .unwrap_or(&transition.project_name);
+                let day = totals.entry((date, project)).or_default();
+                day.project_name = &transition.project_name;
+                day.project_path = transition.project_path.as_deref();
+                let secs = (day_end - start).num_seconds();
+                if transition.state == SessionState::Processing {
+                    day.active_secs += secs;
+                } else {
+                    day.waiting_secs += secs;
+                }
+                day.sessions.insert(&transition.session_id);
+                start = day_end;
+            }
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|((date, _), day)| TimeReportRow {
+            date,
+            project_name: day.project_name.to_string(),
+            project_path: day.project_path.map(String::from),
+            active_secs: day.active_secs as u64,
+            waiting_secs: day.waiting_secs as u64,
+            sessions: day.sessions.len() as u32,
+        })
+        .collect()
+}
+
+/// Quote a CSV field when it holds a comma, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn hours(secs: u64) -> String {
+    format!("{:.2}", secs as f64 / 3600.0)
+}
+
+/// One row per day and project, with times in hours
+fn render_csv(rows: &[TimeReportRow]) -> String {
+    let mut csv = String::from("date,project,project_path,active_hours,waiting_hours,sessions\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            row.date,
+            csv_field(&row.project_name),
+            csv_field(row.project_path.as_deref().unwrap_or_default()),
+            hours(row.active_secs),
+            hours(row.waiting_secs),
+            row.sessions
+        ));
+    }
+    csv
+}
+
+// Tauri command: Write time spent per project and day to a CSV or JSON file, by the path's extension
+#[tauri::command]
+#[specta::specta]
+pub async fn export_time_report(
+    range: ReportRange,
+    path: String,
+) -> Result<Vec<TimeReportRow>, String> {
+    if !Path::new(&path).is_absolute() {
+        return Err("Export path must be absolute".to_string());
+    }
+    if range.end < range.start {
+        return Err("The report range ends before it starts".to_string());
+    }
+    let extension = Path::new(&path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    tokio::task::spawn_blocking(move || {
+        let now = Utc::now();
+        let rows = report(&load(&log_path(), now), &range, now, &Local);
+        let rendered = match extension.as_deref() {
+            Some("csv") => render_csv(&rows),
+            Some("json") => serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?,
+            _ => return Err("Time reports are written as .csv or .json".to_string()),
+        };
+        fs::write(&path, rendered).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        log::info!("Exported time report ({} rows) to {}", rows.len(), path);
+        Ok(rows)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap()
+    }
+
+    fn transition(
+        session_id: &str,
+        project: &str,
+        at: DateTime<Utc>,
+        state: SessionState,
+    ) -> Transition {
+        Transition {
+            at,
+            session_id: session_id.to_string(),
+            project_name: project.to_string(),
+            project_path: Some(format!("/src/{}", project)),
+            state,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn working_and_permission_time_is_summed_per_day_and_project() {
+        let transitions = [
+            transition("a", "api", at(1, 9, 0), SessionState::Processing),
+            transition("a", "api", at(1, 9, 30), SessionState::AwaitingPermission),
+            transition("a", "api", at(1, 9, 40), SessionState::Processing),
+            transition("a", "api", at(1, 10, 0), SessionState::AwaitingInput),
+            transition("b", "api", at(1, 23, 30), SessionState::Processing),
+            transition("b", "api", at(2, 0, 15), SessionState::Complete),
+            // Never seen to stop: counted for two hours at most
+            transition("c", "web, app", at(2, 8, 0), SessionState::Processing),
+            // Outside the range
+            transition("d", "api", at(4, 8, 0), SessionState::Processing),
+        ];
+        let range = ReportRange {
+            start: date(1),
+            end: date(3),
+        };
+        let rows = report(&transitions, &range, at(5, 0, 0), &Utc);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.date.to_string(),
+                    row.project_name.as_str(),
+                    row.active_secs / 60,
+                    row.waiting_secs / 60,
+                    row.sessions,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2026-03-01".to_string(), "api", 80, 10, 2),
+                ("2026-03-02".to_string(), "api", 15, 0, 1),
+                ("2026-03-02".to_string(), "web, app", 120, 0, 1),
+            ]
+        );
+
+        // A day still under way counts up to now
+        let today = report(&transitions[..2], &range, at(1, 9, 10), &Utc);
+        assert_eq!(today[0].active_secs, 600);
+
+        let csv = render_csv(&rows);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("date,project,project_path,active_hours,waiting_hours,sessions")
+        );
+        assert_eq!(lines.next(), Some("2026-03-01,api,/src/api,1.33,0.17,2"));
+        assert_eq!(
+            lines.nth(1),
+            Some("2026-03-02,\"web, app\",\"/src/web, app\",2.00,0.00,1")
+        );
+    }
+
+    #[test]
+    fn transitions_past_retention_are_pruned_from_the_log() {
+        let dir = std::env::temp_dir().join(format!("c3-time-report-{}", std::process::id()));
+        let path = dir.join(LOG_FILE);
+        let now = Utc::now();
+        append(
+            &path,
+            &[
+                transition(
+                    "a",
+                    "api",
+                    now - Duration::days(RETENTION_DAYS + 1),
+                    SessionState::Processing,
+                ),
+                transition("a", "api", now - Duration::days(1), SessionState::Complete),
+            ],
+        );
+        assert_eq!(load(&path, now).len(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,310 @@
+use crate::tmux_scanner::AgentPane;
+use crate::{cmd, AppState};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+
+/// `attach-session -f` and `refresh-client -B` arrived in tmux 3.2
+const MIN_VERSION: (u32, u32) = (3, 2);
+/// Panes are listed in full at least this often, in case an event was missed
+const FULL_LIST_AFTER: Duration = Duration::from_secs(30);
+/// How often sessions without a control client are retried
+const RECONNECT_SECS: u64 = 10;
+
+/// Pushed when a pane starts or stops an agent, dies or changes directory
+const SHAPE_SUBSCRIPTION: &str =
+    "c3-shape:%*:#{pane_current_command} #{pane_dead} #{pane_current_path}";
+/// Pushed when a pane's title changes, which agents use to show their state
+const TITLE_SUBSCRIPTION: &str = "c3-title:%*:#{pane_title}";
+
+/// Notifications that mean panes were added, removed, moved or renamed
+const LIFECYCLE_EVENTS: &[&str] = &[
+    "%window-add",
+    "%window-close",
+    "%window-renamed",
+    "%unlinked-window-add",
+    "%unlinked-window-close",
+    "%unlinked-window-renamed",
+    "%layout-change",
+    "%session-renamed",
+    "%sessions-changed",
+];
+
+struct Listing {
+    panes: Vec<AgentPane>,
+    server_started: Option<i64>,
+    listed_at: Instant,
+}
+
+/// What tmux control-mode clients, one per tmux session, have pushed. While
+/// every session has one, scans reuse the last pane listing instead of
+/// running `list-panes`.
+#[derive(Default)]
+pub struct TmuxControl {
+    /// tmux sessions (`$N`) at the last check
+    sessions: Mutex<HashSet<String>>,
+    /// Sessions with a control client reporting
+    connected: Mutex<HashSet<String>>,
+    /// Panes changed since the last listing
+    changed: AtomicBool,
+    /// Latest title per pane (`%N`)
+    titles: Mutex<HashMap<String, String>>,
+    listing: Mutex<Option<Listing>>,
+    /// Wakes the connection manager when sessions come or go
+    sessions_changed: Notify,
+}
+
+impl TmuxControl {
+    /// Whether every tmux session has a control client reporting
+    pub(crate) fn is_live(&self) -> bool {
+        let connected = self.connected.lock();
+        !connected.is_empty() && *connected == *self.sessions.lock()
+    }
+
+    /// The last pane listing with up-to-date titles, unless control mode is
+    /// down, panes changed or the listing is due for a refresh
+    pub(crate) fn cached_listing(&self) -> Option<(Vec<AgentPane>, Option<i64>)> {
+        if self.changed.swap(false, Ordering::SeqCst) || !self.is_live() {
+            return None;
+        }
+        let listing = self.listing.lock();
+        let listing = listing
+            .as_ref()
+            .filter(|listing| listing.listed_at.elapsed() < FULL_LIST_AFTER)?;
+        let titles = self.titles.lock();
+        let panes = listing
+            .panes
+            .iter()
+            .cloned()
+            .map(|mut pane| {
+                if let Some(title) = titles.get(&pane.pane_id) {
+                    pane.pane_title = title.clone();
+                }
+                pane
+            })
+            .collect();
+        Some((panes, listing.server_started))
+    }
+
+    pub(crate) fn store_listing(&self, panes: &[AgentPane], server_started: Option<i64>) {
+        *self.listing.lock() = Some(Listing {
+            panes: panes.to_vec(),
+            server_started,
+            listed_at: Instant::now(),
+        });
+    }
+
+    /// Apply one line of control-mode output. Returns whether panes changed.
+    fn handle_line(&self, line: &str) -> bool {
+        if let Some(change) = line.strip_prefix("%subscription-changed ") {
+            let Some((head, value)) = change.split_once(" : ") else {
+                return false;
+            };
+            let mut fields = head.split(' ');
+            let name = fields.next().unwrap_or_default();
+            let Some(pane) = fields.find(|field| field.starts_with('%')) else {
+                return false;
+            };
+            if TITLE_SUBSCRIPTION.starts_with(&format!("{}:", name)) {
+                self.titles
+                    .lock()
+                    .insert(pane.to_string(), value.to_string());
+                return false;
+            }
+            self.changed.store(true, Ordering::SeqCst);
+            return true;
+        }
+        let event = line.split(' ').next().unwrap_or_default();
+        if event == "%sessions-changed" {
+            self.sessions_changed.notify_one();
+        }
+        if LIFECYCLE_EVENTS.contains(&event) {
+            self.changed.store(true, Ordering::SeqCst);
+            return true;
+        }
+        false
+    }
+}
+
+/// `(3, 4)` from `tmux 3.4`, `tmux 3.3a` or `tmux next-3.5`
+fn parse_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("tmux ")?;
+    let version = version.strip_prefix("next-").unwrap_or(version);
+    let (major, rest) = version.split_once('.')?;
+    let minor: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn supported() -> bool {
+    cmd("tmux")
+        .arg("-V")
+        .output()
+        .ok()
+        .and_then(|output| parse_version(&String::from_utf8_lossy(&output.stdout)))
+        .is_some_and(|version| version >= MIN_VERSION)
+}
+
+fn list_sessions() -> HashSet<String> {
+    cmd("tmux")
+        .args(["list-sessions", "-F", "#{session_id}"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Keep a control client attached to `session` until it detaches or exits
+async fn follow_session(state: Arc<AppState>, session: String) {
+    let control = &state.tmux_control;
+    let mut command = tokio::process::Command::from(cmd("tmux"));
+    command
+        .args([
+            "-C",
+            "attach-session",
+            "-t",
+            &session,
+            "-f",
+            "no-output,ignore-size",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            log::warn!("tmux control mode failed to start for {}: {}", session, e);
+            return;
+        }
+    };
+    let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return;
+    };
+    let subscribe = format!(
+        "refresh-client -B '{}'\nrefresh-client -B '{}'\n",
+        SHAPE_SUBSCRIPTION, TITLE_SUBSCRIPTION
+    );
+    if stdin.write_all(subscribe.as_bytes()).await.is_err() {
+        return;
+    }
+
+    control.connected.lock().insert(session.clone());
+    control.changed.store(true, Ordering::SeqCst);
+    log::info!("tmux control mode following session {}", session);
+    let mut lines = BufReader::new(stdout).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.starts_with("%error") {
+            log::warn!("tmux control mode error for {}; polling instead", session);
+            break;
+        }
+        if line.starts_with("%exit") {
+            break;
+        }
+        if control.handle_line(&line) {
+            state.scan_now.notify_one();
+        }
+    }
+    // Closing stdin detaches the client
+    drop(stdin);
+    let _ = child.wait().await;
+    control.connected.lock().remove(&session);
+    control.changed.store(true, Ordering::SeqCst);
+    log::info!("tmux control mode stopped following session {}", session);
+}
+
+/// Follow every tmux session over control mode, so panes coming and going
+/// wake the scanner at once and scans skip `list-panes`. Without tmux 3.2
+/// the scanner keeps polling.
+pub async fn start_tmux_control(state: Arc<AppState>, mut shutdown: watch::Receiver<bool>) {
+    let available = tokio::task::spawn_blocking(supported)
+        .await
+        .unwrap_or(false);
+    if !available {
+        log::info!("tmux control mode needs tmux 3.2 or later; the scanner will poll");
+        let _ = shutdown.changed().await;
+        return;
+    }
+
+    let mut clients: HashMap<String, JoinHandle<()>> = HashMap::new();
+    loop {
+        let sessions = tokio::task::spawn_blocking(list_sessions)
+            .await
+            .unwrap_or_default();
+        clients.retain(|session, client| {
+            let keep = sessions.contains(session) && !client.is_finished();
+            if !keep {
+                client.abort();
+            }
+            keep
+        });
+        for session in &sessions {
+            if !clients.contains_key(session) {
+                let client = tokio::spawn(follow_session(Arc::clone(&state), session.clone()));
+                clients.insert(session.clone(), client);
+            }
+        }
+        *state.tmux_control.sessions.lock() = sessions;
+
+        tokio::select! {
+            _ = state.tmux_control.sessions_changed.notified() => {}
+            _ = tokio::time::sleep(Duration::from_secs(RECONNECT_SECS)) => {}
+            _ = shutdown.changed() => {
+                for client in clients.values() {
+                    client.abort();
+                }
+                log::info!("tmux control mode shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn control_mode_events_mark_panes_changed_and_track_titles() {
+        let control = TmuxControl::default();
+        assert!(!control.handle_line("%begin 1700000000 12 0"));
+        assert!(!control.handle_line("%subscription-changed c3-title $0 @1 1 %3 : ✳ Fix tests"));
+        assert_eq!(
+            control.titles.lock().get("%3").map(String::as_str),
+            Some("✳ Fix tests")
+        );
+        assert!(!control.changed.load(Ordering::SeqCst));
+
+        assert!(control.handle_line("%subscription-changed c3-shape $0 @1 1 %3 : zsh 0 /src"));
+        assert!(control.handle_line("%window-close @4"));
+        assert!(control.handle_line("%unlinked-window-add @9"));
+        assert!(!control.handle_line("%window-pane-changed @1 %3"));
+
+        // Only a complete set of followed sessions lets scans reuse a listing
+        *control.sessions.lock() = HashSet::from(["$0".to_string(), "$1".to_string()]);
+        control.connected.lock().insert("$0".to_string());
+        control.store_listing(&[], None);
+        assert!(!control.is_live());
+        control.connected.lock().insert("$1".to_string());
+        assert!(control.is_live());
+        // The pending change forces one full listing first
+        assert!(control.cached_listing().is_none());
+        assert!(control.cached_listing().is_some());
+
+        assert_eq!(parse_version("tmux 3.4\n"), Some((3, 4)));
+        assert_eq!(parse_version("tmux 3.3a"), Some((3, 3)));
+        assert_eq!(parse_version("tmux next-3.5"), Some((3, 5)));
+        assert_eq!(parse_version("tmux master"), None);
+    }
+}
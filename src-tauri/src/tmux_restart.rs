@@ -0,0 +1,208 @@
+use crate::session_identity::{self, Rekey};
+use crate::{AppState, C3Session};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Sessions of a dead server still unmatched after this long are forgotten
+const DEPARTED_TTL: Duration = Duration::from_secs(600);
+
+/// A tmux agent pane by what survives a server restart
+#[derive(Debug, Clone)]
+pub(crate) struct PaneIdentity {
+    /// The session ID the scan resolved for the pane
+    pub(crate) session_id: String,
+    pub(crate) pane_id: String,
+    pub(crate) target: String,
+    pub(crate) agent_kind: String,
+    pub(crate) cwd: String,
+    /// The agent's conversation log the pane was last read from
+    pub(crate) log: Option<PathBuf>,
+}
+
+struct Departed {
+    session: C3Session,
+    identity: PaneIdentity,
+    since: Instant,
+}
+
+#[derive(Default)]
+struct Inner {
+    /// `#{start_time}` of the server seen last scan; None when none answered
+    started_at: Option<i64>,
+    /// Conversation log of each tmux session, by session ID
+    logs: HashMap<String, PathBuf>,
+    /// Sessions whose server went away, waiting for their panes to come back
+    departed: Vec<Departed>,
+}
+
+/// Carries sessions over a tmux server restart. Every pane ID and target
+/// changes (or is reused by another pane) when the server comes back, so
+/// sessions are matched to the new panes by agent, project path and
+/// conversation log, and move to the new ID with their tags, pins, group and
+/// history.
+#[derive(Default)]
+pub struct TmuxServerWatch(Mutex<Inner>);
+
+/// Pair each departed pane with the one new pane of the same agent in the
+/// same directory. When several qualify, the conversation log decides; a
+/// pane that stays ambiguous is left for the scanner to add as new.
+fn pair(departed: &[PaneIdentity], arrived: &[PaneIdentity]) -> Vec<(usize, usize)> {
+    let mut taken = HashSet::new();
+    let mut pairs = Vec::new();
+    for (d, old) in departed.iter().enumerate() {
+        let candidates: Vec<usize> = arrived
+            .iter()
+            .enumerate()
+            .filter(|(a, new)| {
+                !taken.contains(a) && new.agent_kind == old.agent_kind && new.cwd == old.cwd
+            })
+            .map(|(a, _)| a)
+            .collect();
+        let same_log: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|a| old.log.is_some() && arrived[*a].log == old.log)
+            .collect();
+        let chosen = match (candidates.as_slice(), same_log.as_slice()) {
+            (_, [only]) | ([only], []) => *only,
+            _ => continue,
+        };
+        taken.insert(chosen);
+        pairs.push((d, chosen));
+    }
+    pairs
+}
+
+impl TmuxServerWatch {
+    /// Note the server found by this scan. When it isn't the one seen last
+    /// scan, the old server's sessions are set aside before the scan resolves
+    /// session IDs, since their pane IDs and targets now name other panes.
+    pub(crate) fn observe_server(&self, state: &Arc<AppState>, started_at: Option<i64>) {
+        let mut inner = self.0.lock();
+        let previous = inner.started_at;
+        inner.started_at = started_at;
+        if previous.is_none() || started_at == previous {
+            return;
+        }
+        let gone: Vec<C3Session> = {
+            let mut sessions = state.sessions.write();
+            let ids: Vec<String> = sessions
+                .keys()
+                .filter(|id| session_identity::is_pane_session(id))
+                .cloned()
+                .collect();
+            ids.iter().filter_map(|id| sessions.remove(id)).collect()
+        };
+        log::info!(
+            "tmux server {}; {} sessions will move to matching new panes",
+            if started_at.is_some() {
+                "restarted"
+            } else {
+                "went away"
+            },
+            gone.len()
+        );
+        for session in gone {
+            state.events.session_removed(session.id.clone());
+            let identity = PaneIdentity {
+                session_id: session.id.clone(),
+                pane_id: session.tmux_pane_id.clone().unwrap_or_default(),
+                target: session.tmux_target.clone().unwrap_or_default(),
+                agent_kind: session.agent_kind.clone().unwrap_or_default(),
+                cwd: session.project_path.clone().unwrap_or_default(),
+                log: inner.logs.get(&session.id).cloned(),
+            };
+            inner.departed.push(Departed {
+                session,
+                identity,
+                since: Instant::now(),
+            });
+        }
+    }
+
+    /// Move sessions set aside to the matching panes of the new server, once
+    /// the scan has resolved their IDs, so the scan updates them instead of
+    /// adding new ones
+    pub(crate) fn observe_panes(&self, state: &Arc<AppState>, panes: &[PaneIdentity]) {
+        let mut inner = self.0.lock();
+        inner.departed.retain(|d| d.since.elapsed() < DEPARTED_TTL);
+
+        if inner.started_at.is_some() && !inner.departed.is_empty() {
+            let departed = std::mem::take(&mut inner.departed);
+            let arrived: Vec<PaneIdentity> = {
+                let sessions = state.sessions.read();
+                panes
+                    .iter()
+                    .filter(|pane| !sessions.contains_key(&pane.session_id))
+                    .cloned()
+                    .collect()
+            };
+            let identities: Vec<PaneIdentity> =
+                departed.iter().map(|d| d.identity.clone()).collect();
+            let moves: Vec<Rekey> = pair(&identities, &arrived)
+                .into_iter()
+                .map(|(d, a)| Rekey {
+                    session: departed[d].session.clone(),
+                    id: arrived[a].session_id.clone(),
+                    target: arrived[a].target.clone(),
+                    pane_id: Some(arrived[a].pane_id.clone()),
+                })
+                .collect();
+            session_identity::rekey(state, moves);
+        }
+
+        inner.logs = panes
+            .iter()
+            .filter_map(|pane| Some((pane.session_id.clone(), pane.log.clone()?)))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(target: &str, agent_kind: &str, cwd: &str, log: Option<&str>) -> PaneIdentity {
+        PaneIdentity {
+            session_id: format!("tmux:{}", target),
+            pane_id: String::new(),
+            target: target.to_string(),
+            agent_kind: agent_kind.to_string(),
+            cwd: cwd.to_string(),
+            log: log.map(PathBuf::from),
+        }
+    }
+
+    #[test]
+    fn panes_are_matched_by_agent_and_path_then_log() {
+        let departed = [
+            pane("main:1.0", "claude", "/src/api", Some("a.jsonl")),
+            pane("main:2.0", "claude", "/src/web", Some("b.jsonl")),
+            pane("main:2.1", "claude", "/src/web", Some("c.jsonl")),
+            pane("main:3.0", "codex", "/src/api", None),
+            pane("main:4.0", "claude", "/src/docs", None),
+            pane("main:4.1", "claude", "/src/docs", None),
+        ];
+        let arrived = [
+            pane("0:1.0", "claude", "/src/web", Some("c.jsonl")),
+            pane("0:2.0", "claude", "/src/api", Some("a2.jsonl")),
+            pane("0:3.0", "claude", "/src/web", Some("b.jsonl")),
+            pane("0:4.0", "claude", "/src/docs", None),
+            pane("0:4.1", "claude", "/src/docs", None),
+        ];
+        assert_eq!(
+            pair(&departed, &arrived),
+            vec![
+                // The only claude pane in /src/api, though its log changed
+                (0, 1),
+                // Two in /src/web, told apart by log
+                (1, 2),
+                (2, 0),
+                // No codex pane came back; the two /src/docs panes are ambiguous
+            ]
+        );
+    }
+}
@@ -1,65 +1,98 @@
+use crate::agents;
 use crate::cmd;
+use crate::connectivity;
+use crate::git::{self, GitBaseline};
+use crate::jsonl::{
+    self, awaiting_input_state, extract_message_timestamp, is_conversation_message,
+    ConversationState,
+};
+use crate::jsonl_tail::JsonlTails;
+use crate::pane_signal;
+use crate::plugins::power_source;
+use crate::process_table::{ChildCache, PaneChildren, ProcessTable};
+use crate::session_identity::{self, ScannedPane};
+use crate::simulate::SimulatedPane;
+use crate::state_machine::{self, Decision, StateSource};
+use crate::tmux_restart::PaneIdentity;
+use crate::vscode;
 use crate::{
     is_unresolved_hook_session, AppState, C3Session, PendingAction, SessionState, StateDiagnostic,
+    StopRequest,
 };
 use chrono::{DateTime, Utc};
-use std::collections::HashSet;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::time::SystemTime;
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Info about a tmux pane running an AI coding agent
-#[derive(Debug)]
-struct AgentPane {
-    target: String,
-    cwd: String,
-    pane_title: String,
+#[derive(Debug, Clone)]
+pub(crate) struct AgentPane {
+    /// tmux's `%N` ID, stable while the pane lives
+    pub(crate) pane_id: String,
+    /// `session:window.pane`, which changes when windows move or renumber
+    pub(crate) target: String,
+    pub(crate) cwd: String,
+    pub(crate) pane_title: String,
     window_name: String,
     pane_command: String,
     agent_kind: String,
+    /// Exit status of the pane's process when tmux keeps the dead pane (remain-on-exit)
+    dead_status: Option<i32>,
+    /// The agent's command line turns off permission prompts
+    yolo: bool,
 }
 
-/// State derived from reading JSONL conversation files
-#[derive(Debug)]
-struct ConversationState {
-    state: SessionState,
-    pending_action: Option<PendingAction>,
-    last_message_time: Option<DateTime<Utc>>,
+/// Shells an agent pane falls back to once the agent process exits
+pub(crate) fn is_shell_command(command: &str) -> bool {
+    matches!(
+        command.trim_start_matches('-'),
+        "zsh" | "bash" | "fish" | "sh" | "dash" | "nu"
+    )
 }
 
-/// Scan tmux for all panes running Claude Code or Codex
-fn find_agent_panes() -> Vec<AgentPane> {
+/// Scan tmux for all panes running a known agent (see `agents::AGENTS`).
+/// Panes with a pending stop request are kept while they sit at a shell prompt.
+/// Also returns the server's start time. Fails when no server answered.
+fn find_agent_panes(
+    stop_requests: &HashMap<String, StopRequest>,
+    table: &ProcessTable,
+    child_cache: &ChildCache,
+) -> Result<(Vec<AgentPane>, Option<i64>), String> {
     let output = cmd("tmux")
         .args([
             "list-panes",
             "-a",
             "-F",
-            "#{session_name}:#{window_index}.#{pane_index}\t#{pane_pid}\t#{pane_current_command}\t#{pane_current_path}\t#{pane_title}\t#{window_name}",
+            "#{session_name}:#{window_index}.#{pane_index}\t#{pane_pid}\t#{pane_current_command}\t#{pane_current_path}\t#{pane_title}\t#{window_name}\t#{pane_dead}\t#{pane_dead_status}\t#{start_time}\t#{pane_id}",
         ])
         .output();
 
     let output = match output {
         Ok(o) if o.status.success() => o,
         Ok(o) => {
-            let stderr = String::from_utf8_lossy(&o.stderr);
-            log::error!(
+            return Err(format!(
                 "tmux list-panes failed (status {:?}): {}",
                 o.status.code(),
-                stderr
-            );
-            return vec![];
-        }
-        Err(e) => {
-            log::error!("tmux command failed to execute: {}", e);
-            return vec![];
+                String::from_utf8_lossy(&o.stderr).trim()
+            ));
         }
+        Err(e) => return Err(format!("tmux command failed to execute: {}", e)),
     };
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     let mut panes = Vec::new();
+    let mut live_pids = HashSet::new();
+    let server_started = stdout
+        .lines()
+        .next()
+        .and_then(|line| line.split('\t').nth(8))
+        .and_then(|started| started.trim().parse().ok());
 
     for line in stdout.lines() {
         let parts: Vec<&str> = line.split('\t').collect();
@@ -68,114 +101,68 @@ fn find_agent_panes() -> Vec<AgentPane> {
         }
 
         let target = parts[0];
+        let pane_id = parts.get(9).copied().unwrap_or(target);
         let pane_pid = parts[1];
         let pane_command = parts[2];
         let cwd = parts[3];
         let pane_title = parts[4];
         let window_name = parts[5];
+        live_pids.insert(pane_pid.to_string());
+        let dead_status = if parts.get(6) == Some(&"1") {
+            parts
+                .get(7)
+                .and_then(|status| status.trim().parse::<i32>().ok())
+        } else {
+            None
+        };
 
-        // Detect Claude sessions:
-        // 1. pane_current_command contains "claude"
-        // 2. pane_current_command is "node" and child is claude
-        // 3. pane_current_command is a versioned Claude binary (e.g. "2.1.37")
-        let is_active_claude = pane_command.contains("claude")
-            || (pane_command == "node" && is_child_claude(pane_pid))
-            || is_claude_version_binary(pane_command);
-        let is_active_codex =
-            pane_command.contains("codex") || (pane_command == "node" && is_child_codex(pane_pid));
-        let is_active_omp = pane_command.contains("omp")
-            || ((pane_command == "node" || pane_command == "bun") && is_child_omp(pane_pid));
-
-        // Also detect completed sessions (back to shell but title has marker)
-        let has_claude_title = pane_title.contains('✳') || pane_title.contains("Claude");
-        let has_codex_title = pane_title.contains("Codex") || pane_title.contains("codex");
-        let has_omp_title = pane_title.contains("OMP")
-            || pane_title.contains("omp")
-            || pane_title.contains('π');
-
-        if is_active_claude
-            || is_active_codex
-            || is_active_omp
-            || ((has_claude_title || has_codex_title || has_omp_title) && pane_command == "zsh")
-        {
+        if let Some(request) = stop_requests.get(target) {
+            if is_shell_command(pane_command) {
+                panes.push(AgentPane {
+                    pane_id: pane_id.to_string(),
+                    target: target.to_string(),
+                    cwd: cwd.to_string(),
+                    pane_title: pane_title.to_string(),
+                    window_name: window_name.to_string(),
+                    pane_command: pane_command.to_string(),
+                    agent_kind: request.agent_kind.clone(),
+                    dead_status,
+                    yolo: false,
+                });
+                continue;
+            }
+        }
+
+        let children = PaneChildren::new(pane_pid, pane_command, table, child_cache);
+        if let Some(agent) = agents::detect(pane_command, &children, pane_title) {
             panes.push(AgentPane {
+                pane_id: pane_id.to_string(),
                 target: target.to_string(),
                 cwd: cwd.to_string(),
                 pane_title: pane_title.to_string(),
                 window_name: window_name.to_string(),
                 pane_command: pane_command.to_string(),
-                agent_kind: if is_active_omp || has_omp_title {
-                    "omp".to_string()
-                } else if is_active_codex || has_codex_title {
-                    "codex".to_string()
-                } else {
-                    "claude".to_string()
-                },
+                agent_kind: agent.kind.to_string(),
+                dead_status,
+                yolo: agents::runs_yolo(&children),
             });
         }
     }
 
-    panes
-}
-
-/// Check if any child process of the given PID is claude
-fn is_child_claude(pane_pid: &str) -> bool {
-    // pgrep for claude as a child of the pane process
-    cmd("pgrep")
-        .args(["-P", pane_pid, "-f", "claude"])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
-}
-
-/// Check if any child process of the given PID is omp.
-/// macOS pgrep can miss Bun-launched scripts, so inspect the process table.
-fn is_child_omp(pane_pid: &str) -> bool {
-    let output = match cmd("ps").args(["-ax", "-o", "ppid=,command="]).output() {
-        Ok(output) if output.status.success() => output,
-        _ => return false,
-    };
-
-    String::from_utf8_lossy(&output.stdout).lines().any(|line| {
-        let trimmed = line.trim_start();
-        let parent_end = match trimmed.find(|c: char| c.is_whitespace()) {
-            Some(index) => index,
-            None => return false,
-        };
-        let (parent_pid, command) = trimmed.split_at(parent_end);
-        parent_pid == pane_pid
-            && command
-                .split_whitespace()
-                .any(|part| part == "omp" || part.ends_with("/omp"))
-    })
-}
-
-/// Check if any child process of the given PID is codex
-fn is_child_codex(pane_pid: &str) -> bool {
-    cmd("pgrep")
-        .args(["-P", pane_pid, "-f", "codex"])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+    child_cache.retain(&live_pids);
+    Ok((panes, server_started))
 }
 
-/// Check if the command name looks like a versioned Claude Code binary.
-/// Enterprise Claude Code installs to ~/.local/share/claude/versions/<version>,
-/// and tmux reports pane_current_command as the binary name (e.g. "2.1.37").
-/// Old versions get cleaned up, so we pattern-match instead of checking the file.
-fn is_claude_version_binary(command: &str) -> bool {
-    // Match semver-like patterns: digits.digits.digits (e.g. "2.1.75")
-    let parts: Vec<&str> = command.split('.').collect();
-    parts.len() == 3
-        && parts
-            .iter()
-            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+/// Convert a cwd to the Claude projects directory path
+pub(crate) fn claude_projects_dir() -> PathBuf {
+    dirs_next()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join(".claude")
+        .join("projects")
 }
 
-/// Convert a cwd to the Claude projects directory path
-fn cwd_to_project_dir(cwd: &str) -> PathBuf {
-    let home = dirs_next().unwrap_or_else(|| PathBuf::from("/tmp"));
-    let claude_projects = home.join(".claude").join("projects");
+pub(crate) fn cwd_to_project_dir(cwd: &str) -> PathBuf {
+    let claude_projects = claude_projects_dir();
 
     // Claude uses cwd with / replaced by -
     // e.g. /Users/jon/code/foo → -Users-jon-code-foo
@@ -207,14 +194,14 @@ fn find_active_jsonl(project_dir: &Path) -> Option<PathBuf> {
         .map(|e| e.path())
 }
 
-fn codex_sessions_dir() -> PathBuf {
+pub(crate) fn codex_sessions_dir() -> PathBuf {
     dirs_next()
         .unwrap_or_else(|| PathBuf::from("/tmp"))
         .join(".codex")
         .join("sessions")
 }
 
-fn collect_jsonl_files(dir: &Path, out: &mut Vec<PathBuf>) {
+pub(crate) fn collect_jsonl_files(dir: &Path, out: &mut Vec<PathBuf>) {
     let entries = match fs::read_dir(dir) {
         Ok(entries) => entries,
         Err(_) => return,
@@ -255,7 +242,7 @@ fn codex_jsonl_matches_cwd(path: &Path, cwd: &str) -> bool {
     false
 }
 
-fn find_active_codex_jsonl(cwd: &str) -> Option<PathBuf> {
+pub(crate) fn find_active_codex_jsonl(cwd: &str) -> Option<PathBuf> {
     let mut files = Vec::new();
     collect_jsonl_files(&codex_sessions_dir(), &mut files);
     files.sort_by_key(|path| {
@@ -271,7 +258,7 @@ fn find_active_codex_jsonl(cwd: &str) -> Option<PathBuf> {
         .find(|path| codex_jsonl_matches_cwd(path, cwd))
 }
 
-fn omp_sessions_dir() -> PathBuf {
+pub(crate) fn omp_sessions_dir() -> PathBuf {
     dirs_next()
         .unwrap_or_else(|| PathBuf::from("/tmp"))
         .join(".omp")
@@ -321,7 +308,7 @@ fn omp_jsonl_matches_cwd(path: &Path, cwd: &str) -> bool {
     false
 }
 
-fn find_active_omp_jsonl(cwd: &str) -> Option<PathBuf> {
+pub(crate) fn find_active_omp_jsonl(cwd: &str) -> Option<PathBuf> {
     let sessions_dir = omp_sessions_dir();
     if !sessions_dir.exists() {
         return None;
@@ -338,9 +325,102 @@ fn find_active_omp_jsonl(cwd: &str) -> Option<PathBuf> {
     matches.into_iter().next()
 }
 
-fn detect_state_from_omp_jsonl(jsonl_path: &Path) -> ConversationState {
-    let last_msg_time = latest_timestamp_from_jsonl(jsonl_path);
-    let lines = read_last_lines(jsonl_path, 50);
+/// Locate the active conversation log for an agent running in `cwd`
+pub(crate) fn find_agent_jsonl(agent_kind: &str, cwd: &str) -> Option<PathBuf> {
+    let agent = agents::by_kind(agent_kind).unwrap_or(agents::FALLBACK);
+    (agent.find_log)(cwd)
+}
+
+pub(crate) fn find_claude_jsonl(cwd: &str) -> Option<PathBuf> {
+    find_active_jsonl(&cwd_to_project_dir(cwd))
+}
+
+/// Inspect the trailing records of an ended session for a fatal error.
+/// Returns the error text when the last meaningful record is an error,
+/// or None when the agent finished normally.
+fn exit_error_from_lines(lines: &[String], agent_kind: &str) -> Option<String> {
+    for line in lines.iter().rev() {
+        let parsed: serde_json::Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let msg_type = parsed.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+        match agent_kind {
+            "codex" => {
+                let payload = parsed.get("payload").unwrap_or(&serde_json::Value::Null);
+                let payload_type = payload.get("type").and_then(|v| v.as_str()).unwrap_or("");
+                match (msg_type, payload_type) {
+                    ("event_msg", "error") | ("event_msg", "stream_error") => {
+                        let message = payload
+                            .get("message")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("Codex reported an error");
+                        return Some(truncate_chars(message, 200));
+                    }
+                    ("event_msg", "token_count") | ("turn_context", _) => continue,
+                    _ => return None,
+                }
+            }
+            "omp" => {
+                if msg_type != "message" {
+                    continue;
+                }
+                let message = parsed.get("message").unwrap_or(&serde_json::Value::Null);
+                if message.get("stopReason").and_then(|v| v.as_str()) == Some("error") {
+                    let text = message
+                        .get("errorMessage")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("OMP reported an error");
+                    return Some(truncate_chars(text, 200));
+                }
+                return None;
+            }
+            _ => {
+                if msg_type == "system"
+                    && parsed.get("level").and_then(|v| v.as_str()) == Some("error")
+                {
+                    let text = parsed
+                        .get("content")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("Claude reported an error");
+                    return Some(truncate_chars(text, 200));
+                }
+                if parsed
+                    .get("isApiErrorMessage")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+                {
+                    let text =
+                        first_text_block(parsed.get("message").and_then(|m| m.get("content")))
+                            .unwrap_or_else(|| "API error".to_string());
+                    return Some(truncate_chars(&text, 200));
+                }
+                if is_conversation_message(&parsed) {
+                    return None;
+                }
+            }
+        }
+    }
+    None
+}
+
+fn first_text_block(content: Option<&serde_json::Value>) -> Option<String> {
+    match content? {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(blocks) => blocks
+            .iter()
+            .find(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .and_then(|b| b.get("text"))
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string()),
+        _ => None,
+    }
+}
+
+fn detect_state_from_omp_jsonl(tails: &JsonlTails, jsonl_path: &Path) -> ConversationState {
+    let last_msg_time = latest_timestamp_from_jsonl(tails, jsonl_path);
+    let lines = tails.read_last_lines(jsonl_path, 50);
 
     for line in lines.iter().rev() {
         let parsed: serde_json::Value = match serde_json::from_str(line) {
@@ -386,6 +466,7 @@ fn detect_state_from_omp_jsonl(jsonl_path: &Path) -> ConversationState {
                         description: "Waiting for user input".to_string(),
                         tool: None,
                         command: None,
+                        options: Vec::new(),
                     }),
                     last_message_time: last_msg_time,
                 };
@@ -409,21 +490,7 @@ fn detect_state_from_omp_jsonl(jsonl_path: &Path) -> ConversationState {
     }
 }
 
-/// Read the last N lines of a file (reads from end)
-fn read_last_lines(path: &Path, n: usize) -> Vec<String> {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return vec![],
-    };
-
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
-
-    let start = if lines.len() > n { lines.len() - n } else { 0 };
-    lines[start..].to_vec()
-}
-
-fn file_age_secs(path: &Path) -> Option<u64> {
+pub(crate) fn file_age_secs(path: &Path) -> Option<u64> {
     fs::metadata(path)
         .and_then(|m| m.modified())
         .ok()
@@ -431,19 +498,6 @@ fn file_age_secs(path: &Path) -> Option<u64> {
         .map(|d| d.as_secs())
 }
 
-fn awaiting_input_state(last_message_time: Option<DateTime<Utc>>) -> ConversationState {
-    ConversationState {
-        state: SessionState::AwaitingInput,
-        pending_action: Some(PendingAction {
-            action_type: "input".to_string(),
-            description: "Waiting for user input".to_string(),
-            tool: None,
-            command: None,
-        }),
-        last_message_time,
-    }
-}
-
 fn is_codex_spinner_title(title: &str) -> bool {
     title
         .trim()
@@ -498,310 +552,17 @@ fn reconcile_codex_state_with_title(
     conv_state
 }
 
-/// Check if a JSONL message is a real conversation message (not system noise)
-fn is_conversation_message(parsed: &serde_json::Value) -> bool {
-    let msg_type = parsed.get("type").and_then(|v| v.as_str()).unwrap_or("");
-
-    // Skip non-conversation message types entirely
-    if matches!(
-        msg_type,
-        "progress" | "system" | "file-history-snapshot" | "summary"
-    ) {
-        return false;
-    }
-
-    // Skip messages with isMeta flag
-    if parsed
-        .get("isMeta")
-        .and_then(|v| v.as_bool())
-        .unwrap_or(false)
-    {
-        return false;
-    }
-
-    // For user messages, skip internal Claude Code bookkeeping
-    if msg_type == "user" {
-        let content = parsed.get("message").and_then(|m| m.get("content"));
-
-        if let Some(serde_json::Value::String(text)) = content {
-            // These are Claude Code internal messages, not real user input
-            if text.starts_with("<local-command-caveat>")
-                || text.starts_with("<bash-input>")
-                || text.starts_with("<bash-stdout>")
-                || text.starts_with("<bash-stderr>")
-                || text == "[Request interrupted by user]"
-            {
-                return false;
-            }
-        }
-
-        // Also check array content for interrupt markers
-        if let Some(serde_json::Value::Array(blocks)) = content {
-            let has_interrupt = blocks.iter().any(|b| {
-                b.get("type").and_then(|t| t.as_str()) == Some("text")
-                    && b.get("text")
-                        .and_then(|t| t.as_str())
-                        .map(|t| t.contains("[Request interrupted by user]"))
-                        .unwrap_or(false)
-            });
-            if has_interrupt {
-                return false;
-            }
-        }
-    }
-
-    // Must be "user" or "assistant" type with a role
-    let role = parsed
-        .get("message")
-        .and_then(|m| m.get("role"))
-        .and_then(|r| r.as_str())
-        .unwrap_or("");
-
-    matches!(
-        (msg_type, role),
-        ("user", "user") | ("assistant", "assistant")
-    )
-}
-
-/// Extract a timestamp from a JSONL message
-fn extract_message_timestamp(parsed: &serde_json::Value) -> Option<DateTime<Utc>> {
-    // Try top-level timestamp first (ISO 8601 string)
-    if let Some(ts) = parsed.get("timestamp").and_then(|v| v.as_str()) {
-        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-            return Some(dt);
-        }
-    }
-    // Try nested message timestamp
-    if let Some(ts) = parsed
-        .get("message")
-        .and_then(|m| m.get("timestamp"))
-        .and_then(|v| v.as_str())
-    {
-        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-            return Some(dt);
-        }
-    }
-    // Try nested data.message.timestamp (progress messages)
-    if let Some(ts) = parsed
-        .get("data")
-        .and_then(|d| d.get("message"))
-        .and_then(|m| m.get("timestamp"))
-        .and_then(|v| v.as_str())
-    {
-        if let Ok(dt) = ts.parse::<DateTime<Utc>>() {
-            return Some(dt);
-        }
-    }
-    None
-}
-
-/// Determine state from JSONL conversation file
-fn detect_state_from_jsonl(jsonl_path: &Path) -> ConversationState {
+/// Determine state from a Claude JSONL conversation file
+fn detect_state_from_jsonl(tails: &JsonlTails, jsonl_path: &Path) -> ConversationState {
     // Read more lines to look past system noise
-    let last_lines = read_last_lines(jsonl_path, 30);
-
-    if last_lines.is_empty() {
-        return ConversationState {
-            state: SessionState::Processing,
-            pending_action: None,
-            last_message_time: None,
-        };
-    }
-
-    // Check file modification time for staleness
+    let last_lines = tails.read_last_lines(jsonl_path, 30);
+    // File modification time tells a stale transcript from one being written
     let file_age_secs = file_age_secs(jsonl_path).unwrap_or(0);
-
-    // Find the latest timestamp from any message in the last lines
-    let mut latest_timestamp: Option<DateTime<Utc>> = None;
-    for line in last_lines.iter().rev() {
-        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
-            if let Some(ts) = extract_message_timestamp(&parsed) {
-                if latest_timestamp.is_none() || ts > latest_timestamp.unwrap() {
-                    latest_timestamp = Some(ts);
-                    break; // Lines are in order, so the last one with a timestamp is the most recent
-                }
-            }
-        }
-    }
-
-    // Walk backwards through lines, skipping noise, to find last real message
-    for line in last_lines.iter().rev() {
-        let parsed: serde_json::Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
-
-        if !is_conversation_message(&parsed) {
-            continue;
-        }
-
-        let msg_type = parsed.get("type").and_then(|v| v.as_str()).unwrap_or("");
-        let message = parsed.get("message").unwrap_or(&serde_json::Value::Null);
-        let content = message.get("content");
-
-        match msg_type {
-            "user" => {
-                // Check if this is a tool_result (part of ongoing tool use chain)
-                if let Some(serde_json::Value::Array(blocks)) = content {
-                    let has_tool_result = blocks
-                        .iter()
-                        .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_result"));
-                    if has_tool_result {
-                        return ConversationState {
-                            state: SessionState::Processing,
-                            pending_action: None,
-                            last_message_time: latest_timestamp,
-                        };
-                    }
-                }
-                // Real user message — if file is stale, Claude already
-                // processed it and is waiting for more input. If fresh,
-                // Claude is actively generating a response.
-                if file_age_secs > 15 {
-                    return ConversationState {
-                        state: SessionState::AwaitingInput,
-                        pending_action: Some(PendingAction {
-                            action_type: "input".to_string(),
-                            description: "Waiting for user input".to_string(),
-                            tool: None,
-                            command: None,
-                        }),
-                        last_message_time: latest_timestamp,
-                    };
-                }
-                return ConversationState {
-                    state: SessionState::Processing,
-                    pending_action: None,
-                    last_message_time: latest_timestamp,
-                };
-            }
-
-            "assistant" => {
-                if let Some(serde_json::Value::Array(blocks)) = content {
-                    let block_types: Vec<&str> = blocks
-                        .iter()
-                        .filter_map(|b| b.get("type").and_then(|t| t.as_str()))
-                        .collect();
-
-                    // Has tool_use → either actively running or awaiting permission
-                    if block_types.contains(&"tool_use") {
-                        if file_age_secs > 5 {
-                            // Stale file + tool_use = likely awaiting permission
-                            let tool_name = blocks
-                                .iter()
-                                .filter(|b| {
-                                    b.get("type").and_then(|t| t.as_str()) == Some("tool_use")
-                                })
-                                .last()
-                                .and_then(|b| b.get("name"))
-                                .and_then(|n| n.as_str())
-                                .map(|s| s.to_string());
-
-                            let command = blocks
-                                .iter()
-                                .filter(|b| {
-                                    b.get("type").and_then(|t| t.as_str()) == Some("tool_use")
-                                })
-                                .last()
-                                .and_then(|b| b.get("input"))
-                                .and_then(|i| i.get("command"))
-                                .and_then(|c| c.as_str())
-                                .map(|s| {
-                                    if s.len() > 100 {
-                                        format!("{}...", &s[..97])
-                                    } else {
-                                        s.to_string()
-                                    }
-                                });
-
-                            return ConversationState {
-                                state: SessionState::AwaitingPermission,
-                                pending_action: Some(PendingAction {
-                                    action_type: "permission".to_string(),
-                                    description: format!(
-                                        "Wants to use {}",
-                                        tool_name.as_deref().unwrap_or("a tool")
-                                    ),
-                                    tool: tool_name,
-                                    command,
-                                }),
-                                last_message_time: latest_timestamp,
-                            };
-                        }
-                        // Fresh file + tool_use = actively processing
-                        return ConversationState {
-                            state: SessionState::Processing,
-                            pending_action: None,
-                            last_message_time: latest_timestamp,
-                        };
-                    }
-
-                    // Text/thinking only, no tool_use → Claude finished, waiting for input
-                    if block_types.contains(&"text") {
-                        return ConversationState {
-                            state: SessionState::AwaitingInput,
-                            pending_action: Some(PendingAction {
-                                action_type: "input".to_string(),
-                                description: "Waiting for user input".to_string(),
-                                tool: None,
-                                command: None,
-                            }),
-                            last_message_time: latest_timestamp,
-                        };
-                    }
-
-                    // Only thinking block (no text yet) → still processing
-                    if block_types.contains(&"thinking") && !block_types.contains(&"text") {
-                        return ConversationState {
-                            state: SessionState::Processing,
-                            pending_action: None,
-                            last_message_time: latest_timestamp,
-                        };
-                    }
-                }
-
-                // String content → simple text response, waiting for input
-                if content.map(|c| c.is_string()).unwrap_or(false) {
-                    return ConversationState {
-                        state: SessionState::AwaitingInput,
-                        pending_action: Some(PendingAction {
-                            action_type: "input".to_string(),
-                            description: "Waiting for user input".to_string(),
-                            tool: None,
-                            command: None,
-                        }),
-                        last_message_time: latest_timestamp,
-                    };
-                }
-            }
-
-            _ => continue,
-        }
-    }
-
-    // No real conversation messages found in the last 30 lines.
-    // If file is stale, Claude is idle waiting for input.
-    if file_age_secs > 15 {
-        return ConversationState {
-            state: SessionState::AwaitingInput,
-            pending_action: Some(PendingAction {
-                action_type: "input".to_string(),
-                description: "Waiting for user input".to_string(),
-                tool: None,
-                command: None,
-            }),
-            last_message_time: latest_timestamp,
-        };
-    }
-    ConversationState {
-        state: SessionState::Processing,
-        pending_action: None,
-        last_message_time: latest_timestamp,
-    }
+    jsonl::detect_claude_state(&last_lines, file_age_secs)
 }
 
-fn detect_state_from_codex_jsonl(jsonl_path: &Path) -> ConversationState {
-    let last_lines = read_last_lines(jsonl_path, 50);
+fn detect_state_from_codex_jsonl(tails: &JsonlTails, jsonl_path: &Path) -> ConversationState {
+    let last_lines = tails.read_last_lines(jsonl_path, 50);
     if last_lines.is_empty() {
         return ConversationState {
             state: SessionState::Processing,
@@ -850,6 +611,7 @@ fn detect_state_from_codex_jsonl(jsonl_path: &Path) -> ConversationState {
                             description: "Waiting for user input".to_string(),
                             tool: None,
                             command: None,
+                            options: Vec::new(),
                         }),
                         last_message_time: latest_timestamp,
                     };
@@ -862,6 +624,7 @@ fn detect_state_from_codex_jsonl(jsonl_path: &Path) -> ConversationState {
                             description: "Waiting for user input".to_string(),
                             tool: None,
                             command: None,
+                            options: Vec::new(),
                         }),
                         last_message_time: latest_timestamp,
                     };
@@ -923,6 +686,7 @@ fn detect_state_from_codex_jsonl(jsonl_path: &Path) -> ConversationState {
                         description: "Waiting for user input".to_string(),
                         tool: None,
                         command: None,
+                        options: Vec::new(),
                     }),
                     last_message_time: latest_timestamp,
                 };
@@ -965,6 +729,7 @@ fn detect_state_from_codex_jsonl(jsonl_path: &Path) -> ConversationState {
                 description: "Waiting for user input".to_string(),
                 tool: None,
                 command: None,
+                options: Vec::new(),
             }),
             last_message_time: latest_timestamp,
         };
@@ -989,6 +754,7 @@ fn codex_pending_tool_action(payload: &serde_json::Value) -> PendingAction {
         description: format!("Wants to use {}", tool_name.as_deref().unwrap_or("a tool")),
         tool: tool_name,
         command: codex_tool_command(payload),
+        options: Vec::new(),
     }
 }
 
@@ -1028,10 +794,14 @@ fn codex_tool_input(payload: &serde_json::Value) -> Option<serde_json::Value> {
     None
 }
 
-fn codex_permission_diagnostic(jsonl_path: &Path, age_secs: Option<u64>) -> (String, Option<String>) {
+fn codex_permission_diagnostic(
+    tails: &JsonlTails,
+    jsonl_path: &Path,
+    age_secs: Option<u64>,
+) -> (String, Option<String>) {
     let mut completed_call_ids: HashSet<String> = HashSet::new();
 
-    for line in read_last_lines(jsonl_path, 50).iter().rev() {
+    for line in tails.read_last_lines(jsonl_path, 50).iter().rev() {
         let parsed: serde_json::Value = match serde_json::from_str(line) {
             Ok(v) => v,
             Err(_) => continue,
@@ -1158,7 +928,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_omp_jsonl(&path);
+        let state = detect_state_from_omp_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::AwaitingInput);
@@ -1174,7 +944,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_omp_jsonl(&path);
+        let state = detect_state_from_omp_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::Processing);
@@ -1190,7 +960,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_omp_jsonl(&path);
+        let state = detect_state_from_omp_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::Processing);
@@ -1206,7 +976,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_omp_jsonl(&path);
+        let state = detect_state_from_omp_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::Processing);
@@ -1223,7 +993,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_codex_jsonl(&path);
+        let state = detect_state_from_codex_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::Complete);
@@ -1240,7 +1010,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_codex_jsonl(&path);
+        let state = detect_state_from_codex_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::AwaitingPermission);
@@ -1265,7 +1035,7 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_codex_jsonl(&path);
+        let state = detect_state_from_codex_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::AwaitingInput);
@@ -1327,6 +1097,7 @@ mod tests {
                     description: "Waiting for user input".to_string(),
                     tool: None,
                     command: None,
+                    options: Vec::new(),
                 }),
                 last_message_time: None,
             },
@@ -1348,13 +1119,61 @@ mod tests {
             ],
         );
 
-        let state = detect_state_from_codex_jsonl(&path);
+        let state = detect_state_from_codex_jsonl(&JsonlTails::default(), &path);
         let _ = fs::remove_file(path);
 
         assert_eq!(state.state, SessionState::Processing);
         assert!(state.pending_action.is_none());
     }
 
+    #[test]
+    fn claude_api_error_as_last_message_is_exit_error() {
+        let lines: Vec<String> = [
+            r#"{"type":"user","message":{"role":"user","content":"Refactor the parser"}}"#,
+            r#"{"type":"assistant","isApiErrorMessage":true,"message":{"role":"assistant","content":[{"type":"text","text":"API Error: 529 Overloaded"}]}}"#,
+            r#"{"type":"file-history-snapshot"}"#,
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        assert_eq!(
+            exit_error_from_lines(&lines, "claude").as_deref(),
+            Some("API Error: 529 Overloaded")
+        );
+    }
+
+    #[test]
+    fn claude_normal_final_message_is_not_exit_error() {
+        let lines: Vec<String> = [
+            r#"{"type":"assistant","isApiErrorMessage":true,"message":{"role":"assistant","content":[{"type":"text","text":"API Error: 500"}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":"try again"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"text","text":"Done"}]}}"#,
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        assert_eq!(exit_error_from_lines(&lines, "claude"), None);
+    }
+
+    #[test]
+    fn codex_trailing_error_event_is_exit_error() {
+        let lines: Vec<String> = [
+            r#"{"type":"event_msg","payload":{"type":"task_started"}}"#,
+            r#"{"type":"event_msg","payload":{"type":"error","message":"stream disconnected"}}"#,
+            r#"{"type":"event_msg","payload":{"type":"token_count"}}"#,
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+
+        assert_eq!(
+            exit_error_from_lines(&lines, "codex").as_deref(),
+            Some("stream disconnected")
+        );
+    }
+
     #[test]
     fn codex_idle_title_preserves_permission_state() {
         let state = reconcile_codex_state_with_title(
@@ -1366,6 +1185,7 @@ mod tests {
                     description: "Wants to use exec_command".to_string(),
                     tool: Some("exec_command".to_string()),
                     command: None,
+                    options: Vec::new(),
                 }),
                 last_message_time: None,
             },
@@ -1383,8 +1203,8 @@ mod tests {
     }
 }
 
-fn latest_timestamp_from_jsonl(jsonl_path: &Path) -> Option<DateTime<Utc>> {
-    let lines = read_last_lines(jsonl_path, 50);
+fn latest_timestamp_from_jsonl(tails: &JsonlTails, jsonl_path: &Path) -> Option<DateTime<Utc>> {
+    let lines = tails.read_last_lines(jsonl_path, 50);
     for line in lines.iter().rev() {
         if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
             if let Some(ts) = extract_message_timestamp(&parsed) {
@@ -1428,196 +1248,535 @@ fn derive_project_name(pane: &AgentPane) -> String {
         .unwrap_or_else(|| pane.agent_kind.clone())
 }
 
-/// Run a single scan cycle
-pub fn scan_tmux(state: &Arc<AppState>, app_handle: &AppHandle) {
-    let panes = find_agent_panes();
-    let mut found_targets: HashSet<String> = HashSet::new();
-
-    for pane in &panes {
-        found_targets.insert(pane.target.clone());
-        let session_id = format!("tmux:{}", pane.target);
-        let mut codex_jsonl_for_debug: Option<(PathBuf, Option<u64>)> = None;
-
-        // Determine state using pane title as primary signal:
-        // - ✳ = Claude Code idle (waiting for user input)
-        // - Braille spinner (U+2800..U+28FF) = actively processing
-        // - No special Claude prefix = transitional (tool exec, output) — still active
-        // - Codex without a spinner is idle unless JSONL says complete/permission
-        // - zsh = session ended, back at shell
-        let title_trimmed = pane.pane_title.trim();
-        let title_starts_with_idle_marker = title_trimmed.starts_with('✳');
-
-        let conv_state = if pane.pane_command == "zsh" {
-            // Session ended — still grab the last message timestamp from JSONL
-            let last_msg_time = if pane.agent_kind == "codex" {
-                find_active_codex_jsonl(&pane.cwd)
-                    .and_then(|jsonl| latest_timestamp_from_jsonl(&jsonl))
-            } else if pane.agent_kind == "omp" {
-                find_active_omp_jsonl(&pane.cwd)
-                    .and_then(|jsonl| latest_timestamp_from_jsonl(&jsonl))
-            } else {
-                let project_dir = cwd_to_project_dir(&pane.cwd);
-                find_active_jsonl(&project_dir)
-                    .and_then(|jsonl| latest_timestamp_from_jsonl(&jsonl))
-            };
-            ConversationState {
-                state: SessionState::Complete,
-                pending_action: None,
-                last_message_time: last_msg_time,
-            }
-        } else if pane.agent_kind == "codex" {
-            match find_active_codex_jsonl(&pane.cwd) {
-                Some(jsonl) => {
-                    let jsonl_age_secs = file_age_secs(&jsonl);
-                    codex_jsonl_for_debug = Some((jsonl.clone(), jsonl_age_secs));
-                    let detected = detect_state_from_codex_jsonl(&jsonl);
-                    reconcile_codex_state_with_title(&pane.pane_title, detected, jsonl_age_secs)
+/// Codex: the JSONL decides, reconciled with the spinner in the pane title
+pub(crate) fn codex_pane_state(tails: &JsonlTails, pane: &AgentPane) -> ConversationState {
+    match find_active_codex_jsonl(&pane.cwd) {
+        Some(jsonl) => {
+            let jsonl_age_secs = file_age_secs(&jsonl);
+            let detected = detect_state_from_codex_jsonl(tails, &jsonl);
+            reconcile_codex_state_with_title(&pane.pane_title, detected, jsonl_age_secs)
+        }
+        None if is_codex_spinner_title(&pane.pane_title) => ConversationState {
+            state: SessionState::Processing,
+            pending_action: None,
+            last_message_time: None,
+        },
+        None => awaiting_input_state(None),
+    }
+}
+
+/// OMP: the pane's status line decides, the JSONL fills in the rest
+pub(crate) fn omp_pane_state(tails: &JsonlTails, pane: &AgentPane) -> ConversationState {
+    let jsonl_state =
+        find_active_omp_jsonl(&pane.cwd).map(|jsonl| detect_state_from_omp_jsonl(tails, &jsonl));
+    let last_message_time = jsonl_state
+        .as_ref()
+        .and_then(|detected| detected.last_message_time);
+
+    match omp_pane_is_processing(&pane.target) {
+        Some(true) => ConversationState {
+            state: SessionState::Processing,
+            pending_action: None,
+            last_message_time,
+        },
+        Some(false) => awaiting_input_state(last_message_time),
+        None => jsonl_state.unwrap_or(ConversationState {
+            state: SessionState::Processing,
+            pending_action: None,
+            last_message_time: None,
+        }),
+    }
+}
+
+/// Claude Code: the pane title is the primary signal.
+/// - ✳ = idle; the JSONL tells AwaitingInput from AwaitingPermission
+/// - Braille spinner (U+2800..U+28FF) = actively processing
+/// - No special prefix = transitional (tool exec, output) — still active
+pub(crate) fn claude_pane_state(tails: &JsonlTails, pane: &AgentPane) -> ConversationState {
+    let project_dir = cwd_to_project_dir(&pane.cwd);
+    if pane.pane_title.trim().starts_with('✳') {
+        return match find_active_jsonl(&project_dir) {
+            Some(jsonl) => detect_state_from_jsonl(tails, &jsonl),
+            None => ConversationState {
+                state: SessionState::AwaitingInput,
+                pending_action: Some(PendingAction {
+                    action_type: "input".to_string(),
+                    description: "Waiting for user input".to_string(),
+                    tool: None,
+                    command: None,
+                    options: Vec::new(),
+                }),
+                last_message_time: None,
+            },
+        };
+    }
+
+    // Still grab the last message timestamp
+    let last_msg_time = find_active_jsonl(&project_dir).and_then(|jsonl| {
+        let lines = tails.read_last_lines(&jsonl, 30);
+        for line in lines.iter().rev() {
+            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
+                if let Some(ts) = extract_message_timestamp(&parsed) {
+                    return Some(ts);
                 }
-                None if is_codex_spinner_title(&pane.pane_title) => ConversationState {
-                    state: SessionState::Processing,
-                    pending_action: None,
-                    last_message_time: None,
-                },
-                None => awaiting_input_state(None),
             }
-        } else if pane.agent_kind == "omp" {
-            let jsonl_state = find_active_omp_jsonl(&pane.cwd)
-                .map(|jsonl| detect_state_from_omp_jsonl(&jsonl));
-            let last_message_time = jsonl_state
-                .as_ref()
-                .and_then(|detected| detected.last_message_time);
+        }
+        None
+    });
+    ConversationState {
+        state: SessionState::Processing,
+        pending_action: None,
+        last_message_time: last_msg_time,
+    }
+}
 
-            match omp_pane_is_processing(&pane.target) {
-                Some(true) => ConversationState {
-                    state: SessionState::Processing,
-                    pending_action: None,
-                    last_message_time,
-                },
-                Some(false) => awaiting_input_state(last_message_time),
-                None => jsonl_state.unwrap_or(ConversationState {
-                    state: SessionState::Processing,
-                    pending_action: None,
-                    last_message_time: None,
+/// Result of the blocking, per-pane part of a scan cycle
+struct PaneAnalysis {
+    conv_state: ConversationState,
+    error_reason: Option<String>,
+    /// Activity time to fall back on when the JSONL has no message timestamp
+    jsonl_activity: DateTime<Utc>,
+    /// Why a codex pane was classified as AwaitingPermission, for the debug log
+    permission_diagnostic: Option<(String, Option<String>)>,
+    /// Taken once the scan knows the pane has no session yet
+    git_baseline: Option<GitBaseline>,
+    /// The agent's conversation log, to recognize the pane after a tmux restart
+    log_path: Option<PathBuf>,
+    /// UUID of the Claude conversation the log is from
+    conversation: Option<String>,
+}
+
+/// Classify one pane from its title and JSONL transcript.
+/// Blocking (file IO, tmux capture) — run on the blocking pool.
+fn analyze_pane(state: &AppState, pane: &AgentPane, stop_requested: bool) -> PaneAnalysis {
+    let tails = &state.jsonl_tails;
+
+    // A pane back at its shell (or dead) has ended; otherwise the agent's
+    // own parser reads its title, pane and transcript
+    let mut error_reason: Option<String> = None;
+    let log_path = find_agent_jsonl(&pane.agent_kind, &pane.cwd);
+    let conv_state = if pane.dead_status.is_some() || is_shell_command(&pane.pane_command) {
+        // Session ended — still grab the last message timestamp from JSONL,
+        // and tell crashes apart from normal exits.
+        let jsonl = log_path.as_ref();
+        let last_msg_time = jsonl
+            .as_ref()
+            .and_then(|jsonl| latest_timestamp_from_jsonl(tails, jsonl));
+        if !stop_requested {
+            error_reason = match pane.dead_status {
+                Some(status) if status != 0 => {
+                    Some(format!("{} exited with status {}", pane.agent_kind, status))
+                }
+                _ => jsonl.and_then(|jsonl| {
+                    exit_error_from_lines(&tails.read_last_lines(jsonl, 30), &pane.agent_kind)
                 }),
-            }
-        } else if title_starts_with_idle_marker {
-            // ✳ means Claude Code is idle — check JSONL for AwaitingInput vs AwaitingPermission
-            let project_dir = cwd_to_project_dir(&pane.cwd);
-            match find_active_jsonl(&project_dir) {
-                Some(jsonl) => detect_state_from_jsonl(&jsonl),
-                None => ConversationState {
-                    state: SessionState::AwaitingInput,
-                    pending_action: Some(PendingAction {
-                        action_type: "input".to_string(),
-                        description: "Waiting for user input".to_string(),
-                        tool: None,
-                        command: None,
-                    }),
-                    last_message_time: None,
-                },
+            };
+        }
+        ConversationState {
+            state: if error_reason.is_some() {
+                SessionState::Error
+            } else {
+                SessionState::Complete
+            },
+            pending_action: None,
+            last_message_time: last_msg_time,
+        }
+    } else {
+        let agent = agents::by_kind(&pane.agent_kind).unwrap_or(agents::FALLBACK);
+        let conv_state = (agent.pane_state)(tails, pane);
+        if pane.agent_kind == "claude"
+            && state.timings.read().arbiter.pane_signals
+            && !state.saving_power.load(Ordering::Relaxed)
+        {
+            match pane_signal::capture(&pane.target) {
+                Some(capture) => pane_signal::reconcile(conv_state, &capture),
+                None => conv_state,
             }
         } else {
-            // No ✳ = Claude is actively working (spinner or transitional)
-            // Still grab the last message timestamp
-            let project_dir = cwd_to_project_dir(&pane.cwd);
-            let last_msg_time = find_active_jsonl(&project_dir).and_then(|jsonl| {
-                let lines = read_last_lines(&jsonl, 30);
-                for line in lines.iter().rev() {
-                    if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(line) {
-                        if let Some(ts) = extract_message_timestamp(&parsed) {
-                            return Some(ts);
+            conv_state
+        }
+    };
+
+    // Use the JSONL message timestamp for last_activity when available,
+    // fall back to JSONL file modification time, then Utc::now() as last resort
+    let jsonl_activity = conv_state.last_message_time.unwrap_or_else(|| {
+        log_path
+            .as_ref()
+            .and_then(|p| fs::metadata(p).ok())
+            .and_then(|m| m.modified().ok())
+            .and_then(|t| {
+                let duration = t.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+                DateTime::from_timestamp(duration.as_secs() as i64, duration.subsec_nanos())
+            })
+            .unwrap_or_else(Utc::now)
+    });
+
+    let permission_diagnostic = (pane.agent_kind == "codex"
+        && conv_state.state == SessionState::AwaitingPermission)
+        .then(|| {
+            find_active_codex_jsonl(&pane.cwd)
+                .map(|jsonl| codex_permission_diagnostic(tails, &jsonl, file_age_secs(&jsonl)))
+                .unwrap_or_else(|| {
+                    (
+                        "codex scanner classified permission without matching JSONL".to_string(),
+                        None,
+                    )
+                })
+        });
+
+    let conversation = log_path
+        .as_deref()
+        .filter(|_| pane.agent_kind == "claude")
+        .and_then(session_identity::claude_uuid_from_log);
+
+    PaneAnalysis {
+        conv_state,
+        error_reason,
+        jsonl_activity,
+        permission_diagnostic,
+        git_baseline: None,
+        log_path,
+        conversation,
+    }
+}
+
+/// Timing of tmux scan cycles, shown in the debug panel
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanStats {
+    pub cycles: u64,
+    pub last_duration_ms: u64,
+    pub max_duration_ms: u64,
+    /// Cycles that took longer than `SLOW_SCAN_MS`
+    pub slow_cycles: u64,
+    pub last_pane_count: usize,
+    pub last_phases: ScanPhases,
+    /// Pane child-process lookups answered from the cache last cycle
+    pub last_children_cached: u64,
+    /// Pane child-process lookups that needed the process table last cycle
+    pub last_children_read: u64,
+    /// The last cycle reused the pane listing, as control mode reported no changes
+    pub last_listed_by_control: bool,
+    /// Why `list-panes` last failed; no server running shows up here too
+    pub last_tmux_error: Option<String>,
+    pub last_tmux_error_at: Option<DateTime<Utc>>,
+}
+
+/// How long each part of a scan cycle took
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanPhases {
+    /// `tmux list-panes` and agent detection
+    pub list_ms: u64,
+    /// The cycle's one `ps`, None when every pane's children were cached and
+    /// nothing else needed it
+    pub ps_ms: Option<u64>,
+    /// Transcript and pane analysis
+    pub analyze_ms: u64,
+    /// Session identities, baselines and state updates
+    pub apply_ms: u64,
+    /// Claude in VS Code terminals
+    pub vscode_ms: u64,
+}
+
+/// On battery or in Low Power Mode, scans are this many times further apart
+const SAVING_POWER_SCAN_FACTOR: u64 = 4;
+
+/// Scan cycles slower than this are logged
+const SLOW_SCAN_MS: u64 = 1000;
+
+/// Run a single scan cycle
+pub async fn scan_tmux(state: &Arc<AppState>) {
+    if state.simulation_active.load(Ordering::Relaxed) {
+        log::debug!("Skipping tmux scan while a simulation is running");
+        return;
+    }
+    let started = Instant::now();
+    let mut phases = ScanPhases::default();
+    let stop_requests = state.stop_requests.read().clone();
+    // Read at most once per cycle, by agent detection or the VS Code scan
+    let table = Arc::new(ProcessTable::default());
+    // Control mode pushes pane changes; without any, the last listing stands
+    let cached = state.tmux_control.cached_listing();
+    let listed_by_control = cached.is_some();
+    let (panes, server_started) = match cached {
+        Some(listing) => listing,
+        None => {
+            let stop_requests = stop_requests.clone();
+            let state = Arc::clone(state);
+            let table = Arc::clone(&table);
+            let listing = tokio::task::spawn_blocking(move || {
+                let (panes, server_started) =
+                    match find_agent_panes(&stop_requests, &table, &state.child_cache) {
+                        Ok(listing) => listing,
+                        Err(e) => {
+                            log::error!("{}", e);
+                            let mut stats = state.scan_stats.write();
+                            stats.last_tmux_error = Some(e);
+                            stats.last_tmux_error_at = Some(Utc::now());
+                            (vec![], None)
                         }
-                    }
-                }
-                None
+                    };
+                state.tmux_control.store_listing(&panes, server_started);
+                (panes, server_started)
             });
-            ConversationState {
-                state: SessionState::Processing,
-                pending_action: None,
-                last_message_time: last_msg_time,
+            match listing.await {
+                Ok(listing) => listing,
+                Err(e) => {
+                    log::error!("tmux pane listing failed: {}", e);
+                    return;
+                }
             }
-        };
+        }
+    };
+    // Ignored panes drop out here, so apply_scan also removes their sessions
+    let panes: Vec<AgentPane> = {
+        let ignore = state.ignore.read();
+        panes
+            .into_iter()
+            .filter(|pane| {
+                !ignore.ignores(&pane.cwd, Some(&pane.target))
+                    && !ignore.ignores_agent(&pane.agent_kind)
+            })
+            .collect()
+    };
+    phases.list_ms = started.elapsed().as_millis() as u64;
+
+    // Per-pane JSONL analysis is independent, so run it concurrently
+    let analyzing = Instant::now();
+    let analyses = join_all(panes.iter().map(|pane| {
+        let state = Arc::clone(state);
+        let pane = pane.clone();
+        let stop_requested = stop_requests.contains_key(&pane.target);
+        tokio::task::spawn_blocking(move || analyze_pane(&state, &pane, stop_requested))
+    }))
+    .await;
+    let analyses: Vec<Option<PaneAnalysis>> = panes
+        .iter()
+        .zip(analyses)
+        .map(|(pane, analysis)| {
+            analysis
+                .map_err(|e| log::error!("Scan of {} failed: {}", pane.target, e))
+                .ok()
+        })
+        .collect();
+    phases.analyze_ms = analyzing.elapsed().as_millis() as u64;
+
+    let applying = Instant::now();
+    state.tmux_server.observe_server(state, server_started);
+    let ids = session_identity::resolve_pane_ids(
+        &state.sessions.read(),
+        &scanned_panes(&panes, &analyses),
+    );
+    let identities: Vec<PaneIdentity> = panes
+        .iter()
+        .zip(&analyses)
+        .zip(&ids)
+        .map(|((pane, analysis), session_id)| PaneIdentity {
+            session_id: session_id.clone(),
+            pane_id: pane.pane_id.clone(),
+            target: pane.target.clone(),
+            agent_kind: pane.agent_kind.clone(),
+            cwd: pane.cwd.clone(),
+            log: analysis.as_ref().and_then(|a| a.log_path.clone()),
+        })
+        .collect();
+    state.tmux_server.observe_panes(state, &identities);
+    let rekeys = session_identity::rekeys(
+        &state.sessions.read(),
+        &scanned_panes(&panes, &analyses),
+        &ids,
+    );
+    session_identity::rekey(state, rekeys);
+    let analyses = take_new_session_baselines(state, &panes, &ids, analyses).await;
+    apply_scan(state, &panes, &ids, analyses);
+    phases.apply_ms = applying.elapsed().as_millis() as u64;
+    // Claude in VS Code terminals isn't in tmux; find it by process instead
+    let scanning_vscode = Instant::now();
+    let vscode_state = Arc::clone(state);
+    let vscode_table = Arc::clone(&table);
+    if let Err(e) =
+        tokio::task::spawn_blocking(move || vscode::scan(&vscode_state, &vscode_table)).await
+    {
+        log::error!("VS Code terminal scan failed: {}", e);
+    }
+    phases.vscode_ms = scanning_vscode.elapsed().as_millis() as u64;
+    phases.ps_ms = table.read_ms();
+    record_scan_duration(
+        state,
+        started.elapsed(),
+        panes.len(),
+        phases,
+        listed_by_control,
+    );
+}
 
-        let project_name = derive_project_name(pane);
+/// Feed panes from a simulation scenario through the same update path as a real scan
+pub(crate) fn apply_simulated_panes(state: &Arc<AppState>, simulated: &[SimulatedPane]) {
+    let panes: Vec<AgentPane> = simulated
+        .iter()
+        .map(|pane| AgentPane {
+            pane_id: pane.target.clone(),
+            target: pane.target.clone(),
+            cwd: pane.cwd.clone(),
+            pane_title: pane.title.clone(),
+            window_name: String::new(),
+            pane_command: pane.agent_kind.clone(),
+            agent_kind: pane.agent_kind.clone(),
+            dead_status: None,
+            yolo: false,
+        })
+        .collect();
+    let analyses: Vec<Option<PaneAnalysis>> = simulated
+        .iter()
+        .map(|pane| {
+            Some(PaneAnalysis {
+                conv_state: ConversationState {
+                    state: pane.state.clone(),
+                    pending_action: pane.pending_action.clone(),
+                    last_message_time: None,
+                },
+                error_reason: pane.error_reason.clone(),
+                jsonl_activity: Utc::now(),
+                permission_diagnostic: None,
+                git_baseline: None,
+                log_path: None,
+                conversation: None,
+            })
+        })
+        .collect();
+    let ids = session_identity::resolve_pane_ids(
+        &state.sessions.read(),
+        &scanned_panes(&panes, &analyses),
+    );
+    apply_scan(state, &panes, &ids, analyses);
+}
 
-        // Check if this session was recently updated by a hook — if so, don't override
-        let hook_protected = {
-            let timestamps = state.hook_timestamps.read();
-            timestamps
-                .get(&session_id)
-                .map(|t| t.elapsed().as_secs() < crate::HOOK_GRACE_PERIOD_SECS)
-                .unwrap_or(false)
-        };
+fn scanned_panes<'a>(
+    panes: &'a [AgentPane],
+    analyses: &'a [Option<PaneAnalysis>],
+) -> Vec<ScannedPane<'a>> {
+    panes
+        .iter()
+        .zip(analyses)
+        .map(|(pane, analysis)| ScannedPane {
+            pane_id: &pane.pane_id,
+            target: &pane.target,
+            conversation: analysis.as_ref().and_then(|a| a.conversation.as_deref()),
+        })
+        .collect()
+}
 
-        let mut sessions = state.sessions.write();
-        let existing = sessions.get(&session_id);
-
-        if hook_protected && existing.is_some() {
-            // Hook recently set this state — only update non-state fields (path, name, etc.)
-            if let Some(session) = sessions.get_mut(&session_id) {
-                session.project_path = Some(pane.cwd.clone());
-                session.tmux_target = Some(pane.target.clone());
-                session.agent_kind = Some(pane.agent_kind.clone());
-                // Don't touch state, pending_action, or last_activity
+/// Panes about to get a session take over metadata saved under their target
+/// before sessions were keyed by conversation, then get a git baseline
+async fn take_new_session_baselines(
+    state: &Arc<AppState>,
+    panes: &[AgentPane],
+    ids: &[String],
+    mut analyses: Vec<Option<PaneAnalysis>>,
+) -> Vec<Option<PaneAnalysis>> {
+    let new: Vec<(usize, String, String, String)> = {
+        let sessions = state.sessions.read();
+        panes
+            .iter()
+            .zip(ids)
+            .enumerate()
+            .filter(|(i, (_, id))| analyses[*i].is_some() && !sessions.contains_key(*id))
+            .map(|(i, (pane, id))| (i, id.clone(), pane.target.clone(), pane.cwd.clone()))
+            .collect()
+    };
+    if new.is_empty() {
+        return analyses;
+    }
+    let baselines = tokio::task::spawn_blocking(move || {
+        new.into_iter()
+            .map(|(i, id, target, cwd)| {
+                session_identity::adopt_target_meta(&id, &target);
+                (i, git::baseline_for_new_session(&id, &cwd))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await;
+    match baselines {
+        Ok(baselines) => {
+            for (i, baseline) in baselines {
+                if let Some(analysis) = analyses[i].as_mut() {
+                    analysis.git_baseline = baseline;
+                }
             }
-            drop(sessions);
+        }
+        Err(e) => log::error!("Taking git baselines failed: {}", e),
+    }
+    analyses
+}
+
+/// Write scan results into the session map and drop sessions whose pane is gone.
+/// `ids[i]` and `analyses[i]` belong to `panes[i]`; `None` means that pane's
+/// analysis failed.
+fn apply_scan(
+    state: &Arc<AppState>,
+    panes: &[AgentPane],
+    ids: &[String],
+    analyses: Vec<Option<PaneAnalysis>>,
+) {
+    for ((pane, session_id), analysis) in panes.iter().zip(ids).zip(analyses) {
+        let Some(analysis) = analysis else {
+            continue;
+        };
+        let PaneAnalysis {
+            conv_state,
+            error_reason,
+            jsonl_activity,
+            permission_diagnostic,
+            git_baseline,
+            ..
+        } = analysis;
+        if state
+            .archive
+            .suppresses(session_id, &conv_state.state, conv_state.last_message_time)
+        {
             continue;
         }
+        let project_name = derive_project_name(pane);
 
-        // Use the JSONL message timestamp for last_activity when available,
-        // fall back to JSONL file modification time, then Utc::now() as last resort
-        let jsonl_activity = conv_state.last_message_time.unwrap_or_else(|| {
-            let jsonl = if pane.agent_kind == "codex" {
-                find_active_codex_jsonl(&pane.cwd)
-            } else if pane.agent_kind == "omp" {
-                find_active_omp_jsonl(&pane.cwd)
-            } else {
-                let project_dir = cwd_to_project_dir(&pane.cwd);
-                find_active_jsonl(&project_dir)
-            };
-            jsonl
-                .and_then(|p| fs::metadata(&p).ok())
-                .and_then(|m| m.modified().ok())
-                .and_then(|t| {
-                    let duration = t.duration_since(SystemTime::UNIX_EPOCH).ok()?;
-                    DateTime::from_timestamp(duration.as_secs() as i64, duration.subsec_nanos())
-                })
-                .unwrap_or_else(Utc::now)
-        });
+        // Check if a hook outranks this scan — if so, don't override
+        let hook_protected = state.hook_protected(session_id, conv_state.last_message_time);
 
-        let (changed, last_activity) = match existing {
-            Some(prev) if prev.state == conv_state.state => {
-                if conv_state.state == SessionState::Complete {
-                    // Complete sessions: freeze last_activity so they don't re-sort
-                    (false, prev.last_activity)
-                } else {
-                    // Active sessions: update from JSONL so sorting reflects recency
-                    (false, jsonl_activity)
+        let mut sessions = state.sessions.write();
+        let existing = sessions.get(session_id);
+        let previous_state = existing.map(|prev| prev.state.clone());
+
+        let effects = match state_machine::decide(
+            previous_state.as_ref(),
+            &conv_state.state,
+            StateSource::Scanner,
+            hook_protected,
+        ) {
+            Decision::Apply(effects) => effects,
+            Decision::Skip(_) => {
+                // Hook recently set this state — only update non-state fields (path, name, etc.)
+                if let Some(session) = sessions.get_mut(session_id) {
+                    session.project_path = Some(pane.cwd.clone());
+                    session.tmux_target = Some(pane.target.clone());
+                    session.tmux_pane_id = Some(pane.pane_id.clone());
+                    session.agent_kind = Some(pane.agent_kind.clone());
+                    session.yolo |= pane.yolo;
+                    // Don't touch state, pending_action, or last_activity
                 }
+                drop(sessions);
+                continue;
             }
-            Some(_) => {
-                // State changed
-                (true, jsonl_activity)
-            }
-            None => {
-                // New session
-                (true, jsonl_activity)
+        };
+        let changed = effects.emit;
+
+        let last_activity = match existing {
+            // Complete sessions: freeze last_activity so they don't re-sort
+            Some(prev) if !changed && conv_state.state == SessionState::Complete => {
+                prev.last_activity
             }
+            // Active sessions: update from JSONL so sorting reflects recency
+            _ => jsonl_activity,
         };
 
-        if changed
-            && pane.agent_kind == "codex"
-            && conv_state.state == SessionState::AwaitingPermission
-        {
-            let (reason, tool_name) = codex_jsonl_for_debug
-                .as_ref()
-                .map(|(jsonl, age)| codex_permission_diagnostic(jsonl, *age))
-                .unwrap_or_else(|| {
-                    (
-                        "codex scanner classified permission without matching JSONL".to_string(),
-                        None,
-                    )
-                });
+        if let Some((reason, tool_name)) = permission_diagnostic.filter(|_| changed) {
             state.log_state_diagnostic(StateDiagnostic {
                 timestamp: Utc::now().format("%H:%M:%S%.3f").to_string(),
                 source: "tmux-jsonl".to_string(),
@@ -1633,6 +1792,7 @@ pub fn scan_tmux(state: &Arc<AppState>, app_handle: &AppHandle) {
             });
         }
 
+        let stalled = connectivity::stalls(state, &conv_state.state);
         let session = C3Session {
             id: session_id.clone(),
             project_name,
@@ -1640,43 +1800,68 @@ pub fn scan_tmux(state: &Arc<AppState>, app_handle: &AppHandle) {
             agent_kind: Some(pane.agent_kind.clone()),
             state: conv_state.state,
             tmux_target: Some(pane.target.clone()),
+            tmux_pane_id: Some(pane.pane_id.clone()),
             terminal_tty: None,
             last_activity,
-            pending_action: conv_state.pending_action,
-            metrics: None,
+            pending_action: conv_state
+                .pending_action
+                .map(|action| state.redactor.read().redact_action(action)),
+            metrics: existing.and_then(|prev| prev.metrics.clone()),
+            error_reason,
+            git_baseline: existing
+                .and_then(|prev| prev.git_baseline.clone())
+                .or(git_baseline),
+            pull_request: existing.and_then(|prev| prev.pull_request.clone()),
+            summary: existing.and_then(|prev| prev.summary.clone()),
+            yolo: pane.yolo || existing.is_some_and(|prev| prev.yolo),
+            stalled,
         };
 
         if changed {
             log::info!(
-                "{} ({}) → {:?}",
+                "{} ({}) → {:?}{}",
                 pane.target,
                 session.project_name,
-                session.state
+                session.state,
+                session
+                    .error_reason
+                    .as_deref()
+                    .map(|reason| format!(" ({})", reason))
+                    .unwrap_or_default()
             );
         }
 
         sessions.insert(session_id.clone(), session.clone());
         drop(sessions);
 
-        if changed {
-            let _ = app_handle.emit("session-update", session);
+        if effects.timeline {
+            state.timeline.record(
+                session_id,
+                previous_state,
+                session.state.clone(),
+                StateSource::Scanner,
+            );
+        }
+        if effects.emit {
+            state.events.session_updated(session);
         }
     }
 
+    update_stop_requests(state, panes);
+    let live: HashSet<&str> = ids.iter().map(String::as_str).collect();
+    state.archive.retain_panes(|id| live.contains(id));
+
     // Remove sessions for panes that no longer exist
     let mut sessions = state.sessions.write();
-    let tmux_ids: Vec<String> = sessions
+    let gone: Vec<String> = sessions
         .keys()
-        .filter(|id| id.starts_with("tmux:"))
+        .filter(|id| session_identity::is_pane_session(id) && !live.contains(id.as_str()))
         .cloned()
         .collect();
 
-    for id in tmux_ids {
-        let target = id.strip_prefix("tmux:").unwrap_or("");
-        if !found_targets.contains(target) {
-            sessions.remove(&id);
-            let _ = app_handle.emit("session-removed", id);
-        }
+    for id in gone {
+        sessions.remove(&id);
+        state.events.session_removed(id);
     }
 
     let orphan_hook_ids: Vec<String> = sessions
@@ -1687,22 +1872,205 @@ pub fn scan_tmux(state: &Arc<AppState>, app_handle: &AppHandle) {
 
     for id in orphan_hook_ids {
         sessions.remove(&id);
-        let _ = app_handle.emit("session-removed", id);
+        state.events.session_removed(id);
+    }
+}
+
+/// State from an agent's transcript alone, for sessions with no pane to read
+fn transcript_state(
+    tails: &JsonlTails,
+    agent_kind: &str,
+    jsonl: &Path,
+) -> Option<ConversationState> {
+    match agent_kind {
+        "claude" => Some(detect_state_from_jsonl(tails, jsonl)),
+        "codex" => Some(detect_state_from_codex_jsonl(tails, jsonl)),
+        "omp" => Some(detect_state_from_omp_jsonl(tails, jsonl)),
+        _ => None,
+    }
+}
+
+/// Drop a session's hook grace period and cached transcript tail, then work
+/// its state out again: with a scan for tmux sessions, from the transcript
+/// for the rest. For sessions hook suppression left in the wrong state.
+pub(crate) async fn resync_session(
+    state: &Arc<AppState>,
+    session_id: &str,
+) -> Result<C3Session, String> {
+    let session = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    state.hook_timestamps.write().remove(session_id);
+    state.stop_timestamps.write().remove(session_id);
+    let agent_kind = session.agent_kind.clone().unwrap_or_default();
+    let jsonl = session
+        .project_path
+        .as_deref()
+        .and_then(|cwd| find_agent_jsonl(&agent_kind, cwd));
+    if let Some(jsonl) = &jsonl {
+        state.jsonl_tails.reset(jsonl);
+    }
+
+    if session.tmux_target.is_some() {
+        scan_tmux(state).await;
+    } else if let Some(conv_state) = jsonl
+        .as_deref()
+        .and_then(|jsonl| transcript_state(&state.jsonl_tails, &agent_kind, jsonl))
+    {
+        let mut sessions = state.sessions.write();
+        let Some(current) = sessions.get_mut(session_id) else {
+            return Err(format!("Session not found: {}", session_id));
+        };
+        let previous_state = current.state.clone();
+        let Decision::Apply(effects) = state_machine::decide(
+            Some(&previous_state),
+            &conv_state.state,
+            StateSource::Scanner,
+            false,
+        ) else {
+            return Ok(current.clone());
+        };
+        current.state = conv_state.state;
+        current.pending_action = conv_state
+            .pending_action
+            .map(|action| state.redactor.read().redact_action(action));
+        if let Some(last_message_time) = conv_state.last_message_time {
+            current.last_activity = last_message_time;
+        }
+        let current = current.clone();
+        drop(sessions);
+        if effects.timeline {
+            state.timeline.record(
+                session_id,
+                Some(previous_state),
+                current.state.clone(),
+                StateSource::Scanner,
+            );
+        }
+        if effects.emit {
+            state.events.session_updated(current);
+        }
+    }
+
+    let resynced = state
+        .sessions
+        .read()
+        .get(session_id)
+        .cloned()
+        .ok_or_else(|| format!("{} went away while resyncing", session.project_name))?;
+    log::info!(
+        "Resynced {} ({}): {:?} -> {:?}",
+        session_id,
+        session.project_name,
+        session.state,
+        resynced.state
+    );
+    Ok(resynced)
+}
+
+fn record_scan_duration(
+    state: &AppState,
+    elapsed: Duration,
+    pane_count: usize,
+    phases: ScanPhases,
+    listed_by_control: bool,
+) {
+    let duration_ms = elapsed.as_millis() as u64;
+    let (cached, read) = state.child_cache.take_counts();
+    let mut stats = state.scan_stats.write();
+    stats.cycles += 1;
+    stats.last_duration_ms = duration_ms;
+    stats.max_duration_ms = stats.max_duration_ms.max(duration_ms);
+    stats.last_pane_count = pane_count;
+    stats.last_children_cached = cached;
+    stats.last_children_read = read;
+    stats.last_listed_by_control = listed_by_control;
+    if duration_ms > SLOW_SCAN_MS {
+        stats.slow_cycles += 1;
+        log::warn!(
+            "Slow tmux scan: {}ms for {} panes ({:?})",
+            duration_ms,
+            pane_count,
+            phases
+        );
+    }
+    stats.last_phases = phases;
+}
+
+/// How long a stop request may wait for the agent to exit before it is dropped
+const STOP_REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// Confirm stop requests whose pane is back at a shell, and forget requests whose
+/// pane disappeared, started a new agent, or never stopped.
+fn update_stop_requests(state: &Arc<AppState>, panes: &[AgentPane]) {
+    let mut requests = state.stop_requests.write();
+    requests.retain(|target, request| {
+        let pane = match panes.iter().find(|p| &p.target == target) {
+            Some(pane) => pane,
+            None => return false,
+        };
+        if is_shell_command(&pane.pane_command) {
+            if !request.confirmed {
+                log::info!("{} agent exited after stop request", target);
+            }
+            request.confirmed = true;
+            return true;
+        }
+        if request.confirmed {
+            // A new agent was started in the pane — track it normally again
+            return false;
+        }
+        if request.requested_at.elapsed().as_secs() >= STOP_REQUEST_TIMEOUT_SECS {
+            log::warn!(
+                "{} agent still running {}s after stop request",
+                target,
+                STOP_REQUEST_TIMEOUT_SECS
+            );
+            return false;
+        }
+        true
+    });
+}
+
+/// Seconds between scans, longer while saving power
+fn scan_interval_secs(interval_secs: u64, saving_power: bool) -> u64 {
+    if saving_power {
+        interval_secs * SAVING_POWER_SCAN_FACTOR
+    } else {
+        interval_secs
     }
 }
 
-/// Start the periodic tmux scanner
+/// Start the periodic tmux scanner. Control mode (`tmux_control`) wakes it
+/// early when panes change.
 pub async fn start_tmux_scanner(
     state: Arc<AppState>,
-    app_handle: AppHandle,
     mut shutdown: tokio::sync::watch::Receiver<bool>,
 ) {
-    log::info!("Starting tmux scanner (polling every 3s)");
+    log::info!(
+        "Starting tmux scanner (polling every {}s)",
+        state.timings.read().scan_interval_secs
+    );
 
     loop {
-        scan_tmux(&state, &app_handle);
+        scan_tmux(&state).await;
+        // Read each cycle so a changed setting applies without a restart
+        let timings = *state.timings.read();
+        let saving_power = timings.battery_saver && power_source::saving_power();
+        let interval_secs = scan_interval_secs(timings.scan_interval_secs, saving_power);
+        if state.saving_power.swap(saving_power, Ordering::Relaxed) != saving_power {
+            log::info!(
+                "Saving power: {}, scanning every {}s",
+                saving_power,
+                interval_secs
+            );
+        }
         tokio::select! {
-            _ = tokio::time::sleep(tokio::time::Duration::from_secs(3)) => {}
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)) => {}
+            _ = state.scan_now.notified() => {}
             _ = shutdown.changed() => {
                 log::info!("Tmux scanner shutting down");
                 break;
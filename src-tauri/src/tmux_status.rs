@@ -0,0 +1,431 @@
+use crate::{cmd, load_settings, AppState, C3Session, SessionState};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// How often tmux is brought in line with session states
+const INTERVAL_SECS: u64 = 2;
+/// Pane option holding the style C3 gave a waiting pane, so marks left behind
+/// by a crash are found and cleared on the next start
+const PANE_MARK: &str = "@c3_attention";
+/// Window option doing the same for the window's status-line entry
+const WINDOW_MARK: &str = "@c3_attention_window";
+/// Window option holding a window's name from before C3 renamed it
+const ORIGINAL_NAME: &str = "@c3_window_name";
+
+/// A tmux pane and the marks C3 has put on it and its window
+#[derive(Debug, Clone, PartialEq)]
+struct Pane {
+    id: String,
+    target: String,
+    window_id: String,
+    /// Style C3 marked the pane with; empty when unmarked
+    mark: String,
+    window_mark: String,
+    window_name: String,
+    /// Name the window had before C3 renamed it; empty when not renamed
+    original_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    MarkPane(String),
+    ClearPane(String),
+    MarkWindow(String),
+    ClearWindow(String),
+    /// Rename a window, saving its name first when `original` is given
+    NameWindow {
+        id: String,
+        name: String,
+        original: Option<String>,
+    },
+    RestoreWindow {
+        id: String,
+        name: String,
+    },
+}
+
+fn list_panes() -> Option<Vec<Pane>> {
+    let format = format!(
+        "#{{pane_id}}\t#{{session_name}}:#{{window_index}}.#{{pane_index}}\t#{{window_id}}\t#{{{}}}\t#{{{}}}\t#{{{}}}\t#{{window_name}}",
+        PANE_MARK, WINDOW_MARK, ORIGINAL_NAME
+    );
+    let output = cmd("tmux")
+        .args(["list-panes", "-a", "-F", &format])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                // The name goes last, since it may itself contain tabs
+                let mut fields = line.splitn(7, '\t');
+                Some(Pane {
+                    id: fields.next()?.to_string(),
+                    target: fields.next()?.to_string(),
+                    window_id: fields.next()?.to_string(),
+                    mark: fields.next()?.to_string(),
+                    window_mark: fields.next()?.to_string(),
+                    original_name: fields.next()?.to_string(),
+                    window_name: fields.next()?.to_string(),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Sessions with the pane they run in
+fn located<'a>(sessions: &'a [C3Session], panes: &'a [Pane]) -> Vec<(&'a C3Session, &'a Pane)> {
+    sessions
+        .iter()
+        .filter_map(|session| {
+            let pane = panes.iter().find(|pane| {
+                session.tmux_pane_id.as_deref() == Some(pane.id.as_str())
+                    || session.tmux_target.as_deref() == Some(pane.target.as_str())
+            })?;
+            Some((session, pane))
+        })
+        .collect()
+}
+
+fn is_waiting(state: &SessionState) -> bool {
+    matches!(
+        state,
+        SessionState::AwaitingInput | SessionState::AwaitingPermission
+    )
+}
+
+/// Status prefix for a window name, and how urgent the state is: a window
+/// with several sessions shows the most urgent
+fn status(state: &SessionState) -> (&'static str, u8) {
+    match state {
+        SessionState::AwaitingPermission => ("🔐", 5),
+        SessionState::AwaitingInput => ("💬", 4),
+        SessionState::Error => ("❌", 3),
+        SessionState::Spawning | SessionState::Processing => ("⏳", 2),
+        SessionState::Complete => ("✅", 1),
+    }
+}
+
+/// Window names reflecting the state of the sessions in each window, e.g.
+/// `⏳ api-server`
+fn window_labels<'a>(located: &[(&C3Session, &'a Pane)]) -> HashMap<&'a str, String> {
+    let mut urgent: HashMap<&str, (&C3Session, u8)> = HashMap::new();
+    for (session, pane) in located {
+        let rank = status(&session.state).1;
+        let entry = urgent.entry(&pane.window_id).or_insert((session, rank));
+        if rank > entry.1 {
+            *entry = (session, rank);
+        }
+    }
+    urgent
+        .into_iter()
+        .map(|(window_id, (session, _))| {
+            let (prefix, _) = status(&session.state);
+            (window_id, format!("{} {}", prefix, session.project_name))
+        })
+        .collect()
+}
+
+/// What to change so that exactly the `waiting` panes, and their windows,
+/// carry `style`. With no style every mark is cleared.
+fn plan_marks(panes: &[Pane], waiting: &HashSet<&str>, style: Option<&str>) -> Vec<Change> {
+    let style = style.unwrap_or_default();
+    let mut changes = Vec::new();
+    let mut windows: HashMap<&str, (&str, bool)> = HashMap::new();
+    for pane in panes {
+        let wanted = !style.is_empty() && waiting.contains(pane.id.as_str());
+        if wanted && pane.mark != style {
+            changes.push(Change::MarkPane(pane.id.clone()));
+        } else if !wanted && !pane.mark.is_empty() {
+            changes.push(Change::ClearPane(pane.id.clone()));
+        }
+        let window = windows
+            .entry(&pane.window_id)
+            .or_insert((&pane.window_mark, false));
+        window.1 |= wanted;
+    }
+    let mut windows: Vec<_> = windows.into_iter().collect();
+    windows.sort();
+    for (window_id, (mark, wanted)) in windows {
+        if wanted && mark != style {
+            changes.push(Change::MarkWindow(window_id.to_string()));
+        } else if !wanted && !mark.is_empty() {
+            changes.push(Change::ClearWindow(window_id.to_string()));
+        }
+    }
+    changes
+}
+
+/// What to change so that windows carry their `labels`, and windows without
+/// one (or all of them, when `labels` is empty) get their own names back
+fn plan_names(panes: &[Pane], labels: &HashMap<&str, String>) -> Vec<Change> {
+    let mut seen = HashSet::new();
+    let mut changes = Vec::new();
+    for pane in panes {
+        if !seen.insert(pane.window_id.as_str()) {
+            continue;
+        }
+        match labels.get(pane.window_id.as_str()) {
+            Some(label) if *label != pane.window_name || pane.original_name.is_empty() => changes
+                .push(Change::NameWindow {
+                    id: pane.window_id.clone(),
+                    name: label.clone(),
+                    original: pane
+                        .original_name
+                        .is_empty()
+                        .then(|| pane.window_name.clone()),
+                }),
+            None if !pane.original_name.is_empty() => changes.push(Change::RestoreWindow {
+                id: pane.window_id.clone(),
+                name: pane.original_name.clone(),
+            }),
+            _ => {}
+        }
+    }
+    changes
+}
+
+/// Run tmux commands in one call, `;`-separated as tmux takes them
+fn run_tmux(commands: &[&[&str]], change: &Change) {
+    let args: Vec<&str> = commands.join(&";");
+    match cmd("tmux").args(&args).output() {
+        Ok(output) if !output.status.success() => log::debug!(
+            "tmux {:?} failed: {}",
+            change,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Err(e) => log::debug!("tmux {:?} failed: {}", change, e),
+        Ok(_) => {}
+    }
+}
+
+fn apply(change: &Change, style: &str) {
+    match change {
+        // The style, then the mark recording it
+        Change::MarkPane(id) => run_tmux(
+            &[
+                &["set-option", "-p", "-t", id, "window-style", style],
+                &["set-option", "-p", "-t", id, PANE_MARK, style],
+            ],
+            change,
+        ),
+        Change::ClearPane(id) => run_tmux(
+            &[
+                &["set-option", "-p", "-u", "-t", id, "window-style"],
+                &["set-option", "-p", "-u", "-t", id, PANE_MARK],
+            ],
+            change,
+        ),
+        Change::MarkWindow(id) => run_tmux(
+            &[
+                &["set-option", "-w", "-t", id, "window-status-style", style],
+                &["set-option", "-w", "-t", id, WINDOW_MARK, style],
+            ],
+            change,
+        ),
+        Change::ClearWindow(id) => run_tmux(
+            &[
+                &["set-option", "-w", "-u", "-t", id, "window-status-style"],
+                &["set-option", "-w", "-u", "-t", id, WINDOW_MARK],
+            ],
+            change,
+        ),
+        Change::NameWindow { id, name, original } => {
+            // Saved before renaming, so a failed rename can't lose it
+            if let Some(original) = original {
+                run_tmux(
+                    &[&["set-option", "-w", "-t", id, ORIGINAL_NAME, original]],
+                    change,
+                );
+            }
+            run_tmux(&[&["rename-window", "-t", id, name]], change);
+        }
+        // rename-window turns automatic-rename off for the window
+        Change::RestoreWindow { id, name } => run_tmux(
+            &[
+                &["rename-window", "-t", id, name],
+                &["set-option", "-w", "-u", "-t", id, "automatic-rename"],
+                &["set-option", "-w", "-u", "-t", id, ORIGINAL_NAME],
+            ],
+            change,
+        ),
+    }
+}
+
+/// What tmux should reflect; all off clears everything C3 changed
+#[derive(Default)]
+struct Options {
+    /// Style for panes waiting for the user
+    style: Option<String>,
+    /// Rename windows after their sessions' state
+    names: bool,
+}
+
+impl Options {
+    fn from_settings() -> Self {
+        let settings = load_settings();
+        Self {
+            style: settings
+                .tmux_attention_marker
+                .then_some(settings.tmux_attention_style)
+                .filter(|style| !style.trim().is_empty()),
+            names: settings.tmux_window_names,
+        }
+    }
+
+    fn is_off(&self) -> bool {
+        self.style.is_none() && !self.names
+    }
+}
+
+/// Bring tmux in line with the sessions; nothing to do when it isn't running
+fn update(state: &AppState, options: &Options) {
+    let Some(panes) = list_panes() else {
+        return;
+    };
+    let sessions: Vec<C3Session> = state.sessions.read().values().cloned().collect();
+    let located = located(&sessions, &panes);
+    let waiting: HashSet<&str> = located
+        .iter()
+        .filter(|(session, _)| is_waiting(&session.state))
+        .map(|(_, pane)| pane.id.as_str())
+        .collect();
+    let labels = if options.names {
+        window_labels(&located)
+    } else {
+        HashMap::new()
+    };
+    let style = options.style.as_deref();
+    let changes = plan_marks(&panes, &waiting, style)
+        .into_iter()
+        .chain(plan_names(&panes, &labels));
+    for change in changes {
+        apply(&change, style.unwrap_or_default());
+    }
+}
+
+/// Reflect session state in tmux, as switched on in settings: panes waiting
+/// for the user, and their windows' status-line entries, get the attention
+/// style until the state changes, and windows are renamed after their
+/// sessions' state. Everything is put back on shutdown and when switched off.
+pub async fn start_tmux_status(
+    state: Arc<AppState>,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    // Changes may be left over from a crash, so the first pass always runs
+    let mut cleared = false;
+    loop {
+        let options = Options::from_settings();
+        if !options.is_off() || !cleared {
+            let state_update = state.clone();
+            cleared = options.is_off();
+            let _ = tokio::task::spawn_blocking(move || update(&state_update, &options)).await;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => {
+                if !cleared {
+                    let state_clear = state.clone();
+                    let _ = tokio::task::spawn_blocking(move || {
+                        update(&state_clear, &Options::default())
+                    })
+                    .await;
+                }
+                log::info!("Tmux status shutting down");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane(id: &str, window_id: &str, mark: &str, window_mark: &str) -> Pane {
+        Pane {
+            id: id.to_string(),
+            target: format!("work:{}.0", id.trim_start_matches('%')),
+            window_id: window_id.to_string(),
+            mark: mark.to_string(),
+            window_mark: window_mark.to_string(),
+            window_name: "zsh".to_string(),
+            original_name: String::new(),
+        }
+    }
+
+    #[test]
+    fn only_waiting_panes_stay_marked() {
+        let style = "bg=colour52";
+        let panes = [
+            pane("%1", "@1", "", style),
+            pane("%2", "@1", style, style),
+            pane("%3", "@2", "bg=red", "bg=red"),
+        ];
+        let waiting: HashSet<&str> = ["%1", "%3"].into_iter().collect();
+        assert_eq!(
+            plan_marks(&panes, &waiting, Some(style)),
+            vec![
+                Change::MarkPane("%1".to_string()),
+                Change::ClearPane("%2".to_string()),
+                // Marked with an older style
+                Change::MarkPane("%3".to_string()),
+                Change::MarkWindow("@2".to_string()),
+            ]
+        );
+
+        // Switched off: everything C3 marked is cleared
+        assert_eq!(
+            plan_marks(&panes, &waiting, None),
+            vec![
+                Change::ClearPane("%2".to_string()),
+                Change::ClearPane("%3".to_string()),
+                Change::ClearWindow("@1".to_string()),
+                Change::ClearWindow("@2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn windows_are_named_after_their_state_and_restored() {
+        let mut panes = [
+            pane("%1", "@1", "", ""),
+            pane("%2", "@2", "", ""),
+            pane("%3", "@3", "", ""),
+        ];
+        // Already renamed by C3
+        panes[1].window_name = "✅ blog".to_string();
+        panes[1].original_name = "blog".to_string();
+        panes[2].window_name = "💬 api".to_string();
+        panes[2].original_name = "api".to_string();
+
+        let labels: HashMap<&str, String> = [
+            ("@1", "⏳ api-server".to_string()),
+            ("@2", "✅ blog".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            plan_names(&panes, &labels),
+            vec![
+                Change::NameWindow {
+                    id: "@1".to_string(),
+                    name: "⏳ api-server".to_string(),
+                    original: Some("zsh".to_string()),
+                },
+                // No session left in the window
+                Change::RestoreWindow {
+                    id: "@3".to_string(),
+                    name: "api".to_string(),
+                },
+            ]
+        );
+        assert_eq!(plan_names(&panes, &HashMap::new()).len(), 2);
+
+        assert!(status(&SessionState::AwaitingPermission).1 > status(&SessionState::Processing).1);
+        assert_eq!(status(&SessionState::Complete).0, "✅");
+    }
+}
@@ -0,0 +1,336 @@
+use crate::jsonl::{extract_message_timestamp, is_conversation_message};
+use crate::{tmux_scanner, C3Session};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Who produced a message. Tool results count as user turns, as in the
+/// Anthropic message format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MessagePart {
+    Text(String),
+    ToolCall { name: String, input: Value },
+    ToolResult { output: String, is_error: bool },
+}
+
+/// One conversation turn, normalized across agent log formats
+#[derive(Debug, Clone)]
+pub(crate) struct TranscriptMessage {
+    pub role: Role,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub parts: Vec<MessagePart>,
+}
+
+impl TranscriptMessage {
+    /// Text blocks joined with blank lines (tool calls and results excluded)
+    pub fn text(&self) -> String {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                MessagePart::Text(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+fn tool_result_text(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn parse_claude(parsed: &Value) -> Option<TranscriptMessage> {
+    if !is_conversation_message(parsed) {
+        return None;
+    }
+    let message = parsed.get("message")?;
+    let role = match message.get("role").and_then(|r| r.as_str())? {
+        "assistant" => Role::Assistant,
+        _ => Role::User,
+    };
+    let parts = match message.get("content")? {
+        Value::String(text) => vec![MessagePart::Text(text.clone())],
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(|t| t.as_str())? {
+                "text" => Some(MessagePart::Text(block.get("text")?.as_str()?.to_string())),
+                "tool_use" => Some(MessagePart::ToolCall {
+                    name: block.get("name")?.as_str()?.to_string(),
+                    input: block.get("input").cloned().unwrap_or(Value::Null),
+                }),
+                "tool_result" => Some(MessagePart::ToolResult {
+                    output: tool_result_text(block.get("content")),
+                    is_error: block
+                        .get("is_error")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => return None,
+    };
+    Some(TranscriptMessage {
+        role,
+        timestamp: extract_message_timestamp(parsed),
+        parts,
+    })
+}
+
+/// Codex injects these as user messages; they aren't something the user typed
+fn is_codex_injected_context(text: &str) -> bool {
+    let text = text.trim_start();
+    text.starts_with("<environment_context>") || text.starts_with("<user_instructions>")
+}
+
+fn parse_codex(parsed: &Value) -> Option<TranscriptMessage> {
+    if parsed.get("type").and_then(|t| t.as_str()) != Some("response_item") {
+        return None;
+    }
+    let payload = parsed.get("payload")?;
+    let (role, parts) = match payload.get("type").and_then(|t| t.as_str())? {
+        "message" => {
+            let role = match payload.get("role").and_then(|r| r.as_str())? {
+                "assistant" => Role::Assistant,
+                "user" => Role::User,
+                _ => return None,
+            };
+            let parts: Vec<MessagePart> = payload
+                .get("content")?
+                .as_array()?
+                .iter()
+                .filter_map(|block| block.get("text").and_then(|t| t.as_str()))
+                .filter(|text| !is_codex_injected_context(text))
+                .map(|text| MessagePart::Text(text.to_string()))
+                .collect();
+            (role, parts)
+        }
+        "function_call" | "custom_tool_call" | "local_shell_call" => {
+            let input = payload
+                .get("arguments")
+                .or_else(|| payload.get("input"))
+                .or_else(|| payload.get("action"))
+                .map(|args| match args {
+                    // function_call arguments are a JSON-encoded string
+                    Value::String(s) => serde_json::from_str(s).unwrap_or(args.clone()),
+                    other => other.clone(),
+                })
+                .unwrap_or(Value::Null);
+            let name = payload
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("shell")
+                .to_string();
+            (Role::Assistant, vec![MessagePart::ToolCall { name, input }])
+        }
+        "function_call_output" | "custom_tool_call_output" => {
+            let output = match payload.get("output") {
+                Some(Value::String(s)) => serde_json::from_str::<Value>(s)
+                    .ok()
+                    .and_then(|v| v.get("output").and_then(|o| o.as_str()).map(String::from))
+                    .unwrap_or_else(|| s.clone()),
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            (
+                Role::User,
+                vec![MessagePart::ToolResult {
+                    output,
+                    is_error: false,
+                }],
+            )
+        }
+        _ => return None,
+    };
+    if parts.is_empty() {
+        return None;
+    }
+    Some(TranscriptMessage {
+        role,
+        timestamp: extract_message_timestamp(parsed),
+        parts,
+    })
+}
+
+fn parse_omp(parsed: &Value) -> Option<TranscriptMessage> {
+    if parsed.get("type").and_then(|t| t.as_str()) != Some("message") {
+        return None;
+    }
+    let message = parsed.get("message")?;
+    let role_name = message.get("role").and_then(|r| r.as_str())?;
+    let blocks = message.get("content").and_then(|c| c.as_array());
+    let (role, parts) = match role_name {
+        "user" | "assistant" => {
+            let parts = blocks
+                .into_iter()
+                .flatten()
+                .filter_map(|block| match block.get("type").and_then(|t| t.as_str())? {
+                    "text" => Some(MessagePart::Text(block.get("text")?.as_str()?.to_string())),
+                    "toolCall" => Some(MessagePart::ToolCall {
+                        name: block.get("name")?.as_str()?.to_string(),
+                        input: block.get("arguments").cloned().unwrap_or(Value::Null),
+                    }),
+                    _ => None,
+                })
+                .collect();
+            let role = if role_name == "assistant" {
+                Role::Assistant
+            } else {
+                Role::User
+            };
+            (role, parts)
+        }
+        "toolResult" => (
+            Role::User,
+            vec![MessagePart::ToolResult {
+                output: tool_result_text(message.get("content")),
+                is_error: message
+                    .get("isError")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            }],
+        ),
+        _ => return None,
+    };
+    Some(TranscriptMessage {
+        role,
+        timestamp: extract_message_timestamp(parsed),
+        parts,
+    })
+}
+
+/// Parse one JSONL record; None for metadata, progress and other non-message lines
+pub(crate) fn parse_line(agent_kind: &str, line: &str) -> Option<TranscriptMessage> {
+    let parsed: Value = serde_json::from_str(line).ok()?;
+    match agent_kind {
+        "codex" => parse_codex(&parsed),
+        "omp" => parse_omp(&parsed),
+        _ => parse_claude(&parsed),
+    }
+}
+
+/// Working directory recorded in a transcript's header/messages
+pub(crate) fn transcript_cwd(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .take(20)
+        .filter_map(|line| serde_json::from_str::<Value>(&line).ok())
+        .find_map(|parsed| {
+            parsed
+                .get("cwd")
+                .or_else(|| parsed.get("payload").and_then(|p| p.get("cwd")))
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        })
+}
+
+/// Agent kind and JSONL path of a tracked session's conversation
+pub(crate) fn session_transcript(session: &C3Session) -> Option<(String, PathBuf)> {
+    let cwd = session.project_path.as_deref()?;
+    let agent_kind = session
+        .agent_kind
+        .clone()
+        .unwrap_or_else(|| "claude".to_string());
+    let path = tmux_scanner::find_agent_jsonl(&agent_kind, cwd)?;
+    Some((agent_kind, path))
+}
+
+/// Every message in a transcript file, oldest first
+pub(crate) fn read_transcript(
+    agent_kind: &str,
+    path: &Path,
+) -> Result<Vec<TranscriptMessage>, String> {
+    let file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    Ok(BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| parse_line(agent_kind, &line))
+        .collect())
+}
+
+/// Timestamp of the first message, i.e. roughly when the session started
+pub(crate) fn first_message_timestamp(agent_kind: &str, path: &Path) -> Option<DateTime<Utc>> {
+    let file = fs::File::open(path).ok()?;
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| parse_line(agent_kind, &line))
+        .find_map(|message| message.timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(agent_kind: &str, jsonl: &str) -> Vec<TranscriptMessage> {
+        jsonl
+            .lines()
+            .filter_map(|line| parse_line(agent_kind, line))
+            .collect()
+    }
+
+    #[test]
+    fn claude_turns_with_tool_calls() {
+        let messages = parse_all(
+            "claude",
+            include_str!("../fixtures/jsonl/claude/tool_chain_finished.jsonl"),
+        );
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0].role, Role::User);
+        assert_eq!(messages[0].text(), "Run the tests");
+        assert!(matches!(
+            &messages[1].parts[0],
+            MessagePart::ToolCall { name, input } if name == "Bash" && input["command"] == "cargo test"
+        ));
+        assert!(matches!(
+            &messages[2].parts[0],
+            MessagePart::ToolResult { output, is_error: false } if output.contains("29 passed")
+        ));
+        assert_eq!(messages[3].text(), "All 29 tests pass.");
+        assert!(messages[3].timestamp.is_some());
+    }
+
+    #[test]
+    fn codex_skips_injected_context_and_decodes_arguments() {
+        let messages = parse_all(
+            "codex",
+            include_str!("../fixtures/jsonl/codex/conversation.jsonl"),
+        );
+        assert_eq!(messages.len(), 4);
+        assert_eq!(
+            messages[0].text(),
+            "Write the migration script for the orders table"
+        );
+        assert!(matches!(
+            &messages[1].parts[0],
+            MessagePart::ToolCall { name, input } if name == "shell" && input["command"][0] == "ls"
+        ));
+        assert!(matches!(
+            &messages[2].parts[0],
+            MessagePart::ToolResult { output, .. } if output == "001_init.sql\n"
+        ));
+        assert_eq!(messages[3].role, Role::Assistant);
+    }
+}
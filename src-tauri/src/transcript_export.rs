@@ -0,0 +1,412 @@
+use crate::at_rest;
+use crate::redaction::Redactor;
+use crate::transcript::{self, MessagePart, Role, TranscriptMessage};
+use crate::{load_session_meta, AppState};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use specta::Type;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Tool output longer than this is cut off in exports
+const MAX_TOOL_OUTPUT_CHARS: usize = 4000;
+/// Length of the one-line tool call summary shown while collapsed
+const MAX_TOOL_SUMMARY_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[serde(alias = "md")]
+    Markdown,
+    Html,
+}
+
+struct ExportHeader {
+    title: String,
+    agent_kind: String,
+    project_path: Option<String>,
+    /// The session's note from session-meta.json
+    notes: Option<String>,
+    exported_at: DateTime<Utc>,
+}
+
+/// Consecutive messages from one side of the conversation. Tool results are
+/// folded into the assistant turn that requested them.
+struct Turn<'a> {
+    role: Role,
+    timestamp: Option<DateTime<Utc>>,
+    parts: Vec<&'a MessagePart>,
+}
+
+fn group_turns(messages: &[TranscriptMessage]) -> Vec<Turn<'_>> {
+    let mut turns: Vec<Turn> = Vec::new();
+    for message in messages.iter().filter(|m| !m.parts.is_empty()) {
+        let tool_results_only = message
+            .parts
+            .iter()
+            .all(|p| matches!(p, MessagePart::ToolResult { .. }));
+        match turns.last_mut() {
+            Some(turn) if turn.role == message.role || tool_results_only => {
+                turn.parts.extend(message.parts.iter());
+            }
+            _ => turns.push(Turn {
+                role: message.role,
+                timestamp: message.timestamp,
+                parts: message.parts.iter().collect(),
+            }),
+        }
+    }
+    turns
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// `Bash: cargo test`, `Edit: src/lib.rs`, or just the tool name
+fn tool_summary(name: &str, input: &Value) -> String {
+    let detail = ["command", "file_path", "path", "pattern", "description"]
+        .iter()
+        .find_map(|key| match input.get(key)? {
+            Value::String(s) => Some(s.clone()),
+            // Codex shell commands are argv arrays
+            Value::Array(args) => Some(
+                args.iter()
+                    .filter_map(|a| a.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            _ => None,
+        });
+    match detail {
+        Some(detail) => {
+            let line = detail.lines().next().unwrap_or_default();
+            format!("{}: {}", name, truncate_chars(line, MAX_TOOL_SUMMARY_CHARS))
+        }
+        None => name.to_string(),
+    }
+}
+
+fn tool_output(output: &str) -> String {
+    let output = output.trim_end();
+    if output.chars().count() > MAX_TOOL_OUTPUT_CHARS {
+        format!(
+            "{}\n[output truncated]",
+            truncate_chars(output, MAX_TOOL_OUTPUT_CHARS)
+        )
+    } else {
+        output.to_string()
+    }
+}
+
+fn role_label(role: Role) -> &'static str {
+    match role {
+        Role::User => "User",
+        Role::Assistant => "Assistant",
+    }
+}
+
+fn turn_heading(turn: &Turn) -> String {
+    match turn.timestamp {
+        Some(ts) => format!(
+            "{} · {}",
+            role_label(turn.role),
+            ts.format("%Y-%m-%d %H:%M UTC")
+        ),
+        None => role_label(turn.role).to_string(),
+    }
+}
+
+fn header_byline(header: &ExportHeader) -> String {
+    let mut byline = format!("{} session", header.agent_kind);
+    if let Some(path) = &header.project_path {
+        byline.push_str(&format!(" in {}", path));
+    }
+    byline.push_str(&format!(
+        ", exported {}",
+        header.exported_at.format("%Y-%m-%d %H:%M UTC")
+    ));
+    byline
+}
+
+/// A code fence longer than any backtick run in `content`
+fn fence(content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+fn markdown_details(summary: &str, lang: &str, body: &str) -> String {
+    let fence = fence(body);
+    format!(
+        "<details>\n<summary>{}</summary>\n\n{}{}\n{}\n{}\n\n</details>\n\n",
+        html_escape(summary),
+        fence,
+        lang,
+        body,
+        fence
+    )
+}
+
+fn render_markdown(
+    header: &ExportHeader,
+    messages: &[TranscriptMessage],
+    redactor: &Redactor,
+) -> String {
+    let mut out = format!("# {}\n\n_{}_\n\n", header.title, header_byline(header));
+    if let Some(notes) = &header.notes {
+        for line in redactor.redact(notes).lines() {
+            if line.is_empty() {
+                out.push_str(">\n");
+            } else {
+                out.push_str(&format!("> {}\n", line));
+            }
+        }
+        out.push('\n');
+    }
+    for turn in group_turns(messages) {
+        out.push_str(&format!("---\n\n### {}\n\n", turn_heading(&turn)));
+        for part in &turn.parts {
+            match part {
+                MessagePart::Text(text) => {
+                    out.push_str(redactor.redact(text.trim()).as_ref());
+                    out.push_str("\n\n");
+                }
+                MessagePart::ToolCall { name, input } => {
+                    let summary = tool_summary(name, input);
+                    let input = serde_json::to_string_pretty(input).unwrap_or_default();
+                    out.push_str(&markdown_details(
+                        &redactor.redact(&summary),
+                        "json",
+                        &redactor.redact(&input),
+                    ));
+                }
+                MessagePart::ToolResult { output, is_error } => {
+                    let summary = if *is_error { "Error" } else { "Output" };
+                    out.push_str(&markdown_details(
+                        summary,
+                        "",
+                        &redactor.redact(&tool_output(output)),
+                    ));
+                }
+            }
+        }
+    }
+    out
+}
+
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "body{font:14px/1.5 -apple-system,BlinkMacSystemFont,sans-serif;max-width:860px;margin:2em auto;padding:0 1em;color:#1f2328}\
+.byline{color:#656d76}\
+.notes{white-space:pre-wrap;border-left:3px solid #d0d7de;margin:1em 0;padding:0 1em;color:#656d76}\
+.turn{border-top:1px solid #d0d7de;padding:.5em 0}\
+.turn h3{font-size:13px;margin:.5em 0;color:#656d76}\
+.turn.user h3{color:#0969da}\
+.text{white-space:pre-wrap;margin:.5em 0}\
+details{margin:.5em 0;border:1px solid #d0d7de;border-radius:6px;padding:.25em .75em}\
+details.error{border-color:#cf222e}\
+summary{cursor:pointer;font-family:ui-monospace,monospace;font-size:12px}\
+pre{white-space:pre-wrap;font-size:12px;background:#f6f8fa;padding:.5em;overflow-x:auto}";
+
+fn render_html(
+    header: &ExportHeader,
+    messages: &[TranscriptMessage],
+    redactor: &Redactor,
+) -> String {
+    let title = html_escape(&header.title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<p class=\"byline\">{}</p>\n",
+        title,
+        HTML_STYLE,
+        title,
+        html_escape(&header_byline(header))
+    );
+    if let Some(notes) = &header.notes {
+        out.push_str(&format!(
+            "<blockquote class=\"notes\">{}</blockquote>\n",
+            html_escape(&redactor.redact(notes))
+        ));
+    }
+    for turn in group_turns(messages) {
+        let class = match turn.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        out.push_str(&format!(
+            "<section class=\"turn {}\">\n<h3>{}</h3>\n",
+            class,
+            html_escape(&turn_heading(&turn))
+        ));
+        for part in &turn.parts {
+            match part {
+                MessagePart::Text(text) => out.push_str(&format!(
+                    "<div class=\"text\">{}</div>\n",
+                    html_escape(&redactor.redact(text.trim()))
+                )),
+                MessagePart::ToolCall { name, input } => {
+                    let summary = tool_summary(name, input);
+                    let input = serde_json::to_string_pretty(input).unwrap_or_default();
+                    out.push_str(&format!(
+                        "<details><summary>{}</summary><pre>{}</pre></details>\n",
+                        html_escape(&redactor.redact(&summary)),
+                        html_escape(&redactor.redact(&input))
+                    ));
+                }
+                MessagePart::ToolResult { output, is_error } => {
+                    let (class, summary) = if *is_error {
+                        (" class=\"error\"", "Error")
+                    } else {
+                        ("", "Output")
+                    };
+                    out.push_str(&format!(
+                        "<details{}><summary>{}</summary><pre>{}</pre></details>\n",
+                        class,
+                        summary,
+                        html_escape(&redactor.redact(&tool_output(output)))
+                    ));
+                }
+            }
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+// Tauri command: Write a session's conversation to a Markdown or HTML file
+#[tauri::command]
+#[specta::specta]
+pub async fn export_transcript(
+    state: tauri::State<'_, Arc<AppState>>,
+    session_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<String, String> {
+    if !Path::new(&path).is_absolute() {
+        return Err("Export path must be absolute".to_string());
+    }
+    let session = state
+        .sessions
+        .read()
+        .get(&session_id)
+        .cloned()
+        .or_else(|| state.archive.session(&session_id))
+        .ok_or_else(|| format!("Session not found: {}", session_id))?;
+    let notes = load_session_meta()
+        .sessions
+        .get(&session_id)
+        .and_then(|meta| meta.notes.clone());
+    let state = state.inner().clone();
+
+    tokio::task::spawn_blocking(move || {
+        let (agent_kind, transcript_path) = transcript::session_transcript(&session)
+            .ok_or_else(|| format!("No transcript found for {}", session.project_name))?;
+        let messages = transcript::read_transcript(&agent_kind, &transcript_path)?;
+        let header = ExportHeader {
+            title: session.project_name.clone(),
+            agent_kind,
+            project_path: session.project_path.clone(),
+            notes,
+            exported_at: Utc::now(),
+        };
+        let redactor = state.redactor.read();
+        let rendered = match format {
+            ExportFormat::Markdown => render_markdown(&header, &messages, &redactor),
+            ExportFormat::Html => render_html(&header, &messages, &redactor),
+        };
+        let path = at_rest::sealed_path(&path);
+        at_rest::write(Path::new(&path), rendered.as_bytes())
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+        log::info!(
+            "Exported transcript for {} to {}",
+            session.project_name,
+            path
+        );
+        Ok(path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> ExportHeader {
+        ExportHeader {
+            title: "api".to_string(),
+            agent_kind: "claude".to_string(),
+            project_path: Some("/home/ann/work/api".to_string()),
+            notes: None,
+            exported_at: Utc::now(),
+        }
+    }
+
+    fn fixture_messages() -> Vec<TranscriptMessage> {
+        include_str!("../fixtures/jsonl/claude/tool_chain_finished.jsonl")
+            .lines()
+            .filter_map(|line| transcript::parse_line("claude", line))
+            .collect()
+    }
+
+    #[test]
+    fn markdown_groups_tool_results_into_assistant_turn() {
+        let markdown = render_markdown(&header(), &fixture_messages(), &Redactor::default());
+        assert!(markdown.starts_with("# api\n"));
+        assert_eq!(markdown.matches("### User").count(), 1);
+        assert_eq!(markdown.matches("### Assistant").count(), 1);
+        assert!(markdown.contains("Run the tests"));
+        assert!(markdown.contains("<summary>Bash: cargo test</summary>"));
+        assert!(markdown.contains("<summary>Output</summary>"));
+        assert!(markdown.contains("All 29 tests pass."));
+    }
+
+    #[test]
+    fn html_escapes_message_text() {
+        let messages = vec![TranscriptMessage {
+            role: Role::User,
+            timestamp: None,
+            parts: vec![MessagePart::Text(
+                "Why does <script> & \"x\" fail?".to_string(),
+            )],
+        }];
+        let html = render_html(&header(), &messages, &Redactor::default());
+        assert!(html.contains("Why does &lt;script&gt; &amp; &quot;x&quot; fail?"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn notes_are_included_below_the_byline() {
+        let header = ExportHeader {
+            notes: Some("waiting on design answer\n\nthen ship".to_string()),
+            ..header()
+        };
+        let markdown = render_markdown(&header, &[], &Redactor::default());
+        assert!(markdown.contains("> waiting on design answer\n>\n> then ship\n"));
+        let html = render_html(&header, &[], &Redactor::default());
+        assert!(html.contains("<blockquote class=\"notes\">waiting on design answer"));
+    }
+
+    #[test]
+    fn fences_outgrow_backticks_in_content() {
+        assert_eq!(fence("plain"), "```");
+        assert_eq!(fence("has ``` inside"), "````");
+    }
+}
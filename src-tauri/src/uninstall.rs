@@ -0,0 +1,344 @@
+use crate::plugins::login_item;
+use crate::{load_settings, paths, save_settings, secrets};
+use serde::Serialize;
+use serde_json::Value;
+use specta::Type;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+/// Hook commands and status lines mentioning this are C3's
+const HOOK_SCRIPT: &str = "c3-hook.sh";
+/// What C3 writes to its config directory, removed one by one from a
+/// directory it doesn't own; `*` matches anything
+const CONFIG_ENTRIES: &[&str] = &[
+    "settings.json*",
+    "session-meta.json*",
+    "schedules.json",
+    "api-tokens.json",
+    "icon.png",
+    ".migrated",
+    "active-profile",
+    "profiles",
+    "sound-themes",
+    "events.log*",
+    "at-rest-key.txt",
+    "x-callback-key",
+    "lan-cert.pem",
+    "lan-key.pem",
+];
+/// What C3 writes to its state directory, likewise
+const STATE_ENTRIES: &[&str] = &[
+    "c3.log*",
+    "hook-url",
+    "hook-spool",
+    "machine-id",
+    "digest-sent",
+    "permission-requests.jsonl",
+    "state-transitions.jsonl",
+    "summarizer",
+    "updates",
+    "post-*.json",
+    "email-*.eml",
+];
+
+/// What `uninstall_c3_integration` removed, and what it couldn't
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct UninstallReport {
+    pub removed: Vec<String>,
+    /// Copies of agent settings taken before C3's hooks were stripped
+    pub backups: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+fn is_c3_command(hook: &Value) -> bool {
+    hook.get("command")
+        .and_then(Value::as_str)
+        .is_some_and(|command| command.contains(HOOK_SCRIPT))
+}
+
+/// Remove C3's hooks, and a C3 status line, from an agent's settings,
+/// keeping everything else. Returns whether anything changed.
+fn strip_hooks(settings: &mut Value) -> bool {
+    let mut changed = false;
+    if let Some(hooks) = settings.get_mut("hooks").and_then(Value::as_object_mut) {
+        for groups in hooks.values_mut().filter_map(Value::as_array_mut) {
+            groups.retain_mut(|group| {
+                let Some(list) = group.get_mut("hooks").and_then(Value::as_array_mut) else {
+                    return true;
+                };
+                let before = list.len();
+                list.retain(|hook| !is_c3_command(hook));
+                changed |= list.len() != before;
+                // A group left with no hooks was only there for C3
+                list.len() == before || !list.is_empty()
+            });
+        }
+        if changed {
+            hooks.retain(|_, groups| groups.as_array().is_none_or(|g| !g.is_empty()));
+            if hooks.is_empty() {
+                if let Some(settings) = settings.as_object_mut() {
+                    settings.remove("hooks");
+                }
+            }
+        }
+    }
+    if settings.get("statusLine").is_some_and(is_c3_command) {
+        if let Some(settings) = settings.as_object_mut() {
+            settings.remove("statusLine");
+        }
+        changed = true;
+    }
+    changed
+}
+
+/// Strip C3's hooks from a settings file, backing it up first
+fn strip_file(path: &Path, report: &mut UninstallReport) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let mut settings: Value = match serde_json::from_str(&content) {
+        Ok(settings) => settings,
+        Err(e) => {
+            report.errors.push(format!(
+                "{} isn't valid JSON ({}); left it alone",
+                path.display(),
+                e
+            ));
+            return;
+        }
+    };
+    if !strip_hooks(&mut settings) {
+        return;
+    }
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let backup = path.with_file_name(format!(
+        "{}.backup.{}",
+        file_name,
+        chrono::Utc::now().timestamp()
+    ));
+    if let Err(e) = fs::copy(path, &backup) {
+        report
+            .errors
+            .push(format!("Failed to back up {}: {}", path.display(), e));
+        return;
+    }
+    report.backups.push(backup.display().to_string());
+    let written = serde_json::to_string_pretty(&settings)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(path, json).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => report
+            .removed
+            .push(format!("C3 hooks in {}", path.display())),
+        Err(e) => report
+            .errors
+            .push(format!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+fn remove_file(path: &Path, report: &mut UninstallReport) {
+    if !path.exists() {
+        return;
+    }
+    match fs::remove_file(path) {
+        Ok(()) => report.removed.push(path.display().to_string()),
+        Err(e) => report
+            .errors
+            .push(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+fn matches_entry(name: &str, pattern: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => name == pattern,
+    }
+}
+
+/// Remove a data directory. One C3 doesn't own, like a `C3_CONFIG_DIR`
+/// pointing at a directory of the user's, only loses C3's `entries`, and
+/// goes too if that empties it.
+fn remove_dir(path: &Path, owned: bool, entries: &[&str], report: &mut UninstallReport) {
+    if !path.exists() {
+        return;
+    }
+    if owned {
+        match fs::remove_dir_all(path) {
+            Ok(()) => report.removed.push(path.display().to_string()),
+            Err(e) => report
+                .errors
+                .push(format!("Failed to remove {}: {}", path.display(), e)),
+        }
+        return;
+    }
+    let Ok(dir) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in dir.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !entries.iter().any(|pattern| matches_entry(&name, pattern)) {
+            continue;
+        }
+        let entry_path = entry.path();
+        let removed = if entry_path.is_dir() {
+            fs::remove_dir_all(&entry_path)
+        } else {
+            fs::remove_file(&entry_path)
+        };
+        match removed {
+            Ok(()) => report.removed.push(entry_path.display().to_string()),
+            Err(e) => {
+                report
+                    .errors
+                    .push(format!("Failed to remove {}: {}", entry_path.display(), e))
+            }
+        }
+    }
+    if fs::remove_dir(path).is_ok() {
+        report.removed.push(path.display().to_string());
+    }
+}
+
+// Tauri command: Remove C3's hooks, hook script and login item; with delete_data also its settings and state
+#[tauri::command]
+#[specta::specta]
+pub fn uninstall_c3_integration(app: AppHandle, delete_data: bool) -> UninstallReport {
+    let mut report = UninstallReport::default();
+    let home = PathBuf::from(std::env::var("HOME").unwrap_or_default());
+    if home.as_os_str().is_empty() {
+        report
+            .errors
+            .push("Could not determine HOME directory".to_string());
+        return report;
+    }
+
+    strip_file(&home.join(".claude/settings.json"), &mut report);
+    strip_file(&home.join(".codex/hooks.json"), &mut report);
+    remove_file(
+        &home.join(".omp/agent/hooks/post/c3-notify.ts"),
+        &mut report,
+    );
+    remove_file(&home.join(".local/bin/c3-hook.sh"), &mut report);
+
+    match login_item::apply(&app, false) {
+        Ok(()) => report.removed.push("Login item".to_string()),
+        Err(e) => report.errors.push(e),
+    }
+
+    if delete_data {
+        let (config_dir, state_dir) = (paths::config_dir(), paths::state_dir());
+        let (default_config, default_state) = paths::default_dirs();
+        // The state directory first, as it can be inside the config one
+        let owned = state_dir == default_state;
+        remove_dir(&state_dir, owned, STATE_ENTRIES, &mut report);
+        let owned = config_dir == default_config;
+        remove_dir(&config_dir, owned, CONFIG_ENTRIES, &mut report);
+        match secrets::clear_all() {
+            Ok(0) => {}
+            Ok(_) => report.removed.push("Keychain items".to_string()),
+            Err(e) => report
+                .errors
+                .push(format!("Failed to remove keychain items: {}", e)),
+        }
+    } else {
+        let mut settings = load_settings();
+        if settings.launch_at_login {
+            settings.launch_at_login = false;
+            if let Err(e) = save_settings(&settings) {
+                report.errors.push(e);
+            }
+        }
+    }
+
+    log::info!(
+        "Uninstalled C3 integration (data {}): removed {:?}, errors {:?}",
+        if delete_data { "deleted" } else { "kept" },
+        report.removed,
+        report.errors
+    );
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_c3_hooks_are_stripped() {
+        let mut settings = serde_json::json!({
+            "model": "opus",
+            "statusLine": { "type": "command", "command": "$HOME/.local/bin/c3-hook.sh statusline" },
+            "hooks": {
+                "Stop": [
+                    { "matcher": "", "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh Stop" }] },
+                    { "matcher": "", "hooks": [
+                        { "type": "command", "command": "say done" },
+                        { "type": "command", "command": "$HOME/.local/bin/c3-hook.sh Stop" }
+                    ] }
+                ],
+                "Notification": [
+                    { "matcher": "", "hooks": [{ "type": "command", "command": "C3_AGENT_KIND=claude $HOME/.local/bin/c3-hook.sh Notification" }] }
+                ],
+                "PreToolUse": [
+                    { "matcher": "Bash", "hooks": [{ "type": "command", "command": "./lint.sh" }] }
+                ]
+            }
+        });
+        assert!(strip_hooks(&mut settings));
+        assert_eq!(
+            settings,
+            serde_json::json!({
+                "model": "opus",
+                "hooks": {
+                    "Stop": [
+                        { "matcher": "", "hooks": [{ "type": "command", "command": "say done" }] }
+                    ],
+                    "PreToolUse": [
+                        { "matcher": "Bash", "hooks": [{ "type": "command", "command": "./lint.sh" }] }
+                    ]
+                }
+            })
+        );
+        assert!(!strip_hooks(&mut settings));
+
+        let mut only_c3 = serde_json::json!({
+            "hooks": { "Stop": [{ "matcher": "", "hooks": [{ "type": "command", "command": "c3-hook.sh Stop" }] }] }
+        });
+        assert!(strip_hooks(&mut only_c3));
+        assert_eq!(only_c3, serde_json::json!({}));
+    }
+
+    #[test]
+    fn directories_c3_does_not_own_only_lose_its_files() {
+        let dir = std::env::temp_dir().join(format!("c3-uninstall-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("profiles/work")).unwrap();
+        fs::create_dir_all(dir.join("notes")).unwrap();
+        for name in ["settings.json", "events.log.1", "todo.md", "notes/a.md"] {
+            fs::write(dir.join(name), "x").unwrap();
+        }
+
+        let mut report = UninstallReport::default();
+        remove_dir(&dir, false, CONFIG_ENTRIES, &mut report);
+        let mut left: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        left.sort();
+        assert_eq!(left, vec!["notes", "todo.md"]);
+        assert!(report.errors.is_empty());
+
+        // Emptied, it goes too
+        fs::remove_dir_all(dir.join("notes")).unwrap();
+        fs::remove_file(dir.join("todo.md")).unwrap();
+        fs::write(dir.join("api-tokens.json"), "[]").unwrap();
+        remove_dir(&dir, false, CONFIG_ENTRIES, &mut report);
+        assert!(!dir.exists());
+    }
+}
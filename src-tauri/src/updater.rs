@@ -0,0 +1,302 @@
+use crate::hook_protocol::{self, HOOK_PROTOCOL_VERSION};
+use crate::{cmd, load_settings, paths};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+const RELEASES_API_URL: &str =
+    "https://api.github.com/repos/illustriousdevelopment/c3/releases?per_page=20";
+const CHECK_INTERVAL_SECS: u64 = 4 * 60 * 60;
+/// Release notes line with the hook script version the release bundles
+const RELEASE_HOOK_MARKER: &str = "hook-version:";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    /// Stable releases plus prereleases
+    Beta,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateMode {
+    /// Download new releases in the background and say when they're ready
+    Auto,
+    /// Only point at the release; the user downloads it
+    #[default]
+    Prompt,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub available: bool,
+    pub channel: UpdateChannel,
+    pub release_url: String,
+    pub download_url: Option<String>,
+    /// Downloaded DMG, ready to open
+    pub staged_path: Option<String>,
+    /// None when the hook script isn't installed
+    pub installed_hook_version: Option<u32>,
+    /// Hook script version needed by this build or the new release
+    pub required_hook_version: u32,
+    pub hooks_outdated: bool,
+}
+
+fn parse_version(tag: &str) -> Option<Version> {
+    Version::parse(tag.trim().trim_start_matches(['v', 'V'])).ok()
+}
+
+/// Newest published release on the channel
+fn pick_release(releases: &[Release], channel: UpdateChannel) -> Option<(&Release, Version)> {
+    releases
+        .iter()
+        .filter(|r| !r.draft && (channel == UpdateChannel::Beta || !r.prerelease))
+        .filter_map(|r| parse_version(&r.tag_name).map(|v| (r, v)))
+        .max_by(|a, b| a.1.cmp(&b.1))
+}
+
+fn find_dmg(release: &Release) -> Option<&Asset> {
+    release
+        .assets
+        .iter()
+        .find(|a| a.name.ends_with("_aarch64.dmg"))
+        .or_else(|| release.assets.iter().find(|a| a.name.ends_with(".dmg")))
+}
+
+/// `hook-version: 2` in release notes
+fn release_hook_version(notes: &str) -> Option<u32> {
+    notes.lines().find_map(|line| {
+        let line = line.trim().to_ascii_lowercase();
+        line.strip_prefix(RELEASE_HOOK_MARKER)
+            .and_then(|v| v.trim().parse().ok())
+    })
+}
+
+fn staging_dir() -> PathBuf {
+    paths::state_dir().join("updates")
+}
+
+fn fetch_releases() -> Result<Vec<Release>, String> {
+    let output = cmd("curl")
+        .args(["-fsSL", "--max-time", "20"])
+        .args(["-H", "Accept: application/vnd.github+json"])
+        .args(["-H", "User-Agent: C3"])
+        .arg(RELEASES_API_URL)
+        .output()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "GitHub request failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| format!("Unexpected GitHub reply: {}", e))
+}
+
+/// Download the DMG into the staging dir, unless it's already there
+fn stage(asset: &Asset) -> Result<PathBuf, String> {
+    let dir = staging_dir();
+    let path = dir.join(&asset.name);
+    if path.exists() {
+        return Ok(path);
+    }
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let partial = dir.join(format!("{}.partial", asset.name));
+    let status = cmd("curl")
+        .args(["-fsSL", "--max-time", "600", "-o"])
+        .arg(&partial)
+        .arg(&asset.browser_download_url)
+        .status()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+    if !status.success() {
+        let _ = fs::remove_file(&partial);
+        return Err(format!("Failed to download {}", asset.name));
+    }
+    fs::rename(&partial, &path).map_err(|e| e.to_string())?;
+    log::info!("Staged update {}", path.display());
+    Ok(path)
+}
+
+/// Look for a newer release on the configured channel. With `stage_now`,
+/// a new release is downloaded right away.
+fn check(current: &str, stage_now: bool) -> Result<UpdateInfo, String> {
+    let channel = load_settings().update_channel;
+    let releases = fetch_releases()?;
+    let (release, latest) =
+        pick_release(&releases, channel).ok_or("No published releases found")?;
+    let current_version =
+        parse_version(current).ok_or_else(|| format!("Unparseable app version {:?}", current))?;
+    let available = latest > current_version;
+
+    let dmg = find_dmg(release);
+    let staged = dmg
+        .map(|asset| staging_dir().join(&asset.name))
+        .filter(|path| path.exists());
+    let staged = match (staged, dmg) {
+        (Some(path), _) => Some(path),
+        (None, Some(asset)) if available && stage_now => Some(stage(asset)?),
+        _ => None,
+    };
+
+    let release_hooks = release
+        .body
+        .as_deref()
+        .and_then(release_hook_version)
+        .filter(|_| available);
+    let required_hook_version = release_hooks.unwrap_or(0).max(HOOK_PROTOCOL_VERSION);
+    let installed_hook_version = hook_protocol::installed_hook_version();
+
+    Ok(UpdateInfo {
+        current_version: current.to_string(),
+        latest_version: latest.to_string(),
+        available,
+        channel,
+        release_url: release.html_url.clone(),
+        download_url: dmg.map(|a| a.browser_download_url.clone()),
+        staged_path: staged
+            .filter(|_| available)
+            .map(|p| p.to_string_lossy().to_string()),
+        installed_hook_version,
+        required_hook_version,
+        hooks_outdated: installed_hook_version.is_some_and(|v| v < required_hook_version),
+    })
+}
+
+/// Check on a fixed cadence. Emits `update-available` for new releases and,
+/// in auto mode, `update-staged` once the DMG is downloaded.
+pub async fn start_update_checker(
+    app_handle: AppHandle,
+    mut shutdown: tokio::sync::watch::Receiver<bool>,
+) {
+    let current = app_handle.package_info().version.to_string();
+    let mut announced: Option<String> = None;
+    loop {
+        let auto = load_settings().update_mode == UpdateMode::Auto;
+        let current_check = current.clone();
+        match tokio::task::spawn_blocking(move || check(&current_check, auto)).await {
+            Ok(Ok(info)) if info.available => {
+                let key = format!("{}:{}", info.latest_version, info.staged_path.is_some());
+                if announced.as_deref() != Some(key.as_str()) {
+                    log::info!("Update available: {}", info.latest_version);
+                    let event = if info.staged_path.is_some() {
+                        "update-staged"
+                    } else {
+                        "update-available"
+                    };
+                    let _ = app_handle.emit(event, &info);
+                    announced = Some(key);
+                }
+            }
+            Ok(Ok(_)) => {}
+            Ok(Err(e)) => log::warn!("Update check failed: {}", e),
+            Err(e) => log::warn!("Update check failed: {}", e),
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)) => {}
+            _ = shutdown.changed() => break,
+        }
+    }
+}
+
+// Tauri command: Check for a newer release, staging it in auto mode
+#[tauri::command]
+#[specta::specta]
+pub async fn check_for_updates(app: AppHandle) -> Result<UpdateInfo, String> {
+    let current = app.package_info().version.to_string();
+    let auto = load_settings().update_mode == UpdateMode::Auto;
+    let info = tokio::task::spawn_blocking(move || check(&current, auto))
+        .await
+        .map_err(|e| e.to_string())??;
+    if info.staged_path.is_some() {
+        let _ = app.emit("update-staged", &info);
+    }
+    Ok(info)
+}
+
+// Tauri command: Open the downloaded DMG
+#[tauri::command]
+#[specta::specta]
+pub fn open_staged_update(path: String) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    if path.parent() != Some(staging_dir().as_path()) || !path.exists() {
+        return Err("No staged update at that path".to_string());
+    }
+    cmd("open")
+        .arg(&path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open update: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(tag: &str, prerelease: bool) -> Release {
+        Release {
+            tag_name: tag.to_string(),
+            html_url: format!("https://example.com/{}", tag),
+            draft: false,
+            prerelease,
+            body: None,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn channel_decides_whether_prereleases_count() {
+        let mut draft = release("v0.4.0", false);
+        draft.draft = true;
+        let releases = vec![
+            release("v0.2.12", false),
+            release("v0.3.0-beta.2", true),
+            release("v0.3.0-beta.10", true),
+            release("nightly", true),
+            draft,
+        ];
+        let (stable, _) = pick_release(&releases, UpdateChannel::Stable).unwrap();
+        assert_eq!(stable.tag_name, "v0.2.12");
+        let (beta, version) = pick_release(&releases, UpdateChannel::Beta).unwrap();
+        assert_eq!(beta.tag_name, "v0.3.0-beta.10");
+        // A beta of the installed version's successor is newer; the final release beats its betas
+        assert!(version > parse_version("0.2.12").unwrap());
+        assert!(version < parse_version("v0.3.0").unwrap());
+    }
+
+    #[test]
+    fn hook_version_is_read_from_release_notes() {
+        assert_eq!(
+            release_hook_version("Fixes\n\nHook-Version: 2\n- more"),
+            Some(2)
+        );
+        assert_eq!(release_hook_version("Fixes"), None);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::connectivity;
+use crate::jsonl;
+use crate::process_table::{Process, ProcessTable};
+use crate::state_machine::{self, Decision, StateSource};
+use crate::tmux_scanner::{self, file_age_secs};
+use crate::{cmd, normalize_tty, AppState, C3Session};
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Sessions found here are keyed by the agent's PID, since there's no pane
+pub(crate) const SESSION_PREFIX: &str = "vscode:";
+const VSCODE_APP: &str = "Visual Studio Code";
+
+/// A Claude Code process running in a VS Code integrated terminal
+#[derive(Debug, Clone, PartialEq)]
+struct VscodeClaude {
+    pid: u32,
+    /// e.g. `ttys003`, None when ps shows none
+    tty: Option<String>,
+}
+
+fn program_name(command: &str) -> &str {
+    let program = command.split_whitespace().next().unwrap_or_default();
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+/// `claude`, a versioned install (`~/.local/share/claude/versions/2.1.37`),
+/// or the npm package under node
+fn is_claude(command: &str) -> bool {
+    let program = program_name(command);
+    program == "claude"
+        || command.contains("/claude/versions/")
+        || (program == "node" && command.contains("claude-code"))
+}
+
+/// The VS Code app or its terminal host (`Code Helper`)
+fn is_vscode(command: &str) -> bool {
+    command.contains("Visual Studio Code.app") || command.contains("Code Helper")
+}
+
+fn is_tmux(command: &str) -> bool {
+    program_name(command).starts_with("tmux")
+}
+
+/// Claude processes descended from VS Code and not from tmux (those panes are
+/// the tmux scanner's). A wrapper and its child count once.
+fn find_vscode_claudes(processes: &[Process]) -> Vec<VscodeClaude> {
+    let by_pid: HashMap<u32, &Process> = processes.iter().map(|p| (p.pid, p)).collect();
+    let ancestors = |process: &Process| {
+        let mut chain = Vec::new();
+        let mut ppid = process.ppid;
+        // Bounded in case the table has a cycle from PID reuse
+        while let Some(parent) = by_pid.get(&ppid).filter(|_| chain.len() < 64) {
+            chain.push(*parent);
+            ppid = parent.ppid;
+        }
+        chain
+    };
+
+    processes
+        .iter()
+        .filter(|p| is_claude(&p.command))
+        .filter(|p| {
+            !by_pid
+                .get(&p.ppid)
+                .is_some_and(|parent| is_claude(&parent.command))
+        })
+        .filter(|p| {
+            let chain = ancestors(p);
+            let under_vscode = chain.iter().any(|a| is_vscode(&a.command));
+            under_vscode && !chain.iter().any(|a| is_tmux(&a.command))
+        })
+        .map(|p| VscodeClaude {
+            pid: p.pid,
+            tty: Some(p.tty.clone()).filter(|tty| tty != "??" && tty != "?"),
+        })
+        .collect()
+}
+
+/// Working directory of a process: /proc where there is one, lsof on macOS
+fn process_cwd(pid: u32) -> Option<String> {
+    if let Ok(path) = std::fs::read_link(format!("/proc/{}/cwd", pid)) {
+        return Some(path.to_string_lossy().to_string());
+    }
+    let output = cmd("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix('n').map(str::to_string))
+}
+
+/// Add, update and drop `vscode:` sessions to match the running processes.
+/// Blocking (ps, lsof, file IO) — run on the blocking pool.
+pub(crate) fn scan(state: &Arc<AppState>, table: &ProcessTable) {
+    let found = find_vscode_claudes(table.processes());
+    let mut seen = HashSet::new();
+
+    for claude in found {
+        let session_id = format!("{}{}", SESSION_PREFIX, claude.pid);
+        let (existing, hook_tty_taken) = {
+            let sessions = state.sessions.read();
+            // Hooks from this terminal already made a session for it
+            let hook_tty_taken = claude.tty.as_deref().is_some_and(|tty| {
+                sessions.values().any(|s| {
+                    !s.id.starts_with(SESSION_PREFIX)
+                        && s.terminal_tty.as_deref().map(normalize_tty).as_deref() == Some(tty)
+                })
+            });
+            (sessions.get(&session_id).cloned(), hook_tty_taken)
+        };
+        if hook_tty_taken {
+            continue;
+        }
+        let Some(cwd) = existing
+            .as_ref()
+            .and_then(|s| s.project_path.clone())
+            .or_else(|| process_cwd(claude.pid))
+        else {
+            continue;
+        };
+        {
+            let ignore = state.ignore.read();
+            if ignore.ignores_path(&cwd) || ignore.ignores_agent("claude") {
+                continue;
+            }
+        }
+        seen.insert(session_id.clone());
+
+        let conv_state = match tmux_scanner::find_claude_jsonl(&cwd) {
+            Some(path) => {
+                let lines = state.jsonl_tails.read_last_lines(&path, 30);
+                jsonl::detect_claude_state(&lines, file_age_secs(&path).unwrap_or(0))
+            }
+            None => jsonl::awaiting_input_state(None),
+        };
+        let hook_protected = state.hook_protected(&session_id, conv_state.last_message_time);
+        let previous_state = existing.as_ref().map(|s| s.state.clone());
+        let Decision::Apply(effects) = state_machine::decide(
+            previous_state.as_ref(),
+            &conv_state.state,
+            StateSource::Scanner,
+            hook_protected,
+        ) else {
+            continue;
+        };
+
+        let stalled = connectivity::stalls(state, &conv_state.state);
+        let session = C3Session {
+            id: session_id.clone(),
+            project_name: Path::new(&cwd)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| cwd.clone()),
+            project_path: Some(cwd),
+            agent_kind: Some("claude".to_string()),
+            state: conv_state.state,
+            tmux_target: None,
+            tmux_pane_id: None,
+            terminal_tty: claude.tty,
+            last_activity: conv_state
+                .last_message_time
+                .or(existing.as_ref().map(|s| s.last_activity))
+                .unwrap_or_else(Utc::now),
+            pending_action: conv_state
+                .pending_action
+                .map(|action| state.redactor.read().redact_action(action)),
+            metrics: existing.as_ref().and_then(|s| s.metrics.clone()),
+            error_reason: None,
+            git_baseline: existing.as_ref().and_then(|s| s.git_baseline.clone()),
+            pull_request: existing.as_ref().and_then(|s| s.pull_request.clone()),
+            summary: existing.as_ref().and_then(|s| s.summary.clone()),
+            yolo: existing.as_ref().is_some_and(|s| s.yolo),
+            stalled,
+        };
+        state
+            .sessions
+            .write()
+            .insert(session_id.clone(), session.clone());
+        if effects.timeline {
+            state.timeline.record(
+                &session_id,
+                previous_state,
+                session.state.clone(),
+                StateSource::Scanner,
+            );
+        }
+        if effects.emit {
+            state.events.session_updated(session);
+        }
+    }
+
+    // The process exited (or moved to a hook session)
+    let mut sessions = state.sessions.write();
+    let gone: Vec<String> = sessions
+        .keys()
+        .filter(|id| id.starts_with(SESSION_PREFIX) && !seen.contains(*id))
+        .cloned()
+        .collect();
+    for id in gone {
+        sessions.remove(&id);
+        state.events.session_removed(id);
+    }
+}
+
+/// Bring up the VS Code window for the project. `code <folder>` focuses the
+/// window that has it open; without the CLI, just activate the app.
+pub(crate) fn focus(project_path: Option<&str>) -> Result<(), String> {
+    let opened = project_path.is_some_and(|path| {
+        cmd("code")
+            .arg(path)
+            .status()
+            .is_ok_and(|status| status.success())
+    });
+    if !opened {
+        let script = format!("tell application \"{}\" to activate", VSCODE_APP);
+        cmd("osascript")
+            .args(["-e", &script])
+            .output()
+            .map_err(|e| format!("Failed to activate {}: {}", VSCODE_APP, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::process_table::parse_ps;
+
+    #[test]
+    fn claude_under_vscode_but_not_tmux_is_found_once() {
+        let ps = "\
+  100     1 ??       /Applications/Visual Studio Code.app/Contents/MacOS/Electron
+  110   100 ??       /Applications/Visual Studio Code.app/Contents/Frameworks/Code Helper (Plugin).app/Contents/MacOS/Code Helper (Plugin)
+  120   110 ttys003  -zsh
+  130   120 ttys003  claude
+  131   130 ttys003  claude --mcp-server
+  140   110 ttys004  -zsh
+  150   140 ttys004  tmux attach
+  200     1 ??       tmux new-session -d
+  210   200 ttys005  -zsh
+  220   210 ttys005  node /opt/homebrew/lib/node_modules/@anthropic-ai/claude-code/cli.js
+  300     1 ttys006  /Users/ann/.local/share/claude/versions/2.1.37
+  400   110 ttys007  node /Users/ann/.local/share/claude/versions/2.1.37 --resume
+";
+        let found = find_vscode_claudes(&parse_ps(ps));
+        assert_eq!(
+            found,
+            vec![
+                VscodeClaude {
+                    pid: 130,
+                    tty: Some("ttys003".to_string()),
+                },
+                VscodeClaude {
+                    pid: 400,
+                    tty: Some("ttys007".to_string()),
+                },
+            ]
+        );
+    }
+}
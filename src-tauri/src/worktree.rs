@@ -0,0 +1,202 @@
+use crate::git::git;
+use crate::{
+    default_agent_command, load_session_meta, save_session_meta, session_identity, start_agent,
+};
+use serde::Serialize;
+use specta::Type;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeSession {
+    pub path: String,
+    pub branch: String,
+    /// Pane the agent was started in
+    pub tmux_target: String,
+}
+
+/// Branch name as a single path component: `feat/login` → `feat-login`
+fn branch_slug(branch: &str) -> String {
+    branch
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches(|c| c == '-' || c == '.')
+        .to_string()
+}
+
+/// Worktrees sit next to the repo, grouped per repo:
+/// `~/code/api` + `feat/login` → `~/code/api-worktrees/feat-login`
+fn worktree_path(repo_root: &Path, branch: &str) -> Result<PathBuf, String> {
+    let name = repo_root
+        .file_name()
+        .ok_or_else(|| format!("Invalid repository path: {}", repo_root.display()))?;
+    let parent = repo_root
+        .parent()
+        .ok_or_else(|| format!("Invalid repository path: {}", repo_root.display()))?;
+    let slug = branch_slug(branch);
+    if slug.is_empty() {
+        return Err(format!("Invalid branch name: {}", branch));
+    }
+    Ok(parent
+        .join(format!("{}-worktrees", name.to_string_lossy()))
+        .join(slug))
+}
+
+fn add_worktree(repo: &str, branch: &str, base: Option<&str>) -> Result<PathBuf, String> {
+    // git would take these as options
+    if branch.starts_with('-') {
+        return Err(format!("Invalid branch name: {}", branch));
+    }
+    if let Some(base) = base.filter(|base| base.starts_with('-')) {
+        return Err(format!("Invalid base: {}", base));
+    }
+    git(repo, &["check-ref-format", "--branch", branch])
+        .map_err(|_| format!("Invalid branch name: {}", branch))?;
+    let root = git(repo, &["rev-parse", "--show-toplevel"])
+        .map_err(|_| format!("Not a git repository: {}", repo))?;
+    let root = PathBuf::from(root.trim());
+    let path = worktree_path(&root, branch)?;
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+    let path_str = path.to_string_lossy().to_string();
+    let root_str = root.to_string_lossy().to_string();
+
+    let branch_ref = format!("refs/heads/{}", branch);
+    let branch_exists = git(&root_str, &["show-ref", "--verify", "--quiet", &branch_ref]).is_ok();
+    let mut args = vec!["worktree", "add"];
+    if branch_exists {
+        args.extend([path_str.as_str(), branch]);
+    } else {
+        args.extend(["-b", branch, path_str.as_str()]);
+        args.extend(base);
+    }
+    git(&root_str, &args).map_err(|e| format!("git worktree add failed: {}", e))?;
+    log::info!("Created worktree {} on {}", path.display(), branch);
+    Ok(path)
+}
+
+pub(crate) fn remove(path: &str, force: bool) -> Result<(), String> {
+    let git_file = Path::new(path).join(".git");
+    if git_file.is_dir() {
+        return Err("Refusing to remove the main worktree of a repository".to_string());
+    }
+    if !git_file.is_file() {
+        return Err(format!("Not a git worktree: {}", path));
+    }
+    let mut args = vec!["worktree", "remove"];
+    if force {
+        args.push("--force");
+    }
+    args.push(path);
+    git(path, &args).map_err(|e| format!("git worktree remove failed: {}", e))?;
+    log::info!("Removed worktree {}", path);
+    Ok(())
+}
+
+// Tauri command: Create a worktree for a branch and start an agent in a new tmux window there
+#[tauri::command]
+#[specta::specta]
+pub async fn create_worktree(
+    repo: String,
+    branch: String,
+    base: Option<String>,
+    prompt: Option<String>,
+) -> Result<WorktreeSession, String> {
+    tokio::task::spawn_blocking(move || {
+        let path = add_worktree(&repo, branch.trim(), base.as_deref())?;
+        let path = path.to_string_lossy().to_string();
+        let tmux_target = start_agent(&path, default_agent_command(), prompt.as_deref())?;
+        // Kept under the target, which the scanner moves to the pane's session
+        let mut store = load_session_meta();
+        store
+            .sessions
+            .entry(session_identity::tmux_session_id(&tmux_target))
+            .or_default()
+            .worktree = Some(path.clone());
+        if let Err(e) = save_session_meta(&store) {
+            log::warn!("Failed to record worktree {}: {}", path, e);
+        }
+        Ok(WorktreeSession {
+            path,
+            branch: branch.trim().to_string(),
+            tmux_target,
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Tauri command: Remove a worktree created for a parallel agent run
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_worktree(path: String, force: Option<bool>) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || remove(&path, force.unwrap_or(false)))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn worktree_paths_are_siblings_of_the_repo() {
+        assert_eq!(
+            worktree_path(Path::new("/home/ann/code/api"), "feat/login").unwrap(),
+            PathBuf::from("/home/ann/code/api-worktrees/feat-login")
+        );
+        assert_eq!(branch_slug("../escape"), "escape");
+        assert!(worktree_path(Path::new("/home/ann/code/api"), "///").is_err());
+    }
+
+    #[test]
+    fn adds_and_removes_worktrees() {
+        let dir = std::env::temp_dir().join(format!("c3-worktree-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = dir.join("repo");
+        fs::create_dir_all(&repo).unwrap();
+        let repo = repo.to_str().unwrap();
+        git(repo, &["init", "-q"]).unwrap();
+        git(
+            repo,
+            &[
+                "-c",
+                "user.name=c3",
+                "-c",
+                "user.email=c3@example.com",
+                "commit",
+                "-q",
+                "--allow-empty",
+                "-m",
+                "init",
+            ],
+        )
+        .unwrap();
+
+        let path = add_worktree(repo, "feat/login", None).unwrap();
+        assert_eq!(path, dir.join("repo-worktrees").join("feat-login"));
+        assert_eq!(
+            git(path.to_str().unwrap(), &["branch", "--show-current"])
+                .unwrap()
+                .trim(),
+            "feat/login"
+        );
+        assert!(add_worktree(repo, "feat/login", None).is_err());
+        assert!(add_worktree(repo, "--orphan", None).is_err());
+        assert!(add_worktree(repo, "feat/other", Some("--detach")).is_err());
+
+        assert!(remove(repo, false).is_err());
+        remove(path.to_str().unwrap(), false).unwrap();
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,18 +1,22 @@
 import { useEffect, useState, useCallback } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { Header } from './components/Header';
 import { WarRoom } from './components/WarRoom';
 import { ToastContainer } from './components/Toast';
+import { ArchiveNotice } from './components/ArchiveNotice';
+import { SubsystemNotice } from './components/SubsystemNotice';
 import { SearchModal } from './components/SearchModal';
 import { KeyboardHints } from './components/KeyboardHints';
 import { SettingsModal } from './components/SettingsModal';
 import { DebugPanel } from './components/DebugPanel';
+import { DigestModal } from './components/DigestModal';
 import { KillSessionModal } from './components/KillSessionModal';
 import { initializeSessionListeners, useSessionStore } from './stores/sessions';
 import { getVisualSessionOrder } from './types';
 import './App.css';
 
 function App() {
-  const fetchSessions = useSessionStore((state) => state.fetchSessions);
+  const syncSessions = useSessionStore((state) => state.syncSessions);
   const selectNextSession = useSessionStore((state) => state.selectNextSession);
   const selectPrevSession = useSessionStore((state) => state.selectPrevSession);
   const focusSelectedSession = useSessionStore((state) => state.focusSelectedSession);
@@ -29,18 +33,38 @@ function App() {
   const [showKeyboardHints, setShowKeyboardHints] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [showDebug, setShowDebug] = useState(false);
+  const [showDigest, setShowDigest] = useState(false);
 
   useEffect(() => {
     // Initialize event listeners and fetch initial sessions
     initializeSessionListeners();
 
-    // Poll for sessions every 5 seconds as a fallback
+    // Poll for session changes every 5 seconds as a fallback
     const interval = setInterval(() => {
-      fetchSessions();
+      syncSessions();
     }, 5000);
 
-    return () => clearInterval(interval);
-  }, [fetchSessions]);
+    // Resync as soon as the window is visible again (e.g. after sleep)
+    const handleVisibilityChange = () => {
+      if (document.visibilityState === 'visible') {
+        syncSessions();
+      }
+    };
+    document.addEventListener('visibilitychange', handleVisibilityChange);
+
+    return () => {
+      clearInterval(interval);
+      document.removeEventListener('visibilitychange', handleVisibilityChange);
+    };
+  }, [syncSessions]);
+
+  // c3://digest, opened from the weekly digest notification
+  useEffect(() => {
+    const unlisten = listen('show-digest', () => setShowDigest(true));
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
 
   // Global keyboard shortcuts
   const handleKeyDown = useCallback((e: KeyboardEvent) => {
@@ -161,10 +185,13 @@ function App() {
         <WarRoom />
       </main>
       <ToastContainer />
+      <ArchiveNotice />
+      <SubsystemNotice />
       <SearchModal isOpen={isSearchOpen} onClose={() => setIsSearchOpen(false)} />
       <KeyboardHints isOpen={showKeyboardHints} onClose={() => setShowKeyboardHints(false)} />
       <SettingsModal isOpen={showSettings} onClose={() => setShowSettings(false)} />
       <DebugPanel isOpen={showDebug} onClose={() => setShowDebug(false)} />
+      <DigestModal isOpen={showDigest} onClose={() => setShowDigest(false)} />
       <KillSessionModal
         session={pendingKillSessionId ? sessions[pendingKillSessionId] || null : null}
         onCancel={clearKillRequest}
@@ -0,0 +1,1988 @@
+// @ts-nocheck
+// Regenerate with `UPDATE_BINDINGS=1 cargo test bindings`
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async getSessions() : Promise<C3Session[]> {
+    return await TAURI_INVOKE("get_sessions");
+},
+async getSessionsSince(revision: number) : Promise<SessionDelta> {
+    return await TAURI_INVOKE("get_sessions_since", { revision });
+},
+async querySessions(filter: SessionFilter) : Promise<Result<SessionQueryResult, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("query_sessions", { filter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async searchTranscripts(query: string, scope: SearchScope | null) : Promise<Result<TranscriptMatch[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_transcripts", { query, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async exportTranscript(sessionId: string, format: ExportFormat, path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_transcript", { sessionId, format, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionFiles(sessionId: string) : Promise<Result<SessionFiles, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_files", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionDiff(sessionId: string, full: boolean | null) : Promise<Result<SessionDiff, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_diff", { sessionId, full }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openSessionWindow(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_session_window", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionTail(sessionId: string) : Promise<Result<SessionTail, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_session_tail", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async closeSessionWindow(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_session_window", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDownSubsystems() : Promise<SubsystemDown[]> {
+    return await TAURI_INVOKE("get_down_subsystems");
+},
+async checkForUpdates() : Promise<Result<UpdateInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_for_updates") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listAgents() : Promise<AgentInfo[]> {
+    return await TAURI_INVOKE("list_agents");
+},
+async openStagedUpdate(path: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_staged_update", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createWorktree(repo: string, branch: string, base: string | null, prompt: string | null) : Promise<Result<WorktreeSession, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_worktree", { repo, branch, base, prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeWorktree(path: string, force: boolean | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_worktree", { path, force }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getArchivedSessions() : Promise<ArchivedSession[]> {
+    return await TAURI_INVOKE("get_archived_sessions");
+},
+async undoArchive(sessionIds: string[]) : Promise<Result<number, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("undo_archive", { sessionIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getSessionTimeline(sessionId: string) : Promise<TimelineEntry[]> {
+    return await TAURI_INVOKE("get_session_timeline", { sessionId });
+},
+async getNotificationDecisions(sessionId: string) : Promise<NotificationDecision[]> {
+    return await TAURI_INVOKE("get_notification_decisions", { sessionId });
+},
+async tailEvents(count: number) : Promise<Result<JsonValue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("tail_events", { count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getPermissionStats(days: number | null) : Promise<PermissionStats> {
+    return await TAURI_INVOKE("get_permission_stats", { days });
+},
+async exportTimeReport(range: ReportRange, path: string) : Promise<Result<TimeReportRow[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_time_report", { range, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDashboardStats(bucket: StatsBucket, count: number) : Promise<Result<DashboardStats, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_dashboard_stats", { bucket, count }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getWeeklyDigest() : Promise<Result<WeeklyDigest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_weekly_digest") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendWeeklyDigest() : Promise<Result<WeeklyDigest, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_weekly_digest") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testPush(push: PushSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_push", { push }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testTelegram(telegram: TelegramSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_telegram", { telegram }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async testEmail(email: EmailSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("test_email", { email }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listApiTokens() : Promise<ApiToken[]> {
+    return await TAURI_INVOKE("list_api_tokens");
+},
+async createApiToken(name: string, scope: TokenScope) : Promise<Result<CreatedApiToken, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_api_token", { name, scope }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async revokeApiToken(id: string) : Promise<Result<ApiToken[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("revoke_api_token", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLanStatus() : Promise<LanStatus> {
+    return await TAURI_INVOKE("get_lan_status");
+},
+async getAtRestRecipient() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_at_rest_recipient") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getXCallbackKey() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_x_callback_key") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getClaudeAllowlist() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_claude_allowlist") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async addClaudeAllowRule(rule: string) : Promise<Result<AllowlistUpdate, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("add_claude_allow_rule", { rule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeClaudeAllowRule(rule: string) : Promise<Result<AllowlistUpdate, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("remove_claude_allow_rule", { rule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async suggestAllowRule(sessionId: string) : Promise<string | null> {
+    return await TAURI_INVOKE("suggest_allow_rule", { sessionId });
+},
+async alwaysAllowSession(sessionId: string, rule: string) : Promise<Result<AllowlistUpdate, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("always_allow_session", { sessionId, rule }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDebugInfo() : Promise<JsonValue> {
+    return await TAURI_INVOKE("get_debug_info");
+},
+async getProfiles() : Promise<ProfileList> {
+    return await TAURI_INVOKE("get_profiles");
+},
+async createProfile(name: string) : Promise<Result<ProfileList, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async switchProfile(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createDiagnosticBundle() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_diagnostic_bundle") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getDiagnostics() : Promise<Diagnostics> {
+    return await TAURI_INVOKE("get_diagnostics");
+},
+async exportSessionBundle(sessionId: string, path: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_session_bundle", { sessionId, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async importSessionBundle(path: string, projectPath: string | null) : Promise<Result<ImportedSession, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_session_bundle", { path, projectPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async focusTerminal(tmuxTarget: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("focus_terminal", { tmuxTarget }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async zoomPane(tmuxTarget: string, zoom: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("zoom_pane", { tmuxTarget, zoom }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async focusSession(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("focus_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendAction(sessionId: string, action: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_action", { sessionId, action }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async approveSession(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("approve_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async denySession(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("deny_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendSessionInput(sessionId: string, text: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_session_input", { sessionId, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async answerQuestion(sessionId: string, optionIndex: number) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("answer_question", { sessionId, optionIndex }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async removeSession(sessionId: string) : Promise<void> {
+    await TAURI_INVOKE("remove_session", { sessionId });
+},
+async closePane(tmuxTarget: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("close_pane", { tmuxTarget }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopClaude(tmuxTarget: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_claude", { tmuxTarget }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async killSession(sessionId: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("kill_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async playSound(sound: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("play_sound", { sound }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listSoundThemes() : Promise<SoundTheme[]> {
+    return await TAURI_INVOKE("list_sound_themes");
+},
+async listAudioOutputs() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_audio_outputs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAutomationLog() : Promise<AutomationRun[]> {
+    return await TAURI_INVOKE("get_automation_log");
+},
+async getSettings() : Promise<AppSettings> {
+    return await TAURI_INVOKE("get_settings");
+},
+async updateSettings(settings: AppSettings) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_settings", { settings }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getAvailableTerminals() : Promise<string[]> {
+    return await TAURI_INVOKE("get_available_terminals");
+},
+async getSessionMeta() : Promise<SessionMetaStore> {
+    return await TAURI_INVOKE("get_session_meta");
+},
+async updateSessionMeta(sessionId: string, tag: string | null, pinned: boolean | null) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_session_meta", { sessionId, tag, pinned }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async reorderSessions(sessionIds: string[]) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reorder_sessions", { sessionIds }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setSessionNote(sessionId: string, text: string) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_session_note", { sessionId, text }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setNextPrompts(sessionId: string, prompts: string[]) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_next_prompts", { sessionId, prompts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async sendNextPrompt(sessionId: string) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_next_prompt", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async upsertSessionGroup(group: SessionGroup) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("upsert_session_group", { group }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteSessionGroup(groupId: string) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_session_group", { groupId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async assignSessionGroup(sessionId: string, groupId: string | null, groupAssignment: string) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("assign_session_group", { sessionId, groupId, groupAssignment }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setGroupMuted(groupId: string, muted: boolean) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_group_muted", { groupId, muted }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setTagMuted(tag: string, muted: boolean) : Promise<Result<SessionMetaStore, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_tag_muted", { tag, muted }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async createNewTask() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_new_task") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async listScheduledTasks() : Promise<ScheduleEntry[]> {
+    return await TAURI_INVOKE("list_scheduled_tasks");
+},
+async saveScheduledTask(task: ScheduledTask) : Promise<Result<ScheduleEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_scheduled_task", { task }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setScheduledTaskEnabled(id: string, enabled: boolean) : Promise<Result<ScheduleEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_scheduled_task_enabled", { id, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deleteScheduledTask(id: string) : Promise<Result<ScheduleEntry[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_scheduled_task", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async runScheduledTask(id: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_scheduled_task", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getQueue() : Promise<QueuedLaunch[]> {
+    return await TAURI_INVOKE("get_queue");
+},
+async cancelQueued(id: string) : Promise<Result<QueuedLaunch[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_queued", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resumeSession(sessionId: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resume_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async resyncSession(sessionId: string) : Promise<Result<C3Session, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("resync_session", { sessionId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async checkHookStatus() : Promise<HookStatus> {
+    return await TAURI_INVOKE("check_hook_status");
+},
+async getCompatWarnings() : Promise<CompatWarning[]> {
+    return await TAURI_INVOKE("get_compat_warnings");
+},
+async runOnboardingStep(step: OnboardingStep) : Promise<Result<OnboardingProgress, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_onboarding_step", { step }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async installDependency(name: string) : Promise<Result<HookStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("install_dependency", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async uninstallC3Integration(deleteData: boolean) : Promise<UninstallReport> {
+    return await TAURI_INVOKE("uninstall_c3_integration", { deleteData });
+},
+async setupHooks() : Promise<SetupResult> {
+    return await TAURI_INVOKE("setup_hooks");
+},
+async simulateScenario(scenario: Scenario) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("simulate_scenario", { scenario }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async stopSimulation() : Promise<void> {
+    await TAURI_INVOKE("stop_simulation");
+},
+/**
+ * Enables rounded corners for the window (macOS only)
+ * Uses only public APIs - App Store compatible
+ */
+async enableRoundedCorners(offsetX: number | null, offsetY: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_rounded_corners", { offsetX, offsetY }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Enables modern window style with rounded corners and shadow
+ */
+async enableModernWindowStyle(cornerRadius: number | null, offsetX: number | null, offsetY: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("enable_modern_window_style", { cornerRadius, offsetX, offsetY }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Repositions Traffic Lights only (useful after fullscreen toggle)
+ */
+async repositionTrafficLights(offsetX: number | null, offsetY: number | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reposition_traffic_lights", { offsetX, offsetY }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async toggleCompactWindow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_compact_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hideCompactWindow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("hide_compact_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async openMainWindow() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("open_main_window") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setCompactWindowPinned(pinned: boolean) : Promise<void> {
+    await TAURI_INVOKE("set_compact_window_pinned", { pinned });
+},
+async toggleFloatingWidget() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("toggle_floating_widget") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async hideFloatingWidget() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("hide_floating_widget") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getLoginItemStatus() : Promise<Result<LoginItemStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_login_item_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async setLaunchAtLogin(enabled: boolean) : Promise<Result<LoginItemStatus, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_launch_at_login", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * An agent as listed in Settings
+ */
+export type AgentInfo = { kind: string; label: string }
+/**
+ * Result of changing the allowlist
+ */
+export type AllowlistUpdate = { rules: string[]; 
+/**
+ * Copy of settings.json taken before it was rewritten
+ */
+backupPath: string | null }
+/**
+ * A token as shown in settings
+ */
+export type ApiToken = { id: string; name: string; scope: TokenScope; createdAt: string; lastUsedAt: string | null }
+export type AppSettings = { terminal_app?: string; 
+/**
+ * Bundle identifier of `terminal_app`, looked up when it's saved, used
+ * to activate it from AppleScript and notification clicks
+ */
+terminal_bundle_id?: string | null; 
+/**
+ * Terminals to detect and focus besides the known ones
+ */
+custom_terminals?: CustomTerminal[]; default_agent?: string; notifications_enabled?: boolean; permission_sound?: SoundConfig; input_sound?: SoundConfig; complete_sound?: SoundConfig; 
+/**
+ * Seconds between tmux scan cycles
+ */
+scan_interval_secs?: number; 
+/**
+ * Seconds the scanner defers to a hook-set state
+ */
+hook_grace_period_secs?: number; 
+/**
+ * How the scanner and hooks settle a disagreement about a session's state
+ */
+state_arbitration?: Arbitration; 
+/**
+ * With `newest_evidence`, seconds a hook counts as newer than it is
+ */
+hook_confidence_secs?: number; 
+/**
+ * With `newest_evidence`, seconds the scanner's transcript evidence
+ * counts as newer than it is
+ */
+scanner_confidence_secs?: number; 
+/**
+ * Read Claude panes' content (permission box, "esc to interrupt",
+ * input prompt) as a third state signal, weighed by `pane_signal`
+ */
+pane_content_detection?: boolean; 
+/**
+ * Scan less often, and skip reading pane content, on battery or in
+ * Low Power Mode
+ */
+battery_saver?: boolean; 
+/**
+ * Minimum gap between notifications for the same session
+ */
+notification_debounce_ms?: number; 
+/**
+ * Extra regexes masked in commands, previews, diagnostics and notifications
+ */
+redaction_patterns?: string[]; 
+/**
+ * Port of the local hook server (applied on restart)
+ */
+hook_port?: number; 
+/**
+ * Archive Complete sessions after this many hours (0 = never)
+ */
+archive_complete_after_hours?: number; 
+/**
+ * Hide sessions whose transcript hasn't changed in this many days (0 = never)
+ */
+hide_stale_after_days?: number; 
+/**
+ * Project path globs C3 never tracks, e.g. `~/scratch/**`
+ */
+ignored_paths?: string[]; 
+/**
+ * tmux session-name globs C3 never tracks, e.g. `dotfiles`
+ */
+ignored_tmux_sessions?: string[]; 
+/**
+ * Agent kinds (see `agents::AGENTS`) C3 neither scans for nor takes hooks from
+ */
+disabled_agents?: string[]; 
+/**
+ * Global shortcut that shows or hides the floating widget (empty = none)
+ */
+widget_shortcut?: string; 
+/**
+ * Register C3 as a login item
+ */
+launch_at_login?: boolean; 
+/**
+ * Keep the window hidden (tray only) when started at login
+ */
+start_hidden?: boolean; 
+/**
+ * Stable only, or prereleases too
+ */
+update_channel?: UpdateChannel; 
+/**
+ * Download new releases in the background, or just point at them
+ */
+update_mode?: UpdateMode; 
+/**
+ * Alert sound volume, 0–100
+ */
+alert_volume?: number; 
+/**
+ * Output device alerts play on (None = the system default)
+ */
+alert_output_device?: string | null; 
+/**
+ * Named sound theme used for alerts (None = the per-event sounds above)
+ */
+sound_theme?: string | null; 
+/**
+ * Themes for projects under a path glob; the first match wins
+ */
+project_sound_themes?: ProjectSoundTheme[]; 
+/**
+ * Commands typed in a session's pane when it completes, for projects
+ * under a path glob; the first match wins
+ */
+complete_actions?: CompleteAction[]; 
+/**
+ * Time limits per state, globally or for a tag, checked by `sla`
+ */
+sla_rules?: SlaRule[]; 
+/**
+ * Shell commands run on state transitions, by `automation`
+ */
+automation_rules?: AutomationRule[]; 
+/**
+ * Style tmux panes waiting for the user, and their windows in the status line
+ */
+tmux_attention_marker?: boolean; 
+/**
+ * tmux style for marked panes, e.g. `bg=colour52`
+ */
+tmux_attention_style?: string; 
+/**
+ * Rename tmux windows after their sessions' state, e.g. `⏳ api-server`
+ */
+tmux_window_names?: boolean; 
+/**
+ * Zoom a session's tmux pane when focusing it from C3
+ */
+zoom_on_focus?: boolean; 
+/**
+ * Ask before sending a session's queued next prompt, instead of
+ * sending it `chain_delay_secs` after the agent stops
+ */
+chain_confirm?: boolean; chain_delay_secs?: number; 
+/**
+ * Folder shared between machines (iCloud Drive, Syncthing, ...) where
+ * tags, notes, pins and groups are synced, merged by `meta_sync`
+ */
+meta_sync_dir?: string | null; 
+/**
+ * Automatic launches (scheduled tasks, chained prompts) wait in
+ * `launch_queue` while this many sessions are working; 0 for no limit
+ */
+max_working_sessions?: number; 
+/**
+ * The same, counting only sessions in the launch's repo
+ */
+max_working_sessions_per_repo?: number; 
+/**
+ * Summarize stopped sessions with `claude -p` instead of the first
+ * lines of their final reply
+ */
+summarize_with_claude?: boolean; 
+/**
+ * Shell command that summarizes a stopped session instead, given the
+ * transcript's path on stdin and printing the summary
+ */
+summarizer_command?: string | null; 
+/**
+ * OTLP/HTTP collector sent a trace per session, e.g. `http://localhost:4318`
+ */
+otlp_endpoint?: string | null; 
+/**
+ * Append every session event to `events.log` in the config dir
+ */
+event_log?: boolean; 
+/**
+ * Notify when a session's agent processes use more memory than this; 0 to never
+ */
+memory_alert_mb?: number; 
+/**
+ * Send a summary of last week's sessions on Monday morning
+ */
+weekly_digest?: boolean; 
+/**
+ * Slack incoming webhook the weekly digest is also posted to
+ */
+slack_webhook_url?: string | null; 
+/**
+ * Mobile push through ntfy or Pushover, by `push`
+ */
+push?: PushSettings; 
+/**
+ * Telegram bot with approve/deny buttons, by `telegram`
+ */
+telegram?: TelegramSettings; 
+/**
+ * Email about sessions left waiting, by `email`
+ */
+email?: EmailSettings; 
+/**
+ * Serving the API to other devices over TLS, by `lan`; loopback only
+ * when off
+ */
+lan?: LanSettings; 
+/**
+ * Encrypting session bundles and transcript exports, by `at_rest`
+ */
+at_rest?: AtRestSettings }
+/**
+ * How a state the scanner infers is weighed against the last hook
+ */
+export type Arbitration = 
+/**
+ * Hooks win for the grace period after they fire, then the scanner does
+ */
+"hook_grace_period" | 
+/**
+ * Once a session has had a hook, only hooks change its state
+ */
+"hook_always_wins" | 
+/**
+ * The scanner may override a hook on its next cycle
+ */
+"scanner_always_wins" | 
+/**
+ * Whichever source saw the session more recently wins, each source's
+ * evidence counted as that much newer as its confidence
+ */
+"newest_evidence"
+export type ArchiveReason = 
+/**
+ * Complete for longer than `archive_complete_after_hours`
+ */
+"complete" | 
+/**
+ * Transcript untouched for longer than `hide_stale_after_days`
+ */
+"stale"
+/**
+ * Payload of the `sessions-archived` event
+ */
+export type ArchivedBatch = { sessionIds: string[]; undoUntil: string; 
+/**
+ * Worktrees of sessions archived earlier that couldn't be removed,
+ * e.g. with uncommitted changes
+ */
+worktreeErrors: string[] }
+export type ArchivedSession = { session: C3Session; reason: ArchiveReason; archivedAt: string }
+/**
+ * Encrypt the conversation copies C3 writes (session bundles and
+ * transcript exports) with age
+ */
+export type AtRestSettings = { enabled?: boolean; 
+/**
+ * age identity file to use, e.g. from `age-keygen` or an age plugin,
+ * instead of a key C3 creates in the keychain
+ */
+identity_path?: string | null; 
+/**
+ * More age (or SSH) recipients that can decrypt, e.g. other machines
+ * that import this one's bundles
+ */
+recipients?: string[] }
+/**
+ * "When a session moves from `from` to `to`, run `command`"
+ */
+export type AutomationRule = { name: string; enabled?: boolean; 
+/**
+ * The state left; None for any, including a new session
+ */
+from?: SessionState | null; to: SessionState; 
+/**
+ * A project name, or a path the project is in; None for every project
+ */
+project?: string | null; 
+/**
+ * Sessions with this tag; None for every session
+ */
+tag?: string | null; 
+/**
+ * Only sessions with (true) or without (false) permission prompts
+ * turned off; None for every session
+ */
+yolo?: boolean | null; 
+/**
+ * Run with `sh -c` in the project directory, session fields in `C3_*`
+ * environment variables
+ */
+command: string; 
+/**
+ * Log the run without running the command
+ */
+dry_run?: boolean }
+/**
+ * A rule that matched, and what came of it
+ */
+export type AutomationRun = { at: string; rule: string; sessionId: string; projectName: string; from: SessionState | null; to: SessionState; command: string; dryRun: boolean; 
+/**
+ * None for dry runs and commands that failed to start or timed out
+ */
+exitCode: number | null; 
+/**
+ * The end of stdout and stderr, or why the command didn't run
+ */
+output: string }
+export type C3Session = { id: string; projectName: string; projectPath: string | null; agentKind: string | null; state: SessionState; tmuxTarget: string | null; 
+/**
+ * tmux's `%N` pane ID, which survives window moves and renumbering
+ */
+tmuxPaneId?: string | null; terminalTty: string | null; lastActivity: string; pendingAction: PendingAction | null; metrics: SessionMetrics | null; 
+/**
+ * Why the session is in the Error state (e.g. crash exit status or API error)
+ */
+errorReason?: string | null; 
+/**
+ * Commit and branch when C3 first saw the session
+ */
+gitBaseline?: GitBaseline | null; 
+/**
+ * Open PR for the session's branch, refreshed by the PR watcher
+ */
+pullRequest?: PullRequestStatus | null; 
+/**
+ * What the session accomplished, written by `summaries` when it stops
+ */
+summary?: string | null; 
+/**
+ * Started with permission prompts turned off, e.g. with
+ * `--dangerously-skip-permissions`
+ */
+yolo?: boolean; 
+/**
+ * Working while the machine is offline, so most likely stuck retrying
+ * its API
+ */
+stalled?: boolean }
+/**
+ * What a notification is about, so each channel can pick the ones it sends
+ */
+export type ChannelEvent = 
+/**
+ * An agent is waiting on a permission prompt
+ */
+"permission" | 
+/**
+ * An agent asked a question or is idle at its prompt
+ */
+"input" | 
+/**
+ * An agent finished its turn
+ */
+"complete" | 
+/**
+ * A time limit or memory limit was crossed
+ */
+"alert"
+export type CiStatus = "passing" | "failing" | "pending" | 
+/**
+ * No checks configured
+ */
+"none"
+export type CommandCount = { pattern: string; count: number }
+/**
+ * A Claude Code CLI whose hooks or transcripts C3 may misread
+ */
+export type CompatWarning = { 
+/**
+ * None for the installed CLI
+ */
+sessionId: string | null; projectName: string | null; version: string; issues: string[] }
+/**
+ * Run `command` in the pane of sessions under the `path` glob when they
+ * complete
+ */
+export type CompleteAction = { path: string; command: string }
+/**
+ * A new token with its secret, which is shown only this once
+ */
+export type CreatedApiToken = { token: ApiToken; secret: string }
+/**
+ * A terminal app added in settings, e.g. Rio or Tabby. Custom terminals
+ * are tried before the known ones, and replace a known one of the same name.
+ */
+export type CustomTerminal = { 
+/**
+ * App name, as in `/Applications/<name>.app` and its process name
+ */
+name: string; 
+/**
+ * e.g. `com.raphaelamorim.rio`; found and activated by id when set
+ */
+bundle_id?: string | null; 
+/**
+ * Shell command run to focus the terminal instead of activating the app
+ */
+focus_command?: string | null; 
+/**
+ * Shell command that opens a new window running `{command}`, for tmux
+ * sessions no client is showing
+ */
+attach_command?: string | null }
+/**
+ * Series for the dashboard's sparklines, oldest point first
+ */
+export type DashboardStats = { bucket: StatsBucket; points: StatsPoint[]; 
+/**
+ * Share of the sessions started in the period that have reached
+ * Complete, from 0 to 1; None when none started
+ */
+completionRate: number | null }
+/**
+ * A line of `brew install` output, emitted as `dependency-progress`
+ */
+export type DependencyProgress = { name: string; 
+/**
+ * None in the final event
+ */
+line: string | null; done: boolean; success: boolean }
+/**
+ * The state of C3's own machinery, one section per part of the health panel
+ */
+export type Diagnostics = { scanner: ScannerHealth; hookServer: HookServerStatus; 
+/**
+ * Streaming clients on the event bus
+ */
+busSubscribers: number; transcripts: TranscriptHealth; settings: SettingsHealth; subsystems: SubsystemHealth[] }
+export type DiffFile = { path: string; 
+/**
+ * None for binary files
+ */
+insertions: number | null; deletions: number | null }
+/**
+ * Email when sessions have waited on you too long
+ */
+export type EmailSettings = { enabled?: boolean; to?: string; 
+/**
+ * Sender address; sendmail picks one when empty
+ */
+from?: string; transport?: EmailTransport; 
+/**
+ * e.g. `smtps://smtp.example.com:465` or `smtp://localhost:25`
+ */
+smtp_url?: string; 
+/**
+ * With a user, the connection must be encrypted
+ */
+smtp_user?: string; smtp_password?: string; 
+/**
+ * Minutes a session waits for you before it is emailed about
+ */
+after_minutes?: number }
+/**
+ * How emails leave this machine
+ */
+export type EmailTransport = 
+/**
+ * The local `sendmail` (Postfix, msmtp, ...)
+ */
+"sendmail" | 
+/**
+ * An SMTP server, through curl
+ */
+"smtp"
+export type ExportFormat = "markdown" | "html"
+/**
+ * Commit and branch checked out when C3 first saw a session, kept in
+ * session-meta.json so it outlives the session
+ */
+export type GitBaseline = { commit: string; 
+/**
+ * None when HEAD was detached
+ */
+branch: string | null; 
+/**
+ * Directory the baseline was taken in
+ */
+cwd: string; recordedAt: string }
+export type HookNotification = { 
+/**
+ * Hook protocol version; missing from scripts older than protocol 2
+ */
+version?: number | null; hook_type: string; cwd: string; terminal_tty?: string | null; agent_kind?: string | null; session_id?: string | null; tool_name?: string | null; tool_input?: JsonValue | null; skip_permissions?: boolean; approval_hint?: string | null; hook_payload_keys?: string[]; tmux?: TmuxContext | null; 
+/**
+ * Unix time the script spooled this payload because C3 wasn't listening
+ */
+queued_at?: number | null }
+/**
+ * Whether the hook server is accepting hooks
+ */
+export type HookServerStatus = { port: number; listening: boolean; 
+/**
+ * Why binding the port last failed
+ */
+bindError: string | null }
+/**
+ * Payload of the `hook-sound` event
+ */
+export type HookSound = { 
+/**
+ * `permission`, `input` or `complete`
+ */
+kind: string; 
+/**
+ * What to hand `play_sound`
+ */
+sound: string }
+export type HookStatus = { hooks_installed: boolean; claude_hooks_installed: boolean; codex_hooks_installed: boolean; omp_hooks_installed: boolean; hook_script_exists: boolean; jq_installed: boolean; terminal_notifier_installed: boolean; tmux_installed: boolean; 
+/**
+ * `C3_HOOK_VERSION` of the installed script
+ */
+hook_script_version: number | null; 
+/**
+ * The installed script, or a hook that posted recently, speaks an older protocol
+ */
+hook_script_outdated: boolean; 
+/**
+ * `claude --version` of the CLI on PATH
+ */
+claude_version: string | null; 
+/**
+ * What C3 is known to misread with that version, from `compat`
+ */
+claude_compat_issues: string[] }
+/**
+ * A conversation imported from another machine
+ */
+export type ImportedSession = { sessionId: string; projectName: string; projectPath: string; resumeCommand: string; 
+/**
+ * The tmux window it was resumed in, when tmux was running
+ */
+tmuxTarget: string | null }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Serving the `/api` routes to other devices on the network, over TLS
+ */
+export type LanSettings = { enabled?: boolean; port?: number; 
+/**
+ * PEM certificate chain to serve instead of a self-signed one
+ */
+cert_path?: string | null; 
+/**
+ * PEM private key of `cert_path`
+ */
+key_path?: string | null }
+/**
+ * Whether the LAN server is up, and how to reach it
+ */
+export type LanStatus = { listening: boolean; urls: string[]; 
+/**
+ * SHA-256 of the served certificate, to check against on the device
+ */
+fingerprint: string | null; 
+/**
+ * Why the server last failed to start
+ */
+error: string | null }
+export type LoginItemStatus = { 
+/**
+ * Whether the login item is actually registered with the system
+ */
+enabled: boolean; startHidden: boolean }
+/**
+ * What C3 did with one hook's notification
+ */
+export type NotificationDecision = { timestamp: string; hookType: string; sent: boolean; reason: Suppression | null; detail: string | null }
+/**
+ * One thing a step checked or did
+ */
+export type OnboardingItem = { name: string; ok: boolean; 
+/**
+ * What went wrong, or what to do next
+ */
+detail: string | null }
+/**
+ * Where a step is; emitted as `onboarding-progress` after each item, and
+ * returned once `done`
+ */
+export type OnboardingProgress = { step: OnboardingStep; items: OnboardingItem[]; done: boolean; success: boolean }
+/**
+ * A step of the guided setup, run in this order
+ */
+export type OnboardingStep = 
+/**
+ * `brew install` whichever of jq, tmux and terminal-notifier is missing
+ */
+"dependencies" | 
+/**
+ * Install c3-hook.sh and register it with the agents, as Settings does
+ */
+"hooks" | 
+/**
+ * Run the installed script and wait for its payload to reach C3
+ */
+"hook_round_trip" | 
+/**
+ * Show a test notification
+ */
+"notification"
+export type PendingAction = { type: string; description: string; tool: string | null; command: string | null; 
+/**
+ * Choices offered with a question, answered with `answer_question`
+ */
+options?: string[] }
+/**
+ * Which tools asked for permission most often, most frequent first
+ */
+export type PermissionStats = { since: string; total: number; tools: ToolStats[] }
+export type ProfileList = { active: string; profiles: string[] }
+/**
+ * Use `theme` for sessions in projects under the `path` glob
+ */
+export type ProjectSoundTheme = { path: string; theme: string }
+/**
+ * Time agents spent on a project over the week
+ */
+export type ProjectTime = { projectName: string; projectPath: string | null; activeSecs: number; waitingSecs: number }
+/**
+ * Open pull request for the branch a session is working on
+ */
+export type PullRequestStatus = { number: number; title: string; url: string; isDraft: boolean; ci: CiStatus; 
+/**
+ * `APPROVED`, `CHANGES_REQUESTED` or `REVIEW_REQUIRED`; None when the
+ * repo doesn't require reviews
+ */
+reviewDecision: string | null; checkedAt: string }
+/**
+ * Where mobile pushes go
+ */
+export type PushService = "off" | 
+/**
+ * A topic on ntfy.sh or a self-hosted ntfy server
+ */
+"ntfy" | "pushover"
+/**
+ * Push notifications to a phone, for when you've stepped away
+ */
+export type PushSettings = { service?: PushService; 
+/**
+ * Topic URL, e.g. `https://ntfy.sh/my-c3-alerts`
+ */
+ntfy_url?: string; 
+/**
+ * Access token for a protected topic
+ */
+ntfy_token?: string | null; pushover_user_key?: string; 
+/**
+ * API token of a Pushover application
+ */
+pushover_app_token?: string; 
+/**
+ * Which notifications are pushed
+ */
+events?: ChannelEvent[]; 
+/**
+ * Most pushes in an hour; the rest are dropped. 0 for no limit
+ */
+max_per_hour?: number }
+/**
+ * An agent start, or a chained prompt, waiting for fewer sessions to be
+ * working
+ */
+export type QueuedLaunch = { id: string; 
+/**
+ * What asked for it, e.g. `Scheduled: standup`
+ */
+label: string; cwd: string; 
+/**
+ * The repo whose limit it counts against
+ */
+repo: string; queuedAt: string; 
+/**
+ * Agent CLI to start in a new tmux window
+ */
+agentCommand: string | null; 
+/**
+ * First message for a started agent, or the prompt to send
+ */
+prompt: string | null; 
+/**
+ * The session a chained prompt goes to, instead of starting an agent
+ */
+sessionId: string | null }
+/**
+ * Local days to report on, both included
+ */
+export type ReportRange = { start: string; end: string }
+/**
+ * Who produced a message. Tool results count as user turns, as in the
+ * Anthropic message format.
+ */
+export type Role = "user" | "assistant"
+export type ScannerHealth = { cycles: number; lastDurationMs: number; maxDurationMs: number; slowCycles: number; paneCount: number; 
+/**
+ * Pane changes arrive over tmux control mode rather than by polling
+ */
+controlMode: boolean; lastTmuxError: string | null; lastTmuxErrorAt: string | null }
+/**
+ * A recorded sequence of hook payloads and tmux pane lists to replay
+ */
+export type Scenario = { name?: string; 
+/**
+ * Playback speed multiplier (2.0 replays twice as fast)
+ */
+speed?: number; steps: ScenarioStep[] }
+export type ScenarioStep = (
+/**
+ * Same payload c3-hook.sh posts to /hook
+ */
+{ hook: HookNotification } | 
+/**
+ * The complete set of agent panes, as if returned by one tmux scan.
+ * Sessions for panes missing from the list are removed.
+ */
+{ panes: SimulatedPane[] }) & { 
+/**
+ * Offset from the start of the replay
+ */
+at_ms?: number }
+/**
+ * A scheduled task and when it runs next
+ */
+export type ScheduleEntry = { task: ScheduledTask; 
+/**
+ * None when disabled or the schedule never matches
+ */
+nextRun: string | null }
+/**
+ * "Every weekday at 9:00, start claude in ~/notes with this prompt"
+ */
+export type ScheduledTask = { 
+/**
+ * Empty when saving a new task
+ */
+id?: string; name: string; enabled?: boolean; 
+/**
+ * Cron fields in local time, `minute hour day month weekday` (e.g.
+ * `0 9 * * 1-5`), or `@hourly`, `@daily`, `@weekly`, `@monthly`
+ */
+schedule: string; 
+/**
+ * Where the agent starts; `~` is expanded
+ */
+cwd: string; 
+/**
+ * Agent kind, e.g. `claude`; None for the default agent
+ */
+agent?: string | null; 
+/**
+ * First message for the agent
+ */
+prompt?: string | null; 
+/**
+ * Last saved or enabled; runs due before this aren't made up
+ */
+updatedAt?: string; lastRun?: string | null; 
+/**
+ * Why the last run failed to start
+ */
+lastError?: string | null }
+export type SearchScope = 
+/**
+ * Transcripts of sessions currently shown in C3
+ */
+"active" | 
+/**
+ * Every transcript on disk, including sessions C3 no longer tracks
+ */
+"all"
+/**
+ * Payload of the `sessions-batch-update` event
+ */
+export type SessionBatch = { updated: C3Session[]; removed: string[]; 
+/**
+ * Sent along with the removal of `from` and the update of `to`
+ */
+renamed: SessionRename[]; 
+/**
+ * Session revision after this batch
+ */
+revision: number }
+/**
+ * Response of `get_sessions_since`
+ */
+export type SessionDelta = { revision: number; updated: C3Session[]; removed: string[]; 
+/**
+ * The requested revision was too old to diff against; `updated` holds
+ * every session and the client should replace its list.
+ */
+full: boolean }
+export type SessionDiff = { sessionId: string; projectPath: string; 
+/**
+ * Commit the working tree is compared against: the session's recorded
+ * baseline, else the last commit made before it started
+ */
+baseCommit: string; sessionStart: string | null; files: DiffFile[]; insertions: number; deletions: number; 
+/**
+ * Unified diff, only when requested
+ */
+patch: string | null; patchTruncated: boolean }
+export type SessionFiles = { sessionId: string; projectPath: string | null; 
+/**
+ * Most edited first
+ */
+files: TouchedFile[]; totalEdits: number }
+/**
+ * Criteria for `query_sessions`; empty fields match everything
+ */
+export type SessionFilter = { states?: SessionState[]; 
+/**
+ * Glob against the project path, e.g. `~/work/**`
+ */
+pathGlob?: string | null; 
+/**
+ * Session tag, compared case-insensitively
+ */
+tag?: string | null; 
+/**
+ * Case-insensitive substring of the project name
+ */
+text?: string | null }
+export type SessionGroup = { id: string; name: string; color: string; matchText?: string[]; createdAt: string; 
+/**
+ * No notifications or sounds for the group's sessions
+ */
+muted?: boolean }
+export type SessionMeta = { tag?: string | null; pinned?: boolean; groupId?: string | null; groupAssignment?: string | null; gitBaseline?: GitBaseline | null; 
+/**
+ * Position set by drag-reorder; sessions without one follow, by urgency
+ */
+order?: number | null; 
+/**
+ * Free-form scratchpad, e.g. "waiting on design answer"
+ */
+notes?: string | null; 
+/**
+ * Prompts to send after the next Stops, first one first, by `task_chain`
+ */
+nextPrompts: string[]; 
+/**
+ * Worktree `create_worktree` made for the session, removed by the GC
+ * once the session stays archived
+ */
+worktree?: string | null }
+export type SessionMetaStore = { sessions?: Partial<{ [key in string]: SessionMeta }>; groups?: SessionGroup[]; 
+/**
+ * Tags whose sessions send no notifications or sounds
+ */
+mutedTags?: string[] }
+export type SessionMetrics = { tokensUsed: number | null; taskCount: number | null; startTime: string | null; 
+/**
+ * The agent's process, sampled by `resources`
+ */
+pid?: number | null; 
+/**
+ * Resident memory of the agent's process and its children
+ */
+rssBytes?: number | null; 
+/**
+ * CPU the agent's process and its children used since the last sample;
+ * 100 is one core
+ */
+cpuPercent?: number | null }
+export type SessionQueryResult = { 
+/**
+ * Matching sessions: pinned first, then manual order, then by urgency
+ */
+sessions: C3Session[]; 
+/**
+ * Sessions per state among those matching every criterion except `states`,
+ * so state chips can show counts while one of them is selected
+ */
+stateCounts: Partial<{ [key in SessionState]: number }>; total: number }
+/**
+ * A session that moved to a new ID, e.g. after the tmux server restarted;
+ * its metadata moves with it
+ */
+export type SessionRename = { from: string; to: string }
+export type SessionState = "spawning" | "processing" | "awaiting_input" | "awaiting_permission" | "complete" | "error"
+/**
+ * What a session window shows besides the session itself
+ */
+export type SessionTail = { messages: TailMessage[]; 
+/**
+ * Bottom of the tmux pane, None for hook-only sessions
+ */
+pane: string | null }
+export type SettingsHealth = { path: string; exists: boolean; 
+/**
+ * Defaults are used in place of a settings file that doesn't parse
+ */
+parseError: string | null }
+export type SetupResult = { success: boolean; message: string; backup_path: string | null }
+/**
+ * An agent pane with its state already decided
+ */
+export type SimulatedPane = { target: string; cwd: string; title?: string; agent_kind?: string; state: SessionState; pending_action?: PendingAction | null; error_reason?: string | null }
+/**
+ * "Alert if a session stays `state` longer than `minutes`"
+ */
+export type SlaRule = { state: SlaState; minutes: number; 
+/**
+ * Sessions with this tag; None for every session. A tag's rule wins over
+ * the global one.
+ */
+tag?: string | null }
+/**
+ * What a time limit watches
+ */
+export type SlaState = 
+/**
+ * Spawning or Processing
+ */
+"working" | 
+/**
+ * Waiting for input or permission
+ */
+"waiting"
+export type SoundConfig = { enabled?: boolean; sound?: string | null }
+/**
+ * A named set of alert sounds. Each is a system sound name (`Ping`) or a
+ * file path; None falls back to the per-event sound in settings.
+ */
+export type SoundTheme = { name: string; permission?: string | null; input?: string | null; complete?: string | null; 
+/**
+ * Shipped with C3 rather than a user pack
+ */
+bundled?: boolean }
+/**
+ * Where a state change came from. Hooks are reported by the agent itself and
+ * win over the tmux scanner, which only infers state from titles and JSONL.
+ */
+export type StateSource = "hook" | "scanner"
+/**
+ * How wide each point of a series is
+ */
+export type StatsBucket = "hour" | 
+/**
+ * A local day
+ */
+"day"
+/**
+ * Activity within one bucket
+ */
+export type StatsPoint = { start: string; 
+/**
+ * Sessions whose first logged transition falls in the bucket
+ */
+sessionsStarted: number; 
+/**
+ * Sessions that reached Complete in the bucket, each counted once
+ */
+sessionsCompleted: number; 
+/**
+ * Times an agent stopped to ask for permission or input
+ */
+interruptions: number; 
+/**
+ * Mean seconds an agent waited on a permission prompt or question
+ * before being answered, for answers given in the bucket
+ */
+avgResponseSecs: number | null }
+/**
+ * A background task that died and is waiting to be restarted
+ */
+export type SubsystemDown = { name: string; reason: string; restartInSecs: number }
+/**
+ * A background task's current run and its failures so far
+ */
+export type SubsystemHealth = { name: string; running: boolean; 
+/**
+ * Seconds since the current run started; None while waiting to restart
+ */
+uptimeSecs: number | null; restarts: number; 
+/**
+ * Why it last stopped, if it ever did
+ */
+lastError: string | null }
+/**
+ * Why a hook that warrants a notification didn't raise one, or might not
+ * have been shown
+ */
+export type Suppression = 
+/**
+ * A notification for the session was sent within the debounce window
+ */
+"debounce" | 
+/**
+ * Claude's Notification hook that follows a Stop
+ */
+"stop_recently" | 
+/**
+ * The state machine ignored the hook (detail says why)
+ */
+"state_rejected" | 
+/**
+ * Notifications are turned off in settings
+ */
+"disabled" | 
+/**
+ * The session's group or a tag is muted
+ */
+"muted" | 
+/**
+ * The machine was offline, so the agent most likely stopped on a
+ * connection error
+ */
+"offline" | 
+/**
+ * Sent, but a macOS Focus (Do Not Disturb) was on and may have held it
+ */
+"do_not_disturb"
+/**
+ * One transcript turn, flattened for display
+ */
+export type TailMessage = { role: Role; timestamp: string | null; text: string }
+/**
+ * A Telegram bot that posts notifications to one chat, with buttons to
+ * act on them
+ */
+export type TelegramSettings = { enabled?: boolean; 
+/**
+ * From @BotFather
+ */
+bot_token?: string; 
+/**
+ * The only chat the bot posts to and takes button presses from; the
+ * bot replies with a chat's id when it is messaged
+ */
+chat_id?: number | null; 
+/**
+ * Which notifications are posted
+ */
+events?: ChannelEvent[] }
+/**
+ * Time agents spent on one project on one local day
+ */
+export type TimeReportRow = { date: string; projectName: string; projectPath: string | null; 
+/**
+ * Seconds spent working (Processing)
+ */
+activeSecs: number; 
+/**
+ * Seconds spent blocked on a permission prompt
+ */
+waitingSecs: number; 
+/**
+ * Sessions that were active or waiting that day
+ */
+sessions: number }
+/**
+ * One applied state change
+ */
+export type TimelineEntry = { timestamp: string; from: SessionState | null; to: SessionState; source: StateSource }
+export type TmuxContext = { session?: string; window?: string; pane?: string; window_name?: string; 
+/**
+ * `$TMUX_PANE`, e.g. `%12`; sent since protocol version 4
+ */
+pane_id?: string | null }
+/**
+ * What a token may do with the `/api` routes
+ */
+export type TokenScope = 
+/**
+ * Read sessions and subscribe to events, e.g. for status widgets
+ */
+"read" | 
+/**
+ * Everything, including approving, denying and typing input
+ */
+"control"
+/**
+ * Tokens Claude Code sessions used, read from their transcripts
+ */
+export type TokenTotals = { input: number; output: number; cacheRead: number; cacheCreation: number }
+export type ToolStats = { tool: string; count: number; 
+/**
+ * Share of all requests, rounded to a whole percent
+ */
+percent: number; 
+/**
+ * Most requested command patterns, for tools that run commands
+ */
+topCommands: CommandCount[] }
+export type TouchedFile = { 
+/**
+ * Absolute path as the agent wrote it (relative patch paths are resolved
+ * against the project dir)
+ */
+path: string; 
+/**
+ * Path inside the project, when the file is under it
+ */
+relativePath: string | null; 
+/**
+ * Number of modifying tool calls targeting the file
+ */
+edits: number; lastEdited: string | null }
+/**
+ * The incremental reader behind transcript-based state detection
+ */
+export type TranscriptHealth = { followedFiles: number; 
+/**
+ * Seconds since any transcript was read; None before the first read
+ */
+lastReadSecs: number | null }
+export type TranscriptMatch = { 
+/**
+ * C3 session id when the transcript belongs to a tracked session
+ */
+sessionId: string | null; agentKind: string; projectPath: string | null; transcriptPath: string; timestamp: string | null; role: Role; snippet: string }
+/**
+ * What `uninstall_c3_integration` removed, and what it couldn't
+ */
+export type UninstallReport = { removed: string[]; 
+/**
+ * Copies of agent settings taken before C3's hooks were stripped
+ */
+backups: string[]; errors: string[] }
+export type UpdateChannel = "stable" | 
+/**
+ * Stable releases plus prereleases
+ */
+"beta"
+export type UpdateInfo = { currentVersion: string; latestVersion: string; available: boolean; channel: UpdateChannel; releaseUrl: string; downloadUrl: string | null; 
+/**
+ * Downloaded DMG, ready to open
+ */
+stagedPath: string | null; 
+/**
+ * None when the hook script isn't installed
+ */
+installedHookVersion: number | null; 
+/**
+ * Hook script version needed by this build or the new release
+ */
+requiredHookVersion: number; hooksOutdated: boolean }
+export type UpdateMode = 
+/**
+ * Download new releases in the background and say when they're ready
+ */
+"auto" | 
+/**
+ * Only point at the release; the user downloads it
+ */
+"prompt"
+/**
+ * A stretch a session spent waiting on a permission prompt or question
+ */
+export type Wait = { sessionId: string; projectName: string; started: string; secs: number }
+/**
+ * A week of agent activity, Monday to Sunday in local time
+ */
+export type WeeklyDigest = { start: string; end: string; 
+/**
+ * Sessions with any state change in the week
+ */
+sessions: number; activeSecs: number; waitingSecs: number; 
+/**
+ * Projects agents worked on longest, longest first
+ */
+topProjects: ProjectTime[]; longestWaits: Wait[]; tokens: TokenTotals }
+export type WorktreeSession = { path: string; branch: string; 
+/**
+ * Pane the agent was started in
+ */
+tmuxTarget: string }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
@@ -0,0 +1,67 @@
+import { useEffect } from 'react';
+import { Archive, X } from 'lucide-react';
+import { useSessionStore } from '../stores/sessions';
+
+// Offers to undo the latest retention sweep until its undo window closes
+export function ArchiveNotice() {
+  const batch = useSessionStore((state) => state.archivedBatch);
+  const setArchivedBatch = useSessionStore((state) => state.setArchivedBatch);
+  const undoArchive = useSessionStore((state) => state.undoArchive);
+
+  useEffect(() => {
+    if (!batch) return;
+    const remaining = new Date(batch.undoUntil).getTime() - Date.now();
+    const timer = setTimeout(() => setArchivedBatch(null), Math.max(remaining, 0));
+    return () => clearTimeout(timer);
+  }, [batch, setArchivedBatch]);
+
+  if (!batch) return null;
+
+  const count = batch.sessionIds.length;
+  const worktreeErrors = batch.worktreeErrors;
+
+  return (
+    <div className="archive-notice">
+      <div className="toast">
+        <div className="toast-header">
+          <span className="toast-icon">
+            <Archive size={16} />
+          </span>
+          <span className="toast-title">{count > 0 ? 'Sessions archived' : 'Worktrees kept'}</span>
+          <button className="toast-close" onClick={() => setArchivedBatch(null)}>
+            <X size={14} />
+          </button>
+        </div>
+        <div className="toast-body">
+          {count > 0 && (
+            <>
+              {count === 1 ? '1 finished or idle session was' : `${count} finished or idle sessions were`}{' '}
+              hidden. They come back when the agent is active again.
+            </>
+          )}
+          {worktreeErrors.length > 0 && (
+            <p>
+              Couldn't remove the worktrees of archived sessions:
+              {worktreeErrors.map((error) => (
+                <span key={error}>
+                  <br />
+                  {error}
+                </span>
+              ))}
+            </p>
+          )}
+        </div>
+        <div className="toast-buttons">
+          {count > 0 && (
+            <button className="toast-btn primary" onClick={undoArchive}>
+              Undo
+            </button>
+          )}
+          <button className="toast-btn" onClick={() => setArchivedBatch(null)}>
+            Dismiss
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,126 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { Maximize2, Pin, PinOff } from 'lucide-react';
+import { initializeSessionListeners, useSessionStore } from '../stores/sessions';
+import { getVisualSessionOrder, STATE_COLORS } from '../types';
+import type { C3Session } from '../types';
+import '../App.css';
+
+function getStateLabel(state: string): string {
+  switch (state) {
+    case 'awaiting_permission':
+      return 'Permission';
+    case 'awaiting_input':
+      return 'Idle';
+    case 'processing':
+    case 'spawning':
+      return 'Working';
+    case 'complete':
+      return 'Complete';
+    case 'error':
+      return 'Error';
+    default:
+      return state;
+  }
+}
+
+function getDetail(session: C3Session): string {
+  const action = session.pendingAction;
+  if (session.state === 'awaiting_permission' && action) {
+    const detail = action.command || action.description;
+    return action.tool ? `${action.tool}: ${detail}` : detail;
+  }
+  if (session.state === 'error' && session.errorReason) {
+    return session.errorReason;
+  }
+  if (session.state === 'complete' && session.summary) {
+    return session.summary;
+  }
+  return session.projectPath || '';
+}
+
+/**
+ * Condensed session list for the menu bar window. Clicking a row focuses the
+ * session and hides the window.
+ */
+export function CompactView() {
+  const sessions = useSessionStore((state) => state.sessions);
+  const sessionMeta = useSessionStore((state) => state.sessionMeta);
+  const groups = useSessionStore((state) => state.groups);
+  const syncSessions = useSessionStore((state) => state.syncSessions);
+  const focusSession = useSessionStore((state) => state.focusSession);
+  const [pinned, setPinned] = useState(false);
+
+  useEffect(() => {
+    // The main window plays hook sounds; don't double them up
+    initializeSessionListeners({ sounds: false });
+    const interval = setInterval(() => {
+      syncSessions();
+    }, 5000);
+    return () => clearInterval(interval);
+  }, [syncSessions]);
+
+  const ordered = getVisualSessionOrder(Object.values(sessions), sessionMeta, groups);
+
+  const handleSelect = async (sessionId: string) => {
+    await focusSession(sessionId);
+    if (!pinned) {
+      invoke('hide_compact_window').catch(() => {});
+    }
+  };
+
+  const togglePinned = () => {
+    const next = !pinned;
+    setPinned(next);
+    invoke('set_compact_window_pinned', { pinned: next }).catch(() => {});
+  };
+
+  return (
+    <div className="compact-view">
+      <div className="compact-header">
+        <span className="compact-title">C3</span>
+        <span className="compact-count">{ordered.length} sessions</span>
+        <button
+          className="compact-icon-btn"
+          onClick={togglePinned}
+          title={pinned ? 'Hide when focus is lost' : 'Keep open'}
+        >
+          {pinned ? <PinOff size={13} /> : <Pin size={13} />}
+        </button>
+        <button
+          className="compact-icon-btn"
+          onClick={() => invoke('open_main_window').catch(() => {})}
+          title="Open C3"
+        >
+          <Maximize2 size={13} />
+        </button>
+      </div>
+      <div className="compact-list">
+        {ordered.length === 0 && <div className="compact-empty">No active sessions</div>}
+        {ordered.map((session) => {
+          const tag = sessionMeta[session.id]?.tag;
+          return (
+            <button
+              key={session.id}
+              className="compact-row"
+              onClick={() => handleSelect(session.id)}
+              title={session.projectPath ?? undefined}
+            >
+              <span className="compact-dot" style={{ background: STATE_COLORS[session.state] }} />
+              <span className="compact-row-body">
+                <span className="compact-row-name">
+                  {session.projectName}
+                  {tag && <span className="compact-row-tag">{tag}</span>}
+                </span>
+                <span className="compact-row-detail">{getDetail(session)}</span>
+              </span>
+              <span className="compact-row-state" style={{ color: STATE_COLORS[session.state] }}>
+                {getStateLabel(session.state)}
+              </span>
+            </button>
+          );
+        })}
+      </div>
+    </div>
+  );
+}
@@ -25,8 +25,10 @@ export function Cube({ session }: CubeProps) {
 
   const handleClose = (e: React.MouseEvent) => {
     e.stopPropagation();
-    if (session.tmuxTarget) {
-      closePane(session.tmuxTarget);
+    // The pane ID still names the pane if it moved since the last scan
+    const pane = session.tmuxPaneId ?? session.tmuxTarget;
+    if (pane) {
+      closePane(pane);
     }
   };
 
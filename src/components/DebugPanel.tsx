@@ -1,5 +1,6 @@
 import { useState, useEffect, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/core';
+import type { Diagnostics, NotificationDecision, Suppression } from '../types';
 
 interface HookEvent {
   timestamp: string;
@@ -38,13 +39,63 @@ interface SessionInfo {
   project_path: string | null;
 }
 
+interface ScanPhases {
+  list_ms: number;
+  ps_ms: number | null;
+  analyze_ms: number;
+  apply_ms: number;
+  vscode_ms: number;
+}
+
+interface ScanStats {
+  cycles: number;
+  last_duration_ms: number;
+  max_duration_ms: number;
+  slow_cycles: number;
+  last_pane_count: number;
+  last_phases: ScanPhases;
+  last_children_cached: number;
+  last_children_read: number;
+  last_listed_by_control: boolean;
+}
+
 interface DebugInfo {
   hook_events: HookEvent[];
   hook_timestamps: HookTimestamp[];
   state_diagnostics: StateDiagnostic[];
+  scan_stats: ScanStats;
   sessions: SessionInfo[];
 }
 
+const SUPPRESSION_LABELS: Record<Suppression, string> = {
+  debounce: 'debounced',
+  stop_recently: 'Stop fired just before',
+  state_rejected: 'hook ignored',
+  disabled: 'notifications off',
+  muted: 'group or tag muted',
+  offline: 'offline',
+  do_not_disturb: 'Focus on; macOS may have held it',
+};
+
+function formatUptime(secs: number): string {
+  if (secs < 60) return `${secs}s`;
+  if (secs < 3600) return `${Math.floor(secs / 60)}m`;
+  return `${Math.floor(secs / 3600)}h ${Math.floor((secs % 3600) / 60)}m`;
+}
+
+function HealthRow({ label, ok, detail, error }: { label: string; ok: boolean; detail: string; error?: string | null }) {
+  return (
+    <tr style={{ borderBottom: '1px solid var(--border-subtle)' }}>
+      <td style={{ padding: '4px 8px', color: ok ? 'var(--accent-green)' : 'var(--accent-red)', width: 16 }}>{ok ? '●' : '○'}</td>
+      <td style={{ padding: '4px 8px', color: 'var(--text-primary)', fontWeight: 600, whiteSpace: 'nowrap' }}>{label}</td>
+      <td style={{ padding: '4px 8px', color: 'var(--text-secondary)' }}>
+        {detail}
+        {error && <div style={{ color: 'var(--accent-amber)' }}>{error}</div>}
+      </td>
+    </tr>
+  );
+}
+
 interface DebugPanelProps {
   isOpen: boolean;
   onClose: () => void;
@@ -52,14 +103,32 @@ interface DebugPanelProps {
 
 export function DebugPanel({ isOpen, onClose }: DebugPanelProps) {
   const [debugInfo, setDebugInfo] = useState<DebugInfo | null>(null);
+  const [health, setHealth] = useState<Diagnostics | null>(null);
+  const [bundleStatus, setBundleStatus] = useState<string | null>(null);
+  const [selectedSession, setSelectedSession] = useState<string | null>(null);
+  const [decisions, setDecisions] = useState<NotificationDecision[]>([]);
 
   const refresh = useCallback(async () => {
     try {
       const info = await invoke<DebugInfo>('get_debug_info');
       setDebugInfo(info);
+      setHealth(await invoke<Diagnostics>('get_diagnostics'));
+      if (selectedSession) {
+        setDecisions(await invoke<NotificationDecision[]>('get_notification_decisions', { sessionId: selectedSession }));
+      }
     } catch (e) {
       console.error('Failed to get debug info:', e);
     }
+  }, [selectedSession]);
+
+  const exportBundle = useCallback(async () => {
+    setBundleStatus('Exporting…');
+    try {
+      const path = await invoke<string>('create_diagnostic_bundle');
+      setBundleStatus(`Saved to ${path}`);
+    } catch (e) {
+      setBundleStatus(`Export failed: ${e}`);
+    }
   }, []);
 
   useEffect(() => {
@@ -85,10 +154,57 @@ export function DebugPanel({ isOpen, onClose }: DebugPanelProps) {
       <div className="settings-modal" onClick={(e) => e.stopPropagation()} style={{ width: 700, maxHeight: '80vh' }}>
         <div className="settings-header">
           <h2>Debug Panel</h2>
+          <button className="settings-btn" onClick={exportBundle} style={{ marginLeft: 'auto', marginRight: 8 }}>Export Bug Report</button>
           <button className="settings-close" onClick={onClose}>×</button>
         </div>
 
         <div style={{ padding: 16, overflow: 'auto', maxHeight: '70vh', fontSize: 12, fontFamily: "'Geist Mono', 'SF Mono', monospace" }}>
+          {bundleStatus && (
+            <div style={{ color: 'var(--text-secondary)', marginBottom: 12 }}>{bundleStatus}</div>
+          )}
+          {health && (
+            <>
+              <h3 style={{ color: 'var(--accent-blue)', marginBottom: 8 }}>Health</h3>
+              <table style={{ width: '100%', borderCollapse: 'collapse', marginBottom: 16 }}>
+                <tbody>
+                  <HealthRow
+                    label="Scanner"
+                    ok={!health.scanner.lastTmuxErrorAt || Date.now() - new Date(health.scanner.lastTmuxErrorAt).getTime() > 60_000}
+                    detail={`${health.scanner.cycles} cycles · last ${health.scanner.lastDurationMs}ms · ${health.scanner.paneCount} panes · ${health.scanner.controlMode ? 'control mode' : 'polling'}`}
+                    error={health.scanner.lastTmuxError && `${health.scanner.lastTmuxError} (${health.scanner.lastTmuxErrorAt ? new Date(health.scanner.lastTmuxErrorAt).toLocaleTimeString() : '?'})`}
+                  />
+                  <HealthRow
+                    label="Hook server"
+                    ok={health.hookServer.listening}
+                    detail={`port ${health.hookServer.port} · ${health.hookServer.listening ? 'listening' : 'not listening'}`}
+                    error={health.hookServer.bindError}
+                  />
+                  <HealthRow label="Event bus" ok detail={`${health.busSubscribers} streaming clients`} />
+                  <HealthRow
+                    label="Transcripts"
+                    ok
+                    detail={`${health.transcripts.followedFiles} followed · ${health.transcripts.lastReadSecs === null ? 'none read yet' : `last read ${health.transcripts.lastReadSecs}s ago`}`}
+                  />
+                  <HealthRow
+                    label="Settings"
+                    ok={!health.settings.parseError}
+                    detail={`${health.settings.path}${health.settings.exists ? '' : ' (not saved yet, using defaults)'}`}
+                    error={health.settings.parseError && `unreadable, using defaults: ${health.settings.parseError}`}
+                  />
+                  {health.subsystems.map((subsystem) => (
+                    <HealthRow
+                      key={subsystem.name}
+                      label={subsystem.name}
+                      ok={subsystem.running}
+                      detail={`${subsystem.running ? `up ${formatUptime(subsystem.uptimeSecs ?? 0)}` : 'restarting'} · ${subsystem.restarts} restarts`}
+                      error={subsystem.lastError}
+                    />
+                  ))}
+                </tbody>
+              </table>
+            </>
+          )}
+
           <h3 style={{ color: 'var(--accent-blue)', marginBottom: 8 }}>Sessions ({debugInfo.sessions.length})</h3>
           <table style={{ width: '100%', borderCollapse: 'collapse', marginBottom: 16 }}>
             <thead>
@@ -101,7 +217,15 @@ export function DebugPanel({ isOpen, onClose }: DebugPanelProps) {
             </thead>
             <tbody>
               {debugInfo.sessions.map((s) => (
-                <tr key={s.id} style={{ borderBottom: '1px solid var(--border-subtle)' }}>
+                <tr
+                  key={s.id}
+                  onClick={() => {
+                    setDecisions([]);
+                    setSelectedSession(selectedSession === s.id ? null : s.id);
+                  }}
+                  title="Show this session's notification decisions"
+                  style={{ borderBottom: '1px solid var(--border-subtle)', cursor: 'pointer', background: selectedSession === s.id ? 'var(--bg-tertiary)' : undefined }}
+                >
                   <td style={{ padding: '4px 8px', color: 'var(--text-secondary)' }}>{s.id}</td>
                   <td style={{ padding: '4px 8px', color: 'var(--text-primary)' }}>{s.project_name}</td>
                   <td style={{ padding: '4px 8px', color: s.state === 'Complete' ? 'var(--accent-green)' : s.state === 'AwaitingPermission' ? 'var(--accent-red)' : 'var(--accent-amber)' }}>{s.state}</td>
@@ -111,6 +235,46 @@ export function DebugPanel({ isOpen, onClose }: DebugPanelProps) {
             </tbody>
           </table>
 
+          {selectedSession && (
+            <>
+              <h3 style={{ color: 'var(--accent-blue)', marginBottom: 8 }}>Notifications for {selectedSession}</h3>
+              {decisions.length === 0 ? (
+                <p style={{ color: 'var(--text-muted)', marginBottom: 16 }}>No notifications considered for this session yet</p>
+              ) : (
+                <table style={{ width: '100%', borderCollapse: 'collapse', marginBottom: 16 }}>
+                  <thead>
+                    <tr style={{ textAlign: 'left', color: 'var(--text-muted)', borderBottom: '1px solid var(--border-color)' }}>
+                      <th style={{ padding: '4px 8px' }}>Time</th>
+                      <th style={{ padding: '4px 8px' }}>Hook</th>
+                      <th style={{ padding: '4px 8px' }}>Sent</th>
+                      <th style={{ padding: '4px 8px' }}>Why</th>
+                    </tr>
+                  </thead>
+                  <tbody>
+                    {[...decisions].reverse().map((d, i) => (
+                      <tr key={i} style={{ borderBottom: '1px solid var(--border-subtle)', opacity: d.sent ? 1 : 0.7 }}>
+                        <td style={{ padding: '4px 8px', color: 'var(--text-muted)' }}>{new Date(d.timestamp).toLocaleTimeString()}</td>
+                        <td style={{ padding: '4px 8px', color: 'var(--text-primary)', fontWeight: 600 }}>{d.hookType}</td>
+                        <td style={{ padding: '4px 8px', color: d.sent ? 'var(--accent-green)' : 'var(--accent-red)' }}>{d.sent ? 'YES' : 'no'}</td>
+                        <td style={{ padding: '4px 8px', color: d.reason ? 'var(--accent-amber)' : 'var(--text-muted)' }}>
+                          {d.reason ? SUPPRESSION_LABELS[d.reason] : '-'}{d.detail ? ` (${d.detail})` : ''}
+                        </td>
+                      </tr>
+                    ))}
+                  </tbody>
+                </table>
+              )}
+            </>
+          )}
+
+          <h3 style={{ color: 'var(--accent-blue)', marginBottom: 8 }}>Scanner</h3>
+          <p style={{ color: debugInfo.scan_stats.last_duration_ms > 1000 ? 'var(--accent-red)' : 'var(--text-secondary)', marginBottom: 4 }}>
+            last {debugInfo.scan_stats.last_duration_ms}ms · max {debugInfo.scan_stats.max_duration_ms}ms · {debugInfo.scan_stats.last_pane_count} panes · {debugInfo.scan_stats.slow_cycles}/{debugInfo.scan_stats.cycles} slow
+          </p>
+          <p style={{ color: 'var(--text-muted)', marginBottom: 16 }}>
+            list {debugInfo.scan_stats.last_listed_by_control ? 'from control mode' : `${debugInfo.scan_stats.last_phases.list_ms}ms`} · ps {debugInfo.scan_stats.last_phases.ps_ms === null ? 'skipped' : `${debugInfo.scan_stats.last_phases.ps_ms}ms`} · analyze {debugInfo.scan_stats.last_phases.analyze_ms}ms · apply {debugInfo.scan_stats.last_phases.apply_ms}ms · VS Code {debugInfo.scan_stats.last_phases.vscode_ms}ms · pane children {debugInfo.scan_stats.last_children_cached} cached / {debugInfo.scan_stats.last_children_read} read
+          </p>
+
           <h3 style={{ color: 'var(--accent-blue)', marginBottom: 8 }}>Hook Timestamps (Grace Period)</h3>
           {debugInfo.hook_timestamps.length === 0 ? (
             <p style={{ color: 'var(--text-muted)', marginBottom: 16 }}>No hook timestamps recorded</p>
@@ -0,0 +1,108 @@
+import { useState, useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import type { WeeklyDigest } from '../types';
+
+interface DigestModalProps {
+  isOpen: boolean;
+  onClose: () => void;
+}
+
+function formatHours(secs: number): string {
+  return `${(secs / 3600).toFixed(1)} h`;
+}
+
+function formatWait(secs: number): string {
+  if (secs < 3600) return `${Math.floor(secs / 60)} min`;
+  return `${Math.floor(secs / 3600)} h ${Math.floor((secs % 3600) / 60)} min`;
+}
+
+function formatTokens(count: number): string {
+  if (count < 1000) return String(count);
+  if (count < 1_000_000) return `${Math.floor(count / 1000)}k`;
+  return `${(count / 1_000_000).toFixed(1)}M`;
+}
+
+function formatDay(date: string): string {
+  return new Date(`${date}T00:00:00`).toLocaleDateString(undefined, { month: 'short', day: 'numeric' });
+}
+
+export function DigestModal({ isOpen, onClose }: DigestModalProps) {
+  const [digest, setDigest] = useState<WeeklyDigest | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    setError(null);
+    invoke<WeeklyDigest>('get_weekly_digest').then(setDigest).catch((e) => setError(String(e)));
+  }, [isOpen]);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    const handleKeyDown = (e: KeyboardEvent) => {
+      if (e.key === 'Escape') onClose();
+    };
+    window.addEventListener('keydown', handleKeyDown);
+    return () => window.removeEventListener('keydown', handleKeyDown);
+  }, [isOpen, onClose]);
+
+  if (!isOpen) return null;
+
+  return (
+    <div className="settings-overlay" onClick={onClose}>
+      <div className="settings-modal" onClick={(e) => e.stopPropagation()}>
+        <div className="settings-header">
+          <h2>Weekly Digest{digest && ` · ${formatDay(digest.start)} – ${formatDay(digest.end)}`}</h2>
+          <button className="settings-close" onClick={onClose}>×</button>
+        </div>
+
+        <div className="settings-content">
+          {error && <div className="setup-message error">{error}</div>}
+          {digest && (
+            <>
+              <div className="settings-group">
+                <label className="settings-label">Sessions</label>
+                <p className="settings-description">
+                  {digest.sessions} sessions, {formatHours(digest.activeSecs)} working and{' '}
+                  {formatHours(digest.waitingSecs)} waiting on you.
+                </p>
+              </div>
+
+              <div className="settings-group">
+                <label className="settings-label">Top Projects</label>
+                {digest.topProjects.length === 0 && <p className="settings-description">No sessions last week.</p>}
+                {digest.topProjects.map((project) => (
+                  <div key={project.projectPath ?? project.projectName} className="settings-timing-row">
+                    <span title={project.projectPath ?? undefined}>{project.projectName}</span>
+                    <span>
+                      {formatHours(project.activeSecs)} working · {formatHours(project.waitingSecs)} waiting
+                    </span>
+                  </div>
+                ))}
+              </div>
+
+              <div className="settings-group">
+                <label className="settings-label">Tokens</label>
+                <p className="settings-description">
+                  Claude Code: {formatTokens(digest.tokens.input + digest.tokens.cacheCreation)} in,{' '}
+                  {formatTokens(digest.tokens.output)} out, {formatTokens(digest.tokens.cacheRead)} read from cache.
+                  Other agents don't record usage where C3 can read it.
+                </p>
+              </div>
+
+              <div className="settings-group">
+                <label className="settings-label">Longest Waits</label>
+                {digest.longestWaits.length === 0 && <p className="settings-description">No agent waited on you.</p>}
+                {digest.longestWaits.map((wait) => (
+                  <div key={`${wait.sessionId}-${wait.started}`} className="settings-timing-row">
+                    <span>{wait.projectName}</span>
+                    <span title={new Date(wait.started).toLocaleString()}>{formatWait(wait.secs)}</span>
+                  </div>
+                ))}
+              </div>
+            </>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
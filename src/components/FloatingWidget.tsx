@@ -0,0 +1,61 @@
+import { useEffect } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { GripVertical } from 'lucide-react';
+import { initializeSessionListeners, useSessionStore } from '../stores/sessions';
+import { STATE_COLORS } from '../types';
+import type { SessionState } from '../types';
+import '../App.css';
+
+const COUNTS: { label: string; title: string; states: SessionState[] }[] = [
+  { label: '!', title: 'Need permission', states: ['awaiting_permission'] },
+  { label: '○', title: 'Awaiting input', states: ['awaiting_input'] },
+  { label: '↻', title: 'Working', states: ['processing', 'spawning'] },
+  { label: '×', title: 'Errored', states: ['error'] },
+];
+
+/**
+ * Always-on-top strip with session counts by state. Drag by the grip; click
+ * the counts to open the full window.
+ */
+export function FloatingWidget() {
+  const sessions = useSessionStore((state) => state.sessions);
+  const syncSessions = useSessionStore((state) => state.syncSessions);
+
+  useEffect(() => {
+    // The main window plays hook sounds; don't double them up
+    initializeSessionListeners({ sounds: false });
+    const interval = setInterval(() => {
+      syncSessions();
+    }, 5000);
+    return () => clearInterval(interval);
+  }, [syncSessions]);
+
+  const all = Object.values(sessions);
+
+  return (
+    <div className="floating-widget">
+      <span className="floating-widget-grip" data-tauri-drag-region title="Drag to move">
+        <GripVertical size={14} />
+      </span>
+      <button
+        className="floating-widget-counts"
+        onClick={() => invoke('open_main_window').catch(() => {})}
+        title="Open C3"
+      >
+        {COUNTS.map(({ label, title, states }) => {
+          const count = all.filter((s) => states.includes(s.state)).length;
+          return (
+            <span
+              key={title}
+              className={`floating-widget-count ${count === 0 ? 'empty' : ''}`}
+              style={{ color: STATE_COLORS[states[0]] }}
+              title={`${count} ${title.toLowerCase()}`}
+            >
+              {label} {count}
+            </span>
+          );
+        })}
+      </button>
+    </div>
+  );
+}
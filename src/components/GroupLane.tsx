@@ -1,7 +1,8 @@
 import { useEffect, useState } from 'react';
-import { ChevronDown, ChevronRight, Edit3, Folder, Trash2 } from 'lucide-react';
+import { Bell, BellOff, ChevronDown, ChevronRight, Edit3, Folder, Trash2 } from 'lucide-react';
 import { useSessionStore } from '../stores/sessions';
 import { SessionCard } from './SessionCard';
+import { compareSessions } from '../types';
 import type { C3Session, SessionGroup } from '../types';
 
 interface GroupLaneProps {
@@ -29,11 +30,13 @@ function getDraggedSessionId(dataTransfer: DataTransfer): string {
 
 export function GroupLane({ group, sessions, shortcutMap = {}, onEdit }: GroupLaneProps) {
   const deleteGroup = useSessionStore((state) => state.deleteGroup);
+  const setGroupMuted = useSessionStore((state) => state.setGroupMuted);
   const assignSessionGroup = useSessionStore((state) => state.assignSessionGroup);
   const draggingSessionId = useSessionStore((state) => state.draggingSessionId);
   const dragTargetGroupId = useSessionStore((state) => state.dragTargetGroupId);
   const setDragTargetGroupId = useSessionStore((state) => state.setDragTargetGroupId);
   const clearSessionDrag = useSessionStore((state) => state.clearSessionDrag);
+  const sessionMeta = useSessionStore((state) => state.sessionMeta);
   const storageKey = `c3-lane-collapsed-group-${group.id}`;
   const [isCollapsed, setIsCollapsed] = useState(() => {
     const stored = localStorage.getItem(storageKey);
@@ -45,9 +48,7 @@ export function GroupLane({ group, sessions, shortcutMap = {}, onEdit }: GroupLa
     localStorage.setItem(storageKey, String(isCollapsed));
   }, [isCollapsed, storageKey]);
 
-  const sortedSessions = [...sessions].sort(
-    (a, b) => new Date(b.lastActivity).getTime() - new Date(a.lastActivity).getTime()
-  );
+  const sortedSessions = [...sessions].sort(compareSessions(sessionMeta));
 
   const handleEdit = (e: React.MouseEvent) => {
     e.stopPropagation();
@@ -61,6 +62,18 @@ export function GroupLane({ group, sessions, shortcutMap = {}, onEdit }: GroupLa
     onEdit(group);
   };
 
+  const handleMute = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setGroupMuted(group.id, !group.muted);
+  };
+
+  const handleMuteKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key !== 'Enter' && e.key !== ' ') return;
+    e.preventDefault();
+    e.stopPropagation();
+    setGroupMuted(group.id, !group.muted);
+  };
+
   const handleDelete = async (e: React.MouseEvent) => {
     e.stopPropagation();
     const confirmed = window.confirm(`Delete "${group.name}" and return its sessions to normal lanes?`);
@@ -132,6 +145,16 @@ export function GroupLane({ group, sessions, shortcutMap = {}, onEdit }: GroupLa
         <span className="lane-title">{group.name}</span>
         <span className="lane-count">{sortedSessions.length}</span>
         <span className="group-lane-actions">
+          <span
+            role="button"
+            tabIndex={0}
+            className={`group-lane-action ${group.muted ? 'active' : ''}`}
+            title={group.muted ? 'Unmute notifications' : 'Mute notifications'}
+            onClick={handleMute}
+            onKeyDown={handleMuteKeyDown}
+          >
+            {group.muted ? <BellOff size={13} /> : <Bell size={13} />}
+          </span>
           <span
             role="button"
             tabIndex={0}
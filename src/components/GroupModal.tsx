@@ -64,6 +64,7 @@ export function GroupModal({ isOpen, group, onClose }: GroupModalProps) {
       color,
       matchText: matchPreview,
       createdAt: group?.createdAt || new Date().toISOString(),
+      muted: group?.muted ?? false,
     });
     onClose();
   };
@@ -50,8 +50,9 @@ export function Header({ onSearchClick, onSettingsClick }: HeaderProps) {
   const processingCount = sessionList.filter(
     (s) => s.state === 'processing' || s.state === 'spawning'
   ).length;
+  const hookNote = updater.hooksOutdated ? ' — reinstall hooks from Settings afterwards' : '';
   const updateTitle = updater.status === 'available'
-    ? `Update available: ${updater.currentVersion} → ${updater.latestVersion}`
+    ? `${updater.staged ? 'Update downloaded' : 'Update available'}: ${updater.currentVersion} → ${updater.latestVersion}${hookNote}`
     : updater.status === 'checking'
       ? 'Checking for updates'
       : updater.status === 'error'
@@ -10,6 +10,8 @@ import {
   Pin,
 } from 'lucide-react';
 import { SessionCard } from './SessionCard';
+import { useSessionStore } from '../stores/sessions';
+import { compareSessions } from '../types';
 import type { C3Session, Lane as LaneType } from '../types';
 
 interface LaneProps {
@@ -28,6 +30,7 @@ const LANE_ICONS: Record<string, React.ReactNode> = {
 };
 
 export function Lane({ lane, sessions, shortcutMap = {} }: LaneProps) {
+  const sessionMeta = useSessionStore((state) => state.sessionMeta);
   const storageKey = `c3-lane-collapsed-${lane.id}`;
   const [isCollapsed, setIsCollapsed] = useState(() => {
     const stored = localStorage.getItem(storageKey);
@@ -36,7 +39,7 @@ export function Lane({ lane, sessions, shortcutMap = {} }: LaneProps) {
 
   const filteredSessions = sessions
     .filter((s) => lane.states.includes(s.state))
-    .sort((a, b) => new Date(b.lastActivity).getTime() - new Date(a.lastActivity).getTime());
+    .sort(compareSessions(sessionMeta));
 
   useEffect(() => {
     localStorage.setItem(storageKey, String(isCollapsed));
@@ -1,6 +1,8 @@
 import { useState, useEffect } from 'react';
 import { ChevronRight, ChevronDown, Pin } from 'lucide-react';
 import { SessionCard } from './SessionCard';
+import { useSessionStore } from '../stores/sessions';
+import { compareSessions } from '../types';
 import type { C3Session } from '../types';
 
 interface PinnedLaneProps {
@@ -9,6 +11,7 @@ interface PinnedLaneProps {
 }
 
 export function PinnedLane({ sessions, shortcutMap = {} }: PinnedLaneProps) {
+  const sessionMeta = useSessionStore((state) => state.sessionMeta);
   const storageKey = 'c3-lane-collapsed-pinned';
   const [isCollapsed, setIsCollapsed] = useState(() => {
     const stored = localStorage.getItem(storageKey);
@@ -19,9 +22,7 @@ export function PinnedLane({ sessions, shortcutMap = {} }: PinnedLaneProps) {
     localStorage.setItem(storageKey, String(isCollapsed));
   }, [isCollapsed]);
 
-  const sortedSessions = [...sessions].sort(
-    (a, b) => new Date(b.lastActivity).getTime() - new Date(a.lastActivity).getTime()
-  );
+  const sortedSessions = [...sessions].sort(compareSessions(sessionMeta));
 
   if (sortedSessions.length === 0) {
     return null;
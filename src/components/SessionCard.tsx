@@ -1,8 +1,10 @@
 import { useState, useEffect, useRef } from 'react';
 import { createPortal } from 'react-dom';
-import { Terminal, ChevronDown, CircleSlash, FolderInput, Pin, PinOff, Tag, Trash2 } from 'lucide-react';
+import { Terminal, Bell, BellOff, Check, ChevronDown, CircleSlash, CornerDownRight, ExternalLink, FileDown, FolderInput, Laptop, ListOrdered, Maximize2, Pin, PinOff, RefreshCw, RotateCcw, Send, StickyNote, Tag, Trash2 } from 'lucide-react';
+import { save } from '@tauri-apps/plugin-dialog';
+import { openUrl } from '@tauri-apps/plugin-opener';
 import { useSessionStore } from '../stores/sessions';
-import type { C3Session } from '../types';
+import type { C3Session, SessionMetrics } from '../types';
 import { STATE_COLORS } from '../types';
 
 interface SessionCardProps {
@@ -74,12 +76,14 @@ function getStateTitle(state: string): string {
   }
 }
 
-function getAgentLabel(agentKind: string | undefined): string {
+function getAgentLabel(agentKind: string | null): string {
   switch (agentKind) {
     case 'codex':
       return 'Codex';
     case 'omp':
       return 'OMP';
+    case 'aider':
+      return 'Aider';
     case 'claude':
       return 'Claude';
     default:
@@ -87,6 +91,21 @@ function getAgentLabel(agentKind: string | undefined): string {
   }
 }
 
+function getPullRequestTitle(pr: NonNullable<C3Session['pullRequest']>): string {
+  const review = pr.reviewDecision ? `, ${pr.reviewDecision.toLowerCase().replace('_', ' ')}` : '';
+  return `PR #${pr.number}${pr.isDraft ? ' (draft)' : ''}: ${pr.title} — CI ${pr.ci}${review}`;
+}
+
+function formatMemory(bytes: number): string {
+  const mb = bytes / (1024 * 1024);
+  return mb < 1024 ? `${Math.floor(mb)} MB` : `${(mb / 1024).toFixed(1)} GB`;
+}
+
+function resourcesTitle(metrics: SessionMetrics): string {
+  const cpu = metrics.cpuPercent != null ? `, ${metrics.cpuPercent}% CPU` : '';
+  return `Agent PID ${metrics.pid}: ${formatMemory(metrics.rssBytes ?? 0)} memory${cpu}`;
+}
+
 function truncateCommand(cmd: string | undefined, maxLength: number = 60): string {
   if (!cmd) return '';
   if (cmd.length <= maxLength) return cmd;
@@ -95,27 +114,46 @@ function truncateCommand(cmd: string | undefined, maxLength: number = 60): strin
 
 export function SessionCard({ session, shortcut }: SessionCardProps) {
   const focusSession = useSessionStore((state) => state.focusSession);
+  const answerQuestion = useSessionStore((state) => state.answerQuestion);
+  const zoomPane = useSessionStore((state) => state.zoomPane);
   const requestKillSession = useSessionStore((state) => state.requestKillSession);
   const selectSession = useSessionStore((state) => state.selectSession);
   const selectedSessionId = useSessionStore((state) => state.selectedSessionId);
   const sessionMeta = useSessionStore((state) => state.sessionMeta);
   const groups = useSessionStore((state) => state.groups);
   const setSessionTag = useSessionStore((state) => state.setSessionTag);
+  const mutedTags = useSessionStore((state) => state.mutedTags);
+  const setTagMuted = useSessionStore((state) => state.setTagMuted);
+  const setSessionNote = useSessionStore((state) => state.setSessionNote);
+  const setNextPrompts = useSessionStore((state) => state.setNextPrompts);
+  const sendNextPrompt = useSessionStore((state) => state.sendNextPrompt);
   const setSessionPinned = useSessionStore((state) => state.setSessionPinned);
   const assignSessionGroup = useSessionStore((state) => state.assignSessionGroup);
   const setDraggingSessionId = useSessionStore((state) => state.setDraggingSessionId);
   const setDragTargetGroupId = useSessionStore((state) => state.setDragTargetGroupId);
   const clearSessionDrag = useSessionStore((state) => state.clearSessionDrag);
+  const reorderSessions = useSessionStore((state) => state.reorderSessions);
+  const exportTranscript = useSessionStore((state) => state.exportTranscript);
+  const exportSessionBundle = useSessionStore((state) => state.exportSessionBundle);
+  const openSessionWindow = useSessionStore((state) => state.openSessionWindow);
+  const resumeSession = useSessionStore((state) => state.resumeSession);
+  const resyncSession = useSessionStore((state) => state.resyncSession);
 
   const [isHovered, setIsHovered] = useState(false);
   const [menuOpen, setMenuOpen] = useState(false);
   const [tagInput, setTagInput] = useState('');
   const [showTagInput, setShowTagInput] = useState(false);
+  const [noteInput, setNoteInput] = useState('');
+  const [showNoteInput, setShowNoteInput] = useState(false);
+  const [promptsInput, setPromptsInput] = useState('');
+  const [showPromptsInput, setShowPromptsInput] = useState(false);
   const [isDragging, setIsDragging] = useState(false);
   const [dragPreview, setDragPreview] = useState<{ x: number; y: number; width: number } | null>(null);
   const cardRef = useRef<HTMLDivElement>(null);
   const menuRef = useRef<HTMLDivElement>(null);
   const tagInputRef = useRef<HTMLInputElement>(null);
+  const noteInputRef = useRef<HTMLTextAreaElement>(null);
+  const promptsInputRef = useRef<HTMLTextAreaElement>(null);
   const ignoreNextClickRef = useRef(false);
   const pointerStartRef = useRef<{ x: number; y: number; pointerId: number } | null>(null);
   const pointerDraggingRef = useRef(false);
@@ -124,6 +162,9 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
   const isSelected = selectedSessionId === session.id;
   const isPinned = meta.pinned;
   const tag = meta.tag;
+  const notes = meta.notes;
+  const nextPrompts = meta.nextPrompts ?? [];
+  const nextPromptsText = nextPrompts.join('\n');
   const group = groups.find((candidate) => candidate.id === meta.groupId);
 
   useEffect(() => {
@@ -140,6 +181,8 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
       if (menuRef.current && !menuRef.current.contains(e.target as Node)) {
         setMenuOpen(false);
         setShowTagInput(false);
+        setShowNoteInput(false);
+        setShowPromptsInput(false);
       }
     };
 
@@ -155,6 +198,22 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
     }
   }, [showTagInput, tag]);
 
+  // Focus note input when shown
+  useEffect(() => {
+    if (showNoteInput && noteInputRef.current) {
+      noteInputRef.current.focus();
+      setNoteInput(notes || '');
+    }
+  }, [showNoteInput, notes]);
+
+  // Focus queued prompts input when shown, one prompt per line
+  useEffect(() => {
+    if (showPromptsInput && promptsInputRef.current) {
+      promptsInputRef.current.focus();
+      setPromptsInput(nextPromptsText);
+    }
+  }, [showPromptsInput, nextPromptsText]);
+
   useEffect(() => {
     if (!isDragging) return;
 
@@ -180,6 +239,8 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
     e.stopPropagation();
     selectSession(session.id);
     setShowTagInput(false);
+    setShowNoteInput(false);
+    setShowPromptsInput(false);
     setMenuOpen(true);
   };
 
@@ -193,6 +254,8 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
     e.stopPropagation();
     setMenuOpen(!menuOpen);
     setShowTagInput(false);
+    setShowNoteInput(false);
+    setShowPromptsInput(false);
   };
 
   const handlePin = (e: React.MouseEvent) => {
@@ -219,18 +282,143 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
     }
   };
 
+  const isTagMuted = Boolean(tag && mutedTags.includes(tag));
+
+  const handleTagMute = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (tag) setTagMuted(tag, !isTagMuted);
+    setMenuOpen(false);
+  };
+
+  const handleNoteClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setShowNoteInput(true);
+  };
+
+  const handleNoteSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    setSessionNote(session.id, noteInput);
+    setShowNoteInput(false);
+    setMenuOpen(false);
+  };
+
+  const handleNoteKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Escape') {
+      setShowNoteInput(false);
+    } else if (e.key === 'Enter' && (e.metaKey || e.ctrlKey)) {
+      handleNoteSubmit(e);
+    }
+  };
+
+  const handlePromptsClick = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setShowPromptsInput(true);
+  };
+
+  const handlePromptsSubmit = (e: React.FormEvent) => {
+    e.preventDefault();
+    setNextPrompts(session.id, promptsInput.split('\n'));
+    setShowPromptsInput(false);
+    setMenuOpen(false);
+  };
+
+  const handlePromptsKeyDown = (e: React.KeyboardEvent) => {
+    if (e.key === 'Escape') {
+      setShowPromptsInput(false);
+    } else if (e.key === 'Enter' && (e.metaKey || e.ctrlKey)) {
+      handlePromptsSubmit(e);
+    }
+  };
+
+  const handleSendNextPrompt = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    sendNextPrompt(session.id);
+  };
+
   const handleGroupAssign = async (e: React.MouseEvent, groupId: string | null) => {
     e.stopPropagation();
     await assignSessionGroup(session.id, groupId, 'manual');
     setMenuOpen(false);
   };
 
+  const handlePopOut = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    openSessionWindow(session.id);
+  };
+
+  const handleFocusZoomed = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    await focusSession(session.id);
+    if (session.tmuxTarget) {
+      zoomPane(session.tmuxTarget, true);
+    }
+  };
+
+  const handleResume = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    resumeSession(session.id);
+  };
+
+  const handleResync = (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    resyncSession(session.id);
+  };
+
+  const handleExportTranscript = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    const path = await save({
+      defaultPath: `${session.projectName}-transcript.md`,
+      filters: [
+        { name: 'Markdown', extensions: ['md'] },
+        { name: 'HTML', extensions: ['html'] },
+      ],
+    });
+    if (!path) return;
+    const format = /\.html?$/i.test(path) ? 'html' : 'markdown';
+    try {
+      await exportTranscript(session.id, format, path);
+    } catch {
+      // Logged by the store
+    }
+  };
+
+  const handleHandOff = async (e: React.MouseEvent) => {
+    e.stopPropagation();
+    setMenuOpen(false);
+    const path = await save({
+      defaultPath: `${session.projectName}-session.zip`,
+      filters: [{ name: 'C3 session bundle', extensions: ['zip'] }],
+    });
+    if (!path) return;
+    try {
+      await exportSessionBundle(session.id, path);
+    } catch {
+      // Logged by the store
+    }
+  };
+
   const targetGroupIdAtPoint = (clientX: number, clientY: number): string | null => {
     const element = document.elementFromPoint(clientX, clientY);
     const groupElement = element?.closest<HTMLElement>('[data-group-id]');
     return groupElement?.dataset.groupId || null;
   };
 
+  // Another card in the same lane, for drag-reorder
+  const siblingCardAtPoint = (clientX: number, clientY: number): HTMLElement | null => {
+    const element = document.elementFromPoint(clientX, clientY);
+    const card = element?.closest<HTMLElement>('[data-session-id]');
+    if (!card || card === cardRef.current || card.parentElement !== cardRef.current?.parentElement) {
+      return null;
+    }
+    return card;
+  };
+
   const shouldSkipPointerDrag = (target: EventTarget | null): boolean => {
     if (!(target instanceof Element)) return false;
     return Boolean(target.closest('button, input, textarea, .session-menu, .session-menu-container'));
@@ -275,11 +463,23 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
       ignoreNextClickRef.current = false;
     }, 0);
 
+    const siblingCard = cancelled ? null : siblingCardAtPoint(e.clientX, e.clientY);
     const groupId = cancelled ? null : targetGroupIdAtPoint(e.clientX, e.clientY);
     setIsDragging(false);
     setDragPreview(null);
     clearSessionDrag();
 
+    if (siblingCard?.parentElement) {
+      const laneIds = Array.from(
+        siblingCard.parentElement.querySelectorAll<HTMLElement>(':scope > [data-session-id]')
+      ).map((card) => card.dataset.sessionId!);
+      const targetIndex = laneIds.indexOf(siblingCard.dataset.sessionId!);
+      const reordered = laneIds.filter((id) => id !== session.id);
+      reordered.splice(targetIndex, 0, session.id);
+      await reorderSessions(reordered);
+      return;
+    }
+
     if (groupId) {
       await assignSessionGroup(session.id, groupId, 'manual');
     }
@@ -331,6 +531,7 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
         ref={cardRef}
         className={`session-card ${isSelected ? 'selected' : ''} ${isStale ? 'stale' : ''} ${isPinned ? 'pinned' : ''} ${isDragging ? 'dragging' : ''}`}
         data-state={session.state}
+        data-session-id={session.id}
         draggable={false}
         onClick={handleClick}
         onContextMenu={handleContextMenu}
@@ -362,11 +563,28 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
             <span className={`session-agent-badge agent-${session.agentKind || 'unknown'}`}>
               {getAgentLabel(session.agentKind)}
             </span>
+            {session.yolo && (
+              <span className="session-yolo-badge" title="Started with permission prompts turned off">
+                YOLO
+              </span>
+            )}
             {tag && (
               <span className="session-tag" title={tag}>
                 {tag}
               </span>
             )}
+            {session.pullRequest && (
+              <button
+                className={`session-pr-badge ci-${session.pullRequest.ci}`}
+                title={getPullRequestTitle(session.pullRequest)}
+                onClick={(e) => {
+                  e.stopPropagation();
+                  void openUrl(session.pullRequest!.url);
+                }}
+              >
+                #{session.pullRequest.number}
+              </button>
+            )}
             {group && (
               <span
                 className="session-group-badge"
@@ -377,11 +595,15 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
               </span>
             )}
             <span
-              className={`session-state-badge state-${session.state}`}
+              className={`session-state-badge state-${session.state}${session.stalled ? ' stalled' : ''}`}
               style={{ borderColor: color, color: color }}
-              title={getStateTitle(session.state)}
+              title={
+                session.stalled
+                  ? 'Working while this Mac is offline; the agent is most likely retrying'
+                  : getStateTitle(session.state)
+              }
             >
-              {getStateLabel(session.state)}
+              {session.stalled ? 'Stalled (offline)' : getStateLabel(session.state)}
             </span>
           </div>
         </div>
@@ -397,11 +619,40 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
               </span>
             </span>
           )}
+          {session.metrics?.rssBytes != null && (
+            <span className="session-resources" title={resourcesTitle(session.metrics)}>
+              {formatMemory(session.metrics.rssBytes)}
+            </span>
+          )}
           <span className={`session-time ${isRecentlyActive ? 'recent' : ''}`}>
             {timeAgo}
           </span>
         </div>
 
+        {session.summary && session.state !== 'processing' && session.state !== 'awaiting_permission' && (
+          <div className="session-note" title={session.summary}>
+            <Check size={10} />
+            <span>{session.summary}</span>
+          </div>
+        )}
+
+        {notes && (
+          <div className="session-note" title={notes}>
+            <StickyNote size={10} />
+            <span>{notes}</span>
+          </div>
+        )}
+
+        {nextPrompts.length > 0 && (
+          <div className="session-note" title={nextPromptsText}>
+            <CornerDownRight size={10} />
+            <span>
+              Next: {nextPrompts[0]}
+              {nextPrompts.length > 1 && ` (+${nextPrompts.length - 1})`}
+            </span>
+          </div>
+        )}
+
         {isPermission && session.pendingAction && (
           <div className="session-action">
             <span className="action-tool">
@@ -413,6 +664,23 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
           </div>
         )}
 
+        {(session.pendingAction?.options.length ?? 0) > 0 && (
+          <div className="session-options" title={session.pendingAction!.description}>
+            {session.pendingAction!.options.map((option, i) => (
+              <button
+                key={option}
+                className="session-option"
+                onClick={(e) => {
+                  e.stopPropagation();
+                  answerQuestion(session.id, i);
+                }}
+              >
+                {option}
+              </button>
+            ))}
+          </div>
+        )}
+
       </div>
 
       <div className="session-actions">
@@ -450,16 +718,96 @@ export function SessionCard({ session, shortcut }: SessionCardProps) {
                     Save
                   </button>
                 </form>
+              ) : showNoteInput ? (
+                <form onSubmit={handleNoteSubmit} className="tag-input-form note-input-form">
+                  <textarea
+                    ref={noteInputRef}
+                    value={noteInput}
+                    onChange={(e) => setNoteInput(e.target.value)}
+                    onKeyDown={handleNoteKeyDown}
+                    placeholder="e.g. waiting on design answer"
+                    className="tag-input note-input"
+                    rows={3}
+                  />
+                  <button type="submit" className="tag-submit">
+                    Save
+                  </button>
+                </form>
+              ) : showPromptsInput ? (
+                <form onSubmit={handlePromptsSubmit} className="tag-input-form note-input-form">
+                  <textarea
+                    ref={promptsInputRef}
+                    value={promptsInput}
+                    onChange={(e) => setPromptsInput(e.target.value)}
+                    onKeyDown={handlePromptsKeyDown}
+                    placeholder={'One per line, sent after each finish, e.g.\nnow write tests\nnow update the changelog'}
+                    className="tag-input note-input"
+                    rows={4}
+                  />
+                  <button type="submit" className="tag-submit">
+                    Save
+                  </button>
+                </form>
               ) : (
                 <>
                   <button className="session-menu-item" onClick={handleTagClick}>
                     <Tag size={14} />
                     <span>{tag ? 'Edit tag' : 'Add tag'}</span>
                   </button>
+                  {tag && (
+                    <button className="session-menu-item" onClick={handleTagMute}>
+                      {isTagMuted ? <Bell size={14} /> : <BellOff size={14} />}
+                      <span>{isTagMuted ? `Unmute tag "${tag}"` : `Mute tag "${tag}"`}</span>
+                    </button>
+                  )}
+                  <button className="session-menu-item" onClick={handleNoteClick}>
+                    <StickyNote size={14} />
+                    <span>{notes ? 'Edit note' : 'Add note'}</span>
+                  </button>
+                  <button className="session-menu-item" onClick={handlePromptsClick}>
+                    <ListOrdered size={14} />
+                    <span>{nextPrompts.length > 0 ? 'Edit next prompts' : 'Queue next prompts'}</span>
+                  </button>
+                  {nextPrompts.length > 0 && session.tmuxTarget && !isProcessing && !isPermission && (
+                    <button className="session-menu-item" onClick={handleSendNextPrompt}>
+                      <Send size={14} />
+                      <span>Send next prompt</span>
+                    </button>
+                  )}
                   <button className="session-menu-item" onClick={handlePin}>
                     {isPinned ? <PinOff size={14} /> : <Pin size={14} />}
                     <span>{isPinned ? 'Unpin' : 'Pin'}</span>
                   </button>
+                  <button className="session-menu-item" onClick={handleResync}>
+                    <RefreshCw size={14} />
+                    <span>Refresh state</span>
+                  </button>
+                  <button className="session-menu-item" onClick={handleExportTranscript}>
+                    <FileDown size={14} />
+                    <span>Export transcript…</span>
+                  </button>
+                  {session.tmuxTarget && (
+                    <button className="session-menu-item" onClick={handleFocusZoomed}>
+                      <Maximize2 size={14} />
+                      <span>Focus zoomed</span>
+                    </button>
+                  )}
+                  {session.id.startsWith('claude:') && (
+                    <button className="session-menu-item" onClick={handleHandOff}>
+                      <Laptop size={14} />
+                      <span>Hand off to another machine…</span>
+                    </button>
+                  )}
+                  <button className="session-menu-item" onClick={handlePopOut}>
+                    <ExternalLink size={14} />
+                    <span>Open in window</span>
+                  </button>
+                  {(isComplete || session.state === 'error') && session.projectPath && (
+                    <button className="session-menu-item" onClick={handleResume}>
+                      <RotateCcw size={14} />
+                      <span>Resume conversation</span>
+                    </button>
+                  )}
                   <div className="session-menu-divider" />
                   <div className="session-menu-label">Send to Group</div>
                   {groups.length === 0 ? (
@@ -0,0 +1,247 @@
+import { useCallback, useEffect, useRef, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { Check, CheckCheck, Send, Terminal, X } from 'lucide-react';
+import type { C3Session, SessionTail } from '../types';
+import { STATE_COLORS } from '../types';
+import '../App.css';
+
+/** How often the transcript tail and pane preview are refreshed */
+const TAIL_POLL_MS = 2000;
+
+function getStateTitle(state: string): string {
+  switch (state) {
+    case 'awaiting_permission':
+      return 'Permission required';
+    case 'awaiting_input':
+      return 'Idle';
+    case 'processing':
+    case 'spawning':
+      return 'Working';
+    case 'complete':
+      return 'Complete';
+    case 'error':
+      return 'Error';
+    default:
+      return state;
+  }
+}
+
+interface SessionWindowProps {
+  sessionId: string;
+}
+
+/**
+ * Pop-out window for one session: state, transcript tail, pane preview and
+ * actions. The backend only routes this session's updates here.
+ */
+export function SessionWindow({ sessionId: openedId }: SessionWindowProps) {
+  const [session, setSession] = useState<C3Session | null>(null);
+  const [ended, setEnded] = useState(false);
+  const [tail, setTail] = useState<SessionTail | null>(null);
+  const [reply, setReply] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const transcriptRef = useRef<HTMLDivElement>(null);
+
+  useEffect(() => {
+    invoke<C3Session[]>('get_sessions')
+      .then((sessions) => {
+        const found = sessions.find((s) => s.id === openedId);
+        if (found) setSession(found);
+        else setEnded(true);
+      })
+      .catch((e) => setError(String(e)));
+
+    const unlisten = listen<C3Session | null>('session-window-update', (event) => {
+      if (event.payload) {
+        setSession(event.payload);
+        setEnded(false);
+      } else {
+        setEnded(true);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [openedId]);
+
+  // The session can move to a new ID (tmux server restart); updates still arrive here
+  const sessionId = session?.id ?? openedId;
+
+  const refreshTail = useCallback(() => {
+    invoke<SessionTail>('get_session_tail', { sessionId })
+      .then(setTail)
+      .catch(() => {});
+  }, [sessionId]);
+
+  useEffect(() => {
+    if (ended) return;
+    refreshTail();
+    const interval = setInterval(refreshTail, TAIL_POLL_MS);
+    return () => clearInterval(interval);
+  }, [ended, refreshTail]);
+
+  // The allowlist rule "Always allow" suggests for the pending request, and
+  // the rule being edited before it's added
+  const [allowRule, setAllowRule] = useState<string | null>(null);
+  const [editedRule, setEditedRule] = useState<string | null>(null);
+  const pendingCommand = session?.pendingAction?.command;
+  const pendingTool = session?.pendingAction?.tool;
+  useEffect(() => {
+    setAllowRule(null);
+    setEditedRule(null);
+    if (session?.state !== 'awaiting_permission') return;
+    invoke<string | null>('suggest_allow_rule', { sessionId })
+      .then(setAllowRule)
+      .catch(() => {});
+  }, [sessionId, session?.state, pendingTool, pendingCommand]);
+
+  // Keep the newest message in view
+  useEffect(() => {
+    const el = transcriptRef.current;
+    if (el) el.scrollTop = el.scrollHeight;
+  }, [tail?.messages.length]);
+
+  const run = async (command: string, args: Record<string, unknown>) => {
+    setError(null);
+    try {
+      await invoke(command, args);
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  const handleSend = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!reply.trim()) return;
+    await run('send_session_input', { sessionId, text: reply });
+    setReply('');
+    refreshTail();
+  };
+
+  if (!session) {
+    return <div className="session-window-empty">{error || 'Loading session…'}</div>;
+  }
+
+  const color = STATE_COLORS[session.state];
+  const action = session.pendingAction;
+
+  return (
+    <div className="session-window">
+      <div className="session-window-header">
+        <div className="session-window-title">
+          <span className="session-window-name">{session.projectName}</span>
+          <span className="session-window-path" title={session.projectPath ?? undefined}>
+            {session.projectPath}
+          </span>
+        </div>
+        <span className="session-window-state" style={{ borderColor: color, color }}>
+          {ended ? 'Ended' : getStateTitle(session.state)}
+        </span>
+      </div>
+
+      {session.state === 'awaiting_permission' && action && !ended && (
+        <div className="session-window-permission">
+          <span>
+            {action.tool ? `${action.tool}: ` : ''}
+            {action.command || action.description}
+          </span>
+          <button className="settings-btn primary" onClick={() => run('approve_session', { sessionId })}>
+            <Check size={14} /> Approve
+          </button>
+          <button className="settings-btn" onClick={() => run('deny_session', { sessionId })}>
+            <X size={14} /> Deny
+          </button>
+          {allowRule && editedRule === null && (
+            <button
+              className="settings-btn"
+              title="Review a rule for Claude's allowlist in ~/.claude/settings.json"
+              onClick={() => setEditedRule(allowRule)}
+            >
+              <CheckCheck size={14} /> Always allow…
+            </button>
+          )}
+          {editedRule !== null && (
+            <div className="session-window-options">
+              <input
+                type="text"
+                className="settings-input"
+                spellCheck={false}
+                value={editedRule}
+                onChange={(e) => setEditedRule(e.target.value)}
+              />
+              <button
+                className="settings-btn primary"
+                disabled={!editedRule.trim()}
+                onClick={() => run('always_allow_session', { sessionId, rule: editedRule })}
+              >
+                Add and approve
+              </button>
+              <button className="settings-btn" onClick={() => setEditedRule(null)}>
+                Cancel
+              </button>
+            </div>
+          )}
+        </div>
+      )}
+
+      {action && action.options.length > 0 && !ended && (
+        <div className="session-window-question">
+          <span>{action.description}</span>
+          <div className="session-window-options">
+            {action.options.map((option, i) => (
+              <button
+                key={option}
+                className="settings-btn"
+                onClick={() => run('answer_question', { sessionId, optionIndex: i })}
+              >
+                {option}
+              </button>
+            ))}
+          </div>
+        </div>
+      )}
+
+      <div className="session-window-transcript" ref={transcriptRef}>
+        {tail?.messages.length === 0 && (
+          <div className="session-window-empty">No transcript found</div>
+        )}
+        {tail?.messages.map((message, i) => (
+          <div key={i} className={`session-window-message ${message.role}`}>
+            {message.text}
+          </div>
+        ))}
+      </div>
+
+      {tail?.pane && <pre className="session-window-pane">{tail.pane}</pre>}
+
+      {error && <div className="session-window-error">{error}</div>}
+
+      <div className="session-window-actions">
+        <button
+          className="settings-btn"
+          onClick={() => run('focus_session', { sessionId })}
+          title="Focus the tmux pane"
+        >
+          <Terminal size={14} />
+        </button>
+        <form className="session-window-reply" onSubmit={handleSend}>
+          <input
+            className="settings-input"
+            value={reply}
+            onChange={(e) => setReply(e.target.value)}
+            placeholder={session.tmuxTarget ? 'Reply to the agent…' : 'Not running in tmux'}
+            disabled={!session.tmuxTarget || ended}
+          />
+          <button
+            type="submit"
+            className="settings-btn primary"
+            disabled={!session.tmuxTarget || ended || !reply.trim()}
+          >
+            <Send size={14} />
+          </button>
+        </form>
+      </div>
+    </div>
+  );
+}
@@ -1,8 +1,9 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
+import { listen } from '@tauri-apps/api/event';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { Volume2, Check, X, AlertTriangle, Download, RefreshCw } from 'lucide-react';
-import type { AppSettings, SoundConfig, HookStatus, SetupResult } from '../types';
+import type { AgentInfo, ApiToken, AppSettings, AtRestSettings, Arbitration, AutomationRule, AutomationRun, ChannelEvent, CreatedApiToken, CustomTerminal, DependencyProgress, EmailSettings, LanSettings, LanStatus, SoundConfig, HookStatus, ImportedSession, OnboardingProgress, OnboardingStep, ScheduleEntry, ScheduledTask, SetupResult, ProfileList, PermissionStats, PushSettings, QueuedLaunch, AllowlistUpdate, SessionState, SlaRule, SoundTheme, TelegramSettings, TimeReportRow, TokenScope, UninstallReport, WeeklyDigest } from '../types';
 
 interface SettingsModalProps {
   isOpen: boolean;
@@ -30,13 +31,121 @@ const SOUND_OPTIONS = [
 
 const defaultSettings: AppSettings = {
   terminal_app: 'auto',
+  terminal_bundle_id: null,
+  custom_terminals: [],
   default_agent: 'codex',
   notifications_enabled: true,
   permission_sound: { enabled: true, sound: null },
   input_sound: { enabled: true, sound: null },
   complete_sound: { enabled: false, sound: null },
+  scan_interval_secs: 3,
+  hook_grace_period_secs: 10,
+  state_arbitration: 'hook_grace_period',
+  hook_confidence_secs: 2,
+  scanner_confidence_secs: 0,
+  pane_content_detection: false,
+  battery_saver: false,
+  notification_debounce_ms: 1000,
+  redaction_patterns: [],
+  hook_port: 9398,
+  archive_complete_after_hours: 24,
+  hide_stale_after_days: 7,
+  ignored_paths: [],
+  ignored_tmux_sessions: [],
+  disabled_agents: [],
+  widget_shortcut: 'CmdOrCtrl+Shift+Period',
+  launch_at_login: false,
+  start_hidden: false,
+  update_channel: 'stable',
+  update_mode: 'prompt',
+  sla_rules: [],
+  automation_rules: [],
+  sound_theme: null,
+  project_sound_themes: [],
+  complete_actions: [],
+  alert_volume: 100,
+  alert_output_device: null,
+  tmux_attention_marker: false,
+  tmux_attention_style: 'bg=colour52',
+  tmux_window_names: false,
+  zoom_on_focus: false,
+  chain_confirm: true,
+  chain_delay_secs: 10,
+  meta_sync_dir: null,
+  max_working_sessions: 4,
+  max_working_sessions_per_repo: 2,
+  summarize_with_claude: false,
+  summarizer_command: null,
+  otlp_endpoint: null,
+  event_log: false,
+  memory_alert_mb: 4096,
+  weekly_digest: false,
+  slack_webhook_url: null,
+  push: {
+    service: 'off',
+    ntfy_url: '',
+    ntfy_token: null,
+    pushover_user_key: '',
+    pushover_app_token: '',
+    events: ['permission', 'alert'],
+    max_per_hour: 20,
+  },
+  telegram: {
+    enabled: false,
+    bot_token: '',
+    chat_id: null,
+    events: ['permission', 'input'],
+  },
+  email: {
+    enabled: false,
+    to: '',
+    from: '',
+    transport: 'sendmail',
+    smtp_url: '',
+    smtp_user: '',
+    smtp_password: '',
+    after_minutes: 30,
+  },
+  lan: {
+    enabled: false,
+    port: 9399,
+    cert_path: null,
+    key_path: null,
+  },
+  at_rest: {
+    enabled: false,
+    identity_path: null,
+    recipients: [],
+  },
 };
 
+const CHANNEL_EVENTS: { id: ChannelEvent; label: string }[] = [
+  { id: 'permission', label: 'Permission requests' },
+  { id: 'input', label: 'Questions and idle prompts' },
+  { id: 'complete', label: 'Finished turns' },
+  { id: 'alert', label: 'Time and memory limits' },
+];
+
+const emptySchedule: ScheduledTask = {
+  id: '',
+  name: '',
+  enabled: true,
+  schedule: '0 9 * * 1-5',
+  cwd: '',
+  agent: null,
+  prompt: null,
+  updatedAt: '',
+  lastRun: null,
+  lastError: null,
+};
+
+// YYYY-MM-DD in local time, as date inputs and time reports expect
+function localDate(date: Date): string {
+  const month = String(date.getMonth() + 1).padStart(2, '0');
+  const day = String(date.getDate()).padStart(2, '0');
+  return `${date.getFullYear()}-${month}-${day}`;
+}
+
 interface SoundConfigRowProps {
   label: string;
   config: SoundConfig;
@@ -116,10 +225,48 @@ function SoundConfigRow({ label, config, onChange }: SoundConfigRowProps) {
 export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
   const [settings, setSettings] = useState<AppSettings>(defaultSettings);
   const [availableTerminals, setAvailableTerminals] = useState<string[]>(['auto']);
+  const [agents, setAgents] = useState<AgentInfo[]>([]);
+  const [soundThemes, setSoundThemes] = useState<SoundTheme[]>([]);
+  const [audioOutputs, setAudioOutputs] = useState<string[]>([]);
+  const [audioOutputsError, setAudioOutputsError] = useState<string | null>(null);
   const [isSaving, setIsSaving] = useState(false);
   const [hookStatus, setHookStatus] = useState<HookStatus | null>(null);
   const [isInstallingHooks, setIsInstallingHooks] = useState(false);
+  const [onboarding, setOnboarding] = useState<OnboardingProgress[]>([]);
+  const [isOnboarding, setIsOnboarding] = useState(false);
+  const [installingDep, setInstallingDep] = useState<string | null>(null);
+  const [depOutput, setDepOutput] = useState<string | null>(null);
+  const [deleteDataOnUninstall, setDeleteDataOnUninstall] = useState(false);
+  const [uninstallReport, setUninstallReport] = useState<UninstallReport | null>(null);
   const [setupMessage, setSetupMessage] = useState<{ text: string; success: boolean } | null>(null);
+  const [saveError, setSaveError] = useState<string | null>(null);
+  const [profiles, setProfiles] = useState<ProfileList | null>(null);
+  const [newProfileName, setNewProfileName] = useState('');
+  const [profileError, setProfileError] = useState<string | null>(null);
+  const [permissionStats, setPermissionStats] = useState<PermissionStats | null>(null);
+  const [allowRules, setAllowRules] = useState<string[]>([]);
+  const [newAllowRule, setNewAllowRule] = useState('');
+  const [allowlistError, setAllowlistError] = useState<string | null>(null);
+  const [handoffMessage, setHandoffMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [automationLog, setAutomationLog] = useState<AutomationRun[]>([]);
+  const [schedules, setSchedules] = useState<ScheduleEntry[]>([]);
+  const [newSchedule, setNewSchedule] = useState<ScheduledTask>(emptySchedule);
+  const [scheduleError, setScheduleError] = useState<string | null>(null);
+  const [launchQueue, setLaunchQueue] = useState<QueuedLaunch[]>([]);
+  const [reportStart, setReportStart] = useState(() => localDate(new Date(new Date().setDate(1))));
+  const [reportEnd, setReportEnd] = useState(() => localDate(new Date()));
+  const [timeReportMessage, setTimeReportMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [digestMessage, setDigestMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [pushMessage, setPushMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [telegramMessage, setTelegramMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [emailMessage, setEmailMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [apiTokens, setApiTokens] = useState<ApiToken[]>([]);
+  const [newTokenName, setNewTokenName] = useState('');
+  const [newTokenScope, setNewTokenScope] = useState<TokenScope>('read');
+  const [apiTokenMessage, setApiTokenMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [lanStatus, setLanStatus] = useState<LanStatus | null>(null);
+  const [atRestMessage, setAtRestMessage] = useState<{ success: boolean; text: string } | null>(null);
+  const [xCallbackMessage, setXCallbackMessage] = useState<{ success: boolean; text: string } | null>(null);
 
   const refreshHookStatus = () => {
     invoke<HookStatus>('check_hook_status').then(setHookStatus).catch(console.error);
@@ -129,9 +276,32 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
     if (isOpen) {
       invoke<AppSettings>('get_settings').then(setSettings).catch(console.error);
       invoke<string[]>('get_available_terminals').then(setAvailableTerminals).catch(console.error);
+      invoke<AgentInfo[]>('list_agents').then(setAgents).catch(console.error);
+      invoke<SoundTheme[]>('list_sound_themes').then(setSoundThemes).catch(console.error);
+      invoke<string[]>('list_audio_outputs')
+        .then((outputs) => {
+          setAudioOutputs(outputs);
+          setAudioOutputsError(null);
+        })
+        .catch((e) => setAudioOutputsError(String(e)));
+      invoke<ProfileList>('get_profiles').then(setProfiles).catch(console.error);
+      invoke<PermissionStats>('get_permission_stats', { days: null }).then(setPermissionStats).catch(console.error);
+      invoke<string[]>('get_claude_allowlist').then(setAllowRules).catch((e) => setAllowlistError(String(e)));
+      refreshAutomationLog();
+      invoke<ScheduleEntry[]>('list_scheduled_tasks').then(setSchedules).catch(console.error);
+      invoke<QueuedLaunch[]>('get_queue').then(setLaunchQueue).catch(console.error);
+      invoke<ApiToken[]>('list_api_tokens').then(setApiTokens).catch(console.error);
+      invoke<LanStatus>('get_lan_status').then(setLanStatus).catch(console.error);
       refreshHookStatus();
     } else {
+      setApiTokenMessage(null);
+      setAtRestMessage(null);
       setSetupMessage(null);
+      setSaveError(null);
+      setProfileError(null);
+      setNewProfileName('');
+      setAllowlistError(null);
+      setNewAllowRule('');
     }
   }, [isOpen]);
 
@@ -151,16 +321,308 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
 
   const handleSave = async () => {
     setIsSaving(true);
+    setSaveError(null);
     try {
       await invoke('update_settings', { settings });
       onClose();
     } catch (e) {
       console.error('Failed to save settings:', e);
+      setSaveError(String(e));
     } finally {
       setIsSaving(false);
     }
   };
 
+  const handleCreateProfile = async () => {
+    setProfileError(null);
+    try {
+      setProfiles(await invoke<ProfileList>('create_profile', { name: newProfileName.trim() }));
+      setNewProfileName('');
+    } catch (e) {
+      setProfileError(String(e));
+    }
+  };
+
+  const handleSwitchProfile = async (name: string) => {
+    setProfileError(null);
+    try {
+      // The app restarts into the new profile
+      await invoke('switch_profile', { name });
+    } catch (e) {
+      setProfileError(String(e));
+    }
+  };
+
+  const handleAllowlistChange = async (command: string, rule: string) => {
+    setAllowlistError(null);
+    try {
+      const update = await invoke<AllowlistUpdate>(command, { rule });
+      setAllowRules(update.rules);
+      setNewAllowRule('');
+    } catch (e) {
+      setAllowlistError(String(e));
+    }
+  };
+
+  const importBundle = async (path: string, projectPath: string | null): Promise<void> => {
+    try {
+      const imported = await invoke<ImportedSession>('import_session_bundle', { path, projectPath });
+      setHandoffMessage({
+        success: true,
+        text: imported.tmuxTarget
+          ? `Resumed ${imported.projectName} in ${imported.tmuxTarget}`
+          : `Imported ${imported.projectName}; run ${imported.resumeCommand} in ${imported.projectPath}`,
+      });
+    } catch (e) {
+      const message = String(e);
+      // The project lives somewhere else on this machine: ask where
+      if (projectPath === null && message.includes("doesn't exist on this machine")) {
+        const folder = await open({ directory: true, multiple: false, title: message });
+        if (folder) return importBundle(path, folder as string);
+      }
+      setHandoffMessage({ success: false, text: message });
+    }
+  };
+
+  const handleImportBundle = async () => {
+    setHandoffMessage(null);
+    const path = await open({
+      filters: [{ name: 'C3 session bundle', extensions: ['zip', 'age'] }],
+      directory: false,
+      multiple: false,
+    });
+    if (path) {
+      await importBundle(path as string, null);
+    }
+  };
+
+  const handleExportTimeReport = async () => {
+    setTimeReportMessage(null);
+    const path = await save({
+      defaultPath: `c3-time-${reportStart}-to-${reportEnd}.csv`,
+      filters: [
+        { name: 'CSV', extensions: ['csv'] },
+        { name: 'JSON', extensions: ['json'] },
+      ],
+    });
+    if (!path) return;
+    try {
+      const rows = await invoke<TimeReportRow[]>('export_time_report', {
+        range: { start: reportStart, end: reportEnd },
+        path,
+      });
+      const hours = rows.reduce((total, row) => total + row.activeSecs, 0) / 3600;
+      setTimeReportMessage({ success: true, text: `Exported ${hours.toFixed(1)} active hours across ${rows.length} rows` });
+    } catch (e) {
+      setTimeReportMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const handleSendDigest = async () => {
+    setDigestMessage(null);
+    try {
+      const digest = await invoke<WeeklyDigest>('send_weekly_digest');
+      setDigestMessage({ success: true, text: `Sent the digest for the week of ${digest.start}` });
+    } catch (e) {
+      setDigestMessage({ success: false, text: String(e) });
+    }
+  };
+
+  // Fields with serde defaults come out optional; the backend always sends them
+  const push = settings.push as PushSettings;
+  const setPush = (changes: Partial<PushSettings>) => setSettings({ ...settings, push: { ...push, ...changes } });
+
+  const handleTestPush = async () => {
+    setPushMessage(null);
+    try {
+      await invoke('test_push', { push });
+      setPushMessage({ success: true, text: 'Sent a test push' });
+    } catch (e) {
+      setPushMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const telegram = settings.telegram as TelegramSettings;
+  const setTelegram = (changes: Partial<TelegramSettings>) =>
+    setSettings({ ...settings, telegram: { ...telegram, ...changes } });
+
+  const handleTestTelegram = async () => {
+    setTelegramMessage(null);
+    try {
+      await invoke('test_telegram', { telegram });
+      setTelegramMessage({ success: true, text: 'Posted a test message' });
+    } catch (e) {
+      setTelegramMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const email = settings.email as EmailSettings;
+  const setEmail = (changes: Partial<EmailSettings>) => setSettings({ ...settings, email: { ...email, ...changes } });
+
+  const handleTestEmail = async () => {
+    setEmailMessage(null);
+    try {
+      await invoke('test_email', { email });
+      setEmailMessage({ success: true, text: `Sent a test email to ${email.to}` });
+    } catch (e) {
+      setEmailMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const lan = settings.lan as LanSettings;
+  const setLan = (changes: Partial<LanSettings>) => setSettings({ ...settings, lan: { ...lan, ...changes } });
+
+  const atRest = settings.at_rest as AtRestSettings;
+  const setAtRest = (changes: Partial<AtRestSettings>) =>
+    setSettings({ ...settings, at_rest: { ...atRest, ...changes } });
+
+  const handleShowRecipient = async () => {
+    try {
+      const recipient = await invoke<string>('get_at_rest_recipient');
+      setAtRestMessage({ success: true, text: `This Mac's recipient: ${recipient}` });
+    } catch (e) {
+      setAtRestMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const handleShowXCallbackKey = async () => {
+    try {
+      const key = await invoke<string>('get_x_callback_key');
+      setXCallbackMessage({ success: true, text: `Add key=${key} to your x-callback-url links` });
+    } catch (e) {
+      setXCallbackMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const handleCreateApiToken = async () => {
+    try {
+      const created = await invoke<CreatedApiToken>('create_api_token', { name: newTokenName, scope: newTokenScope });
+      setApiTokens([...apiTokens, created.token]);
+      setNewTokenName('');
+      setApiTokenMessage({ success: true, text: `Copy this token now; it isn't shown again: ${created.secret}` });
+    } catch (e) {
+      setApiTokenMessage({ success: false, text: String(e) });
+    }
+  };
+
+  const handleRevokeApiToken = async (id: string) => {
+    try {
+      setApiTokens(await invoke<ApiToken[]>('revoke_api_token', { id }));
+      setApiTokenMessage(null);
+    } catch (e) {
+      setApiTokenMessage({ success: false, text: String(e) });
+    }
+  };
+
+  // Frequent commands not yet allowlisted
+  const refreshAutomationLog = () => {
+    invoke<AutomationRun[]>('get_automation_log').then(setAutomationLog).catch(console.error);
+  };
+
+  const handleScheduleChange = async (command: string, args: Record<string, unknown>) => {
+    try {
+      setSchedules(await invoke<ScheduleEntry[]>(command, args));
+      setScheduleError(null);
+      return true;
+    } catch (e) {
+      setScheduleError(String(e));
+      return false;
+    }
+  };
+
+  const handleAddSchedule = async () => {
+    // The backend stamps new tasks
+    const { updatedAt: _, ...task } = newSchedule;
+    if (await handleScheduleChange('save_scheduled_task', { task })) {
+      setNewSchedule(emptySchedule);
+    }
+  };
+
+  const handleRunSchedule = async (id: string) => {
+    try {
+      await invoke<string>('run_scheduled_task', { id });
+      setScheduleError(null);
+    } catch (e) {
+      setScheduleError(String(e));
+    }
+    invoke<ScheduleEntry[]>('list_scheduled_tasks').then(setSchedules).catch(console.error);
+  };
+
+  const handleInstallDependency = async (name: string) => {
+    setInstallingDep(name);
+    setDepOutput(null);
+    setSetupMessage(null);
+    const unlisten = await listen<DependencyProgress>('dependency-progress', (event) => {
+      if (event.payload.name === name && event.payload.line) setDepOutput(event.payload.line);
+    });
+    try {
+      setHookStatus(await invoke<HookStatus>('install_dependency', { name }));
+      setSetupMessage({ text: `Installed ${name}`, success: true });
+    } catch (e) {
+      setSetupMessage({ text: `Couldn't install ${name}: ${e}`, success: false });
+      refreshHookStatus();
+    } finally {
+      unlisten();
+      setInstallingDep(null);
+      setDepOutput(null);
+    }
+  };
+
+  const handleUninstall = async () => {
+    const what = deleteDataOnUninstall ? "C3's hooks, login item, settings and data" : "C3's hooks and login item";
+    if (!window.confirm(`Remove ${what}? Agent settings are backed up first.`)) return;
+    try {
+      setUninstallReport(await invoke<UninstallReport>('uninstall_c3_integration', { deleteData: deleteDataOnUninstall }));
+    } catch (e) {
+      setUninstallReport({ removed: [], backups: [], errors: [String(e)] });
+    }
+    refreshHookStatus();
+  };
+
+  // Runs the steps in order, stopping at the first that fails; items arrive as each step goes
+  const handleGuidedSetup = async () => {
+    const steps: OnboardingStep[] = ['dependencies', 'hooks', 'hook_round_trip', 'notification'];
+    setIsOnboarding(true);
+    setOnboarding([]);
+    const unlisten = await listen<OnboardingProgress>('onboarding-progress', (event) => {
+      setOnboarding((current) => [...current.filter((p) => p.step !== event.payload.step), event.payload]);
+    });
+    try {
+      for (const step of steps) {
+        const progress = await invoke<OnboardingProgress>('run_onboarding_step', { step });
+        if (!progress.success) break;
+      }
+    } catch (e) {
+      setSetupMessage({ text: `Setup failed: ${e}`, success: false });
+    } finally {
+      unlisten();
+      setIsOnboarding(false);
+      refreshHookStatus();
+    }
+  };
+
+  const handleCancelQueued = async (id: string) => {
+    try {
+      setLaunchQueue(await invoke<QueuedLaunch[]>('cancel_queued', { id }));
+    } catch (e) {
+      console.error(e);
+      invoke<QueuedLaunch[]>('get_queue').then(setLaunchQueue).catch(console.error);
+    }
+  };
+
+  const stateOptions: { value: SessionState; label: string }[] = [
+    { value: 'spawning', label: 'Starting' },
+    { value: 'processing', label: 'Working' },
+    { value: 'awaiting_input', label: 'Waiting for input' },
+    { value: 'awaiting_permission', label: 'Needs permission' },
+    { value: 'complete', label: 'Complete' },
+    { value: 'error', label: 'Error' },
+  ];
+
+  const suggestedRules = (permissionStats?.tools.find((tool) => tool.tool === 'Bash')?.topCommands ?? [])
+    .map((command) => `Bash(${command.pattern}:*)`)
+    .filter((rule) => !allowRules.includes(rule));
+
   if (!isOpen) return null;
 
   return (
@@ -172,6 +634,37 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
         </div>
 
         <div className="settings-content">
+          {profiles && (
+            <div className="settings-group">
+              <label className="settings-label">Profile</label>
+              <p className="settings-description">
+                Each profile has its own settings and session groups. Switching restarts C3.
+              </p>
+              <select
+                className="settings-select"
+                value={profiles.active}
+                onChange={(e) => handleSwitchProfile(e.target.value)}
+              >
+                {profiles.profiles.map((name) => (
+                  <option key={name} value={name}>{name}</option>
+                ))}
+              </select>
+              <div className="settings-timing-row">
+                <input
+                  type="text"
+                  className="settings-input"
+                  placeholder="new-profile"
+                  value={newProfileName}
+                  onChange={(e) => setNewProfileName(e.target.value)}
+                />
+                <button className="settings-btn" disabled={!newProfileName.trim()} onClick={handleCreateProfile}>
+                  Create
+                </button>
+              </div>
+              {profileError && <div className="setup-message error">{profileError}</div>}
+            </div>
+          )}
+
           <div className="settings-group">
             <label className="settings-label">Terminal Application</label>
             <p className="settings-description">
@@ -188,6 +681,75 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                 </option>
               ))}
             </select>
+            {settings.terminal_app !== 'auto' && settings.terminal_bundle_id && (
+              <p className="settings-description">Activated as {settings.terminal_bundle_id}</p>
+            )}
+            <p className="settings-description">
+              Terminals C3 doesn't know about, tried first when auto-detecting. The bundle id finds apps outside
+              /Applications; a focus command, if set, is run instead of activating the app. The attach command opens a new window running
+              {'{command}'} when clicking a tmux session no client is showing.
+            </p>
+            {settings.custom_terminals.map((terminal, i) => {
+              const updateTerminal = (changes: Partial<CustomTerminal>) =>
+                setSettings({
+                  ...settings,
+                  custom_terminals: settings.custom_terminals.map((t, j) => (j === i ? { ...t, ...changes } : t)),
+                });
+              return (
+                <div className="settings-timing-row" key={i}>
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="Rio"
+                    value={terminal.name}
+                    onChange={(e) => updateTerminal({ name: e.target.value })}
+                  />
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="com.raphaelamorim.rio"
+                    value={terminal.bundle_id ?? ''}
+                    onChange={(e) => updateTerminal({ bundle_id: e.target.value || null })}
+                  />
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="Focus command (optional)"
+                    value={terminal.focus_command ?? ''}
+                    onChange={(e) => updateTerminal({ focus_command: e.target.value || null })}
+                  />
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="open -na Rio --args -e {command}"
+                    value={terminal.attach_command ?? ''}
+                    onChange={(e) => updateTerminal({ attach_command: e.target.value || null })}
+                  />
+                  <button
+                    className="settings-btn"
+                    onClick={() =>
+                      setSettings({
+                        ...settings,
+                        custom_terminals: settings.custom_terminals.filter((_, j) => j !== i),
+                      })
+                    }
+                  >
+                    Remove
+                  </button>
+                </div>
+              );
+            })}
+            <button
+              className="settings-btn"
+              onClick={() =>
+                setSettings({
+                  ...settings,
+                  custom_terminals: [...settings.custom_terminals, { name: '', bundle_id: null, focus_command: null, attach_command: null }],
+                })
+              }
+            >
+              Add terminal
+            </button>
           </div>
 
           <div className="settings-group">
@@ -198,13 +760,42 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
             <select
               className="settings-select"
               value={settings.default_agent}
-              onChange={(e) => setSettings({ ...settings, default_agent: e.target.value as AppSettings['default_agent'] })}
+              onChange={(e) => setSettings({ ...settings, default_agent: e.target.value })}
             >
-              <option value="codex">Codex</option>
-              <option value="claude">Claude Code</option>
+              {agents.map((agent) => (
+                <option key={agent.kind} value={agent.kind}>
+                  {agent.label}
+                </option>
+              ))}
             </select>
           </div>
 
+          <div className="settings-group">
+            <label className="settings-label">Agents</label>
+            <p className="settings-description">
+              Agents C3 tracks. Disabled agents are skipped by the scanner and their hooks are ignored.
+            </p>
+            {agents.map((agent) => (
+              <div className="settings-row" key={agent.kind}>
+                <label className="settings-checkbox">
+                  <input
+                    type="checkbox"
+                    checked={!settings.disabled_agents.includes(agent.kind)}
+                    onChange={(e) =>
+                      setSettings({
+                        ...settings,
+                        disabled_agents: e.target.checked
+                          ? settings.disabled_agents.filter((kind) => kind !== agent.kind)
+                          : [...settings.disabled_agents, agent.kind],
+                      })
+                    }
+                  />
+                  <span>{agent.label}</span>
+                </label>
+              </div>
+            ))}
+          </div>
+
           <div className="settings-group">
             <label className="settings-label">Notifications</label>
             <div className="settings-row">
@@ -219,11 +810,166 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
             </div>
           </div>
 
+          <div className="settings-group">
+            <label className="settings-label">Startup</label>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.launch_at_login}
+                  onChange={(e) => setSettings({ ...settings, launch_at_login: e.target.checked })}
+                />
+                <span>Start C3 at login</span>
+              </label>
+            </div>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.start_hidden}
+                  disabled={!settings.launch_at_login}
+                  onChange={(e) => setSettings({ ...settings, start_hidden: e.target.checked })}
+                />
+                <span>Start hidden in the menu bar</span>
+              </label>
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Updates</label>
+            <p className="settings-description">
+              The beta channel includes prereleases. Automatic mode downloads new releases in the
+              background; the update button in the header then opens the downloaded DMG.
+            </p>
+            <select
+              className="settings-select"
+              value={settings.update_channel}
+              onChange={(e) => setSettings({ ...settings, update_channel: e.target.value as AppSettings['update_channel'] })}
+            >
+              <option value="stable">Stable</option>
+              <option value="beta">Beta</option>
+            </select>
+            <select
+              className="settings-select"
+              value={settings.update_mode}
+              onChange={(e) => setSettings({ ...settings, update_mode: e.target.value as AppSettings['update_mode'] })}
+            >
+              <option value="prompt">Tell me about new releases</option>
+              <option value="auto">Download new releases automatically</option>
+            </select>
+          </div>
+
           <div className="settings-group">
             <label className="settings-label">Sounds</label>
             <p className="settings-description">
-              Configure sounds for each notification type.
+              Configure sounds for each notification type, or pick a theme with a matching set. Theme packs in
+              ~/.config/c3/sound-themes/&lt;name&gt;/theme.json are listed too.
             </p>
+            <div className="settings-timing-row">
+              <span>Volume ({settings.alert_volume}%)</span>
+              <input
+                type="range"
+                min={0}
+                max={100}
+                step={5}
+                value={settings.alert_volume}
+                onChange={(e) => setSettings({ ...settings, alert_volume: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>Play on</span>
+              <select
+                className="settings-select"
+                value={settings.alert_output_device ?? ''}
+                disabled={audioOutputs.length === 0}
+                onChange={(e) => setSettings({ ...settings, alert_output_device: e.target.value || null })}
+              >
+                <option value="">System output</option>
+                {settings.alert_output_device && !audioOutputs.includes(settings.alert_output_device) && (
+                  <option value={settings.alert_output_device}>{settings.alert_output_device} (not connected)</option>
+                )}
+                {audioOutputs.map((device) => (
+                  <option key={device} value={device}>{device}</option>
+                ))}
+              </select>
+            </div>
+            {audioOutputsError && <p className="settings-description">{audioOutputsError}</p>}
+            <div className="settings-timing-row">
+              <span>Theme</span>
+              <select
+                className="settings-select"
+                value={settings.sound_theme ?? ''}
+                onChange={(e) => setSettings({ ...settings, sound_theme: e.target.value || null })}
+              >
+                <option value="">None (sounds below)</option>
+                {soundThemes.map((theme) => (
+                  <option key={theme.name} value={theme.name}>{theme.name}</option>
+                ))}
+              </select>
+              <button
+                className="sound-test-btn"
+                title="Test theme"
+                disabled={!settings.sound_theme}
+                onClick={() => {
+                  const theme = soundThemes.find((t) => t.name === settings.sound_theme);
+                  [theme?.permission, theme?.input, theme?.complete]
+                    .filter((sound): sound is string => Boolean(sound))
+                    .forEach((sound, i) => setTimeout(() => invoke('play_sound', { sound }).catch(console.error), i * 800));
+                }}
+              >
+                <Volume2 size={14} />
+              </button>
+            </div>
+            {settings.project_sound_themes.map((project, i) => {
+              const updateProject = (changes: Partial<typeof project>) =>
+                setSettings({
+                  ...settings,
+                  project_sound_themes: settings.project_sound_themes.map((p, j) => (j === i ? { ...p, ...changes } : p)),
+                });
+              return (
+                <div className="settings-timing-row" key={i}>
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="~/src/prod-api/**"
+                    value={project.path}
+                    onChange={(e) => updateProject({ path: e.target.value })}
+                  />
+                  <select
+                    className="settings-select"
+                    value={project.theme}
+                    onChange={(e) => updateProject({ theme: e.target.value })}
+                  >
+                    {soundThemes.map((theme) => (
+                      <option key={theme.name} value={theme.name}>{theme.name}</option>
+                    ))}
+                  </select>
+                  <button
+                    className="settings-btn"
+                    onClick={() =>
+                      setSettings({
+                        ...settings,
+                        project_sound_themes: settings.project_sound_themes.filter((_, j) => j !== i),
+                      })
+                    }
+                  >
+                    Remove
+                  </button>
+                </div>
+              );
+            })}
+            <button
+              className="settings-btn"
+              disabled={soundThemes.length === 0}
+              onClick={() =>
+                setSettings({
+                  ...settings,
+                  project_sound_themes: [...settings.project_sound_themes, { path: '', theme: soundThemes[0].name }],
+                })
+              }
+            >
+              Add project theme
+            </button>
             <div className="sound-configs">
               <SoundConfigRow
                 label="Permission requested"
@@ -244,77 +990,1463 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
           </div>
 
           <div className="settings-group">
-            <label className="settings-label">Setup Hooks</label>
+            <label className="settings-label">Timing</label>
             <p className="settings-description">
-              Install C3 hooks into Claude Code, Codex, and OMP for real-time session tracking.
+              Increase these on slow machines or if session states flicker.
             </p>
-
-            <div className="hook-status">
-              <div className="hook-status-row">
-                <span className="hook-status-label">Any hooks installed</span>
-                <span className={`hook-status-badge ${hookStatus?.hooks_installed ? 'installed' : 'not-installed'}`}>
-                  {hookStatus?.hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
-                </span>
-              </div>
-              <div className="hook-status-row">
-                <span className="hook-status-label">Claude hooks</span>
-                <span className={`hook-status-badge ${hookStatus?.claude_hooks_installed ? 'installed' : 'not-installed'}`}>
-                  {hookStatus?.claude_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
-                </span>
-              </div>
-              <div className="hook-status-row">
-                <span className="hook-status-label">Codex hooks</span>
-                <span className={`hook-status-badge ${hookStatus?.codex_hooks_installed ? 'installed' : 'not-installed'}`}>
-                  {hookStatus?.codex_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
-                </span>
-              </div>
-
-              <div className="hook-status-row">
-                <span className="hook-status-label">OMP hooks</span>
-                <span className={`hook-status-badge ${hookStatus?.omp_hooks_installed ? 'installed' : 'not-installed'}`}>
-                  {hookStatus?.omp_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
-                </span>
-              </div>
-
-              <div className="hook-deps">
-                <div className="hook-dep-row">
-                  <span>jq</span>
-                  {hookStatus?.jq_installed
-                    ? <span className="dep-ok"><Check size={12} /></span>
-                    : <span className="dep-missing"><AlertTriangle size={12} /> missing</span>
-                  }
-                </div>
-                <div className="hook-dep-row">
-                  <span>terminal-notifier</span>
-                  {hookStatus?.terminal_notifier_installed
-                    ? <span className="dep-ok"><Check size={12} /></span>
-                    : <span className="dep-warn"><AlertTriangle size={12} /> optional</span>
-                  }
+            <div className="settings-timing-row">
+              <span>Scan interval (1–30 s)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={1}
+                max={30}
+                value={settings.scan_interval_secs}
+                onChange={(e) => setSettings({ ...settings, scan_interval_secs: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>Hook grace period (2–60 s)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={2}
+                max={60}
+                value={settings.hook_grace_period_secs}
+                onChange={(e) => setSettings({ ...settings, hook_grace_period_secs: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>When hooks and the scanner disagree</span>
+              <select
+                className="settings-select"
+                value={settings.state_arbitration}
+                onChange={(e) => setSettings({ ...settings, state_arbitration: e.target.value as Arbitration })}
+              >
+                <option value="hook_grace_period">Hook wins for the grace period</option>
+                <option value="hook_always_wins">Hook always wins</option>
+                <option value="scanner_always_wins">Scanner always wins</option>
+                <option value="newest_evidence">Newest evidence wins</option>
+              </select>
+            </div>
+            {settings.state_arbitration === 'newest_evidence' && (
+              <>
+                <div className="settings-timing-row">
+                  <span>Hook confidence (0–60 s)</span>
+                  <input
+                    type="number"
+                    className="settings-input"
+                    min={0}
+                    max={60}
+                    value={settings.hook_confidence_secs}
+                    onChange={(e) => setSettings({ ...settings, hook_confidence_secs: Number(e.target.value) })}
+                  />
                 </div>
-                <div className="hook-dep-row">
-                  <span>tmux</span>
-                  {hookStatus?.tmux_installed
-                    ? <span className="dep-ok"><Check size={12} /></span>
-                    : <span className="dep-missing"><AlertTriangle size={12} /> missing</span>
-                  }
+                <div className="settings-timing-row">
+                  <span>Scanner confidence (0–60 s)</span>
+                  <input
+                    type="number"
+                    className="settings-input"
+                    min={0}
+                    max={60}
+                    value={settings.scanner_confidence_secs}
+                    onChange={(e) => setSettings({ ...settings, scanner_confidence_secs: Number(e.target.value) })}
+                  />
                 </div>
-              </div>
+              </>
+            )}
+            <label className="settings-checkbox">
+              <input
+                type="checkbox"
+                checked={settings.pane_content_detection}
+                onChange={(e) => setSettings({ ...settings, pane_content_detection: e.target.checked })}
+              />
+              <span>Read Claude panes' content (permission box, "esc to interrupt", input prompt) as a third state signal</span>
+            </label>
+            <label className="settings-checkbox">
+              <input
+                type="checkbox"
+                checked={settings.battery_saver}
+                onChange={(e) => setSettings({ ...settings, battery_saver: e.target.checked })}
+              />
+              <span>On battery or in Low Power Mode, scan 4× less often and don't read pane content</span>
+            </label>
+            <div className="settings-timing-row">
+              <span>Notification debounce (0–10000 ms)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                max={10000}
+                step={100}
+                value={settings.notification_debounce_ms}
+                onChange={(e) => setSettings({ ...settings, notification_debounce_ms: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>Hook server port (restart to apply)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={1024}
+                max={65535}
+                value={settings.hook_port}
+                onChange={(e) => setSettings({ ...settings, hook_port: Number(e.target.value) })}
+              />
+            </div>
+          </div>
 
-              {setupMessage && (
-                <div className={`setup-message ${setupMessage.success ? 'success' : 'error'}`}>
-                  {setupMessage.text}
+          <div className="settings-group">
+            <label className="settings-label">Retention</label>
+            <p className="settings-description">
+              Finished and idle sessions are archived automatically and come back when the agent is
+              active again. Set to 0 to keep them.
+            </p>
+            <div className="settings-timing-row">
+              <span>Archive complete sessions after (hours)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                value={settings.archive_complete_after_hours}
+                onChange={(e) => setSettings({ ...settings, archive_complete_after_hours: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>Hide sessions with no transcript changes for (days)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                value={settings.hide_stale_after_days}
+                onChange={(e) => setSettings({ ...settings, hide_stale_after_days: Number(e.target.value) })}
+              />
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Time Limits</label>
+            <p className="settings-description">
+              Notify when a session stays working or waiting for you longer than this, and again at twice and four
+              times the limit. A limit for a tag replaces the global one for sessions with that tag.
+            </p>
+            {settings.sla_rules.map((rule, i) => {
+              const updateRule = (changes: Partial<SlaRule>) =>
+                setSettings({
+                  ...settings,
+                  sla_rules: settings.sla_rules.map((r, j) => (j === i ? { ...r, ...changes } : r)),
+                });
+              return (
+                <div className="settings-timing-row" key={i}>
+                  <select
+                    className="settings-select"
+                    value={rule.state}
+                    onChange={(e) => updateRule({ state: e.target.value as SlaRule['state'] })}
+                  >
+                    <option value="working">Working</option>
+                    <option value="waiting">Waiting for me</option>
+                  </select>
+                  <input
+                    type="number"
+                    className="settings-input"
+                    min={1}
+                    value={rule.minutes}
+                    onChange={(e) => updateRule({ minutes: Number(e.target.value) })}
+                  />
+                  <span>min</span>
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="any tag"
+                    value={rule.tag ?? ''}
+                    onChange={(e) => updateRule({ tag: e.target.value.trim() || null })}
+                  />
+                  <button
+                    className="settings-btn"
+                    onClick={() =>
+                      setSettings({ ...settings, sla_rules: settings.sla_rules.filter((_, j) => j !== i) })
+                    }
+                  >
+                    Remove
+                  </button>
                 </div>
-              )}
+              );
+            })}
+            <button
+              className="settings-btn"
+              onClick={() =>
+                setSettings({
+                  ...settings,
+                  sla_rules: [...settings.sla_rules, { state: 'working', minutes: 30, tag: null }],
+                })
+              }
+            >
+              Add limit
+            </button>
+          </div>
 
-              <div className="hook-actions">
-                <button
-                  className="settings-btn primary hook-install-btn"
-                  disabled={isInstallingHooks}
-                  onClick={async () => {
-                    setIsInstallingHooks(true);
-                    setSetupMessage(null);
-                    try {
-                      const result = await invoke<SetupResult>('setup_hooks');
+          <div className="settings-group">
+            <label className="settings-label">Memory</label>
+            <p className="settings-description">
+              Each session's agent and the processes it starts are sampled every 10 seconds, and their memory shown
+              on its card. Notify when a session goes over this, e.g. a runaway node process. 0 never notifies.
+            </p>
+            <div className="settings-timing-row">
+              <span>Notify above</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                step={512}
+                value={settings.memory_alert_mb}
+                onChange={(e) => setSettings({ ...settings, memory_alert_mb: Number(e.target.value) })}
+              />
+              <span>MB</span>
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Automation</label>
+            <p className="settings-description">
+              Run a shell command when a session changes state, e.g. a deploy script when the agent finishes in a
+              project. It runs in the project directory with <code>C3_SESSION_ID</code>, <code>C3_PROJECT_NAME</code>,{' '}
+              <code>C3_PROJECT_PATH</code>, <code>C3_STATE</code>, <code>C3_FROM_STATE</code>, <code>C3_TAG</code> and{' '}
+              <code>C3_TMUX_TARGET</code> set. A dry run only logs what would have run.
+            </p>
+            {settings.automation_rules.map((rule, i) => {
+              const updateRule = (changes: Partial<AutomationRule>) =>
+                setSettings({
+                  ...settings,
+                  automation_rules: settings.automation_rules.map((r, j) => (j === i ? { ...r, ...changes } : r)),
+                });
+              return (
+                <div key={i}>
+                  <div className="settings-timing-row">
+                    <label className="settings-checkbox">
+                      <input
+                        type="checkbox"
+                        checked={rule.enabled}
+                        onChange={(e) => updateRule({ enabled: e.target.checked })}
+                      />
+                    </label>
+                    <input
+                      type="text"
+                      className="settings-input"
+                      placeholder="Name"
+                      value={rule.name}
+                      onChange={(e) => updateRule({ name: e.target.value })}
+                    />
+                    <select
+                      className="settings-select"
+                      value={rule.from ?? ''}
+                      onChange={(e) => updateRule({ from: (e.target.value || null) as SessionState | null })}
+                    >
+                      <option value="">Any state</option>
+                      {stateOptions.map((o) => (
+                        <option key={o.value} value={o.value}>{o.label}</option>
+                      ))}
+                    </select>
+                    <span>→</span>
+                    <select
+                      className="settings-select"
+                      value={rule.to}
+                      onChange={(e) => updateRule({ to: e.target.value as SessionState })}
+                    >
+                      {stateOptions.map((o) => (
+                        <option key={o.value} value={o.value}>{o.label}</option>
+                      ))}
+                    </select>
+                  </div>
+                  <div className="settings-timing-row">
+                    <input
+                      type="text"
+                      className="settings-input"
+                      placeholder="any project (name or path)"
+                      value={rule.project ?? ''}
+                      onChange={(e) => updateRule({ project: e.target.value.trim() || null })}
+                    />
+                    <input
+                      type="text"
+                      className="settings-input"
+                      placeholder="any tag"
+                      value={rule.tag ?? ''}
+                      onChange={(e) => updateRule({ tag: e.target.value.trim() || null })}
+                    />
+                    <select
+                      className="settings-select"
+                      value={rule.yolo === null ? 'any' : rule.yolo ? 'yolo' : 'prompts'}
+                      onChange={(e) =>
+                        updateRule({ yolo: e.target.value === 'any' ? null : e.target.value === 'yolo' })
+                      }
+                    >
+                      <option value="any">any permissions</option>
+                      <option value="yolo">skipping permissions</option>
+                      <option value="prompts">asking permission</option>
+                    </select>
+                  </div>
+                  <div className="settings-timing-row">
+                    <input
+                      type="text"
+                      className="settings-input"
+                      placeholder="./scripts/deploy.sh"
+                      value={rule.command}
+                      onChange={(e) => updateRule({ command: e.target.value })}
+                    />
+                    <label className="settings-checkbox">
+                      <input
+                        type="checkbox"
+                        checked={rule.dry_run}
+                        onChange={(e) => updateRule({ dry_run: e.target.checked })}
+                      />
+                      <span>Dry run</span>
+                    </label>
+                    <button
+                      className="settings-btn"
+                      onClick={() =>
+                        setSettings({
+                          ...settings,
+                          automation_rules: settings.automation_rules.filter((_, j) => j !== i),
+                        })
+                      }
+                    >
+                      Remove
+                    </button>
+                  </div>
+                </div>
+              );
+            })}
+            <button
+              className="settings-btn"
+              onClick={() =>
+                setSettings({
+                  ...settings,
+                  automation_rules: [
+                    ...settings.automation_rules,
+                    {
+                      name: `Rule ${settings.automation_rules.length + 1}`,
+                      enabled: true,
+                      from: null,
+                      to: 'complete',
+                      project: null,
+                      tag: null,
+                      yolo: null,
+                      command: '',
+                      dry_run: true,
+                    },
+                  ],
+                })
+              }
+            >
+              Add rule
+            </button>
+            <div className="hook-status">
+              {automationLog.map((run, i) => (
+                <div className="hook-status-row" key={i}>
+                  <span className="hook-status-label" title={run.output || run.command}>
+                    {new Date(run.at).toLocaleTimeString()} {run.rule}: {run.projectName} → {run.to}
+                  </span>
+                  <span>
+                    {run.dryRun ? 'dry run' : run.exitCode === null ? 'failed' : `exit ${run.exitCode}`}
+                  </span>
+                </div>
+              ))}
+              {automationLog.length === 0 && <p className="settings-description">No rules have matched yet.</p>}
+            </div>
+            <button className="settings-btn" onClick={refreshAutomationLog}>
+              Refresh log
+            </button>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">tmux</label>
+            <p className="settings-description">
+              Show session state in tmux itself: mark panes waiting for you, and their windows in the
+              status line, until their state changes, or rename windows after it (e.g.{' '}
+              <code>⏳ api-server</code>). Switching either off puts tmux back. Needs tmux 3.0 or later.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.tmux_attention_marker}
+                  onChange={(e) => setSettings({ ...settings, tmux_attention_marker: e.target.checked })}
+                />
+                <span>Mark waiting panes</span>
+              </label>
+            </div>
+            <div className="settings-timing-row">
+              <span>Style</span>
+              <input
+                type="text"
+                className="settings-input"
+                spellCheck={false}
+                placeholder="bg=colour52"
+                value={settings.tmux_attention_style}
+                disabled={!settings.tmux_attention_marker}
+                onChange={(e) => setSettings({ ...settings, tmux_attention_style: e.target.value })}
+              />
+            </div>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.tmux_window_names}
+                  onChange={(e) => setSettings({ ...settings, tmux_window_names: e.target.checked })}
+                />
+                <span>Name windows after session state</span>
+              </label>
+            </div>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.zoom_on_focus}
+                  onChange={(e) => setSettings({ ...settings, zoom_on_focus: e.target.checked })}
+                />
+                <span>Zoom panes when focusing them</span>
+              </label>
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Scheduled Tasks</label>
+            <p className="settings-description">
+              Start an agent in a new tmux window on a schedule, e.g. every weekday at 9:00 in ~/notes with "summarize
+              yesterday's commits". Schedules are cron fields in local time (<code>minute hour day month weekday</code>)
+              or <code>@hourly</code>, <code>@daily</code>, <code>@weekly</code>. A run missed while the Mac slept is
+              made up if it was due in the last hour.
+            </p>
+            <div className="hook-status">
+              {schedules.map(({ task, nextRun }) => (
+                <div className="hook-status-row" key={task.id}>
+                  <label className="settings-checkbox">
+                    <input
+                      type="checkbox"
+                      checked={task.enabled}
+                      onChange={(e) =>
+                        handleScheduleChange('set_scheduled_task_enabled', { id: task.id, enabled: e.target.checked })
+                      }
+                    />
+                  </label>
+                  <span className="hook-status-label" title={task.lastError ?? task.prompt ?? task.cwd}>
+                    {task.name}: <code>{task.schedule}</code> in {task.cwd}
+                    {nextRun && `, next ${new Date(nextRun).toLocaleString()}`}
+                    {task.lastError && ' (last run failed)'}
+                  </span>
+                  <button className="settings-btn" onClick={() => handleRunSchedule(task.id)}>
+                    Run now
+                  </button>
+                  <button className="settings-btn" onClick={() => handleScheduleChange('delete_scheduled_task', { id: task.id })}>
+                    Remove
+                  </button>
+                </div>
+              ))}
+            </div>
+            <div className="settings-timing-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Name"
+                value={newSchedule.name}
+                onChange={(e) => setNewSchedule({ ...newSchedule, name: e.target.value })}
+              />
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="0 9 * * 1-5"
+                value={newSchedule.schedule}
+                onChange={(e) => setNewSchedule({ ...newSchedule, schedule: e.target.value })}
+              />
+              <select
+                className="settings-select"
+                value={newSchedule.agent ?? ''}
+                onChange={(e) => setNewSchedule({ ...newSchedule, agent: e.target.value || null })}
+              >
+                <option value="">Default agent</option>
+                {agents.map((agent) => (
+                  <option key={agent.kind} value={agent.kind}>{agent.label}</option>
+                ))}
+              </select>
+            </div>
+            <div className="settings-timing-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="~/notes"
+                value={newSchedule.cwd}
+                onChange={(e) => setNewSchedule({ ...newSchedule, cwd: e.target.value })}
+              />
+              <button
+                className="settings-btn"
+                onClick={async () => {
+                  const folder = await open({ directory: true, multiple: false });
+                  if (folder) setNewSchedule({ ...newSchedule, cwd: folder as string });
+                }}
+              >
+                Choose…
+              </button>
+            </div>
+            <div className="settings-timing-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Prompt (optional)"
+                value={newSchedule.prompt ?? ''}
+                onChange={(e) => setNewSchedule({ ...newSchedule, prompt: e.target.value || null })}
+              />
+              <button className="settings-btn" onClick={handleAddSchedule}>
+                Add task
+              </button>
+            </div>
+            {scheduleError && <div className="setup-message error">{scheduleError}</div>}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Chained Prompts</label>
+            <p className="settings-description">
+              Queue follow-up prompts from a session's menu ("now write tests", "now update the changelog"); each time
+              the agent finishes, the next one is typed at its prompt. Unless you turn off asking, C3 notifies you and
+              waits for "Send next prompt".
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.chain_confirm}
+                  onChange={(e) => setSettings({ ...settings, chain_confirm: e.target.checked })}
+                />
+                <span>Ask before sending the next prompt</span>
+              </label>
+            </div>
+            <div className="settings-timing-row">
+              <span>Otherwise send it after (seconds)</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                disabled={settings.chain_confirm}
+                value={settings.chain_delay_secs}
+                onChange={(e) => setSettings({ ...settings, chain_delay_secs: Number(e.target.value) })}
+              />
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Session Summaries</label>
+            <p className="settings-description">
+              When a session stops, C3 notes what it accomplished on its card, from the first lines of the agent's
+              final reply. Claude can write the summary instead, reading the end of the transcript with{' '}
+              <code>claude -p</code>; that costs a short request per stop.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.summarize_with_claude}
+                  disabled={!!settings.summarizer_command}
+                  onChange={(e) => setSettings({ ...settings, summarize_with_claude: e.target.checked })}
+                />
+                <span>Summarize with <code>claude -p</code></span>
+              </label>
+            </div>
+            <p className="settings-description">
+              Or use your own summarizer, such as <code>llm</code> or a local model: a shell command that reads the
+              transcript's path from stdin and prints the summary, e.g.{' '}
+              <code>xargs cat | llm -s 'Summarize what this agent session accomplished'</code>. It runs with{' '}
+              <code>C3_AGENT_KIND</code> and <code>C3_PROJECT_PATH</code> set, and is stopped after 2 minutes.
+            </p>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Summarizer command"
+                value={settings.summarizer_command ?? ''}
+                onChange={(e) => setSettings({ ...settings, summarizer_command: e.target.value || null })}
+              />
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">When Sessions Complete</label>
+            <p className="settings-description">
+              Type a check into the pane of a session that finishes, such as <code>npm test</code> or{' '}
+              <code>git status</code>, for projects under a path. While the agent is still open it runs in its shell
+              mode (<code>!npm test</code> in Claude Code and Codex), so the agent sees the output too. Sessions with
+              a queued prompt, or that end on a question, are left alone.
+            </p>
+            {settings.complete_actions.map((action, i) => {
+              const updateAction = (changes: Partial<typeof action>) =>
+                setSettings({
+                  ...settings,
+                  complete_actions: settings.complete_actions.map((a, j) => (j === i ? { ...a, ...changes } : a)),
+                });
+              return (
+                <div className="settings-timing-row" key={i}>
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="~/src/api/**"
+                    value={action.path}
+                    onChange={(e) => updateAction({ path: e.target.value })}
+                  />
+                  <input
+                    type="text"
+                    className="settings-input"
+                    placeholder="npm test"
+                    value={action.command}
+                    onChange={(e) => updateAction({ command: e.target.value })}
+                  />
+                  <button
+                    className="settings-btn"
+                    onClick={() =>
+                      setSettings({
+                        ...settings,
+                        complete_actions: settings.complete_actions.filter((_, j) => j !== i),
+                      })
+                    }
+                  >
+                    Remove
+                  </button>
+                </div>
+              );
+            })}
+            <button
+              className="settings-btn"
+              onClick={() =>
+                setSettings({ ...settings, complete_actions: [...settings.complete_actions, { path: '', command: '' }] })
+              }
+            >
+              Add command
+            </button>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">OpenTelemetry</label>
+            <p className="settings-description">
+              Send a trace per session to an OTLP/HTTP collector: a span for the session, one for each stretch spent
+              working or waiting on permission, and one per tool call from the transcript. Spans are sent as they end;
+              none are kept while the collector is down.
+            </p>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="http://localhost:4318"
+                value={settings.otlp_endpoint ?? ''}
+                onChange={(e) => setSettings({ ...settings, otlp_endpoint: e.target.value || null })}
+              />
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Event Log</label>
+            <p className="settings-description">
+              Append every state change, hook, notification and action to <code>~/.config/c3/events.log</code> as
+              JSON Lines, for your own reports with <code>jq</code>. The log rotates at 10 MB; see "Event log" in
+              the README for the schema.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.event_log}
+                  onChange={(e) => setSettings({ ...settings, event_log: e.target.checked })}
+                />
+                <span>Write the event log</span>
+              </label>
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Weekly Digest</label>
+            <p className="settings-description">
+              On Monday morning, notify with last week's sessions, top projects, Claude Code token usage and the
+              longest waits for you; clicking it opens the full digest. With a Slack incoming webhook, the digest is
+              posted there too. Send now uses the saved webhook.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={settings.weekly_digest}
+                  onChange={(e) => setSettings({ ...settings, weekly_digest: e.target.checked })}
+                />
+                <span>Send a weekly digest</span>
+              </label>
+            </div>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="https://hooks.slack.com/services/…"
+                value={settings.slack_webhook_url ?? ''}
+                onChange={(e) => setSettings({ ...settings, slack_webhook_url: e.target.value || null })}
+              />
+              <button className="settings-btn" onClick={handleSendDigest}>
+                Send now
+              </button>
+            </div>
+            {digestMessage && (
+              <div className={`setup-message ${digestMessage.success ? 'success' : 'error'}`}>
+                {digestMessage.text}
+              </div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Push Notifications</label>
+            <p className="settings-description">
+              Send notifications to your phone through an <a href="https://ntfy.sh">ntfy</a> topic or Pushover, for
+              when you've stepped away. Only notifications C3 would show on this Mac are pushed, so muted groups and
+              tags stay quiet. Pushes past the hourly limit are dropped; 0 means no limit.
+            </p>
+            <div className="settings-row">
+              <select
+                className="settings-select"
+                value={push.service}
+                onChange={(e) => setPush({ service: e.target.value as PushSettings['service'] })}
+              >
+                <option value="off">Off</option>
+                <option value="ntfy">ntfy</option>
+                <option value="pushover">Pushover</option>
+              </select>
+              <button className="settings-btn" onClick={handleTestPush} disabled={push.service === 'off'}>
+                Send test
+              </button>
+            </div>
+            {push.service === 'ntfy' && (
+              <div className="settings-row">
+                <input
+                  type="text"
+                  className="settings-input"
+                  placeholder="https://ntfy.sh/your-topic"
+                  value={push.ntfy_url}
+                  onChange={(e) => setPush({ ntfy_url: e.target.value })}
+                />
+                <input
+                  type="password"
+                  className="settings-input"
+                  placeholder="Access token (optional)"
+                  value={push.ntfy_token ?? ''}
+                  onChange={(e) => setPush({ ntfy_token: e.target.value || null })}
+                />
+              </div>
+            )}
+            {push.service === 'pushover' && (
+              <div className="settings-row">
+                <input
+                  type="text"
+                  className="settings-input"
+                  placeholder="User key"
+                  value={push.pushover_user_key}
+                  onChange={(e) => setPush({ pushover_user_key: e.target.value })}
+                />
+                <input
+                  type="password"
+                  className="settings-input"
+                  placeholder="App token"
+                  value={push.pushover_app_token}
+                  onChange={(e) => setPush({ pushover_app_token: e.target.value })}
+                />
+              </div>
+            )}
+            {CHANNEL_EVENTS.map((event) => (
+              <div key={event.id} className="settings-row">
+                <label className="settings-checkbox">
+                  <input
+                    type="checkbox"
+                    checked={push.events.includes(event.id)}
+                    onChange={(e) =>
+                      setPush({
+                        events: e.target.checked
+                          ? [...push.events, event.id]
+                          : push.events.filter((id) => id !== event.id),
+                      })
+                    }
+                  />
+                  <span>{event.label}</span>
+                </label>
+              </div>
+            ))}
+            <div className="settings-timing-row">
+              <span>At most</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                value={push.max_per_hour}
+                onChange={(e) => setPush({ max_per_hour: Number(e.target.value) })}
+              />
+              <span>pushes an hour</span>
+            </div>
+            {pushMessage && (
+              <div className={`setup-message ${pushMessage.success ? 'success' : 'error'}`}>{pushMessage.text}</div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Telegram</label>
+            <p className="settings-description">
+              Post notifications to a Telegram chat through your own bot (create one with @BotFather), with Approve,
+              Deny and Later buttons under permission requests. Later posts the session again in 15 minutes if it's
+              still waiting. Message the bot and it replies with the chat's id; only that chat can press buttons.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={telegram.enabled}
+                  onChange={(e) => setTelegram({ enabled: e.target.checked })}
+                />
+                <span>Run the Telegram bot</span>
+              </label>
+            </div>
+            <div className="settings-row">
+              <input
+                type="password"
+                className="settings-input"
+                placeholder="Bot token"
+                value={telegram.bot_token}
+                onChange={(e) => setTelegram({ bot_token: e.target.value })}
+              />
+              <input
+                type="number"
+                className="settings-input"
+                placeholder="Chat id"
+                value={telegram.chat_id ?? ''}
+                onChange={(e) => setTelegram({ chat_id: e.target.value ? Number(e.target.value) : null })}
+              />
+              <button
+                className="settings-btn"
+                onClick={handleTestTelegram}
+                disabled={!telegram.bot_token || telegram.chat_id === null}
+              >
+                Send test
+              </button>
+            </div>
+            {CHANNEL_EVENTS.map((event) => (
+              <div key={event.id} className="settings-row">
+                <label className="settings-checkbox">
+                  <input
+                    type="checkbox"
+                    checked={telegram.events.includes(event.id)}
+                    onChange={(e) =>
+                      setTelegram({
+                        events: e.target.checked
+                          ? [...telegram.events, event.id]
+                          : telegram.events.filter((id) => id !== event.id),
+                      })
+                    }
+                  />
+                  <span>{event.label}</span>
+                </label>
+              </div>
+            ))}
+            {telegramMessage && (
+              <div className={`setup-message ${telegramMessage.success ? 'success' : 'error'}`}>
+                {telegramMessage.text}
+              </div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Email</label>
+            <p className="settings-description">
+              Email when a session has waited for your permission or input longer than this, for headless machines
+              and long absences. Sessions that go over in the same quarter hour share one email, and each wait is
+              emailed about once. Mail goes through the local <code>sendmail</code> or an SMTP server; with a user
+              set, the SMTP connection must be encrypted.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input type="checkbox" checked={email.enabled} onChange={(e) => setEmail({ enabled: e.target.checked })} />
+                <span>Email about waiting sessions</span>
+              </label>
+            </div>
+            <div className="settings-timing-row">
+              <span>After</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={1}
+                value={email.after_minutes}
+                onChange={(e) => setEmail({ after_minutes: Number(e.target.value) })}
+              />
+              <span>minutes</span>
+            </div>
+            <div className="settings-row">
+              <input
+                type="email"
+                className="settings-input"
+                placeholder="To"
+                value={email.to}
+                onChange={(e) => setEmail({ to: e.target.value })}
+              />
+              <input
+                type="email"
+                className="settings-input"
+                placeholder="From"
+                value={email.from}
+                onChange={(e) => setEmail({ from: e.target.value })}
+              />
+            </div>
+            <div className="settings-row">
+              <select
+                className="settings-select"
+                value={email.transport}
+                onChange={(e) => setEmail({ transport: e.target.value as EmailSettings['transport'] })}
+              >
+                <option value="sendmail">sendmail</option>
+                <option value="smtp">SMTP</option>
+              </select>
+              <button className="settings-btn" onClick={handleTestEmail} disabled={!email.to}>
+                Send test
+              </button>
+            </div>
+            {email.transport === 'smtp' && (
+              <div className="settings-row">
+                <input
+                  type="text"
+                  className="settings-input"
+                  placeholder="smtps://smtp.example.com:465"
+                  value={email.smtp_url}
+                  onChange={(e) => setEmail({ smtp_url: e.target.value })}
+                />
+                <input
+                  type="text"
+                  className="settings-input"
+                  placeholder="User"
+                  value={email.smtp_user}
+                  onChange={(e) => setEmail({ smtp_user: e.target.value })}
+                />
+                <input
+                  type="password"
+                  className="settings-input"
+                  placeholder="Password"
+                  value={email.smtp_password}
+                  onChange={(e) => setEmail({ smtp_password: e.target.value })}
+                />
+              </div>
+            )}
+            {emailMessage && (
+              <div className={`setup-message ${emailMessage.success ? 'success' : 'error'}`}>{emailMessage.text}</div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">API Tokens</label>
+            <p className="settings-description">
+              Once a token exists, the <code>/api</code> routes of the local HTTP API need one as{' '}
+              <code>Authorization: Bearer</code>. Read tokens can list sessions and follow events; control tokens can
+              also focus, approve, deny and type input, which always needs one. Revoking a token refuses it from the
+              next request.
+            </p>
+            <div className="hook-status">
+              {apiTokens.map((token) => (
+                <div className="hook-status-row" key={token.id}>
+                  <span className="hook-status-label">
+                    {token.name} ({token.scope}),{' '}
+                    {token.lastUsedAt ? `last used ${new Date(token.lastUsedAt).toLocaleString()}` : 'never used'}
+                  </span>
+                  <button className="settings-btn" onClick={() => handleRevokeApiToken(token.id)}>
+                    Revoke
+                  </button>
+                </div>
+              ))}
+            </div>
+            <div className="settings-timing-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Name, e.g. Stream Deck"
+                value={newTokenName}
+                onChange={(e) => setNewTokenName(e.target.value)}
+              />
+              <select
+                className="settings-select"
+                value={newTokenScope}
+                onChange={(e) => setNewTokenScope(e.target.value as TokenScope)}
+              >
+                <option value="read">Read</option>
+                <option value="control">Control</option>
+              </select>
+              <button className="settings-btn" onClick={handleCreateApiToken} disabled={!newTokenName.trim()}>
+                Create token
+              </button>
+            </div>
+            {apiTokenMessage && (
+              <div className={`setup-message ${apiTokenMessage.success ? 'success' : 'error'}`}>{apiTokenMessage.text}</div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Shortcuts</label>
+            <p className="settings-description">
+              <code>c3://x-callback-url</code> links that list sessions, type input or approve need this Mac's key as{' '}
+              <code>key=</code>, so a web page can't trigger them. Without it, approving only opens the request for
+              you to click. Callbacks can go to apps like <code>shortcuts://</code>, never to web pages.
+            </p>
+            <div className="settings-row">
+              <button className="settings-btn" onClick={handleShowXCallbackKey}>
+                Show key
+              </button>
+            </div>
+            {xCallbackMessage && (
+              <div className={`setup-message ${xCallbackMessage.success ? 'success' : 'error'}`}>
+                {xCallbackMessage.text}
+              </div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">LAN Access</label>
+            <p className="settings-description">
+              Serve the <code>/api</code> routes over HTTPS to other devices on your network, e.g. a tablet showing
+              your sessions. Every request needs an API token, even before you'd need one locally; hooks and
+              everything else stay on this Mac. Without a certificate of your own, C3 makes a self-signed one: check
+              its fingerprint on the device when it asks to trust it.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input type="checkbox" checked={lan.enabled} onChange={(e) => setLan({ enabled: e.target.checked })} />
+                <span>Listen on the network</span>
+              </label>
+            </div>
+            <div className="settings-timing-row">
+              <span>Port</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={1024}
+                max={65535}
+                value={lan.port}
+                onChange={(e) => setLan({ port: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Certificate (PEM), optional"
+                value={lan.cert_path ?? ''}
+                onChange={(e) => setLan({ cert_path: e.target.value || null })}
+              />
+            </div>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Private key (PEM), optional"
+                value={lan.key_path ?? ''}
+                onChange={(e) => setLan({ key_path: e.target.value || null })}
+              />
+            </div>
+            {lan.enabled && apiTokens.length === 0 && (
+              <div className="setup-message error">Create an API token above; the LAN server refuses requests without one.</div>
+            )}
+            {lanStatus?.listening && (
+              <div className="setup-message success">
+                Listening on {lanStatus.urls.join(', ')}
+                {lanStatus.fingerprint && (
+                  <>
+                    <br />
+                    SHA-256 <code>{lanStatus.fingerprint}</code>
+                  </>
+                )}
+              </div>
+            )}
+            {lanStatus?.error && <div className="setup-message error">{lanStatus.error}</div>}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Encryption at Rest</label>
+            <p className="settings-description">
+              Encrypt session bundles and transcript exports with <code>age</code> (<code>brew install age</code>).
+              Both get <code>.age</code> appended; decrypt exports with <code>age -d</code>, and importing a bundle
+              decrypts it. C3 keeps its key in the Keychain unless you pick an identity file, e.g. from an age
+              plugin. To import on another Mac, add that Mac's recipient below. The event log, synced session
+              metadata and email being sent stay in plain text.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={atRest.enabled}
+                  onChange={(e) => setAtRest({ enabled: e.target.checked })}
+                />
+                <span>Encrypt bundles and exports</span>
+              </label>
+            </div>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Identity file, optional"
+                value={atRest.identity_path ?? ''}
+                onChange={(e) => setAtRest({ identity_path: e.target.value || null })}
+              />
+            </div>
+            <textarea
+              className="settings-input settings-textarea"
+              rows={2}
+              spellCheck={false}
+              placeholder="More recipients (age1… or ssh-ed25519 …), one per line"
+              value={atRest.recipients.join('\n')}
+              onChange={(e) => setAtRest({ recipients: e.target.value.split('\n') })}
+            />
+            <div className="settings-row">
+              <button className="settings-btn" onClick={handleShowRecipient}>
+                Show this Mac's recipient
+              </button>
+            </div>
+            {atRestMessage && (
+              <div className={`setup-message ${atRestMessage.success ? 'success' : 'error'}`}>{atRestMessage.text}</div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Concurrency</label>
+            <p className="settings-description">
+              Scheduled tasks and chained prompts wait while this many sessions are working, so automation never has
+              a dozen agents burning tokens at once. Sessions you start yourself always start. 0 means no limit.
+            </p>
+            <div className="settings-timing-row">
+              <span>Working sessions at most</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                value={settings.max_working_sessions}
+                onChange={(e) => setSettings({ ...settings, max_working_sessions: Number(e.target.value) })}
+              />
+            </div>
+            <div className="settings-timing-row">
+              <span>Per repository</span>
+              <input
+                type="number"
+                className="settings-input"
+                min={0}
+                value={settings.max_working_sessions_per_repo}
+                onChange={(e) => setSettings({ ...settings, max_working_sessions_per_repo: Number(e.target.value) })}
+              />
+            </div>
+            {launchQueue.length > 0 && (
+              <div className="hook-status">
+                {launchQueue.map((launch) => (
+                  <div className="hook-status-row" key={launch.id}>
+                    <span className="hook-status-label" title={launch.prompt ?? launch.cwd}>
+                      {launch.label} in {launch.repo}, queued {new Date(launch.queuedAt).toLocaleTimeString()}
+                    </span>
+                    <button className="settings-btn" onClick={() => handleCancelQueued(launch.id)}>
+                      Cancel
+                    </button>
+                  </div>
+                ))}
+              </div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Ignore</label>
+            <p className="settings-description">
+              Agents in these project paths or tmux sessions are never tracked. One glob per line,
+              e.g. <code>~/scratch/**</code> or <code>dotfiles</code>.
+            </p>
+            <textarea
+              className="settings-input settings-textarea"
+              rows={2}
+              spellCheck={false}
+              placeholder="Project paths"
+              value={settings.ignored_paths.join('\n')}
+              onChange={(e) => setSettings({ ...settings, ignored_paths: e.target.value.split('\n') })}
+            />
+            <textarea
+              className="settings-input settings-textarea"
+              rows={2}
+              spellCheck={false}
+              placeholder="tmux session names"
+              value={settings.ignored_tmux_sessions.join('\n')}
+              onChange={(e) => setSettings({ ...settings, ignored_tmux_sessions: e.target.value.split('\n') })}
+            />
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Floating Widget</label>
+            <p className="settings-description">
+              A small always-on-top bar with session counts. Toggle it from the tray menu or with
+              this shortcut; leave empty to disable the shortcut.
+            </p>
+            <input
+              type="text"
+              className="settings-input"
+              spellCheck={false}
+              placeholder="e.g. CmdOrCtrl+Shift+Period"
+              value={settings.widget_shortcut}
+              onChange={(e) => setSettings({ ...settings, widget_shortcut: e.target.value })}
+            />
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Redaction</label>
+            <p className="settings-description">
+              API keys, tokens and passwords are masked in commands, diagnostics and notifications.
+              Add extra regular expressions to mask, one per line.
+            </p>
+            <textarea
+              className="settings-input settings-textarea"
+              rows={3}
+              spellCheck={false}
+              placeholder="e.g. internal-[0-9]+"
+              value={settings.redaction_patterns.join('\n')}
+              onChange={(e) => setSettings({ ...settings, redaction_patterns: e.target.value.split('\n') })}
+            />
+            {saveError && <div className="setup-message error">{saveError}</div>}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Permission Requests</label>
+            <p className="settings-description">
+              Which tools asked for permission most in the last 30 days. Frequent commands are candidates for the
+              agent's allowlist.
+            </p>
+            {permissionStats && permissionStats.total > 0 ? (
+              <div className="hook-status">
+                {permissionStats.tools.map((tool) => (
+                  <div className="hook-status-row" key={tool.tool}>
+                    <span className="hook-status-label">
+                      {tool.tool}: {tool.percent}%
+                      {tool.topCommands.length > 0 &&
+                        `, top commands: ${tool.topCommands.map((c) => c.pattern).join(', ')}`}
+                    </span>
+                    <span>{tool.count}</span>
+                  </div>
+                ))}
+              </div>
+            ) : (
+              <p className="settings-description">No permission requests yet.</p>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Time Reports</label>
+            <p className="settings-description">
+              Time agents spent working, and waiting on permission prompts, per project and day. Export as CSV or JSON
+              for billing.
+            </p>
+            <div className="settings-row">
+              <input
+                type="date"
+                className="settings-input"
+                value={reportStart}
+                max={reportEnd}
+                onChange={(e) => setReportStart(e.target.value)}
+              />
+              <input
+                type="date"
+                className="settings-input"
+                value={reportEnd}
+                min={reportStart}
+                onChange={(e) => setReportEnd(e.target.value)}
+              />
+              <button className="settings-btn" onClick={handleExportTimeReport}>
+                Export…
+              </button>
+            </div>
+            {timeReportMessage && (
+              <div className={`setup-message ${timeReportMessage.success ? 'success' : 'error'}`}>
+                {timeReportMessage.text}
+              </div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Claude Allowlist</label>
+            <p className="settings-description">
+              Rules in <code>permissions.allow</code> of ~/.claude/settings.json, which Claude Code runs without
+              asking. The file is backed up before every change.
+            </p>
+            <div className="hook-status">
+              {allowRules.map((rule) => (
+                <div className="hook-status-row" key={rule}>
+                  <code className="hook-status-label">{rule}</code>
+                  <button className="settings-btn" onClick={() => handleAllowlistChange('remove_claude_allow_rule', rule)}>
+                    Remove
+                  </button>
+                </div>
+              ))}
+              {suggestedRules.map((rule) => (
+                <div className="hook-status-row" key={rule}>
+                  <code className="hook-status-label">{rule}</code>
+                  <button className="settings-btn" onClick={() => handleAllowlistChange('add_claude_allow_rule', rule)}>
+                    Always allow
+                  </button>
+                </div>
+              ))}
+            </div>
+            <div className="settings-timing-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="e.g. Bash(npm test:*)"
+                value={newAllowRule}
+                onChange={(e) => setNewAllowRule(e.target.value)}
+              />
+              <button
+                className="settings-btn"
+                disabled={!newAllowRule.trim()}
+                onClick={() => handleAllowlistChange('add_claude_allow_rule', newAllowRule)}
+              >
+                Add
+              </button>
+            </div>
+            {allowlistError && <div className="setup-message error">{allowlistError}</div>}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Metadata Sync</label>
+            <p className="settings-description">
+              Keep tags, notes, pins and groups in step across machines through a folder they all sync, such as
+              iCloud Drive or a Syncthing share. Each machine writes its own file there; when two change the same
+              session, the later change wins.
+            </p>
+            <div className="settings-row">
+              <input
+                type="text"
+                className="settings-input"
+                placeholder="Not synced"
+                value={settings.meta_sync_dir ?? ''}
+                onChange={(e) => setSettings({ ...settings, meta_sync_dir: e.target.value || null })}
+              />
+              <button
+                className="settings-btn"
+                onClick={async () => {
+                  const folder = await open({ directory: true, multiple: false, title: 'Sync metadata through' });
+                  if (folder) setSettings({ ...settings, meta_sync_dir: folder as string });
+                }}
+              >
+                Choose…
+              </button>
+              {settings.meta_sync_dir && (
+                <button className="settings-btn" onClick={() => setSettings({ ...settings, meta_sync_dir: null })}>
+                  Stop syncing
+                </button>
+              )}
+            </div>
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Session Handoff</label>
+            <p className="settings-description">
+              Continue a Claude conversation started on another machine. Export it there with "Hand off to another
+              machine…" in the session's menu, then import the bundle here to resume it with{' '}
+              <code>claude --resume</code>.
+            </p>
+            <button className="settings-btn" onClick={handleImportBundle}>
+              Import bundle…
+            </button>
+            {handoffMessage && (
+              <div className={`setup-message ${handoffMessage.success ? 'success' : 'error'}`}>{handoffMessage.text}</div>
+            )}
+          </div>
+
+          <div className="settings-group">
+            <label className="settings-label">Setup Hooks</label>
+            <p className="settings-description">
+              Install C3 hooks into Claude Code, Codex, and OMP for real-time session tracking.
+            </p>
+
+            <div className="hook-status">
+              <div className="hook-status-row">
+                <span className="hook-status-label">Any hooks installed</span>
+                <span className={`hook-status-badge ${hookStatus?.hooks_installed ? 'installed' : 'not-installed'}`}>
+                  {hookStatus?.hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
+                </span>
+              </div>
+              <div className="hook-status-row">
+                <span className="hook-status-label">Claude hooks</span>
+                <span className={`hook-status-badge ${hookStatus?.claude_hooks_installed ? 'installed' : 'not-installed'}`}>
+                  {hookStatus?.claude_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
+                </span>
+              </div>
+              <div className="hook-status-row">
+                <span className="hook-status-label">Codex hooks</span>
+                <span className={`hook-status-badge ${hookStatus?.codex_hooks_installed ? 'installed' : 'not-installed'}`}>
+                  {hookStatus?.codex_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
+                </span>
+              </div>
+
+              <div className="hook-status-row">
+                <span className="hook-status-label">OMP hooks</span>
+                <span className={`hook-status-badge ${hookStatus?.omp_hooks_installed ? 'installed' : 'not-installed'}`}>
+                  {hookStatus?.omp_hooks_installed ? <><Check size={12} /> Installed</> : <><X size={12} /> Not installed</>}
+                </span>
+              </div>
+
+              <div className="hook-status-row">
+                <span className="hook-status-label">Claude Code</span>
+                <span className={`hook-status-badge ${hookStatus?.claude_version && !hookStatus.claude_compat_issues.length ? 'installed' : 'not-installed'}`}>
+                  {hookStatus?.claude_version ?? 'Not found'}
+                </span>
+              </div>
+
+              {hookStatus && hookStatus.claude_compat_issues.length > 0 && (
+                <div className="setup-message error">
+                  <AlertTriangle size={12} /> {hookStatus.claude_compat_issues.join('. ')}.
+                </div>
+              )}
+
+              {hookStatus?.hook_script_outdated && (
+                <div className="setup-message error">
+                  <AlertTriangle size={12} /> Hook script outdated
+                  {hookStatus.hook_script_version ? ` (version ${hookStatus.hook_script_version})` : ''} —
+                  reinstall the hooks below to keep state updates working.
+                </div>
+              )}
+
+              <div className="hook-deps">
+                {[
+                  { name: 'jq', ok: hookStatus?.jq_installed, optional: false },
+                  { name: 'terminal-notifier', ok: hookStatus?.terminal_notifier_installed, optional: true },
+                  { name: 'tmux', ok: hookStatus?.tmux_installed, optional: false },
+                ].map(({ name, ok, optional }) => (
+                  <div className="hook-dep-row" key={name}>
+                    <span>{name}</span>
+                    {ok ? (
+                      <span className="dep-ok"><Check size={12} /></span>
+                    ) : (
+                      <>
+                        <span className={optional ? 'dep-warn' : 'dep-missing'}>
+                          <AlertTriangle size={12} /> {optional ? 'optional' : 'missing'}
+                        </span>
+                        <button
+                          className="settings-btn"
+                          disabled={installingDep !== null}
+                          onClick={() => handleInstallDependency(name)}
+                        >
+                          {installingDep === name ? <><RefreshCw size={12} className="spin" /> Installing...</> : 'Install'}
+                        </button>
+                      </>
+                    )}
+                  </div>
+                ))}
+              </div>
+              {installingDep && depOutput && <p className="settings-description">{depOutput}</p>}
+
+              {setupMessage && (
+                <div className={`setup-message ${setupMessage.success ? 'success' : 'error'}`}>
+                  {setupMessage.text}
+                </div>
+              )}
+
+              <div className="hook-actions">
+                <button
+                  className="settings-btn primary hook-install-btn"
+                  disabled={isInstallingHooks}
+                  onClick={async () => {
+                    setIsInstallingHooks(true);
+                    setSetupMessage(null);
+                    try {
+                      const result = await invoke<SetupResult>('setup_hooks');
                       setSetupMessage({ text: result.message, success: result.success });
                       refreshHookStatus();
                     } catch (e) {
@@ -330,10 +2462,59 @@ export function SettingsModal({ isOpen, onClose }: SettingsModalProps) {
                     <><Download size={14} /> Install Agent Hooks</>
                   )}
                 </button>
+                <button className="settings-btn" disabled={isOnboarding} onClick={handleGuidedSetup}>
+                  {isOnboarding ? <><RefreshCw size={14} className="spin" /> Setting up...</> : 'Guided setup'}
+                </button>
               </div>
+
+              {onboarding.length > 0 && (
+                <div className="hook-status">
+                  {onboarding.flatMap((progress) =>
+                    progress.items.map((item, i) => (
+                      <div className="hook-status-row" key={`${progress.step}-${i}`}>
+                        <span className="hook-status-label">
+                          {item.name}
+                          {item.detail && ` — ${item.detail}`}
+                        </span>
+                        <span className={`hook-status-badge ${item.ok ? 'installed' : 'not-installed'}`}>
+                          {item.ok ? <Check size={12} /> : <X size={12} />}
+                        </span>
+                      </div>
+                    ))
+                  )}
+                </div>
+              )}
             </div>
           </div>
 
+          <div className="settings-group">
+            <label className="settings-label">Uninstall</label>
+            <p className="settings-description">
+              Remove C3's hooks from Claude Code, Codex and OMP (backing up their settings first), the hook script and
+              the login item. Quit C3 afterwards and drag it to the Trash to finish.
+            </p>
+            <div className="settings-row">
+              <label className="settings-checkbox">
+                <input
+                  type="checkbox"
+                  checked={deleteDataOnUninstall}
+                  onChange={(e) => setDeleteDataOnUninstall(e.target.checked)}
+                />
+                <span>Also delete C3's settings, tags, notes and logs</span>
+              </label>
+            </div>
+            <button className="settings-btn" onClick={handleUninstall}>
+              Remove C3 integration
+            </button>
+            {uninstallReport && (
+              <div className={`setup-message ${uninstallReport.errors.length ? 'error' : 'success'}`}>
+                {uninstallReport.removed.length ? `Removed ${uninstallReport.removed.join(', ')}.` : 'Nothing to remove.'}
+                {uninstallReport.backups.length > 0 && ` Backups: ${uninstallReport.backups.join(', ')}.`}
+                {uninstallReport.errors.length > 0 && ` ${uninstallReport.errors.join('. ')}.`}
+              </div>
+            )}
+          </div>
+
           <div className="settings-info">
             <h3>About C3</h3>
             <p>
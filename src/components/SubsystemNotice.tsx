@@ -0,0 +1,48 @@
+import { AlertTriangle, X } from 'lucide-react';
+import { useSessionStore } from '../stores/sessions';
+
+// Shown while a crashed background task waits to be restarted, so a blind C3 doesn't look healthy,
+// and while a Claude Code version C3 is known to misread is in use
+export function SubsystemNotice() {
+  const downSubsystems = useSessionStore((state) => state.downSubsystems);
+  const compatWarnings = useSessionStore((state) => state.compatWarnings);
+  const dismissCompatWarning = useSessionStore((state) => state.dismissCompatWarning);
+  const down = Object.values(downSubsystems);
+  const warnings = Object.entries(compatWarnings);
+
+  if (down.length === 0 && warnings.length === 0) return null;
+
+  return (
+    <div className="subsystem-notice">
+      {down.map(({ name, reason, restartInSecs }) => (
+        <div key={name} className="toast toast-error">
+          <div className="toast-header">
+            <span className="toast-icon">
+              <AlertTriangle size={16} />
+            </span>
+            <span className="toast-title">{name} stopped</span>
+          </div>
+          <div className="toast-body">
+            {reason}. Restarting in {restartInSecs}s; session updates may lag until then.
+          </div>
+        </div>
+      ))}
+      {warnings.map(([key, { projectName, version, issues }]) => (
+        <div key={key} className="toast toast-error">
+          <div className="toast-header">
+            <span className="toast-icon">
+              <AlertTriangle size={16} />
+            </span>
+            <span className="toast-title">
+              Claude Code {version}{projectName ? ` in ${projectName}` : ''}
+            </span>
+            <button className="toast-close" onClick={() => dismissCompatWarning(key)}>
+              <X size={14} />
+            </button>
+          </div>
+          <div className="toast-body">{issues.join('. ')}.</div>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -20,6 +20,7 @@ export function ToastContainer() {
   const [toasts, setToasts] = useState<ToastData[]>([]);
   const sessions = useSessionStore((state) => state.sessions);
   const focusTerminal = useSessionStore((state) => state.focusTerminal);
+  const answerQuestion = useSessionStore((state) => state.answerQuestion);
   const seenRef = useRef<Set<string>>(new Set());
 
   // Detect new awaiting sessions
@@ -63,6 +64,11 @@ export function ToastContainer() {
     if (toast) dismissToast(toast.id);
   };
 
+  const handleAnswer = (toast: ToastData, optionIndex: number) => {
+    answerQuestion(toast.session.id, optionIndex);
+    dismissToast(toast.id);
+  };
+
   // Auto-dismiss after 15 seconds
   useEffect(() => {
     const interval = setInterval(() => {
@@ -127,6 +133,20 @@ export function ToastContainer() {
               </div>
             )}
           </div>
+          {(toast.session.pendingAction?.options.length ?? 0) > 0 && (
+            <div className="toast-options">
+              {toast.session.pendingAction!.options.map((option, i) => (
+                <button
+                  key={option}
+                  className="toast-btn"
+                  title={`Answer "${option}"`}
+                  onClick={() => handleAnswer(toast, i)}
+                >
+                  {option}
+                </button>
+              ))}
+            </div>
+          )}
           <div className="toast-buttons">
             <button
               className="toast-btn primary"
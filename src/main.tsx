@@ -1,9 +1,23 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
 import App from "./App";
+import { CompactView } from "./components/CompactView";
+import { FloatingWidget } from "./components/FloatingWidget";
+import { SessionWindow } from "./components/SessionWindow";
+
+// Secondary windows load index.html#compact, #widget and #session/<id>
+const views: Record<string, React.ComponentType> = {
+  "#compact": CompactView,
+  "#widget": FloatingWidget,
+};
+const hash = window.location.hash;
+const poppedOutSession = hash.startsWith("#session/")
+  ? decodeURIComponent(hash.slice("#session/".length))
+  : null;
+const View = views[hash] ?? App;
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {poppedOutSession ? <SessionWindow sessionId={poppedOutSession} /> : <View />}
   </React.StrictMode>,
 );
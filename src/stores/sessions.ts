@@ -3,12 +3,19 @@ import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type {
   AppSettings,
+  ArchivedBatch,
   C3Session,
+  CompatWarning,
   GroupAssignment,
+  HookSound,
   SessionGroup,
   SessionMeta,
+  SessionBatch,
+  SessionDelta,
   SessionMetaStore,
-  SoundConfig,
+  SubsystemDown,
+  TranscriptFormat,
+  WorktreeSession,
 } from '../types';
 import { getVisualSessionOrder } from '../types';
 
@@ -16,17 +23,28 @@ interface SessionStore {
   sessions: Record<string, C3Session>;
   sessionMeta: Record<string, SessionMeta>;
   groups: SessionGroup[];
+  // Tags whose sessions send no notifications or sounds
+  mutedTags: string[];
   draggingSessionId: string | null;
   dragTargetGroupId: string | null;
   selectedSessionId: string | null;
   pendingKillSessionId: string | null;
   isConnected: boolean;
   notificationsEnabled: boolean;
+  // Backend session revision the store is in sync with
+  revision: number;
+  // Latest batch hidden by the retention rules, while it can still be undone
+  archivedBatch: ArchivedBatch | null;
+  // Background tasks that crashed and are waiting to restart, by name
+  downSubsystems: Record<string, SubsystemDown>;
+  // Claude Code CLIs C3 is known to misread, by session id ('' for the installed CLI)
+  compatWarnings: Record<string, CompatWarning>;
 
   // Actions
   setSessions: (sessions: C3Session[]) => void;
   updateSession: (session: C3Session) => void;
   removeSession: (sessionId: string) => void;
+  applySessionBatch: (batch: SessionBatch) => void;
   selectSession: (sessionId: string | null) => void;
   requestKillSession: (sessionId: string) => void;
   clearKillRequest: () => void;
@@ -39,10 +57,16 @@ interface SessionStore {
   // Session metadata
   setSessionTag: (sessionId: string, tag: string) => Promise<void>;
   setSessionPinned: (sessionId: string, pinned: boolean) => Promise<void>;
+  reorderSessions: (sessionIds: string[]) => Promise<void>;
+  setSessionNote: (sessionId: string, text: string) => Promise<void>;
+  setNextPrompts: (sessionId: string, prompts: string[]) => Promise<void>;
+  sendNextPrompt: (sessionId: string) => Promise<void>;
   loadSessionMeta: () => Promise<void>;
   upsertGroup: (group: SessionGroup) => Promise<void>;
   deleteGroup: (groupId: string) => Promise<void>;
   assignSessionGroup: (sessionId: string, groupId: string | null, assignment: GroupAssignment) => Promise<void>;
+  setGroupMuted: (groupId: string, muted: boolean) => Promise<void>;
+  setTagMuted: (tag: string, muted: boolean) => Promise<void>;
   autoAssignGroups: (sessions?: C3Session[]) => Promise<void>;
 
   // Navigation
@@ -52,22 +76,38 @@ interface SessionStore {
 
   // Tauri commands
   fetchSessions: () => Promise<void>;
+  syncSessions: () => Promise<void>;
   focusTerminal: (tmuxTarget: string) => Promise<void>;
   focusSession: (sessionId: string) => Promise<void>;
   sendAction: (sessionId: string, action: string) => Promise<void>;
   closePane: (tmuxTarget: string) => Promise<void>;
+  zoomPane: (tmuxTarget: string, zoom: boolean) => Promise<void>;
   killSession: (sessionId: string) => Promise<void>;
   createNewTask: () => Promise<string>;
+  exportTranscript: (sessionId: string, format: TranscriptFormat, path: string) => Promise<string>;
+  exportSessionBundle: (sessionId: string, path: string) => Promise<string>;
+  openSessionWindow: (sessionId: string) => Promise<void>;
+  resumeSession: (sessionId: string) => Promise<void>;
+  resyncSession: (sessionId: string) => Promise<void>;
+  answerQuestion: (sessionId: string, optionIndex: number) => Promise<void>;
+  createWorktree: (repo: string, branch: string, prompt?: string) => Promise<WorktreeSession>;
+  removeWorktree: (path: string, force?: boolean) => Promise<void>;
+  setArchivedBatch: (batch: ArchivedBatch | null) => void;
+  setSubsystemDown: (name: string, down: SubsystemDown | null) => void;
+  setCompatWarning: (warning: CompatWarning) => void;
+  dismissCompatWarning: (key: string) => void;
+  undoArchive: () => Promise<void>;
 }
 
 // Track previous states for notification logic
 const previousStates: Record<string, string> = {};
 const autoAssigning = new Set<string>();
 
-function applyMetaStore(store: SessionMetaStore): Pick<SessionStore, 'sessionMeta' | 'groups'> {
+function applyMetaStore(store: SessionMetaStore): Pick<SessionStore, 'sessionMeta' | 'groups' | 'mutedTags'> {
   return {
     sessionMeta: store.sessions || {},
     groups: store.groups || [],
+    mutedTags: store.mutedTags || [],
   };
 }
 
@@ -93,12 +133,17 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
   sessions: {},
   sessionMeta: {},
   groups: [],
+  mutedTags: [],
   draggingSessionId: null,
   dragTargetGroupId: null,
   selectedSessionId: null,
   pendingKillSessionId: null,
   isConnected: false,
   notificationsEnabled: true,
+  revision: 0,
+  archivedBatch: null,
+  downSubsystems: {},
+  compatWarnings: {},
 
   setSessions: (sessions) => {
     const record: Record<string, C3Session> = {};
@@ -136,6 +181,50 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     });
   },
 
+  applySessionBatch: ({ updated, removed, renamed, revision }) => {
+    updated.forEach((session) => {
+      previousStates[session.id] = session.state;
+    });
+    removed.forEach((sessionId) => {
+      delete previousStates[sessionId];
+    });
+
+    set((state) => {
+      const sessions = { ...state.sessions };
+      updated.forEach((session) => {
+        sessions[session.id] = session;
+      });
+      removed.forEach((sessionId) => {
+        delete sessions[sessionId];
+      });
+      // The backend has already moved the metadata of renamed sessions
+      const sessionMeta = { ...state.sessionMeta };
+      const renamedTo = new Map(renamed.map(({ from, to }) => [from, to]));
+      renamed.forEach(({ from, to }) => {
+        if (sessionMeta[from]) {
+          sessionMeta[to] = sessionMeta[from];
+          delete sessionMeta[from];
+        }
+      });
+      const follow = (id: string | null) => (id !== null && renamedTo.get(id)) || id;
+      const isRemoved = (id: string | null) => id !== null && removed.includes(id);
+      const selectedSessionId = follow(state.selectedSessionId);
+      const pendingKillSessionId = follow(state.pendingKillSessionId);
+      return {
+        sessions,
+        sessionMeta: renamed.length > 0 ? sessionMeta : state.sessionMeta,
+        revision: Math.max(state.revision, revision),
+        selectedSessionId: isRemoved(selectedSessionId) ? null : selectedSessionId,
+        pendingKillSessionId: isRemoved(pendingKillSessionId) ? null : pendingKillSessionId,
+      };
+    });
+    if (updated.length > 0) {
+      queueMicrotask(() => {
+        get().autoAssignGroups(updated);
+      });
+    }
+  },
+
   selectSession: (sessionId) => {
     set({ selectedSessionId: sessionId });
   },
@@ -202,6 +291,42 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     }
   },
 
+  setSessionNote: async (sessionId, text) => {
+    try {
+      const result = await invoke<SessionMetaStore>('set_session_note', { sessionId, text });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to set session note:', e);
+    }
+  },
+
+  setNextPrompts: async (sessionId, prompts) => {
+    try {
+      const result = await invoke<SessionMetaStore>('set_next_prompts', { sessionId, prompts });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to queue prompts:', e);
+    }
+  },
+
+  sendNextPrompt: async (sessionId) => {
+    try {
+      const result = await invoke<SessionMetaStore>('send_next_prompt', { sessionId });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to send queued prompt:', e);
+    }
+  },
+
+  reorderSessions: async (sessionIds) => {
+    try {
+      const result = await invoke<SessionMetaStore>('reorder_sessions', { sessionIds });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to reorder sessions:', e);
+    }
+  },
+
   loadSessionMeta: async () => {
     try {
       const result = await invoke<SessionMetaStore>('get_session_meta');
@@ -257,6 +382,24 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     }
   },
 
+  setGroupMuted: async (groupId, muted) => {
+    try {
+      const result = await invoke<SessionMetaStore>('set_group_muted', { groupId, muted });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to mute group:', e);
+    }
+  },
+
+  setTagMuted: async (tag, muted) => {
+    try {
+      const result = await invoke<SessionMetaStore>('set_tag_muted', { tag, muted });
+      set(applyMetaStore(result));
+    } catch (e) {
+      console.error('[C3] Failed to mute tag:', e);
+    }
+  },
+
   autoAssignGroups: async (targetSessions) => {
     const { groups, sessions, sessionMeta } = get();
     if (groups.length === 0) return;
@@ -328,6 +471,23 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     }
   },
 
+  // Cheap resync: only fetch sessions changed since the last known revision
+  syncSessions: async () => {
+    try {
+      const delta = await invoke<SessionDelta>('get_sessions_since', { revision: get().revision });
+      if (delta.full) {
+        get().setSessions(delta.updated);
+        set({ revision: delta.revision });
+      } else if (delta.updated.length > 0 || delta.removed.length > 0 || delta.revision !== get().revision) {
+        get().applySessionBatch(delta);
+        set({ isConnected: true });
+      }
+    } catch (e) {
+      console.error('[C3] Failed to sync sessions:', e);
+      get().setConnected(false);
+    }
+  },
+
   focusTerminal: async (tmuxTarget) => {
     try {
       await invoke('focus_terminal', { tmuxTarget });
@@ -360,6 +520,14 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
     }
   },
 
+  zoomPane: async (tmuxTarget, zoom) => {
+    try {
+      await invoke('zoom_pane', { tmuxTarget, zoom });
+    } catch (e) {
+      console.error('[C3] Failed to zoom pane:', e);
+    }
+  },
+
   killSession: async (sessionId) => {
     try {
       await invoke('kill_session', { sessionId });
@@ -378,32 +546,114 @@ export const useSessionStore = create<SessionStore>((set, get) => ({
       throw e;
     }
   },
-}));
 
-// Play sound based on config
-async function playSound(config: SoundConfig) {
-  if (!config.enabled) return;
+  exportTranscript: async (sessionId, format, path) => {
+    try {
+      return await invoke<string>('export_transcript', { sessionId, format, path });
+    } catch (e) {
+      console.error('[C3] Failed to export transcript:', e);
+      throw e;
+    }
+  },
 
-  const sound = config.sound || 'Ping'; // Default sound
-  try {
-    await invoke('play_sound', { sound });
-  } catch (e) {
-    console.error('[C3] Failed to play sound:', e);
-  }
-}
+  exportSessionBundle: async (sessionId, path) => {
+    try {
+      return await invoke<string>('export_session_bundle', { sessionId, path });
+    } catch (e) {
+      console.error('[C3] Failed to export session bundle:', e);
+      throw e;
+    }
+  },
+
+  openSessionWindow: async (sessionId) => {
+    try {
+      await invoke('open_session_window', { sessionId });
+    } catch (e) {
+      console.error('[C3] Failed to open session window:', e);
+    }
+  },
+
+  resumeSession: async (sessionId) => {
+    try {
+      await invoke<string>('resume_session', { sessionId });
+    } catch (e) {
+      console.error('[C3] Failed to resume session:', e);
+    }
+  },
+
+  resyncSession: async (sessionId) => {
+    try {
+      await invoke<C3Session>('resync_session', { sessionId });
+    } catch (e) {
+      console.error('[C3] Failed to resync session:', e);
+    }
+  },
+
+  answerQuestion: async (sessionId, optionIndex) => {
+    try {
+      await invoke('answer_question', { sessionId, optionIndex });
+    } catch (e) {
+      console.error('[C3] Failed to answer question:', e);
+    }
+  },
 
-// Play the appropriate sound for a hook event type.
+  createWorktree: async (repo, branch, prompt) => {
+    try {
+      return await invoke<WorktreeSession>('create_worktree', { repo, branch, prompt });
+    } catch (e) {
+      console.error('[C3] Failed to create worktree:', e);
+      throw e;
+    }
+  },
+
+  removeWorktree: async (path, force) => {
+    try {
+      await invoke('remove_worktree', { path, force });
+    } catch (e) {
+      console.error('[C3] Failed to remove worktree:', e);
+      throw e;
+    }
+  },
+
+  setArchivedBatch: (batch) => {
+    set({ archivedBatch: batch });
+  },
+
+  setSubsystemDown: (name, down) => {
+    const { [name]: _, ...rest } = get().downSubsystems;
+    set({ downSubsystems: down ? { ...rest, [name]: down } : rest });
+  },
+
+  setCompatWarning: (warning) => {
+    const key = warning.sessionId ?? '';
+    const { [key]: _, ...rest } = get().compatWarnings;
+    set({ compatWarnings: warning.issues.length > 0 ? { ...rest, [key]: warning } : rest });
+  },
+
+  dismissCompatWarning: (key) => {
+    const { [key]: _, ...rest } = get().compatWarnings;
+    set({ compatWarnings: rest });
+  },
+
+  undoArchive: async () => {
+    const batch = get().archivedBatch;
+    if (!batch) return;
+    set({ archivedBatch: null });
+    try {
+      await invoke<number>('undo_archive', { sessionIds: batch.sessionIds });
+    } catch (e) {
+      console.error('[C3] Failed to undo archive:', e);
+    }
+  },
+}));
+
+// Play the sound the backend picked for a hook event (from the project's theme).
 // Desktop notifications are handled by the Rust backend via terminal-notifier.
-async function triggerSound(type: 'permission' | 'input' | 'complete') {
+async function triggerSound(sound: HookSound) {
+  // The backend checks the saved settings; this is the bell button in the header
+  if (!useSessionStore.getState().notificationsEnabled) return;
   try {
-    // Check the in-memory toggle first (bell button in header)
-    if (!useSessionStore.getState().notificationsEnabled) return;
-    const settings = await invoke<AppSettings>('get_settings');
-    if (!settings.notifications_enabled) return;
-    const soundConfig = type === 'permission' ? settings.permission_sound
-      : type === 'input' ? settings.input_sound
-      : settings.complete_sound;
-    playSound(soundConfig);
+    await invoke('play_sound', { sound: sound.sound });
   } catch (e) {
     console.error('[C3] Failed to play notification sound:', e);
   }
@@ -411,31 +661,61 @@ async function triggerSound(type: 'permission' | 'input' | 'complete') {
 
 // Initialize event listeners
 let initialized = false;
-export async function initializeSessionListeners() {
+export async function initializeSessionListeners({ sounds = true }: { sounds?: boolean } = {}) {
   if (initialized) return;
   initialized = true;
 
   console.log('[C3] Initializing event listeners...');
 
   try {
-    // Listen for session updates from Rust
-    await listen<C3Session>('session-update', (event) => {
-      console.log('[C3] Session update:', event.payload.projectName, event.payload.state);
-      useSessionStore.getState().updateSession(event.payload);
+    // Listen for coalesced session updates from Rust
+    await listen<SessionBatch>('sessions-batch-update', (event) => {
+      const { updated, removed } = event.payload;
+      console.log('[C3] Session batch:', updated.length, 'updated,', removed.length, 'removed');
+      useSessionStore.getState().applySessionBatch(event.payload);
     });
 
-    await listen<string>('session-removed', (event) => {
-      console.log('[C3] Session removed:', event.payload);
-      useSessionStore.getState().removeSession(event.payload);
+    // Sessions hidden by the retention rules; offer an undo
+    await listen<ArchivedBatch>('sessions-archived', (event) => {
+      const batch = event.payload;
+      console.log('[C3] Archived', batch.sessionIds.length, 'sessions');
+      const current = useSessionStore.getState().archivedBatch;
+      // Worktree errors alone keep the undo of an earlier sweep on offer
+      useSessionStore
+        .getState()
+        .setArchivedBatch(
+          batch.sessionIds.length === 0 && current ? { ...current, worktreeErrors: batch.worktreeErrors } : batch,
+        );
     });
 
-    // Listen for hook-triggered sounds (separate from state changes)
-    await listen<string>('hook-sound', (event) => {
-      const soundType = event.payload as 'permission' | 'input' | 'complete';
-      console.log('[C3] Hook sound:', soundType);
-      triggerSound(soundType);
+    // Background tasks restarted by the supervisor
+    await listen<SubsystemDown>('subsystem-down', (event) => {
+      console.warn('[C3] Subsystem down:', event.payload.name, event.payload.reason);
+      useSessionStore.getState().setSubsystemDown(event.payload.name, event.payload);
+    });
+    await listen<string>('subsystem-up', (event) => {
+      useSessionStore.getState().setSubsystemDown(event.payload, null);
     });
 
+    // Claude Code versions whose hooks or transcripts C3 may misread
+    await listen<CompatWarning>('cli-compat-warning', (event) => {
+      console.warn('[C3] Claude Code', event.payload.version, event.payload.issues);
+      useSessionStore.getState().setCompatWarning(event.payload);
+    });
+
+    // Tags, notes and groups changed on another machine
+    await listen('session-meta-changed', () => {
+      useSessionStore.getState().loadSessionMeta();
+    });
+
+    // Listen for hook-triggered sounds (separate from state changes)
+    if (sounds) {
+      await listen<HookSound>('hook-sound', (event) => {
+        console.log('[C3] Hook sound:', event.payload.kind);
+        triggerSound(event.payload);
+      });
+    }
+
     console.log('[C3] Event listeners ready');
   } catch (e) {
     console.error('[C3] Failed to set up listeners:', e);
@@ -449,6 +729,20 @@ export async function initializeSessionListeners() {
     console.error('[C3] Failed to load initial settings:', e);
   }
 
+  // Anything that went down before this window was listening
+  try {
+    const down = await invoke<SubsystemDown[]>('get_down_subsystems');
+    down.forEach((d) => useSessionStore.getState().setSubsystemDown(d.name, d));
+  } catch (e) {
+    console.error('[C3] Failed to load subsystem status:', e);
+  }
+  try {
+    const warnings = await invoke<CompatWarning[]>('get_compat_warnings');
+    warnings.forEach((w) => useSessionStore.getState().setCompatWarning(w));
+  } catch (e) {
+    console.error('[C3] Failed to load compatibility warnings:', e);
+  }
+
   await useSessionStore.getState().loadSessionMeta();
-  await useSessionStore.getState().fetchSessions();
+  await useSessionStore.getState().syncSessions();
 }
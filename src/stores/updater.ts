@@ -1,65 +1,26 @@
 import { useCallback, useEffect, useState } from 'react';
-import { getVersion } from '@tauri-apps/api/app';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { openUrl } from '@tauri-apps/plugin-opener';
+import type { UpdateInfo } from '../types';
 
-const RELEASE_API_URL = 'https://api.github.com/repos/illustriousdevelopment/c3/releases/latest';
-const CHECK_INTERVAL_MS = 4 * 60 * 60 * 1000;
-
+// The backend also checks on its own schedule and emits update-available / update-staged
 type UpdateStatus = 'checking' | 'available' | 'up_to_date' | 'error';
 
-interface GitHubAsset {
-  name: string;
-  browser_download_url: string;
-}
-
-interface GitHubRelease {
-  tag_name: string;
-  html_url: string;
-  assets: GitHubAsset[];
-}
-
 interface UpdateState {
   status: UpdateStatus;
-  currentVersion: string | null;
-  latestVersion: string | null;
-  downloadUrl: string | null;
-  releaseUrl: string | null;
+  info: UpdateInfo | null;
   error: string | null;
 }
 
 const initialState: UpdateState = {
   status: 'checking',
-  currentVersion: null,
-  latestVersion: null,
-  downloadUrl: null,
-  releaseUrl: null,
+  info: null,
   error: null,
 };
 
-function normalizeVersion(version: string): number[] {
-  return version
-    .trim()
-    .replace(/^v/i, '')
-    .split(/[.-]/)
-    .slice(0, 3)
-    .map((part) => Number.parseInt(part, 10))
-    .map((part) => (Number.isFinite(part) ? part : 0));
-}
-
-function compareVersions(a: string, b: string): number {
-  const left = normalizeVersion(a);
-  const right = normalizeVersion(b);
-  for (let i = 0; i < Math.max(left.length, right.length); i += 1) {
-    const l = left[i] ?? 0;
-    const r = right[i] ?? 0;
-    if (l !== r) return l - r;
-  }
-  return 0;
-}
-
-function findDmgAsset(release: GitHubRelease): GitHubAsset | undefined {
-  return release.assets.find((asset) => asset.name.endsWith('_aarch64.dmg'))
-    ?? release.assets.find((asset) => asset.name.endsWith('.dmg'));
+function stateFor(info: UpdateInfo): UpdateState {
+  return { status: info.available ? 'available' : 'up_to_date', info, error: null };
 }
 
 export function useUpdateChecker() {
@@ -71,57 +32,50 @@ export function useUpdateChecker() {
     }
 
     try {
-      const [currentVersion, response] = await Promise.all([
-        getVersion(),
-        fetch(RELEASE_API_URL, {
-          cache: 'no-store',
-          headers: { Accept: 'application/vnd.github+json' },
-        }),
-      ]);
-
-      if (!response.ok) {
-        throw new Error(`GitHub returned ${response.status}`);
-      }
-
-      const release = await response.json() as GitHubRelease;
-      const latestVersion = release.tag_name.replace(/^v/i, '');
-      const dmgAsset = findDmgAsset(release);
-      const updateAvailable = compareVersions(latestVersion, currentVersion) > 0;
-
-      setState({
-        status: updateAvailable ? 'available' : 'up_to_date',
-        currentVersion,
-        latestVersion,
-        downloadUrl: dmgAsset?.browser_download_url ?? null,
-        releaseUrl: release.html_url,
-        error: null,
-      });
+      const info = await invoke<UpdateInfo>('check_for_updates');
+      setState(stateFor(info));
     } catch (error) {
       setState((prev) => ({
         ...prev,
         status: 'error',
-        error: error instanceof Error ? error.message : 'Failed to check for updates',
+        error: typeof error === 'string' ? error : 'Failed to check for updates',
       }));
     }
   }, []);
 
   const openUpdate = useCallback(async () => {
-    const target = state.downloadUrl ?? state.releaseUrl;
-    if (state.status === 'available' && target) {
-      await openUrl(target);
+    const info = state.info;
+    if (state.status !== 'available' || !info) {
+      await checkForUpdates();
+      return;
+    }
+    if (info.stagedPath) {
+      await invoke('open_staged_update', { path: info.stagedPath });
       return;
     }
-    await checkForUpdates();
-  }, [checkForUpdates, state.downloadUrl, state.releaseUrl, state.status]);
+    await openUrl(info.downloadUrl ?? info.releaseUrl);
+  }, [checkForUpdates, state.info, state.status]);
 
   useEffect(() => {
     checkForUpdates(true);
-    const interval = window.setInterval(() => checkForUpdates(true), CHECK_INTERVAL_MS);
-    return () => window.clearInterval(interval);
+    const unlistenAvailable = listen<UpdateInfo>('update-available', (event) => {
+      setState(stateFor(event.payload));
+    });
+    const unlistenStaged = listen<UpdateInfo>('update-staged', (event) => {
+      setState(stateFor(event.payload));
+    });
+    return () => {
+      unlistenAvailable.then((fn) => fn());
+      unlistenStaged.then((fn) => fn());
+    };
   }, [checkForUpdates]);
 
   return {
     ...state,
+    currentVersion: state.info?.currentVersion ?? null,
+    latestVersion: state.info?.latestVersion ?? null,
+    staged: Boolean(state.info?.stagedPath),
+    hooksOutdated: state.info?.hooksOutdated ?? false,
     checkForUpdates,
     openUpdate,
   };
@@ -1,88 +1,88 @@
-export type SessionState =
-  | 'spawning'
-  | 'processing'
-  | 'awaiting_input'
-  | 'awaiting_permission'
-  | 'complete'
-  | 'error';
-
-export interface PendingAction {
-  type: 'input' | 'permission';
-  description: string;
-  tool?: string;
-  command?: string;
-}
-
-export interface SessionMetrics {
-  tokensUsed?: number;
-  taskCount?: number;
-  startTime?: string;
-}
+// Types shared with the backend are generated from the Rust structs into
+// src/bindings.ts; see "Shared types" in the README to regenerate them.
+import type * as Bindings from '../bindings';
+
+export type {
+  AgentInfo,
+  AllowlistUpdate,
+  ApiToken,
+  Arbitration,
+  ArchivedBatch,
+  AutomationRule,
+  AutomationRun,
+  C3Session,
+  ChannelEvent,
+  CompatWarning,
+  CreatedApiToken,
+  CiStatus,
+  CustomTerminal,
+  DependencyProgress,
+  Diagnostics,
+  DiffFile,
+  GitBaseline,
+  HookSound,
+  HookStatus,
+  ImportedSession,
+  LanStatus,
+  NotificationDecision,
+  OnboardingItem,
+  OnboardingProgress,
+  OnboardingStep,
+  PendingAction,
+  PermissionStats,
+  ProfileList,
+  PullRequestStatus,
+  QueuedLaunch,
+  ScheduleEntry,
+  ScheduledTask,
+  SearchScope,
+  SessionBatch,
+  SessionDelta,
+  SessionDiff,
+  SessionFiles,
+  SessionFilter,
+  SessionMeta,
+  SessionMetrics,
+  SessionQueryResult,
+  SessionState,
+  SessionTail,
+  SetupResult,
+  SlaRule,
+  SoundTheme,
+  SubsystemDown,
+  SubsystemHealth,
+  Suppression,
+  TailMessage,
+  TimeReportRow,
+  TokenScope,
+  TouchedFile,
+  TranscriptMatch,
+  UninstallReport,
+  UpdateInfo,
+  WeeklyDigest,
+  WorktreeSession,
+} from '../bindings';
+
+import type { C3Session, SessionMeta, SessionState } from '../bindings';
 
 export type GroupAssignment = 'auto' | 'manual';
 
-export interface SessionGroup {
-  id: string;
-  name: string;
-  color: string;
-  matchText: string[];
-  createdAt: string;
-}
+export type TranscriptFormat = Bindings.ExportFormat;
 
-export interface SessionMeta {
-  tag?: string;
-  pinned: boolean;
-  groupId?: string;
-  groupAssignment?: GroupAssignment;
-}
+// Fields with serde defaults come out optional; the backend always sends them
+export type AppSettings = Required<Bindings.AppSettings>;
+export type SoundConfig = Required<Bindings.SoundConfig>;
+export type PushSettings = Required<Bindings.PushSettings>;
+export type TelegramSettings = Required<Bindings.TelegramSettings>;
+export type EmailSettings = Required<Bindings.EmailSettings>;
+export type LanSettings = Required<Bindings.LanSettings>;
+export type AtRestSettings = Required<Bindings.AtRestSettings>;
+export type SessionGroup = Required<Bindings.SessionGroup>;
 
 export interface SessionMetaStore {
   sessions: Record<string, SessionMeta>;
   groups: SessionGroup[];
-}
-
-export interface C3Session {
-  id: string;
-  projectName: string;
-  projectPath?: string;
-  agentKind?: 'claude' | 'codex' | 'omp' | 'unknown';
-  state: SessionState;
-  tmuxTarget?: string;
-  terminalTty?: string;
-  lastActivity: string;
-  pendingAction?: PendingAction;
-  metrics?: SessionMetrics;
-}
-
-export interface SoundConfig {
-  enabled: boolean;
-  sound: string | null; // null = default, string = system sound name or file path
-}
-
-export interface AppSettings {
-  terminal_app: string;
-  default_agent: 'claude' | 'codex';
-  notifications_enabled: boolean;
-  permission_sound: SoundConfig;
-  input_sound: SoundConfig;
-  complete_sound: SoundConfig;
-}
-
-export interface HookStatus {
-  hooks_installed: boolean;
-  claude_hooks_installed: boolean;
-  codex_hooks_installed: boolean;
-  omp_hooks_installed: boolean;
-  hook_script_exists: boolean;
-  jq_installed: boolean;
-  terminal_notifier_installed: boolean;
-  tmux_installed: boolean;
-}
-
-export interface SetupResult {
-  success: boolean;
-  message: string;
-  backup_path: string | null;
+  mutedTags: string[];
 }
 
 export interface Lane {
@@ -132,9 +132,21 @@ export const LANES: Lane[] = [
   },
 ];
 
+/**
+ * Within a lane: drag-reordered sessions by position, then the rest by recency
+ */
+export function compareSessions(sessionMeta: Record<string, SessionMeta>) {
+  return (a: C3Session, b: C3Session) => {
+    const orderA = sessionMeta[a.id]?.order ?? Infinity;
+    const orderB = sessionMeta[b.id]?.order ?? Infinity;
+    if (orderA !== orderB) return orderA - orderB;
+    return new Date(b.lastActivity).getTime() - new Date(a.lastActivity).getTime();
+  };
+}
+
 /**
  * Compute the visual session order matching what the UI renders:
- * Pinned (by recency) → Permission (by recency) → Processing (by recency) → Idle (by recency) → Error (by recency)
+ * Pinned → Permission → Processing → Idle → Error, each by manual order then recency
  * Returns the ordered session list. Sessions that appear in pinned also appear in their lane,
  * so we deduplicate: pinned sessions come first, then remaining sessions by lane order.
  */
@@ -143,10 +155,9 @@ export function getVisualSessionOrder(
   sessionMeta: Record<string, SessionMeta>,
   groups: SessionGroup[] = [],
 ): C3Session[] {
-  const byRecency = (a: C3Session, b: C3Session) =>
-    new Date(b.lastActivity).getTime() - new Date(a.lastActivity).getTime();
+  const byOrder = compareSessions(sessionMeta);
 
-  const pinned = sessions.filter((s) => sessionMeta[s.id]?.pinned).sort(byRecency);
+  const pinned = sessions.filter((s) => sessionMeta[s.id]?.pinned).sort(byOrder);
   const pinnedIds = new Set(pinned.map((s) => s.id));
   const orderedGroups = [...groups].sort(
     (a, b) => new Date(a.createdAt).getTime() - new Date(b.createdAt).getTime()
@@ -163,14 +174,14 @@ export function getVisualSessionOrder(
   for (const group of orderedGroups) {
     const groupSessions = sessions
       .filter((s) => !pinnedIds.has(s.id) && sessionMeta[s.id]?.groupId === group.id)
-      .sort(byRecency);
+      .sort(byOrder);
     ordered.push(...groupSessions);
   }
 
   for (const lane of LANES) {
     const laneSessions = unpinnedUngrouped
       .filter((s) => lane.states.includes(s.state))
-      .sort(byRecency);
+      .sort(byOrder);
     ordered.push(...laneSessions);
   }
   return ordered;